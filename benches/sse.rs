@@ -0,0 +1,126 @@
+//! Compares the `Bytes`-based SSE parser with the previous `String` buffer
+//!
+//! Run with `cargo bench --bench sse`. Deserializing the chunks dominates the
+//! run time, so before timing, the allocations and allocated bytes of one pass
+//! over the stream are printed for both parsers as well.
+
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use groqai::fixtures;
+use groqai::sse::SseParser;
+use groqai::types::ChatCompletionChunk;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Counts heap allocations so the parsers' allocation behavior can be compared
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Returns the allocations and allocated bytes of one run of `parse`
+fn allocations(parse: fn(Vec<Bytes>) -> usize, reads: Vec<Bytes>) -> (usize, usize) {
+    let (count, bytes) = (ALLOCATIONS.load(Ordering::Relaxed), ALLOCATED_BYTES.load(Ordering::Relaxed));
+    black_box(parse(reads));
+    (
+        ALLOCATIONS.load(Ordering::Relaxed) - count,
+        ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes,
+    )
+}
+
+/// Builds a stream body of `events` chunks with multi-byte content
+fn transcript(events: usize) -> Vec<u8> {
+    let mut chunk: serde_json::Value = serde_json::from_str(fixtures::CHAT_COMPLETION_CHUNK).unwrap();
+    let mut body = String::new();
+    for i in 0..events {
+        chunk["choices"][0]["delta"]["content"] = format!("token {} — héllo 世界 ", i).into();
+        body.push_str("data: ");
+        body.push_str(&chunk.to_string());
+        body.push_str("\n\n");
+    }
+    body.push_str("data: [DONE]\n\n");
+    body.into_bytes()
+}
+
+/// Splits `body` into network-sized reads that cut through lines and characters
+fn reads(body: &[u8], size: usize) -> Vec<Bytes> {
+    body.chunks(size).map(Bytes::copy_from_slice).collect()
+}
+
+/// The parser before it moved to `Bytes`: a lossy copy of every read into a `String`
+fn legacy_parse(reads: Vec<Bytes>) -> usize {
+    let mut buffer = String::new();
+    let mut parsed = 0;
+    for bytes in reads {
+        buffer.push_str(&String::from_utf8_lossy(&bytes));
+        let Some(last_newline) = buffer.rfind('\n') else {
+            continue;
+        };
+        let (complete, remaining) = buffer.split_at(last_newline + 1);
+        for line in complete.lines() {
+            if let Some(json) = line.strip_prefix("data: ") {
+                if json.ends_with("[DONE]") || json.contains("\"error\"") {
+                    continue;
+                }
+                if serde_json::from_str::<ChatCompletionChunk>(json).is_ok() {
+                    parsed += 1;
+                }
+            }
+        }
+        buffer = remaining.to_string();
+    }
+    parsed
+}
+
+fn parse(reads: Vec<Bytes>) -> usize {
+    let mut parser = SseParser::new();
+    reads.into_iter().map(|bytes| parser.push(bytes).len()).sum()
+}
+
+fn bench_sse(c: &mut Criterion) {
+    let body = transcript(500);
+    let mut group = c.benchmark_group("sse");
+    group.measurement_time(Duration::from_secs(3));
+    group.throughput(Throughput::Bytes(body.len() as u64));
+    for size in [61, 1460, 16384] {
+        let input = reads(&body, size);
+        assert_eq!(legacy_parse(input.clone()), parse(input.clone()));
+        for (name, parse) in [("string", legacy_parse as fn(Vec<Bytes>) -> usize), ("bytes", parse)] {
+            let (count, bytes) = allocations(parse, input.clone());
+            println!("sse/{}/{}: {} allocations, {} bytes allocated", name, size, count, bytes);
+        }
+        group.bench_function(format!("string/{}", size), |b| {
+            b.iter_batched(|| input.clone(), |r| black_box(legacy_parse(r)), BatchSize::SmallInput)
+        });
+        group.bench_function(format!("bytes/{}", size), |b| {
+            b.iter_batched(|| input.clone(), |r| black_box(parse(r)), BatchSize::SmallInput)
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_sse);
+criterion_main!(benches);
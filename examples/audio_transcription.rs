@@ -1,7 +1,7 @@
 // examples/audio_transcription.rs
 // Audio transcription and translation example
 
-use groqai::{GroqClient, AudioTranscriptionRequest, AudioTranslationRequest};
+use groqai::{GroqClient, AudioResponseFormat, AudioTranscriptionRequest, AudioTranslationRequest};
 use std::path::PathBuf;
 
 #[tokio::main]
@@ -16,7 +16,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         model: "whisper-large-v3".to_string(),
         language: Some("en".to_string()),
         prompt: None,
-        response_format: Some("json".to_string()),
+        response_format: Some(AudioResponseFormat::Json),
         temperature: Some(0.0),
         timestamp_granularities: None,
     };
@@ -32,7 +32,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         url: None,
         model: "whisper-large-v3".to_string(),
         prompt: None,
-        response_format: Some("json".to_string()),
+        response_format: Some(AudioResponseFormat::Json),
         temperature: Some(0.0),
     };
     
@@ -1,7 +1,7 @@
 // examples/batch_processing.rs
 // Batch processing example
 
-use groqai::{GroqClient, BatchCreateRequest};
+use groqai::{GroqClient, BatchCreateRequest, BatchEndpoint, CompletionWindow};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -11,8 +11,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Create a batch job
     let request = BatchCreateRequest {
         input_file_id: "file_abc123".to_string(),
-        endpoint: "/chat/completions".to_string(),
-        completion_window: "24h".to_string(),
+        endpoint: BatchEndpoint::ChatCompletions,
+        completion_window: CompletionWindow::Hours24,
         metadata: None,
     };
     
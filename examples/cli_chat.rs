@@ -1,11 +1,11 @@
-/// examples/cli_chat.rs
-
-/// CLI chat application example using the groq llama-3.3-70b-versatile model
-/// Enable streaming conversation with the --stream command line argument
-/// Read the proxy server URL from the environment variable PROXY_URL
-/// CLI聊天应用示例，使用 llama-3.3-70b-versatile 模型
-/// 使用 --stream 命令行参数开启流式对话
-/// 使用环境变量PROXY_URL读取代理服务器路径
+//! examples/cli_chat.rs
+//!
+//! CLI chat application example using the groq llama-3.3-70b-versatile model
+//! Enable streaming conversation with the --stream command line argument
+//! Read the proxy server URL from the environment variable PROXY_URL
+//! CLI聊天应用示例，使用 llama-3.3-70b-versatile 模型
+//! 使用 --stream 命令行参数开启流式对话
+//! 使用环境变量PROXY_URL读取代理服务器路径
 
 use groqai::client::GroqClientBuilder;
 use groqai::error::GroqError;
@@ -85,19 +85,14 @@ async fn main() -> Result<(), GroqError> {
                         consecutive_errors = 0;
                         
                         if let Some(choice) = chunk.choices.first() {
-                            if let Some(content) = &choice.delta.content {
-                                match content {
-                                    groqai::types::MessageContent::Text(text) => {
-                                        if first_chunk {
-                                            print!("\x1b[33mAI says: \x1b[0m");
-                                            first_chunk = false;
-                                        }
-                                        print!("{}", text);
-                                        ai_response.push_str(text);
-                                        io::stdout().flush().unwrap();
-                                    },
-                                    _ => {}
+                            if let Some(groqai::types::MessageContent::Text(text)) = &choice.delta.content {
+                                if first_chunk {
+                                    print!("\x1b[33mAI says: \x1b[0m");
+                                    first_chunk = false;
                                 }
+                                print!("{}", text);
+                                ai_response.push_str(text);
+                                io::stdout().flush().unwrap();
                             }
                         }
                     }
@@ -66,7 +66,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 async fn demo_chat(_client: &GroqClient, method: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let _messages = vec![
+    let _messages = [
         ChatMessage::new_text(Role::User, "Say hello in one word")
     ];
 
@@ -74,10 +74,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n=== Quick Setup Commands ===");
     println!("# Get your API key from: https://console.groq.com/");
     println!("export GROQ_API_KEY=\"gsk_your_actual_api_key_here\"");
-    println!("");
+    println!();
     println!("# Optional: Set proxy if needed");
     println!("export GROQ_PROXY_URL=\"http://your-proxy:8080\"");
-    println!("");
+    println!();
     println!("# Then use in your code:");
     println!("use groqai::prelude::*;");
     println!("let client = GroqClient::new()?;");
@@ -38,7 +38,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("   ✓ Client created successfully");
             
             // All common types are available without qualification
-            let _messages = vec![
+            let _messages = [
                 ChatMessage::new_text(Role::User, "Hello")
             ];
             
@@ -24,7 +24,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     
     // Get specific model details
-    match client.models().retrieve("llama-3.1-70b-versatile".to_string()).await {
+    match client.models().retrieve("llama-3.1-70b-versatile").await {
         Ok(model) => {
             println!("\nModel details for {}:", model.id);
             println!("Context window: {} tokens", model.context_window);
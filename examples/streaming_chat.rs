@@ -26,13 +26,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         match chunk {
             Ok(chunk) => {
                 if let Some(choice) = chunk.choices.first() {
-                    if let Some(content) = &choice.delta.content {
-                        match content {
-                            groqai::types::MessageContent::Text(text) => {
-                                print!("{}", text);
-                            }
-                            _ => {}
-                        }
+                    if let Some(groqai::types::MessageContent::Text(text)) = &choice.delta.content {
+                        print!("{}", text);
                     }
                 }
             }
@@ -0,0 +1,37 @@
+//! Feeds arbitrary bytes to `SseParser` in reads cut at fuzzer-chosen positions
+//!
+//! Run with `cargo +nightly fuzz run sse_parser` from the repository root.
+//! The first byte picks the read size; the parse must not panic and must give
+//! the same results as parsing the whole input in one read.
+
+#![no_main]
+
+use bytes::Bytes;
+use groqai::sse::SseParser;
+use libfuzzer_sys::fuzz_target;
+
+/// Describes the results of parsing `reads`, up to and including the first error
+fn parse<'a>(reads: impl Iterator<Item = &'a [u8]>) -> Vec<String> {
+    let mut parser = SseParser::new();
+    let mut results = Vec::new();
+    for read in reads {
+        for result in parser.push(Bytes::copy_from_slice(read)) {
+            match result {
+                Ok(chunk) => results.push(format!("{:?}", chunk)),
+                Err(e) => {
+                    results.push(e.to_string());
+                    return results;
+                }
+            }
+        }
+    }
+    results
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&size, body)) = data.split_first() else {
+        return;
+    };
+    let size = usize::from(size).max(1);
+    assert_eq!(parse(body.chunks(size)), parse(std::iter::once(body)));
+});
@@ -1,12 +1,20 @@
-//! Audio transcription and translation API implementation
+//! Audio transcription, translation and speech synthesis API implementation
 //! 
-//! 音频转录和翻译 API 实现，支持多种音频格式处理
+//! 音频转录、翻译和语音合成 API 实现，支持多种音频格式处理
 
 use crate::client::GroqClient;
+use crate::endpoint::Endpoint;
 use crate::error::GroqError;
-use crate::types::{Transcription, Translation};
-use serde::Serialize;
+use crate::queue::Priority;
+use crate::transport::{ByteStream, Request, RequestOptions, UploadProgress};
+use crate::text::estimate_tokens;
+use crate::types::{ChatMessage, Role, Transcription, Translation, Usage};
+use bytes::Bytes;
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 /// Request structure for audio transcription
 /// 
@@ -15,8 +23,8 @@ use std::path::PathBuf;
 /// 
 /// # Examples
 /// 
-/// ```rust,no_run
-/// use groqai::api::audio::AudioTranscriptionRequest;
+/// ```rust
+/// use groqai::api::audio::{AudioResponseFormat, AudioTranscriptionRequest};
 /// use std::path::PathBuf;
 /// 
 /// let request = AudioTranscriptionRequest {
@@ -25,12 +33,13 @@ use std::path::PathBuf;
 ///     model: "whisper-large-v3".to_string(),
 ///     language: Some("en".to_string()),
 ///     prompt: None,
-///     response_format: Some("json".to_string()),
+///     response_format: Some(AudioResponseFormat::Json),
 ///     temperature: Some(0.0),
 ///     timestamp_granularities: None,
 /// };
 /// ```
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, Default)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 pub struct AudioTranscriptionRequest {
     /// Path to the audio file to transcribe
     pub file: Option<PathBuf>,
@@ -46,15 +55,46 @@ pub struct AudioTranscriptionRequest {
     pub prompt: Option<String>,
     /// Format of the response (json, text, srt, verbose_json, vtt)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub response_format: Option<String>,
+    pub response_format: Option<AudioResponseFormat>,
     /// Sampling temperature between 0 and 1
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
     /// Timestamp granularities (word, segment)
+    /// 
+    /// Timestamps are only returned with `verbose_json`, which is requested
+    /// when `response_format` is `None`. Word timestamps are parsed into
+    /// [`Transcription::words`].
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timestamp_granularities: Option<Vec<String>>,
 }
 
+impl AudioTranscriptionRequest {
+    /// Checks the request before it is sent
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError::InvalidMessage` unless exactly one of `file` and
+    /// `url` is set, or if timestamp granularities are requested with a
+    /// response format other than `verbose_json`.
+    pub fn validate(&self) -> Result<(), GroqError> {
+        validate_source(self.file.as_ref(), self.url.as_deref())?;
+        match &self.response_format {
+            Some(format) if self.wants_timestamps() && *format != AudioResponseFormat::VerboseJson => {
+                Err(GroqError::InvalidMessage(format!(
+                    "Timestamp granularities require the verbose_json response format, not {}",
+                    format
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns true if any timestamp granularity is requested
+    fn wants_timestamps(&self) -> bool {
+        self.timestamp_granularities.as_ref().is_some_and(|g| !g.is_empty())
+    }
+}
+
 /// Request structure for audio translation
 /// 
 /// This struct contains parameters for translating audio files to English text.
@@ -62,8 +102,8 @@ pub struct AudioTranscriptionRequest {
 /// 
 /// # Examples
 /// 
-/// ```rust,no_run
-/// use groqai::api::audio::AudioTranslationRequest;
+/// ```rust
+/// use groqai::api::audio::{AudioResponseFormat, AudioTranslationRequest};
 /// use std::path::PathBuf;
 /// 
 /// let request = AudioTranslationRequest {
@@ -71,11 +111,12 @@ pub struct AudioTranscriptionRequest {
 ///     url: None,
 ///     model: "whisper-large-v3".to_string(),
 ///     prompt: None,
-///     response_format: Some("json".to_string()),
+///     response_format: Some(AudioResponseFormat::Json),
 ///     temperature: Some(0.0),
 /// };
 /// ```
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, Default)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 pub struct AudioTranslationRequest {
     /// Path to the audio file to translate
     pub file: Option<PathBuf>,
@@ -88,12 +129,501 @@ pub struct AudioTranslationRequest {
     pub prompt: Option<String>,
     /// Format of the response (json, text, srt, verbose_json, vtt)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub response_format: Option<String>,
+    pub response_format: Option<AudioResponseFormat>,
     /// Sampling temperature between 0 and 1
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
 }
 
+impl AudioTranslationRequest {
+    /// Checks the request before it is sent
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError::InvalidMessage` unless exactly one of `file` and
+    /// `url` is set.
+    pub fn validate(&self) -> Result<(), GroqError> {
+        validate_source(self.file.as_ref(), self.url.as_deref())
+    }
+}
+
+/// Checks that the audio comes from either a file or a URL
+fn validate_source(file: Option<&PathBuf>, url: Option<&str>) -> Result<(), GroqError> {
+    match (file, url) {
+        (Some(_), Some(_)) => Err(GroqError::InvalidMessage(
+            "Set either `file` or `url` for the audio, not both".to_string(),
+        )),
+        (None, None) => Err(GroqError::InvalidMessage(
+            "Set `file` or `url` to the audio to process".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Response format of a transcription or translation
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use groqai::api::audio::AudioResponseFormat;
+/// 
+/// assert_eq!(AudioResponseFormat::VerboseJson.as_str(), "verbose_json");
+/// assert_eq!(AudioResponseFormat::from("srt"), AudioResponseFormat::Srt);
+/// assert!(AudioResponseFormat::Vtt.is_text());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[serde(into = "String", from = "String")]
+pub enum AudioResponseFormat {
+    /// `json`, the API default: the text only
+    Json,
+    /// `text`: the transcript as plain text
+    Text,
+    /// `srt`: SubRip subtitles
+    Srt,
+    /// `verbose_json`: the text with language, duration, segments and words
+    VerboseJson,
+    /// `vtt`: WebVTT subtitles
+    Vtt,
+    /// Any other format, sent as-is
+    Other(String),
+}
+
+impl AudioResponseFormat {
+    /// Returns the value sent to the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            AudioResponseFormat::Json => "json",
+            AudioResponseFormat::Text => "text",
+            AudioResponseFormat::Srt => "srt",
+            AudioResponseFormat::VerboseJson => "verbose_json",
+            AudioResponseFormat::Vtt => "vtt",
+            AudioResponseFormat::Other(format) => format,
+        }
+    }
+
+    /// Returns true if the response body is plain text rather than JSON
+    pub fn is_text(&self) -> bool {
+        matches!(self, AudioResponseFormat::Text | AudioResponseFormat::Srt | AudioResponseFormat::Vtt)
+    }
+}
+
+impl fmt::Display for AudioResponseFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for AudioResponseFormat {
+    fn from(format: &str) -> Self {
+        match format {
+            "json" => AudioResponseFormat::Json,
+            "text" => AudioResponseFormat::Text,
+            "srt" => AudioResponseFormat::Srt,
+            "verbose_json" => AudioResponseFormat::VerboseJson,
+            "vtt" => AudioResponseFormat::Vtt,
+            _ => AudioResponseFormat::Other(format.to_string()),
+        }
+    }
+}
+
+impl From<String> for AudioResponseFormat {
+    fn from(format: String) -> Self {
+        AudioResponseFormat::from(format.as_str())
+    }
+}
+
+impl From<AudioResponseFormat> for String {
+    fn from(format: AudioResponseFormat) -> Self {
+        match format {
+            AudioResponseFormat::Other(format) => format,
+            known => known.as_str().to_string(),
+        }
+    }
+}
+
+/// Returns true for response formats whose body is plain text rather than JSON
+pub(crate) fn is_text_format(format: &str) -> bool {
+    AudioResponseFormat::from(format).is_text()
+}
+
+/// A transcription in the requested response format
+/// 
+/// Returned by [`AudioRequestBuilder::transcribe_output`].
+#[derive(Debug, Clone)]
+pub enum TranscriptionOutput {
+    /// `json` or `verbose_json`
+    Json(Transcription),
+    /// `text`: the transcript without metadata
+    Text(String),
+    /// `srt`: SubRip subtitles
+    Srt(String),
+    /// `vtt`: WebVTT subtitles
+    Vtt(String),
+}
+
+impl TranscriptionOutput {
+    fn from_response(format: Option<&AudioResponseFormat>, response: serde_json::Value) -> Result<Self, GroqError> {
+        match (format, response) {
+            (Some(AudioResponseFormat::Srt), serde_json::Value::String(srt)) => Ok(TranscriptionOutput::Srt(srt)),
+            (Some(AudioResponseFormat::Vtt), serde_json::Value::String(vtt)) => Ok(TranscriptionOutput::Vtt(vtt)),
+            (_, serde_json::Value::String(text)) => Ok(TranscriptionOutput::Text(text)),
+            (_, response) => Ok(TranscriptionOutput::Json(serde_json::from_value(response)?)),
+        }
+    }
+
+    /// Returns the transcript text, or the subtitle file for `srt` and `vtt`
+    pub fn text(&self) -> &str {
+        match self {
+            TranscriptionOutput::Json(transcription) => &transcription.text,
+            TranscriptionOutput::Text(text) | TranscriptionOutput::Srt(text) | TranscriptionOutput::Vtt(text) => text,
+        }
+    }
+
+    /// Converts the output into a [`Transcription`]
+    /// 
+    /// Plain text and subtitle outputs become a transcription with only `text` set.
+    pub fn into_transcription(self) -> Transcription {
+        match self {
+            TranscriptionOutput::Json(transcription) => transcription,
+            TranscriptionOutput::Text(text) | TranscriptionOutput::Srt(text) | TranscriptionOutput::Vtt(text) => {
+                text_response(text)
+            }
+        }
+    }
+}
+
+/// Builds a response with only `text` set from a plain text body
+fn text_response<T: serde::de::DeserializeOwned>(text: String) -> T {
+    serde_json::from_value(serde_json::json!({ "text": text })).expect("text is the only required field")
+}
+
+/// Sample rates in Hz the speech endpoint can produce
+pub const SPEECH_SAMPLE_RATES: &[u32] = &[8_000, 16_000, 22_050, 24_000, 32_000, 44_100, 48_000];
+
+/// A voice for speech synthesis
+/// 
+/// The English voices work with `playai-tts`, the Arabic ones with
+/// `playai-tts-arabic`.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use groqai::api::audio::Voice;
+/// 
+/// assert_eq!(Voice::Fritz.as_str(), "Fritz-PlayAI");
+/// assert_eq!(Voice::from("Amira-PlayAI"), Voice::Amira);
+/// assert!(Voice::Amira.is_arabic());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[serde(into = "String", from = "String")]
+pub enum Voice {
+    Arista,
+    Atlas,
+    Basil,
+    Briggs,
+    Calum,
+    Celeste,
+    Cheyenne,
+    Chip,
+    Cillian,
+    Deedee,
+    #[default]
+    Fritz,
+    Gail,
+    Indigo,
+    Mamaw,
+    Mason,
+    Mikail,
+    Mitch,
+    Quinn,
+    Thunder,
+    /// Arabic
+    Ahmad,
+    /// Arabic
+    Amira,
+    /// Arabic
+    Khalid,
+    /// Arabic
+    Nasser,
+    /// Any other voice, sent as-is
+    Other(String),
+}
+
+impl Voice {
+    /// Returns the voice name sent to the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            Voice::Arista => "Arista-PlayAI",
+            Voice::Atlas => "Atlas-PlayAI",
+            Voice::Basil => "Basil-PlayAI",
+            Voice::Briggs => "Briggs-PlayAI",
+            Voice::Calum => "Calum-PlayAI",
+            Voice::Celeste => "Celeste-PlayAI",
+            Voice::Cheyenne => "Cheyenne-PlayAI",
+            Voice::Chip => "Chip-PlayAI",
+            Voice::Cillian => "Cillian-PlayAI",
+            Voice::Deedee => "Deedee-PlayAI",
+            Voice::Fritz => "Fritz-PlayAI",
+            Voice::Gail => "Gail-PlayAI",
+            Voice::Indigo => "Indigo-PlayAI",
+            Voice::Mamaw => "Mamaw-PlayAI",
+            Voice::Mason => "Mason-PlayAI",
+            Voice::Mikail => "Mikail-PlayAI",
+            Voice::Mitch => "Mitch-PlayAI",
+            Voice::Quinn => "Quinn-PlayAI",
+            Voice::Thunder => "Thunder-PlayAI",
+            Voice::Ahmad => "Ahmad-PlayAI",
+            Voice::Amira => "Amira-PlayAI",
+            Voice::Khalid => "Khalid-PlayAI",
+            Voice::Nasser => "Nasser-PlayAI",
+            Voice::Other(voice) => voice,
+        }
+    }
+
+    /// Returns true for the voices of `playai-tts-arabic`
+    pub fn is_arabic(&self) -> bool {
+        matches!(self, Voice::Ahmad | Voice::Amira | Voice::Khalid | Voice::Nasser)
+    }
+}
+
+impl fmt::Display for Voice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for Voice {
+    fn from(voice: &str) -> Self {
+        const KNOWN: &[Voice] = &[
+            Voice::Arista,
+            Voice::Atlas,
+            Voice::Basil,
+            Voice::Briggs,
+            Voice::Calum,
+            Voice::Celeste,
+            Voice::Cheyenne,
+            Voice::Chip,
+            Voice::Cillian,
+            Voice::Deedee,
+            Voice::Fritz,
+            Voice::Gail,
+            Voice::Indigo,
+            Voice::Mamaw,
+            Voice::Mason,
+            Voice::Mikail,
+            Voice::Mitch,
+            Voice::Quinn,
+            Voice::Thunder,
+            Voice::Ahmad,
+            Voice::Amira,
+            Voice::Khalid,
+            Voice::Nasser,
+        ];
+        KNOWN
+            .iter()
+            .find(|known| known.as_str() == voice)
+            .cloned()
+            .unwrap_or_else(|| Voice::Other(voice.to_string()))
+    }
+}
+
+impl From<String> for Voice {
+    fn from(voice: String) -> Self {
+        Voice::from(voice.as_str())
+    }
+}
+
+impl From<Voice> for String {
+    fn from(voice: Voice) -> Self {
+        match voice {
+            Voice::Other(voice) => voice,
+            known => known.as_str().to_string(),
+        }
+    }
+}
+
+/// Container format of synthesized speech
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use groqai::api::audio::AudioFormat;
+/// 
+/// assert_eq!(AudioFormat::Wav.as_str(), "wav");
+/// assert_eq!(AudioFormat::from("mp3"), AudioFormat::Mp3);
+/// assert_eq!(AudioFormat::Mulaw.sample_rates(), &[8_000]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[serde(into = "String", from = "String")]
+pub enum AudioFormat {
+    /// `flac`
+    Flac,
+    /// `mp3`, the API default
+    Mp3,
+    /// `mulaw`, 8 kHz telephony audio
+    Mulaw,
+    /// `ogg`
+    Ogg,
+    /// `opus`
+    Opus,
+    /// `wav`
+    Wav,
+    /// Any other format, sent as-is
+    Other(String),
+}
+
+impl AudioFormat {
+    /// Returns the value sent to the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            AudioFormat::Flac => "flac",
+            AudioFormat::Mp3 => "mp3",
+            AudioFormat::Mulaw => "mulaw",
+            AudioFormat::Ogg => "ogg",
+            AudioFormat::Opus => "opus",
+            AudioFormat::Wav => "wav",
+            AudioFormat::Other(format) => format,
+        }
+    }
+
+    /// Returns the MIME type of the audio
+    pub fn mime_type(&self) -> &str {
+        match self {
+            AudioFormat::Flac => "audio/flac",
+            AudioFormat::Mp3 => "audio/mpeg",
+            AudioFormat::Mulaw => "audio/basic",
+            AudioFormat::Ogg | AudioFormat::Opus => "audio/ogg",
+            AudioFormat::Wav => "audio/wav",
+            AudioFormat::Other(_) => "application/octet-stream",
+        }
+    }
+
+    /// Returns the sample rates in Hz this format can be produced at
+    pub fn sample_rates(&self) -> &'static [u32] {
+        match self {
+            AudioFormat::Mulaw => &SPEECH_SAMPLE_RATES[..1],
+            _ => SPEECH_SAMPLE_RATES,
+        }
+    }
+}
+
+impl fmt::Display for AudioFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for AudioFormat {
+    fn from(format: &str) -> Self {
+        match format {
+            "flac" => AudioFormat::Flac,
+            "mp3" => AudioFormat::Mp3,
+            "mulaw" => AudioFormat::Mulaw,
+            "ogg" => AudioFormat::Ogg,
+            "opus" => AudioFormat::Opus,
+            "wav" => AudioFormat::Wav,
+            _ => AudioFormat::Other(format.to_string()),
+        }
+    }
+}
+
+impl From<String> for AudioFormat {
+    fn from(format: String) -> Self {
+        AudioFormat::from(format.as_str())
+    }
+}
+
+impl From<AudioFormat> for String {
+    fn from(format: AudioFormat) -> Self {
+        match format {
+            AudioFormat::Other(format) => format,
+            known => known.as_str().to_string(),
+        }
+    }
+}
+
+/// Request structure for speech synthesis
+/// 
+/// This struct contains parameters for turning text into spoken audio.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use groqai::api::audio::{AudioFormat, AudioSpeechRequest, Voice};
+/// 
+/// let request = AudioSpeechRequest {
+///     model: "playai-tts".to_string(),
+///     input: "Your order has shipped.".to_string(),
+///     voice: Voice::Fritz,
+///     response_format: Some(AudioFormat::Wav),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+pub struct AudioSpeechRequest {
+    /// Model to use for synthesis (e.g., "playai-tts")
+    pub model: String,
+    /// Text to speak
+    pub input: String,
+    /// Voice to speak with
+    pub voice: Voice,
+    /// Audio format; the API defaults to mp3
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<AudioFormat>,
+    /// Sample rate of the audio in Hz, one of [`SPEECH_SAMPLE_RATES`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample_rate: Option<u32>,
+    /// Speaking speed, from 0.5 to 5.0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f32>,
+}
+
+impl AudioSpeechRequest {
+    /// Checks the request before it is sent
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError::InvalidMessage` if the input is empty, the sample
+    /// rate is not available for the format, the speed is outside 0.5 to 5.0,
+    /// or an Arabic voice is combined with an English model or vice versa.
+    pub fn validate(&self) -> Result<(), GroqError> {
+        if self.input.trim().is_empty() {
+            return Err(GroqError::InvalidMessage("Speech input must not be empty".to_string()));
+        }
+        if let Some(rate) = self.sample_rate {
+            let format = self.response_format.clone().unwrap_or(AudioFormat::Mp3);
+            if !matches!(format, AudioFormat::Other(_)) && !format.sample_rates().contains(&rate) {
+                return Err(GroqError::InvalidMessage(format!(
+                    "Sample rate {} Hz is not available for {}; use one of {:?}",
+                    rate,
+                    format,
+                    format.sample_rates()
+                )));
+            }
+        }
+        if let Some(speed) = self.speed {
+            if !(0.5..=5.0).contains(&speed) {
+                return Err(GroqError::InvalidMessage(format!(
+                    "Speed must be between 0.5 and 5.0, got {}",
+                    speed
+                )));
+            }
+        }
+        let arabic_model = self.model.ends_with("-arabic");
+        if !matches!(self.voice, Voice::Other(_)) && self.voice.is_arabic() != arabic_model {
+            return Err(GroqError::InvalidMessage(format!(
+                "Voice {} cannot be used with model {}",
+                self.voice, self.model
+            )));
+        }
+        Ok(())
+    }
+}
+
 /// Builder for audio processing requests
 /// 
 /// This builder provides methods for transcribing and translating audio files
@@ -101,13 +631,14 @@ pub struct AudioTranslationRequest {
 /// 
 /// # Examples
 /// 
-/// ```rust,no_run
+/// ```rust
 /// use groqai::{GroqClientBuilder, AudioTranscriptionRequest};
 /// use std::path::PathBuf;
 /// 
 /// # #[tokio::main]
 /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+/// # let client = groqai::doctest::client();
 /// 
 /// let request = AudioTranscriptionRequest {
 ///     file: Some(PathBuf::from("audio.mp3")),
@@ -127,6 +658,29 @@ pub struct AudioTranslationRequest {
 /// ```
 pub struct AudioRequestBuilder<'a> {
     client: &'a GroqClient,
+    options: RequestOptions,
+    transcription_model: String,
+    translation_model: String,
+}
+
+/// Whisper model used for translations unless another is set
+const DEFAULT_TRANSLATION_MODEL: &str = "whisper-large-v3";
+
+/// Result of [`AudioRequestBuilder::transcribe_and_translate`]
+#[derive(Debug, Clone)]
+pub struct TranslatedTranscript {
+    /// The transcription in the spoken language
+    pub transcript: Transcription,
+    /// The transcript translated into the target language
+    pub translation: String,
+    /// The language the transcript was translated into
+    pub target_language: String,
+    /// Time spent transcribing
+    pub transcription_time: Duration,
+    /// Time spent translating
+    pub translation_time: Duration,
+    /// Token usage of the translation request
+    pub translation_usage: Usage,
 }
 
 impl<'a> AudioRequestBuilder<'a> {
@@ -136,7 +690,170 @@ impl<'a> AudioRequestBuilder<'a> {
     /// 
     /// * `client` - Reference to the GroqClient
     pub fn new(client: &'a GroqClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            options: RequestOptions::default(),
+            transcription_model: "whisper-large-v3".to_string(),
+            translation_model: "llama-3.3-70b-versatile".to_string(),
+        }
+    }
+
+    /// Adds a header sent with every request made by this builder
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - The header name
+    /// * `value` - The header value
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options = self.options.header(name, value);
+        self
+    }
+
+    /// Sets the priority of this builder's requests in the client's request queue
+    /// 
+    /// Requests are [`Priority::Interactive`] by default. Without a queue (see
+    /// `GroqClientBuilder::request_queue`) it has no effect.
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.options = self.options.priority(priority);
+        self
+    }
+
+    /// Calls `callback` with the bytes sent so far while uploading the audio file of a transcription or translation
+    /// 
+    /// Files are streamed from disk, so large uploads do not need to fit in
+    /// memory; the callback runs after each 64 KiB read.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `callback` - Receives the bytes sent and the file size
+    pub fn upload_progress(mut self, callback: impl Fn(UploadProgress) + Send + Sync + 'static) -> Self {
+        self.options = self.options.upload_progress(callback);
+        self
+    }
+
+    /// Sends the audio file with `mime` as its content type
+    /// 
+    /// By default the content type is taken from the file extension, or from
+    /// the first bytes of the file if the extension is not known. Gateways that
+    /// check the type of uploads may need it set explicitly.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mime` - The content type, e.g. `audio/mpeg`
+    pub fn file_content_type(mut self, mime: impl Into<String>) -> Self {
+        self.options = self.options.file_content_type(mime);
+        self
+    }
+
+    /// Sets the Whisper model used by `transcribe_and_translate`
+    /// 
+    /// Defaults to `whisper-large-v3`.
+    pub fn transcription_model(mut self, model: impl Into<String>) -> Self {
+        self.transcription_model = model.into();
+        self
+    }
+
+    /// Sets the chat model used by `transcribe_and_translate`
+    /// 
+    /// Defaults to `llama-3.3-70b-versatile`.
+    pub fn translation_model(mut self, model: impl Into<String>) -> Self {
+        self.translation_model = model.into();
+        self
+    }
+
+    /// Starts a transcription of a local audio file
+    /// 
+    /// The model defaults to the builder's
+    /// [`transcription_model`](AudioRequestBuilder::transcription_model).
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::{AudioResponseFormat, GroqClient};
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # groqai::doctest::set_api_key();
+    /// let client = GroqClient::new()?;
+    /// # let client = groqai::doctest::client();
+    /// let transcription = client
+    ///     .audio()
+    ///     .transcribe_file("meeting.mp3")
+    ///     .model("whisper-large-v3-turbo")
+    ///     .language("en")
+    ///     .response_format(AudioResponseFormat::VerboseJson)
+    ///     .send()
+    ///     .await?;
+    /// println!("{}", transcription.text);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn transcribe_file(self, file: impl Into<PathBuf>) -> TranscriptionBuilder<'a> {
+        let request = AudioTranscriptionRequest {
+            file: Some(file.into()),
+            model: self.transcription_model.clone(),
+            ..Default::default()
+        };
+        TranscriptionBuilder { audio: self, request }
+    }
+
+    /// Starts a transcription of audio the API downloads from `url`
+    /// 
+    /// The model defaults to the builder's
+    /// [`transcription_model`](AudioRequestBuilder::transcription_model).
+    pub fn transcribe_url(self, url: impl Into<String>) -> TranscriptionBuilder<'a> {
+        let request = AudioTranscriptionRequest {
+            url: Some(url.into()),
+            model: self.transcription_model.clone(),
+            ..Default::default()
+        };
+        TranscriptionBuilder { audio: self, request }
+    }
+
+    /// Starts a translation of a local audio file into English
+    /// 
+    /// The model defaults to `whisper-large-v3`.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClient;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # groqai::doctest::set_api_key();
+    /// let client = GroqClient::new()?;
+    /// # let client = groqai::doctest::client();
+    /// let translation = client
+    ///     .audio()
+    ///     .translate_file("entrevista.mp3")
+    ///     .prompt("An interview about renewable energy.")
+    ///     .temperature(0.0)
+    ///     .send()
+    ///     .await?;
+    /// println!("{}", translation.text);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn translate_file(self, file: impl Into<PathBuf>) -> TranslationBuilder<'a> {
+        let request = AudioTranslationRequest {
+            file: Some(file.into()),
+            model: DEFAULT_TRANSLATION_MODEL.to_string(),
+            ..Default::default()
+        };
+        TranslationBuilder { audio: self, request }
+    }
+
+    /// Starts a translation into English of audio the API downloads from `url`
+    /// 
+    /// The model defaults to `whisper-large-v3`.
+    pub fn translate_url(self, url: impl Into<String>) -> TranslationBuilder<'a> {
+        let request = AudioTranslationRequest {
+            url: Some(url.into()),
+            model: DEFAULT_TRANSLATION_MODEL.to_string(),
+            ..Default::default()
+        };
+        TranslationBuilder { audio: self, request }
     }
 
     /// Transcribes audio to text
@@ -151,17 +868,20 @@ impl<'a> AudioRequestBuilder<'a> {
     /// 
     /// # Errors
     /// 
-    /// Returns `GroqError` if the transcription fails
+    /// Returns `GroqError` if the transcription fails, or
+    /// `GroqError::InvalidMessage` if the request is invalid (see
+    /// [`AudioTranscriptionRequest::validate`]).
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
-    /// use groqai::{GroqClientBuilder, AudioTranscriptionRequest};
+    /// ```rust
+    /// use groqai::{GroqClientBuilder, AudioResponseFormat, AudioTranscriptionRequest};
     /// use std::path::PathBuf;
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
     /// 
     /// let request = AudioTranscriptionRequest {
     ///     file: Some(PathBuf::from("meeting.mp3")),
@@ -169,20 +889,78 @@ impl<'a> AudioRequestBuilder<'a> {
     ///     model: "whisper-large-v3".to_string(),
     ///     language: Some("en".to_string()),
     ///     prompt: Some("This is a business meeting transcript.".to_string()),
-    ///     response_format: Some("json".to_string()),
+    ///     response_format: Some(AudioResponseFormat::VerboseJson),
     ///     temperature: Some(0.0),
     ///     timestamp_granularities: Some(vec!["word".to_string()]),
     /// };
     /// 
     /// let result = client.audio().transcribe(request).await?;
     /// println!("Transcribed text: {}", result.text);
+    /// for word in &result.words {
+    ///     println!("{:>6.2}s {}", word.start, word.word);
+    /// }
     /// # Ok(())
     /// # }
     /// ```
     pub async fn transcribe(self, req: AudioTranscriptionRequest) -> Result<Transcription, GroqError> {
-        let body = serde_json::to_value(req)?;
-        let response = self.client.transport.post_multipart("audio/transcriptions", &body).await?;
-        serde_json::from_value(response).map_err(GroqError::from)
+        self.transcribe_output(req).await.map(TranscriptionOutput::into_transcription)
+    }
+
+    /// Transcribes audio in any response format, including `text`, `srt` and `vtt`
+    /// 
+    /// [`transcribe`](AudioRequestBuilder::transcribe) puts plain text and
+    /// subtitle responses into `Transcription::text`; this method tells them apart.
+    /// 
+    /// Like chat completions, the request goes through the client's rate
+    /// limiter, and `429` and `503` responses are retried with backoff,
+    /// honoring `retry-after`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `req` - The transcription request parameters
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError` if the transcription fails, or
+    /// `GroqError::InvalidMessage` if the request is invalid (see
+    /// [`AudioTranscriptionRequest::validate`]).
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::{GroqClient, AudioResponseFormat, AudioTranscriptionRequest, TranscriptionOutput};
+    /// use std::path::PathBuf;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # groqai::doctest::set_api_key();
+    /// let client = GroqClient::new()?;
+    /// # let client = groqai::doctest::client();
+    /// # groqai::doctest::scratch_dir();
+    /// let request = AudioTranscriptionRequest {
+    ///     file: Some(PathBuf::from("talk.mp3")),
+    ///     model: "whisper-large-v3".to_string(),
+    ///     response_format: Some(AudioResponseFormat::Srt),
+    ///     ..Default::default()
+    /// };
+    /// 
+    /// if let TranscriptionOutput::Srt(subtitles) = client.audio().transcribe_output(request).await? {
+    ///     std::fs::write("talk.srt", subtitles)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn transcribe_output(self, mut req: AudioTranscriptionRequest) -> Result<TranscriptionOutput, GroqError> {
+        req.validate()?;
+        if req.wants_timestamps() {
+            req.response_format = Some(AudioResponseFormat::VerboseJson);
+        }
+        let format = req.response_format.clone();
+        let request = Request::post(Endpoint::AudioTranscriptions)
+            .multipart(serde_json::to_value(req)?)
+            .options(self.options);
+        let response = self.client.execute_with_retry(request).await?;
+        TranscriptionOutput::from_response(format.as_ref(), response.body)
     }
 
     /// Translates audio to English text
@@ -195,26 +973,32 @@ impl<'a> AudioRequestBuilder<'a> {
     /// 
     /// A `Translation` containing the translated text and metadata
     /// 
+    /// `429` and `503` responses are retried as for
+    /// [`transcribe_output`](AudioRequestBuilder::transcribe_output).
+    /// 
     /// # Errors
     /// 
-    /// Returns `GroqError` if the translation fails
+    /// Returns `GroqError` if the translation fails, or
+    /// `GroqError::InvalidMessage` if the request is invalid (see
+    /// [`AudioTranslationRequest::validate`]).
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
-    /// use groqai::{GroqClientBuilder, AudioTranslationRequest};
+    /// ```rust
+    /// use groqai::{GroqClientBuilder, AudioResponseFormat, AudioTranslationRequest};
     /// use std::path::PathBuf;
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
     /// 
     /// let request = AudioTranslationRequest {
     ///     file: Some(PathBuf::from("spanish_interview.mp3")),
     ///     url: None,
     ///     model: "whisper-large-v3".to_string(),
     ///     prompt: Some("This is an interview transcript.".to_string()),
-    ///     response_format: Some("json".to_string()),
+    ///     response_format: Some(AudioResponseFormat::Json),
     ///     temperature: Some(0.0),
     /// };
     /// 
@@ -224,36 +1008,331 @@ impl<'a> AudioRequestBuilder<'a> {
     /// # }
     /// ```
     pub async fn translate(self, req: AudioTranslationRequest) -> Result<Translation, GroqError> {
-        let body = serde_json::to_value(req)?;
-        let response = self.client.transport.post_multipart("audio/translations", &body).await?;
-        serde_json::from_value(response).map_err(GroqError::from)
+        req.validate()?;
+        let request = Request::post(Endpoint::AudioTranslations)
+            .multipart(serde_json::to_value(req)?)
+            .options(self.options);
+        match self.client.execute_with_retry(request).await?.body {
+            serde_json::Value::String(text) => Ok(text_response(text)),
+            response => serde_json::from_value(response).map_err(GroqError::from),
+        }
     }
-}
 
-impl Default for AudioTranscriptionRequest {
-    fn default() -> Self {
-        Self {
-            file: None,
-            url: None,
-            model: String::new(),
-            language: None,
-            prompt: None,
-            response_format: None,
-            temperature: None,
-            timestamp_granularities: None,
+    /// Synthesizes speech from text
+    /// 
+    /// Waits for the whole file; use [`speech_stream`](AudioRequestBuilder::speech_stream)
+    /// to start playback earlier.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `req` - The speech request parameters
+    /// 
+    /// # Returns
+    /// 
+    /// The audio file in the requested format
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError` if the synthesis fails, or
+    /// `GroqError::InvalidMessage` if the request is invalid (see
+    /// [`AudioSpeechRequest::validate`]).
+    pub async fn speech(self, req: AudioSpeechRequest) -> Result<Bytes, GroqError> {
+        let chunks: Vec<Bytes> = self.speech_stream(req).await?.try_collect().await?;
+        Ok(chunks.concat().into())
+    }
+
+    /// Synthesizes speech from text, streaming the audio as it is generated
+    /// 
+    /// The stream yields the audio file in pieces, so playback can start
+    /// before synthesis is complete. Concatenated, the pieces are the same
+    /// file [`speech`](AudioRequestBuilder::speech) returns.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `req` - The speech request parameters
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError` if the request fails, or
+    /// `GroqError::InvalidMessage` if the request is invalid (see
+    /// [`AudioSpeechRequest::validate`]). Errors while the audio is being
+    /// received are yielded by the stream.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use futures::StreamExt;
+    /// use groqai::api::audio::{AudioFormat, AudioSpeechRequest, Voice};
+    /// use groqai::GroqClient;
+    /// use std::io::Write;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # groqai::doctest::set_api_key();
+    /// let client = GroqClient::new()?;
+    /// # let client = groqai::doctest::client();
+    /// let request = AudioSpeechRequest {
+    ///     model: "playai-tts".to_string(),
+    ///     input: "Welcome back! You have three new messages.".to_string(),
+    ///     voice: Voice::Fritz,
+    ///     response_format: Some(AudioFormat::Wav),
+    ///     ..Default::default()
+    /// };
+    /// 
+    /// let mut audio = client.audio().speech_stream(request).await?;
+    /// let mut player = std::io::stdout();
+    /// while let Some(chunk) = audio.next().await {
+    ///     player.write_all(&chunk?)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn speech_stream(self, req: AudioSpeechRequest) -> Result<ByteStream, GroqError> {
+        req.validate()?;
+        let request = Request::post(Endpoint::AudioSpeech)
+            .json(serde_json::to_value(req)?)
+            .options(self.options);
+        self.client.transport.execute_stream(request).await
+    }
+
+    /// Transcribes audio, then translates the transcript into any language
+    /// 
+    /// Whisper's own translation endpoint only produces English. This pipeline
+    /// transcribes in the spoken language and translates the text with a chat
+    /// model, returning both along with the time each step took.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `file` - Path to the audio file
+    /// * `target_language` - Language to translate into, e.g. "German" or "de"
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError` if either step fails, or
+    /// `GroqError::InvalidMessage` if the chat model returns no choices.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClient;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # groqai::doctest::set_api_key();
+    /// let client = GroqClient::new()?;
+    /// # let client = groqai::doctest::client();
+    /// let result = client
+    ///     .audio()
+    ///     .transcribe_and_translate("interview.mp3", "Japanese")
+    ///     .await?;
+    /// println!("{}\n---\n{}", result.transcript.text, result.translation);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn transcribe_and_translate(
+        self,
+        file: impl Into<PathBuf>,
+        target_language: impl Into<String>,
+    ) -> Result<TranslatedTranscript, GroqError> {
+        let client = self.client;
+        let translation_model = self.translation_model.clone();
+        let options = self.options.clone();
+        let target_language = target_language.into();
+
+        let started = Instant::now();
+        let request = AudioTranscriptionRequest {
+            file: Some(file.into()),
+            model: self.transcription_model.clone(),
+            ..Default::default()
+        };
+        let transcript = self.transcribe(request).await?;
+        let transcription_time = started.elapsed();
+
+        let started = Instant::now();
+        // Leave room for translations that are longer than the source
+        let max_tokens = (estimate_tokens(&transcript.text) * 2 + 256).min(32_768) as u32;
+        let mut chat = client.chat(translation_model);
+        for (name, value) in options.headers() {
+            chat = chat.header(name.as_str(), value.as_str());
         }
+        let response = chat
+            .message(ChatMessage::new_text(
+                Role::System,
+                format!(
+                    "Translate the user's text into {}. Reply with the translation only, \
+                     preserving paragraph breaks.",
+                    target_language
+                ),
+            ))
+            .message(ChatMessage::new_text(Role::User, transcript.text.clone()))
+            .temperature(0.0)
+            .max_completion_tokens(max_tokens)
+            .send()
+            .await?;
+        let translation = response
+            .choices
+            .first()
+            .map(|c| c.message.content.to_string())
+            .ok_or_else(|| GroqError::InvalidMessage("Response contained no choices".to_string()))?;
+
+        Ok(TranslatedTranscript {
+            transcript,
+            translation,
+            target_language,
+            transcription_time,
+            translation_time: started.elapsed(),
+            translation_usage: response.usage,
+        })
     }
 }
 
-impl Default for AudioTranslationRequest {
-    fn default() -> Self {
-        Self {
-            file: None,
-            url: None,
-            model: String::new(),
-            prompt: None,
-            response_format: None,
-            temperature: None,
-        }
+/// Fluent builder for a transcription, created by
+/// [`AudioRequestBuilder::transcribe_file`] or [`AudioRequestBuilder::transcribe_url`]
+/// 
+/// Headers and upload progress callbacks set on the [`AudioRequestBuilder`]
+/// apply to the request.
+pub struct TranscriptionBuilder<'a> {
+    audio: AudioRequestBuilder<'a>,
+    request: AudioTranscriptionRequest,
+}
+
+impl<'a> TranscriptionBuilder<'a> {
+    /// Sets the model, e.g. `whisper-large-v3-turbo`
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.request.model = model.into();
+        self
+    }
+
+    /// Sets the language of the audio (ISO-639-1, e.g. `en`)
+    /// 
+    /// Setting it improves accuracy and latency.
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.request.language = Some(language.into());
+        self
     }
-}
\ No newline at end of file
+
+    /// Sets a prompt to guide the style or spelling of the transcript
+    pub fn prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.request.prompt = Some(prompt.into());
+        self
+    }
+
+    /// Sets the response format
+    pub fn response_format(mut self, format: impl Into<AudioResponseFormat>) -> Self {
+        self.request.response_format = Some(format.into());
+        self
+    }
+
+    /// Sets the sampling temperature between 0 and 1
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.request.temperature = Some(temperature);
+        self
+    }
+
+    /// Sends the audio file with `mime` as its content type
+    /// 
+    /// See [`AudioRequestBuilder::file_content_type`].
+    pub fn file_content_type(mut self, mime: impl Into<String>) -> Self {
+        self.audio = self.audio.file_content_type(mime);
+        self
+    }
+
+    /// Requests word and segment timestamps in `verbose_json`
+    /// 
+    /// The words are returned in [`Transcription::words`].
+    pub fn word_timestamps(self) -> Self {
+        self.timestamp_granularities(["word", "segment"])
+    }
+
+    /// Sets the timestamp granularities, `word` and/or `segment`
+    /// 
+    /// Timestamps are only returned with [`AudioResponseFormat::VerboseJson`],
+    /// which is requested unless another response format is set.
+    pub fn timestamp_granularities<S: Into<String>>(mut self, granularities: impl IntoIterator<Item = S>) -> Self {
+        self.request.timestamp_granularities = Some(granularities.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Returns the request that will be sent
+    pub fn request(&self) -> &AudioTranscriptionRequest {
+        &self.request
+    }
+
+    /// Sends the transcription
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError` if the transcription fails
+    pub async fn send(self) -> Result<Transcription, GroqError> {
+        self.audio.transcribe(self.request).await
+    }
+
+    /// Sends the transcription, keeping plain text and subtitle responses apart
+    /// 
+    /// See [`AudioRequestBuilder::transcribe_output`].
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError` if the transcription fails
+    pub async fn send_output(self) -> Result<TranscriptionOutput, GroqError> {
+        self.audio.transcribe_output(self.request).await
+    }
+}
+
+/// Fluent builder for a translation into English, created by
+/// [`AudioRequestBuilder::translate_file`] or [`AudioRequestBuilder::translate_url`]
+/// 
+/// Headers and upload progress callbacks set on the [`AudioRequestBuilder`]
+/// apply to the request.
+pub struct TranslationBuilder<'a> {
+    audio: AudioRequestBuilder<'a>,
+    request: AudioTranslationRequest,
+}
+
+impl<'a> TranslationBuilder<'a> {
+    /// Sets the model; only `whisper-large-v3` supports translation
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.request.model = model.into();
+        self
+    }
+
+    /// Sends the audio file with `mime` as its content type
+    /// 
+    /// See [`AudioRequestBuilder::file_content_type`].
+    pub fn file_content_type(mut self, mime: impl Into<String>) -> Self {
+        self.audio = self.audio.file_content_type(mime);
+        self
+    }
+
+    /// Sets a prompt to guide the style of the translation; it should be in English
+    pub fn prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.request.prompt = Some(prompt.into());
+        self
+    }
+
+    /// Sets the response format
+    pub fn response_format(mut self, format: impl Into<AudioResponseFormat>) -> Self {
+        self.request.response_format = Some(format.into());
+        self
+    }
+
+    /// Sets the sampling temperature between 0 and 1
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.request.temperature = Some(temperature);
+        self
+    }
+
+    /// Returns the request that will be sent
+    pub fn request(&self) -> &AudioTranslationRequest {
+        &self.request
+    }
+
+    /// Sends the translation
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError` if the translation fails
+    pub async fn send(self) -> Result<Translation, GroqError> {
+        self.audio.translate(self.request).await
+    }
+}
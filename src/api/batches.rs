@@ -2,10 +2,239 @@
 //! 
 //! 批处理 API 实现，支持大规模异步任务处理
 
+use crate::api::chat::ChatCompletionRequest;
+use crate::api::poll::{check_status, PollOptions, Poller};
+#[cfg(feature = "files")]
+use crate::api::files::{FileCreateRequest, FilePurpose};
+#[cfg(feature = "files")]
+use crate::types::WorkFile;
 use crate::client::GroqClient;
+use crate::endpoint::Endpoint;
 use crate::error::GroqError;
+use crate::queue::Priority;
+use crate::transport::{Request, RequestOptions};
 use crate::types::{Batch, BatchList};
-use serde::Serialize;
+use bytes::Bytes;
+use futures::{Stream, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// API endpoint a batch is processed against
+/// 
+/// Serializes to the path expected by the API (e.g. `"/v1/chat/completions"`).
+/// Parsing accepts paths with or without the `/v1` prefix.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use groqai::api::batches::BatchEndpoint;
+/// 
+/// assert_eq!(BatchEndpoint::from("/chat/completions"), BatchEndpoint::ChatCompletions);
+/// assert_eq!(BatchEndpoint::ChatCompletions.as_str(), "/v1/chat/completions");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[serde(into = "String", from = "String")]
+pub enum BatchEndpoint {
+    /// `/v1/chat/completions`
+    ChatCompletions,
+    /// `/v1/embeddings` (not currently accepted by Groq for batches)
+    Embeddings,
+    /// `/v1/audio/transcriptions`
+    AudioTranscriptions,
+    /// `/v1/audio/translations`
+    AudioTranslations,
+    /// Any other endpoint path, sent as-is
+    Other(String),
+}
+
+impl BatchEndpoint {
+    /// Returns the endpoint path sent to the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            BatchEndpoint::ChatCompletions => "/v1/chat/completions",
+            BatchEndpoint::Embeddings => "/v1/embeddings",
+            BatchEndpoint::AudioTranscriptions => "/v1/audio/transcriptions",
+            BatchEndpoint::AudioTranslations => "/v1/audio/translations",
+            BatchEndpoint::Other(path) => path,
+        }
+    }
+
+    /// Returns true if Groq is known to accept batches for this endpoint
+    /// 
+    /// `Other` endpoints are assumed supported so new endpoints can be used
+    /// before this SDK learns about them.
+    pub fn is_supported(&self) -> bool {
+        !matches!(self, BatchEndpoint::Embeddings)
+    }
+
+    /// Returns true if a JSONL request line's `url` targets this endpoint
+    pub fn matches_url(&self, url: &str) -> bool {
+        BatchEndpoint::from(url) == *self
+    }
+
+    /// Validates that every request in a batch input file targets this endpoint
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError::InvalidMessage` if the file cannot be read, a line is
+    /// not valid JSON, or a line's `url` is missing or points to another endpoint.
+    pub fn validate_input_file(&self, path: impl AsRef<Path>) -> Result<(), GroqError> {
+        let file = File::open(path.as_ref())
+            .map_err(|e| GroqError::InvalidMessage(format!("Failed to open file: {}", e)))?;
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| {
+                GroqError::InvalidMessage(format!("Failed to read line {}: {}", index + 1, e))
+            })?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let value: serde_json::Value = serde_json::from_str(&line).map_err(|e| {
+                GroqError::InvalidMessage(format!("Invalid JSONL at line {}: {}", index + 1, e))
+            })?;
+            match value.get("url").and_then(|u| u.as_str()) {
+                Some(url) if self.matches_url(url) => {}
+                Some(url) => {
+                    return Err(GroqError::InvalidMessage(format!(
+                        "Line {} targets {} but the batch endpoint is {}",
+                        index + 1,
+                        url,
+                        self
+                    )))
+                }
+                None => {
+                    return Err(GroqError::InvalidMessage(format!(
+                        "Line {} is missing the \"url\" field",
+                        index + 1
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for BatchEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for BatchEndpoint {
+    fn from(path: &str) -> Self {
+        let normalized = path.strip_prefix("/v1").unwrap_or(path);
+        match normalized {
+            "/chat/completions" => BatchEndpoint::ChatCompletions,
+            "/embeddings" => BatchEndpoint::Embeddings,
+            "/audio/transcriptions" => BatchEndpoint::AudioTranscriptions,
+            "/audio/translations" => BatchEndpoint::AudioTranslations,
+            _ => BatchEndpoint::Other(path.to_string()),
+        }
+    }
+}
+
+impl From<String> for BatchEndpoint {
+    fn from(path: String) -> Self {
+        BatchEndpoint::from(path.as_str())
+    }
+}
+
+impl From<BatchEndpoint> for String {
+    fn from(endpoint: BatchEndpoint) -> Self {
+        match endpoint {
+            BatchEndpoint::Other(path) => path,
+            known => known.as_str().to_string(),
+        }
+    }
+}
+
+/// Time window in which a batch must complete
+/// 
+/// Serializes to the value expected by the API (e.g. `"24h"`, `"7d"`).
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use groqai::api::batches::CompletionWindow;
+/// use std::time::Duration;
+/// 
+/// assert_eq!(CompletionWindow::Days7.as_str(), "7d");
+/// assert_eq!(CompletionWindow::from("24h"), CompletionWindow::Hours24);
+/// assert_eq!(CompletionWindow::Hours24.as_duration(), Some(Duration::from_secs(24 * 3600)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[serde(into = "String", from = "String")]
+pub enum CompletionWindow {
+    /// `24h`
+    #[default]
+    Hours24,
+    /// `7d`
+    Days7,
+    /// Any other window, sent as-is (e.g. `"48h"`)
+    Other(String),
+}
+
+impl CompletionWindow {
+    /// Returns the value sent to the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            CompletionWindow::Hours24 => "24h",
+            CompletionWindow::Days7 => "7d",
+            CompletionWindow::Other(window) => window,
+        }
+    }
+
+    /// Returns the window as a duration, if it uses the `<n>h` or `<n>d` form
+    pub fn as_duration(&self) -> Option<Duration> {
+        let window = self.as_str();
+        let (last, unit) = window.char_indices().last()?;
+        let amount: u64 = window[..last].parse().ok()?;
+        let seconds = match unit {
+            'h' => amount.checked_mul(3600)?,
+            'd' => amount.checked_mul(24 * 3600)?,
+            _ => return None,
+        };
+        Some(Duration::from_secs(seconds))
+    }
+}
+
+impl fmt::Display for CompletionWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for CompletionWindow {
+    fn from(window: &str) -> Self {
+        match window {
+            "24h" => CompletionWindow::Hours24,
+            "7d" => CompletionWindow::Days7,
+            _ => CompletionWindow::Other(window.to_string()),
+        }
+    }
+}
+
+impl From<String> for CompletionWindow {
+    fn from(window: String) -> Self {
+        CompletionWindow::from(window.as_str())
+    }
+}
+
+impl From<CompletionWindow> for String {
+    fn from(window: CompletionWindow) -> Self {
+        match window {
+            CompletionWindow::Other(window) => window,
+            known => known.as_str().to_string(),
+        }
+    }
+}
 
 /// Request structure for creating a batch job
 /// 
@@ -14,29 +243,250 @@ use serde::Serialize;
 /// 
 /// # Examples
 /// 
-/// ```rust,no_run
-/// use groqai::api::batches::BatchCreateRequest;
+/// ```rust
+/// use groqai::api::batches::{BatchCreateRequest, BatchEndpoint, CompletionWindow};
 /// 
 /// let request = BatchCreateRequest {
 ///     input_file_id: "file_abc123".to_string(),
-///     endpoint: "/chat/completions".to_string(),
-///     completion_window: "24h".to_string(),
+///     endpoint: BatchEndpoint::ChatCompletions,
+///     completion_window: CompletionWindow::Hours24,
 ///     metadata: Some(serde_json::json!({"project": "my_project"})),
 /// };
 /// ```
 #[derive(Serialize, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 pub struct BatchCreateRequest {
     /// ID of the input file containing the batch requests
     pub input_file_id: String,
     /// The API endpoint to process the batch against
-    pub endpoint: String,
-    /// Time window for batch completion ("24h" only currently supported)
-    pub completion_window: String,
+    pub endpoint: BatchEndpoint,
+    /// Time window for batch completion
+    pub completion_window: CompletionWindow,
     /// Optional metadata to attach to the batch
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<serde_json::Value>,
 }
 
+/// Builds the JSONL input file of a chat completion batch
+/// 
+/// Each request becomes a line with its `custom_id`, `method`, `url` and
+/// `body`, so batch inputs need not be written by hand. Custom IDs must be
+/// unique within a batch; the API reports them in the output file to match
+/// results to requests.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use groqai::api::batches::BatchInputBuilder;
+/// use groqai::{ChatCompletionRequest, ChatMessage, Role};
+/// 
+/// # fn main() -> Result<(), groqai::GroqError> {
+/// let mut input = BatchInputBuilder::new();
+/// for (i, question) in ["What is Rust?", "What is Tokio?"].iter().enumerate() {
+///     let request = ChatCompletionRequest {
+///         messages: vec![ChatMessage::new_text(Role::User, *question)],
+///         model: "llama-3.1-8b-instant".to_string(),
+///         ..Default::default()
+///     };
+///     input.push(format!("question-{}", i), request)?;
+/// }
+/// assert!(input.push("question-0", ChatCompletionRequest::default()).is_err());
+/// 
+/// let jsonl = input.to_bytes()?;
+/// assert_eq!(jsonl.split(|b| *b == b'\n').filter(|line| !line.is_empty()).count(), 2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BatchInputBuilder {
+    lines: Vec<serde_json::Value>,
+    custom_ids: HashSet<String>,
+}
+
+impl BatchInputBuilder {
+    /// Creates an empty batch input
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a batch input from `(custom_id, request)` pairs
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError::InvalidMessage` if a pair is rejected by [`push`](Self::push)
+    pub fn from_requests<I, S>(requests: I) -> Result<Self, GroqError>
+    where
+        I: IntoIterator<Item = (S, ChatCompletionRequest)>,
+        S: Into<String>,
+    {
+        let mut input = Self::new();
+        for (custom_id, request) in requests {
+            input.push(custom_id, request)?;
+        }
+        Ok(input)
+    }
+
+    /// Adds a chat completion request under `custom_id`
+    /// 
+    /// Batches do not stream, so `stream` and `stream_options` are dropped.
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError::InvalidMessage` if `custom_id` is empty or already
+    /// used, or if the request exceeds an API limit checked by
+    /// [`ChatCompletionRequest::validate`]
+    pub fn push(&mut self, custom_id: impl Into<String>, mut request: ChatCompletionRequest) -> Result<(), GroqError> {
+        let custom_id = custom_id.into();
+        if custom_id.is_empty() {
+            return Err(GroqError::InvalidMessage("custom_id must not be empty".to_string()));
+        }
+        if self.custom_ids.contains(&custom_id) {
+            return Err(GroqError::InvalidMessage(format!(
+                "Duplicate custom_id {:?} in batch input",
+                custom_id
+            )));
+        }
+        request.validate()?;
+        request.stream = None;
+        request.stream_options = None;
+        self.lines.push(serde_json::json!({
+            "custom_id": custom_id,
+            "method": "POST",
+            "url": BatchEndpoint::ChatCompletions.as_str(),
+            "body": request,
+        }));
+        self.custom_ids.insert(custom_id);
+        Ok(())
+    }
+
+    /// Returns the number of requests
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns `true` if no request was added
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Serializes the requests as JSONL, one request per line
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError::InvalidMessage` if no request was added
+    pub fn to_bytes(&self) -> Result<Bytes, GroqError> {
+        if self.is_empty() {
+            return Err(GroqError::InvalidMessage("Batch input has no requests".to_string()));
+        }
+        let mut jsonl = Vec::new();
+        for line in &self.lines {
+            serde_json::to_writer(&mut jsonl, line)?;
+            jsonl.push(b'\n');
+        }
+        Ok(Bytes::from(jsonl))
+    }
+
+    /// Writes the JSONL input to a new file in the system temp directory
+    /// 
+    /// The file is not deleted; remove it once it has been uploaded.
+    /// 
+    /// # Returns
+    /// 
+    /// The path of the written `.jsonl` file
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError::InvalidMessage` if no request was added or the file
+    /// cannot be written
+    pub fn write_temp_file(&self) -> Result<PathBuf, GroqError> {
+        let content = self.to_bytes()?;
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or_default();
+        let path = std::env::temp_dir().join(format!("groqai-batch-{}-{}.jsonl", std::process::id(), nanos));
+        std::fs::write(&path, &content)
+            .map_err(|e| GroqError::InvalidMessage(format!("Failed to write batch input: {}", e)))?;
+        Ok(path)
+    }
+
+    /// Returns an upload request for the input, ready for `files().create`
+    /// 
+    /// The content is sent from memory as `filename` with purpose `batch`.
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError::InvalidMessage` if no request was added or
+    /// `filename` does not end in `.jsonl`
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::api::batches::BatchInputBuilder;
+    /// use groqai::{ChatCompletionRequest, ChatMessage, GroqClientBuilder, Role};
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
+    /// let request = ChatCompletionRequest {
+    ///     messages: vec![ChatMessage::new_text(Role::User, "Hello!")],
+    ///     model: "llama-3.1-8b-instant".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// let input = BatchInputBuilder::from_requests([("greeting", request)])?;
+    /// let file = client.files().create(input.file_request("greetings.jsonl")?).await?;
+    /// println!("Uploaded batch input {}", file.id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "files")]
+    pub fn file_request(&self, filename: impl Into<String>) -> Result<FileCreateRequest, GroqError> {
+        FileCreateRequest::from_bytes(self.to_bytes()?, filename, FilePurpose::Batch)
+    }
+}
+
+/// A batch created from chat requests by [`BatchRequestBuilder::submit_chat_requests`]
+#[derive(Debug, Clone)]
+pub struct BatchSubmission {
+    /// The created batch
+    pub batch: Batch,
+    /// ID of the uploaded input file
+    pub input_file_id: String,
+}
+
+/// A change in a batch's status or request counts, observed while waiting
+#[derive(Debug, Clone)]
+pub struct BatchProgress {
+    /// The batch as last retrieved
+    pub batch: Batch,
+    /// Time since waiting started
+    pub elapsed: Duration,
+}
+
+impl BatchProgress {
+    /// Returns the batch's status, e.g. "in_progress"
+    pub fn status(&self) -> &str {
+        &self.batch.status
+    }
+
+    /// Returns the share of requests that completed or failed, from 0.0 to 1.0
+    ///
+    /// Returns `None` while the batch does not report a request total.
+    pub fn fraction_done(&self) -> Option<f64> {
+        let counts = &self.batch.request_counts;
+        (counts.total > 0).then(|| f64::from(counts.completed + counts.failed) / f64::from(counts.total))
+    }
+
+    /// Returns true if this is the final update
+    pub fn is_terminal(&self) -> bool {
+        self.batch.is_terminal()
+    }
+}
+
+/// A boxed stream of batch progress updates
+pub type BatchProgressStream = Pin<Box<dyn Stream<Item = Result<BatchProgress, GroqError>> + Send>>;
+
 /// Builder for batch processing requests
 /// 
 /// This builder provides methods for creating, retrieving, listing, and canceling
@@ -44,18 +494,19 @@ pub struct BatchCreateRequest {
 /// 
 /// # Examples
 /// 
-/// ```rust,no_run
-/// use groqai::{GroqClientBuilder, BatchCreateRequest};
+/// ```rust
+/// use groqai::{GroqClientBuilder, BatchCreateRequest, BatchEndpoint, CompletionWindow};
 /// 
 /// # #[tokio::main]
 /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+/// # let client = groqai::doctest::client();
 /// 
 /// // Create a new batch
 /// let request = BatchCreateRequest {
 ///     input_file_id: "file_abc123".to_string(),
-///     endpoint: "/chat/completions".to_string(),
-///     completion_window: "24h".to_string(),
+///     endpoint: BatchEndpoint::ChatCompletions,
+///     completion_window: CompletionWindow::Hours24,
 ///     metadata: None,
 /// };
 /// 
@@ -70,6 +521,7 @@ pub struct BatchCreateRequest {
 /// ```
 pub struct BatchRequestBuilder<'a> {
     client: &'a GroqClient,
+    options: RequestOptions,
 }
 
 impl<'a> BatchRequestBuilder<'a> {
@@ -79,7 +531,30 @@ impl<'a> BatchRequestBuilder<'a> {
     /// 
     /// * `client` - Reference to the GroqClient
     pub fn new(client: &'a GroqClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            options: RequestOptions::default(),
+        }
+    }
+
+    /// Adds a header sent with every request made by this builder
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - The header name
+    /// * `value` - The header value
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options = self.options.header(name, value);
+        self
+    }
+
+    /// Sets the priority of this builder's requests in the client's request queue
+    /// 
+    /// Requests are [`Priority::Interactive`] by default. Without a queue (see
+    /// `GroqClientBuilder::request_queue`) it has no effect.
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.options = self.options.priority(priority);
+        self
     }
 
     /// Creates a new batch processing job
@@ -94,21 +569,23 @@ impl<'a> BatchRequestBuilder<'a> {
     /// 
     /// # Errors
     /// 
-    /// Returns `GroqError` if the batch creation fails
+    /// Returns `GroqError::InvalidMessage` if the endpoint is known not to support
+    /// batches, or another `GroqError` if the batch creation fails
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
-    /// use groqai::{GroqClientBuilder, BatchCreateRequest};
+    /// ```rust
+    /// use groqai::{GroqClientBuilder, BatchCreateRequest, BatchEndpoint, CompletionWindow};
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
     /// 
     /// let request = BatchCreateRequest {
     ///     input_file_id: "file_abc123".to_string(),
-    ///     endpoint: "/chat/completions".to_string(),
-    ///     completion_window: "24h".to_string(),
+    ///     endpoint: BatchEndpoint::ChatCompletions,
+    ///     completion_window: CompletionWindow::Hours24,
     ///     metadata: Some(serde_json::json!({"description": "Monthly report generation"})),
     /// };
     /// 
@@ -118,9 +595,104 @@ impl<'a> BatchRequestBuilder<'a> {
     /// # }
     /// ```
     pub async fn create(self, req: BatchCreateRequest) -> Result<Batch, GroqError> {
-        let body = serde_json::to_value(req)?;
-        let response = self.client.transport.post_json("batches", &body).await?;
-        serde_json::from_value(response).map_err(GroqError::from)
+        if !req.endpoint.is_supported() {
+            return Err(GroqError::InvalidMessage(format!(
+                "Endpoint {} does not support batch processing",
+                req.endpoint
+            )));
+        }
+        let request = Request::post(Endpoint::Batches)
+            .json(serde_json::to_value(req)?)
+            .options(self.options);
+        self.client.transport.execute(request).await?.json()
+    }
+
+    /// Uploads chat requests as a batch input file and creates a batch for it
+    /// 
+    /// Builds the input with [`BatchInputBuilder`], uploads it with purpose
+    /// `batch` and creates a chat completion batch. If creating the batch
+    /// fails, the uploaded file is deleted again.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `requests` - `(custom_id, request)` pairs; custom IDs must be unique
+    /// * `completion_window` - Time window for the batch, e.g. `"24h"`
+    /// 
+    /// # Returns
+    /// 
+    /// The created batch and the ID of its input file
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError::InvalidMessage` if there are no requests or a
+    /// custom ID is repeated, or another `GroqError` if the upload or the
+    /// batch creation fails
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::{ChatCompletionRequest, ChatMessage, GroqClientBuilder, Role};
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
+    /// 
+    /// let requests = ["Summarize chapter 1", "Summarize chapter 2"]
+    ///     .iter()
+    ///     .enumerate()
+    ///     .map(|(i, prompt)| {
+    ///         let request = ChatCompletionRequest {
+    ///             messages: vec![ChatMessage::new_text(Role::User, *prompt)],
+    ///             model: "llama-3.1-8b-instant".to_string(),
+    ///             ..Default::default()
+    ///         };
+    ///         (format!("chapter-{}", i + 1), request)
+    ///     });
+    /// let submission = client.batches().submit_chat_requests(requests, "24h").await?;
+    /// println!("Batch {} reads {}", submission.batch.id, submission.input_file_id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "files")]
+    pub async fn submit_chat_requests<I, S>(
+        self,
+        requests: I,
+        completion_window: impl Into<CompletionWindow>,
+    ) -> Result<BatchSubmission, GroqError>
+    where
+        I: IntoIterator<Item = (S, ChatCompletionRequest)>,
+        S: Into<String>,
+    {
+        let input = BatchInputBuilder::from_requests(requests)?;
+        let upload = input.file_request("batch_input.jsonl")?;
+        let content = upload.content.clone().unwrap_or_default();
+        let request = Request::post(Endpoint::Files)
+            .multipart_file(serde_json::to_value(&upload)?, content)
+            .options(self.options.clone());
+        let file: WorkFile = self.client.transport.execute(request).await?.json()?;
+
+        let create = BatchCreateRequest {
+            input_file_id: file.id.clone(),
+            endpoint: BatchEndpoint::ChatCompletions,
+            completion_window: completion_window.into(),
+            metadata: None,
+        };
+        let client = self.client;
+        let options = self.options.clone();
+        match self.create(create).await {
+            Ok(batch) => Ok(BatchSubmission {
+                batch,
+                input_file_id: file.id,
+            }),
+            Err(e) => {
+                let request = Request::delete(Endpoint::File(file.id.clone())).options(options);
+                if let Err(delete_error) = client.transport.execute(request).await {
+                    tracing::warn!("Failed to delete batch input file {}: {}", file.id, delete_error);
+                }
+                Err(e)
+            }
+        }
     }
 
     /// Retrieves details of a specific batch
@@ -139,23 +711,159 @@ impl<'a> BatchRequestBuilder<'a> {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::GroqClientBuilder;
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
     /// 
-    /// let batch = client.batches().retrieve("batch_abc123".to_string()).await?;
+    /// let batch = client.batches().retrieve("batch_abc123").await?;
     /// println!("Batch status: {}", batch.status);
     /// println!("Completed: {}/{}", batch.request_counts.completed, batch.request_counts.total);
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn retrieve(self, batch_id: String) -> Result<Batch, GroqError> {
-        let path = format!("batches/{}", batch_id);
-        let response = self.client.transport.get_json(&path).await?;
-        serde_json::from_value(response).map_err(GroqError::from)
+    pub async fn retrieve(self, batch_id: impl Into<String>) -> Result<Batch, GroqError> {
+        let request = Request::get(Endpoint::Batch(batch_id.into())).options(self.options);
+        self.client.transport.execute(request).await?.json()
+    }
+
+    /// Polls a batch until it completes, fails or the timeout elapses
+    /// 
+    /// # Arguments
+    /// 
+    /// * `batch_id` - The ID of the batch to wait for
+    /// * `opts` - Polling interval, backoff and timeout
+    /// 
+    /// # Returns
+    /// 
+    /// The final `Batch` record of a completed batch
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError::JobFailed` with the batch's validation errors if it
+    /// failed, expired or was cancelled, `GroqError::JobTimeout` if
+    /// `opts.timeout` elapsed, or another `GroqError` if polling fails.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::api::poll::PollOptions;
+    /// use groqai::GroqClientBuilder;
+    /// use std::time::Duration;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
+    /// 
+    /// let opts = PollOptions::new()
+    ///     .interval(Duration::from_secs(30))
+    ///     .backoff(Duration::from_secs(10 * 60))
+    ///     .timeout(Duration::from_secs(24 * 60 * 60));
+    /// let batch = client.batches().wait("batch_abc123", opts).await?;
+    /// println!("Output file: {:?}", batch.output_file_id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait(self, batch_id: impl Into<String>, opts: PollOptions) -> Result<Batch, GroqError> {
+        let id = batch_id.into();
+        let mut updates = self.wait_stream(id.clone(), opts);
+        let mut last = None;
+        while let Some(progress) = updates.try_next().await? {
+            last = Some(progress.batch);
+        }
+        match last {
+            Some(batch) if batch.is_completed() => Ok(batch),
+            Some(batch) => Err(GroqError::JobFailed {
+                id,
+                status: batch.status,
+                error: batch.errors.and_then(|errors| serde_json::to_value(errors).ok()),
+            }),
+            None => Err(GroqError::InvalidMessage(format!("Batch {} was not retrieved", id))),
+        }
+    }
+
+    /// Polls a batch and yields an update whenever its status or request counts change
+    /// 
+    /// The first retrieved state is always yielded. The stream ends after the
+    /// batch reaches a terminal status (`completed`, `failed`, `expired` or
+    /// `cancelled`), which is yielded as the last update. A failed batch is
+    /// not an error here; check [`Batch::is_completed`] on the last update.
+    /// Status checks go through the client's rate limiter and backoff, and the
+    /// last one happens when `opts.timeout` elapses.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `batch_id` - The ID of the batch to watch
+    /// * `opts` - Polling interval, backoff and timeout
+    /// 
+    /// # Errors
+    /// 
+    /// Yields `GroqError::JobTimeout` if `opts.timeout` elapses, or another
+    /// `GroqError` if polling fails; the stream ends after an error.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use futures::TryStreamExt;
+    /// use groqai::api::poll::PollOptions;
+    /// use groqai::GroqClientBuilder;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
+    /// 
+    /// let mut updates = client.batches().wait_stream("batch_abc123", PollOptions::new());
+    /// while let Some(progress) = updates.try_next().await? {
+    ///     println!("{} ({:.0}% done)", progress.status(), progress.fraction_done().unwrap_or(0.0) * 100.0);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn wait_stream(self, batch_id: impl Into<String>, opts: PollOptions) -> BatchProgressStream {
+        struct Poll {
+            last: Option<(String, u32, u32, u32)>,
+            poller: Poller,
+            first: bool,
+        }
+
+        let client = self.client.clone();
+        let options = self.options;
+        let id = batch_id.into();
+        let first = Poll {
+            last: None,
+            poller: Poller::new(opts),
+            first: true,
+        };
+        let updates = futures::stream::try_unfold(Some(first), move |poll| {
+            let (client, options, id) = (client.clone(), options.clone(), id.clone());
+            async move {
+                let Some(mut poll) = poll else {
+                    return Ok(None);
+                };
+                loop {
+                    if !std::mem::take(&mut poll.first) {
+                        poll.poller.wait(&id).await?;
+                    }
+                    let request = Request::get(Endpoint::Batch(id.clone())).options(options.clone());
+                    let batch: Batch = check_status(&client, request).await?;
+                    let counts = &batch.request_counts;
+                    let state = (batch.status.clone(), counts.total, counts.completed, counts.failed);
+                    if poll.last.as_ref() == Some(&state) {
+                        continue;
+                    }
+                    poll.last = Some(state);
+                    let elapsed = poll.poller.elapsed();
+                    let next = (!batch.is_terminal()).then_some(poll);
+                    return Ok(Some((BatchProgress { batch, elapsed }, next)));
+                }
+            }
+        });
+        Box::pin(updates)
     }
 
     /// Lists batch processing jobs with optional pagination
@@ -175,12 +883,13 @@ impl<'a> BatchRequestBuilder<'a> {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::GroqClientBuilder;
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
     /// 
     /// // List first 10 batches
     /// let batches = client.batches().list(None, Some(10)).await?;
@@ -198,21 +907,14 @@ impl<'a> BatchRequestBuilder<'a> {
     /// # }
     /// ```
     pub async fn list(self, after: Option<String>, limit: Option<u32>) -> Result<BatchList, GroqError> {
-        let mut params = Vec::new();
+        let mut request = Request::get(Endpoint::Batches).options(self.options);
         if let Some(after_id) = after {
-            params.push(("after", after_id));
+            request = request.query("after", after_id);
         }
         if let Some(limit_val) = limit {
-            params.push(("limit", limit_val.to_string()));
-        }
-        
-        if params.is_empty() {
-            let response = self.client.transport.get_json("batches").await?;
-            serde_json::from_value(response).map_err(GroqError::from)
-        } else {
-            let response = self.client.transport.get_with_params("batches", &params).await?;
-            serde_json::from_value(response).map_err(GroqError::from)
+            request = request.query("limit", limit_val.to_string());
         }
+        self.client.transport.execute(request).await?.json()
     }
 
     /// Cancels a batch processing job
@@ -231,22 +933,21 @@ impl<'a> BatchRequestBuilder<'a> {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::GroqClientBuilder;
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
     /// 
-    /// let cancelled_batch = client.batches().cancel("batch_abc123".to_string()).await?;
+    /// let cancelled_batch = client.batches().cancel("batch_abc123").await?;
     /// println!("Batch {} status: {}", cancelled_batch.id, cancelled_batch.status);
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn cancel(self, batch_id: String) -> Result<Batch, GroqError> {
-        let path = format!("batches/{}/cancel", batch_id);
-        let body = serde_json::Value::Null;
-        let response = self.client.transport.post_json(&path, &body).await?;
-        serde_json::from_value(response).map_err(GroqError::from)
+    pub async fn cancel(self, batch_id: impl Into<String>) -> Result<Batch, GroqError> {
+        let request = Request::post(Endpoint::BatchCancel(batch_id.into())).options(self.options);
+        self.client.transport.execute(request).await?.json()
     }
 }
\ No newline at end of file
@@ -2,15 +2,19 @@
 //! 
 //! 聊天完成 API 实现，支持流式和非流式对话
 
-use crate::client::GroqClient;
+use crate::client::{ChatSendOptions, GroqClient};
 use crate::error::GroqError;
+use crate::prompts::PromptExperiment;
+use crate::queue::Priority;
+use crate::language::LanguageEnforcer;
+use crate::reasoning::ReasoningStripper;
 use crate::types::{
-    ChatCompletionResponse, ChatCompletionChunk, ChatMessage, Tool, ToolChoice,
+    ChatCompletionResponse, ChatMessage, Role, Tool, ToolChoice,
     ResponseFormat, ServiceTier, StopSequence, StreamOptions, CompoundCustom, SearchSettings
 };
-use serde::Serialize;
-use std::pin::Pin;
-use futures::Stream;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "streaming")]
+use crate::transport::ChatCompletionStream;
 
 /// Request structure for chat completions
 /// 
@@ -19,7 +23,7 @@ use futures::Stream;
 /// 
 /// # Examples
 /// 
-/// ```rust,no_run
+/// ```rust
 /// use groqai::api::chat::ChatCompletionRequest;
 /// use groqai::types::{ChatMessage, Role};
 /// 
@@ -31,7 +35,8 @@ use futures::Stream;
 ///     ..Default::default()
 /// };
 /// ```
-#[derive(Serialize, Default, Clone)]
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub struct ChatCompletionRequest {
     /// List of messages in the conversation
@@ -74,6 +79,9 @@ pub struct ChatCompletionRequest {
     /// Reasoning effort level for the model
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reasoning_effort: Option<String>,
+    /// How reasoning models return their reasoning: "raw", "parsed" or "hidden"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_format: Option<String>,
     /// Search settings for web search capabilities
     #[serde(skip_serializing_if = "Option::is_none")]
     pub search_settings: Option<SearchSettings>,
@@ -100,6 +108,82 @@ pub struct ChatCompletionRequest {
     pub compound_custom: Option<CompoundCustom>,
 }
 
+/// Maximum number of tools in one request
+pub const MAX_TOOLS: usize = 128;
+
+/// Maximum serialized size of all tool definitions in one request, in bytes
+pub const MAX_TOOLS_BYTES: usize = 256 * 1024;
+
+/// Maximum number of `logit_bias` entries in one request
+pub const MAX_LOGIT_BIAS_ENTRIES: usize = 300;
+
+impl ChatCompletionRequest {
+    /// Checks the request against API size limits before it is sent
+    ///
+    /// The API rejects oversized tool lists and `logit_bias` maps with a
+    /// generic 400 response; this reports which limit was hit and where.
+    /// Called automatically by the client for every chat request.
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` describing the first limit exceeded:
+    /// [`MAX_TOOLS`], [`MAX_TOOLS_BYTES`], [`MAX_LOGIT_BIAS_ENTRIES`], a
+    /// `logit_bias` key that is not a token ID or a bias outside -100..=100,
+    /// or an image limit checked by [`validate_images`](crate::api::vision::validate_images).
+    pub fn validate(&self) -> Result<(), GroqError> {
+        if let Some(tools) = &self.tools {
+            if tools.len() > MAX_TOOLS {
+                return Err(GroqError::InvalidMessage(format!(
+                    "Request has {} tools; the limit is {}",
+                    tools.len(),
+                    MAX_TOOLS
+                )));
+            }
+            let mut total = 0;
+            let mut largest = ("", 0);
+            for tool in tools {
+                let size = serde_json::to_vec(tool)?.len();
+                total += size;
+                if size > largest.1 {
+                    largest = (tool.function.name.as_str(), size);
+                }
+            }
+            if total > MAX_TOOLS_BYTES {
+                return Err(GroqError::InvalidMessage(format!(
+                    "Tool definitions total {} bytes; the limit is {} (largest: `{}` at {} bytes)",
+                    total, MAX_TOOLS_BYTES, largest.0, largest.1
+                )));
+            }
+        }
+        if let Some(bias) = &self.logit_bias {
+            if bias.len() > MAX_LOGIT_BIAS_ENTRIES {
+                return Err(GroqError::InvalidMessage(format!(
+                    "logit_bias has {} entries; the limit is {}",
+                    bias.len(),
+                    MAX_LOGIT_BIAS_ENTRIES
+                )));
+            }
+            let mut entries: Vec<_> = bias.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            for (token, value) in entries {
+                if token.parse::<u32>().is_err() {
+                    return Err(GroqError::InvalidMessage(format!(
+                        "logit_bias key {:?} is not a token ID",
+                        token
+                    )));
+                }
+                if !(-100.0..=100.0).contains(value) {
+                    return Err(GroqError::InvalidMessage(format!(
+                        "logit_bias for token {} is {}; biases must be between -100 and 100",
+                        token, value
+                    )));
+                }
+            }
+        }
+        crate::api::vision::validate_images(self)
+    }
+}
+
 /// Builder for creating chat completion requests
 /// 
 /// This builder provides a fluent interface for constructing chat completion requests
@@ -107,12 +191,13 @@ pub struct ChatCompletionRequest {
 /// 
 /// # Examples
 /// 
-/// ```rust,no_run
+/// ```rust
 /// use groqai::{GroqClientBuilder, ChatMessage, Role};
 /// 
 /// # #[tokio::main]
 /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+/// # let client = groqai::doctest::client();
 /// 
 /// let response = client.chat("llama-3.1-70b-versatile")
 ///     .message(ChatMessage::new_text(Role::User, "Hello!"))
@@ -128,6 +213,10 @@ pub struct ChatRequestBuilder<'a> {
     client: &'a GroqClient,
     request: ChatCompletionRequest,
     stream: bool,
+    options: ChatSendOptions,
+    strip_reasoning: Option<ReasoningStripper>,
+    language: Option<LanguageEnforcer>,
+    auto_continue: u32,
 }
 
 impl<'a> ChatRequestBuilder<'a> {
@@ -138,16 +227,24 @@ impl<'a> ChatRequestBuilder<'a> {
     /// * `client` - Reference to the GroqClient
     /// * `model` - The model to use for completion
     pub fn new(client: &'a GroqClient, model: impl Into<String>) -> Self {
+        let mut request = ChatCompletionRequest {
+            model: model.into(),
+            messages: Vec::new(),
+            ..Default::default()
+        };
+        if let Some(defaults) = &client.model_defaults {
+            defaults.apply(&mut request);
+        }
+        request.temperature.get_or_insert(0.7);
+        request.max_completion_tokens.get_or_insert(1000);
         Self {
             client,
-            request: ChatCompletionRequest {
-                model: model.into(),
-                messages: Vec::new(),
-                temperature: Some(0.7),
-                max_completion_tokens: Some(1000),
-                ..Default::default()
-            },
+            request,
             stream: false,
+            options: ChatSendOptions::default(),
+            strip_reasoning: None,
+            language: None,
+            auto_continue: 0,
         }
     }
 
@@ -159,10 +256,9 @@ impl<'a> ChatRequestBuilder<'a> {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::{ChatMessage, Role};
-    /// # use groqai::GroqClientBuilder;
-    /// # let client = GroqClientBuilder::new("gsk_key".to_string()).unwrap().build().unwrap();
+    /// # let client = groqai::doctest::client();
     /// 
     /// let builder = client.chat("llama-3.1-70b-versatile")
     ///     .message(ChatMessage::new_text(Role::User, "Hello!"));
@@ -297,8 +393,112 @@ impl<'a> ChatRequestBuilder<'a> {
     /// # Arguments
     /// 
     /// * `reasoning_effort` - The reasoning effort level
-    pub fn reasoning_effort(mut self, reasoning_effort: String) -> Self {
-        self.request.reasoning_effort = Some(reasoning_effort);
+    pub fn reasoning_effort(mut self, reasoning_effort: impl Into<String>) -> Self {
+        self.request.reasoning_effort = Some(reasoning_effort.into());
+        self
+    }
+
+    /// Sets how reasoning models return their reasoning
+    /// 
+    /// # Arguments
+    /// 
+    /// * `format` - "raw" (inline `<think>` blocks), "parsed" (separate
+    ///   `reasoning` field) or "hidden"
+    pub fn reasoning_format(mut self, format: impl Into<String>) -> Self {
+        self.request.reasoning_format = Some(format.into());
+        self
+    }
+
+    /// Moves inline `<think>` blocks out of the answer
+    /// 
+    /// The blocks end up in `Choice::reasoning`, or `MessageDelta::reasoning`
+    /// for streams, so `content` holds only the final answer.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `strip` - Whether to strip reasoning blocks
+    pub fn strip_reasoning(mut self, strip: bool) -> Self {
+        self.strip_reasoning = strip.then(ReasoningStripper::new);
+        self
+    }
+
+    /// Strips reasoning blocks delimited by custom markers
+    /// 
+    /// # Arguments
+    /// 
+    /// * `stripper` - Stripper configured with the model's markers
+    pub fn reasoning_stripper(mut self, stripper: ReasoningStripper) -> Self {
+        self.strip_reasoning = Some(stripper);
+        self
+    }
+
+    /// Instructs the model to reply in `language`
+    /// 
+    /// The instruction is added to the leading system message (or a new one)
+    /// when the request is sent. Use `language_enforcer` to also check the
+    /// reply's language.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `language` - ISO 639-1 code or language tag, e.g. `"de"` or `"pt-BR"`
+    pub fn force_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(LanguageEnforcer::new(language));
+        self
+    }
+
+    /// Keeps the reply in one language, optionally validating and retrying
+    /// 
+    /// # Arguments
+    /// 
+    /// * `enforcer` - The language and how strictly to enforce it
+    pub fn language_enforcer(mut self, enforcer: LanguageEnforcer) -> Self {
+        self.language = Some(enforcer);
+        self
+    }
+
+    /// Continues replies cut off by the token limit
+    /// 
+    /// When the reply is truncated (see `ChatCompletionResponse::was_truncated`,
+    /// or `was_truncated_json` with a JSON `response_format`), up to
+    /// `max_continuations` follow-up requests ask the model to carry on, and
+    /// their outputs are appended to the reply. The returned usage sums the
+    /// tokens of all requests, including the prompts resent with each
+    /// follow-up. Applies to `send()` only.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `max_continuations` - Maximum number of follow-up requests (0 disables)
+    pub fn auto_continue(mut self, max_continuations: u32) -> Self {
+        self.auto_continue = max_continuations;
+        self
+    }
+
+    /// Limits the worst-case cost of this request
+    /// 
+    /// Applies on top of the cost limit of the client's spend guard, which it
+    /// can tighten but not raise, or with the built-in pricing table if the
+    /// client has none. Requests that may cost more fail with
+    /// `GroqError::BudgetExceeded` before they are sent.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `usd` - Maximum cost in US dollars
+    pub fn max_cost_usd(mut self, usd: f64) -> Self {
+        self.options.max_cost_usd = Some(usd);
+        self
+    }
+
+    /// Sends the request even if it exceeds the client's spend guard
+    /// 
+    /// The escape hatch for requests that must go through, e.g. a final
+    /// summary after a long session. Their tokens still count toward the
+    /// hourly limit.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `override_guard` - Whether to skip the spending checks
+    pub fn override_spend_guard(mut self, override_guard: bool) -> Self {
+        self.options.override_spend_guard = override_guard;
         self
     }
 
@@ -383,6 +583,98 @@ impl<'a> ChatRequestBuilder<'a> {
         self
     }
 
+    /// Opts this request in or out of client-side dedupe and coalescing
+    /// 
+    /// Has no effect unless the client was built with `dedupe_window` or
+    /// `coalesce_requests`. Defaults to `true`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `enable` - Whether this request may share a result with identical requests
+    pub fn dedupe(mut self, enable: bool) -> Self {
+        self.options.allow_sharing = enable;
+        self
+    }
+
+    /// Opts this request in or out of the client's caches
+    /// 
+    /// Has no effect unless the client was built with a cache. By default the
+    /// response and semantic caches only serve and stores deterministic requests
+    /// (temperature `0` or a fixed `seed`); `true` caches a sampled request
+    /// too, and `false` bypasses every cache.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `enable` - Whether a cached reply may be returned and this reply stored
+    pub fn cache(mut self, enable: bool) -> Self {
+        self.options.use_cache = Some(enable);
+        self
+    }
+
+    /// Uses the system prompt variant an experiment assigns to `key`
+    /// 
+    /// Replaces the leading system message, or inserts one, and attributes the
+    /// request's usage to the variant in the client's `UsageTracker`. The same
+    /// key always gets the same variant. Does nothing if the experiment has no
+    /// variant with a positive weight.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `experiment` - The prompt variants to choose from
+    /// * `key` - A stable identifier such as a user ID or tag
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::prompts::PromptExperiment;
+    /// use groqai::{ChatMessage, Role};
+    /// # let client = groqai::doctest::client();
+    /// 
+    /// let experiment = PromptExperiment::new("tone")
+    ///     .variant("a", "Answer briefly.", 1)
+    ///     .variant("b", "Answer in detail.", 1);
+    /// let builder = client.chat("llama-3.1-8b-instant")
+    ///     .message(ChatMessage::new_text(Role::User, "Hello!"))
+    ///     .prompt_variant(&experiment, "user-42");
+    /// ```
+    pub fn prompt_variant(mut self, experiment: &PromptExperiment, key: &str) -> Self {
+        let Some(variant) = experiment.assign(key) else {
+            return self;
+        };
+        let system = ChatMessage::new_text(Role::System, variant.system_prompt.clone());
+        match self.request.messages.first_mut() {
+            Some(first) if first.role == Role::System => *first = system,
+            _ => self.request.messages.insert(0, system),
+        }
+        self.options.prompt_variant = Some((experiment.name().to_string(), variant.name.clone()));
+        self
+    }
+
+    /// Adds a header sent with this request only
+    /// 
+    /// Useful for gateways that read per-request hints, such as a routing
+    /// key. Setting any header opts the request out of client-side dedupe
+    /// and coalescing.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - The header name
+    /// * `value` - The header value
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options.request = self.options.request.header(name, value);
+        self
+    }
+
+    /// Sets the priority of this request in the client's request queue
+    /// 
+    /// Requests are [`Priority::Interactive`] by default; mark work no user is
+    /// waiting for as `Background` so it queues behind interactive requests.
+    /// Without a queue (see `GroqClientBuilder::request_queue`) it has no effect.
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.options.request = self.options.request.priority(priority);
+        self
+    }
+
     /// Sends the chat completion request
     /// 
     /// # Returns
@@ -401,7 +693,31 @@ impl<'a> ChatRequestBuilder<'a> {
         if self.stream {
             panic!("Use send_stream() for streaming requests");
         }
-        self.client.chat_completions(self.request).await
+        let continuation = (self.auto_continue > 0).then(|| {
+            let mut request = self.request.clone();
+            if let Some(enforcer) = &self.language {
+                enforcer.apply(&mut request, false);
+            }
+            (request, self.options.clone())
+        });
+        let mut response = match &self.language {
+            Some(enforcer) => enforcer.send(self.client, self.request, self.options).await?,
+            None => self.client.chat_completions_with(self.request, self.options).await?,
+        };
+        if let Some((request, options)) = continuation {
+            response = crate::continuation::continue_truncated(
+                self.client,
+                request,
+                options,
+                response,
+                self.auto_continue,
+            )
+            .await?;
+        }
+        if let Some(stripper) = &self.strip_reasoning {
+            stripper.apply(&mut response);
+        }
+        Ok(response)
     }
 
     /// Sends a streaming chat completion request
@@ -418,10 +734,22 @@ impl<'a> ChatRequestBuilder<'a> {
     /// # Panics
     /// 
     /// Panics if streaming is disabled. Use `send()` instead.
-    pub async fn send_stream(self) -> Result<Pin<Box<dyn Stream<Item = Result<ChatCompletionChunk, GroqError>> + Send>>, GroqError> {
+    #[cfg(feature = "streaming")]
+    pub async fn send_stream(self) -> Result<ChatCompletionStream, GroqError> {
         if !self.stream {
             panic!("Use send() for non-streaming requests");
         }
-        self.client.chat_completions_stream(self.request).await
+        let mut request = self.request;
+        if let Some(enforcer) = &self.language {
+            enforcer.apply(&mut request, false);
+        }
+        let stream = self
+            .client
+            .chat_completions_stream_with(request, &self.options)
+            .await?;
+        Ok(match &self.strip_reasoning {
+            Some(stripper) => stripper.strip_stream(stream),
+            None => stream,
+        })
     }
 }
\ No newline at end of file
@@ -3,49 +3,69 @@
 //! 文件管理 API 实现，支持文件上传、列表、检索和删除操作
 
 use crate::client::GroqClient;
+use crate::endpoint::Endpoint;
 use crate::error::GroqError;
+use crate::queue::Priority;
+use crate::transport::{ByteStream, Request, RequestOptions, UploadProgress};
+pub use crate::types::FilePurpose;
 use crate::types::{WorkFile, WorkFileList, WorkFileDeletion};
+use bytes::Bytes;
+use futures::{Stream, StreamExt, TryStreamExt};
 use serde::Serialize;
 use std::path::PathBuf;
+use std::pin::Pin;
 use std::io::{BufRead, BufReader};
 use std::fs::File;
+use tokio::io::{AsyncRead, AsyncReadExt};
 
 /// Request structure for creating/uploading a file
 /// 
 /// This struct contains the parameters needed to upload a file to Groq.
 /// Files must be in JSONL format and are typically used for batch processing
-/// or fine-tuning operations.
+/// or fine-tuning operations. The file is read from disk, or sent from memory
+/// when created with [`from_bytes`](FileCreateRequest::from_bytes) or
+/// [`from_reader`](FileCreateRequest::from_reader).
 /// 
 /// # Examples
 /// 
-/// ```rust,no_run
+/// ```rust
 /// use groqai::api::files::FileCreateRequest;
 /// use std::path::PathBuf;
 /// 
+/// # groqai::doctest::scratch_dir();
+/// # groqai::doctest::jsonl_file("training_data.jsonl");
 /// let request = FileCreateRequest::new(
 ///     PathBuf::from("training_data.jsonl"),
 ///     "batch".to_string()
 /// )?;
+/// 
+/// // Or from memory, without a file on disk
+/// let generated = FileCreateRequest::from_bytes("{\"custom_id\": \"1\"}\n", "generated.jsonl", "batch")?;
 /// # Ok::<(), groqai::GroqError>(())
 /// ```
 #[derive(Serialize, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 pub struct FileCreateRequest {
-    /// Path to the file to upload
+    /// Path to the file to upload, or only its name when `content` is set
     pub file: PathBuf,
-    /// Purpose of the file (e.g., "batch", "fine-tune")
-    pub purpose: String,
+    /// Purpose of the file
+    pub purpose: FilePurpose,
+    /// Content to upload instead of reading `file` from disk
+    #[serde(skip)]
+    pub content: Option<Bytes>,
 }
 
 impl FileCreateRequest {
     /// Creates a new file upload request with validation
     /// 
     /// This method validates that the file exists, has the correct extension (.jsonl),
-    /// and contains valid JSON lines.
+    /// and contains valid JSON lines. It reads the whole file with blocking I/O;
+    /// in async code use [`open`](FileCreateRequest::open) instead.
     /// 
     /// # Arguments
     /// 
     /// * `file` - Path to the JSONL file to upload
-    /// * `purpose` - Purpose of the file (e.g., "batch", "fine-tune")
+    /// * `purpose` - Purpose of the file, e.g. `FilePurpose::Batch` or `"fine-tune"`
     /// 
     /// # Returns
     /// 
@@ -60,10 +80,12 @@ impl FileCreateRequest {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::api::files::FileCreateRequest;
     /// use std::path::PathBuf;
     /// 
+    /// # groqai::doctest::scratch_dir();
+    /// # groqai::doctest::jsonl_file("batch_requests.jsonl");
     /// // Valid JSONL file for batch processing
     /// let request = FileCreateRequest::new(
     ///     PathBuf::from("batch_requests.jsonl"),
@@ -78,31 +100,182 @@ impl FileCreateRequest {
     /// assert!(invalid_request.is_err());
     /// # Ok::<(), groqai::GroqError>(())
     /// ```
-    pub fn new(file: PathBuf, purpose: String) -> Result<Self, GroqError> {
-        // Validate file extension
-        if file.extension().and_then(|ext| ext.to_str()) != Some("jsonl") {
-            return Err(GroqError::InvalidMessage(
-                "File must have .jsonl extension".to_string(),
-            ));
-        }
+    pub fn new(file: impl Into<PathBuf>, purpose: impl Into<FilePurpose>) -> Result<Self, GroqError> {
+        let file = file.into();
+        let purpose = purpose.into();
+        validate_extension(&file)?;
 
         // Validate file content (each line must be valid JSON)
         let file_reader = File::open(&file)
             .map_err(|e| GroqError::InvalidMessage(format!("Failed to open file: {}", e)))?;
-        let reader = BufReader::new(file_reader);
-        for (index, line) in reader.lines().enumerate() {
-            let line = line.map_err(|e| {
-                GroqError::InvalidMessage(format!("Failed to read line {}: {}", index + 1, e))
-            })?;
-            if !line.trim().is_empty() {
-                serde_json::from_str::<serde_json::Value>(&line).map_err(|e| {
-                    GroqError::InvalidMessage(format!("Invalid JSONL at line {}: {}", index + 1, e))
-                })?;
-            }
+        validate_jsonl(BufReader::new(file_reader))?;
+
+        Ok(Self { file, purpose, content: None })
+    }
+
+    /// Creates a new file upload request, validating the file on a blocking thread
+    /// 
+    /// Performs the same checks as [`new`](FileCreateRequest::new), but reads
+    /// and parses the file on tokio's blocking thread pool, so validating a
+    /// large file does not stall the runtime.
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError::InvalidMessage` as for [`new`](FileCreateRequest::new)
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::api::files::FileCreateRequest;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), groqai::GroqError> {
+    /// # groqai::doctest::scratch_dir();
+    /// # groqai::doctest::jsonl_file("batch_requests.jsonl");
+    /// let request = FileCreateRequest::open("batch_requests.jsonl", "batch").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn open(file: impl Into<PathBuf>, purpose: impl Into<FilePurpose>) -> Result<Self, GroqError> {
+        let (file, purpose) = (file.into(), purpose.into());
+        tokio::task::spawn_blocking(move || Self::new(file, purpose))
+            .await
+            .map_err(|e| GroqError::InvalidMessage(format!("File validation failed: {}", e)))?
+    }
+
+    /// Creates a new file upload request without validating the file
+    /// 
+    /// Nothing is read until the file is uploaded, and the API reports an
+    /// invalid file instead. Use it for files that were already validated,
+    /// e.g. because the program generated them.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::api::files::FileCreateRequest;
+    /// 
+    /// let request = FileCreateRequest::unchecked("batch_requests.jsonl", "batch");
+    /// assert!(request.content.is_none());
+    /// ```
+    pub fn unchecked(file: impl Into<PathBuf>, purpose: impl Into<FilePurpose>) -> Self {
+        Self {
+            file: file.into(),
+            purpose: purpose.into(),
+            content: None,
         }
+    }
+
+    /// Creates an upload request for JSONL content held in memory
+    /// 
+    /// Programs that generate batch inputs can upload them without writing a
+    /// temporary file. The content is validated like a file's.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `content` - The JSONL content
+    /// * `filename` - Name the file gets in your account; must end in `.jsonl`
+    /// * `purpose` - Purpose of the file, e.g. `FilePurpose::Batch` or `"fine-tune"`
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError::InvalidMessage` if the file name does not end in
+    /// `.jsonl` or a line is not valid JSON
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::api::files::FileCreateRequest;
+    /// use groqai::GroqClientBuilder;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
+    /// 
+    /// let lines: Vec<String> = ["Hello", "Goodbye"]
+    ///     .iter()
+    ///     .enumerate()
+    ///     .map(|(i, text)| {
+    ///         serde_json::json!({
+    ///             "custom_id": format!("request-{}", i),
+    ///             "method": "POST",
+    ///             "url": "/v1/chat/completions",
+    ///             "body": {"model": "llama-3.1-8b-instant", "messages": [{"role": "user", "content": text}]}
+    ///         })
+    ///         .to_string()
+    ///     })
+    ///     .collect();
+    /// let request = FileCreateRequest::from_bytes(lines.join("\n"), "greetings.jsonl", "batch")?;
+    /// let file = client.files().create(request).await?;
+    /// println!("Uploaded file: {}", file.id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_bytes(
+        content: impl Into<Bytes>,
+        filename: impl Into<String>,
+        purpose: impl Into<FilePurpose>,
+    ) -> Result<Self, GroqError> {
+        let file = PathBuf::from(filename.into());
+        validate_extension(&file)?;
+        let content = content.into();
+        validate_jsonl(content.as_ref())?;
+        Ok(Self {
+            file,
+            purpose: purpose.into(),
+            content: Some(content),
+        })
+    }
+
+    /// Creates an upload request for JSONL content read from `reader`
+    /// 
+    /// The reader is read to the end before validating, as with
+    /// [`from_bytes`](FileCreateRequest::from_bytes); the content is held in
+    /// memory so a failed upload can be retried.
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError::InvalidMessage` if reading fails, the file name
+    /// does not end in `.jsonl` or a line is not valid JSON
+    pub async fn from_reader<R>(
+        mut reader: R,
+        filename: impl Into<String>,
+        purpose: impl Into<FilePurpose>,
+    ) -> Result<Self, GroqError>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let mut content = Vec::new();
+        reader
+            .read_to_end(&mut content)
+            .await
+            .map_err(|e| GroqError::InvalidMessage(format!("Failed to read file content: {}", e)))?;
+        Self::from_bytes(content, filename, purpose)
+    }
+}
 
-        Ok(Self { file, purpose })
+fn validate_extension(file: &std::path::Path) -> Result<(), GroqError> {
+    if file.extension().and_then(|ext| ext.to_str()) != Some("jsonl") {
+        return Err(GroqError::InvalidMessage(
+            "File must have .jsonl extension".to_string(),
+        ));
     }
+    Ok(())
+}
+
+/// Checks that every non-empty line is valid JSON
+fn validate_jsonl(reader: impl BufRead) -> Result<(), GroqError> {
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| {
+            GroqError::InvalidMessage(format!("Failed to read line {}: {}", index + 1, e))
+        })?;
+        if !line.trim().is_empty() {
+            serde_json::from_str::<serde_json::Value>(&line).map_err(|e| {
+                GroqError::InvalidMessage(format!("Invalid JSONL at line {}: {}", index + 1, e))
+            })?;
+        }
+    }
+    Ok(())
 }
 
 /// Builder for file management requests
@@ -112,19 +285,16 @@ impl FileCreateRequest {
 /// 
 /// # Examples
 /// 
-/// ```rust,no_run
+/// ```rust
 /// use groqai::{GroqClientBuilder, FileCreateRequest};
-/// use std::path::PathBuf;
 /// 
 /// # #[tokio::main]
 /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+/// # let client = groqai::doctest::client();
 /// 
 /// // Upload a file
-/// let request = FileCreateRequest::new(
-///     PathBuf::from("data.jsonl"),
-///     "batch".to_string()
-/// )?;
+/// let request = FileCreateRequest::from_bytes("{\"custom_id\": \"1\"}\n", "data.jsonl", "batch")?;
 /// let file = client.files().create(request).await?;
 /// println!("Uploaded file: {}", file.id);
 /// 
@@ -136,6 +306,159 @@ impl FileCreateRequest {
 /// ```
 pub struct FileRequestBuilder<'a> {
     client: &'a GroqClient,
+    options: RequestOptions,
+    dry_run: bool,
+    concurrency: usize,
+}
+
+/// A boxed stream of files, fetched page by page
+pub type WorkFileStream = Pin<Box<dyn Stream<Item = Result<WorkFile, GroqError>> + Send>>;
+
+/// Parameters of one page of a file listing
+/// 
+/// Every parameter is sent to the API as a query parameter. Pass the ID of the
+/// last file of a page as `after` to fetch the next one, or let
+/// [`FileRequestBuilder::list_stream`] follow the pages.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use groqai::api::files::FileListParams;
+/// 
+/// let params = FileListParams::new().purpose("batch").limit(100).after("file_abc123");
+/// assert_eq!(params.limit, Some(100));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileListParams {
+    /// Only files with this purpose
+    pub purpose: Option<FilePurpose>,
+    /// Maximum number of files in a page
+    pub limit: Option<u32>,
+    /// Only files listed after the file with this ID
+    pub after: Option<String>,
+}
+
+impl FileListParams {
+    /// Creates parameters for the first page of all files
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only list files with the given purpose
+    pub fn purpose(mut self, purpose: impl Into<FilePurpose>) -> Self {
+        self.purpose = Some(purpose.into());
+        self
+    }
+
+    /// Return at most `limit` files per page
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Start listing after the file with ID `file_id`
+    pub fn after(mut self, file_id: impl Into<String>) -> Self {
+        self.after = Some(file_id.into());
+        self
+    }
+
+    fn request(&self) -> Request {
+        let mut request = Request::get(Endpoint::Files);
+        if let Some(purpose) = &self.purpose {
+            request = request.query("purpose", purpose.as_str());
+        }
+        if let Some(limit) = self.limit {
+            request = request.query("limit", limit.to_string());
+        }
+        if let Some(after) = &self.after {
+            request = request.query("after", after.clone());
+        }
+        request
+    }
+}
+
+/// Filters for listing files
+/// 
+/// `purpose` is sent to the API as a query parameter; every filter is also
+/// applied client-side, so results are correct even where the API ignores it.
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use groqai::api::files::{FileListQuery, FilePurpose};
+/// 
+/// let query = FileListQuery::new()
+///     .purpose(FilePurpose::Batch)
+///     .created_after(1_700_000_000)
+///     .limit(50);
+/// assert_eq!(query.purpose, Some(FilePurpose::Batch));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FileListQuery {
+    /// Only files with this purpose
+    pub purpose: Option<FilePurpose>,
+    /// Only files created at or after this Unix timestamp
+    pub created_after: Option<u64>,
+    /// Only files created before this Unix timestamp
+    pub created_before: Option<u64>,
+    /// Maximum number of files to return
+    pub limit: Option<usize>,
+}
+
+impl FileListQuery {
+    /// Creates a query matching all files
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only return files with the given purpose
+    pub fn purpose(mut self, purpose: impl Into<FilePurpose>) -> Self {
+        self.purpose = Some(purpose.into());
+        self
+    }
+
+    /// Only return files created at or after `timestamp`
+    pub fn created_after(mut self, timestamp: u64) -> Self {
+        self.created_after = Some(timestamp);
+        self
+    }
+
+    /// Only return files created before `timestamp`
+    pub fn created_before(mut self, timestamp: u64) -> Self {
+        self.created_before = Some(timestamp);
+        self
+    }
+
+    /// Return at most `limit` files
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Returns true if `file` passes every filter
+    pub fn matches(&self, file: &WorkFile) -> bool {
+        self.purpose.as_ref().is_none_or(|p| &file.purpose == p)
+            && self.created_after.is_none_or(|t| file.created_at >= t)
+            && self.created_before.is_none_or(|t| file.created_at < t)
+    }
+
+    fn server_params(&self) -> FileListParams {
+        FileListParams {
+            purpose: self.purpose.clone(),
+            ..FileListParams::default()
+        }
+    }
+}
+
+/// Outcome of a bulk file deletion
+#[derive(Debug, Clone, Default)]
+pub struct FileCleanupReport {
+    /// Files that matched the filter
+    pub matched: Vec<WorkFile>,
+    /// IDs of files that were deleted (empty in dry-run mode)
+    pub deleted: Vec<String>,
+    /// IDs of files whose deletion failed, with the error
+    pub failed: Vec<(String, GroqError)>,
 }
 
 impl<'a> FileRequestBuilder<'a> {
@@ -145,7 +468,63 @@ impl<'a> FileRequestBuilder<'a> {
     /// 
     /// * `client` - Reference to the GroqClient
     pub fn new(client: &'a GroqClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            options: RequestOptions::default(),
+            dry_run: false,
+            concurrency: 4,
+        }
+    }
+
+    /// Adds a header sent with every request made by this builder
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - The header name
+    /// * `value` - The header value
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options = self.options.header(name, value);
+        self
+    }
+
+    /// Sets the priority of this builder's requests in the client's request queue
+    /// 
+    /// Requests are [`Priority::Interactive`] by default. Without a queue (see
+    /// `GroqClientBuilder::request_queue`) it has no effect.
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.options = self.options.priority(priority);
+        self
+    }
+
+    /// Calls `callback` with the bytes sent so far while uploading a file with `create`
+    /// 
+    /// Files are streamed from disk, so large uploads do not need to fit in
+    /// memory; the callback runs after each 64 KiB read. Content uploaded
+    /// from memory is reported in pieces of the same size.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `callback` - Receives the bytes sent and the file size
+    pub fn upload_progress(mut self, callback: impl Fn(UploadProgress) + Send + Sync + 'static) -> Self {
+        self.options = self.options.upload_progress(callback);
+        self
+    }
+
+    /// Reports what bulk deletions would delete without deleting anything
+    /// 
+    /// Applies to `delete_where` and `delete_all`.
+    pub fn dry_run(mut self, enable: bool) -> Self {
+        self.dry_run = enable;
+        self
+    }
+
+    /// Sets how many uploads or deletions bulk operations run concurrently (default 4)
+    /// 
+    /// Applies to `create_many`, `delete_where` and `delete_all`. Values below
+    /// 1 are treated as 1.
+    pub fn concurrency(mut self, limit: usize) -> Self {
+        self.concurrency = limit.max(1);
+        self
     }
 
     /// Uploads a file to Groq
@@ -164,18 +543,18 @@ impl<'a> FileRequestBuilder<'a> {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::{GroqClientBuilder, FileCreateRequest};
     /// use std::path::PathBuf;
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
+    /// # groqai::doctest::scratch_dir();
+    /// # groqai::doctest::jsonl_file("training_data.jsonl");
     /// 
-    /// let request = FileCreateRequest::new(
-    ///     PathBuf::from("training_data.jsonl"),
-    ///     "fine-tune".to_string()
-    /// )?;
+    /// let request = FileCreateRequest::open(PathBuf::from("training_data.jsonl"), "fine-tune").await?;
     /// 
     /// let file = client.files().create(request).await?;
     /// println!("File uploaded: {} ({} bytes)", file.filename, file.bytes);
@@ -183,9 +562,72 @@ impl<'a> FileRequestBuilder<'a> {
     /// # }
     /// ```
     pub async fn create(self, req: FileCreateRequest) -> Result<WorkFile, GroqError> {
-        let body = serde_json::to_value(req)?;
-        let response = self.client.transport.post_multipart("files", &body).await?;
-        serde_json::from_value(response).map_err(GroqError::from)
+        let body = serde_json::to_value(&req)?;
+        let request = match req.content {
+            Some(content) => Request::post(Endpoint::Files).multipart_file(body, content),
+            None => Request::post(Endpoint::Files).multipart(body),
+        }
+        .options(self.options);
+        self.client.transport.execute(request).await?.json()
+    }
+
+    /// Uploads several files concurrently
+    /// 
+    /// At most [`concurrency`](Self::concurrency) uploads run at a time.
+    /// Uploads rejected with `429` or `503` are retried with the client's
+    /// backoff, honouring `retry-after`, and a `429` holds back the other
+    /// uploads as well. One failed upload does not stop the others.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `requests` - The files to upload
+    /// 
+    /// # Returns
+    /// 
+    /// The result of each upload, in the order of `requests`
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::{GroqClientBuilder, FileCreateRequest};
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
+    /// # groqai::doctest::scratch_dir();
+    /// # for i in 0..24 {
+    /// #     groqai::doctest::jsonl_file(&format!("shard_{:02}.jsonl", i));
+    /// # }
+    /// 
+    /// let mut shards = Vec::new();
+    /// for i in 0..24 {
+    ///     shards.push(FileCreateRequest::open(format!("shard_{:02}.jsonl", i), "batch").await?);
+    /// }
+    /// for result in client.files().concurrency(8).create_many(shards).await {
+    ///     match result {
+    ///         Ok(file) => println!("Uploaded {}", file.id),
+    ///         Err(e) => eprintln!("Upload failed: {}", e),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_many(self, requests: Vec<FileCreateRequest>) -> Vec<Result<WorkFile, GroqError>> {
+        let this = &self;
+        futures::stream::iter(requests)
+            .map(|req| async move {
+                let body = serde_json::to_value(&req)?;
+                let request = match req.content {
+                    Some(content) => Request::post(Endpoint::Files).multipart_file(body, content),
+                    None => Request::post(Endpoint::Files).multipart(body),
+                }
+                .options(this.options.clone());
+                this.client.execute_with_retry(request).await?.json()
+            })
+            .buffered(self.concurrency)
+            .collect()
+            .await
     }
 
     /// Lists all files in your account
@@ -200,12 +642,13 @@ impl<'a> FileRequestBuilder<'a> {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::GroqClientBuilder;
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
     /// 
     /// let files = client.files().list().await?;
     /// for file in files.data {
@@ -215,8 +658,150 @@ impl<'a> FileRequestBuilder<'a> {
     /// # }
     /// ```
     pub async fn list(self) -> Result<WorkFileList, GroqError> {
-        let response = self.client.transport.get_json("files").await?;
-        serde_json::from_value(response).map_err(GroqError::from)
+        let request = Request::get(Endpoint::Files).options(self.options);
+        self.client.transport.execute(request).await?.json()
+    }
+
+    /// Lists one page of files
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Purpose filter, page size and the file to start after
+    /// 
+    /// # Returns
+    /// 
+    /// A `WorkFileList` whose `has_more` tells whether another page follows
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError` if the listing fails
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::api::files::FileListParams;
+    /// use groqai::GroqClientBuilder;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
+    /// 
+    /// let params = FileListParams::new().purpose("batch").limit(20);
+    /// let page = client.files().list_with(params.clone()).await?;
+    /// if let (true, Some(last)) = (page.has_more, page.data.last()) {
+    ///     let next = client.files().list_with(params.after(&last.id)).await?;
+    ///     println!("Next page: {} files", next.data.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_with(self, params: FileListParams) -> Result<WorkFileList, GroqError> {
+        let request = params.request().options(self.options);
+        self.client.transport.execute(request).await?.json()
+    }
+
+    /// Streams every file, fetching the next page when the previous one is used up
+    /// 
+    /// `params.limit` sets the page size and `params.after` where listing
+    /// starts. The stream ends after the first error.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Purpose filter, page size and the file to start after
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use futures::TryStreamExt;
+    /// use groqai::api::files::FileListParams;
+    /// use groqai::GroqClientBuilder;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
+    /// 
+    /// let mut files = client.files().list_stream(FileListParams::new().purpose("batch").limit(100));
+    /// while let Some(file) = files.try_next().await? {
+    ///     println!("File: {} ({} bytes)", file.filename, file.bytes);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_stream(self, params: FileListParams) -> WorkFileStream {
+        let client = self.client.clone();
+        let options = self.options;
+        let pages = futures::stream::try_unfold(Some(params), move |params| {
+            let (client, options) = (client.clone(), options.clone());
+            async move {
+                let Some(mut params) = params else {
+                    return Ok(None);
+                };
+                let request = params.request().options(options);
+                let page: WorkFileList = client.execute_with_retry(request).await?.json()?;
+                let next = match page.data.last() {
+                    Some(last) if page.has_more => {
+                        params.after = Some(last.id.clone());
+                        Some(params)
+                    }
+                    _ => None,
+                };
+                Ok::<_, GroqError>(Some((futures::stream::iter(page.data.into_iter().map(Ok)), next)))
+            }
+        });
+        Box::pin(pages.try_flatten())
+    }
+
+    /// Lists files matching a query
+    /// 
+    /// Follows pagination and applies the query's filters, sending those the
+    /// API supports as query parameters.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `query` - Filters to apply
+    /// 
+    /// # Returns
+    /// 
+    /// An iterator over the matching files
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError` if the listing fails
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::api::files::FileListQuery;
+    /// use groqai::GroqClientBuilder;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
+    /// 
+    /// let files = client.files().list_filtered(FileListQuery::new().purpose("batch")).await?;
+    /// for file in files {
+    ///     println!("File: {} ({})", file.filename, file.created_at);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_filtered(
+        self,
+        query: FileListQuery,
+    ) -> Result<std::vec::IntoIter<WorkFile>, GroqError> {
+        let mut files: Vec<WorkFile> = self
+            .list_pages(query.server_params())
+            .await?
+            .into_iter()
+            .filter(|f| query.matches(f))
+            .collect();
+        if let Some(limit) = query.limit {
+            files.truncate(limit);
+        }
+        Ok(files.into_iter())
     }
 
     /// Retrieves details of a specific file
@@ -235,23 +820,107 @@ impl<'a> FileRequestBuilder<'a> {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::GroqClientBuilder;
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
     /// 
-    /// let file = client.files().retrieve("file_abc123".to_string()).await?;
+    /// let file = client.files().retrieve("file_abc123").await?;
     /// println!("File: {} ({} bytes)", file.filename, file.bytes);
     /// println!("Created: {}", file.created_at);
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn retrieve(self, file_id: String) -> Result<WorkFile, GroqError> {
-        let path = format!("files/{}", file_id);
-        let response = self.client.transport.get_json(&path).await?;
-        serde_json::from_value(response).map_err(GroqError::from)
+    pub async fn retrieve(self, file_id: impl Into<String>) -> Result<WorkFile, GroqError> {
+        let request = Request::get(Endpoint::File(file_id.into())).options(self.options);
+        self.client.transport.execute(request).await?.json()
+    }
+
+    /// Downloads the content of a file
+    /// 
+    /// Waits for the whole file; use [`content_stream`](FileRequestBuilder::content_stream)
+    /// for large files such as batch outputs.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `file_id` - The ID of the file to download, e.g. a batch's `output_file_id`
+    /// 
+    /// # Returns
+    /// 
+    /// The file content as uploaded or, for batch results, as generated
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError` if the file is not found or the download fails
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
+    /// 
+    /// let batch = client.batches().retrieve("batch_abc123").await?;
+    /// if let Some(output_file_id) = &batch.output_file_id {
+    ///     let output = client.files().content(output_file_id).await?;
+    ///     for line in String::from_utf8_lossy(&output).lines() {
+    ///         let result: serde_json::Value = serde_json::from_str(line)?;
+    ///         println!("{}: {}", result["custom_id"], result["response"]["status_code"]);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn content(self, file_id: impl Into<String>) -> Result<Bytes, GroqError> {
+        let chunks: Vec<Bytes> = self.content_stream(file_id).await?.try_collect().await?;
+        Ok(chunks.concat().into())
+    }
+
+    /// Downloads the content of a file as a stream
+    /// 
+    /// The stream yields the file in pieces as they arrive, so large files
+    /// can be written to disk without holding them in memory. Concatenated,
+    /// the pieces are the bytes [`content`](FileRequestBuilder::content) returns.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `file_id` - The ID of the file to download
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError` if the file is not found or the request fails.
+    /// Errors while the file is being received are yielded by the stream.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use futures::StreamExt;
+    /// use groqai::GroqClientBuilder;
+    /// use std::io::Write;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
+    /// # groqai::doctest::scratch_dir();
+    /// 
+    /// let mut content = client.files().content_stream("file_abc123").await?;
+    /// let mut output = std::fs::File::create("batch_output.jsonl")?;
+    /// while let Some(chunk) = content.next().await {
+    ///     output.write_all(&chunk?)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn content_stream(self, file_id: impl Into<String>) -> Result<ByteStream, GroqError> {
+        let request = Request::get(Endpoint::FileContent(file_id.into())).options(self.options);
+        self.client.transport.execute_stream(request).await
     }
 
     /// Deletes a file from your account
@@ -270,23 +939,139 @@ impl<'a> FileRequestBuilder<'a> {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::GroqClientBuilder;
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
     /// 
-    /// let deletion = client.files().delete("file_abc123".to_string()).await?;
+    /// let deletion = client.files().delete("file_abc123").await?;
     /// if deletion.deleted {
     ///     println!("File {} successfully deleted", deletion.id);
     /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn delete(self, file_id: String) -> Result<WorkFileDeletion, GroqError> {
-        let path = format!("files/{}", file_id);
-        let response = self.client.transport.delete_json(&path).await?;
-        serde_json::from_value(response).map_err(GroqError::from)
+    pub async fn delete(self, file_id: impl Into<String>) -> Result<WorkFileDeletion, GroqError> {
+        self.delete_file(&file_id.into()).await
+    }
+
+    /// Deletes every file matching `predicate`
+    /// 
+    /// Lists all files (following pagination), filters them and deletes the
+    /// matches with bounded concurrency. Rate-limited requests are retried
+    /// with backoff; individual deletion failures are collected in the report
+    /// rather than aborting the cleanup.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `predicate` - Returns true for files that should be deleted
+    /// 
+    /// # Returns
+    /// 
+    /// A `FileCleanupReport` listing matched, deleted and failed files
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError` if listing files fails
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::api::files::FilePurpose;
+    /// use groqai::GroqClientBuilder;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
+    /// let cutoff = 1_700_000_000;
+    /// 
+    /// let report = client
+    ///     .files()
+    ///     .dry_run(true)
+    ///     .delete_where(|f| f.purpose == FilePurpose::Batch && f.created_at < cutoff)
+    ///     .await?;
+    /// println!("Would delete {} files", report.matched.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_where<F>(self, predicate: F) -> Result<FileCleanupReport, GroqError>
+    where
+        F: Fn(&WorkFile) -> bool,
+    {
+        self.delete_listed(FileListParams::new(), predicate).await
+    }
+
+    /// Deletes every file with the given purpose
+    /// 
+    /// Like `delete_where(|f| f.purpose == purpose)`, but only lists files
+    /// with that purpose.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `purpose` - The purpose of the files to delete, e.g. `FilePurpose::Batch`
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError` if listing files fails
+    pub async fn delete_all(self, purpose: impl Into<FilePurpose>) -> Result<FileCleanupReport, GroqError> {
+        let purpose = purpose.into();
+        // The API filter is checked again client-side, in case it is ignored
+        self.delete_listed(FileListParams::new().purpose(purpose.clone()), |f| f.purpose == purpose)
+            .await
+    }
+
+    /// Deletes the files listed with `params` that match `predicate`
+    async fn delete_listed<F>(self, params: FileListParams, predicate: F) -> Result<FileCleanupReport, GroqError>
+    where
+        F: Fn(&WorkFile) -> bool,
+    {
+        let matched: Vec<WorkFile> = self
+            .list_pages(params)
+            .await?
+            .into_iter()
+            .filter(|f| predicate(f))
+            .collect();
+        let mut report = FileCleanupReport {
+            matched,
+            ..Default::default()
+        };
+        if self.dry_run {
+            return Ok(report);
+        }
+
+        let ids: Vec<String> = report.matched.iter().map(|f| f.id.clone()).collect();
+        let this = &self;
+        let mut results = futures::stream::iter(ids)
+            .map(|id| async move {
+                let result = this.delete_file(&id).await;
+                (id, result)
+            })
+            .buffer_unordered(self.concurrency);
+        while let Some((id, result)) = results.next().await {
+            match result {
+                Ok(_) => report.deleted.push(id),
+                Err(e) => report.failed.push((id, e)),
+            }
+        }
+        Ok(report)
+    }
+
+    async fn list_pages(&self, params: FileListParams) -> Result<Vec<WorkFile>, GroqError> {
+        let files = FileRequestBuilder {
+            client: self.client,
+            options: self.options.clone(),
+            dry_run: self.dry_run,
+            concurrency: self.concurrency,
+        };
+        files.list_stream(params).try_collect().await
+    }
+
+    async fn delete_file(&self, file_id: &str) -> Result<WorkFileDeletion, GroqError> {
+        let request = Request::delete(Endpoint::File(file_id.to_string())).options(self.options.clone());
+        self.client.execute_with_retry(request).await?.json()
     }
 }
\ No newline at end of file
@@ -2,9 +2,15 @@
 //! 
 //! 微调 API 实现，支持自定义模型训练
 
+use std::collections::HashSet;
+
+use crate::api::poll::{check_status, PollOptions, Poller};
 use crate::client::GroqClient;
+use crate::endpoint::Endpoint;
 use crate::error::GroqError;
+use crate::transport::{Request, RequestOptions};
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 /// Request structure for creating a fine-tuning job
 /// 
@@ -13,7 +19,7 @@ use serde::{Deserialize, Serialize};
 /// 
 /// # Examples
 /// 
-/// ```rust,no_run
+/// ```rust
 /// use groqai::api::fine_tunings::FineTuningCreateRequest;
 /// 
 /// let request = FineTuningCreateRequest {
@@ -23,7 +29,8 @@ use serde::{Deserialize, Serialize};
 ///     type_: "supervised".to_string(),
 /// };
 /// ```
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 pub struct FineTuningCreateRequest {
     /// Base model to fine-tune from
     pub base_model: String,
@@ -31,14 +38,17 @@ pub struct FineTuningCreateRequest {
     pub input_file_id: String,
     /// Name for the fine-tuned model
     pub name: String,
-    /// Type of fine-tuning (e.g., "supervised")
+    /// Type of fine-tuning (e.g., "lora")
+    #[serde(rename = "type", alias = "type_")]
     pub type_: String,
 }
 
 /// Fine-tuning job details
 /// 
 /// This struct represents a fine-tuning job and its current status.
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct FineTuning {
     /// Unique identifier for the fine-tuning job
     pub id: String,
@@ -47,6 +57,7 @@ pub struct FineTuning {
     /// Base model used for fine-tuning
     pub base_model: String,
     /// Type of fine-tuning
+    #[serde(rename = "type", alias = "type_")]
     pub type_: String,
     /// ID of the input training file
     pub input_file_id: String,
@@ -62,8 +73,55 @@ pub struct FineTuning {
     pub error: Option<serde_json::Value>,
 }
 
+impl FineTuning {
+    /// Returns true if the job finished successfully
+    pub fn is_succeeded(&self) -> bool {
+        matches!(self.status.as_str(), "succeeded" | "completed")
+    }
+
+    /// Returns true if the job failed or was cancelled
+    pub fn is_failed(&self) -> bool {
+        matches!(self.status.as_str(), "failed" | "cancelled" | "canceled")
+    }
+}
+
+/// A progress event reported for a fine-tuning job
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct FineTuningEvent {
+    /// Unique identifier for the event
+    pub id: String,
+    /// Timestamp when the event was created
+    pub created_at: u64,
+    /// Severity of the event (e.g., "info", "warn", "error")
+    #[serde(default)]
+    pub level: Option<String>,
+    /// Human-readable description of the event
+    pub message: String,
+    /// Structured event data, such as training metrics
+    #[serde(default)]
+    pub data: Option<serde_json::Value>,
+}
+
+/// List of fine-tuning job events
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct FineTuningEventList {
+    /// Object type identifier
+    pub object: String,
+    /// List of events
+    pub data: Vec<FineTuningEvent>,
+    /// Whether there are more results available
+    #[serde(default)]
+    pub has_more: bool,
+}
+
 /// List of fine-tuning jobs
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct FineTuningList {
     /// Object type identifier
     pub object: String,
@@ -73,6 +131,9 @@ pub struct FineTuningList {
     pub has_more: bool,
 }
 
+/// Callback invoked for each fine-tuning event observed while waiting
+type EventCallback<'a> = Box<dyn FnMut(&FineTuningEvent) + Send + 'a>;
+
 /// Builder for fine-tuning requests
 /// 
 /// This builder provides methods for creating, retrieving, and listing
@@ -80,12 +141,13 @@ pub struct FineTuningList {
 /// 
 /// # Examples
 /// 
-/// ```rust,no_run
+/// ```rust
 /// use groqai::{GroqClientBuilder, FineTuningCreateRequest};
 /// 
 /// # #[tokio::main]
 /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+/// # let client = groqai::doctest::client();
 /// 
 /// let request = FineTuningCreateRequest {
 ///     base_model: "llama-3.1-8b-instant".to_string(),
@@ -101,6 +163,8 @@ pub struct FineTuningList {
 /// ```
 pub struct FineTuningRequestBuilder<'a> {
     client: &'a GroqClient,
+    options: RequestOptions,
+    on_event: Option<EventCallback<'a>>,
 }
 
 impl<'a> FineTuningRequestBuilder<'a> {
@@ -110,7 +174,35 @@ impl<'a> FineTuningRequestBuilder<'a> {
     /// 
     /// * `client` - Reference to the GroqClient
     pub fn new(client: &'a GroqClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            options: RequestOptions::default(),
+            on_event: None,
+        }
+    }
+
+    /// Adds a header sent with every request made by this builder
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - The header name
+    /// * `value` - The header value
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options = self.options.header(name, value);
+        self
+    }
+
+    /// Sets a callback invoked for each new job event while waiting
+    /// 
+    /// Used by `wait_for_completion`. Events are delivered oldest first and
+    /// each event is delivered once.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `callback` - Function called with every newly observed event
+    pub fn on_event(mut self, callback: impl FnMut(&FineTuningEvent) + Send + 'a) -> Self {
+        self.on_event = Some(Box::new(callback));
+        self
     }
 
     /// Creates a new fine-tuning job
@@ -127,9 +219,10 @@ impl<'a> FineTuningRequestBuilder<'a> {
     /// 
     /// Returns `GroqError` if the fine-tuning job creation fails
     pub async fn create(self, req: FineTuningCreateRequest) -> Result<FineTuning, GroqError> {
-        let body = serde_json::to_value(req)?;
-        let response = self.client.transport.post_json("fine_tuning/jobs", &body).await?;
-        serde_json::from_value(response).map_err(GroqError::from)
+        let request = Request::post(Endpoint::FineTuningJobs)
+            .json(serde_json::to_value(req)?)
+            .options(self.options);
+        self.client.transport.execute(request).await?.json()
     }
 
     /// Retrieves details of a specific fine-tuning job
@@ -145,10 +238,9 @@ impl<'a> FineTuningRequestBuilder<'a> {
     /// # Errors
     /// 
     /// Returns `GroqError` if the job is not found or retrieval fails
-    pub async fn retrieve(self, fine_tuning_id: String) -> Result<FineTuning, GroqError> {
-        let path = format!("fine_tuning/jobs/{}", fine_tuning_id);
-        let response = self.client.transport.get_json(&path).await?;
-        serde_json::from_value(response).map_err(GroqError::from)
+    pub async fn retrieve(self, fine_tuning_id: impl Into<String>) -> Result<FineTuning, GroqError> {
+        let request = Request::get(Endpoint::FineTuningJob(fine_tuning_id.into())).options(self.options);
+        self.client.transport.execute(request).await?.json()
     }
 
     /// Lists fine-tuning jobs with optional pagination
@@ -166,20 +258,164 @@ impl<'a> FineTuningRequestBuilder<'a> {
     /// 
     /// Returns `GroqError` if the listing fails
     pub async fn list(self, after: Option<String>, limit: Option<u32>) -> Result<FineTuningList, GroqError> {
-        let mut params = Vec::new();
+        let mut request = Request::get(Endpoint::FineTuningJobs).options(self.options);
         if let Some(after_id) = after {
-            params.push(("after", after_id));
+            request = request.query("after", after_id);
         }
         if let Some(limit_val) = limit {
-            params.push(("limit", limit_val.to_string()));
+            request = request.query("limit", limit_val.to_string());
         }
-        
-        if params.is_empty() {
-            let response = self.client.transport.get_json("fine_tuning/jobs").await?;
-            serde_json::from_value(response).map_err(GroqError::from)
-        } else {
-            let response = self.client.transport.get_with_params("fine_tuning/jobs", &params).await?;
-            serde_json::from_value(response).map_err(GroqError::from)
+        self.client.transport.execute(request).await?.json()
+    }
+
+    /// Lists events for a fine-tuning job
+    /// 
+    /// # Arguments
+    /// 
+    /// * `fine_tuning_id` - The ID of the fine-tuning job
+    /// * `after` - Optional event ID to start listing after (for pagination)
+    /// * `limit` - Optional limit on the number of events to return
+    /// 
+    /// # Returns
+    /// 
+    /// A `FineTuningEventList` containing the job's events
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError` if the job is not found or listing fails
+    pub async fn events(
+        self,
+        fine_tuning_id: String,
+        after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<FineTuningEventList, GroqError> {
+        self.fetch_events(&fine_tuning_id, after, limit).await
+    }
+
+    async fn fetch_events(
+        &self,
+        fine_tuning_id: &str,
+        after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<FineTuningEventList, GroqError> {
+        let mut request = Request::get(Endpoint::FineTuningJobEvents(fine_tuning_id.to_string()))
+            .options(self.options.clone());
+        if let Some(after_id) = after {
+            request = request.query("after", after_id);
+        }
+        if let Some(limit_val) = limit {
+            request = request.query("limit", limit_val.to_string());
+        }
+        self.client.transport.execute(request).await?.json()
+    }
+
+    /// Polls a fine-tuning job until it succeeds, fails or the timeout elapses
+    /// 
+    /// Events from the job's events endpoint are passed to the `on_event`
+    /// callback as they appear. Failures to fetch events are logged and do not
+    /// interrupt waiting. Status checks go through the client's rate limiter
+    /// and backoff, and the last one happens when `opts.timeout` elapses.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `fine_tuning_id` - The ID of the fine-tuning job to wait for
+    /// * `opts` - Polling interval, backoff and timeout
+    /// 
+    /// # Returns
+    /// 
+    /// The final `FineTuning` record of a succeeded job
+    /// 
+    /// # Errors
+    /// 
+    /// Returns `GroqError::JobFailed` with the job's error payload if it failed
+    /// or was cancelled, `GroqError::JobTimeout` if `opts.timeout` elapsed, or
+    /// another `GroqError` if polling fails.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::api::poll::PollOptions;
+    /// use groqai::GroqClient;
+    /// use std::time::Duration;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # groqai::doctest::set_api_key();
+    /// let client = GroqClient::new()?;
+    /// # let client = groqai::doctest::client();
+    /// let job = client
+    ///     .fine_tunings()
+    ///     .on_event(|event| println!("[{}] {}", event.created_at, event.message))
+    ///     .wait_for_completion("ft_abc123", PollOptions::new().interval(Duration::from_secs(30)))
+    ///     .await?;
+    /// println!("Fine-tuned model: {:?}", job.fine_tuned_model);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_for_completion(
+        mut self,
+        fine_tuning_id: impl Into<String>,
+        opts: PollOptions,
+    ) -> Result<FineTuning, GroqError> {
+        let id = fine_tuning_id.into();
+        let endpoint = Endpoint::FineTuningJob(id.clone());
+        let mut poller = Poller::new(opts);
+        let mut seen = HashSet::new();
+        loop {
+            if self.on_event.is_some() {
+                match self.new_events(&id, &seen).await {
+                    Ok(mut events) => {
+                        events.sort_by_key(|e| e.created_at);
+                        if let Some(callback) = self.on_event.as_mut() {
+                            for event in events {
+                                callback(&event);
+                                seen.insert(event.id);
+                            }
+                        }
+                    }
+                    Err(e) => warn!("Failed to fetch fine-tuning events for {}: {}", id, e),
+                }
+            }
+
+            let request = Request::get(endpoint.clone()).options(self.options.clone());
+            let job: FineTuning = check_status(self.client, request).await?;
+            if job.is_succeeded() {
+                return Ok(job);
+            }
+            if job.is_failed() {
+                return Err(GroqError::JobFailed {
+                    id,
+                    status: job.status,
+                    error: job.error,
+                });
+            }
+            poller.wait(&id).await?;
+        }
+    }
+
+    /// Returns the events of a job not in `seen`
+    ///
+    /// Events are listed newest first, so pages are followed with `after`
+    /// until one reaches an event delivered earlier.
+    async fn new_events(&self, fine_tuning_id: &str, seen: &HashSet<String>) -> Result<Vec<FineTuningEvent>, GroqError> {
+        let mut events = Vec::new();
+        let mut after = None;
+        loop {
+            let request = Request::get(Endpoint::FineTuningJobEvents(fine_tuning_id.to_string()))
+                .options(self.options.clone())
+                .query("limit", "100");
+            let request = match after {
+                Some(after_id) => request.query("after", after_id),
+                None => request,
+            };
+            let page: FineTuningEventList = check_status(self.client, request).await?;
+            let last = page.data.last().map(|e| e.id.clone());
+            let reached_seen = page.data.iter().any(|e| seen.contains(&e.id));
+            events.extend(page.data.into_iter().filter(|e| !seen.contains(&e.id)));
+            match last {
+                Some(last) if page.has_more && !reached_seen => after = Some(last),
+                _ => return Ok(events),
+            }
         }
     }
 
@@ -196,10 +432,8 @@ impl<'a> FineTuningRequestBuilder<'a> {
     /// # Errors
     /// 
     /// Returns `GroqError` if the job cannot be cancelled or is not found
-    pub async fn cancel(self, fine_tuning_id: String) -> Result<FineTuning, GroqError> {
-        let path = format!("fine_tuning/jobs/{}/cancel", fine_tuning_id);
-        let body = serde_json::Value::Null;
-        let response = self.client.transport.post_json(&path, &body).await?;
-        serde_json::from_value(response).map_err(GroqError::from)
+    pub async fn cancel(self, fine_tuning_id: impl Into<String>) -> Result<FineTuning, GroqError> {
+        let request = Request::post(Endpoint::FineTuningJobCancel(fine_tuning_id.into())).options(self.options);
+        self.client.transport.execute(request).await?.json()
     }
 }
\ No newline at end of file
@@ -6,16 +6,26 @@
 pub mod chat;
 
 /// Audio transcription and translation API endpoints
+#[cfg(feature = "audio")]
 pub mod audio;
 
 /// Batch processing API endpoints for efficient bulk operations
+#[cfg(feature = "batches")]
 pub mod batches;
 
 /// File management API endpoints for upload, list, retrieve, and delete operations
+#[cfg(feature = "files")]
 pub mod files;
 
 /// Model information API endpoints for listing and retrieving model details
 pub mod models;
 
 /// Fine-tuning API endpoints for custom model training
-pub mod fine_tunings;
\ No newline at end of file
+#[cfg(feature = "fine-tuning")]
+pub mod fine_tunings;
+
+/// Vision helpers (OCR, captioning, question answering) built on multimodal chat completions
+pub mod vision;
+
+/// Polling options shared by helpers that wait for long-running jobs
+pub mod poll;
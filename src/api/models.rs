@@ -3,7 +3,9 @@
 //! 模型 API 实现，用于获取可用模型信息
 
 use crate::client::GroqClient;
+use crate::endpoint::Endpoint;
 use crate::error::GroqError;
+use crate::transport::{Request, RequestOptions};
 use crate::types::{Model, ModelList};
 
 /// Builder for model information requests
@@ -13,12 +15,13 @@ use crate::types::{Model, ModelList};
 /// 
 /// # Examples
 /// 
-/// ```rust,no_run
+/// ```rust
 /// use groqai::GroqClientBuilder;
 /// 
 /// # #[tokio::main]
 /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+/// # let client = groqai::doctest::client();
 /// 
 /// // List all available models
 /// let models = client.models().list().await?;
@@ -27,13 +30,14 @@ use crate::types::{Model, ModelList};
 /// }
 /// 
 /// // Get details of a specific model
-/// let model = client.models().retrieve("llama-3.1-70b-versatile".to_string()).await?;
+/// let model = client.models().retrieve("llama-3.1-70b-versatile").await?;
 /// println!("Context window: {} tokens", model.context_window);
 /// # Ok(())
 /// # }
 /// ```
 pub struct ModelsRequestBuilder<'a> {
     client: &'a GroqClient,
+    options: RequestOptions,
 }
 
 impl<'a> ModelsRequestBuilder<'a> {
@@ -43,7 +47,21 @@ impl<'a> ModelsRequestBuilder<'a> {
     /// 
     /// * `client` - Reference to the GroqClient
     pub fn new(client: &'a GroqClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            options: RequestOptions::default(),
+        }
+    }
+
+    /// Adds a header sent with every request made by this builder
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - The header name
+    /// * `value` - The header value
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options = self.options.header(name, value);
+        self
     }
 
     /// Lists all available models
@@ -58,12 +76,13 @@ impl<'a> ModelsRequestBuilder<'a> {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::GroqClientBuilder;
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
     /// 
     /// let models = client.models().list().await?;
     /// println!("Available models:");
@@ -78,8 +97,8 @@ impl<'a> ModelsRequestBuilder<'a> {
     /// # }
     /// ```
     pub async fn list(self) -> Result<ModelList, GroqError> {
-        let response = self.client.transport.get_json("models").await?;
-        serde_json::from_value(response).map_err(GroqError::from)
+        let request = Request::get(Endpoint::Models).options(self.options);
+        self.client.transport.execute(request).await?.json()
     }
 
     /// Retrieves detailed information about a specific model
@@ -98,14 +117,15 @@ impl<'a> ModelsRequestBuilder<'a> {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::GroqClientBuilder;
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
     /// 
-    /// let model = client.models().retrieve("llama-3.1-70b-versatile".to_string()).await?;
+    /// let model = client.models().retrieve("llama-3.1-70b-versatile").await?;
     /// println!("Model: {}", model.id);
     /// println!("Owner: {}", model.owned_by);
     /// println!("Context Window: {} tokens", model.context_window);
@@ -114,9 +134,8 @@ impl<'a> ModelsRequestBuilder<'a> {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn retrieve(self, model_id: String) -> Result<Model, GroqError> {
-        let path = format!("models/{}", model_id);
-        let response = self.client.transport.get_json(&path).await?;
-        serde_json::from_value(response).map_err(GroqError::from)
+    pub async fn retrieve(self, model_id: impl Into<String>) -> Result<Model, GroqError> {
+        let request = Request::get(Endpoint::Model(model_id.into())).options(self.options);
+        self.client.transport.execute(request).await?.json()
     }
 }
\ No newline at end of file
@@ -0,0 +1,143 @@
+//! Polling options for long-running jobs
+//! 
+//! 长时间运行任务的轮询选项
+
+use std::time::Duration;
+#[cfg(any(feature = "batches", feature = "fine-tuning"))]
+use std::time::Instant;
+
+#[cfg(any(feature = "batches", feature = "fine-tuning"))]
+use crate::client::GroqClient;
+#[cfg(any(feature = "batches", feature = "fine-tuning"))]
+use crate::error::GroqError;
+#[cfg(any(feature = "batches", feature = "fine-tuning"))]
+use crate::transport::Request;
+
+/// Controls how often and how long a waiter polls a long-running job
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use groqai::api::poll::PollOptions;
+/// use std::time::Duration;
+/// 
+/// let opts = PollOptions::new()
+///     .interval(Duration::from_secs(30))
+///     .timeout(Duration::from_secs(4 * 60 * 60))
+///     .backoff(Duration::from_secs(5 * 60));
+/// assert_eq!(opts.interval, Duration::from_secs(30));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollOptions {
+    /// Time to wait between status checks
+    pub interval: Duration,
+    /// Maximum total time to wait, or `None` to wait indefinitely
+    pub timeout: Option<Duration>,
+    /// Upper bound when doubling the interval after each check, or `None` for a fixed interval
+    pub max_interval: Option<Duration>,
+}
+
+impl Default for PollOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(10),
+            timeout: None,
+            max_interval: None,
+        }
+    }
+}
+
+impl PollOptions {
+    /// Creates options polling every 10 seconds without a timeout
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the time to wait between status checks
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the maximum total time to wait
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Doubles the interval after each status check, up to `max_interval`
+    pub fn backoff(mut self, max_interval: Duration) -> Self {
+        self.max_interval = Some(max_interval);
+        self
+    }
+
+    /// Returns the interval to wait after waiting `current`
+    #[cfg(any(feature = "batches", feature = "fine-tuning"))]
+    pub(crate) fn next_interval(&self, current: Duration) -> Duration {
+        match self.max_interval {
+            Some(max) => current.saturating_mul(2).min(max.max(self.interval)),
+            None => self.interval,
+        }
+    }
+}
+
+/// Paces the status checks of a waiter
+///
+/// Shared by the waiters of long-running jobs so they all back off, time out
+/// and retry the same way.
+#[cfg(any(feature = "batches", feature = "fine-tuning"))]
+pub(crate) struct Poller {
+    opts: PollOptions,
+    started: Instant,
+    interval: Duration,
+}
+
+#[cfg(any(feature = "batches", feature = "fine-tuning"))]
+impl Poller {
+    /// Starts the clock of a waiter
+    pub(crate) fn new(opts: PollOptions) -> Self {
+        Self {
+            opts,
+            started: Instant::now(),
+            interval: opts.interval,
+        }
+    }
+
+    /// Returns the time since the waiter started
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Sleeps until the next status check of job `id`
+    ///
+    /// Never sleeps past the timeout, so the last check happens at the
+    /// deadline. Fails with `GroqError::JobTimeout` once it has passed.
+    pub(crate) async fn wait(&mut self, id: &str) -> Result<(), GroqError> {
+        let elapsed = self.elapsed();
+        let sleep = match self.opts.timeout {
+            Some(timeout) if elapsed >= timeout => {
+                return Err(GroqError::JobTimeout {
+                    id: id.to_string(),
+                    elapsed,
+                })
+            }
+            Some(timeout) => self.interval.min(timeout - elapsed),
+            None => self.interval,
+        };
+        tokio::time::sleep(sleep).await;
+        self.interval = self.opts.next_interval(self.interval);
+        Ok(())
+    }
+}
+
+/// Sends a status check through the client's rate limiter and backoff loop
+///
+/// Rate limits and temporary unavailability during a long wait are retried
+/// instead of ending it.
+#[cfg(any(feature = "batches", feature = "fine-tuning"))]
+pub(crate) async fn check_status<T: serde::de::DeserializeOwned>(
+    client: &GroqClient,
+    request: Request,
+) -> Result<T, GroqError> {
+    client.execute_with_retry(request).await?.json()
+}
@@ -0,0 +1,412 @@
+//! Vision convenience API built on multimodal chat completions
+//!
+//! 视觉便捷 API，基于多模态聊天补全实现
+//!
+//! [`VisionRequestBuilder`] covers the common single-image tasks: OCR,
+//! captioning and visual question answering. Each helper picks a default
+//! vision model and image detail level, encodes local files, and returns the
+//! model's answer as plain text.
+
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use serde::Deserialize;
+
+use crate::api::chat::ChatCompletionRequest;
+use crate::client::GroqClient;
+use crate::document::read_file;
+use crate::error::GroqError;
+use crate::types::{ChatMessage, ImageUrl, MessagePart, ResponseFormat, Role};
+
+/// Default vision-capable model
+pub const DEFAULT_VISION_MODEL: &str = "meta-llama/llama-4-scout-17b-16e-instruct";
+
+/// Maximum number of images per request accepted by Groq's vision models
+pub const MAX_IMAGES_PER_REQUEST: usize = 5;
+
+/// Maximum total size of inline (`data:` URL) images in one request
+pub const MAX_INLINE_IMAGE_BYTES: usize = 4 * 1024 * 1024;
+
+const OCR_PROMPT: &str = "Transcribe all text visible in the image exactly as written, \
+preserving line breaks and reading order. Do not describe the image, translate, or add \
+commentary. If there is no text, reply with nothing.";
+
+const DESCRIBE_PROMPT: &str = "Describe this image in a few sentences: the main subject, \
+setting and any notable details. Reply with the description only.";
+
+const ASK_PROMPT: &str = "Answer the question about the image concisely, based only on what \
+is visible. If the image does not show the answer, say so.";
+
+const OCR_STRUCTURED_PROMPT: &str = "Transcribe all text visible in the image exactly as \
+written. Group it into blocks (paragraphs, table cells, labels, captions) in reading order. \
+Reply with a JSON object {\"blocks\": [{\"lines\": [\"...\"]}]}.";
+
+/// An image given by URL, local file or raw bytes
+///
+/// Local files and bytes are sent inline as base64 `data:` URLs.
+///
+/// # Examples
+///
+/// ```rust
+/// use groqai::api::vision::ImageSource;
+///
+/// let remote = ImageSource::from("https://example.com/receipt.png");
+/// let local = ImageSource::from("scans/receipt.png");
+/// assert!(matches!(remote, ImageSource::Url(_)));
+/// assert!(matches!(local, ImageSource::Path(_)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageSource {
+    /// An `http(s)://` or `data:` URL
+    Url(String),
+    /// A local image file; the MIME type is taken from the extension
+    Path(PathBuf),
+    /// Encoded image bytes with their MIME type, e.g. `image/png`
+    Bytes {
+        /// The encoded image
+        data: Vec<u8>,
+        /// MIME type of `data`
+        mime_type: String,
+    },
+}
+
+impl ImageSource {
+    /// Resolves the source to an `ImageUrl`, reading and encoding local files
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if a file cannot be read or has an
+    /// unsupported extension.
+    pub async fn to_image_url(&self) -> Result<ImageUrl, GroqError> {
+        let url = match self {
+            ImageSource::Url(url) => url.clone(),
+            ImageSource::Path(path) => {
+                let mime_type = image_mime_type(path)?;
+                let data = read_file(path)
+                    .await
+                    .map_err(|e| GroqError::InvalidMessage(format!("Failed to open file: {}", e)))?;
+                data_url(mime_type, &data)
+            }
+            ImageSource::Bytes { data, mime_type } => data_url(mime_type, data),
+        };
+        Ok(ImageUrl::new(url))
+    }
+}
+
+impl From<&str> for ImageSource {
+    /// Treats `http://`, `https://` and `data:` strings as URLs, anything else as a path
+    fn from(source: &str) -> Self {
+        if ["http://", "https://", "data:"].iter().any(|p| source.starts_with(p)) {
+            ImageSource::Url(source.to_string())
+        } else {
+            ImageSource::Path(PathBuf::from(source))
+        }
+    }
+}
+
+impl From<String> for ImageSource {
+    fn from(source: String) -> Self {
+        ImageSource::from(source.as_str())
+    }
+}
+
+impl From<PathBuf> for ImageSource {
+    fn from(path: PathBuf) -> Self {
+        ImageSource::Path(path)
+    }
+}
+
+impl From<&Path> for ImageSource {
+    fn from(path: &Path) -> Self {
+        ImageSource::Path(path.to_path_buf())
+    }
+}
+
+fn data_url(mime_type: &str, data: &[u8]) -> String {
+    format!(
+        "data:{};base64,{}",
+        mime_type,
+        base64::engine::general_purpose::STANDARD.encode(data)
+    )
+}
+
+fn image_mime_type(path: &Path) -> Result<&'static str, GroqError> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("png") => Ok("image/png"),
+        Some("jpg") | Some("jpeg") => Ok("image/jpeg"),
+        Some("gif") => Ok("image/gif"),
+        Some("webp") => Ok("image/webp"),
+        _ => Err(GroqError::InvalidMessage(format!(
+            "Unsupported image type: {}",
+            path.display()
+        ))),
+    }
+}
+
+/// Returns the image limit of `model`, or `None` if the model is not known
+/// to accept images
+pub fn max_images_for_model(model: &str) -> Option<usize> {
+    let model = model.to_ascii_lowercase();
+    if model.contains("llama-4") || model.contains("vision") {
+        Some(MAX_IMAGES_PER_REQUEST)
+    } else {
+        None
+    }
+}
+
+/// Checks the images of a chat request against the model's image limit and
+/// the inline payload limit
+///
+/// Requests without images always pass. Models without a known limit are
+/// only checked for payload size.
+///
+/// # Errors
+///
+/// Returns `GroqError::InvalidMessage` naming the limit that was exceeded.
+pub fn validate_images(request: &ChatCompletionRequest) -> Result<(), GroqError> {
+    let images: Vec<&ImageUrl> = request
+        .messages
+        .iter()
+        .flat_map(|m| m.content.images())
+        .collect();
+    if images.is_empty() {
+        return Ok(());
+    }
+    if let Some(max) = max_images_for_model(&request.model) {
+        if images.len() > max {
+            return Err(GroqError::InvalidMessage(format!(
+                "Model {} accepts at most {} images per request, got {}",
+                request.model,
+                max,
+                images.len()
+            )));
+        }
+    }
+    let inline_bytes: usize = images
+        .iter()
+        .filter(|image| image.url.starts_with("data:"))
+        .map(|image| image.url.len())
+        .sum();
+    if inline_bytes > MAX_INLINE_IMAGE_BYTES {
+        return Err(GroqError::InvalidMessage(format!(
+            "Inline images total {} bytes, more than the {} byte limit; pass large images by URL",
+            inline_bytes, MAX_INLINE_IMAGE_BYTES
+        )));
+    }
+    Ok(())
+}
+
+/// A block of text found by [`VisionRequestBuilder::ocr_structured`]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct OcrBlock {
+    /// Lines of the block in reading order
+    #[serde(default)]
+    pub lines: Vec<String>,
+}
+
+/// Text found in an image, grouped into blocks
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct OcrResult {
+    /// Blocks in reading order
+    #[serde(default)]
+    pub blocks: Vec<OcrBlock>,
+}
+
+impl OcrResult {
+    /// Returns all text, with lines separated by newlines and blocks by blank lines
+    pub fn text(&self) -> String {
+        self.blocks
+            .iter()
+            .map(|b| b.lines.join("\n"))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// Builder for vision helpers
+///
+/// # Examples
+///
+/// ```rust
+/// use groqai::GroqClient;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # groqai::doctest::set_api_key();
+/// let client = GroqClient::new()?;
+/// # let client = groqai::doctest::client();
+/// # groqai::doctest::scratch_dir();
+/// # groqai::doctest::image_file("scans/receipt.png");
+/// let text = client.vision().ocr("scans/receipt.png").await?;
+/// println!("{}", text);
+/// # Ok(())
+/// # }
+/// ```
+pub struct VisionRequestBuilder<'a> {
+    client: &'a GroqClient,
+    model: String,
+    detail: Option<String>,
+}
+
+impl<'a> VisionRequestBuilder<'a> {
+    /// Creates a new vision request builder
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Reference to the GroqClient
+    pub fn new(client: &'a GroqClient) -> Self {
+        Self {
+            client,
+            model: DEFAULT_VISION_MODEL.to_string(),
+            detail: None,
+        }
+    }
+
+    /// Sets the vision-capable model
+    ///
+    /// Defaults to [`DEFAULT_VISION_MODEL`].
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    /// Overrides the image detail level (`"low"`, `"high"` or `"auto"`)
+    ///
+    /// By default OCR uses `"high"` so small print stays legible, and
+    /// `describe`/`ask` use `"auto"`.
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Describes an image in a few sentences
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - URL, local path or bytes of the image
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError` if the image cannot be read or the request fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = groqai::doctest::client();
+    /// # groqai::doctest::scratch_dir();
+    /// # groqai::doctest::image_file("photos/beach.jpg");
+    /// let caption = client.vision().describe("photos/beach.jpg").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn describe(self, image: impl Into<ImageSource>) -> Result<String, GroqError> {
+        let image = self.resolve(image.into(), "auto").await?;
+        self.complete(DESCRIBE_PROMPT, image, false).await
+    }
+
+    /// Answers a question about an image
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - URL, local path or bytes of the image
+    /// * `question` - What to ask about the image
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError` if the image cannot be read or the request fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use groqai::GroqClient;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = groqai::doctest::client();
+    /// let answer = client
+    ///     .vision()
+    ///     .ask("https://example.com/street.jpg", "How many cars are parked?")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn ask(
+        self,
+        image: impl Into<ImageSource>,
+        question: impl Into<String>,
+    ) -> Result<String, GroqError> {
+        let image = self.resolve(image.into(), "auto").await?;
+        let prompt = format!("{}\n\nQuestion: {}", ASK_PROMPT, question.into());
+        self.complete(&prompt, image, false).await
+    }
+
+    /// Extracts the text in an image
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - URL, local path or bytes of the image
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError` if the image cannot be read or the request fails.
+    pub async fn ocr(self, image: impl Into<ImageSource>) -> Result<String, GroqError> {
+        let image = self.resolve(image.into(), "high").await?;
+        self.complete(OCR_PROMPT, image, false).await
+    }
+
+    /// Extracts the text in an image as blocks of lines using JSON mode
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - URL, local path or bytes of the image
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError` if the image cannot be read, the request fails, or
+    /// the model's reply is not in the expected shape.
+    pub async fn ocr_structured(self, image: impl Into<ImageSource>) -> Result<OcrResult, GroqError> {
+        let image = self.resolve(image.into(), "high").await?;
+        let reply = self.complete(OCR_STRUCTURED_PROMPT, image, true).await?;
+        serde_json::from_str(&reply).map_err(GroqError::from)
+    }
+
+    /// Resolves the image and applies the detail level
+    async fn resolve(&self, image: ImageSource, default_detail: &str) -> Result<ImageUrl, GroqError> {
+        let mut image = image.to_image_url().await?;
+        image.detail = Some(self.detail.clone().unwrap_or_else(|| default_detail.to_string()));
+        Ok(image)
+    }
+
+    /// Sends one image with an instruction and returns the reply text
+    async fn complete(self, prompt: &str, image: ImageUrl, json: bool) -> Result<String, GroqError> {
+        let mut request = self
+            .client
+            .chat(self.model)
+            .message(ChatMessage::new_multimodal(
+                Role::User,
+                vec![
+                    MessagePart::Text { text: prompt.to_string() },
+                    MessagePart::ImageUrl { image_url: image },
+                ],
+            ))
+            .temperature(0.0)
+            .max_completion_tokens(4096);
+        if json {
+            request = request.response_format(ResponseFormat {
+                type_: "json_object".to_string(),
+                json_schema: None,
+            });
+        }
+        let response = request.send().await?;
+        response
+            .choices
+            .first()
+            .map(|c| c.message.content.to_string())
+            .ok_or_else(|| GroqError::InvalidMessage("Response contained no choices".to_string()))
+    }
+}
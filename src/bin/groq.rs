@@ -0,0 +1,296 @@
+//! `groq` command-line tool built on the groqai library
+//!
+//! 基于 groqai 库的 `groq` 命令行工具
+//!
+//! Build with `cargo install groqai --features cli`. Reads `GROQ_API_KEY` (and
+//! optionally `GROQ_PROXY_URL` / `GROQ_TIMEOUT_SECS` / `GROQ_BASE_URL`) from
+//! the environment.
+
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use futures::StreamExt;
+use groqai::api::batches::{BatchCreateRequest, BatchEndpoint, CompletionWindow};
+use groqai::api::files::FileCreateRequest;
+use groqai::{AudioTranscriptionRequest, ChatMessage, GroqClient, GroqError, Role};
+
+#[derive(Parser)]
+#[command(name = "groq", version, about = "Command-line client for the Groq API")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Interactive chat session
+    Chat {
+        /// Model to chat with
+        #[arg(short, long, default_value = "llama-3.1-8b-instant")]
+        model: String,
+        /// Optional system prompt
+        #[arg(short, long)]
+        system: Option<String>,
+        /// Print tokens as they arrive
+        #[arg(long)]
+        stream: bool,
+    },
+    /// Transcribe an audio file
+    Transcribe {
+        /// Path to the audio file
+        file: PathBuf,
+        /// Transcription model
+        #[arg(short, long, default_value = "whisper-large-v3")]
+        model: String,
+        /// Language of the audio (ISO-639-1)
+        #[arg(short, long)]
+        language: Option<String>,
+    },
+    /// List available models
+    Models,
+    /// Manage files
+    #[command(subcommand)]
+    Files(FilesCommand),
+    /// Manage batches
+    #[command(subcommand)]
+    Batches(BatchesCommand),
+}
+
+#[derive(Subcommand)]
+enum FilesCommand {
+    /// List uploaded files
+    List,
+    /// Upload a JSONL file
+    Upload {
+        /// Path to the file
+        file: PathBuf,
+        /// Purpose of the file
+        #[arg(short, long, default_value = "batch")]
+        purpose: String,
+    },
+    /// Print the content of a file, such as a batch's output
+    Content {
+        /// ID of the file
+        id: String,
+    },
+    /// Delete a file
+    Delete {
+        /// ID of the file
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum BatchesCommand {
+    /// List batches
+    List {
+        /// Maximum number of batches to show
+        #[arg(short, long)]
+        limit: Option<u32>,
+    },
+    /// Create a batch from an uploaded file
+    Create {
+        /// ID of the uploaded input file
+        input_file_id: String,
+        /// Completion window, e.g. 24h or 7d
+        #[arg(short, long, default_value = "24h")]
+        window: String,
+    },
+    /// Show a batch
+    Get {
+        /// ID of the batch
+        id: String,
+    },
+    /// Cancel a batch
+    Cancel {
+        /// ID of the batch
+        id: String,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    if let Err(e) = run(cli).await {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), GroqError> {
+    let client = GroqClient::from_env()?;
+    match cli.command {
+        Command::Chat {
+            model,
+            system,
+            stream,
+        } => chat(&client, &model, system, stream).await,
+        Command::Transcribe {
+            file,
+            model,
+            language,
+        } => {
+            let request = AudioTranscriptionRequest {
+                file: Some(file),
+                model,
+                language,
+                ..Default::default()
+            };
+            let transcription = client.audio().transcribe(request).await?;
+            println!("{}", transcription.text);
+            Ok(())
+        }
+        Command::Models => {
+            println!("{}", client.models().list().await?);
+            Ok(())
+        }
+        Command::Files(command) => files(&client, command).await,
+        Command::Batches(command) => batches(&client, command).await,
+    }
+}
+
+async fn chat(
+    client: &GroqClient,
+    model: &str,
+    system: Option<String>,
+    stream: bool,
+) -> Result<(), GroqError> {
+    let mut history = Vec::new();
+    if let Some(system) = system {
+        history.push(ChatMessage::new_text(Role::System, system));
+    }
+    loop {
+        print!("> ");
+        io::stdout().flush().ok();
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input).unwrap_or(0) == 0 {
+            return Ok(());
+        }
+        let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+        if matches!(input, "exit" | "quit" | "/exit" | "/quit") {
+            return Ok(());
+        }
+        history.push(ChatMessage::new_text(Role::User, input));
+
+        // 请求失败时丢弃本轮输入，会话继续
+        match reply(client, model, &history, stream).await {
+            Ok(reply) => history.push(ChatMessage::new_text(Role::Assistant, reply)),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                history.pop();
+            }
+        }
+    }
+}
+
+/// Sends the conversation and prints the reply as it arrives
+async fn reply(client: &GroqClient, model: &str, history: &[ChatMessage], stream: bool) -> Result<String, GroqError> {
+    let request = client.chat(model).messages(history.to_vec());
+    if !stream {
+        let response = request.send().await?;
+        println!("{}", response);
+        return Ok(response
+            .choices
+            .first()
+            .map(|c| c.message.content.to_string())
+            .unwrap_or_default());
+    }
+    let mut chunks = request.stream(true).send_stream().await?;
+    let mut reply = String::new();
+    while let Some(chunk) = chunks.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                println!();
+                return Err(e);
+            }
+        };
+        if let Some(content) = chunk.choices.first().and_then(|c| c.delta.content.as_ref()) {
+            let text = content.to_string();
+            print!("{}", text);
+            io::stdout().flush().ok();
+            reply.push_str(&text);
+        }
+    }
+    println!();
+    Ok(reply)
+}
+
+async fn files(client: &GroqClient, command: FilesCommand) -> Result<(), GroqError> {
+    match command {
+        FilesCommand::List => {
+            let files = client.files().list().await?;
+            let rows: Vec<Vec<String>> = files
+                .data
+                .into_iter()
+                .map(|f| vec![f.id, f.filename, f.purpose.into(), f.bytes.to_string(), f.created_at.to_string()])
+                .collect();
+            println!(
+                "{}",
+                groqai::pretty::table(&["ID", "FILENAME", "PURPOSE", "BYTES", "CREATED"], &rows)
+            );
+        }
+        FilesCommand::Upload { file, purpose } => {
+            let file = client.files().create(FileCreateRequest::open(file, purpose).await?).await?;
+            println!("{}", file.id);
+        }
+        FilesCommand::Content { id } => {
+            let io_err = |e: io::Error| GroqError::InvalidMessage(format!("Failed to write file content: {}", e));
+            let mut content = client.files().content_stream(id).await?;
+            let mut stdout = io::stdout().lock();
+            while let Some(chunk) = content.next().await {
+                stdout.write_all(&chunk?).map_err(io_err)?;
+            }
+            stdout.flush().map_err(io_err)?;
+        }
+        FilesCommand::Delete { id } => {
+            let deletion = client.files().delete(id).await?;
+            println!("{} deleted: {}", deletion.id, deletion.deleted);
+        }
+    }
+    Ok(())
+}
+
+async fn batches(client: &GroqClient, command: BatchesCommand) -> Result<(), GroqError> {
+    match command {
+        BatchesCommand::List { limit } => {
+            println!("{}", client.batches().list(None, limit).await?);
+        }
+        BatchesCommand::Create {
+            input_file_id,
+            window,
+        } => {
+            let request = BatchCreateRequest {
+                input_file_id,
+                endpoint: BatchEndpoint::ChatCompletions,
+                completion_window: CompletionWindow::from(window),
+                metadata: None,
+            };
+            let batch = client.batches().create(request).await?;
+            println!("{} {}", batch.id, batch.status);
+        }
+        BatchesCommand::Get { id } => {
+            let batch = client.batches().retrieve(id).await?;
+            let counts = &batch.request_counts;
+            println!("id:       {}", batch.id);
+            println!("status:   {}", batch.status);
+            println!("endpoint: {}", batch.endpoint);
+            println!("progress: {}/{} ({} failed)", counts.completed, counts.total, counts.failed);
+            if let Some(output) = &batch.output_file_id {
+                println!("output:   {}", output);
+            }
+            if let Some(errors) = &batch.error_file_id {
+                println!("errors:   {}", errors);
+            }
+        }
+        BatchesCommand::Cancel { id } => {
+            let batch = client.batches().cancel(id).await?;
+            println!("{} {}", batch.id, batch.status);
+        }
+    }
+    Ok(())
+}
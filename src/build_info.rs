@@ -0,0 +1,93 @@
+//! Build information for bug reports and support
+//!
+//! 构建信息：SDK 版本、已启用的功能与目标平台
+//!
+//! [`VERSION`] is the crate version and [`BuildInfo`] adds the enabled
+//! features and the target platform; its `Display` output is meant to be
+//! pasted into bug reports. Clients built with the SDK's HTTP transport also
+//! send the version in the `x-groqai-sdk` header, which
+//! [`GroqClientBuilder::disable_sdk_header`](crate::GroqClientBuilder::disable_sdk_header)
+//! turns off.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::BuildInfo;
+//!
+//! let info = BuildInfo::current();
+//! assert_eq!(info.version, groqai::VERSION);
+//! println!("{}", info); // groqai 0.1.10 (linux x86_64; features: html, pdf)
+//! ```
+
+use std::fmt;
+
+/// Version of the groqai crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Name of the header identifying the SDK
+pub const SDK_HEADER: &str = "x-groqai-sdk";
+
+/// Returns the default value of the [`SDK_HEADER`], `groqai-rust/<version>`
+pub fn sdk_header_value() -> String {
+    format!("groqai-rust/{}", VERSION)
+}
+
+/// Version, enabled features and target of this build of the SDK
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// Crate version, the same as [`VERSION`]
+    pub version: &'static str,
+    /// Optional Cargo features the crate was built with
+    pub features: Vec<&'static str>,
+    /// Target operating system, e.g. `linux`
+    pub target_os: &'static str,
+    /// Target architecture, e.g. `x86_64`
+    pub target_arch: &'static str,
+    /// Whether debug assertions are enabled
+    pub debug: bool,
+}
+
+impl BuildInfo {
+    /// Returns the information for the running build
+    pub fn current() -> Self {
+        let features = [
+            ("audio", cfg!(feature = "audio")),
+            ("batches", cfg!(feature = "batches")),
+            ("cli", cfg!(feature = "cli")),
+            ("encrypted-cache", cfg!(feature = "encrypted-cache")),
+            ("files", cfg!(feature = "files")),
+            ("fine-tuning", cfg!(feature = "fine-tuning")),
+            ("html", cfg!(feature = "html")),
+            ("jsonschema", cfg!(feature = "jsonschema")),
+            ("metrics", cfg!(feature = "metrics")),
+            ("middleware", cfg!(feature = "middleware")),
+            ("pdf", cfg!(feature = "pdf")),
+            ("redis", cfg!(feature = "redis")),
+            ("schema-export", cfg!(feature = "schema-export")),
+            ("semantic-cache", cfg!(feature = "semantic-cache")),
+            ("sqlite", cfg!(feature = "sqlite")),
+            ("streaming", cfg!(feature = "streaming")),
+            ("strict-schema", cfg!(feature = "strict-schema")),
+        ];
+        Self {
+            version: VERSION,
+            features: features.iter().filter(|(_, on)| *on).map(|(name, _)| *name).collect(),
+            target_os: std::env::consts::OS,
+            target_arch: std::env::consts::ARCH,
+            debug: cfg!(debug_assertions),
+        }
+    }
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "groqai {} ({} {}", self.version, self.target_os, self.target_arch)?;
+        if !self.features.is_empty() {
+            write!(f, "; features: {}", self.features.join(", "))?;
+        }
+        if self.debug {
+            f.write_str("; debug")?;
+        }
+        f.write_str(")")
+    }
+}
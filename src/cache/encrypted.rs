@@ -0,0 +1,234 @@
+//! AES-256-GCM encrypted on-disk response cache
+//!
+//! 基于 AES-256-GCM 加密的磁盘响应缓存
+//!
+//! Each entry is one file named after the SHA-256 of its key, holding a random
+//! 96-bit nonce followed by the encrypted entry. The cache key itself is
+//! stored inside the ciphertext, so file names reveal nothing about prompts.
+//! Files written with another key, or tampered with, fail authentication and
+//! are reported as errors (and therefore misses).
+//!
+//! Every write sweeps the directory: entries older than the
+//! [`ttl`](EncryptedFileCache::ttl) are deleted, then the oldest entries beyond
+//! [`max_entries`](EncryptedFileCache::max_entries), if set.
+//!
+//! The 256-bit key is supplied by the application, e.g. from the OS keyring,
+//! or read from the `GROQ_CACHE_KEY` environment variable as 64 hex digits.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::cache::encrypted::EncryptedFileCache;
+//! use groqai::GroqClientBuilder;
+//!
+//! # groqai::doctest::scratch_dir();
+//! # std::env::set_var("GROQ_CACHE_KEY", "00".repeat(32));
+//! let cache = EncryptedFileCache::from_env("cache")?;
+//! let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+//!     .response_cache(cache)
+//!     .build()?;
+//! # Ok::<(), groqai::GroqError>(())
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::ResponseCache;
+use crate::error::GroqError;
+use crate::types::ChatCompletionResponse;
+
+/// Environment variable read by [`EncryptedFileCache::from_env`]
+pub const CACHE_KEY_ENV: &str = "GROQ_CACHE_KEY";
+
+const NONCE_LEN: usize = 12;
+
+#[derive(Serialize, Deserialize)]
+struct StoredEntry {
+    key: String,
+    stored_at: u64,
+    response: ChatCompletionResponse,
+}
+
+/// Response cache persisting AES-256-GCM encrypted entries in a directory
+pub struct EncryptedFileCache {
+    dir: PathBuf,
+    cipher: Aes256Gcm,
+    ttl: Option<Duration>,
+    max_entries: Option<usize>,
+}
+
+impl EncryptedFileCache {
+    /// Creates a cache in `dir` encrypted with `key`
+    ///
+    /// The directory is created on the first write.
+    pub fn new(dir: impl Into<PathBuf>, key: [u8; 32]) -> Self {
+        Self {
+            dir: dir.into(),
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)),
+            ttl: None,
+            max_entries: None,
+        }
+    }
+
+    /// Creates a cache in `dir` with the key from `GROQ_CACHE_KEY`
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if the variable is unset or not 64
+    /// hex digits.
+    pub fn from_env(dir: impl Into<PathBuf>) -> Result<Self, GroqError> {
+        let hex = std::env::var(CACHE_KEY_ENV)
+            .map_err(|_| GroqError::InvalidMessage(format!("{} is not set", CACHE_KEY_ENV)))?;
+        Ok(Self::new(dir, parse_hex_key(hex.trim())?))
+    }
+
+    /// Expires entries older than `ttl`
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Keeps at most `max_entries` files, deleting the oldest; values below 1 are treated as 1
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries.max(1));
+        self
+    }
+
+    /// Returns the cache directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.bin", to_hex(&Sha256::digest(key.as_bytes()))))
+    }
+
+    /// Deletes expired entries, then the oldest beyond `max_entries`
+    ///
+    /// Ages come from the file modification times, so expired entries are
+    /// found without decrypting them.
+    async fn sweep(&self) -> std::io::Result<()> {
+        if self.ttl.is_none() && self.max_entries.is_none() {
+            return Ok(());
+        }
+        let mut files = Vec::new();
+        let mut dir = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "bin") {
+                let modified = entry.metadata().await?.modified()?;
+                files.push((modified, path));
+            }
+        }
+        let now = SystemTime::now();
+        if let Some(ttl) = self.ttl {
+            let (expired, live): (Vec<_>, Vec<_>) = files
+                .into_iter()
+                .partition(|(modified, _)| now.duration_since(*modified).is_ok_and(|age| age >= ttl));
+            for (_, path) in expired {
+                let _ = tokio::fs::remove_file(path).await;
+            }
+            files = live;
+        }
+        if let Some(max) = self.max_entries {
+            files.sort();
+            let excess = files.len().saturating_sub(max);
+            for (_, path) in files.into_iter().take(excess) {
+                let _ = tokio::fs::remove_file(path).await;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ResponseCache for EncryptedFileCache {
+    async fn get(&self, key: &str) -> Result<Option<ChatCompletionResponse>, GroqError> {
+        let path = self.path_for(key);
+        let data = match tokio::fs::read(&path).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(GroqError::InvalidMessage(format!("Failed to read cache entry: {}", e))),
+        };
+        if data.len() < NONCE_LEN {
+            return Err(GroqError::InvalidMessage("Cache entry is truncated".to_string()));
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| GroqError::InvalidMessage("Failed to decrypt cache entry".to_string()))?;
+        let entry: StoredEntry = serde_json::from_slice(&plaintext)?;
+        if entry.key != key {
+            return Ok(None);
+        }
+        if let Some(ttl) = self.ttl {
+            if unix_now().saturating_sub(entry.stored_at) >= ttl.as_secs() {
+                let _ = tokio::fs::remove_file(&path).await;
+                return Ok(None);
+            }
+        }
+        Ok(Some(entry.response))
+    }
+
+    async fn put(&self, key: &str, response: &ChatCompletionResponse) -> Result<(), GroqError> {
+        let entry = StoredEntry {
+            key: key.to_string(),
+            stored_at: unix_now(),
+            response: response.clone(),
+        };
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, serde_json::to_vec(&entry)?.as_slice())
+            .map_err(|_| GroqError::InvalidMessage("Failed to encrypt cache entry".to_string()))?;
+        // The random nonce also names the temporary file, so concurrent writes never share one
+        let tmp_name = format!("{}.tmp", to_hex(&nonce));
+        let mut data = nonce.to_vec();
+        data.extend_from_slice(&ciphertext);
+
+        let io_err = |e: std::io::Error| GroqError::InvalidMessage(format!("Failed to write cache entry: {}", e));
+        tokio::fs::create_dir_all(&self.dir).await.map_err(io_err)?;
+        // Write then rename so readers never observe a partial entry
+        let path = self.path_for(key);
+        let tmp = self.dir.join(tmp_name);
+        tokio::fs::write(&tmp, data).await.map_err(io_err)?;
+        if let Err(e) = tokio::fs::rename(&tmp, &path).await {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(io_err(e));
+        }
+        self.sweep()
+            .await
+            .map_err(|e| GroqError::InvalidMessage(format!("Failed to sweep cache directory: {}", e)))
+    }
+}
+
+fn parse_hex_key(hex: &str) -> Result<[u8; 32], GroqError> {
+    let invalid = || GroqError::InvalidMessage(format!("{} must be 64 hex digits", CACHE_KEY_ENV));
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    let mut key = [0u8; 32];
+    for (byte, pair) in key.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        let pair = std::str::from_utf8(pair).map_err(|_| invalid())?;
+        *byte = u8::from_str_radix(pair, 16).map_err(|_| invalid())?;
+    }
+    Ok(key)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
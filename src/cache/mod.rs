@@ -0,0 +1,181 @@
+//! Response caching for chat completions
+//!
+//! 聊天补全响应缓存模块
+//!
+//! A [`ResponseCache`] attached with
+//! [`GroqClientBuilder::response_cache`](crate::GroqClientBuilder::response_cache)
+//! serves repeated non-streaming chat requests without calling the API. Keys
+//! are derived from the full request body with [`cache_key`], so any change in
+//! model, messages or sampling parameters is a miss. Only deterministic
+//! requests (temperature `0` or a fixed `seed`) are cached unless a request
+//! opts in with [`ChatRequestBuilder::cache(true)`](crate::ChatRequestBuilder::cache),
+//! and any request can bypass the cache with `cache(false)`.
+//!
+//! Backends:
+//!
+//! - [`MemoryCache`]: in-process, bounded, optionally expiring entries.
+//! - [`EncryptedFileCache`](encrypted::EncryptedFileCache): AES-256-GCM
+//!   encrypted files that survive restarts (feature `encrypted-cache`).
+//!
+//! [`PrefixCache`](prefix::PrefixCache), attached with
+//! [`GroqClientBuilder::prefix_cache`](crate::GroqClientBuilder::prefix_cache),
+//! caches only deterministic requests, keyed by conversation prefix, and can
+//! drop the replies that depend on an edited message.
+//!
+//! [`SemanticCache`](semantic::SemanticCache), attached with
+//! `GroqClientBuilder::semantic_cache` (feature `semantic-cache`), serves the
+//! reply to an earlier prompt that means nearly the same thing.
+//!
+//! Responses served from any of these caches report it in
+//! [`ChatCompletionResponse::cache_hit`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::cache::MemoryCache;
+//! use groqai::GroqClientBuilder;
+//! use std::time::Duration;
+//!
+//! let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+//!     .response_cache(MemoryCache::with_ttl(Duration::from_secs(3600)))
+//! #     .transport(groqai::doctest::FixtureTransport::new())
+//!     .build()?;
+//! # Ok::<(), groqai::GroqError>(())
+//! ```
+
+#[cfg(feature = "encrypted-cache")]
+pub mod encrypted;
+pub mod prefix;
+#[cfg(feature = "semantic-cache")]
+pub mod semantic;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::api::chat::ChatCompletionRequest;
+use crate::error::GroqError;
+use crate::types::ChatCompletionResponse;
+
+/// Which cache served a response
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CacheHit {
+    /// The [`ResponseCache`] held a reply to the identical request
+    Exact,
+    /// The [`PrefixCache`](prefix::PrefixCache) held a reply to the identical conversation
+    Prefix,
+    /// A [`SemanticCache`](semantic::SemanticCache) held a reply to a similar prompt
+    #[cfg(feature = "semantic-cache")]
+    Semantic {
+        /// Cosine similarity of the prompt to the cached one
+        similarity: f64,
+    },
+}
+
+/// Storage for cached chat completion responses
+///
+/// Errors returned by a cache are logged and treated as misses; they never
+/// fail the request.
+#[async_trait]
+pub trait ResponseCache: Send + Sync {
+    /// Returns the response stored under `key`, if any
+    async fn get(&self, key: &str) -> Result<Option<ChatCompletionResponse>, GroqError>;
+
+    /// Stores `response` under `key`
+    async fn put(&self, key: &str, response: &ChatCompletionResponse) -> Result<(), GroqError>;
+}
+
+/// Computes the cache key of a request
+///
+/// The key is the serialized request body, which is stable across processes
+/// and releases of this crate as long as the request type is unchanged.
+pub fn cache_key(request: &ChatCompletionRequest) -> Result<String, GroqError> {
+    Ok(serde_json::to_string(request)?)
+}
+
+const DEFAULT_MAX_ENTRIES: usize = 1000;
+
+/// In-memory response cache
+///
+/// Keeps at most 1000 entries by default. Expired entries are dropped when
+/// another is stored; when still full, the oldest entry is dropped.
+pub struct MemoryCache {
+    ttl: Option<Duration>,
+    max_entries: usize,
+    entries: Mutex<HashMap<String, (Instant, ChatCompletionResponse)>>,
+}
+
+impl Default for MemoryCache {
+    fn default() -> Self {
+        Self {
+            ttl: None,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            entries: Mutex::default(),
+        }
+    }
+}
+
+impl MemoryCache {
+    /// Creates a cache whose entries never expire
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a cache whose entries expire after `ttl`
+    pub fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            ttl: Some(ttl),
+            ..Self::default()
+        }
+    }
+
+    /// Sets how many entries are kept; values below 1 are treated as 1
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries.max(1);
+        self
+    }
+
+    /// Returns the number of stored entries, including expired ones not yet evicted
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Returns `true` if the cache holds no entries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait]
+impl ResponseCache for MemoryCache {
+    async fn get(&self, key: &str) -> Result<Option<ChatCompletionResponse>, GroqError> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        match (entries.get(key), self.ttl) {
+            (Some((stored, _)), Some(ttl)) if stored.elapsed() >= ttl => {
+                entries.remove(key);
+                Ok(None)
+            }
+            (entry, _) => Ok(entry.map(|(_, response)| response.clone())),
+        }
+    }
+
+    async fn put(&self, key: &str, response: &ChatCompletionResponse) -> Result<(), GroqError> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(ttl) = self.ttl {
+            entries.retain(|_, (stored, _)| stored.elapsed() < ttl);
+        }
+        if !entries.contains_key(key) {
+            while entries.len() >= self.max_entries {
+                let oldest = entries.iter().min_by_key(|(_, (stored, _))| *stored).map(|(key, _)| key.clone());
+                match oldest {
+                    Some(oldest) => entries.remove(&oldest),
+                    None => break,
+                };
+            }
+        }
+        entries.insert(key.to_string(), (Instant::now(), response.clone()));
+        Ok(())
+    }
+}
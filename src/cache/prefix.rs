@@ -0,0 +1,200 @@
+//! Conversation-prefix cache for deterministic chat requests
+//!
+//! 按对话前缀缓存确定性聊天回复
+//!
+//! A [`PrefixCache`] stores the reply to each conversation prefix, keyed by
+//! the messages and the request parameters, and only for requests that always
+//! produce the same completion: temperature `0` or a fixed `seed`. Chat UIs
+//! that regenerate or replay a turn then get the reply back without a request.
+//!
+//! At most [`max_entries`](PrefixCache::max_entries) replies are kept (1000
+//! by default); when full, the oldest is dropped.
+//!
+//! Every entry remembers the prefixes it was built on, so an edit to the
+//! history can drop the replies that depended on the old text with
+//! [`invalidate`](PrefixCache::invalidate). Register it as a
+//! [`Conversation::on_edit`](crate::Conversation::on_edit) hook to do this
+//! automatically.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::cache::prefix::PrefixCache;
+//! use groqai::{Conversation, GroqClientBuilder};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let cache = PrefixCache::new();
+//! let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+//!     .prefix_cache(cache.clone())
+//! #     .transport(groqai::doctest::FixtureTransport::new())
+//!     .build()?;
+//!
+//! let mut conversation = Conversation::new("llama-3.1-8b-instant").with_temperature(0.0);
+//! conversation.on_edit(move |stale| {
+//!     cache.invalidate(stale);
+//! });
+//! conversation.send(&client, "Name three prime numbers.").await?;
+//! // Served from the cache
+//! conversation.regenerate(&client).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use crate::api::chat::ChatCompletionRequest;
+use crate::dedupe::RequestDeduplicator;
+use crate::error::GroqError;
+use crate::types::{ChatCompletionResponse, ChatMessage};
+
+const DEFAULT_MAX_ENTRIES: usize = 1000;
+
+/// A cached reply and the hash of every prefix of the conversation it answers
+struct PrefixEntry {
+    prefixes: Vec<u64>,
+    response: ChatCompletionResponse,
+    /// Insertion order, to drop the oldest entry when full
+    stored: u64,
+}
+
+#[derive(Default)]
+struct PrefixState {
+    /// Entries keyed by the serialized request
+    entries: HashMap<String, PrefixEntry>,
+    next: u64,
+}
+
+/// In-memory cache of replies to deterministic requests, keyed by conversation prefix
+///
+/// Cloning is cheap and clones share the entries, so one clone can be given to
+/// the client while another invalidates entries as the history is edited.
+/// When full, the oldest entry is dropped.
+#[derive(Clone)]
+pub struct PrefixCache {
+    max_entries: usize,
+    state: Arc<Mutex<PrefixState>>,
+}
+
+impl Default for PrefixCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrefixCache {
+    /// Creates an empty cache keeping at most 1000 entries
+    pub fn new() -> Self {
+        Self {
+            max_entries: DEFAULT_MAX_ENTRIES,
+            state: Arc::default(),
+        }
+    }
+
+    /// Sets how many entries are kept; values below 1 are treated as 1
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries.max(1);
+        self
+    }
+
+    /// Returns the reply cached for `request`
+    ///
+    /// Always `None` for sampled requests.
+    pub fn get(&self, request: &ChatCompletionRequest) -> Result<Option<ChatCompletionResponse>, GroqError> {
+        if !RequestDeduplicator::is_deterministic(request) {
+            return Ok(None);
+        }
+        let (key, _) = entry_key(request)?;
+        Ok(self.lock().entries.get(&key).map(|entry| entry.response.clone()))
+    }
+
+    /// Stores `response` as the reply to `request`; sampled requests are ignored
+    pub fn put(&self, request: &ChatCompletionRequest, response: &ChatCompletionResponse) -> Result<(), GroqError> {
+        if !RequestDeduplicator::is_deterministic(request) {
+            return Ok(());
+        }
+        let (key, prefixes) = entry_key(request)?;
+        let mut state = self.lock();
+        if !state.entries.contains_key(&key) {
+            while state.entries.len() >= self.max_entries {
+                let oldest = state.entries.iter().min_by_key(|(_, entry)| entry.stored).map(|(key, _)| key.clone());
+                match oldest {
+                    Some(oldest) => state.entries.remove(&oldest),
+                    None => break,
+                };
+            }
+        }
+        let stored = state.next;
+        state.next += 1;
+        state.entries.insert(
+            key,
+            PrefixEntry {
+                prefixes,
+                response: response.clone(),
+                stored,
+            },
+        );
+        Ok(())
+    }
+
+    /// Drops every reply to a conversation that starts with `prefix`
+    ///
+    /// Pass the history up to and including the first message that changed,
+    /// as it was before the edit. Returns the number of dropped replies.
+    pub fn invalidate(&self, prefix: &[ChatMessage]) -> usize {
+        let Some(target) = prefix_hashes(prefix).last().copied() else {
+            return 0;
+        };
+        let entries = &mut self.lock().entries;
+        let before = entries.len();
+        entries.retain(|_, entry| entry.prefixes.get(prefix.len() - 1) != Some(&target));
+        before - entries.len()
+    }
+
+    /// Drops every cached reply
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    /// Returns the number of cached replies
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Returns `true` if no reply is cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, PrefixState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Hashes every prefix of `messages`, each hash chained from the previous one
+fn prefix_hashes(messages: &[ChatMessage]) -> Vec<u64> {
+    let mut previous = 0u64;
+    messages
+        .iter()
+        .map(|message| {
+            let mut hasher = DefaultHasher::new();
+            previous.hash(&mut hasher);
+            // Serializing a message cannot fail
+            serde_json::to_string(message).unwrap_or_default().hash(&mut hasher);
+            previous = hasher.finish();
+            previous
+        })
+        .collect()
+}
+
+/// Returns the key of `request` and the hashes of its message prefixes
+///
+/// The key is the full serialized request, so a change in the conversation,
+/// model or parameters is always a miss. The prefix hashes are only used to
+/// invalidate, where a collision drops an extra entry at worst.
+fn entry_key(request: &ChatCompletionRequest) -> Result<(String, Vec<u64>), GroqError> {
+    Ok((serde_json::to_string(request)?, prefix_hashes(&request.messages)))
+}
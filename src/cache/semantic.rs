@@ -0,0 +1,244 @@
+//! Semantic response cache for near-duplicate prompts
+//!
+//! 按语义相似度缓存聊天回复
+//!
+//! Exact-match caches miss whenever a user rephrases a question. A
+//! [`SemanticCache`] embeds the last user message of each request with an
+//! [`Embedder`] and serves the reply to an earlier request whose message is
+//! similar enough, measured by cosine similarity against a
+//! [`threshold`](SemanticCache::threshold). The rest of the request (model,
+//! parameters and the earlier messages) must match exactly, so a rephrased
+//! question only hits within the same context. This suits FAQ-style traffic,
+//! where many users ask the same thing in different words. Like the response
+//! cache, it only takes deterministic requests (temperature `0` or a fixed
+//! `seed`) unless a request opts in with `ChatRequestBuilder::cache(true)`.
+//!
+//! Responses served from the cache carry
+//! [`CacheHit::Semantic`](super::CacheHit::Semantic) in
+//! [`ChatCompletionResponse::cache_hit`].
+//!
+//! Embeddings come from the OpenAI-compatible `/embeddings` endpoint with
+//! [`ApiEmbedder`], or from any local model implementing [`Embedder`].
+//! Entries are searched linearly, so keep
+//! [`max_entries`](SemanticCache::max_entries) in the thousands.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::cache::semantic::{ApiEmbedder, SemanticCache};
+//! use groqai::GroqClientBuilder;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let embeddings = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+//! #     .transport(groqai::doctest::FixtureTransport::new())
+//!     .build()?;
+//! let cache = SemanticCache::new(ApiEmbedder::new(embeddings, "nomic-embed-text-v1.5")).threshold(0.92);
+//! let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+//!     .semantic_cache(cache.clone())
+//! #     .transport(groqai::doctest::FixtureTransport::new())
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::CacheHit;
+use crate::api::chat::ChatCompletionRequest;
+use crate::client::GroqClient;
+use crate::endpoint::Endpoint;
+use crate::error::GroqError;
+use crate::similarity::cosine;
+use crate::transport::Request;
+use crate::types::{ChatCompletionResponse, MessageContent, Role};
+
+/// Default similarity a cached prompt needs to be served
+const DEFAULT_THRESHOLD: f64 = 0.95;
+
+/// Default number of entries kept
+const DEFAULT_MAX_ENTRIES: usize = 1000;
+
+/// Turns text into an embedding vector
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Returns the embedding of `text`
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, GroqError>;
+}
+
+/// Embeds text through the OpenAI-compatible `/embeddings` endpoint
+///
+/// Use a client without a semantic cache, e.g. one pointed at a gateway that
+/// serves embeddings.
+pub struct ApiEmbedder {
+    client: GroqClient,
+    model: String,
+}
+
+impl ApiEmbedder {
+    /// Creates an embedder sending requests for `model` through `client`
+    pub fn new(client: GroqClient, model: impl Into<String>) -> Self {
+        Self {
+            client,
+            model: model.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Embedder for ApiEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, GroqError> {
+        let request = Request::post(Endpoint::Embeddings).json(json!({ "model": self.model, "input": text }));
+        let response = self.client.execute_with_retry(request).await?.body;
+        match response["data"].get(0).map(|item| item["embedding"].clone()) {
+            Some(embedding) => Ok(serde_json::from_value(embedding)?),
+            None => Err(GroqError::InvalidMessage(
+                "Embeddings response did not contain a vector".to_string(),
+            )),
+        }
+    }
+}
+
+/// The embedded prompt of a request, used to look it up and store its reply
+#[derive(Debug, Clone)]
+pub struct SemanticKey {
+    /// Everything in the request but the prompt, serialized
+    ///
+    /// Kept whole so contexts never match through a hash collision.
+    context: String,
+    embedding: Vec<f32>,
+}
+
+struct SemanticEntry {
+    key: SemanticKey,
+    stored: Instant,
+    response: ChatCompletionResponse,
+}
+
+/// In-memory cache serving replies to prompts similar to earlier ones
+///
+/// Cloning is cheap and clones share the entries and the embedder. When
+/// full, the oldest entry is dropped.
+#[derive(Clone)]
+pub struct SemanticCache {
+    embedder: Arc<dyn Embedder>,
+    threshold: f64,
+    max_entries: usize,
+    ttl: Option<Duration>,
+    entries: Arc<Mutex<VecDeque<SemanticEntry>>>,
+}
+
+impl SemanticCache {
+    /// Creates an empty cache embedding prompts with `embedder`
+    ///
+    /// The threshold is `0.95` and at most 1000 entries are kept.
+    pub fn new(embedder: impl Embedder + 'static) -> Self {
+        Self {
+            embedder: Arc::new(embedder),
+            threshold: DEFAULT_THRESHOLD,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            ttl: None,
+            entries: Arc::default(),
+        }
+    }
+
+    /// Sets the cosine similarity a cached prompt needs to be served, clamped to `0.0..=1.0`
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets how many entries are kept; values below 1 are treated as 1
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries.max(1);
+        self
+    }
+
+    /// Expires entries `ttl` after they were stored
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Embeds the prompt of `request`
+    ///
+    /// Returns `None` if the last message is not a text message from the user;
+    /// such requests are not cached.
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError` if the embedder fails
+    pub async fn key(&self, request: &ChatCompletionRequest) -> Result<Option<SemanticKey>, GroqError> {
+        let Some((prompt, context)) = request.messages.split_last() else {
+            return Ok(None);
+        };
+        let text = match (&prompt.role, &prompt.content) {
+            (Role::User, MessageContent::Text(text)) => text,
+            _ => return Ok(None),
+        };
+        let mut params = serde_json::to_value(request)?;
+        if let Some(map) = params.as_object_mut() {
+            map.insert("messages".to_string(), serde_json::to_value(context)?);
+        }
+        Ok(Some(SemanticKey {
+            context: params.to_string(),
+            embedding: self.embedder.embed(text).await?,
+        }))
+    }
+
+    /// Returns the reply to the most similar cached prompt above the threshold
+    ///
+    /// The response's `cache_hit` reports the similarity.
+    pub fn get(&self, key: &SemanticKey) -> Option<ChatCompletionResponse> {
+        let mut entries = self.lock();
+        if let Some(ttl) = self.ttl {
+            entries.retain(|entry| entry.stored.elapsed() < ttl);
+        }
+        let (similarity, entry) = entries
+            .iter()
+            .filter(|entry| entry.key.context == key.context)
+            .map(|entry| (cosine(&entry.key.embedding, &key.embedding), entry))
+            .filter(|(similarity, _)| *similarity >= self.threshold)
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))?;
+        let mut response = entry.response.clone();
+        response.cache_hit = Some(CacheHit::Semantic { similarity });
+        Some(response)
+    }
+
+    /// Stores `response` as the reply to the prompt of `key`
+    pub fn put(&self, key: SemanticKey, response: &ChatCompletionResponse) {
+        let mut entries = self.lock();
+        while entries.len() >= self.max_entries {
+            entries.pop_front();
+        }
+        entries.push_back(SemanticEntry {
+            key,
+            stored: Instant::now(),
+            response: response.clone(),
+        });
+    }
+
+    /// Drops every entry
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Returns the number of entries, including expired ones not yet evicted
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if the cache holds no entries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<SemanticEntry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,172 @@
+//! Text classification into a fixed set of labels
+//!
+//! 基于固定标签集合的文本分类模块
+//!
+//! [`GroqClient::classify`] asks a chat model to pick exactly one of the given
+//! labels, with structured outputs constraining the reply to a strict JSON
+//! schema whose `label` is an enum of the labels. Answers outside the set are
+//! still rejected, for models that do not enforce the schema. It derives a
+//! confidence from the log probabilities of the label's tokens when the model
+//! returns them. [`GroqClient::classify_many`] runs many inputs with bounded
+//! concurrency.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::GroqClient;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # groqai::doctest::set_api_key();
+//! let client = GroqClient::new()?;
+//! # let client = groqai::doctest::client_replying(r#"{"label": "billing"}"#);
+//! let result = client
+//!     .classify("llama-3.1-8b-instant", "My card was charged twice", &["billing", "bug", "other"])
+//!     .await?;
+//! println!("{} ({:?})", result.label, result.confidence);
+//! # Ok(())
+//! # }
+//! ```
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::client::GroqClient;
+use crate::error::GroqError;
+use crate::types::{ChatMessage, LogProbs, ResponseFormat, Role};
+
+/// The label chosen for a text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Classification {
+    /// One of the labels passed to the request
+    pub label: String,
+    /// Probability of the label's tokens between 0.0 and 1.0, or `None` if the
+    /// model did not return log probabilities
+    pub confidence: Option<f64>,
+}
+
+impl GroqClient {
+    /// Classifies `text` into one of `labels`
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - Chat model that supports structured outputs
+    /// * `text` - Text to classify
+    /// * `labels` - Allowed labels; the model must pick exactly one
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if `labels` is empty or the model
+    /// answers with a label outside the set, or `GroqError` if the request fails.
+    pub async fn classify<S: AsRef<str>>(
+        &self,
+        model: &str,
+        text: &str,
+        labels: &[S],
+    ) -> Result<Classification, GroqError> {
+        let labels: Vec<&str> = labels.iter().map(AsRef::as_ref).collect();
+        if labels.is_empty() {
+            return Err(GroqError::InvalidMessage("Classification needs at least one label".to_string()));
+        }
+        let schema = json!({
+            "type": "object",
+            "properties": {"label": {"type": "string", "enum": labels}},
+            "required": ["label"],
+            "additionalProperties": false
+        });
+        let response = self
+            .chat(model)
+            .message(ChatMessage::new_text(
+                Role::System,
+                format!(
+                    "Classify the user's text. Reply with a JSON object matching this schema: {}",
+                    schema
+                ),
+            ))
+            .message(ChatMessage::new_text(Role::User, text))
+            .temperature(0.0)
+            .logprobs(true)
+            .response_format(ResponseFormat::json_schema("classification", schema, true))
+            .send()
+            .await?;
+        let choice = response
+            .choices
+            .first()
+            .ok_or_else(|| GroqError::InvalidMessage("Response contained no choices".to_string()))?;
+        let reply = choice.message.content.to_string();
+        let answer: Value = serde_json::from_str(&reply)?;
+        let answer = answer.get("label").and_then(Value::as_str).unwrap_or_default();
+        let label = labels
+            .iter()
+            .find(|l| **l == answer)
+            .or_else(|| labels.iter().find(|l| l.trim().eq_ignore_ascii_case(answer.trim())))
+            .ok_or_else(|| {
+                GroqError::InvalidMessage(format!("Model answered with unknown label {:?}", answer))
+            })?;
+        Ok(Classification {
+            label: label.to_string(),
+            confidence: choice.logprobs.as_ref().and_then(|lp| label_confidence(lp, answer)),
+        })
+    }
+
+    /// Classifies each of `texts`, running up to `concurrency` requests at a time
+    ///
+    /// Results are returned in input order.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of any classification.
+    pub async fn classify_many<T, S>(
+        &self,
+        model: &str,
+        texts: &[T],
+        labels: &[S],
+        concurrency: usize,
+    ) -> Result<Vec<Classification>, GroqError>
+    where
+        T: AsRef<str>,
+        S: AsRef<str>,
+    {
+        stream::iter(texts)
+            .map(|text| self.classify(model, text.as_ref(), labels))
+            .buffered(concurrency.max(1))
+            .try_collect()
+            .await
+    }
+}
+
+/// Returns the joint probability of the tokens that spell `answer` in the reply
+fn label_confidence(logprobs: &LogProbs, answer: &str) -> Option<f64> {
+    let reply: String = logprobs.tokens().iter().map(|t| t.token.as_str()).collect();
+    let (start, end) = label_span(&reply)?;
+    // The span holds the label as written, escapes included
+    let label: String = serde_json::from_str(&reply[start - 1..end + 1]).ok()?;
+    if label != answer {
+        return None;
+    }
+    logprobs.span_probability(start, end)
+}
+
+/// Returns the byte range of the top-level `label` string in `reply`, without its quotes
+fn label_span(reply: &str) -> Option<(usize, usize)> {
+    let mut pos = reply.find('{')? + 1;
+    loop {
+        let key_start = pos + reply[pos..].find('"')?;
+        let (key, key_end) = next_value::<String>(reply, key_start)?;
+        let colon = key_end + reply[key_end..].find(':')? + 1;
+        let value_start = colon + reply[colon..].len() - reply[colon..].trim_start().len();
+        let (value, value_end) = next_value::<Value>(reply, value_start)?;
+        if key == "label" && value.is_string() {
+            return Some((value_start + 1, value_end - 1));
+        }
+        pos = value_end;
+    }
+}
+
+/// Parses the JSON value starting at byte `start` and returns it with the offset after it
+fn next_value<T: DeserializeOwned>(text: &str, start: usize) -> Option<(T, usize)> {
+    let mut values = serde_json::Deserializer::from_str(&text[start..]).into_iter::<T>();
+    let value = values.next()?.ok()?;
+    Some((value, start + values.byte_offset()))
+}
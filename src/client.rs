@@ -3,31 +3,44 @@
 //! 客户端实现模块，提供 Groq API 的主要接口
 
 use std::sync::Arc;
-use std::time::Duration;
-use std::pin::Pin;
-use futures::Stream;
+use std::time::{Duration, Instant};
 
-use backoff::future::{Retry, Sleeper};
-use tokio::time::{self, Sleep};
-use tracing::instrument;
+use reqwest::header::HeaderValue;
+use tokio::time;
+use tracing::{instrument, warn};
 use url::Url;
 
 use crate::api::chat::{ChatCompletionRequest, ChatRequestBuilder};
+use crate::build_info::sdk_header_value;
+use crate::cache::prefix::PrefixCache;
+use crate::cache::{CacheHit, ResponseCache};
+#[cfg(feature = "semantic-cache")]
+use crate::cache::semantic::SemanticCache;
+#[cfg(feature = "cert-pinning")]
+use crate::pinning::CertificatePins;
+use crate::dedupe::RequestDeduplicator;
+use crate::defaults::ModelDefaultsTable;
+use crate::endpoint::Endpoint;
+use crate::metrics::{MetricsSnapshot, TransportMetrics};
+use crate::usage::{UsageRecord, UsageTracker};
 use crate::error::GroqError;
-use crate::types::{ChatCompletionResponse, ChatCompletionChunk};
-use crate::rate_limit::RateLimiter;
-use crate::transport::{ApiKey, HttpTransport, Transport};
+use crate::types::ChatCompletionResponse;
+use crate::rate_limit::{Budget, RateLimiter};
+use crate::content_filter::{ContentFilterAction, ContentFilterPolicy};
+use crate::schema::apply_strict_mode;
+use crate::journal::{Journal, JournalTransport};
+use crate::pricing::SpendGuard;
+use crate::queue::{QueuedTransport, RequestQueue};
+use crate::shutdown::{DrainingTransport, Lifecycle};
+use crate::signing::RequestSigner;
+#[cfg(feature = "streaming")]
+use crate::sse::decode_chat_stream;
+#[cfg(feature = "streaming")]
+use crate::transport::ChatCompletionStream;
+use crate::transport::{ApiKey, CompatMode, HttpTransport, Request, RequestOptions, Response, Transport};
 
-#[derive(Debug, Clone)]
-struct TokioSleeper;
-
-impl Sleeper for TokioSleeper {
-    type Sleep = Sleep;
-
-    fn sleep(&self, duration: Duration) -> Self::Sleep {
-        time::sleep(duration)
-    }
-}
+/// Request timeout of clients built without `timeout`
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// The main client for interacting with the Groq API.
 /// 
@@ -36,12 +49,13 @@ impl Sleeper for TokioSleeper {
 /// 
 /// # Examples
 /// 
-/// ```rust,no_run
+/// ```rust
 /// use groqai::{GroqClientBuilder, ChatMessage, Role};
 /// 
 /// #[tokio::main]
 /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
 ///     let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+/// #         .transport(groqai::doctest::FixtureTransport::new())
 ///         .build()?;
 ///     
 ///     // Use chat completions
@@ -55,11 +69,80 @@ impl Sleeper for TokioSleeper {
 ///     Ok(())
 /// }
 /// ```
+/// 
+/// # Cloning
+/// 
+/// `GroqClient` is a cheap handle: clones share the transport and its
+/// connection pool, the rate limiter's pause and backoff state, caches,
+/// budgets, spend guards, usage trackers and shutdown state. Clone it into
+/// tasks instead of building one client per task; clients built separately
+/// share nothing.
 #[derive(Clone)]
 pub struct GroqClient {
     pub transport: Arc<dyn Transport>,
     pub rate_limiter: RateLimiter,
     pub default_timeout: Duration,
+    pub(crate) dedupe: Option<Arc<RequestDeduplicator>>,
+    pub(crate) coalesce: Option<Arc<RequestDeduplicator>>,
+    pub(crate) usage: Option<UsageTracker>,
+    pub(crate) cache: Option<Arc<dyn ResponseCache>>,
+    pub(crate) prefix_cache: Option<PrefixCache>,
+    #[cfg(feature = "semantic-cache")]
+    pub(crate) semantic_cache: Option<SemanticCache>,
+    pub(crate) budget: Option<Budget>,
+    pub(crate) spend_guard: Option<SpendGuard>,
+    pub(crate) content_filter: Option<Arc<dyn ContentFilterPolicy>>,
+    pub(crate) model_defaults: Option<ModelDefaultsTable>,
+    pub(crate) request_queue: Option<RequestQueue>,
+    pub(crate) lifecycle: Arc<Lifecycle>,
+}
+
+/// Per-request options threaded from `ChatRequestBuilder` to the client
+#[derive(Debug, Clone)]
+pub(crate) struct ChatSendOptions {
+    /// Whether the request may share a result through dedupe or coalescing
+    pub allow_sharing: bool,
+    /// Experiment and prompt variant to attribute usage to
+    pub prompt_variant: Option<(String, String)>,
+    /// Whether the client's caches may serve and store the request; by
+    /// default the response cache only takes deterministic requests
+    pub use_cache: Option<bool>,
+    /// Settings passed to the transport, such as extra headers
+    pub request: RequestOptions,
+    /// Cost limit for this request, applied on top of the spend guard's own
+    pub max_cost_usd: Option<f64>,
+    /// Whether the spend guard is skipped
+    pub override_spend_guard: bool,
+}
+
+impl ChatSendOptions {
+    /// Attributes `record` to the request's prompt variant, if any
+    fn attribute(&self, record: UsageRecord) -> UsageRecord {
+        match &self.prompt_variant {
+            Some((experiment, variant)) => record.with_prompt_variant(experiment, variant),
+            None => record,
+        }
+    }
+}
+
+impl Default for ChatSendOptions {
+    fn default() -> Self {
+        Self {
+            allow_sharing: true,
+            prompt_variant: None,
+            use_cache: None,
+            request: RequestOptions::default(),
+            max_cost_usd: None,
+            override_spend_guard: false,
+        }
+    }
+}
+
+/// An HTTP client supplied by the application instead of built by the SDK
+enum InjectedClient {
+    Reqwest(reqwest::Client),
+    #[cfg(feature = "middleware")]
+    Middleware(reqwest_middleware::ClientWithMiddleware),
 }
 
 /// Builder for creating a `GroqClient` instance.
@@ -69,21 +152,45 @@ pub struct GroqClient {
 /// 
 /// # Examples
 /// 
-/// ```rust,no_run
+/// ```rust
 /// use groqai::GroqClientBuilder;
 /// use std::time::Duration;
 /// 
 /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
 ///     .timeout(Duration::from_secs(60))
+/// #     .transport(groqai::doctest::FixtureTransport::new())
 ///     .build()?;
 /// # Ok::<(), groqai::GroqError>(())
 /// ```
 pub struct GroqClientBuilder {
     api_key: ApiKey,
     base_url: Url,
-    timeout: Duration,
+    /// Request timeout, if set explicitly
+    timeout: Option<Duration>,
     rate_limiter: RateLimiter,
     proxy: Option<reqwest::Proxy>,
+    compat_mode: CompatMode,
+    dedupe_window: Option<Duration>,
+    coalesce_requests: bool,
+    usage_tracker: Option<UsageTracker>,
+    response_cache: Option<Arc<dyn ResponseCache>>,
+    prefix_cache: Option<PrefixCache>,
+    #[cfg(feature = "semantic-cache")]
+    semantic_cache: Option<SemanticCache>,
+    budget: Option<Budget>,
+    spend_guard: Option<SpendGuard>,
+    content_filter: Option<Arc<dyn ContentFilterPolicy>>,
+    model_defaults: Option<ModelDefaultsTable>,
+    journal: Option<Journal>,
+    request_queue: Option<RequestQueue>,
+    signer: Option<Arc<dyn RequestSigner>>,
+    transport: Option<Arc<dyn Transport>>,
+    root_certificates: Vec<reqwest::Certificate>,
+    custom_roots_only: bool,
+    #[cfg(feature = "cert-pinning")]
+    certificate_pins: Option<CertificatePins>,
+    http_client: Option<InjectedClient>,
+    sdk_header: Option<String>,
 }
 
 impl GroqClientBuilder {
@@ -99,20 +206,42 @@ impl GroqClientBuilder {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::GroqClientBuilder;
     /// 
     /// let builder = GroqClientBuilder::new("gsk_your_api_key".to_string())?;
     /// # Ok::<(), groqai::GroqError>(())
     /// ```
-    pub fn new(api_key: String) -> Result<Self, GroqError> {
+    pub fn new(api_key: impl Into<String>) -> Result<Self, GroqError> {
         let api_key = ApiKey::new(api_key)?;
         Ok(Self {
             api_key,
             base_url: Url::parse("https://api.groq.com/openai/v1/")?,
-            timeout: Duration::from_secs(30),
+            timeout: None,
             rate_limiter: RateLimiter::new(),
             proxy: None,
+            compat_mode: CompatMode::default(),
+            dedupe_window: None,
+            coalesce_requests: false,
+            usage_tracker: None,
+            response_cache: None,
+            prefix_cache: None,
+            #[cfg(feature = "semantic-cache")]
+            semantic_cache: None,
+            budget: None,
+            spend_guard: None,
+            content_filter: None,
+            model_defaults: None,
+            journal: None,
+            request_queue: None,
+            signer: None,
+            transport: None,
+            root_certificates: Vec::new(),
+            custom_roots_only: false,
+            #[cfg(feature = "cert-pinning")]
+            certificate_pins: None,
+            http_client: None,
+            sdk_header: Some(sdk_header_value()),
         })
     }
 
@@ -124,7 +253,7 @@ impl GroqClientBuilder {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::GroqClientBuilder;
     /// use url::Url;
     /// 
@@ -139,13 +268,16 @@ impl GroqClientBuilder {
 
     /// Sets the request timeout duration.
     /// 
+    /// Defaults to 30 seconds. A client passed to `http_client` keeps its own
+    /// timeout unless this is set.
+    /// 
     /// # Arguments
     /// 
     /// * `duration` - The timeout duration for HTTP requests
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::GroqClientBuilder;
     /// use std::time::Duration;
     /// 
@@ -154,7 +286,7 @@ impl GroqClientBuilder {
     /// # Ok::<(), groqai::GroqError>(())
     /// ```
     pub fn timeout(mut self, duration: Duration) -> Self {
-        self.timeout = duration;
+        self.timeout = Some(duration);
         self
     }
 
@@ -166,7 +298,7 @@ impl GroqClientBuilder {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::GroqClientBuilder;
     /// 
     /// let proxy = reqwest::Proxy::http("http://proxy.example.com:8080")?;
@@ -179,6 +311,493 @@ impl GroqClientBuilder {
         self
     }
 
+    /// Sets the wire compatibility mode for chat requests.
+    /// 
+    /// Use `CompatMode::OpenAI` when pointing the client at an OpenAI-compatible
+    /// gateway that does not accept Groq's parameter names.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// use groqai::transport::CompatMode;
+    /// use url::Url;
+    /// 
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+    ///     .base_url(Url::parse("https://gateway.example.com/v1/")?)
+    ///     .compat_mode(CompatMode::OpenAI)
+    /// #     .transport(groqai::doctest::FixtureTransport::new())
+    ///     .build()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn compat_mode(mut self, mode: CompatMode) -> Self {
+        self.compat_mode = mode;
+        self
+    }
+
+    /// Adds a trusted root certificate for TLS connections.
+    /// 
+    /// Use this when requests pass through a TLS-intercepting proxy or a
+    /// gateway signed by a private CA. May be called multiple times.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `cert` - The CA certificate to trust
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// 
+    /// # groqai::doctest::scratch_dir();
+    /// # groqai::doctest::ca_file("corporate-ca.pem");
+    /// let pem = std::fs::read("corporate-ca.pem")?;
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+    ///     .add_root_certificate(reqwest::Certificate::from_pem(&pem)?)
+    ///     .build()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add_root_certificate(mut self, cert: reqwest::Certificate) -> Self {
+        self.root_certificates.push(cert);
+        self
+    }
+
+    /// Trusts only the certificates added with `add_root_certificate`.
+    /// 
+    /// Replaces the platform's built-in root store with a custom trust store
+    /// of the added CAs. Any certificate chaining to one of them is accepted;
+    /// use `pin_certificates` to also require a known public key. `build()`
+    /// fails if this is enabled without any root certificate.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// 
+    /// # groqai::doctest::scratch_dir();
+    /// # groqai::doctest::ca_file("corporate-ca.pem");
+    /// let pem = std::fs::read("corporate-ca.pem")?;
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+    ///     .add_root_certificate(reqwest::Certificate::from_pem(&pem)?)
+    ///     .custom_roots_only(true)
+    ///     .build()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn custom_roots_only(mut self, enable: bool) -> Self {
+        self.custom_roots_only = enable;
+        self
+    }
+
+    /// Pins the public keys the API's certificate chain must contain.
+    /// 
+    /// TLS connections are made with rustls and fail unless the verified
+    /// chain contains a certificate whose public key matches one of the
+    /// pins. Roots for verifying the chain are part of `pins`, so
+    /// `add_root_certificate` and `custom_roots_only` cannot be combined with
+    /// it. See the [`pinning`](crate::pinning) module.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `pins` - The accepted public keys and the roots chains are verified against
+    #[cfg(feature = "cert-pinning")]
+    pub fn pin_certificates(mut self, pins: CertificatePins) -> Self {
+        self.certificate_pins = Some(pins);
+        self
+    }
+
+    /// Enables deduplication of identical chat requests.
+    /// 
+    /// A chat completion request whose body is identical to one issued less
+    /// than `window` ago returns that request's result instead of calling the
+    /// API again. Failed requests are never reused. Disabled by default.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// use std::time::Duration;
+    /// 
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+    ///     .dedupe_window(Duration::from_secs(2))
+    /// #     .transport(groqai::doctest::FixtureTransport::new())
+    ///     .build()?;
+    /// # Ok::<(), groqai::GroqError>(())
+    /// ```
+    pub fn dedupe_window(mut self, window: Duration) -> Self {
+        self.dedupe_window = Some(window);
+        self
+    }
+
+    /// Enables coalescing of identical concurrent deterministic chat requests.
+    /// 
+    /// When several tasks send the same request with temperature 0 or a fixed
+    /// seed at the same time, only one upstream call is made and its response
+    /// is fanned out to all waiters. Disabled by default; individual requests
+    /// can opt out with `ChatRequestBuilder::dedupe(false)`.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// 
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+    ///     .coalesce_requests(true)
+    /// #     .transport(groqai::doctest::FixtureTransport::new())
+    ///     .build()?;
+    /// # Ok::<(), groqai::GroqError>(())
+    /// ```
+    pub fn coalesce_requests(mut self, enable: bool) -> Self {
+        self.coalesce_requests = enable;
+        self
+    }
+
+    /// Records the token usage of every successful chat completion.
+    /// 
+    /// The tracker is shared, so keep a clone to read totals per model or per
+    /// prompt variant. See the [`usage`](crate::usage) module.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// use groqai::usage::UsageTracker;
+    /// 
+    /// let tracker = UsageTracker::new();
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+    ///     .usage_tracker(tracker.clone())
+    /// #     .transport(groqai::doctest::FixtureTransport::new())
+    ///     .build()?;
+    /// # Ok::<(), groqai::GroqError>(())
+    /// ```
+    pub fn usage_tracker(mut self, tracker: UsageTracker) -> Self {
+        self.usage_tracker = Some(tracker);
+        self
+    }
+
+    /// Serves repeated non-streaming chat requests from a response cache.
+    /// 
+    /// Requests are matched on their full body. Only deterministic requests
+    /// (temperature `0` or a fixed `seed`) are cached, since sampled requests
+    /// ask for a fresh completion; opt a sampled request in with
+    /// `ChatRequestBuilder::cache(true)`, or any request out with
+    /// `cache(false)`. Cache errors are logged and treated as misses. See the
+    /// [`cache`](crate::cache) module.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// use groqai::cache::MemoryCache;
+    /// 
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+    ///     .response_cache(MemoryCache::new())
+    /// #     .transport(groqai::doctest::FixtureTransport::new())
+    ///     .build()?;
+    /// # Ok::<(), groqai::GroqError>(())
+    /// ```
+    pub fn response_cache(mut self, cache: impl ResponseCache + 'static) -> Self {
+        self.response_cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Serves deterministic chat requests from a conversation-prefix cache.
+    /// 
+    /// Only requests with temperature `0` or a `seed` are cached, keyed by
+    /// their messages and parameters, so regenerating a turn costs no request.
+    /// Keep a clone of `cache` to [`invalidate`](PrefixCache::invalidate)
+    /// replies when the history is edited. `ChatRequestBuilder::cache(false)`
+    /// bypasses it like the response cache. See the
+    /// [`cache::prefix`](crate::cache::prefix) module.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// use groqai::cache::prefix::PrefixCache;
+    /// 
+    /// let cache = PrefixCache::new();
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+    ///     .prefix_cache(cache.clone())
+    /// #     .transport(groqai::doctest::FixtureTransport::new())
+    ///     .build()?;
+    /// # Ok::<(), groqai::GroqError>(())
+    /// ```
+    pub fn prefix_cache(mut self, cache: PrefixCache) -> Self {
+        self.prefix_cache = Some(cache);
+        self
+    }
+
+    /// Serves chat requests whose prompt is similar to an earlier one from a semantic cache.
+    /// 
+    /// Checked after the response and prefix caches miss. Each request costs
+    /// one embedding of its last user message; embedding errors are logged and
+    /// treated as misses. Like the response cache, it only takes deterministic
+    /// requests unless `ChatRequestBuilder::cache(true)` opts one in, and
+    /// `ChatRequestBuilder::cache(false)` bypasses it. See the [`cache::semantic`](crate::cache::semantic) module.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// use groqai::cache::semantic::{ApiEmbedder, SemanticCache};
+    /// 
+    /// let embeddings = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+    /// #     .transport(groqai::doctest::FixtureTransport::new())
+    ///     .build()?;
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+    ///     .semantic_cache(SemanticCache::new(ApiEmbedder::new(embeddings, "nomic-embed-text-v1.5")))
+    /// #     .transport(groqai::doctest::FixtureTransport::new())
+    ///     .build()?;
+    /// # Ok::<(), groqai::GroqError>(())
+    /// ```
+    #[cfg(feature = "semantic-cache")]
+    pub fn semantic_cache(mut self, cache: SemanticCache) -> Self {
+        self.semantic_cache = Some(cache);
+        self
+    }
+
+    /// Sends requests through a queue that limits concurrency by priority.
+    /// 
+    /// Every request of the client and its clones waits for a slot in
+    /// `queue`; mark requests with `priority` on the request builders, or
+    /// `RequestOptions::priority`, to let interactive ones go first. See the
+    /// [`queue`](crate::queue) module.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::{GroqClientBuilder, Priority, RequestQueue};
+    /// 
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+    ///     .request_queue(RequestQueue::new(8).limit(Priority::Background, 6))
+    /// #     .transport(groqai::doctest::FixtureTransport::new())
+    ///     .build()?;
+    /// # Ok::<(), groqai::GroqError>(())
+    /// ```
+    pub fn request_queue(mut self, queue: RequestQueue) -> Self {
+        self.request_queue = Some(queue);
+        self
+    }
+
+    /// Limits chat requests and tokens per time window.
+    /// 
+    /// Requests over the limit wait for the next window. Streamed requests
+    /// count their tokens when the chunk carrying the usage arrives. Back the budget with
+    /// a shared `BudgetStore` to enforce it across several processes. See
+    /// [`Budget`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `budget` - The limits and the store counting usage against them
+    pub fn budget(mut self, budget: Budget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Rejects chat requests that would exceed a spending limit.
+    /// 
+    /// Requests over the limit fail with `GroqError::BudgetExceeded` before
+    /// they are sent. See [`SpendGuard`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `guard` - The cost and hourly token limits and the pricing table
+    pub fn spend_guard(mut self, guard: SpendGuard) -> Self {
+        self.spend_guard = Some(guard);
+        self
+    }
+
+    /// Decides what happens to chat replies stopped by the content filter.
+    /// 
+    /// Without a policy, such replies fail with `GroqError::ContentFiltered`.
+    /// The policy may accept the reply or send a reworded request once. See
+    /// [`content_filter`](crate::content_filter).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `policy` - Called with the request and the filtered response
+    pub fn content_filter_policy(mut self, policy: impl ContentFilterPolicy + 'static) -> Self {
+        self.content_filter = Some(Arc::new(policy));
+        self
+    }
+
+    /// Records every request and its outcome in an audit journal.
+    /// 
+    /// Each call to the API, including retries and streams, becomes one
+    /// `JournalEntry` with the request ID, latency and the bodies after the
+    /// journal's redaction. See [`Journal`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `journal` - The sink and redaction rules
+    pub fn journal(mut self, journal: Journal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Signs every request before it is sent, for gateways requiring signatures.
+    /// 
+    /// The signer sees the final method, URL, headers and body and may add or
+    /// replace headers. See [`RequestSigner`].
+    /// 
+    /// # Arguments
+    /// 
+    /// * `signer` - The signing implementation, e.g. a SigV4 or HMAC adapter
+    pub fn request_signer(mut self, signer: impl RequestSigner + 'static) -> Self {
+        self.signer = Some(Arc::new(signer));
+        self
+    }
+
+    /// Sets the value of the `x-groqai-sdk` header sent with every request.
+    /// 
+    /// The header defaults to `groqai-rust/<version>` and helps Groq support
+    /// identify the SDK in bug reports. Applications that wrap the SDK can
+    /// append their own name, e.g. `groqai-rust/0.1.10 my-app/2.0`. Only the
+    /// built-in HTTP transport sends it, so `build()` fails if a custom
+    /// `transport` is also set.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `value` - The header value
+    pub fn sdk_header(mut self, value: impl Into<String>) -> Self {
+        self.sdk_header = Some(value.into());
+        self
+    }
+
+    /// Stops sending the `x-groqai-sdk` header.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// 
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+    ///     .disable_sdk_header()
+    /// #     .transport(groqai::doctest::FixtureTransport::new())
+    ///     .build()?;
+    /// # Ok::<(), groqai::GroqError>(())
+    /// ```
+    pub fn disable_sdk_header(mut self) -> Self {
+        self.sdk_header = None;
+        self
+    }
+
+    /// Applies per-model default parameters to chat requests.
+    /// 
+    /// Defaults fill in parameters a request does not set, and take precedence
+    /// over the builder's own defaults (temperature 0.7, 1000 tokens). The
+    /// table can be updated at runtime. See the [`defaults`](crate::defaults)
+    /// module.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// use groqai::defaults::{ModelDefaults, ModelDefaultsTable};
+    /// 
+    /// let table = ModelDefaultsTable::new();
+    /// table.set("llama-3.1-8b-instant", ModelDefaults {
+    ///     temperature: Some(0.2),
+    ///     ..Default::default()
+    /// });
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+    ///     .model_defaults(table)
+    /// #     .transport(groqai::doctest::FixtureTransport::new())
+    ///     .build()?;
+    /// # Ok::<(), groqai::GroqError>(())
+    /// ```
+    pub fn model_defaults(mut self, table: ModelDefaultsTable) -> Self {
+        self.model_defaults = Some(table);
+        self
+    }
+
+    /// Uses an existing `reqwest::Client` instead of building a new one.
+    /// 
+    /// Lets applications share connection pools or reuse a client configured
+    /// with custom middleware. The client's own timeout is kept unless
+    /// `timeout` is set on this builder, which then applies to each request.
+    /// Proxy and TLS settings must be configured on the injected
+    /// client; `build()` fails if they are also set on this builder.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `client` - The preconfigured HTTP client
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// 
+    /// let shared = reqwest::Client::new();
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+    ///     .http_client(shared.clone())
+    ///     .build()?;
+    /// # Ok::<(), groqai::GroqError>(())
+    /// ```
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(InjectedClient::Reqwest(client));
+        self
+    }
+
+    /// Sends requests through a `reqwest-middleware` client.
+    /// 
+    /// Tracing, retry or caching middleware configured on `client` then applies
+    /// to every Groq call. Behaves like `http_client` otherwise. Requires the
+    /// `middleware` feature.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// 
+    /// // Attach tracing, retry or cache middleware with `.with(...)`
+    /// let middleware = reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build();
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+    ///     .middleware_client(middleware)
+    ///     .build()?;
+    /// # Ok::<(), groqai::GroqError>(())
+    /// ```
+    #[cfg(feature = "middleware")]
+    pub fn middleware_client(mut self, client: reqwest_middleware::ClientWithMiddleware) -> Self {
+        self.http_client = Some(InjectedClient::Middleware(client));
+        self
+    }
+
+    /// Replaces the HTTP transport with a custom implementation.
+    /// 
+    /// Intended for tests and replay, e.g. with `mock::MockTransport`. When set,
+    /// `base_url`, `timeout` and `compat_mode` are ignored, and `build()` fails
+    /// if an http client, a proxy, TLS options, certificate pins or a custom
+    /// SDK header are also set on this builder.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// use groqai::mock::MockTransport;
+    /// 
+    /// let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+    ///     .transport(MockTransport::new())
+    ///     .build()?;
+    /// # Ok::<(), groqai::GroqError>(())
+    /// ```
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Returns true if certificate pins were set
+    fn has_certificate_pins(&self) -> bool {
+        #[cfg(feature = "cert-pinning")]
+        {
+            self.certificate_pins.is_some()
+        }
+        #[cfg(not(feature = "cert-pinning"))]
+        {
+            false
+        }
+    }
+
     /// Builds the final `GroqClient` instance.
     /// 
     /// # Errors
@@ -188,19 +807,139 @@ impl GroqClientBuilder {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::GroqClientBuilder;
     /// 
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+    /// #     .transport(groqai::doctest::FixtureTransport::new())
     ///     .build()?;
     /// # Ok::<(), groqai::GroqError>(())
     /// ```
     pub fn build(self) -> Result<GroqClient, GroqError> {
-        let transport = HttpTransport::new(self.base_url, self.api_key, self.timeout, self.proxy)?;
+        let sdk_header = self
+            .sdk_header
+            .as_deref()
+            .map(HeaderValue::from_str)
+            .transpose()
+            .map_err(|e| GroqError::InvalidMessage(format!("Invalid SDK header value: {}", e)))?;
+        let pinned = self.has_certificate_pins();
+        // 只有显式修改过的 SDK 头才与自定义传输冲突
+        let custom_sdk_header = self.sdk_header.as_deref().is_some_and(|v| v != sdk_header_value());
+        let transport: Arc<dyn Transport> = match (self.transport, self.http_client) {
+            (Some(_), _) if self.signer.is_some() => {
+                return Err(GroqError::InvalidMessage(
+                    "A request signer requires the built-in HTTP transport".to_string(),
+                ));
+            }
+            (Some(_), Some(_)) => {
+                return Err(GroqError::InvalidMessage(
+                    "Set either a custom transport or an injected http client, not both".to_string(),
+                ));
+            }
+            (Some(_), None)
+                if self.proxy.is_some()
+                    || self.custom_roots_only
+                    || !self.root_certificates.is_empty()
+                    || pinned
+                    || custom_sdk_header =>
+            {
+                return Err(GroqError::InvalidMessage(
+                    "Proxy, TLS and SDK header options require the built-in HTTP transport".to_string(),
+                ));
+            }
+            (Some(transport), None) => transport,
+            (None, Some(injected)) => {
+                if self.proxy.is_some()
+                    || self.custom_roots_only
+                    || !self.root_certificates.is_empty()
+                    || pinned
+                {
+                    return Err(GroqError::InvalidMessage(
+                        "Proxy and TLS options must be configured on the injected http client"
+                            .to_string(),
+                    ));
+                }
+                let transport = match injected {
+                    InjectedClient::Reqwest(http) => {
+                        HttpTransport::from_client(http, self.base_url, self.api_key)
+                    }
+                    #[cfg(feature = "middleware")]
+                    InjectedClient::Middleware(http) => {
+                        HttpTransport::from_middleware_client(http, self.base_url, self.api_key)
+                    }
+                };
+                let mut transport = transport
+                    .with_compat_mode(self.compat_mode)
+                    .with_sdk_header(sdk_header);
+                if let Some(timeout) = self.timeout {
+                    transport = transport.with_request_timeout(timeout);
+                }
+                if let Some(signer) = self.signer {
+                    transport = transport.with_signer(signer);
+                }
+                Arc::new(transport)
+            }
+            (None, None) => {
+                if self.custom_roots_only && self.root_certificates.is_empty() {
+                    return Err(GroqError::InvalidMessage(
+                        "A custom trust store requires at least one root certificate".to_string(),
+                    ));
+                }
+                let mut http = reqwest::Client::builder()
+                    .timeout(self.timeout.unwrap_or(DEFAULT_TIMEOUT))
+                    .tls_built_in_root_certs(!self.custom_roots_only);
+                #[cfg(feature = "cert-pinning")]
+                if let Some(pins) = &self.certificate_pins {
+                    if self.custom_roots_only || !self.root_certificates.is_empty() {
+                        return Err(GroqError::InvalidMessage(
+                            "Add root certificates to the certificate pins instead of the builder".to_string(),
+                        ));
+                    }
+                    http = http.use_preconfigured_tls(pins.client_config()?);
+                }
+                if let Some(proxy) = self.proxy {
+                    http = http.proxy(proxy);
+                }
+                for cert in self.root_certificates {
+                    http = http.add_root_certificate(cert);
+                }
+                let mut transport = HttpTransport::from_client(http.build()?, self.base_url, self.api_key)
+                    .with_compat_mode(self.compat_mode)
+                    .with_sdk_header(sdk_header);
+                if let Some(signer) = self.signer {
+                    transport = transport.with_signer(signer);
+                }
+                Arc::new(transport)
+            }
+        };
+        let transport: Arc<dyn Transport> = match self.journal {
+            Some(journal) => Arc::new(JournalTransport::new(transport, journal)),
+            None => transport,
+        };
+        let transport: Arc<dyn Transport> = match &self.request_queue {
+            Some(queue) => Arc::new(QueuedTransport::new(transport, queue.clone())),
+            None => transport,
+        };
+        let lifecycle = Arc::new(Lifecycle::default());
         Ok(GroqClient {
-            transport: Arc::new(transport),
+            transport: Arc::new(DrainingTransport::new(transport, lifecycle.clone())),
             rate_limiter: self.rate_limiter,
-            default_timeout: self.timeout,
+            default_timeout: self.timeout.unwrap_or(DEFAULT_TIMEOUT),
+            dedupe: self.dedupe_window.map(|w| Arc::new(RequestDeduplicator::new(w))),
+            coalesce: self
+                .coalesce_requests
+                .then(|| Arc::new(RequestDeduplicator::in_flight())),
+            usage: self.usage_tracker,
+            cache: self.response_cache,
+            prefix_cache: self.prefix_cache,
+            #[cfg(feature = "semantic-cache")]
+            semantic_cache: self.semantic_cache,
+            budget: self.budget,
+            spend_guard: self.spend_guard,
+            content_filter: self.content_filter,
+            model_defaults: self.model_defaults,
+            request_queue: self.request_queue,
+            lifecycle,
         })
     }
 }
@@ -214,6 +953,7 @@ impl GroqClient {
     /// Optional:
     /// - GROQ_PROXY_URL / HTTPS_PROXY / HTTP_PROXY
     /// - GROQ_TIMEOUT_SECS (default: 30)
+    /// - GROQ_BASE_URL, e.g. for an OpenAI-compatible gateway (must end with `/`)
     pub fn from_env() -> Result<Self, GroqError> {
         let api_key = std::env::var("GROQ_API_KEY")
             .map_err(|_| GroqError::InvalidApiKey("GROQ_API_KEY not set".into()))?;
@@ -235,6 +975,12 @@ impl GroqClient {
             .unwrap_or(30);
         builder = builder.timeout(std::time::Duration::from_secs(timeout_secs));
 
+        if let Ok(base_url) = std::env::var("GROQ_BASE_URL") {
+            let base_url = Url::parse(&base_url)
+                .map_err(|e| GroqError::InvalidMessage(format!("Invalid GROQ_BASE_URL: {}", e)))?;
+            builder = builder.base_url(base_url);
+        }
+
         builder.build()
     }
 
@@ -258,12 +1004,13 @@ impl GroqClient {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::{GroqClientBuilder, ChatMessage, Role};
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
     /// let messages = vec![ChatMessage::new_text(Role::User, "Hello!")];
     /// 
     /// let response = client.chat("llama-3.1-70b-versatile")
@@ -281,13 +1028,14 @@ impl GroqClient {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::{GroqClientBuilder, AudioTranscriptionRequest};
     /// use std::path::PathBuf;
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
     /// 
     /// let request = AudioTranscriptionRequest {
     ///     file: Some(PathBuf::from("audio.mp3")),
@@ -304,6 +1052,7 @@ impl GroqClient {
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg(feature = "audio")]
     pub fn audio<'a>(&'a self) -> crate::api::audio::AudioRequestBuilder<'a> {
         crate::api::audio::AudioRequestBuilder::new(self)
     }
@@ -312,17 +1061,18 @@ impl GroqClient {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
-    /// use groqai::{GroqClientBuilder, BatchCreateRequest};
+    /// ```rust
+    /// use groqai::{GroqClientBuilder, BatchCreateRequest, BatchEndpoint, CompletionWindow};
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
     /// 
     /// let request = BatchCreateRequest {
     ///     input_file_id: "file_123".to_string(),
-    ///     endpoint: "/chat/completions".to_string(),
-    ///     completion_window: "24h".to_string(),
+    ///     endpoint: BatchEndpoint::ChatCompletions,
+    ///     completion_window: CompletionWindow::Hours24,
     ///     metadata: None,
     /// };
     /// 
@@ -330,6 +1080,7 @@ impl GroqClient {
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg(feature = "batches")]
     pub fn batches<'a>(&'a self) -> crate::api::batches::BatchRequestBuilder<'a> {
         crate::api::batches::BatchRequestBuilder::new(self)
     }
@@ -338,18 +1089,20 @@ impl GroqClient {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::GroqClientBuilder;
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
     /// 
     /// let files = client.files().list().await?;
     /// println!("Found {} files", files.data.len());
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg(feature = "files")]
     pub fn files<'a>(&'a self) -> crate::api::files::FileRequestBuilder<'a> {
         crate::api::files::FileRequestBuilder::new(self)
     }
@@ -358,12 +1111,13 @@ impl GroqClient {
     /// 
     /// # Examples
     /// 
-    /// ```rust,no_run
+    /// ```rust
     /// use groqai::GroqClientBuilder;
     /// 
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
     /// 
     /// let models = client.models().list().await?;
     /// for model in models.data {
@@ -376,6 +1130,49 @@ impl GroqClient {
         crate::api::models::ModelsRequestBuilder::new(self)
     }
 
+    /// Creates a fine-tuning request builder.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
+    /// 
+    /// let jobs = client.fine_tunings().list(None, None).await?;
+    /// println!("Found {} fine-tuning jobs", jobs.data.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "fine-tuning")]
+    pub fn fine_tunings<'a>(&'a self) -> crate::api::fine_tunings::FineTuningRequestBuilder<'a> {
+        crate::api::fine_tunings::FineTuningRequestBuilder::new(self)
+    }
+
+    /// Creates a vision request builder.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqClientBuilder;
+    /// 
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+    /// # let client = groqai::doctest::client();
+    /// 
+    /// let text = client.vision().ocr("https://example.com/sign.jpg").await?;
+    /// println!("{}", text);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn vision<'a>(&'a self) -> crate::api::vision::VisionRequestBuilder<'a> {
+        crate::api::vision::VisionRequestBuilder::new(self)
+    }
+
     /// Sends a chat completion request with retry logic.
     /// 
     /// This method includes built-in rate limiting and retry mechanisms
@@ -383,7 +1180,8 @@ impl GroqClient {
     /// 
     /// # Arguments
     /// 
-    /// * `request` - The chat completion request to send
+    /// * `request` - The chat completion request to send; unset parameters are
+    ///   filled from the client's per-model defaults
     /// 
     /// # Errors
     /// 
@@ -391,27 +1189,334 @@ impl GroqClient {
     #[instrument(skip(self, request), fields(model = %request.model))]
     pub async fn chat_completions(
         &self,
+        mut request: ChatCompletionRequest,
+    ) -> Result<ChatCompletionResponse, GroqError> {
+        if let Some(defaults) = &self.model_defaults {
+            defaults.apply(&mut request);
+        }
+        self.chat_completions_with(request, ChatSendOptions::default()).await
+    }
+
+    /// Returns the per-model defaults table, for updating it at runtime
+    pub fn model_defaults(&self) -> Option<&ModelDefaultsTable> {
+        self.model_defaults.as_ref()
+    }
+
+    /// Returns the transport metrics recorded so far
+    /// 
+    /// Empty if the client uses a custom transport that records no metrics.
+    pub fn metrics_snapshot(&self) -> MetricsSnapshot {
+        self.transport
+            .metrics()
+            .map(TransportMetrics::snapshot)
+            .unwrap_or_default()
+    }
+
+    /// Returns the transport metrics in the Prometheus text exposition format
+    /// 
+    /// Serve the result from a `/metrics` endpoint to scrape request counts,
+    /// errors, retries, token usage and latency histograms. See
+    /// `MetricsSnapshot::to_prometheus` for the exported series.
+    pub fn prometheus_metrics(&self) -> String {
+        self.metrics_snapshot().to_prometheus()
+    }
+
+    /// Returns the usage tracker configured with `GroqClientBuilder::usage_tracker`
+    pub fn usage_tracker(&self) -> Option<&UsageTracker> {
+        self.usage.as_ref()
+    }
+
+    /// Returns the cache configured with `GroqClientBuilder::prefix_cache`
+    pub fn prefix_cache(&self) -> Option<&PrefixCache> {
+        self.prefix_cache.as_ref()
+    }
+
+    /// Returns the cache configured with `GroqClientBuilder::semantic_cache`
+    #[cfg(feature = "semantic-cache")]
+    pub fn semantic_cache(&self) -> Option<&SemanticCache> {
+        self.semantic_cache.as_ref()
+    }
+
+    /// Returns the queue configured with `GroqClientBuilder::request_queue`
+    pub fn request_queue(&self) -> Option<&RequestQueue> {
+        self.request_queue.as_ref()
+    }
+
+    /// Sends a chat completion request with per-request options
+    pub(crate) async fn chat_completions_with(
+        &self,
+        mut request: ChatCompletionRequest,
+        options: ChatSendOptions,
+    ) -> Result<ChatCompletionResponse, GroqError> {
+        apply_strict_mode(&mut request)?;
+        request.validate()?;
+        let prefix_cache = self.prefix_cache.as_ref().filter(|_| options.use_cache != Some(false));
+        if let Some(prefix_cache) = prefix_cache {
+            match prefix_cache.get(&request) {
+                Ok(Some(mut response)) => {
+                    response.cache_hit = Some(CacheHit::Prefix);
+                    return Ok(response);
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Prefix cache lookup failed: {}", e),
+            }
+        }
+        // 采样请求默认不缓存，除非请求显式开启
+        let cache = self.cache.as_ref().filter(|_| {
+            options.use_cache.unwrap_or_else(|| RequestDeduplicator::is_deterministic(&request))
+        });
+        let cache_key = match cache {
+            Some(_) => Some(crate::cache::cache_key(&request)?),
+            None => None,
+        };
+        if let (Some(cache), Some(key)) = (cache, &cache_key) {
+            match cache.get(key).await {
+                Ok(Some(mut response)) => {
+                    response.cache_hit = Some(CacheHit::Exact);
+                    return Ok(response);
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Response cache lookup failed: {}", e),
+            }
+        }
+        #[cfg(feature = "semantic-cache")]
+        let semantic_key = match self.semantic_cache.as_ref().filter(|_| {
+            options.use_cache.unwrap_or_else(|| RequestDeduplicator::is_deterministic(&request))
+        }) {
+            Some(semantic_cache) => match semantic_cache.key(&request).await {
+                Ok(key) => key,
+                Err(e) => {
+                    warn!("Semantic cache lookup failed: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+        #[cfg(feature = "semantic-cache")]
+        if let (Some(semantic_cache), Some(key)) = (&self.semantic_cache, &semantic_key) {
+            if let Some(response) = semantic_cache.get(key) {
+                return Ok(response);
+            }
+        }
+        self.check_spend(&request, &options)?;
+        let reservation = match &self.budget {
+            Some(budget) => Some(budget.acquire(&request).await),
+            None => None,
+        };
+        let started = Instant::now();
+        let sharing = match (&self.dedupe, &self.coalesce) {
+            // Requests with their own headers may be routed differently
+            _ if !options.allow_sharing || !options.request.headers().is_empty() => None,
+            (Some(dedupe), _) => Some(dedupe),
+            (None, Some(coalesce)) if RequestDeduplicator::is_deterministic(&request) => {
+                Some(coalesce)
+            }
+            _ => None,
+        };
+        // 内容过滤的重试在共享的请求内进行，等待者只重试一次
+        let send = {
+            let client = self.clone();
+            let request = request.clone();
+            let options = options.clone();
+            async move {
+                let response = Self::send_chat(
+                    client.transport.clone(),
+                    client.rate_limiter.clone(),
+                    request.clone(),
+                    options.request.clone(),
+                )
+                .await?;
+                client.check_content_filter(&request, response, &options, started).await
+            }
+        };
+        let (response, sent) = match sharing {
+            Some(dedupe) => dedupe.run(RequestDeduplicator::key(&request)?, send).await?,
+            None => (send.await?, true),
+        };
+        if let (Some(cache), Some(key)) = (cache, &cache_key) {
+            if let Err(e) = cache.put(key, &response).await {
+                warn!("Response cache store failed: {}", e);
+            }
+        }
+        if let Some(prefix_cache) = prefix_cache {
+            if let Err(e) = prefix_cache.put(&request, &response) {
+                warn!("Prefix cache store failed: {}", e);
+            }
+        }
+        #[cfg(feature = "semantic-cache")]
+        if let (Some(semantic_cache), Some(key)) = (&self.semantic_cache, semantic_key) {
+            semantic_cache.put(key, &response);
+        }
+        // 共享的响应只在发出请求的调用中计入用量
+        if sent {
+            self.record_usage(&request.model, &response, started.elapsed(), &options).await;
+        }
+        drop(reservation);
+        Ok(response)
+    }
+
+    /// Counts the usage of `response` against the budget, the spend guard and the usage tracker
+    async fn record_usage(
+        &self,
+        model: &str,
+        response: &ChatCompletionResponse,
+        elapsed: Duration,
+        options: &ChatSendOptions,
+    ) {
+        if let Some(budget) = &self.budget {
+            budget.record(model, &response.usage, elapsed).await;
+        }
+        if let Some(guard) = &self.spend_guard {
+            guard.record(u64::from(response.usage.total_tokens));
+        }
+        if let Some(usage) = &self.usage {
+            usage.record(options.attribute(UsageRecord::from_response(response)));
+        }
+    }
+
+    /// Applies the spend guard, or a per-request cost limit without one
+    fn check_spend(&self, request: &ChatCompletionRequest, options: &ChatSendOptions) -> Result<(), GroqError> {
+        if options.override_spend_guard {
+            return Ok(());
+        }
+        match (&self.spend_guard, options.max_cost_usd) {
+            (Some(guard), max_cost) => guard.check(request, max_cost),
+            (None, Some(max_cost)) => SpendGuard::new().check(request, Some(max_cost)),
+            (None, None) => Ok(()),
+        }
+    }
+
+    /// Applies the content filter policy to a reply stopped by the content filter
+    ///
+    /// A filtered reply that is not returned has still cost tokens, so its
+    /// usage is counted here. A retry goes through the same strict mode,
+    /// validation and spend checks as the original request.
+    async fn check_content_filter(
+        &self,
+        request: &ChatCompletionRequest,
+        mut response: ChatCompletionResponse,
+        options: &ChatSendOptions,
+        started: Instant,
+    ) -> Result<ChatCompletionResponse, GroqError> {
+        let mut retried = false;
+        while response.is_content_filtered() {
+            let action = match &self.content_filter {
+                Some(policy) => policy.on_filtered(request, &response).await,
+                None => ContentFilterAction::Fail,
+            };
+            if matches!(action, ContentFilterAction::Allow) {
+                break;
+            }
+            self.record_usage(&request.model, &response, started.elapsed(), options).await;
+            match action {
+                ContentFilterAction::Retry(mut retry) if !retried => {
+                    retried = true;
+                    apply_strict_mode(&mut retry)?;
+                    retry.validate()?;
+                    self.check_spend(&retry, options)?;
+                    response = Self::send_chat(
+                        self.transport.clone(),
+                        self.rate_limiter.clone(),
+                        *retry,
+                        options.request.clone(),
+                    )
+                    .await?;
+                }
+                _ => {
+                    return Err(GroqError::ContentFiltered {
+                        model: response.model.clone(),
+                        response: Box::new(response),
+                    })
+                }
+            }
+        }
+        Ok(response)
+    }
+
+    /// Sends a chat request through the retry loop without borrowing the client
+    async fn send_chat(
+        transport: Arc<dyn Transport>,
+        rate_limiter: RateLimiter,
         request: ChatCompletionRequest,
+        options: RequestOptions,
     ) -> Result<ChatCompletionResponse, GroqError> {
-        let op = || async {
-            let res = self.transport.post_chat("chat/completions", &request).await;
-            match res {
-                Ok(response) => Ok(response),
+        let request = Request::post(Endpoint::ChatCompletions)
+            .json(serde_json::to_value(&request)?)
+            .options(options);
+        let response: ChatCompletionResponse = Self::execute_with_backoff(transport.as_ref(), &rate_limiter, request)
+            .await?
+            .json()?;
+        if let Some(metrics) = transport.metrics() {
+            metrics.record_tokens(
+                &Endpoint::ChatCompletions.path(),
+                response.usage.prompt_tokens,
+                response.usage.completion_tokens,
+            );
+        }
+        Ok(response)
+    }
+
+    /// Sends `request` through the client's rate limiter and backoff loop
+    #[cfg(any(
+        feature = "audio",
+        feature = "files",
+        feature = "semantic-cache",
+        feature = "batches",
+        feature = "fine-tuning"
+    ))]
+    pub(crate) async fn execute_with_retry(&self, request: Request) -> Result<Response, GroqError> {
+        Self::execute_with_backoff(self.transport.as_ref(), &self.rate_limiter, request).await
+    }
+
+    /// Sends `request`, retrying `429` and `503` responses with exponential backoff
+    ///
+    /// Waits come from the backoff sequence `rate_limiter` shares with its
+    /// clones, so concurrent requests back off together, and the sequence
+    /// starts over once a request succeeds. A `retry-after` sent with the
+    /// error replaces the backoff interval, and a `429` pauses every clone of
+    /// `rate_limiter` for the wait. Retrying stops once the next wait would
+    /// end after the backoff's `max_elapsed_time`.
+    async fn execute_with_backoff(
+        transport: &dyn Transport,
+        rate_limiter: &RateLimiter,
+        request: Request,
+    ) -> Result<Response, GroqError> {
+        let path = request.endpoint.path();
+        let started = Instant::now();
+        loop {
+            rate_limiter.wait().await;
+            let api_err = match transport.execute(request.clone()).await {
+                Ok(response) => {
+                    rate_limiter.reset();
+                    return Ok(response);
+                }
                 Err(GroqError::Api(api_err))
-                    if api_err.status == reqwest::StatusCode::TOO_MANY_REQUESTS =>
+                    if api_err.status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                        || api_err.status == reqwest::StatusCode::SERVICE_UNAVAILABLE =>
                 {
-                    Err(backoff::Error::Transient {
-                        err: GroqError::RateLimited,
-                        retry_after: api_err.retry_after,
-                    })
+                    api_err
                 }
-                Err(e) => Err(backoff::Error::Permanent(e)),
+                Err(e) => return Err(e),
+            };
+            let retry_after = api_err.retry_after;
+            let rate_limited = api_err.status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+            let err = if rate_limited { GroqError::RateLimited } else { GroqError::Api(api_err) };
+            let Some(wait) = rate_limiter.next_backoff(retry_after) else {
+                return Err(err);
+            };
+            let max_elapsed = rate_limiter.backoff.max_elapsed_time;
+            if max_elapsed.is_some_and(|max| started.elapsed() + wait > max) {
+                return Err(err);
             }
-        };
-        let notify = |_: GroqError, _: Duration| {};
-        Retry::new(TokioSleeper, self.rate_limiter.backoff.clone(), notify, op)
-            .await
-            .map_err(GroqError::from)
+            // Clones of this client hold back until the pause has passed too
+            if rate_limited {
+                rate_limiter.pause_for(wait);
+            }
+            if let Some(metrics) = transport.metrics() {
+                metrics.record_retry(&path);
+            }
+            time::sleep(wait).await;
+        }
     }
 
     /// Sends a streaming chat completion request.
@@ -425,12 +1530,65 @@ impl GroqClient {
     /// # Returns
     /// 
     /// A stream of `ChatCompletionChunk` items or errors.
+    #[cfg(feature = "streaming")]
     #[instrument(skip(self, request), fields(model = %request.model))]
     pub async fn chat_completions_stream(
         &self,
         request: ChatCompletionRequest,
-    ) -> Result<Pin<Box<dyn Stream<Item = Result<ChatCompletionChunk, GroqError>> + Send>>, GroqError> {
-        let url = self.transport.base_url().join("chat/completions")?;
-        self.transport.post_stream(url, &request).await
+    ) -> Result<ChatCompletionStream, GroqError> {
+        self.chat_completions_stream_with(request, &ChatSendOptions::default()).await
+    }
+
+    /// Sends a streaming chat completion request with per-request options
+    #[cfg(feature = "streaming")]
+    pub(crate) async fn chat_completions_stream_with(
+        &self,
+        mut request: ChatCompletionRequest,
+        options: &ChatSendOptions,
+    ) -> Result<ChatCompletionStream, GroqError> {
+        apply_strict_mode(&mut request)?;
+        request.validate()?;
+        self.check_spend(&request, options)?;
+        let reservation = match &self.budget {
+            Some(budget) => Some(budget.acquire(&request).await),
+            None => None,
+        };
+        let started = Instant::now();
+        self.rate_limiter.wait().await;
+        request.stream = Some(true);
+        let request = Request::post(Endpoint::ChatCompletions)
+            .json(serde_json::to_value(&request)?)
+            .options(options.request.clone());
+        let stream = decode_chat_stream(self.transport.execute_stream(request).await?);
+        if self.spend_guard.is_none() && self.usage.is_none() && self.budget.is_none() {
+            return Ok(stream);
+        }
+        // 流结束时最后一个分块携带用量，计入花费上限、预算与用量统计
+        let guard = self.spend_guard.clone();
+        let tracker = self.usage.clone();
+        let budget = self.budget.clone();
+        let options = options.clone();
+        // 预约随流存活，直到用量到达或流被丢弃
+        let mut reservation = reservation;
+        Ok(Box::pin(futures::StreamExt::then(stream, move |chunk| {
+            let usage = chunk.as_ref().ok().and_then(|c| Some((c.model.clone(), c.reported_usage()?)));
+            if let Some((model, usage)) = &usage {
+                if let Some(guard) = &guard {
+                    guard.record(u64::from(usage.total_tokens));
+                }
+                if let Some(tracker) = &tracker {
+                    tracker.record(options.attribute(UsageRecord::from_usage(model, usage)));
+                }
+            }
+            let budget = budget.clone().zip(usage);
+            let finished = budget.as_ref().and_then(|_| reservation.take());
+            async move {
+                if let Some((budget, (model, usage))) = budget {
+                    budget.record(&model, &usage, started.elapsed()).await;
+                }
+                drop(finished);
+                chunk
+            }
+        })))
     }
 }
\ No newline at end of file
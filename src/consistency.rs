@@ -0,0 +1,201 @@
+//! Self-consistency voting over sampled answers
+//!
+//! 自洽性投票：多次采样并按多数票选出答案
+//!
+//! [`GroqClient::self_consistency`] samples several responses to the same
+//! prompt at a raised temperature, extracts the final answer from each, and
+//! returns the answer most responses agree on. It trades extra requests for
+//! better accuracy on reasoning and arithmetic tasks.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::GroqClient;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # groqai::doctest::set_api_key();
+//! let client = GroqClient::new()?;
+//! # let client = groqai::doctest::client();
+//! let vote = client
+//!     .self_consistency(
+//!         "llama-3.3-70b-versatile",
+//!         "A bat and a ball cost $1.10. The bat costs $1 more than the ball. \
+//!          How much is the ball? Think step by step, then end with `Answer: <value>`.",
+//!         5,
+//!     )
+//!     .await?;
+//! println!("{} ({} of {} votes)", vote.answer, vote.votes[0].1, vote.responses.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+
+use crate::client::GroqClient;
+use crate::error::GroqError;
+use crate::types::{ChatMessage, Role};
+
+/// Pulls the final answer out of a response, or `None` if it has none
+pub type AnswerExtractor = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// Outcome of a self-consistency vote
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsistencyVote {
+    /// The answer with the most votes; ties go to the answer seen first
+    pub answer: String,
+    /// Every extracted answer with its vote count, most votes first
+    pub votes: Vec<(String, usize)>,
+    /// The full text of every sampled response
+    pub responses: Vec<String>,
+}
+
+/// Options for [`GroqClient::self_consistency_with`]
+#[derive(Clone)]
+pub struct ConsistencyOptions {
+    temperature: f32,
+    concurrency: usize,
+    max_completion_tokens: u32,
+    extractor: AnswerExtractor,
+}
+
+impl fmt::Debug for ConsistencyOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConsistencyOptions")
+            .field("temperature", &self.temperature)
+            .field("concurrency", &self.concurrency)
+            .field("max_completion_tokens", &self.max_completion_tokens)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for ConsistencyOptions {
+    fn default() -> Self {
+        Self {
+            temperature: 0.8,
+            concurrency: 4,
+            max_completion_tokens: 1024,
+            extractor: Arc::new(final_answer),
+        }
+    }
+}
+
+impl ConsistencyOptions {
+    /// Creates the default options: temperature 0.8, 4 requests at a time,
+    /// answers taken by [`final_answer`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the sampling temperature
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// Sets the maximum number of requests in flight
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Sets the token limit of each sampled response
+    pub fn max_completion_tokens(mut self, max: u32) -> Self {
+        self.max_completion_tokens = max;
+        self
+    }
+
+    /// Sets how the final answer is taken from a response
+    ///
+    /// Responses for which the extractor returns `None` do not vote.
+    pub fn extractor(mut self, extractor: impl Fn(&str) -> Option<String> + Send + Sync + 'static) -> Self {
+        self.extractor = Arc::new(extractor);
+        self
+    }
+}
+
+/// Default answer extractor
+///
+/// Takes the text after the last `Answer:` marker (case-insensitive), or the
+/// last non-empty line otherwise, trimmed of whitespace, markdown emphasis and
+/// a trailing period.
+pub fn final_answer(response: &str) -> Option<String> {
+    // ASCII lowercasing keeps byte offsets valid for `response`
+    let answer = match response.to_ascii_lowercase().rfind("answer:") {
+        Some(i) => response[i + "answer:".len()..].lines().next().unwrap_or_default(),
+        None => response.lines().rev().find(|l| !l.trim().is_empty())?,
+    };
+    let answer = answer.trim().trim_end_matches('.').trim_matches('*').trim();
+    (!answer.is_empty()).then(|| answer.to_string())
+}
+
+impl GroqClient {
+    /// Samples `k` responses and returns the majority answer with the default
+    /// [`ConsistencyOptions`]
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError` if a request fails, or `GroqError::InvalidMessage`
+    /// if no response contained an answer.
+    pub async fn self_consistency(&self, model: &str, prompt: &str, k: usize) -> Result<ConsistencyVote, GroqError> {
+        self.self_consistency_with(model, prompt, k, &ConsistencyOptions::default()).await
+    }
+
+    /// Samples `k` responses and returns the majority answer
+    ///
+    /// The samples bypass the response cache and request deduplication, so
+    /// every one is a fresh completion.
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError` if a request fails, or `GroqError::InvalidMessage`
+    /// if no response contained an answer.
+    pub async fn self_consistency_with(
+        &self,
+        model: &str,
+        prompt: &str,
+        k: usize,
+        options: &ConsistencyOptions,
+    ) -> Result<ConsistencyVote, GroqError> {
+        let responses: Vec<String> = stream::iter(0..k.max(1))
+            .map(|_| async move {
+                let response = self
+                    .chat(model)
+                    .message(ChatMessage::new_text(Role::User, prompt))
+                    .temperature(options.temperature)
+                    .max_completion_tokens(options.max_completion_tokens)
+                    .dedupe(false)
+                    .cache(false)
+                    .send()
+                    .await?;
+                Ok::<_, GroqError>(
+                    response
+                        .choices
+                        .first()
+                        .map(|c| c.message.content.to_string())
+                        .unwrap_or_default(),
+                )
+            })
+            .buffered(options.concurrency)
+            .try_collect()
+            .await?;
+
+        let mut votes: Vec<(String, usize)> = Vec::new();
+        for answer in responses.iter().filter_map(|r| (options.extractor)(r)) {
+            match votes.iter_mut().find(|(a, _)| *a == answer) {
+                Some((_, count)) => *count += 1,
+                None => votes.push((answer, 1)),
+            }
+        }
+        // Stable sort keeps first-seen order among ties
+        votes.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        let answer = votes
+            .first()
+            .map(|(a, _)| a.clone())
+            .ok_or_else(|| GroqError::InvalidMessage("No sampled response contained an answer".to_string()))?;
+        Ok(ConsistencyVote { answer, votes, responses })
+    }
+}
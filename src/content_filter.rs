@@ -0,0 +1,86 @@
+//! Policies for replies stopped by the content filter
+//!
+//! 内容过滤策略：处理被内容过滤器拦截的回复
+//!
+//! When the API filters a reply, its `finish_reason` is `content_filter`.
+//! Chat requests then fail with [`GroqError::ContentFiltered`] instead of
+//! returning the partial reply. A [`ContentFilterPolicy`] set with
+//! [`GroqClientBuilder::content_filter_policy`](crate::GroqClientBuilder::content_filter_policy)
+//! runs first and may log or notify, accept the reply, or send a reworded
+//! request once. The reworded request is validated and checked against the
+//! spend guard like the original, and the usage of a filtered reply that is
+//! not returned is still counted. Streamed replies are not checked; compare
+//! the final chunk's `finish_reason` with [`CONTENT_FILTER`] instead.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::content_filter::ContentFilterAction;
+//! use groqai::types::{ChatMessage, Role};
+//! use groqai::{ChatCompletionRequest, ChatCompletionResponse, GroqClientBuilder};
+//!
+//! let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+//!     .content_filter_policy(|request: &ChatCompletionRequest, _: &ChatCompletionResponse| {
+//!         tracing::warn!("Reply for {} was filtered", request.model);
+//!         let mut softened = request.clone();
+//!         softened.messages.insert(
+//!             0,
+//!             ChatMessage::new_text(Role::System, "Answer in a neutral, family-friendly tone."),
+//!         );
+//!         ContentFilterAction::Retry(Box::new(softened))
+//!     })
+//! #     .transport(groqai::doctest::FixtureTransport::new())
+//!     .build()?;
+//! # Ok::<(), groqai::GroqError>(())
+//! ```
+//!
+//! [`GroqError::ContentFiltered`]: crate::GroqError::ContentFiltered
+
+use async_trait::async_trait;
+
+use crate::api::chat::ChatCompletionRequest;
+use crate::types::ChatCompletionResponse;
+
+/// The `finish_reason` of a reply stopped by the content filter
+pub const CONTENT_FILTER: &str = "content_filter";
+
+/// What to do with a reply stopped by the content filter
+#[derive(Clone)]
+pub enum ContentFilterAction {
+    /// Fail with `GroqError::ContentFiltered`
+    Fail,
+    /// Return the filtered reply as is
+    Allow,
+    /// Send this request instead, e.g. with softened wording
+    ///
+    /// Only one retry is made per call; a retried reply that is filtered
+    /// again fails.
+    Retry(Box<ChatCompletionRequest>),
+}
+
+/// Decides what happens to replies stopped by the content filter
+///
+/// Implemented for closures taking the request and the filtered response.
+#[async_trait]
+pub trait ContentFilterPolicy: Send + Sync {
+    /// Called with the request and its filtered response
+    async fn on_filtered(
+        &self,
+        request: &ChatCompletionRequest,
+        response: &ChatCompletionResponse,
+    ) -> ContentFilterAction;
+}
+
+#[async_trait]
+impl<F> ContentFilterPolicy for F
+where
+    F: Fn(&ChatCompletionRequest, &ChatCompletionResponse) -> ContentFilterAction + Send + Sync,
+{
+    async fn on_filtered(
+        &self,
+        request: &ChatCompletionRequest,
+        response: &ChatCompletionResponse,
+    ) -> ContentFilterAction {
+        self(request, response)
+    }
+}
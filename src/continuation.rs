@@ -0,0 +1,121 @@
+//! Continuing truncated replies
+//!
+//! 截断回复的自动续写模块
+//!
+//! A reply that reaches `max_completion_tokens` stops mid-sentence, or in JSON
+//! mode mid-document. [`ChatCompletionResponse::was_truncated`] detects this
+//! ([`ChatCompletionResponse::was_truncated_json`] for JSON replies), and [`ChatRequestBuilder::auto_continue`](crate::api::chat::ChatRequestBuilder::auto_continue)
+//! sends follow-up requests asking the model to carry on from where it
+//! stopped. The parts are spliced into a single response whose usage covers
+//! every request that was made.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::{ChatMessage, GroqClient, Role};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # groqai::doctest::set_api_key();
+//! let client = GroqClient::new()?;
+//! # let client = groqai::doctest::client();
+//! let response = client
+//!     .chat("llama-3.3-70b-versatile")
+//!     .message(ChatMessage::new_text(Role::User, "Write a long story about a lighthouse."))
+//!     .max_completion_tokens(500)
+//!     .auto_continue(3)
+//!     .send()
+//!     .await?;
+//! if response.was_truncated() {
+//!     println!("Still truncated after 3 continuations");
+//! }
+//! println!("{} completion tokens", response.usage.completion_tokens);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::chat::ChatCompletionRequest;
+use crate::client::{ChatSendOptions, GroqClient};
+use crate::error::GroqError;
+use crate::types::{ChatCompletionResponse, ChatMessage, MessageContent, Role, Usage};
+
+/// Follow-up instruction sent after the truncated reply
+const CONTINUE_PROMPT: &str = "Continue exactly where your previous reply stopped. Do not repeat \
+     anything you already wrote and do not add any introduction.";
+
+/// Requests continuations of `response` while it is truncated
+///
+/// `request` is the request that produced `response`. Each follow-up resends
+/// it with the reply so far as an assistant message and a request to
+/// continue. The `response_format` is dropped from follow-ups, since a
+/// fragment of a JSON document is not valid JSON on its own. Unclosed JSON
+/// only counts as truncated when `request` asked for a JSON reply.
+pub(crate) async fn continue_truncated(
+    client: &GroqClient,
+    request: ChatCompletionRequest,
+    options: ChatSendOptions,
+    mut response: ChatCompletionResponse,
+    max_continuations: u32,
+) -> Result<ChatCompletionResponse, GroqError> {
+    let json = request.response_format.as_ref().is_some_and(|format| format.type_ != "text");
+    let mut follow_up = request;
+    follow_up.response_format = None;
+    for _ in 0..max_continuations {
+        let truncated = if json { response.was_truncated_json() } else { response.was_truncated() };
+        if !truncated {
+            break;
+        }
+        let Some(MessageContent::Text(partial)) = response.choices.first().map(|c| &c.message.content) else {
+            break;
+        };
+        let mut continuation_request = follow_up.clone();
+        continuation_request
+            .messages
+            .push(ChatMessage::new_text(Role::Assistant, partial.clone()));
+        continuation_request
+            .messages
+            .push(ChatMessage::new_text(Role::User, CONTINUE_PROMPT));
+        let continuation = client
+            .chat_completions_with(continuation_request, options.clone())
+            .await?;
+        splice(&mut response, continuation);
+    }
+    Ok(response)
+}
+
+/// Appends the first choice of `continuation` to the first choice of `response`
+///
+/// The finish reason becomes the continuation's, log probabilities are
+/// concatenated and token counts and timings are summed.
+fn splice(response: &mut ChatCompletionResponse, continuation: ChatCompletionResponse) {
+    add_usage(&mut response.usage, &continuation.usage);
+    let (Some(choice), Some(next)) = (response.choices.first_mut(), continuation.choices.into_iter().next()) else {
+        return;
+    };
+    if let MessageContent::Text(text) = &mut choice.message.content {
+        text.push_str(&next.message.content.to_string());
+    }
+    choice.finish_reason = next.finish_reason;
+    match (&mut choice.logprobs, next.logprobs) {
+        (Some(logprobs), Some(next_logprobs)) => {
+            if let (Some(tokens), Some(next_tokens)) = (&mut logprobs.content, next_logprobs.content) {
+                tokens.extend(next_tokens);
+            }
+        }
+        (logprobs, _) => *logprobs = None,
+    }
+}
+
+fn add_usage(usage: &mut Usage, other: &Usage) {
+    let sum = |a: Option<f64>, b: Option<f64>| match (a, b) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or(0.0) + b.unwrap_or(0.0)),
+    };
+    usage.prompt_tokens += other.prompt_tokens;
+    usage.completion_tokens += other.completion_tokens;
+    usage.total_tokens += other.total_tokens;
+    usage.queue_time = sum(usage.queue_time, other.queue_time);
+    usage.prompt_time = sum(usage.prompt_time, other.prompt_time);
+    usage.completion_time = sum(usage.completion_time, other.completion_time);
+    usage.total_time = sum(usage.total_time, other.total_time);
+}
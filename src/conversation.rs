@@ -0,0 +1,367 @@
+//! Multi-turn conversation state
+//!
+//! 多轮对话状态管理模块
+//!
+//! [`Conversation`] keeps the message history of a chat and appends each turn
+//! after a successful request. [`SharedConversation`] wraps it for use from
+//! several tasks at once: turns are serialized so histories never interleave.
+//!
+//! Hooks registered with [`Conversation::on_edit`] are told which part of the
+//! history an edit made stale, for example to drop cached replies from a
+//! [`PrefixCache`](crate::cache::prefix::PrefixCache).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::conversation::{Conversation, SharedConversation};
+//! use groqai::GroqClient;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # groqai::doctest::set_api_key();
+//! let client = GroqClient::new()?;
+//! # let client = groqai::doctest::client();
+//! let conversation = Conversation::new("llama-3.1-8b-instant")
+//!     .with_system("You are a concise assistant.");
+//! let shared = SharedConversation::new(client, conversation);
+//!
+//! let handle = {
+//!     let shared = shared.clone();
+//!     tokio::spawn(async move { shared.send("What is Rust?").await })
+//! };
+//! shared.send("And what is Tokio?").await?;
+//! handle.await??;
+//! assert_eq!(shared.messages().await.len(), 5);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::client::GroqClient;
+use crate::document::{attachment_text, AttachOptions, Document, DocumentAttachment};
+use crate::error::GroqError;
+use crate::transcript::{self, TranscriptOptions};
+use crate::types::{ChatCompletionResponse, ChatMessage, Role};
+
+/// A callback receiving the history up to the first edited message, as it was before the edit
+pub type EditCallback = Arc<dyn Fn(&[ChatMessage]) + Send + Sync>;
+
+/// Wraps the edit callback so [`Conversation`] keeps its derives
+#[derive(Clone)]
+struct EditHook(EditCallback);
+
+impl fmt::Debug for EditHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EditHook")
+    }
+}
+
+/// The message history of a chat with one model
+///
+/// Edit hooks are not serialized; register them again after deserializing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Conversation {
+    model: String,
+    messages: Vec<ChatMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    seed: Option<i32>,
+    #[serde(skip)]
+    edit_hooks: Vec<EditHook>,
+}
+
+impl Conversation {
+    /// Creates an empty conversation with `model`
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            messages: Vec::new(),
+            temperature: None,
+            seed: None,
+            edit_hooks: Vec::new(),
+        }
+    }
+
+    /// Adds a system prompt to the conversation
+    pub fn with_system(mut self, prompt: impl Into<String>) -> Self {
+        self.messages.push(ChatMessage::new_text(Role::System, prompt));
+        self
+    }
+
+    /// Sets the sampling temperature of every turn
+    ///
+    /// With `0`, or with a [`seed`](Conversation::with_seed), replies can be
+    /// served from the client's [`PrefixCache`](crate::cache::prefix::PrefixCache).
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Sets the random seed of every turn
+    pub fn with_seed(mut self, seed: i32) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Returns the model used for every turn
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Returns the message history
+    pub fn messages(&self) -> &[ChatMessage] {
+        &self.messages
+    }
+
+    /// Appends a message to the history without sending it
+    pub fn push(&mut self, message: ChatMessage) {
+        self.messages.push(message);
+    }
+
+    /// Removes every message except system prompts
+    pub fn clear(&mut self) {
+        if let Some(first) = self.messages.iter().position(|m| m.role != Role::System) {
+            self.notify_edit(first);
+        }
+        self.messages.retain(|m| m.role == Role::System);
+    }
+
+    /// Calls `hook` before every edit of existing messages
+    ///
+    /// The hook receives the history up to and including the first message the
+    /// edit changes or removes, as it was before the edit. [`edit`](Conversation::edit),
+    /// [`truncate`](Conversation::truncate) and [`clear`](Conversation::clear)
+    /// call it; appending turns does not.
+    pub fn on_edit(&mut self, hook: impl Fn(&[ChatMessage]) + Send + Sync + 'static) {
+        self.edit_hooks.push(EditHook(Arc::new(hook)));
+    }
+
+    /// Replaces the message at `index` and drops every later message
+    ///
+    /// Later messages answered the old text, so they are removed as when a
+    /// chat UI edits a message and asks again.
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if `index` is out of range.
+    pub fn edit(&mut self, index: usize, message: ChatMessage) -> Result<(), GroqError> {
+        if index >= self.messages.len() {
+            return Err(GroqError::InvalidMessage(format!(
+                "Cannot edit message {}: the conversation has {} messages",
+                index,
+                self.messages.len()
+            )));
+        }
+        self.notify_edit(index);
+        self.messages.truncate(index);
+        self.messages.push(message);
+        Ok(())
+    }
+
+    /// Keeps the first `len` messages and drops the rest
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.messages.len() {
+            self.notify_edit(len);
+            self.messages.truncate(len);
+        }
+    }
+
+    /// Renders the history as Markdown, with system prompts redacted
+    ///
+    /// The transcript is titled with the model. See [`transcript`](crate::transcript).
+    pub fn to_markdown(&self) -> String {
+        self.to_markdown_with(&TranscriptOptions::new())
+    }
+
+    /// Renders the history as Markdown with `options`
+    ///
+    /// Without a title in `options`, the transcript is titled with the model.
+    pub fn to_markdown_with(&self, options: &TranscriptOptions) -> String {
+        transcript::to_markdown(&self.messages, &self.transcript_options(options))
+    }
+
+    /// Renders the history as an HTML fragment, with system prompts redacted
+    ///
+    /// The transcript is titled with the model. See [`transcript`](crate::transcript).
+    pub fn to_html(&self) -> String {
+        self.to_html_with(&TranscriptOptions::new())
+    }
+
+    /// Renders the history as an HTML fragment with `options`
+    ///
+    /// Without a title in `options`, the transcript is titled with the model.
+    pub fn to_html_with(&self, options: &TranscriptOptions) -> String {
+        transcript::to_html(&self.messages, &self.transcript_options(options))
+    }
+
+    fn transcript_options(&self, options: &TranscriptOptions) -> TranscriptOptions {
+        let mut options = options.clone();
+        if options.title.is_none() {
+            options.title = Some(format!("Conversation with {}", self.model));
+        }
+        options
+    }
+
+    fn notify_edit(&self, index: usize) {
+        let stale = &self.messages[..=index];
+        for hook in &self.edit_hooks {
+            (hook.0)(stale);
+        }
+    }
+
+    /// Appends the text of `document` as a user message, within a token budget
+    ///
+    /// The document is split into chunks and the leading chunks that fit
+    /// `options.max_tokens()` are attached in one message headed by the
+    /// document's name. When the rest is cut off, the message says so, so the
+    /// model does not assume it saw the whole document.
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if the document has no text or its
+    /// first chunk alone exceeds the budget; the history is left unchanged.
+    pub fn attach_document(
+        &mut self,
+        document: &Document,
+        options: &AttachOptions,
+    ) -> Result<DocumentAttachment, GroqError> {
+        let (text, attachment) = attachment_text(document, options)?;
+        self.messages.push(ChatMessage::new_text(Role::User, text));
+        Ok(attachment)
+    }
+
+    /// Sends a user message and appends the assistant's reply
+    ///
+    /// The history is only changed once the reply arrives, so it is left
+    /// unchanged if the request fails or the future is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError` if the request fails or the response has no choices.
+    pub async fn send(
+        &mut self,
+        client: &GroqClient,
+        text: impl Into<String>,
+    ) -> Result<ChatCompletionResponse, GroqError> {
+        let message = ChatMessage::new_text(Role::User, text);
+        let mut messages = self.messages.clone();
+        messages.push(message.clone());
+        let (response, reply) = self.complete(client, messages).await?;
+        self.messages.push(message);
+        self.messages.push(reply);
+        Ok(response)
+    }
+
+    /// Discards the last assistant reply and asks the model again
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if the conversation does not end with
+    /// an assistant reply to a user message, or `GroqError` if the request fails.
+    /// The previous reply is only replaced once the new one arrives.
+    pub async fn regenerate(&mut self, client: &GroqClient) -> Result<ChatCompletionResponse, GroqError> {
+        let messages = match self.messages.split_last() {
+            Some((last, earlier)) if last.role == Role::Assistant => earlier.to_vec(),
+            _ => {
+                return Err(GroqError::InvalidMessage(
+                    "Nothing to regenerate: the last message is not an assistant reply".to_string(),
+                ))
+            }
+        };
+        let (response, reply) = self.complete(client, messages).await?;
+        self.messages.pop();
+        self.messages.push(reply);
+        Ok(response)
+    }
+
+    /// Sends `messages` and returns the response with its reply
+    async fn complete(
+        &self,
+        client: &GroqClient,
+        messages: Vec<ChatMessage>,
+    ) -> Result<(ChatCompletionResponse, ChatMessage), GroqError> {
+        let mut builder = client.chat(self.model.clone()).messages(messages);
+        if let Some(temperature) = self.temperature {
+            builder = builder.temperature(temperature);
+        }
+        if let Some(seed) = self.seed {
+            builder = builder.seed(seed);
+        }
+        let response = builder.send().await?;
+        let reply = response
+            .choices
+            .first()
+            .ok_or_else(|| GroqError::InvalidMessage("Response contained no choices".to_string()))?
+            .message
+            .clone();
+        Ok((response, reply))
+    }
+}
+
+/// A [`Conversation`] shared between tasks
+///
+/// Cloning is cheap and clones refer to the same conversation. Each turn holds
+/// the conversation lock for the duration of its request, so concurrent
+/// `send` calls are answered one after another against a consistent history.
+#[derive(Clone)]
+pub struct SharedConversation {
+    client: GroqClient,
+    inner: Arc<Mutex<Conversation>>,
+}
+
+impl SharedConversation {
+    /// Wraps `conversation` for shared use with `client`
+    pub fn new(client: GroqClient, conversation: Conversation) -> Self {
+        Self {
+            client,
+            inner: Arc::new(Mutex::new(conversation)),
+        }
+    }
+
+    /// Sends a user message once all earlier turns have completed
+    ///
+    /// # Errors
+    ///
+    /// See [`Conversation::send`].
+    pub async fn send(&self, text: impl Into<String>) -> Result<ChatCompletionResponse, GroqError> {
+        self.inner.lock().await.send(&self.client, text).await
+    }
+
+    /// Regenerates the last reply once all earlier turns have completed
+    ///
+    /// # Errors
+    ///
+    /// See [`Conversation::regenerate`].
+    pub async fn regenerate(&self) -> Result<ChatCompletionResponse, GroqError> {
+        self.inner.lock().await.regenerate(&self.client).await
+    }
+
+    /// Attaches a document once all earlier turns have completed
+    ///
+    /// # Errors
+    ///
+    /// See [`Conversation::attach_document`].
+    pub async fn attach_document(
+        &self,
+        document: &Document,
+        options: &AttachOptions,
+    ) -> Result<DocumentAttachment, GroqError> {
+        self.inner.lock().await.attach_document(document, options)
+    }
+
+    /// Returns a copy of the message history
+    pub async fn messages(&self) -> Vec<ChatMessage> {
+        self.inner.lock().await.messages().to_vec()
+    }
+
+    /// Returns a copy of the conversation
+    pub async fn snapshot(&self) -> Conversation {
+        self.inner.lock().await.clone()
+    }
+}
@@ -0,0 +1,107 @@
+//! Client-side deduplication of identical chat requests
+//!
+//! 客户端请求去重模块，避免重复提交相同的请求
+//!
+//! Two modes are available on [`GroqClientBuilder`](crate::GroqClientBuilder):
+//!
+//! - [`dedupe_window`](crate::GroqClientBuilder::dedupe_window): a chat
+//!   completion request whose body is identical to one issued within the window
+//!   shares the first request's result instead of calling the API again. This
+//!   protects against accidental double-submits, e.g. from UI retries.
+//! - [`coalesce_requests`](crate::GroqClientBuilder::coalesce_requests)
+//!   ("singleflight"): identical *deterministic* requests (temperature 0 or a
+//!   fixed seed) that are in flight at the same time share one upstream call.
+//!
+//! Individual requests can opt out with
+//! [`ChatRequestBuilder::dedupe(false)`](crate::ChatRequestBuilder::dedupe).
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use futures::future::{BoxFuture, FutureExt, Shared};
+
+use crate::api::chat::ChatCompletionRequest;
+use crate::error::GroqError;
+use crate::types::ChatCompletionResponse;
+
+type SharedResponse = Shared<BoxFuture<'static, Result<ChatCompletionResponse, GroqError>>>;
+
+/// Shares results between identical requests
+pub struct RequestDeduplicator {
+    window: Option<Duration>,
+    /// Entries keyed by the serialized request body
+    entries: Mutex<HashMap<String, (Instant, SharedResponse)>>,
+}
+
+impl RequestDeduplicator {
+    /// Creates a deduplicator with the given window
+    ///
+    /// A request joins an earlier identical request if the earlier one was
+    /// issued less than `window` ago. Failed requests are never reused.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window: Some(window),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Creates a deduplicator that only shares requests while they are in flight
+    pub fn in_flight() -> Self {
+        Self {
+            window: None,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the deduplication window, or `None` for in-flight sharing only
+    pub fn window(&self) -> Option<Duration> {
+        self.window
+    }
+
+    /// Returns true if the request always produces the same completion
+    ///
+    /// Only such requests are coalesced, since sharing one sampled answer
+    /// between callers asking for independent samples would change semantics.
+    pub fn is_deterministic(request: &ChatCompletionRequest) -> bool {
+        request.temperature == Some(0.0) || request.seed.is_some()
+    }
+
+    /// Returns the deduplication key of a request: its serialized body
+    ///
+    /// The full body is the key, so different requests never share a result
+    /// through a hash collision.
+    pub fn key(request: &ChatCompletionRequest) -> Result<String, GroqError> {
+        Ok(serde_json::to_string(request)?)
+    }
+
+    /// Runs `send` unless an identical request is already in the window
+    ///
+    /// Returns the response and whether this call sent the request, so usage
+    /// is counted once per upstream response.
+    pub(crate) async fn run<F>(&self, key: String, send: F) -> Result<(ChatCompletionResponse, bool), GroqError>
+    where
+        F: Future<Output = Result<ChatCompletionResponse, GroqError>> + Send + 'static,
+    {
+        let mut sent = false;
+        let shared = {
+            let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            entries.retain(|_, (issued, shared)| match (self.window, shared.peek()) {
+                (_, Some(Err(_))) => false,
+                (Some(window), _) => now.duration_since(*issued) < window,
+                (None, result) => result.is_none(),
+            });
+            entries
+                .entry(key)
+                .or_insert_with(|| {
+                    sent = true;
+                    (now, send.boxed().shared())
+                })
+                .1
+                .clone()
+        };
+        Ok((shared.await?, sent))
+    }
+}
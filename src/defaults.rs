@@ -0,0 +1,155 @@
+//! Per-model default request parameters
+//!
+//! 按模型配置的默认请求参数
+//!
+//! A [`ModelDefaultsTable`] holds operator-tuned defaults (temperature, token
+//! limit, stop sequences) per model. The client applies them to every chat
+//! request for that model that does not set the parameter itself. The table
+//! can be loaded from a JSON file and updated while the client is running, so
+//! tuning production behavior does not require a deploy.
+//!
+//! The file maps model IDs to defaults; the `"*"` entry applies to models
+//! without their own entry:
+//!
+//! ```json
+//! {
+//!   "*": { "max_completion_tokens": 1024 },
+//!   "llama-3.1-8b-instant": { "temperature": 0.2, "stop": ["\n\n"] }
+//! }
+//! ```
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::defaults::ModelDefaultsTable;
+//! use groqai::GroqClientBuilder;
+//!
+//! # groqai::doctest::scratch_dir();
+//! # std::fs::write("model-defaults.json", r#"{"*": {"max_completion_tokens": 1024}}"#).unwrap();
+//! let table = ModelDefaultsTable::from_file("model-defaults.json")?;
+//! let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+//!     .model_defaults(table.clone())
+//!     .build()?;
+//!
+//! // Later, e.g. on SIGHUP:
+//! table.reload_file("model-defaults.json")?;
+//! # Ok::<(), groqai::GroqError>(())
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::chat::ChatCompletionRequest;
+use crate::error::GroqError;
+use crate::types::StopSequence;
+
+/// Model ID whose defaults apply to models without their own entry
+pub const FALLBACK_MODEL: &str = "*";
+
+/// Default parameters for one model
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelDefaults {
+    /// Sampling temperature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Maximum number of tokens to generate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_completion_tokens: Option<u32>,
+    /// Stop sequences
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<StopSequence>,
+}
+
+/// Runtime-updatable table of [`ModelDefaults`]
+///
+/// Clones share the same table, so updates made through any clone (or
+/// through [`GroqClient::model_defaults`](crate::GroqClient::model_defaults))
+/// affect every request built afterwards.
+#[derive(Debug, Clone, Default)]
+pub struct ModelDefaultsTable {
+    entries: Arc<RwLock<HashMap<String, ModelDefaults>>>,
+}
+
+impl ModelDefaultsTable {
+    /// Creates an empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a table from JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::Serde` if the JSON is malformed or contains unknown
+    /// parameters.
+    pub fn from_json_str(json: &str) -> Result<Self, GroqError> {
+        let table = Self::new();
+        table.replace(serde_json::from_str(json)?);
+        Ok(table)
+    }
+
+    /// Loads a table from a JSON file
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if the file cannot be read, or
+    /// `GroqError::Serde` if it is not a valid table.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, GroqError> {
+        let table = Self::new();
+        table.reload_file(path)?;
+        Ok(table)
+    }
+
+    /// Replaces every entry with the contents of a JSON file
+    ///
+    /// The table is left unchanged if the file is invalid.
+    ///
+    /// # Errors
+    ///
+    /// See [`from_file`](Self::from_file).
+    pub fn reload_file(&self, path: impl AsRef<Path>) -> Result<(), GroqError> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| GroqError::InvalidMessage(format!("Failed to open file: {}", e)))?;
+        self.replace(serde_json::from_str(&json)?);
+        Ok(())
+    }
+
+    /// Sets the defaults of `model`; use [`FALLBACK_MODEL`] for all other models
+    pub fn set(&self, model: impl Into<String>, defaults: ModelDefaults) {
+        self.write().insert(model.into(), defaults);
+    }
+
+    /// Removes the defaults of `model`
+    pub fn remove(&self, model: &str) -> Option<ModelDefaults> {
+        self.write().remove(model)
+    }
+
+    /// Returns the defaults that apply to `model`
+    pub fn get(&self, model: &str) -> Option<ModelDefaults> {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        entries.get(model).or_else(|| entries.get(FALLBACK_MODEL)).cloned()
+    }
+
+    /// Fills parameters `request` leaves unset with the defaults of its model
+    pub fn apply(&self, request: &mut ChatCompletionRequest) {
+        if let Some(defaults) = self.get(&request.model) {
+            request.temperature = request.temperature.or(defaults.temperature);
+            request.max_completion_tokens = request.max_completion_tokens.or(defaults.max_completion_tokens);
+            if request.stop.is_none() {
+                request.stop = defaults.stop;
+            }
+        }
+    }
+
+    fn replace(&self, entries: HashMap<String, ModelDefaults>) {
+        *self.write() = entries;
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<String, ModelDefaults>> {
+        self.entries.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,205 @@
+//! Speaker diarization of transcriptions
+//!
+//! 转录结果的说话人分离模块
+//!
+//! Whisper does not tell speakers apart. A [`Diarizer`] turns the timed
+//! segments of a transcription (request `response_format: "verbose_json"`)
+//! into [`Utterance`]s attributed to speakers. Plug in your own engine, e.g.
+//! one backed by a local diarization model, or use the [`PauseDiarizer`]
+//! heuristic for two-party recordings with clear turn-taking.
+//! [`label_speakers`] optionally asks a chat model to replace generic labels
+//! with roles or names inferred from the conversation.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::diarize::{label_speakers, PauseDiarizer};
+//! use groqai::{AudioResponseFormat, AudioTranscriptionRequest, GroqClient};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # groqai::doctest::set_api_key();
+//! let client = GroqClient::new()?;
+//! # let client = groqai::doctest::client_replying(r#"{"SPEAKER_1": "Interviewer"}"#);
+//! let transcription = client
+//!     .audio()
+//!     .transcribe(AudioTranscriptionRequest {
+//!         file: Some("interview.mp3".into()),
+//!         model: "whisper-large-v3".to_string(),
+//!         response_format: Some(AudioResponseFormat::VerboseJson),
+//!         ..Default::default()
+//!     })
+//!     .await?;
+//!
+//! let utterances = transcription.diarize(&PauseDiarizer::new()).await?;
+//! let utterances = label_speakers(&client, "llama-3.3-70b-versatile", utterances).await?;
+//! for u in utterances {
+//!     println!("[{:.1}s] {}: {}", u.start, u.speaker, u.text);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::client::GroqClient;
+use crate::error::GroqError;
+use crate::types::{ChatMessage, ResponseFormat, Role, Transcription};
+
+/// A stretch of speech by one speaker
+#[derive(Debug, Clone, PartialEq)]
+pub struct Utterance {
+    /// Speaker label, e.g. "SPEAKER_1" or a name assigned by [`label_speakers`]
+    pub speaker: String,
+    /// Start time in seconds
+    pub start: f64,
+    /// End time in seconds
+    pub end: f64,
+    /// What was said
+    pub text: String,
+}
+
+/// Attributes the speech in a transcription to speakers
+#[async_trait]
+pub trait Diarizer: Send + Sync {
+    /// Splits `transcription` into utterances in chronological order
+    async fn diarize(&self, transcription: &Transcription) -> Result<Vec<Utterance>, GroqError>;
+}
+
+impl Transcription {
+    /// Runs `diarizer` over this transcription
+    ///
+    /// # Errors
+    ///
+    /// Returns whatever error the diarizer reports.
+    pub async fn diarize(&self, diarizer: &dyn Diarizer) -> Result<Vec<Utterance>, GroqError> {
+        diarizer.diarize(self).await
+    }
+}
+
+/// Heuristic diarizer that switches speaker at long pauses
+///
+/// Speakers take turns in rotation: each silence of at least `min_pause`
+/// between segments starts the next speaker's turn. Works reasonably for
+/// interviews and calls; use a real diarization engine for anything else.
+#[derive(Debug, Clone)]
+pub struct PauseDiarizer {
+    min_pause: Duration,
+    speakers: usize,
+}
+
+impl Default for PauseDiarizer {
+    fn default() -> Self {
+        Self {
+            min_pause: Duration::from_millis(1500),
+            speakers: 2,
+        }
+    }
+}
+
+impl PauseDiarizer {
+    /// Creates a diarizer for two speakers switching at pauses of 1.5 seconds
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the silence that marks a change of speaker
+    pub fn min_pause(mut self, pause: Duration) -> Self {
+        self.min_pause = pause;
+        self
+    }
+
+    /// Sets the number of speakers to rotate through
+    pub fn speakers(mut self, speakers: usize) -> Self {
+        self.speakers = speakers.max(1);
+        self
+    }
+}
+
+#[async_trait]
+impl Diarizer for PauseDiarizer {
+    async fn diarize(&self, transcription: &Transcription) -> Result<Vec<Utterance>, GroqError> {
+        if transcription.segments.is_empty() {
+            return Err(GroqError::InvalidMessage(
+                "Diarization needs timed segments; transcribe with response_format \"verbose_json\"".to_string(),
+            ));
+        }
+        let min_pause = self.min_pause.as_secs_f64();
+        let mut speaker = 0;
+        let mut utterances: Vec<Utterance> = Vec::new();
+        for segment in &transcription.segments {
+            let text = segment.text.trim();
+            match utterances.last_mut() {
+                Some(last) if segment.start - last.end < min_pause => {
+                    last.end = segment.end;
+                    if !text.is_empty() {
+                        last.text.push(' ');
+                        last.text.push_str(text);
+                    }
+                }
+                last => {
+                    if last.is_some() {
+                        speaker = (speaker + 1) % self.speakers;
+                    }
+                    utterances.push(Utterance {
+                        speaker: format!("SPEAKER_{}", speaker + 1),
+                        start: segment.start,
+                        end: segment.end,
+                        text: text.to_string(),
+                    });
+                }
+            }
+        }
+        Ok(utterances)
+    }
+}
+
+/// Asks a chat model to replace generic speaker labels with roles or names
+///
+/// The model sees the labelled transcript and returns a mapping such as
+/// `SPEAKER_1 -> Interviewer`. Labels the model leaves out are kept.
+///
+/// # Errors
+///
+/// Returns `GroqError` if the request fails or the reply is not a JSON object.
+pub async fn label_speakers(
+    client: &GroqClient,
+    model: &str,
+    mut utterances: Vec<Utterance>,
+) -> Result<Vec<Utterance>, GroqError> {
+    let transcript: Vec<String> = utterances
+        .iter()
+        .map(|u| format!("[{}] {}", u.speaker, u.text))
+        .collect();
+    let response = client
+        .chat(model)
+        .message(ChatMessage::new_text(
+            Role::System,
+            "The user sends a transcript with generic speaker labels. Infer each speaker's \
+             name or, if no name is mentioned, their role. Reply with a JSON object mapping \
+             each label to its name or role, e.g. {\"SPEAKER_1\": \"Interviewer\"}.",
+        ))
+        .message(ChatMessage::new_text(Role::User, transcript.join("\n")))
+        .temperature(0.0)
+        .response_format(ResponseFormat {
+            type_: "json_object".to_string(),
+            json_schema: None,
+        })
+        .send()
+        .await?;
+    let reply = response
+        .choices
+        .first()
+        .map(|c| c.message.content.to_string())
+        .unwrap_or_default();
+    let labels: HashMap<String, String> = serde_json::from_str(&reply)?;
+    for utterance in &mut utterances {
+        if let Some(label) = labels.get(&utterance.speaker).filter(|l| !l.trim().is_empty()) {
+            utterance.speaker = label.trim().to_string();
+        }
+    }
+    Ok(utterances)
+}
@@ -0,0 +1,275 @@
+//! Offline client for running documentation examples
+//!
+//! 文档示例使用的离线客户端
+//!
+//! [`client`] returns a [`GroqClient`] whose [`FixtureTransport`] answers every
+//! endpoint with the matching [`fixtures`] document instead of calling the
+//! API. The crate's doc examples create it in a hidden line, so they run as
+//! tests on every build while showing the usual setup.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::{ChatMessage, GroqClientBuilder, Role};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?.build()?;
+//! # let client = groqai::doctest::client();
+//! let response = client
+//!     .chat("llama-3.1-8b-instant")
+//!     .message(ChatMessage::new_text(Role::User, "Hello!"))
+//!     .send()
+//!     .await?;
+//! println!("{}", response.choices[0].message.content);
+//! # Ok(())
+//! # }
+//! ```
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::client::{GroqClient, GroqClientBuilder};
+use crate::endpoint::Endpoint;
+use crate::error::GroqError;
+use crate::fixtures;
+use crate::transport::{ByteStream, Method, Request, Response, Transport};
+
+/// Header of a WAV file with no samples, returned for speech requests
+const SILENT_WAV: &[u8] = b"RIFF\x24\x00\x00\x00WAVEfmt \x10\x00\x00\x00\x01\x00\x01\x00\x80\x3e\x00\x00\x00\x7d\x00\x00\x02\x00\x10\x00data\x00\x00\x00\x00";
+
+/// A transparent 1x1 PNG image, written for examples reading local images
+const TINY_PNG: &[u8] = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR\x00\x00\x00\x01\x00\x00\x00\x01\x08\x06\x00\x00\x00\x1f\x15\xc4\x89\x00\x00\x00\rIDATx\x9cc\x00\x01\x00\x00\x05\x00\x01\r\n-\xb4\x00\x00\x00\x00IEND\xaeB`\x82";
+
+/// Sets `GROQ_API_KEY` to a placeholder so examples can call [`GroqClient::new`]
+pub fn set_api_key() {
+    if std::env::var_os("GROQ_API_KEY").is_none() {
+        std::env::set_var("GROQ_API_KEY", "gsk_doctest_key");
+    }
+}
+
+/// Moves into a scratch directory, so examples can read and write files by name
+pub fn scratch_dir() {
+    let dir = std::env::temp_dir().join(format!("groqai-doctest-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("doctest scratch directory");
+    std::env::set_current_dir(&dir).expect("doctest scratch directory");
+}
+
+/// Writes a JSONL file with one batch request line at `path`
+pub fn jsonl_file(path: &str) {
+    let line = json!({
+        "custom_id": "request-1",
+        "method": "POST",
+        "url": "/v1/chat/completions",
+        "body": {"model": "llama-3.1-8b-instant", "messages": [{"role": "user", "content": "Hello!"}]}
+    });
+    write(path, format!("{}\n", line).as_bytes());
+}
+
+/// Writes a stream recorded by [`StreamRecorder`](crate::stream::StreamRecorder) at `path`
+pub fn ndjson_file(path: &str) {
+    let line = json!({"timestamp_ms": 1730241104000u64, "elapsed_ms": 0, "chunk": fixture(fixtures::CHAT_COMPLETION_CHUNK)});
+    write(path, format!("{}\n", line).as_bytes());
+}
+
+/// Writes a one-pixel PNG image at `path`
+pub fn image_file(path: &str) {
+    write(path, TINY_PNG);
+}
+
+/// Writes the PEM certificate of a private test CA at `path`
+pub fn ca_file(path: &str) {
+    write(path, include_bytes!("../tests/fixtures/private-ca.pem"));
+}
+
+fn write(path: &str, content: &[u8]) {
+    let path = std::path::Path::new(path);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).expect("doctest file directory");
+    }
+    std::fs::write(path, content).expect("doctest file");
+}
+
+/// Returns a client answering every request from the fixtures
+pub fn client() -> GroqClient {
+    GroqClientBuilder::new("gsk_doctest_key".to_string())
+        .and_then(|builder| builder.transport(FixtureTransport::new()).build())
+        .expect("doctest client")
+}
+
+/// Returns a client like [`client`] whose chat completions reply with `content`
+pub fn client_replying(content: &str) -> GroqClient {
+    GroqClientBuilder::new("gsk_doctest_key".to_string())
+        .and_then(|builder| builder.transport(FixtureTransport::new().chat_reply(content)).build())
+        .expect("doctest client")
+}
+
+/// A [`Transport`] answering every endpoint with a fixture response
+///
+/// Unlike [`MockTransport`](crate::mock::MockTransport), nothing is queued:
+/// each request gets the fixture for its endpoint and method, with the ID from
+/// the path filled in, so an example can make any number of calls.
+#[derive(Debug, Clone)]
+pub struct FixtureTransport {
+    base_url: Url,
+    chat_reply: Option<String>,
+}
+
+impl Default for FixtureTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FixtureTransport {
+    /// Creates a fixture transport
+    pub fn new() -> Self {
+        Self {
+            base_url: Url::parse("https://api.groq.com/openai/v1/").expect("valid default url"),
+            chat_reply: None,
+        }
+    }
+
+    /// Replies to chat completions with `content` instead of the fixture's text
+    pub fn chat_reply(mut self, content: impl Into<String>) -> Self {
+        self.chat_reply = Some(content.into());
+        self
+    }
+}
+
+fn fixture(document: &str) -> Value {
+    serde_json::from_str(document).expect("valid fixture")
+}
+
+/// Returns `document` with its `id` replaced by the one the request asked for
+fn with_id(document: &str, id: &str) -> Value {
+    let mut value = fixture(document);
+    value["id"] = Value::String(id.to_string());
+    value
+}
+
+fn fine_tuning(id: &str) -> Value {
+    json!({
+        "id": id,
+        "name": "my-custom-model",
+        "base_model": "llama-3.1-8b-instant",
+        "type": "supervised",
+        "input_file_id": "file_01jh6x76wtemjr74t1fh0faj5t",
+        "created_at": 1736472600,
+        "status": "succeeded",
+        "fine_tuned_model": "ft:llama-3.1-8b-instant:my-custom-model",
+        "training_progress": null,
+        "error": null
+    })
+}
+
+/// Transcripts in a plain text or subtitle format are returned as text
+fn transcript(document: &str, body: &Value) -> Value {
+    let text = fixture(document)["text"].as_str().unwrap_or_default().trim().to_string();
+    match body["response_format"].as_str() {
+        Some("text") => Value::String(text),
+        Some("srt") => Value::String(format!("1\n00:00:00,000 --> 00:00:04,200\n{}\n", text)),
+        Some("vtt") => Value::String(format!("WEBVTT\n\n00:00:00.000 --> 00:00:04.200\n{}\n", text)),
+        _ => fixture(document),
+    }
+}
+
+fn embeddings(body: &Value) -> Value {
+    let inputs = match &body["input"] {
+        Value::Array(inputs) => inputs.len(),
+        _ => 1,
+    };
+    let data: Vec<Value> = (0..inputs)
+        .map(|index| json!({"object": "embedding", "index": index, "embedding": [1.0, 0.0, 0.0]}))
+        .collect();
+    json!({"object": "list", "data": data, "model": body["model"]})
+}
+
+/// A batch output file with one line answered by the chat fixture
+fn batch_output() -> String {
+    let line = json!({
+        "id": "batch_req_1",
+        "custom_id": "request-1",
+        "response": {"status_code": 200, "body": fixture(fixtures::CHAT_COMPLETION_RESPONSE)},
+        "error": null
+    });
+    format!("{}\n", line)
+}
+
+#[async_trait]
+impl Transport for FixtureTransport {
+    async fn execute(&self, request: Request) -> Result<Response, GroqError> {
+        let body = request.body_json().unwrap_or(Value::Null);
+        let post = request.method == Method::POST;
+        let value = match &request.endpoint {
+            Endpoint::ChatCompletions => {
+                let mut response = fixture(fixtures::CHAT_COMPLETION_RESPONSE);
+                if let Some(content) = &self.chat_reply {
+                    response["choices"][0]["message"]["content"] = Value::String(content.clone());
+                }
+                response
+            }
+            Endpoint::AudioTranscriptions if body["response_format"] == "verbose_json" => {
+                fixture(fixtures::TRANSCRIPTION_VERBOSE)
+            }
+            Endpoint::AudioTranscriptions => transcript(fixtures::TRANSCRIPTION, &body),
+            Endpoint::AudioTranslations => transcript(fixtures::TRANSLATION, &body),
+            Endpoint::AudioSpeech => Value::String(String::from_utf8_lossy(SILENT_WAV).into_owned()),
+            Endpoint::Embeddings => embeddings(&body),
+            Endpoint::Files if post => fixture(fixtures::FILE),
+            Endpoint::Files => fixture(fixtures::FILE_LIST),
+            Endpoint::File(id) if request.method == Method::DELETE => with_id(fixtures::FILE_DELETION, id),
+            Endpoint::File(id) => with_id(fixtures::FILE, id),
+            Endpoint::FileContent(_) => Value::String(batch_output()),
+            Endpoint::Batches if post => fixture(fixtures::BATCH),
+            Endpoint::Batches => fixture(fixtures::BATCH_LIST),
+            Endpoint::Batch(id) => with_id(fixtures::BATCH, id),
+            Endpoint::BatchCancel(id) => {
+                let mut batch = with_id(fixtures::BATCH, id);
+                batch["status"] = json!("cancelled");
+                batch["cancelled_at"] = batch["completed_at"].take();
+                batch
+            }
+            Endpoint::Models => fixture(fixtures::MODEL_LIST),
+            Endpoint::Model(id) => {
+                let mut model = fixture(fixtures::MODEL_LIST)["data"][0].take();
+                model["id"] = Value::String(id.clone());
+                model
+            }
+            Endpoint::FineTuningJobs if post => fine_tuning("ft_01jh6xa7reempvjyh6n3yst2zw"),
+            Endpoint::FineTuningJobs => {
+                json!({"object": "list", "data": [fine_tuning("ft_01jh6xa7reempvjyh6n3yst2zw")], "has_more": false})
+            }
+            Endpoint::FineTuningJob(id) => fine_tuning(id),
+            Endpoint::FineTuningJobCancel(id) => {
+                let mut job = fine_tuning(id);
+                job["status"] = json!("cancelled");
+                job
+            }
+            Endpoint::FineTuningJobEvents(_) => json!({
+                "object": "list",
+                "data": [{"id": "ftevent_1", "created_at": 1736472607, "level": "info", "message": "Job succeeded"}],
+                "has_more": false
+            }),
+        };
+        Ok(Response::new(value))
+    }
+
+    async fn execute_stream(&self, request: Request) -> Result<ByteStream, GroqError> {
+        let chunks = match request.endpoint {
+            Endpoint::ChatCompletions => vec![
+                Bytes::from(format!("data: {}\n\n", fixture(fixtures::CHAT_COMPLETION_CHUNK))),
+                Bytes::from_static(b"data: [DONE]\n\n"),
+            ],
+            Endpoint::FileContent(_) => vec![Bytes::from(batch_output())],
+            _ => vec![Bytes::from_static(SILENT_WAV)],
+        };
+        Ok(Box::pin(futures::stream::iter(chunks.into_iter().map(Ok))))
+    }
+
+    fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+}
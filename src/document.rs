@@ -0,0 +1,343 @@
+//! Document ingestion for chatting with a document
+//!
+//! 文档导入模块：将 PDF、HTML 与 Markdown 文档转换为对话上下文
+//!
+//! A [`Document`] holds the text extracted from a file. Plain text and
+//! Markdown are read as they are; HTML needs the `html` feature and PDF the
+//! `pdf` feature. [`Conversation::attach_document`](crate::Conversation::attach_document)
+//! splits the text with a [`Splitter`] and adds as many chunks as fit a token
+//! budget to the conversation, so later questions are answered from the
+//! document.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::document::{AttachOptions, Document};
+//! use groqai::{Conversation, GroqClient};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # groqai::doctest::set_api_key();
+//! let client = GroqClient::new()?;
+//! # let client = groqai::doctest::client();
+//! # groqai::doctest::scratch_dir();
+//! # std::fs::write("handbook.md", "# Leave\n\nEmployees get 25 vacation days a year.\n")?;
+//! let document = Document::open("handbook.md").await?;
+//! let mut conversation = Conversation::new("llama-3.3-70b-versatile")
+//!     .with_system("Answer from the attached document only.");
+//! let attached = conversation.attach_document(&document, &AttachOptions::new(6_000))?;
+//! if attached.is_truncated() {
+//!     println!("Only {} of {} parts fit", attached.chunks, attached.total_chunks);
+//! }
+//! let response = conversation.send(&client, "How many vacation days do I get?").await?;
+//! println!("{}", response.choices[0].message.content);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::path::Path;
+
+use crate::error::GroqError;
+use crate::text::{estimate_tokens, Splitter};
+
+/// Chunk size used when [`AttachOptions`] has no splitter
+const DEFAULT_CHUNK_TOKENS: usize = 500;
+
+/// Line width for HTML rendering; wide enough that paragraphs are not wrapped
+#[cfg(feature = "html")]
+const HTML_WIDTH: usize = 10_000;
+
+/// Format of a [`Document`]'s source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFormat {
+    /// Plain text
+    Text,
+    /// Markdown, split at headings
+    Markdown,
+    /// HTML, rendered to Markdown-like text; requires the `html` feature
+    Html,
+    /// PDF, with the text of every page extracted; requires the `pdf` feature
+    Pdf,
+}
+
+impl DocumentFormat {
+    /// Guesses the format from a file extension such as `md` or `pdf`
+    ///
+    /// Returns `None` for extensions that are not a known document format.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "txt" | "text" => Some(DocumentFormat::Text),
+            "md" | "markdown" => Some(DocumentFormat::Markdown),
+            "html" | "htm" | "xhtml" => Some(DocumentFormat::Html),
+            "pdf" => Some(DocumentFormat::Pdf),
+            _ => None,
+        }
+    }
+
+    /// Returns the splitter that keeps this format's structure together
+    fn splitter(self, max_tokens: usize) -> Splitter {
+        match self {
+            // Rendered HTML keeps its headings as `#` lines
+            DocumentFormat::Markdown | DocumentFormat::Html => Splitter::markdown_headings(max_tokens),
+            DocumentFormat::Text | DocumentFormat::Pdf => Splitter::sentences(max_tokens),
+        }
+    }
+}
+
+impl fmt::Display for DocumentFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DocumentFormat::Text => "text",
+            DocumentFormat::Markdown => "markdown",
+            DocumentFormat::Html => "html",
+            DocumentFormat::Pdf => "pdf",
+        })
+    }
+}
+
+/// Text extracted from a document, ready to be attached to a conversation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    name: String,
+    format: DocumentFormat,
+    text: String,
+}
+
+impl Document {
+    /// Creates a document from plain text
+    pub fn from_text(name: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            format: DocumentFormat::Text,
+            text: text.into(),
+        }
+    }
+
+    /// Creates a document from Markdown
+    pub fn from_markdown(name: impl Into<String>, markdown: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            format: DocumentFormat::Markdown,
+            text: markdown.into(),
+        }
+    }
+
+    /// Creates a document from the raw contents of a file in `format`
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if text is not valid UTF-8, if the
+    /// text cannot be extracted, or if `format` needs a feature that is not
+    /// enabled.
+    pub fn from_bytes(name: impl Into<String>, format: DocumentFormat, bytes: &[u8]) -> Result<Self, GroqError> {
+        let text = match format {
+            DocumentFormat::Text | DocumentFormat::Markdown => String::from_utf8(bytes.to_vec())
+                .map_err(|e| GroqError::InvalidMessage(format!("Document is not valid UTF-8: {}", e)))?,
+            DocumentFormat::Html => extract_html(bytes)?,
+            DocumentFormat::Pdf => extract_pdf(bytes)?,
+        };
+        Ok(Self {
+            name: name.into(),
+            format,
+            text,
+        })
+    }
+
+    /// Reads a document from disk, taking the format from the file extension
+    ///
+    /// Files with an unknown extension are read as plain text.
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if the file cannot be read, or as
+    /// for [`from_bytes`](Document::from_bytes).
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self, GroqError> {
+        let path = path.as_ref();
+        let format = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(DocumentFormat::from_extension)
+            .unwrap_or(DocumentFormat::Text);
+        let bytes = read_file(path)
+            .await
+            .map_err(|e| GroqError::InvalidMessage(format!("Cannot read {}: {}", path.display(), e)))?;
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        Self::from_bytes(name, format, &bytes)
+    }
+
+    /// Returns the document's name, shown to the model when it is attached
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the format the text was extracted from
+    pub fn format(&self) -> DocumentFormat {
+        self.format
+    }
+
+    /// Returns the extracted text
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the estimated number of tokens in the text
+    pub fn estimated_tokens(&self) -> usize {
+        estimate_tokens(&self.text)
+    }
+
+    /// Splits the text into chunks with `splitter`
+    pub fn chunks(&self, splitter: &Splitter) -> Vec<String> {
+        splitter.split(&self.text)
+    }
+}
+
+#[cfg(feature = "html")]
+fn extract_html(bytes: &[u8]) -> Result<String, GroqError> {
+    html2text::from_read(bytes, HTML_WIDTH)
+        .map_err(|e| GroqError::InvalidMessage(format!("Cannot extract text from HTML: {}", e)))
+}
+
+#[cfg(not(feature = "html"))]
+fn extract_html(_bytes: &[u8]) -> Result<String, GroqError> {
+    Err(GroqError::InvalidMessage(
+        "HTML documents require the `html` feature".to_string(),
+    ))
+}
+
+#[cfg(feature = "pdf")]
+fn extract_pdf(bytes: &[u8]) -> Result<String, GroqError> {
+    pdf_extract::extract_text_from_mem(bytes)
+        .map_err(|e| GroqError::InvalidMessage(format!("Cannot extract text from PDF: {}", e)))
+}
+
+#[cfg(not(feature = "pdf"))]
+fn extract_pdf(_bytes: &[u8]) -> Result<String, GroqError> {
+    Err(GroqError::InvalidMessage(
+        "PDF documents require the `pdf` feature".to_string(),
+    ))
+}
+
+/// Options for [`Conversation::attach_document`](crate::Conversation::attach_document)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttachOptions {
+    max_tokens: usize,
+    splitter: Option<Splitter>,
+}
+
+impl AttachOptions {
+    /// Attaches at most `max_tokens` estimated tokens of the document
+    pub fn new(max_tokens: usize) -> Self {
+        Self {
+            max_tokens,
+            splitter: None,
+        }
+    }
+
+    /// Splits the document with `splitter` instead of the format's default
+    ///
+    /// By default Markdown and HTML are split at headings and other formats
+    /// at sentences, into chunks of about 500 tokens.
+    pub fn splitter(mut self, splitter: Splitter) -> Self {
+        self.splitter = Some(splitter);
+        self
+    }
+
+    /// Returns the token budget
+    pub fn max_tokens(&self) -> usize {
+        self.max_tokens
+    }
+
+    /// Returns the splitter used for `document`
+    pub fn splitter_for(&self, document: &Document) -> Splitter {
+        self.splitter
+            .unwrap_or_else(|| document.format.splitter(DEFAULT_CHUNK_TOKENS.min(self.max_tokens)))
+    }
+}
+
+/// What [`Conversation::attach_document`](crate::Conversation::attach_document) attached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DocumentAttachment {
+    /// Chunks that fit the budget and were attached
+    pub chunks: usize,
+    /// Chunks the document was split into
+    pub total_chunks: usize,
+    /// Estimated tokens of the attached message
+    pub tokens: usize,
+}
+
+impl DocumentAttachment {
+    /// Returns true if the end of the document did not fit the budget
+    pub fn is_truncated(&self) -> bool {
+        self.chunks < self.total_chunks
+    }
+}
+
+/// Builds the message text for `document`, keeping the leading chunks that fit
+///
+/// # Errors
+///
+/// Returns `GroqError::InvalidMessage` if the document has no text or not even
+/// its first chunk fits the budget.
+pub(crate) fn attachment_text(
+    document: &Document,
+    options: &AttachOptions,
+) -> Result<(String, DocumentAttachment), GroqError> {
+    let chunks = document.chunks(&options.splitter_for(document));
+    if chunks.is_empty() {
+        return Err(GroqError::InvalidMessage(format!(
+            "Document {} contains no text",
+            document.name
+        )));
+    }
+
+    let mut text = format!("Document: {}", document.name);
+    let mut attached = 0;
+    for (i, chunk) in chunks.iter().enumerate() {
+        // Leave room for the truncation note in case the next chunk does not fit
+        let note = if i + 1 < chunks.len() {
+            estimate_tokens(&truncation_note(i + 1, chunks.len()))
+        } else {
+            0
+        };
+        if estimate_tokens(&text) + 1 + estimate_tokens(chunk) + note > options.max_tokens {
+            break;
+        }
+        text.push_str("\n\n");
+        text.push_str(chunk);
+        attached += 1;
+    }
+    if attached == 0 {
+        return Err(GroqError::InvalidMessage(format!(
+            "Document {} does not fit in {} tokens",
+            document.name, options.max_tokens
+        )));
+    }
+    if attached < chunks.len() {
+        text.push_str(&truncation_note(attached, chunks.len()));
+    }
+    let attachment = DocumentAttachment {
+        chunks: attached,
+        total_chunks: chunks.len(),
+        tokens: estimate_tokens(&text),
+    };
+    Ok((text, attachment))
+}
+
+fn truncation_note(attached: usize, total: usize) -> String {
+    format!("\n\n[Truncated: the first {} of {} parts of the document]", attached, total)
+}
+
+/// Reads a file on the blocking thread pool
+///
+/// Used instead of `tokio::fs` so builds without the upload features don't
+/// need tokio's `fs` feature.
+pub(crate) async fn read_file(path: &Path) -> std::io::Result<Vec<u8>> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || std::fs::read(path))
+        .await
+        .map_err(std::io::Error::other)?
+}
@@ -0,0 +1,156 @@
+//! Typed API endpoints
+//!
+//! 类型化的 API 端点模块
+//!
+//! Every request the SDK sends goes to one of the [`Endpoint`] variants, which
+//! render the path relative to the base URL. Paths are built in one place, so
+//! a typo cannot send a request to a path that does not exist. Parsing a path
+//! back with [`Endpoint::from_path`] gives its [`label`](Endpoint::label), the
+//! path with IDs replaced by `{id}`, which metrics use to group requests. With
+//! the `middleware` feature the endpoint is also put into the request's
+//! extensions, so `reqwest-middleware` middleware can label or route by it.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::Endpoint;
+//!
+//! let endpoint = Endpoint::BatchCancel("batch_01abc".to_string());
+//! assert_eq!(endpoint.path(), "batches/batch_01abc/cancel");
+//! assert_eq!(endpoint.label(), "batches/{id}/cancel");
+//!
+//! let parsed = Endpoint::from_path("models/openai/gpt-oss-20b").unwrap();
+//! assert_eq!(parsed, Endpoint::Model("openai/gpt-oss-20b".to_string()));
+//! ```
+
+use std::fmt;
+
+/// An API endpoint, relative to the client's base URL
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// `chat/completions`
+    ChatCompletions,
+    /// `audio/transcriptions`
+    AudioTranscriptions,
+    /// `audio/translations`
+    AudioTranslations,
+    /// `audio/speech`
+    AudioSpeech,
+    /// `embeddings`
+    Embeddings,
+    /// `files`
+    Files,
+    /// `files/{id}`
+    File(String),
+    /// `files/{id}/content`
+    FileContent(String),
+    /// `batches`
+    Batches,
+    /// `batches/{id}`
+    Batch(String),
+    /// `batches/{id}/cancel`
+    BatchCancel(String),
+    /// `models`
+    Models,
+    /// `models/{id}`; model IDs may contain `/`
+    Model(String),
+    /// `fine_tuning/jobs`
+    FineTuningJobs,
+    /// `fine_tuning/jobs/{id}`
+    FineTuningJob(String),
+    /// `fine_tuning/jobs/{id}/events`
+    FineTuningJobEvents(String),
+    /// `fine_tuning/jobs/{id}/cancel`
+    FineTuningJobCancel(String),
+}
+
+impl Endpoint {
+    /// Returns the path relative to the base URL
+    pub fn path(&self) -> String {
+        match self {
+            Endpoint::File(id) => format!("files/{}", id),
+            Endpoint::FileContent(id) => format!("files/{}/content", id),
+            Endpoint::Batch(id) => format!("batches/{}", id),
+            Endpoint::BatchCancel(id) => format!("batches/{}/cancel", id),
+            Endpoint::Model(id) => format!("models/{}", id),
+            Endpoint::FineTuningJob(id) => format!("fine_tuning/jobs/{}", id),
+            Endpoint::FineTuningJobEvents(id) => format!("fine_tuning/jobs/{}/events", id),
+            Endpoint::FineTuningJobCancel(id) => format!("fine_tuning/jobs/{}/cancel", id),
+            _ => self.label().to_string(),
+        }
+    }
+
+    /// Returns the path with IDs replaced by `{id}`, for grouping requests
+    pub fn label(&self) -> &'static str {
+        match self {
+            Endpoint::ChatCompletions => "chat/completions",
+            Endpoint::AudioTranscriptions => "audio/transcriptions",
+            Endpoint::AudioTranslations => "audio/translations",
+            Endpoint::AudioSpeech => "audio/speech",
+            Endpoint::Embeddings => "embeddings",
+            Endpoint::Files => "files",
+            Endpoint::File(_) => "files/{id}",
+            Endpoint::FileContent(_) => "files/{id}/content",
+            Endpoint::Batches => "batches",
+            Endpoint::Batch(_) => "batches/{id}",
+            Endpoint::BatchCancel(_) => "batches/{id}/cancel",
+            Endpoint::Models => "models",
+            Endpoint::Model(_) => "models/{id}",
+            Endpoint::FineTuningJobs => "fine_tuning/jobs",
+            Endpoint::FineTuningJob(_) => "fine_tuning/jobs/{id}",
+            Endpoint::FineTuningJobEvents(_) => "fine_tuning/jobs/{id}/events",
+            Endpoint::FineTuningJobCancel(_) => "fine_tuning/jobs/{id}/cancel",
+        }
+    }
+
+    /// Returns the ID in the path, if the endpoint has one
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            Endpoint::File(id)
+            | Endpoint::FileContent(id)
+            | Endpoint::Batch(id)
+            | Endpoint::BatchCancel(id)
+            | Endpoint::Model(id)
+            | Endpoint::FineTuningJob(id)
+            | Endpoint::FineTuningJobEvents(id)
+            | Endpoint::FineTuningJobCancel(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Parses a path relative to the base URL; a query string is ignored
+    ///
+    /// Returns `None` for paths that are not a known endpoint.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let path = path.split(['?', '#']).next().unwrap_or_default().trim_matches('/');
+        let segments: Vec<&str> = path.split('/').collect();
+        let id = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        let endpoint = match segments.as_slice() {
+            ["chat", "completions"] => Endpoint::ChatCompletions,
+            ["audio", "transcriptions"] => Endpoint::AudioTranscriptions,
+            ["audio", "translations"] => Endpoint::AudioTranslations,
+            ["audio", "speech"] => Endpoint::AudioSpeech,
+            ["embeddings"] => Endpoint::Embeddings,
+            ["files"] => Endpoint::Files,
+            ["files", file] => Endpoint::File(id(file)?),
+            ["files", file, "content"] => Endpoint::FileContent(id(file)?),
+            ["batches"] => Endpoint::Batches,
+            ["batches", batch] => Endpoint::Batch(id(batch)?),
+            ["batches", batch, "cancel"] => Endpoint::BatchCancel(id(batch)?),
+            ["models"] => Endpoint::Models,
+            ["models", ..] => Endpoint::Model(id(&path["models/".len()..])?),
+            ["fine_tuning", "jobs"] => Endpoint::FineTuningJobs,
+            ["fine_tuning", "jobs", job] => Endpoint::FineTuningJob(id(job)?),
+            ["fine_tuning", "jobs", job, "events"] => Endpoint::FineTuningJobEvents(id(job)?),
+            ["fine_tuning", "jobs", job, "cancel"] => Endpoint::FineTuningJobCancel(id(job)?),
+            _ => return None,
+        };
+        Some(endpoint)
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.path())
+    }
+}
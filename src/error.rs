@@ -7,6 +7,8 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use thiserror::Error;
 
+use crate::types::ChatCompletionResponse;
+
 /// Wrapper for JSON serialization errors
 #[derive(Debug, Clone, Error)]
 #[error("JSON serialization error: {0}")]
@@ -31,6 +33,7 @@ impl From<reqwest::Error> for TransportError {
 
 /// Details of an API error response from Groq
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 pub struct GroqApiErrorDetails {
     /// The error message from the API
     pub message: String,
@@ -45,6 +48,7 @@ pub struct GroqApiErrorDetails {
 
 /// API error response structure from Groq
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 pub struct GroqApiError {
     /// HTTP status code of the error response
     #[serde(skip)]
@@ -75,7 +79,7 @@ impl GroqApiError {
             .ok()
             .and_then(|v| v.get("error").cloned())
             .and_then(|e| serde_json::from_value(e).ok())
-            .unwrap_or_else(|| GroqApiErrorDetails {
+            .unwrap_or(GroqApiErrorDetails {
                 message: body,
                 error_type: None,
                 code: None,
@@ -88,6 +92,67 @@ impl GroqApiError {
             retry_after,
         }
     }
+
+    /// Creates an API error from a streamed SSE payload carrying an `error` object
+    /// 
+    /// Errors sent mid-stream arrive after a `200 OK`, so the status is taken
+    /// from a `status_code` field in the payload if present, or else derived
+    /// from the error type; unknown types map to `500 Internal Server Error`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `data` - The payload of an SSE `data:` line
+    /// 
+    /// Returns `None` if the payload is not JSON with an `error` field.
+    pub fn from_stream_event(data: &str) -> Option<Self> {
+        use serde_json::Value;
+
+        let value: Value = serde_json::from_str(data).ok()?;
+        let error = value.get("error")?;
+        // Codes are sometimes numbers, which `GroqApiErrorDetails` would reject
+        let text = |field: &str| match error.get(field) {
+            Some(Value::String(s)) => Some(s.clone()),
+            Some(Value::Number(n)) => Some(n.to_string()),
+            _ => None,
+        };
+        let details = match error {
+            Value::String(message) => GroqApiErrorDetails {
+                message: message.clone(),
+                error_type: None,
+                code: None,
+                param: None,
+            },
+            Value::Object(_) => GroqApiErrorDetails {
+                message: text("message").unwrap_or_else(|| error.to_string()),
+                error_type: text("type"),
+                code: text("code"),
+                param: text("param"),
+            },
+            _ => return None,
+        };
+
+        let status = error
+            .get("status_code")
+            .or(value.get("status_code"))
+            .and_then(Value::as_u64)
+            .and_then(|code| u16::try_from(code).ok())
+            .and_then(|code| StatusCode::from_u16(code).ok())
+            .unwrap_or(match details.error_type.as_deref() {
+                Some("invalid_request_error") => StatusCode::BAD_REQUEST,
+                Some("authentication_error") => StatusCode::UNAUTHORIZED,
+                Some("permission_error") => StatusCode::FORBIDDEN,
+                Some("not_found_error") => StatusCode::NOT_FOUND,
+                Some("rate_limit_exceeded") | Some("tokens") => StatusCode::TOO_MANY_REQUESTS,
+                Some("service_unavailable") | Some("overloaded_error") => StatusCode::SERVICE_UNAVAILABLE,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            });
+
+        Some(Self {
+            status,
+            error: details,
+            retry_after: None,
+        })
+    }
 }
 
 impl std::fmt::Display for GroqApiError {
@@ -98,6 +163,58 @@ impl std::fmt::Display for GroqApiError {
 
 impl std::error::Error for GroqApiError {}
 
+/// Client-safe error representation for services that wrap this SDK
+/// 
+/// Serializes to the OpenAI-style `{"error": {...}}` shape. Messages never
+/// contain credentials or internal transport details: errors caused by the
+/// service's own configuration or upstream connectivity are reported generically.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+pub struct ErrorBody {
+    /// The error details
+    pub error: ErrorBodyDetails,
+}
+
+/// Details of an [`ErrorBody`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+pub struct ErrorBodyDetails {
+    /// Human-readable error message
+    pub message: String,
+    /// Error category, e.g. "invalid_request_error" or "rate_limit_exceeded"
+    #[serde(rename = "type")]
+    pub error_type: String,
+    /// Upstream error code, if provided
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Parameter that caused the error, if applicable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub param: Option<String>,
+    /// Seconds the caller should wait before retrying, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<u64>,
+}
+
+impl ErrorBody {
+    fn new(message: impl Into<String>, error_type: &str) -> Self {
+        Self {
+            error: ErrorBodyDetails {
+                message: message.into(),
+                error_type: error_type.to_string(),
+                code: None,
+                param: None,
+                retry_after: None,
+            },
+        }
+    }
+}
+
+impl From<&GroqError> for ErrorBody {
+    fn from(err: &GroqError) -> Self {
+        err.to_error_body()
+    }
+}
+
 /// Main error type for the Groq client library
 /// 
 /// This enum covers all possible error conditions that can occur
@@ -135,6 +252,43 @@ pub enum GroqError {
     /// Backoff/retry mechanism error
     #[error("Backoff error: {0}")]
     Backoff(String),
+
+    /// A long-running job ended without succeeding
+    #[error("Job {id} ended with status {status}")]
+    JobFailed {
+        /// ID of the job
+        id: String,
+        /// Final status reported by the API, e.g. "failed" or "cancelled"
+        status: String,
+        /// Error payload reported by the API, if any
+        error: Option<serde_json::Value>,
+    },
+
+    /// Waiting for a long-running job exceeded the configured timeout
+    #[error("Timed out after {elapsed:?} waiting for job {id}")]
+    JobTimeout {
+        /// ID of the job
+        id: String,
+        /// Time spent waiting
+        elapsed: Duration,
+    },
+
+    /// The client was shut down before or while sending the request
+    #[error("Client is shut down")]
+    Shutdown,
+
+    /// The request was rejected by a spending guard before it was sent
+    #[error("Budget exceeded: {0}")]
+    BudgetExceeded(String),
+
+    /// The content filter stopped the reply
+    #[error("Reply from {model} was stopped by the content filter")]
+    ContentFiltered {
+        /// Model that produced the reply
+        model: String,
+        /// The filtered response, with any partial content
+        response: Box<ChatCompletionResponse>,
+    },
 }
 
 impl From<serde_json::Error> for GroqError {
@@ -149,6 +303,18 @@ impl From<reqwest::Error> for GroqError {
     }
 }
 
+#[cfg(feature = "middleware")]
+impl From<reqwest_middleware::Error> for GroqError {
+    fn from(err: reqwest_middleware::Error) -> Self {
+        match err {
+            reqwest_middleware::Error::Reqwest(e) => e.into(),
+            reqwest_middleware::Error::Middleware(e) => {
+                GroqError::Transport(TransportError(format!("{:#}", e)))
+            }
+        }
+    }
+}
+
 impl<E> From<backoff::Error<E>> for GroqError
 where
     E: Into<GroqError>,
@@ -206,4 +372,110 @@ impl GroqError {
     pub fn is_auth_error(&self) -> bool {
         matches!(self, GroqError::InvalidApiKey(_))
     }
+
+    /// Returns the HTTP status a service wrapping this SDK should answer with
+    /// 
+    /// Invalid requests and rate limits map to their 4xx codes. Failures of the
+    /// service's own credentials or configuration map to 500, and upstream
+    /// outages or unreadable upstream responses map to 502/503.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqError;
+    /// use reqwest::StatusCode;
+    /// 
+    /// assert_eq!(GroqError::RateLimited.to_http_status(), StatusCode::TOO_MANY_REQUESTS);
+    /// assert_eq!(
+    ///     GroqError::InvalidMessage("empty messages".to_string()).to_http_status(),
+    ///     StatusCode::BAD_REQUEST
+    /// );
+    /// ```
+    pub fn to_http_status(&self) -> StatusCode {
+        match self {
+            GroqError::InvalidApiKey(_) | GroqError::UrlParse(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            GroqError::InvalidMessage(_) => StatusCode::BAD_REQUEST,
+            GroqError::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            GroqError::Api(e) => match e.status {
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => StatusCode::BAD_GATEWAY,
+                status if status.is_server_error() => StatusCode::BAD_GATEWAY,
+                status if status.is_client_error() => status,
+                _ => StatusCode::BAD_GATEWAY,
+            },
+            GroqError::Transport(_) | GroqError::Serde(_) => StatusCode::BAD_GATEWAY,
+            GroqError::Backoff(_) => StatusCode::SERVICE_UNAVAILABLE,
+            GroqError::JobFailed { .. } => StatusCode::BAD_GATEWAY,
+            GroqError::JobTimeout { .. } => StatusCode::GATEWAY_TIMEOUT,
+            GroqError::Shutdown => StatusCode::SERVICE_UNAVAILABLE,
+            GroqError::BudgetExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
+            GroqError::ContentFiltered { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    /// Returns a client-safe, serializable representation of this error
+    /// 
+    /// Only messages from Groq's own client errors are passed through. Local
+    /// errors such as `InvalidMessage`, which may name files or echo input,
+    /// get a generic message; log the error itself for the details.
+    /// 
+    /// # Examples
+    /// 
+    /// ```rust
+    /// use groqai::GroqError;
+    /// 
+    /// let body = GroqError::InvalidApiKey("gsk_secret".to_string()).to_error_body();
+    /// let json = serde_json::to_string(&body).unwrap();
+    /// assert!(!json.contains("gsk_secret"));
+    /// 
+    /// let body = GroqError::InvalidMessage("Failed to read /home/me/notes.txt".to_string()).to_error_body();
+    /// assert_eq!(body.error.message, "Invalid request");
+    /// ```
+    pub fn to_error_body(&self) -> ErrorBody {
+        match self {
+            GroqError::InvalidApiKey(_) | GroqError::UrlParse(_) => {
+                ErrorBody::new("Upstream service is misconfigured", "internal_error")
+            }
+            // 可能包含本地路径或 IO 细节，不返回给客户端
+            GroqError::InvalidMessage(_) => ErrorBody::new("Invalid request", "invalid_request_error"),
+            GroqError::RateLimited => ErrorBody::new("Rate limit exceeded", "rate_limit_exceeded"),
+            GroqError::Api(e) => {
+                let status = self.to_http_status();
+                let mut body = if status == StatusCode::BAD_GATEWAY {
+                    ErrorBody::new("Upstream service error", "upstream_error")
+                } else {
+                    let error_type = e
+                        .error
+                        .error_type
+                        .clone()
+                        .unwrap_or_else(|| match status {
+                            StatusCode::TOO_MANY_REQUESTS => "rate_limit_exceeded".to_string(),
+                            _ => "invalid_request_error".to_string(),
+                        });
+                    let mut body = ErrorBody::new(e.error.message.clone(), &error_type);
+                    body.error.code = e.error.code.clone();
+                    body.error.param = e.error.param.clone();
+                    body
+                };
+                body.error.retry_after = e.retry_after.map(|d| d.as_secs());
+                body
+            }
+            GroqError::Transport(_) | GroqError::Serde(_) => {
+                ErrorBody::new("Upstream service error", "upstream_error")
+            }
+            GroqError::Backoff(_) => {
+                ErrorBody::new("Upstream service unavailable", "service_unavailable")
+            }
+            GroqError::JobFailed { status, .. } => {
+                ErrorBody::new(format!("Upstream job ended with status {}", status), "upstream_error")
+            }
+            GroqError::JobTimeout { .. } => {
+                ErrorBody::new("Timed out waiting for upstream job", "timeout")
+            }
+            GroqError::Shutdown => ErrorBody::new("Service is shutting down", "service_unavailable"),
+            GroqError::BudgetExceeded(_) => ErrorBody::new("Spending limit reached", "budget_exceeded"),
+            GroqError::ContentFiltered { .. } => {
+                ErrorBody::new("The reply was blocked by the content filter", "content_filter")
+            }
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,215 @@
+//! Structured data extraction into typed structs
+//!
+//! 从文本中抽取结构化数据并反序列化为类型化结构体
+//!
+//! [`GroqClient::extract`] asks a chat model in JSON mode to fill in a type's
+//! JSON schema from free text, validates the reply against the schema,
+//! deserializes it, and on failure shows the model what was wrong and asks
+//! again. Types describe their schema by implementing [`ExtractSchema`], or
+//! with the `schema-export` feature by deriving `schemars::JsonSchema`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::extract::ExtractSchema;
+//! use groqai::GroqClient;
+//! use serde::Deserialize;
+//! use serde_json::{json, Value};
+//!
+//! #[derive(Deserialize)]
+//! struct Invoice {
+//!     number: String,
+//!     total: f64,
+//! }
+//!
+//! impl ExtractSchema for Invoice {
+//!     fn json_schema() -> Value {
+//!         json!({
+//!             "type": "object",
+//!             "properties": {
+//!                 "number": {"type": "string"},
+//!                 "total": {"type": "number"}
+//!             },
+//!             "required": ["number", "total"]
+//!         })
+//!     }
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # groqai::doctest::set_api_key();
+//! let client = GroqClient::new()?;
+//! # let client = groqai::doctest::client_replying(r#"{"number": "INV-7", "total": 120.5}"#);
+//! let invoice: Invoice = client
+//!     .extract("llama-3.3-70b-versatile", "Invoice INV-7 is due: 120.50 EUR")
+//!     .await?;
+//! println!("{} {}", invoice.number, invoice.total);
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::client::GroqClient;
+use crate::error::GroqError;
+use crate::tools::{SchemaValidator, ToolArgumentValidator};
+use crate::types::{ChatMessage, FunctionDef, ResponseFormat, Role};
+
+/// A type that can be extracted from text
+///
+/// With the `schema-export` feature, every type deriving
+/// `schemars::JsonSchema` and `Deserialize` implements it, with
+/// subschemas inlined so the built-in validator can check replies.
+pub trait ExtractSchema: DeserializeOwned {
+    /// Returns the JSON schema of the type's serialized form
+    fn json_schema() -> Value;
+}
+
+#[cfg(feature = "schema-export")]
+impl<T: DeserializeOwned + schemars::JsonSchema> ExtractSchema for T {
+    fn json_schema() -> Value {
+        let mut settings = schemars::generate::SchemaSettings::draft07().for_deserialize();
+        settings.inline_subschemas = true;
+        settings.into_generator().root_schema_for::<T>().to_value()
+    }
+}
+
+/// Options for [`GroqClient::extract_with`]
+#[derive(Debug, Clone)]
+pub struct ExtractOptions {
+    max_attempts: u32,
+    instructions: Option<String>,
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            instructions: None,
+        }
+    }
+}
+
+impl ExtractOptions {
+    /// Creates the default options: up to 3 attempts, no extra instructions
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many replies the model may give before extraction fails
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Adds instructions to the system prompt, e.g. how to normalize dates
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+}
+
+impl GroqClient {
+    /// Extracts a `T` from `text` with the default [`ExtractOptions`]
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - Chat model that supports JSON mode
+    /// * `text` - Text to extract the data from
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError` if a request fails, or `GroqError::InvalidMessage`
+    /// if no reply matched the schema within the allowed attempts.
+    pub async fn extract<T: ExtractSchema>(&self, model: &str, text: &str) -> Result<T, GroqError> {
+        self.extract_with(model, text, &ExtractOptions::default()).await
+    }
+
+    /// Extracts a `T` from `text`
+    ///
+    /// Each reply is validated against `T::json_schema()` and deserialized.
+    /// If either step fails, the problems are sent back to the model and it
+    /// is asked to try again.
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError` if a request fails, or `GroqError::InvalidMessage`
+    /// if no reply matched the schema within the allowed attempts.
+    pub async fn extract_with<T: ExtractSchema>(
+        &self,
+        model: &str,
+        text: &str,
+        options: &ExtractOptions,
+    ) -> Result<T, GroqError> {
+        let schema = T::json_schema();
+        let mut system = format!(
+            "Extract data from the user's text. Reply with a single JSON object matching \
+             this JSON schema:\n{}\nUse null for optional fields the text does not mention. \
+             Do not invent values.",
+            serde_json::to_string_pretty(&schema)?
+        );
+        if let Some(instructions) = &options.instructions {
+            system.push_str("\n\n");
+            system.push_str(instructions);
+        }
+        let definition = FunctionDef {
+            name: "extract".to_string(),
+            description: None,
+            parameters: schema,
+        };
+        let mut messages = vec![
+            ChatMessage::new_text(Role::System, system),
+            ChatMessage::new_text(Role::User, text),
+        ];
+
+        let mut problem = String::new();
+        for _ in 0..options.max_attempts {
+            let response = self
+                .chat(model)
+                .messages(messages.clone())
+                .temperature(0.0)
+                .response_format(ResponseFormat {
+                    type_: "json_object".to_string(),
+                    json_schema: None,
+                })
+                .send()
+                .await?;
+            let reply = response
+                .choices
+                .first()
+                .map(|c| c.message.content.to_string())
+                .unwrap_or_default();
+            problem = match parse_reply::<T>(&definition, &reply) {
+                Ok(value) => return Ok(value),
+                Err(problem) => problem,
+            };
+            messages.push(ChatMessage::new_text(Role::Assistant, reply));
+            messages.push(ChatMessage::new_text(
+                Role::User,
+                format!(
+                    "That reply does not match the schema: {}. Reply with the corrected JSON object only.",
+                    problem
+                ),
+            ));
+        }
+        Err(GroqError::InvalidMessage(format!(
+            "Extraction failed after {} attempts: {}",
+            options.max_attempts, problem
+        )))
+    }
+}
+
+/// Parses, validates and deserializes a reply, describing the first problem found
+fn parse_reply<T: DeserializeOwned>(definition: &FunctionDef, reply: &str) -> Result<T, String> {
+    let value: Value = serde_json::from_str(reply).map_err(|e| format!("invalid JSON ({})", e))?;
+    let violations = SchemaValidator.validate(Some(definition), &value);
+    if !violations.is_empty() {
+        let described: Vec<String> = violations
+            .iter()
+            .map(|v| format!("{}: {}", if v.path.is_empty() { "/" } else { &v.path }, v.message))
+            .collect();
+        return Err(described.join("; "));
+    }
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
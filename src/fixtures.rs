@@ -0,0 +1,112 @@
+//! Golden wire-format fixtures for the Groq API
+//!
+//! 与 Groq API 线上格式一致的黄金 JSON 样例
+//!
+//! Each constant is a JSON document in the exact shape the API sends or
+//! accepts. The crate's `wire_compat` tests check every request and response
+//! type against them, so a serde change that would break compatibility fails
+//! CI. Downstream crates can reuse them to test their own mocks and proxies.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::fixtures;
+//! use groqai::ChatCompletionResponse;
+//!
+//! let response: ChatCompletionResponse =
+//!     serde_json::from_str(fixtures::CHAT_COMPLETION_RESPONSE).unwrap();
+//! assert_eq!(response.choices.len(), 1);
+//! ```
+
+/// Body of `POST /chat/completions` with multimodal content, tools and most options set
+pub const CHAT_COMPLETION_REQUEST: &str = include_str!("../fixtures/wire/chat_completion_request.json");
+
+/// Body of `POST /chat/completions` requiring a tool call and a JSON schema reply
+pub const CHAT_COMPLETION_REQUEST_STRUCTURED: &str =
+    include_str!("../fixtures/wire/chat_completion_request_structured.json");
+
+/// Response of `POST /chat/completions`
+pub const CHAT_COMPLETION_RESPONSE: &str = include_str!("../fixtures/wire/chat_completion_response.json");
+
+/// One `data:` payload of a streamed chat completion
+pub const CHAT_COMPLETION_CHUNK: &str = include_str!("../fixtures/wire/chat_completion_chunk.json");
+
+/// Response of `POST /audio/transcriptions`
+pub const TRANSCRIPTION: &str = include_str!("../fixtures/wire/transcription.json");
+
+/// Response of `POST /audio/transcriptions` with `response_format: "verbose_json"`
+pub const TRANSCRIPTION_VERBOSE: &str = include_str!("../fixtures/wire/transcription_verbose.json");
+
+/// Response of `POST /audio/translations`
+pub const TRANSLATION: &str = include_str!("../fixtures/wire/translation.json");
+
+/// Body of `POST /audio/speech`
+pub const SPEECH_REQUEST: &str = include_str!("../fixtures/wire/speech_request.json");
+
+/// Response of `GET /models`
+pub const MODEL_LIST: &str = include_str!("../fixtures/wire/model_list.json");
+
+/// Response of `GET /models/{id}`
+pub const MODEL: &str = include_str!("../fixtures/wire/model.json");
+
+/// Response of `POST /files` and `GET /files/{id}`
+pub const FILE: &str = include_str!("../fixtures/wire/file.json");
+
+/// Response of `GET /files`
+pub const FILE_LIST: &str = include_str!("../fixtures/wire/file_list.json");
+
+/// Response of `DELETE /files/{id}`
+pub const FILE_DELETION: &str = include_str!("../fixtures/wire/file_deletion.json");
+
+/// Body of `POST /batches`
+pub const BATCH_CREATE_REQUEST: &str = include_str!("../fixtures/wire/batch_create_request.json");
+
+/// Response of `POST /batches` and `GET /batches/{id}`
+pub const BATCH: &str = include_str!("../fixtures/wire/batch.json");
+
+/// Response of `GET /batches/{id}` for a batch whose input file failed validation
+pub const BATCH_FAILED: &str = include_str!("../fixtures/wire/batch_failed.json");
+
+/// Response of `GET /batches`
+pub const BATCH_LIST: &str = include_str!("../fixtures/wire/batch_list.json");
+
+/// Body of `POST /fine_tuning/jobs`
+pub const FINE_TUNING_CREATE_REQUEST: &str = include_str!("../fixtures/wire/fine_tuning_create_request.json");
+
+/// Response of `POST /fine_tuning/jobs` and `GET /fine_tuning/jobs/{id}`
+pub const FINE_TUNING: &str = include_str!("../fixtures/wire/fine_tuning.json");
+
+/// Response of `GET /fine_tuning/jobs`
+pub const FINE_TUNING_LIST: &str = include_str!("../fixtures/wire/fine_tuning_list.json");
+
+/// Response of `GET /fine_tuning/jobs/{id}/events`
+pub const FINE_TUNING_EVENT_LIST: &str = include_str!("../fixtures/wire/fine_tuning_event_list.json");
+
+/// Error body of a 429 response
+pub const ERROR: &str = include_str!("../fixtures/wire/error.json");
+
+/// Every fixture with its file name (without extension)
+pub const ALL: &[(&str, &str)] = &[
+    ("chat_completion_request", CHAT_COMPLETION_REQUEST),
+    ("chat_completion_request_structured", CHAT_COMPLETION_REQUEST_STRUCTURED),
+    ("chat_completion_response", CHAT_COMPLETION_RESPONSE),
+    ("chat_completion_chunk", CHAT_COMPLETION_CHUNK),
+    ("transcription", TRANSCRIPTION),
+    ("transcription_verbose", TRANSCRIPTION_VERBOSE),
+    ("translation", TRANSLATION),
+    ("speech_request", SPEECH_REQUEST),
+    ("model_list", MODEL_LIST),
+    ("model", MODEL),
+    ("file", FILE),
+    ("file_list", FILE_LIST),
+    ("file_deletion", FILE_DELETION),
+    ("batch_create_request", BATCH_CREATE_REQUEST),
+    ("batch", BATCH),
+    ("batch_failed", BATCH_FAILED),
+    ("batch_list", BATCH_LIST),
+    ("fine_tuning_create_request", FINE_TUNING_CREATE_REQUEST),
+    ("fine_tuning", FINE_TUNING),
+    ("fine_tuning_list", FINE_TUNING_LIST),
+    ("fine_tuning_event_list", FINE_TUNING_EVENT_LIST),
+    ("error", ERROR),
+];
@@ -0,0 +1,300 @@
+//! Import of chat histories from OpenAI JSON and ChatML
+//!
+//! 从 OpenAI JSON 与 ChatML 格式导入对话历史
+//!
+//! Prompts and datasets written for OpenAI tooling can be reused as
+//! [`ChatMessage`]s. [`from_openai_json`] reads a JSON array of chat-format
+//! messages, or an object with a `messages` array such as a request body or a
+//! fine-tuning dataset line. [`from_chatml`] reads ChatML text
+//! (`<|im_start|>role ... <|im_end|>`).
+//!
+//! Both understand tool calls: OpenAI `tool_calls` and legacy
+//! `function_call` messages, and `<tool_call>` / `<tool_response>` blocks in
+//! ChatML. OpenAI content parts may be text or images; the `developer` role
+//! becomes a system message.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::import;
+//! use groqai::Role;
+//!
+//! let messages = import::from_chatml(
+//!     "<|im_start|>system\nYou are terse.<|im_end|>\n<|im_start|>user\nHi!<|im_end|>\n<|im_start|>assistant\n",
+//! )?;
+//! assert_eq!(messages.len(), 2);
+//! assert_eq!(messages[1].role, Role::User);
+//!
+//! let messages = import::from_openai_json(r#"{"messages": [
+//!     {"role": "developer", "content": "You are terse."},
+//!     {"role": "user", "content": [
+//!         {"type": "text", "text": "What is this?"},
+//!         {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}}
+//!     ]}
+//! ]}"#)?;
+//! assert_eq!(messages[0].role, Role::System);
+//! assert_eq!(messages[1].content.images().len(), 1);
+//! # Ok::<(), groqai::GroqError>(())
+//! ```
+
+use serde_json::Value;
+
+use crate::error::GroqError;
+use crate::types::{ChatMessage, FunctionCall, ImageUrl, MessageContent, MessagePart, Role, ToolCall};
+
+const IM_START: &str = "<|im_start|>";
+const IM_END: &str = "<|im_end|>";
+
+/// Parses OpenAI chat-format JSON into messages
+///
+/// # Arguments
+///
+/// * `json` - A JSON array of messages, or an object with a `messages` array
+///
+/// # Errors
+///
+/// Returns `GroqError::InvalidMessage` naming the offending message if the
+/// JSON is malformed, a role is unknown or a content part is not text or an
+/// image.
+pub fn from_openai_json(json: &str) -> Result<Vec<ChatMessage>, GroqError> {
+    let value: Value = serde_json::from_str(json)
+        .map_err(|e| GroqError::InvalidMessage(format!("Failed to parse OpenAI messages: {}", e)))?;
+    from_openai_value(&value)
+}
+
+/// Converts already parsed OpenAI chat-format JSON into messages
+///
+/// See [`from_openai_json`].
+///
+/// # Errors
+///
+/// Returns `GroqError::InvalidMessage` as [`from_openai_json`] does.
+pub fn from_openai_value(value: &Value) -> Result<Vec<ChatMessage>, GroqError> {
+    let Some(messages) = value.as_array().or_else(|| value.get("messages").and_then(Value::as_array)) else {
+        return Err(GroqError::InvalidMessage(
+            "OpenAI messages must be an array or an object with a `messages` array".to_string(),
+        ));
+    };
+    // Legacy `function_call` messages carry no id; results are matched by name
+    let mut legacy_calls: Vec<(String, String)> = Vec::new();
+    messages
+        .iter()
+        .enumerate()
+        .map(|(index, message)| {
+            openai_message(message, index, &mut legacy_calls)
+                .map_err(|e| GroqError::InvalidMessage(format!("Invalid OpenAI message {}: {}", index, e)))
+        })
+        .collect()
+}
+
+fn openai_message(
+    message: &Value,
+    index: usize,
+    legacy_calls: &mut Vec<(String, String)>,
+) -> Result<ChatMessage, String> {
+    let role = message["role"].as_str().ok_or("missing `role`")?;
+    let role = parse_role(role).ok_or_else(|| format!("unknown role `{}`", role))?;
+    let content = openai_content(&message["content"])?;
+
+    let mut tool_calls = Vec::new();
+    if let Some(calls) = message["tool_calls"].as_array() {
+        for call in calls {
+            let function = &call["function"];
+            tool_calls.push(ToolCall {
+                id: call["id"].as_str().ok_or("tool call without `id`")?.to_string(),
+                type_: call["type"].as_str().unwrap_or("function").to_string(),
+                function: FunctionCall {
+                    name: function["name"].as_str().ok_or("tool call without a function name")?.to_string(),
+                    arguments: arguments(&function["arguments"]),
+                },
+            });
+        }
+    }
+    if let Some(name) = message["function_call"]["name"].as_str() {
+        let id = format!("call_{}", index);
+        legacy_calls.push((name.to_string(), id.clone()));
+        tool_calls.push(ToolCall {
+            id,
+            type_: "function".to_string(),
+            function: FunctionCall {
+                name: name.to_string(),
+                arguments: arguments(&message["function_call"]["arguments"]),
+            },
+        });
+    }
+
+    let mut tool_call_id = message["tool_call_id"].as_str().map(str::to_string);
+    if message["role"] == "function" && tool_call_id.is_none() {
+        let name = message["name"].as_str().ok_or("function message without `name`")?;
+        let position = legacy_calls
+            .iter()
+            .position(|(call, _)| call == name)
+            .ok_or_else(|| format!("no earlier call of function `{}`", name))?;
+        tool_call_id = Some(legacy_calls.remove(position).1);
+    }
+
+    Ok(ChatMessage {
+        role,
+        content,
+        tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+        tool_call_id,
+    })
+}
+
+fn openai_content(content: &Value) -> Result<MessageContent, String> {
+    let parts = match content {
+        Value::Null => return Ok(MessageContent::text("")),
+        Value::String(text) => return Ok(MessageContent::text(text.as_str())),
+        Value::Array(parts) => parts,
+        _ => return Err("`content` must be a string, an array of parts or null".to_string()),
+    };
+    let mut converted = Vec::with_capacity(parts.len());
+    for part in parts {
+        match part["type"].as_str() {
+            Some("text") | Some("input_text") => {
+                let text = part["text"].as_str().ok_or("text part without `text`")?;
+                converted.push(MessagePart::Text { text: text.to_string() });
+            }
+            Some("image_url") => {
+                let image_url = match &part["image_url"] {
+                    Value::String(url) => ImageUrl::new(url.as_str()),
+                    image => {
+                        let url = image["url"].as_str().ok_or("image part without `url`")?;
+                        let mut image_url = ImageUrl::new(url);
+                        image_url.detail = image["detail"].as_str().map(str::to_string);
+                        image_url
+                    }
+                };
+                converted.push(MessagePart::ImageUrl { image_url });
+            }
+            Some(other) => return Err(format!("unsupported content part `{}`", other)),
+            None => return Err("content part without `type`".to_string()),
+        }
+    }
+    // A single text part is the same as plain text
+    match converted.as_slice() {
+        [MessagePart::Text { text }] => Ok(MessageContent::text(text.as_str())),
+        _ => Ok(MessageContent::Parts(converted)),
+    }
+}
+
+/// Returns tool call arguments as a JSON string; some datasets store them as objects
+fn arguments(arguments: &Value) -> String {
+    match arguments {
+        Value::String(arguments) => arguments.clone(),
+        Value::Null => "{}".to_string(),
+        arguments => arguments.to_string(),
+    }
+}
+
+fn parse_role(role: &str) -> Option<Role> {
+    match role {
+        "system" | "developer" => Some(Role::System),
+        "user" => Some(Role::User),
+        "assistant" => Some(Role::Assistant),
+        "tool" | "function" => Some(Role::Tool),
+        _ => None,
+    }
+}
+
+/// Parses ChatML text into messages
+///
+/// Each message starts with `<|im_start|>` followed by the role (and an
+/// optional `name=...`) on the same line, and ends with `<|im_end|>`. A final
+/// empty turn left open as a generation prompt, such as
+/// `<|im_start|>assistant\n`, is skipped.
+///
+/// `<tool_call>{"name": ..., "arguments": ...}</tool_call>` blocks in assistant
+/// messages become tool calls with ids `call_0`, `call_1`, ..., and `tool`
+/// messages answer them in order, with any `<tool_response>` tags removed.
+///
+/// # Arguments
+///
+/// * `text` - The ChatML text
+///
+/// # Errors
+///
+/// Returns `GroqError::InvalidMessage` naming the offending message if there
+/// is text outside messages, a role is unknown, a message other than the last
+/// is not closed, or a tool call is not valid JSON.
+pub fn from_chatml(text: &str) -> Result<Vec<ChatMessage>, GroqError> {
+    let mut messages = Vec::new();
+    let mut pending_calls: Vec<String> = Vec::new();
+    let mut next_call = 0;
+    let mut rest = text;
+    while !rest.trim().is_empty() {
+        let index = messages.len();
+        let invalid = |reason: String| GroqError::InvalidMessage(format!("Invalid ChatML message {}: {}", index, reason));
+        let start = rest.find(IM_START).ok_or_else(|| invalid("text outside of a message".to_string()))?;
+        if !rest[..start].trim().is_empty() {
+            return Err(invalid("text outside of a message".to_string()));
+        }
+        let body = &rest[start + IM_START.len()..];
+        let (header, body) = body.split_once('\n').unwrap_or((body, ""));
+        let role_name = header.split_whitespace().next().unwrap_or("");
+        let role = parse_role(role_name).ok_or_else(|| invalid(format!("unknown role `{}`", role_name)))?;
+        let next = body.find(IM_START);
+        let (content, after) = match body.find(IM_END) {
+            Some(end) if next.is_none_or(|next| end < next) => (&body[..end], &body[end + IM_END.len()..]),
+            _ if next.is_some() => return Err(invalid("missing <|im_end|>".to_string())),
+            _ if body.trim().is_empty() => break,
+            _ => (body, ""),
+        };
+        rest = after;
+
+        let mut message = ChatMessage::new_text(role.clone(), "");
+        match role {
+            Role::Assistant => {
+                let (text, calls) = chatml_tool_calls(content).map_err(invalid)?;
+                let calls: Vec<ToolCall> = calls
+                    .into_iter()
+                    .map(|function| {
+                        let id = format!("call_{}", next_call);
+                        next_call += 1;
+                        pending_calls.push(id.clone());
+                        ToolCall {
+                            id,
+                            type_: "function".to_string(),
+                            function,
+                        }
+                    })
+                    .collect();
+                message.content = MessageContent::text(text);
+                message.tool_calls = (!calls.is_empty()).then_some(calls);
+            }
+            Role::Tool => {
+                let content = content.trim();
+                let content = content
+                    .strip_prefix("<tool_response>")
+                    .and_then(|c| c.strip_suffix("</tool_response>"))
+                    .unwrap_or(content);
+                message.content = MessageContent::text(content.trim());
+                message.tool_call_id = (!pending_calls.is_empty()).then(|| pending_calls.remove(0));
+            }
+            _ => message.content = MessageContent::text(content.trim_end_matches('\n')),
+        }
+        messages.push(message);
+    }
+    Ok(messages)
+}
+
+/// Splits `<tool_call>` blocks out of an assistant message
+fn chatml_tool_calls(content: &str) -> Result<(String, Vec<FunctionCall>), String> {
+    let mut text = String::new();
+    let mut calls = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("<tool_call>") {
+        text.push_str(&rest[..start]);
+        let body = &rest[start + "<tool_call>".len()..];
+        let end = body.find("</tool_call>").ok_or("unclosed <tool_call>")?;
+        let call: Value =
+            serde_json::from_str(body[..end].trim()).map_err(|e| format!("invalid tool call: {}", e))?;
+        calls.push(FunctionCall {
+            name: call["name"].as_str().ok_or("tool call without `name`")?.to_string(),
+            arguments: arguments(&call["arguments"]),
+        });
+        rest = &body[end + "</tool_call>".len()..];
+    }
+    text.push_str(rest);
+    Ok((text.trim().to_string(), calls))
+}
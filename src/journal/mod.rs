@@ -0,0 +1,486 @@
+//! Persistent request journal for audit and compliance
+//!
+//! 用于审计与合规的持久化请求日志模块
+//!
+//! A [`Journal`] set with
+//! [`GroqClientBuilder::journal`](crate::GroqClientBuilder::journal) records
+//! every request the client sends, with its response or error, the request ID
+//! and the latency, to a [`JournalSink`]. Streams are recorded once they end,
+//! with the streamed text assembled into one response. Bodies pass through
+//! the journal's redaction before they reach the sink, so personal data can
+//! be kept out of the audit trail: named fields are always maskable, and a
+//! [`Redactor`](crate::redact::Redactor) can scrub free text (feature
+//! `redact`).
+//!
+//! Sinks:
+//!
+//! - [`FileJournal`]: JSON lines in a file, rotated by size with a cap on the
+//!   number of rotated files.
+//! - [`SqliteJournal`](sqlite::SqliteJournal): rows in a SQLite table, capped
+//!   by row count (feature `sqlite`).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::journal::{FileJournal, Journal};
+//! use groqai::GroqClientBuilder;
+//!
+//! # groqai::doctest::scratch_dir();
+//! let sink = FileJournal::open("logs/groq.jsonl")?
+//!     .max_file_bytes(64 * 1024 * 1024)
+//!     .max_files(10);
+//! let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+//!     .journal(Journal::new(sink).redact_field("user"))
+//!     .build()?;
+//! # Ok::<(), groqai::GroqError>(())
+//! ```
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+use url::Url;
+
+use crate::endpoint::Endpoint;
+use crate::error::GroqError;
+use crate::metrics::TransportMetrics;
+#[cfg(feature = "redact")]
+use crate::redact::Redactor;
+use crate::sse::SseParser;
+use crate::transport::{ByteStream, Request, RequestOptions, Response, Transport};
+
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+/// Value that replaces redacted fields
+const REDACTED: &str = "[REDACTED]";
+
+/// One request and its outcome
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// When the request started, in milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    /// Groq request ID (`x_groq.id`), or the `x-request-id` header sent
+    pub request_id: Option<String>,
+    /// HTTP method
+    pub method: String,
+    /// Path relative to the base URL, e.g. `chat/completions`
+    pub endpoint: String,
+    /// Request body, if it has one and bodies are recorded
+    pub request: Option<Value>,
+    /// Response body, if the request succeeded and bodies are recorded
+    pub response: Option<Value>,
+    /// Error message, if the request failed
+    pub error: Option<String>,
+    /// Time until the response was read, or until a stream ended
+    pub duration_ms: u64,
+}
+
+/// Destination of journal entries
+///
+/// Writes are synchronous so that an entry is stored before the call that
+/// produced it returns. On a multi-threaded Tokio runtime they run inside
+/// `block_in_place`, so a slow disk does not stall other tasks on the same
+/// worker. Failed writes are logged and do not fail the request.
+pub trait JournalSink: Send + Sync {
+    /// Stores one entry
+    fn record(&self, entry: &JournalEntry) -> Result<(), GroqError>;
+}
+
+/// Journal configuration: a sink plus redaction rules
+#[derive(Clone)]
+pub struct Journal {
+    sink: Arc<dyn JournalSink>,
+    #[cfg(feature = "redact")]
+    redactor: Option<Redactor>,
+    redact_fields: Vec<String>,
+    record_bodies: bool,
+}
+
+impl Journal {
+    /// Creates a journal writing unredacted entries to `sink`
+    pub fn new(sink: impl JournalSink + 'static) -> Self {
+        Self {
+            sink: Arc::new(sink),
+            #[cfg(feature = "redact")]
+            redactor: None,
+            redact_fields: Vec::new(),
+            record_bodies: true,
+        }
+    }
+
+    /// Masks personal data in every string of the bodies and error messages
+    #[cfg(feature = "redact")]
+    pub fn redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+
+    /// Replaces the value of every body field named `name` with `[REDACTED]`
+    pub fn redact_field(mut self, name: impl Into<String>) -> Self {
+        self.redact_fields.push(name.into());
+        self
+    }
+
+    /// Sets whether request and response bodies are recorded (default true)
+    ///
+    /// Without bodies, entries keep only metadata: endpoint, request ID,
+    /// latency and error.
+    pub fn record_bodies(mut self, record: bool) -> Self {
+        self.record_bodies = record;
+        self
+    }
+
+    /// Redacts `entry` and hands it to the sink
+    fn write(&self, mut entry: JournalEntry) {
+        if self.record_bodies {
+            for body in [&mut entry.request, &mut entry.response].into_iter().flatten() {
+                self.redact(body);
+            }
+        } else {
+            entry.request = None;
+            entry.response = None;
+        }
+        #[cfg(feature = "redact")]
+        if let (Some(redactor), Some(error)) = (&self.redactor, &mut entry.error) {
+            *error = redactor.redact(error).redacted;
+        }
+        if let Err(e) = block_in_place(|| self.sink.record(&entry)) {
+            warn!("Journal write failed: {}", e);
+        }
+    }
+
+    fn redact(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, field) in map.iter_mut() {
+                    if self.redact_fields.contains(key) {
+                        *field = Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact(field);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact(item)),
+            #[cfg(feature = "redact")]
+            Value::String(text) => {
+                if let Some(redactor) = &self.redactor {
+                    *text = redactor.redact(text).redacted;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl std::fmt::Debug for Journal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("Journal");
+        #[cfg(feature = "redact")]
+        debug.field("redactor", &self.redactor.is_some());
+        debug
+            .field("redact_fields", &self.redact_fields)
+            .field("record_bodies", &self.record_bodies)
+            .finish()
+    }
+}
+
+/// [`JournalSink`] appending JSON lines to a file
+///
+/// When a write would grow the file past
+/// [`max_file_bytes`](FileJournal::max_file_bytes), the file is renamed to
+/// `<path>.1`, older rotations shift to `<path>.2` and so on, and files beyond
+/// [`max_files`](FileJournal::max_files) are deleted.
+#[derive(Debug)]
+pub struct FileJournal {
+    path: PathBuf,
+    max_file_bytes: Option<u64>,
+    max_files: usize,
+    file: Mutex<(File, u64)>,
+}
+
+impl FileJournal {
+    /// Opens `path` for appending, creating it and its directory if needed
+    ///
+    /// Files grow without limit until `max_file_bytes` is set.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, GroqError> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let file = open_append(&path)?;
+        let size = file.metadata().map_err(io_error)?.len();
+        Ok(Self {
+            path,
+            max_file_bytes: None,
+            max_files: 5,
+            file: Mutex::new((file, size)),
+        })
+    }
+
+    /// Rotates the file before it would grow past `bytes`
+    pub fn max_file_bytes(mut self, bytes: u64) -> Self {
+        self.max_file_bytes = Some(bytes);
+        self
+    }
+
+    /// Sets how many rotated files are kept (default 5)
+    pub fn max_files(mut self, files: usize) -> Self {
+        self.max_files = files;
+        self
+    }
+
+    /// Returns the path of rotated file number `index`
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&self) -> Result<File, GroqError> {
+        let remove = |path: PathBuf| match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_error(e)),
+            _ => Ok(()),
+        };
+        if self.max_files == 0 {
+            remove(self.path.clone())?;
+        } else {
+            remove(self.rotated_path(self.max_files))?;
+            for index in (1..self.max_files).rev() {
+                let from = self.rotated_path(index);
+                if from.exists() {
+                    std::fs::rename(&from, self.rotated_path(index + 1)).map_err(io_error)?;
+                }
+            }
+            std::fs::rename(&self.path, self.rotated_path(1)).map_err(io_error)?;
+        }
+        open_append(&self.path)
+    }
+}
+
+impl JournalSink for FileJournal {
+    fn record(&self, entry: &JournalEntry) -> Result<(), GroqError> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        let mut state = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        let (file, size) = &mut *state;
+        if let Some(max) = self.max_file_bytes {
+            if *size > 0 && *size + line.len() as u64 > max {
+                *file = self.rotate()?;
+                *size = 0;
+            }
+        }
+        file.write_all(&line).map_err(io_error)?;
+        file.flush().map_err(io_error)?;
+        *size += line.len() as u64;
+        Ok(())
+    }
+}
+
+/// Runs blocking I/O without holding up other tasks on a multi-threaded runtime
+///
+/// `block_in_place` panics on a current-thread runtime, where `f` runs directly.
+fn block_in_place<T>(f: impl FnOnce() -> T) -> T {
+    use tokio::runtime::{Handle, RuntimeFlavor};
+
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => tokio::task::block_in_place(f),
+        _ => f(),
+    }
+}
+
+fn open_append(path: &Path) -> Result<File, GroqError> {
+    OpenOptions::new().create(true).append(true).open(path).map_err(io_error)
+}
+
+fn io_error(e: std::io::Error) -> GroqError {
+    GroqError::InvalidMessage(format!("Journal I/O error: {}", e))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Returns the Groq request ID of a response body
+fn response_request_id(response: &Value) -> Option<String> {
+    response
+        .get("x_groq")
+        .and_then(|x| x.get("id"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Returns the `x-request-id` header of a request, if one was set
+fn header_request_id(options: &RequestOptions) -> Option<String> {
+    options
+        .headers()
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("x-request-id"))
+        .map(|(_, value)| value.clone())
+}
+
+/// A [`Transport`] that records every call in a [`Journal`]
+pub(crate) struct JournalTransport {
+    inner: Arc<dyn Transport>,
+    journal: Arc<Journal>,
+}
+
+impl JournalTransport {
+    pub(crate) fn new(inner: Arc<dyn Transport>, journal: Journal) -> Self {
+        Self {
+            inner,
+            journal: Arc::new(journal),
+        }
+    }
+
+    /// Returns the entry for `request`, before its outcome is known
+    fn entry(request: &Request) -> JournalEntry {
+        JournalEntry {
+            timestamp_ms: now_ms(),
+            request_id: header_request_id(&request.options),
+            method: request.method.to_string(),
+            endpoint: request.endpoint.path(),
+            request: request.body_json(),
+            response: None,
+            error: None,
+            duration_ms: 0,
+        }
+    }
+}
+
+/// A streamed response in progress; written to the journal when dropped
+///
+/// Chat streams are recorded with the content they delivered. The bytes of
+/// other streams, such as synthesized speech, are only counted.
+struct StreamRecord {
+    journal: Arc<Journal>,
+    entry: JournalEntry,
+    started: Instant,
+    bytes: usize,
+    chat: Option<ChatSummary>,
+}
+
+/// What a chat stream delivered so far
+#[derive(Default)]
+struct ChatSummary {
+    parser: SseParser,
+    id: Option<String>,
+    content: String,
+    chunks: usize,
+    finish_reason: Option<String>,
+}
+
+impl StreamRecord {
+    fn observe(&mut self, item: &Result<Bytes, GroqError>) {
+        let bytes = match item {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                self.entry.error = Some(e.to_string());
+                return;
+            }
+        };
+        self.bytes += bytes.len();
+        let Some(chat) = &mut self.chat else {
+            return;
+        };
+        for chunk in chat.parser.push(bytes.clone()) {
+            match chunk {
+                Ok(chunk) => {
+                    chat.chunks += 1;
+                    chat.id.get_or_insert_with(|| chunk.id.clone());
+                    for choice in &chunk.choices {
+                        if let Some(content) = &choice.delta.content {
+                            chat.content.push_str(&content.to_string());
+                        }
+                        if choice.finish_reason.is_some() {
+                            chat.finish_reason = choice.finish_reason.clone();
+                        }
+                    }
+                }
+                Err(e) => self.entry.error = Some(e.to_string()),
+            }
+        }
+    }
+}
+
+impl Drop for StreamRecord {
+    fn drop(&mut self) {
+        let mut entry = self.entry.clone();
+        entry.duration_ms = self.started.elapsed().as_millis() as u64;
+        entry.response = match &mut self.chat {
+            Some(chat) if entry.error.is_none() || chat.chunks > 0 => Some(serde_json::json!({
+                "id": chat.id,
+                "object": "chat.completion.stream",
+                "chunks": chat.chunks,
+                "content": std::mem::take(&mut chat.content),
+                "finish_reason": chat.finish_reason,
+            })),
+            None if entry.error.is_none() || self.bytes > 0 => {
+                Some(serde_json::json!({"object": "binary", "bytes": self.bytes}))
+            }
+            _ => None,
+        };
+        self.journal.write(entry);
+    }
+}
+
+#[async_trait]
+impl Transport for JournalTransport {
+    async fn execute(&self, request: Request) -> Result<Response, GroqError> {
+        let mut entry = Self::entry(&request);
+        let started = Instant::now();
+        let result = self.inner.execute(request).await;
+        entry.duration_ms = started.elapsed().as_millis() as u64;
+        match &result {
+            Ok(response) => {
+                entry.request_id = response_request_id(&response.body).or(entry.request_id);
+                entry.response = Some(response.body.clone());
+            }
+            Err(e) => entry.error = Some(e.to_string()),
+        }
+        self.journal.write(entry);
+        result
+    }
+
+    async fn execute_stream(&self, request: Request) -> Result<ByteStream, GroqError> {
+        let mut record = StreamRecord {
+            journal: self.journal.clone(),
+            entry: Self::entry(&request),
+            started: Instant::now(),
+            bytes: 0,
+            chat: (request.endpoint == Endpoint::ChatCompletions).then(ChatSummary::default),
+        };
+        let stream = match self.inner.execute_stream(request).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                record.entry.error = Some(e.to_string());
+                return Err(e);
+            }
+        };
+        Ok(Box::pin(stream.map(move |item| {
+            // Capture the whole record so it is written when the stream drops
+            let record = &mut record;
+            record.observe(&item);
+            item
+        })))
+    }
+
+    fn base_url(&self) -> &Url {
+        self.inner.base_url()
+    }
+
+    fn metrics(&self) -> Option<&TransportMetrics> {
+        self.inner.metrics()
+    }
+}
@@ -0,0 +1,151 @@
+//! SQLite request journal sink
+//!
+//! 基于 SQLite 的请求日志存储
+//!
+//! Entries are rows of a `groqai_journal` table with one column per
+//! [`JournalEntry`] field; bodies are stored as JSON text. A row cap keeps
+//! the database from growing without limit by deleting the oldest rows in
+//! batches.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::journal::sqlite::SqliteJournal;
+//! use groqai::journal::Journal;
+//! use groqai::GroqClientBuilder;
+//!
+//! # groqai::doctest::scratch_dir();
+//! let sink = SqliteJournal::open("groq-journal.db")?.max_rows(1_000_000);
+//! let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+//!     .journal(Journal::new(sink))
+//!     .build()?;
+//! # Ok::<(), groqai::GroqError>(())
+//! ```
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use rusqlite::{params, Connection};
+
+use super::{JournalEntry, JournalSink};
+use crate::error::GroqError;
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS groqai_journal (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp_ms INTEGER NOT NULL,
+    request_id TEXT,
+    method TEXT NOT NULL,
+    endpoint TEXT NOT NULL,
+    request TEXT,
+    response TEXT,
+    error TEXT,
+    duration_ms INTEGER NOT NULL
+)";
+
+fn sqlite_error(e: rusqlite::Error) -> GroqError {
+    GroqError::InvalidMessage(format!("SQLite error: {}", e))
+}
+
+/// [`JournalSink`] storing entries in a SQLite database
+#[derive(Debug)]
+pub struct SqliteJournal {
+    /// The connection and the number of rows inserted since the last prune
+    conn: Mutex<(Connection, u64)>,
+    max_rows: Option<u64>,
+}
+
+impl SqliteJournal {
+    /// Opens or creates the database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, GroqError> {
+        Self::from_connection(Connection::open(path).map_err(sqlite_error)?)
+    }
+
+    /// Creates a journal in memory, e.g. for tests
+    pub fn open_in_memory() -> Result<Self, GroqError> {
+        Self::from_connection(Connection::open_in_memory().map_err(sqlite_error)?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self, GroqError> {
+        conn.execute(SCHEMA, []).map_err(sqlite_error)?;
+        Ok(Self {
+            conn: Mutex::new((conn, 0)),
+            max_rows: None,
+        })
+    }
+
+    /// Keeps at most `rows` entries, deleting the oldest
+    ///
+    /// The oldest rows are deleted once every `rows / 100` inserts rather
+    /// than after each one, so the table can briefly hold up to 1% more
+    /// rows than the cap.
+    pub fn max_rows(mut self, rows: u64) -> Self {
+        self.max_rows = Some(rows);
+        self
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, (Connection, u64)> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns all stored entries, oldest first
+    pub fn entries(&self) -> Result<Vec<JournalEntry>, GroqError> {
+        let state = self.conn();
+        let mut statement = state
+            .0
+            .prepare(
+                "SELECT timestamp_ms, request_id, method, endpoint, request, response, error, duration_ms
+                 FROM groqai_journal ORDER BY id",
+            )
+            .map_err(sqlite_error)?;
+        let rows = statement
+            .query_map([], |row| {
+                let json = |text: Option<String>| text.and_then(|t| serde_json::from_str(&t).ok());
+                Ok(JournalEntry {
+                    timestamp_ms: row.get::<_, i64>(0)? as u64,
+                    request_id: row.get(1)?,
+                    method: row.get(2)?,
+                    endpoint: row.get(3)?,
+                    request: json(row.get(4)?),
+                    response: json(row.get(5)?),
+                    error: row.get(6)?,
+                    duration_ms: row.get::<_, i64>(7)? as u64,
+                })
+            })
+            .map_err(sqlite_error)?;
+        rows.collect::<Result<_, _>>().map_err(sqlite_error)
+    }
+}
+
+impl JournalSink for SqliteJournal {
+    fn record(&self, entry: &JournalEntry) -> Result<(), GroqError> {
+        let json = |value: &Option<serde_json::Value>| value.as_ref().map(ToString::to_string);
+        let mut state = self.conn();
+        let (conn, pending) = &mut *state;
+        conn.execute(
+            "INSERT INTO groqai_journal
+             (timestamp_ms, request_id, method, endpoint, request, response, error, duration_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                entry.timestamp_ms as i64,
+                entry.request_id,
+                entry.method,
+                entry.endpoint,
+                json(&entry.request),
+                json(&entry.response),
+                entry.error,
+                entry.duration_ms as i64,
+            ],
+        )
+        .map_err(sqlite_error)?;
+        *pending += 1;
+        if let Some(max_rows) = self.max_rows.filter(|max_rows| *pending > max_rows / 100) {
+            conn.execute(
+                "DELETE FROM groqai_journal WHERE id <= (SELECT MAX(id) FROM groqai_journal) - ?1",
+                params![max_rows as i64],
+            )
+            .map_err(sqlite_error)?;
+            *pending = 0;
+        }
+        Ok(())
+    }
+}
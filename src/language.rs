@@ -0,0 +1,301 @@
+//! Response language enforcement
+//!
+//! 回复语言约束模块
+//!
+//! [`ChatRequestBuilder::force_language`](crate::api::chat::ChatRequestBuilder::force_language)
+//! adds a system instruction telling the model which language to answer in.
+//! The instruction is added when the request is sent, so it survives later
+//! changes to the messages. With a [`LanguageEnforcer`] that validates, each
+//! reply's language is checked with [`detect_language`], a lightweight
+//! stopword and script detector, and a reply in the wrong language is retried
+//! with a stronger instruction.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::language::LanguageEnforcer;
+//! use groqai::{ChatMessage, GroqClient, Role};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # groqai::doctest::set_api_key();
+//! let client = GroqClient::new()?;
+//! # let client = groqai::doctest::client();
+//! let response = client
+//!     .chat("llama-3.3-70b-versatile")
+//!     .message(ChatMessage::new_text(Role::User, "What is the capital of France?"))
+//!     .language_enforcer(LanguageEnforcer::new("de").validate(true))
+//!     .send()
+//!     .await?;
+//! println!("{}", response.choices[0].message.content);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::chat::ChatCompletionRequest;
+use crate::client::{ChatSendOptions, GroqClient};
+use crate::error::GroqError;
+use crate::reasoning::ReasoningStripper;
+use crate::types::{ChatCompletionResponse, ChatMessage, MessageContent, Role};
+
+/// ISO 639-1 codes and English names of languages named in instructions
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("ar", "Arabic"),
+    ("bg", "Bulgarian"),
+    ("de", "German"),
+    ("el", "Greek"),
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fa", "Persian"),
+    ("fr", "French"),
+    ("he", "Hebrew"),
+    ("hi", "Hindi"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("mk", "Macedonian"),
+    ("mr", "Marathi"),
+    ("ne", "Nepali"),
+    ("nl", "Dutch"),
+    ("pl", "Polish"),
+    ("pt", "Portuguese"),
+    ("ru", "Russian"),
+    ("sr", "Serbian"),
+    ("sv", "Swedish"),
+    ("th", "Thai"),
+    ("tr", "Turkish"),
+    ("uk", "Ukrainian"),
+    ("ur", "Urdu"),
+    ("zh", "Chinese"),
+];
+
+/// Languages written in a script that [`detect_language`] reports under another code
+const SHARED_SCRIPTS: &[(&str, &[&str])] = &[
+    ("ru", &["be", "bg", "kk", "ky", "mk", "mn", "sr", "tg", "uk"]),
+    ("ar", &["fa", "ku", "ps", "sd", "ug", "ur"]),
+    ("hi", &["mr", "ne", "sa"]),
+];
+
+/// Frequent short words of the Latin-script languages the detector knows
+const STOPWORDS: &[(&str, &[&str])] = &[
+    ("en", &["the", "and", "is", "of", "to", "in", "that", "it", "with", "for", "are", "was", "this", "you", "not"]),
+    ("de", &["der", "die", "das", "und", "ist", "nicht", "ein", "eine", "zu", "mit", "den", "von", "sie", "ich", "auch"]),
+    ("fr", &["le", "la", "les", "et", "est", "des", "une", "un", "du", "que", "pas", "pour", "dans", "qui", "sur"]),
+    ("es", &["el", "los", "las", "y", "es", "del", "una", "que", "por", "para", "con", "no", "se", "su", "como"]),
+    ("it", &["il", "lo", "gli", "e", "di", "che", "una", "per", "non", "sono", "della", "con", "del", "si", "anche"]),
+    ("pt", &["o", "os", "as", "e", "do", "da", "que", "uma", "um", "não", "para", "com", "em", "se", "por"]),
+    ("nl", &["de", "het", "een", "en", "is", "van", "niet", "dat", "op", "te", "zijn", "met", "voor", "ik", "ook"]),
+];
+
+/// Returns the language of `text` as an ISO 639-1 code, or `None` if unsure
+///
+/// Non-Latin scripts are recognized by script (Chinese, Japanese, Korean,
+/// Russian for Cyrillic, Arabic, Greek, Hebrew, Hindi for Devanagari, Thai),
+/// so Ukrainian is reported as Russian and Persian as Arabic.
+/// Latin-script text is told apart by stopwords for English, German, French,
+/// Spanish, Italian, Portuguese and Dutch; it needs a few words to decide.
+///
+/// # Examples
+///
+/// ```rust
+/// use groqai::language::detect_language;
+///
+/// assert_eq!(detect_language("Die Hauptstadt von Frankreich ist Paris."), Some("de"));
+/// assert_eq!(detect_language("东京是日本的首都。"), Some("zh"));
+/// assert_eq!(detect_language("Paris"), None);
+/// ```
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let mut latin = 0usize;
+    let mut kana = 0usize;
+    let mut scripts: Vec<(&'static str, usize)> = Vec::new();
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        let script = match c as u32 {
+            0x3040..=0x30FF => {
+                kana += 1;
+                "ja"
+            }
+            0x4E00..=0x9FFF | 0x3400..=0x4DBF => "zh",
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => "ko",
+            0x0400..=0x04FF => "ru",
+            0x0600..=0x06FF => "ar",
+            0x0370..=0x03FF => "el",
+            0x0590..=0x05FF => "he",
+            0x0900..=0x097F => "hi",
+            0x0E00..=0x0E7F => "th",
+            _ => {
+                latin += 1;
+                continue;
+            }
+        };
+        match scripts.iter_mut().find(|(s, _)| *s == script) {
+            Some((_, count)) => *count += 1,
+            None => scripts.push((script, 1)),
+        }
+    }
+    let non_latin: usize = scripts.iter().map(|(_, count)| count).sum();
+    if non_latin > latin {
+        // Japanese mixes kanji with kana; any kana decides it
+        if kana > 0 {
+            return Some("ja");
+        }
+        return scripts.iter().max_by_key(|(_, count)| *count).map(|(script, _)| *script);
+    }
+
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let mut scores: Vec<(&'static str, usize)> = STOPWORDS
+        .iter()
+        .map(|(language, stopwords)| {
+            let hits = words.iter().filter(|w| stopwords.contains(&w.as_str())).count();
+            (*language, hits)
+        })
+        .collect();
+    scores.sort_by_key(|(_, hits)| std::cmp::Reverse(*hits));
+    let (best, hits) = scores[0];
+    let runner_up = scores[1].1;
+    (hits >= 2 && hits > runner_up).then_some(best)
+}
+
+/// Normalizes a language tag such as `de-DE` to its lowercase primary subtag
+fn primary_subtag(language: &str) -> String {
+    language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Returns the code [`detect_language`] reports for text in `language`
+fn detected_code(language: &str) -> &str {
+    SHARED_SCRIPTS
+        .iter()
+        .find(|(_, languages)| languages.contains(&language))
+        .map_or(language, |(code, _)| code)
+}
+
+/// Keeps replies in one language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageEnforcer {
+    language: String,
+    validate: bool,
+    max_attempts: u32,
+}
+
+impl LanguageEnforcer {
+    /// Creates an enforcer for `language`, an ISO 639-1 code or tag like `pt-BR`
+    ///
+    /// Only the system instruction is added; replies are not checked unless
+    /// [`validate`](LanguageEnforcer::validate) is enabled.
+    pub fn new(language: impl Into<String>) -> Self {
+        Self {
+            language: language.into(),
+            validate: false,
+            max_attempts: 3,
+        }
+    }
+
+    /// Checks each reply's language and retries replies in another language
+    ///
+    /// Replies whose language cannot be detected, and languages the detector
+    /// does not know, are accepted. Applies to [`send`](crate::api::chat::ChatRequestBuilder::send)
+    /// only; streams get the instruction but are not checked.
+    pub fn validate(mut self, enable: bool) -> Self {
+        self.validate = enable;
+        self
+    }
+
+    /// Sets how many replies may be requested before giving up (default 3)
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Returns the language as given
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    fn language_name(&self) -> String {
+        let code = primary_subtag(&self.language);
+        match LANGUAGE_NAMES.iter().find(|(c, _)| *c == code) {
+            Some((_, name)) => format!("{} ({})", name, self.language),
+            None => self.language.clone(),
+        }
+    }
+
+    /// Returns the system instruction; `strict` is the wording used for retries
+    pub fn instruction(&self, strict: bool) -> String {
+        let name = self.language_name();
+        if strict {
+            format!(
+                "Your previous reply was not in {name}. Respond only in {name}. Do not use any \
+                 other language, even if the user writes in another language or asks you to."
+            )
+        } else {
+            format!("Always respond in {name}, regardless of the language of the user's messages.")
+        }
+    }
+
+    /// Returns true unless `text` was detected to be in another language
+    ///
+    /// Reasoning blocks are ignored. Languages sharing a script the detector
+    /// cannot tell apart, such as Ukrainian and Russian, only need the right
+    /// script.
+    pub fn matches(&self, text: &str) -> bool {
+        let answer = ReasoningStripper::new().split(text).answer;
+        match detect_language(&answer) {
+            Some(detected) => detected == detected_code(&primary_subtag(&self.language)),
+            None => true,
+        }
+    }
+
+    /// Adds the instruction to the leading system message, or inserts one
+    pub(crate) fn apply(&self, request: &mut ChatCompletionRequest, strict: bool) {
+        let instruction = self.instruction(strict);
+        match request.messages.first_mut() {
+            Some(first) if first.role == Role::System => match &mut first.content {
+                MessageContent::Text(text) => {
+                    text.push_str("\n\n");
+                    text.push_str(&instruction);
+                }
+                _ => request.messages.insert(0, ChatMessage::new_text(Role::System, instruction)),
+            },
+            _ => request.messages.insert(0, ChatMessage::new_text(Role::System, instruction)),
+        }
+    }
+
+    /// Sends `request` with the instruction, retrying replies in another language
+    pub(crate) async fn send(
+        &self,
+        client: &GroqClient,
+        request: ChatCompletionRequest,
+        options: ChatSendOptions,
+    ) -> Result<ChatCompletionResponse, GroqError> {
+        let attempts = if self.validate { self.max_attempts } else { 1 };
+        let mut detected = None;
+        for attempt in 0..attempts {
+            let mut attempt_request = request.clone();
+            self.apply(&mut attempt_request, attempt > 0);
+            let response = client.chat_completions_with(attempt_request, options.clone()).await?;
+            let text = response
+                .choices
+                .first()
+                .map(|c| c.message.content.to_string())
+                .unwrap_or_default();
+            if !self.validate || self.matches(&text) {
+                return Ok(response);
+            }
+            detected = detect_language(&ReasoningStripper::new().split(&text).answer);
+        }
+        Err(GroqError::InvalidMessage(format!(
+            "Reply was in {} instead of {} after {} attempts",
+            detected.unwrap_or("another language"),
+            self.language,
+            attempts
+        )))
+    }
+}
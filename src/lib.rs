@@ -26,7 +26,7 @@
 //! export GROQ_API_KEY="gsk_your_api_key_here"
 //! ```
 //! 
-//! ```rust,no_run
+//! ```rust
 //! // Option 1: Import specific types
 //! use groqai::{GroqClient, ChatMessage, Role};
 //! 
@@ -36,7 +36,9 @@
 //! #[tokio::main]
 //! async fn main() -> Result<(), Box<dyn std::error::Error>> {
 //!     // Creates client from GROQ_API_KEY environment variable
+//! #     groqai::doctest::set_api_key();
 //!     let client = GroqClient::new()?;
+//! #     let client = groqai::doctest::client();
 //!     
 //!     let messages = vec![
 //!         ChatMessage::new_text(Role::User, "Hello, how are you?")
@@ -55,12 +57,13 @@
 //! 
 //! ### Using API Key Directly
 //! 
-//! ```rust,no_run
+//! ```rust
 //! use groqai::prelude::*;  // Convenient import
 //! 
 //! #[tokio::main]
 //! async fn main() -> Result<(), Box<dyn std::error::Error>> {
 //!     let client = GroqClient::with_api_key("gsk_your_api_key")?;
+//! #     let client = groqai::doctest::client();
 //!     
 //!     let messages = vec![
 //!         ChatMessage::new_text(Role::User, "Hello!")
@@ -96,11 +99,61 @@
 //! You can obtain one from the [Groq Console](https://console.groq.com/).
 
 pub mod api;
+pub mod build_info;
+pub mod cache;
+pub mod classify;
 pub mod client;
+pub mod consistency;
+pub mod content_filter;
+pub mod continuation;
+pub mod conversation;
+pub mod dedupe;
+pub mod defaults;
+pub mod endpoint;
+pub mod diarize;
+#[cfg(feature = "doctest")]
+#[doc(hidden)]
+pub mod doctest;
+pub mod document;
 pub mod error;
+pub mod extract;
+pub mod fixtures;
+pub mod import;
+pub mod journal;
+pub mod language;
+#[cfg(feature = "streaming")]
+pub mod markdown;
+pub mod metrics;
+pub mod mock;
+#[cfg(any(feature = "audio", feature = "files"))]
+mod multipart;
+#[cfg(feature = "streaming")]
+pub mod partial_json;
+#[cfg(feature = "cert-pinning")]
+pub mod pinning;
+pub mod pretty;
+pub mod pricing;
+pub mod prompts;
+pub mod queue;
+pub mod similarity;
 pub mod types;
 pub mod rate_limit;
+pub mod schema;
+pub mod shutdown;
+pub mod signing;
+pub mod sse;
+pub mod reasoning;
+#[cfg(feature = "redact")]
+pub mod redact;
+#[cfg(feature = "redis")]
+pub mod redis;
 pub mod transport;
+pub mod tools;
+#[cfg(feature = "streaming")]
+pub mod stream;
+pub mod text;
+pub mod transcript;
+pub mod usage;
 
 #[cfg(test)]
 mod tests {
@@ -138,7 +191,11 @@ mod tests {
 
 // Core Client (Most Important - Users need these first)
 pub use client::{GroqClient, GroqClientBuilder};
-pub use error::GroqError;
+pub use build_info::{BuildInfo, VERSION};
+pub use endpoint::Endpoint;
+pub use error::{ErrorBody, GroqError};
+pub use transport::{CompatMode, RequestOptions, RetryPolicy, UploadProgress};
+pub use queue::{Priority, RequestQueue};
 
 // Essential Types (Common usage)
 pub use types::{
@@ -147,41 +204,63 @@ pub use types::{
     // Model types
     KnownModel,
     // Response types
-    ChatCompletionResponse, Choice, Usage,
-    ChatCompletionChunk, ChoiceChunk, MessageDelta,
+    ChatCompletionResponse, Choice, Usage, LogProbs, TokenLogProb, TopLogProb,
+    ChatCompletionChunk, ChoiceChunk, MessageDelta, ToolCallDelta, FunctionCallDelta,
 };
 
 // Request Builders (Fluent API)
 pub use api::chat::ChatRequestBuilder;
-pub use api::audio::AudioRequestBuilder;
+#[cfg(feature = "audio")]
+pub use api::audio::{AudioRequestBuilder, TranscriptionBuilder, TranslationBuilder};
+#[cfg(feature = "files")]
 pub use api::files::FileRequestBuilder;
+#[cfg(feature = "batches")]
 pub use api::batches::BatchRequestBuilder;
 pub use api::models::ModelsRequestBuilder;
+#[cfg(feature = "fine-tuning")]
 pub use api::fine_tunings::FineTuningRequestBuilder;
+pub use api::vision::{ImageSource, VisionRequestBuilder};
+pub use api::poll::PollOptions;
+pub use conversation::{Conversation, SharedConversation};
 
 // Request Types (For advanced usage)
 pub use api::chat::ChatCompletionRequest;
-pub use api::audio::{AudioTranscriptionRequest, AudioTranslationRequest};
+#[cfg(feature = "audio")]
+pub use api::audio::{AudioFormat, AudioResponseFormat, AudioSpeechRequest, AudioTranscriptionRequest, AudioTranslationRequest, TranscriptionOutput, Voice};
+#[cfg(feature = "files")]
 pub use api::files::FileCreateRequest;
-pub use api::batches::BatchCreateRequest;
+#[cfg(feature = "batches")]
+pub use api::batches::{
+    BatchCreateRequest, BatchEndpoint, BatchInputBuilder, BatchProgress, BatchProgressStream, BatchSubmission,
+    CompletionWindow,
+};
+#[cfg(feature = "fine-tuning")]
 pub use api::fine_tunings::FineTuningCreateRequest;
 
 // Response Types (For advanced usage)
 pub use types::{
     // Audio responses
-    Transcription, Translation,
+    Transcription, TranscriptionSegment, TranscriptionWord, Translation,
     // File responses
-    WorkFile, WorkFileList, WorkFileDeletion,
+    FilePurpose, WorkFile, WorkFileList, WorkFileDeletion,
     // Model responses
     Model, ModelList,
     // Batch responses
-    Batch, BatchList, RequestCounts,
+    Batch, BatchErrorDetail, BatchErrors, BatchList, RequestCounts,
     // Advanced types
     Tool, ToolCall, FunctionCall, FunctionDef,
     ResponseFormat, ToolChoice, ServiceTier, StopSequence,
     StreamOptions, CompoundCustom, SearchSettings,
 };
 
+// Stream adapters
+#[cfg(feature = "streaming")]
+pub use stream::{ChatEvent, ChatStreamExt, Coalesce, StreamRecorder};
+pub use sse::SseParser;
+
+// Tool call validation
+pub use tools::{ToolGuard, ToolRejection};
+
 // ============================================================================
 // Convenience Re-exports for Common Patterns
 // ============================================================================
@@ -0,0 +1,243 @@
+//! Markdown structure tracking for streamed replies
+//!
+//! 流式回复的 Markdown 结构跟踪模块
+//!
+//! Deltas split markdown at arbitrary points, so a UI that re-renders the
+//! reply after every chunk briefly shows half-open code fences and list items
+//! missing their end. [`MarkdownTracker`] follows the structure across deltas
+//! and reports when a code block starts or ends, and when a heading or list
+//! item line is complete. [`ChatStreamExt::markdown`](crate::stream::ChatStreamExt::markdown)
+//! turns a chat stream into these events.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::markdown::{MarkdownEvent, MarkdownTracker};
+//!
+//! let mut tracker = MarkdownTracker::new();
+//! let mut events = tracker.push("Here:\n``");
+//! events.extend(tracker.push("`rust\nfn main() {}\n`"));
+//! assert!(tracker.in_code_block());
+//! assert_eq!(tracker.snapshot(), "Here:\n```rust\nfn main() {}\n`\n```");
+//! events.extend(tracker.push("``\n"));
+//! events.extend(tracker.finish());
+//!
+//! let structure: Vec<_> = events
+//!     .into_iter()
+//!     .filter(|e| !matches!(e, MarkdownEvent::Text(_)))
+//!     .collect();
+//! assert_eq!(
+//!     structure,
+//!     [
+//!         MarkdownEvent::CodeBlockStart { language: Some("rust".to_string()) },
+//!         MarkdownEvent::CodeBlockEnd {
+//!             language: Some("rust".to_string()),
+//!             code: "fn main() {}\n".to_string(),
+//!         },
+//!     ]
+//! );
+//! ```
+
+use std::pin::Pin;
+
+use futures::Stream;
+
+use crate::error::GroqError;
+
+/// A structural event in streamed markdown
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MarkdownEvent {
+    /// Text as received, split so that it never spans a structural event
+    Text(String),
+    /// The opening fence line of a code block is complete
+    CodeBlockStart {
+        /// First word of the fence's info string, such as `rust`
+        language: Option<String>,
+    },
+    /// A code block was closed, or the stream ended inside it
+    CodeBlockEnd {
+        /// First word of the opening fence's info string
+        language: Option<String>,
+        /// Contents of the block without its fences
+        code: String,
+    },
+    /// A heading line is complete
+    Heading {
+        /// Number of `#` characters, from 1 to 6
+        level: u8,
+        /// Heading text without the markers
+        text: String,
+    },
+    /// A list item line is complete
+    ListItem {
+        /// Whether the item is numbered
+        ordered: bool,
+        /// Number of leading spaces, which indicates nesting
+        indent: usize,
+        /// Item text without the marker
+        text: String,
+    },
+}
+
+/// A boxed stream of markdown events
+pub type MarkdownEventStream = Pin<Box<dyn Stream<Item = Result<MarkdownEvent, GroqError>> + Send>>;
+
+/// An open fenced code block
+#[derive(Debug, Clone)]
+struct Fence {
+    marker: char,
+    len: usize,
+    language: Option<String>,
+    code: String,
+}
+
+/// Follows markdown structure across streamed deltas
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownTracker {
+    text: String,
+    line: String,
+    fence: Option<Fence>,
+}
+
+impl MarkdownTracker {
+    /// Creates a tracker for a new reply
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a delta and returns the events it completes, in order
+    pub fn push(&mut self, delta: &str) -> Vec<MarkdownEvent> {
+        let mut events = Vec::new();
+        for segment in delta.split_inclusive('\n') {
+            self.text.push_str(segment);
+            events.push(MarkdownEvent::Text(segment.to_string()));
+            match segment.strip_suffix('\n') {
+                Some(rest) => {
+                    self.line.push_str(rest);
+                    let line = std::mem::take(&mut self.line);
+                    events.extend(self.complete_line(&line));
+                }
+                None => self.line.push_str(segment),
+            }
+        }
+        events
+    }
+
+    /// Completes the last line and closes a code block left open
+    pub fn finish(&mut self) -> Vec<MarkdownEvent> {
+        let line = std::mem::take(&mut self.line);
+        let mut events = if line.is_empty() { Vec::new() } else { self.complete_line(&line) };
+        if let Some(fence) = self.fence.take() {
+            events.push(MarkdownEvent::CodeBlockEnd {
+                language: fence.language,
+                code: fence.code,
+            });
+        }
+        events
+    }
+
+    /// Returns true while a code block is open
+    pub fn in_code_block(&self) -> bool {
+        self.fence.is_some()
+    }
+
+    /// Returns all text received so far
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the text so far with an open code block closed, safe to render
+    pub fn snapshot(&self) -> String {
+        let mut snapshot = self.text.clone();
+        if let Some(fence) = &self.fence {
+            if !snapshot.ends_with('\n') {
+                snapshot.push('\n');
+            }
+            snapshot.extend(std::iter::repeat_n(fence.marker, fence.len));
+        }
+        snapshot
+    }
+
+    fn complete_line(&mut self, line: &str) -> Vec<MarkdownEvent> {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let indent = line.len() - line.trim_start_matches(' ').len();
+        let trimmed = &line[indent..];
+
+        if let Some(fence) = &mut self.fence {
+            let closes = indent <= 3 && fence_len(trimmed, fence.marker) >= fence.len && {
+                let rest = trimmed.trim_start_matches(fence.marker);
+                rest.trim().is_empty()
+            };
+            if !closes {
+                fence.code.push_str(line);
+                fence.code.push('\n');
+                return Vec::new();
+            }
+            let fence = self.fence.take().expect("fence is open");
+            return vec![MarkdownEvent::CodeBlockEnd {
+                language: fence.language,
+                code: fence.code,
+            }];
+        }
+        if indent > 3 {
+            return Vec::new();
+        }
+
+        for marker in ['`', '~'] {
+            let len = fence_len(trimmed, marker);
+            if len < 3 {
+                continue;
+            }
+            let info = trimmed[len..].trim();
+            // A backtick fence's info string may not contain backticks
+            if marker == '`' && info.contains('`') {
+                return Vec::new();
+            }
+            let language = info.split_whitespace().next().map(str::to_string);
+            self.fence = Some(Fence {
+                marker,
+                len,
+                language: language.clone(),
+                code: String::new(),
+            });
+            return vec![MarkdownEvent::CodeBlockStart { language }];
+        }
+
+        let hashes = trimmed.len() - trimmed.trim_start_matches('#').len();
+        let after = &trimmed[hashes..];
+        if (1..=6).contains(&hashes) && (after.is_empty() || after.starts_with([' ', '\t'])) {
+            return vec![MarkdownEvent::Heading {
+                level: hashes as u8,
+                text: after.trim().trim_end_matches('#').trim_end().to_string(),
+            }];
+        }
+
+        list_item(trimmed)
+            .map(|(ordered, text)| MarkdownEvent::ListItem {
+                ordered,
+                indent,
+                text: text.to_string(),
+            })
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Returns the number of leading `marker` characters
+fn fence_len(line: &str, marker: char) -> usize {
+    line.chars().take_while(|&c| c == marker).count()
+}
+
+/// Parses a bullet or numbered list item into (ordered, text)
+fn list_item(line: &str) -> Option<(bool, &str)> {
+    if let Some(text) = line.strip_prefix(['-', '*', '+']) {
+        // `---` and `***` are thematic breaks, not items
+        return text.starts_with(' ').then(|| (false, text.trim()));
+    }
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if !(1..=9).contains(&digits) {
+        return None;
+    }
+    let text = line[digits..].strip_prefix(['.', ')'])?;
+    text.starts_with(' ').then(|| (true, text.trim()))
+}
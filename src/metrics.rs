@@ -0,0 +1,380 @@
+//! Transport-level request metrics
+//!
+//! 传输层请求指标模块
+//!
+//! [`HttpTransport`](crate::transport::HttpTransport) records the request and
+//! response body size, duration and outcome of every call, plus retries, per
+//! endpoint. Read them with
+//! [`GroqClient::metrics_snapshot`](crate::GroqClient::metrics_snapshot), e.g.
+//! for capacity planning of a proxy service. With the `metrics` feature the
+//! same values are also reported through the [`metrics`](https://docs.rs/metrics)
+//! facade, to whatever recorder the application installed.
+//!
+//! [`MetricsSnapshot::to_prometheus`] renders a snapshot in the Prometheus
+//! text exposition format, for services that serve a `/metrics` endpoint
+//! without installing a recorder.
+//!
+//! Endpoints are labelled by [`Endpoint::label`](crate::Endpoint::label), the
+//! path with IDs replaced by `{id}`, so `files/file_01abc` and
+//! `files/file_02def` share the label `files/{id}`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::GroqClient;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # groqai::doctest::set_api_key();
+//! let client = GroqClient::new()?;
+//! # let client = groqai::doctest::client();
+//! client.models().list().await?;
+//!
+//! for (endpoint, stats) in &client.metrics_snapshot().endpoints {
+//!     println!(
+//!         "{}: {} requests, p95 {:?} ms",
+//!         endpoint,
+//!         stats.requests,
+//!         stats.duration_ms.quantile(0.95)
+//!     );
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::endpoint::Endpoint;
+
+/// Upper bounds of the size buckets, in bytes
+const BYTE_BOUNDS: &[f64] = &[
+    256.0,
+    1024.0,
+    4096.0,
+    16384.0,
+    65536.0,
+    262_144.0,
+    1_048_576.0,
+    4_194_304.0,
+    16_777_216.0,
+    67_108_864.0,
+];
+
+/// Upper bounds of the duration buckets, in milliseconds
+const DURATION_BOUNDS_MS: &[f64] = &[
+    5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10_000.0, 30_000.0, 60_000.0,
+];
+
+/// A fixed-bucket histogram
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    /// `(upper bound, count)` per bucket; the last bound is `f64::INFINITY`
+    pub buckets: Vec<(f64, u64)>,
+    /// Number of observations
+    pub count: u64,
+    /// Sum of all observations
+    pub sum: f64,
+    /// Largest observation, or 0.0 if there are none
+    pub max: f64,
+}
+
+impl Histogram {
+    fn with_bounds(bounds: &[f64]) -> Self {
+        Self {
+            buckets: bounds
+                .iter()
+                .copied()
+                .chain(std::iter::once(f64::INFINITY))
+                .map(|b| (b, 0))
+                .collect(),
+            count: 0,
+            sum: 0.0,
+            max: 0.0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        if let Some(bucket) = self.buckets.iter_mut().find(|(bound, _)| value <= *bound) {
+            bucket.1 += 1;
+        }
+        self.count += 1;
+        self.sum += value;
+        self.max = self.max.max(value);
+    }
+
+    /// Returns the mean observation, or `None` if there are none
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+
+    /// Returns the upper bound of the bucket holding the `q` quantile
+    ///
+    /// `q` is clamped to 0.0..=1.0. Observations in the overflow bucket are
+    /// reported as [`max`](Histogram::max). Returns `None` if there are no
+    /// observations.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = (q.clamp(0.0, 1.0) * self.count as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (bound, count) in &self.buckets {
+            seen += count;
+            if seen >= rank {
+                return Some(if bound.is_finite() { bound.min(self.max) } else { self.max });
+            }
+        }
+        Some(self.max)
+    }
+}
+
+/// Metrics of one endpoint
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointMetrics {
+    /// Number of calls, including failed ones
+    pub requests: u64,
+    /// Calls that failed with a transport or API error
+    pub errors: u64,
+    /// Retries after a failed attempt
+    pub retries: u64,
+    /// Prompt tokens reported in chat completion responses
+    pub prompt_tokens: u64,
+    /// Completion tokens reported in chat completion responses
+    pub completion_tokens: u64,
+    /// Serialized request body sizes, for requests whose size is known
+    pub request_bytes: Histogram,
+    /// Response body sizes; streams are measured when they end
+    pub response_bytes: Histogram,
+    /// Call durations in milliseconds, until the full body was read
+    pub duration_ms: Histogram,
+}
+
+impl Default for EndpointMetrics {
+    fn default() -> Self {
+        Self {
+            requests: 0,
+            errors: 0,
+            retries: 0,
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            request_bytes: Histogram::with_bounds(BYTE_BOUNDS),
+            response_bytes: Histogram::with_bounds(BYTE_BOUNDS),
+            duration_ms: Histogram::with_bounds(DURATION_BOUNDS_MS),
+        }
+    }
+}
+
+/// Point-in-time copy of the recorded metrics
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    /// Metrics per endpoint label, sorted by label
+    pub endpoints: BTreeMap<String, EndpointMetrics>,
+}
+
+/// A Prometheus counter: name, help text and value
+type CounterSeries = (&'static str, &'static str, fn(&EndpointMetrics) -> u64);
+
+/// A Prometheus histogram: name, help text, unit divisor and histogram
+type HistogramSeries = (&'static str, &'static str, f64, fn(&EndpointMetrics) -> &Histogram);
+
+const PROMETHEUS_COUNTERS: &[CounterSeries] = &[
+    ("groqai_requests_total", "Requests sent to the Groq API.", |m| m.requests),
+    ("groqai_request_errors_total", "Requests that failed with a transport or API error.", |m| m.errors),
+    ("groqai_retries_total", "Retries after a failed attempt.", |m| m.retries),
+    ("groqai_prompt_tokens_total", "Prompt tokens used by chat completions.", |m| m.prompt_tokens),
+    ("groqai_completion_tokens_total", "Completion tokens used by chat completions.", |m| m.completion_tokens),
+];
+
+const PROMETHEUS_HISTOGRAMS: &[HistogramSeries] = &[
+    (
+        "groqai_request_duration_seconds",
+        "Request duration until the full body was read.",
+        1000.0,
+        |m| &m.duration_ms,
+    ),
+    ("groqai_request_bytes", "Serialized request body size.", 1.0, |m| &m.request_bytes),
+    ("groqai_response_bytes", "Response body size.", 1.0, |m| &m.response_bytes),
+];
+
+impl MetricsSnapshot {
+    /// Renders the snapshot in the Prometheus text exposition format
+    ///
+    /// Every series carries an `endpoint` label. Durations are exported in
+    /// seconds and sizes in bytes, as `groqai_requests_total`,
+    /// `groqai_request_errors_total`, `groqai_retries_total`,
+    /// `groqai_prompt_tokens_total`, `groqai_completion_tokens_total`,
+    /// `groqai_request_duration_seconds`, `groqai_request_bytes` and
+    /// `groqai_response_bytes`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use groqai::metrics::MetricsSnapshot;
+    ///
+    /// let text = MetricsSnapshot::default().to_prometheus();
+    /// assert!(text.contains("# TYPE groqai_requests_total counter"));
+    /// ```
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        for &(name, help, value) in PROMETHEUS_COUNTERS {
+            write_header(&mut out, name, help, "counter");
+            for (endpoint, metrics) in &self.endpoints {
+                out.push_str(&format!("{}{{endpoint=\"{}\"}} {}\n", name, escape_label(endpoint), value(metrics)));
+            }
+        }
+        for &(name, help, divisor, histogram) in PROMETHEUS_HISTOGRAMS {
+            write_header(&mut out, name, help, "histogram");
+            for (endpoint, metrics) in &self.endpoints {
+                write_histogram(&mut out, name, &escape_label(endpoint), histogram(metrics), divisor);
+            }
+        }
+        out
+    }
+}
+
+fn write_header(out: &mut String, name: &str, help: &str, kind: &str) {
+    out.push_str(&format!("# HELP {} {}\n# TYPE {} {}\n", name, help, name, kind));
+}
+
+/// Writes cumulative buckets, with bounds and sum divided by `divisor`
+fn write_histogram(out: &mut String, name: &str, endpoint: &str, histogram: &Histogram, divisor: f64) {
+    let mut cumulative = 0;
+    for (bound, count) in &histogram.buckets {
+        cumulative += count;
+        let le = if bound.is_finite() { (bound / divisor).to_string() } else { "+Inf".to_string() };
+        out.push_str(&format!(
+            "{}_bucket{{endpoint=\"{}\",le=\"{}\"}} {}\n",
+            name, endpoint, le, cumulative
+        ));
+    }
+    out.push_str(&format!("{}_sum{{endpoint=\"{}\"}} {}\n", name, endpoint, histogram.sum / divisor));
+    out.push_str(&format!("{}_count{{endpoint=\"{}\"}} {}\n", name, endpoint, histogram.count));
+}
+
+/// Escapes a label value for the text exposition format
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// One finished call
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RequestSample {
+    pub request_bytes: Option<usize>,
+    pub response_bytes: Option<usize>,
+    pub duration: Duration,
+    pub ok: bool,
+}
+
+/// Records transport metrics; clones share the same data
+#[derive(Debug, Clone, Default)]
+pub struct TransportMetrics {
+    endpoints: Arc<Mutex<BTreeMap<String, EndpointMetrics>>>,
+}
+
+impl TransportMetrics {
+    /// Creates an empty recorder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn endpoints(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, EndpointMetrics>> {
+        self.endpoints.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Records one finished call to `path`
+    pub(crate) fn record(&self, path: &str, sample: RequestSample) {
+        let endpoint = endpoint_label(path);
+        #[cfg(feature = "metrics")]
+        report(&endpoint, &sample);
+        let mut endpoints = self.endpoints();
+        let stats = endpoints.entry(endpoint).or_default();
+        stats.requests += 1;
+        if !sample.ok {
+            stats.errors += 1;
+        }
+        if let Some(bytes) = sample.request_bytes {
+            stats.request_bytes.observe(bytes as f64);
+        }
+        if let Some(bytes) = sample.response_bytes {
+            stats.response_bytes.observe(bytes as f64);
+        }
+        stats.duration_ms.observe(sample.duration.as_secs_f64() * 1000.0);
+    }
+
+    /// Records a retry of a call to `path`
+    pub(crate) fn record_retry(&self, path: &str) {
+        let endpoint = endpoint_label(path);
+        #[cfg(feature = "metrics")]
+        ::metrics::counter!("groqai_retries_total", "endpoint" => endpoint.clone()).increment(1);
+        self.endpoints().entry(endpoint).or_default().retries += 1;
+    }
+
+    /// Records the token usage of a chat completion from `path`
+    pub(crate) fn record_tokens(&self, path: &str, prompt_tokens: u32, completion_tokens: u32) {
+        let endpoint = endpoint_label(path);
+        #[cfg(feature = "metrics")]
+        {
+            ::metrics::counter!("groqai_prompt_tokens_total", "endpoint" => endpoint.clone())
+                .increment(u64::from(prompt_tokens));
+            ::metrics::counter!("groqai_completion_tokens_total", "endpoint" => endpoint.clone())
+                .increment(u64::from(completion_tokens));
+        }
+        let mut endpoints = self.endpoints();
+        let stats = endpoints.entry(endpoint).or_default();
+        stats.prompt_tokens += u64::from(prompt_tokens);
+        stats.completion_tokens += u64::from(completion_tokens);
+    }
+
+    /// Returns a copy of the metrics recorded so far
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            endpoints: self.endpoints().clone(),
+        }
+    }
+
+    /// Clears all recorded metrics
+    pub fn reset(&self) {
+        self.endpoints().clear();
+    }
+}
+
+/// Reports a call through the `metrics` facade
+#[cfg(feature = "metrics")]
+fn report(endpoint: &str, sample: &RequestSample) {
+    let endpoint = endpoint.to_string();
+    ::metrics::counter!("groqai_requests_total", "endpoint" => endpoint.clone()).increment(1);
+    if !sample.ok {
+        ::metrics::counter!("groqai_request_errors_total", "endpoint" => endpoint.clone()).increment(1);
+    }
+    if let Some(bytes) = sample.request_bytes {
+        ::metrics::histogram!("groqai_request_bytes", "endpoint" => endpoint.clone()).record(bytes as f64);
+    }
+    if let Some(bytes) = sample.response_bytes {
+        ::metrics::histogram!("groqai_response_bytes", "endpoint" => endpoint.clone()).record(bytes as f64);
+    }
+    ::metrics::histogram!("groqai_request_duration_seconds", "endpoint" => endpoint)
+        .record(sample.duration.as_secs_f64());
+}
+
+/// Returns the endpoint label of a path relative to the base URL
+pub(crate) fn endpoint_label(path: &str) -> String {
+    if let Some(endpoint) = Endpoint::from_path(path) {
+        return endpoint.label().to_string();
+    }
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    path.trim_matches('/')
+        .split('/')
+        .enumerate()
+        .map(|(i, segment)| {
+            // IDs contain digits; fixed path segments do not
+            if i > 0 && segment.bytes().any(|b| b.is_ascii_digit()) {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -0,0 +1,264 @@
+//! In-memory transport for tests and incident replay
+//!
+//! 用于测试和故障回放的内存传输层
+//!
+//! [`MockTransport`] implements [`Transport`] without touching the network.
+//! Responses are queued up front and returned in order. Streams recorded with
+//! [`StreamRecorder`](crate::stream::StreamRecorder) can be replayed with their
+//! original (or scaled) timing via [`MockTransport::stream_from_ndjson`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use futures::StreamExt;
+//! use groqai::mock::MockTransport;
+//! use groqai::{ChatMessage, GroqClientBuilder, Role};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # groqai::doctest::scratch_dir();
+//! # groqai::doctest::ndjson_file("incident-1234.ndjson");
+//! let mock = MockTransport::stream_from_ndjson("incident-1234.ndjson")?.time_scale(0.0);
+//! let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+//!     .transport(mock)
+//!     .build()?;
+//!
+//! let mut stream = client
+//!     .chat("llama-3.1-8b-instant")
+//!     .message(ChatMessage::new_text(Role::User, "Hello"))
+//!     .stream(true)
+//!     .send_stream()
+//!     .await?;
+//! while let Some(chunk) = stream.next().await {
+//!     println!("{:?}", chunk?);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+#[cfg(feature = "streaming")]
+use std::io::{BufRead, BufReader};
+#[cfg(feature = "streaming")]
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use serde_json::Value;
+use url::Url;
+
+use crate::endpoint::Endpoint;
+use crate::error::GroqError;
+use crate::sse::encode_chunk;
+#[cfg(feature = "streaming")]
+use crate::stream::RecordedChunk;
+use crate::transport::{ByteStream, Method, Request, Response, Transport};
+use crate::types::{ChatCompletionChunk, ChatCompletionResponse};
+
+/// A queued stream: each chunk with its offset from the start of the stream
+type ScriptedStream = Vec<(Duration, ChatCompletionChunk)>;
+
+/// A request observed by [`MockTransport`]
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    /// HTTP method of the request
+    pub method: Method,
+    /// Path the request was sent to, relative to the base URL
+    pub path: String,
+    /// Request body, the query parameters as an object, or `Value::Null` for
+    /// requests with neither
+    pub body: Value,
+    /// Per-request headers set through [`RequestOptions`](crate::transport::RequestOptions)
+    pub headers: Vec<(String, String)>,
+}
+
+/// A [`Transport`] returning queued responses instead of calling the API
+///
+/// Clones share their queues and observed requests, so a test can keep a
+/// clone to inspect [`requests`](MockTransport::requests) after handing the
+/// transport to a client.
+#[derive(Clone)]
+pub struct MockTransport {
+    base_url: Url,
+    time_scale: f64,
+    state: Arc<MockState>,
+}
+
+#[derive(Default)]
+struct MockState {
+    chat_responses: Mutex<VecDeque<Result<ChatCompletionResponse, GroqError>>>,
+    json_responses: Mutex<VecDeque<Result<Value, GroqError>>>,
+    streams: Mutex<VecDeque<ScriptedStream>>,
+    binary_streams: Mutex<VecDeque<Vec<Bytes>>>,
+    requests: Mutex<Vec<MockRequest>>,
+}
+
+impl Default for MockTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockTransport {
+    /// Creates a mock transport with no queued responses
+    pub fn new() -> Self {
+        Self {
+            base_url: Url::parse("https://api.groq.com/openai/v1/").expect("valid default url"),
+            time_scale: 1.0,
+            state: Arc::new(MockState::default()),
+        }
+    }
+
+    /// Creates a mock transport replaying a stream recorded as NDJSON
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if the file cannot be read or a
+    /// line is not a [`RecordedChunk`].
+    #[cfg(feature = "streaming")]
+    pub fn stream_from_ndjson(path: impl AsRef<Path>) -> Result<Self, GroqError> {
+        Self::new().with_ndjson_stream(path)
+    }
+
+    /// Scales replay timing: `1.0` keeps the original pacing, `0.0` replays instantly
+    ///
+    /// Negative scales are treated as `0.0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is NaN or infinite.
+    pub fn time_scale(mut self, scale: f64) -> Self {
+        assert!(scale.is_finite(), "time_scale must be finite, got {}", scale);
+        self.time_scale = scale.max(0.0);
+        self
+    }
+
+    /// Queues a response for the next chat completion request
+    pub fn with_chat_response(self, response: Result<ChatCompletionResponse, GroqError>) -> Self {
+        lock(&self.state.chat_responses).push_back(response);
+        self
+    }
+
+    /// Queues a response for the next JSON or multipart request
+    pub fn with_json_response(self, response: Result<Value, GroqError>) -> Self {
+        lock(&self.state.json_responses).push_back(response);
+        self
+    }
+
+    /// Queues a stream that yields `chunks` without delay
+    pub fn with_stream(self, chunks: Vec<ChatCompletionChunk>) -> Self {
+        let scripted = chunks.into_iter().map(|c| (Duration::ZERO, c)).collect();
+        lock(&self.state.streams).push_back(scripted);
+        self
+    }
+
+    /// Queues a binary response, such as synthesized speech, delivered in `chunks`
+    pub fn with_binary_stream(self, chunks: Vec<Vec<u8>>) -> Self {
+        let chunks = chunks.into_iter().map(Bytes::from).collect();
+        lock(&self.state.binary_streams).push_back(chunks);
+        self
+    }
+
+    /// Queues a stream recorded by [`StreamRecorder`](crate::stream::StreamRecorder)
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if the file cannot be read or a
+    /// line is not a [`RecordedChunk`].
+    #[cfg(feature = "streaming")]
+    pub fn with_ndjson_stream(self, path: impl AsRef<Path>) -> Result<Self, GroqError> {
+        let file = std::fs::File::open(path.as_ref())
+            .map_err(|e| GroqError::InvalidMessage(format!("Failed to open file: {}", e)))?;
+        let mut scripted = Vec::new();
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| {
+                GroqError::InvalidMessage(format!("Failed to read line {}: {}", index + 1, e))
+            })?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: RecordedChunk = serde_json::from_str(&line).map_err(|e| {
+                GroqError::InvalidMessage(format!("Invalid NDJSON at line {}: {}", index + 1, e))
+            })?;
+            scripted.push((Duration::from_millis(record.elapsed_ms), record.chunk));
+        }
+        lock(&self.state.streams).push_back(scripted);
+        Ok(self)
+    }
+
+    /// Returns the requests received so far, in order
+    pub fn requests(&self) -> Vec<MockRequest> {
+        lock(&self.state.requests).clone()
+    }
+
+    fn observe(&self, request: &Request) {
+        lock(&self.state.requests).push(MockRequest {
+            method: request.method.clone(),
+            path: request.endpoint.path(),
+            body: request.body_json().unwrap_or(Value::Null),
+            headers: request.options.headers().to_vec(),
+        });
+    }
+
+    /// Replays the next queued chat stream as server-sent events
+    fn next_stream(&self) -> Result<ByteStream, GroqError> {
+        let scripted = lock(&self.state.streams)
+            .pop_front()
+            .ok_or_else(|| exhausted("stream"))?;
+        let scale = self.time_scale;
+        let stream = futures::stream::unfold(
+            (scripted.into_iter(), Duration::ZERO),
+            move |(mut chunks, previous)| async move {
+                let (offset, chunk) = chunks.next()?;
+                let delay = offset.saturating_sub(previous).mul_f64(scale);
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+                Some((encode_chunk(&chunk), (chunks, offset)))
+            },
+        );
+        Ok(Box::pin(stream))
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn exhausted(kind: &str) -> GroqError {
+    GroqError::InvalidMessage(format!("MockTransport: no {} queued", kind))
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn execute(&self, request: Request) -> Result<Response, GroqError> {
+        self.observe(&request);
+        if request.endpoint == Endpoint::ChatCompletions {
+            let response = lock(&self.state.chat_responses)
+                .pop_front()
+                .unwrap_or_else(|| Err(exhausted("chat response")))?;
+            return Ok(Response::new(serde_json::to_value(response)?));
+        }
+        lock(&self.state.json_responses)
+            .pop_front()
+            .unwrap_or_else(|| Err(exhausted("JSON response")))
+            .map(Response::new)
+    }
+
+    async fn execute_stream(&self, request: Request) -> Result<ByteStream, GroqError> {
+        self.observe(&request);
+        if request.endpoint == Endpoint::ChatCompletions {
+            return self.next_stream();
+        }
+        let chunks = lock(&self.state.binary_streams)
+            .pop_front()
+            .ok_or_else(|| exhausted("binary stream"))?;
+        Ok(Box::pin(futures::stream::iter(chunks.into_iter().map(Ok))))
+    }
+
+    fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+}
@@ -0,0 +1,199 @@
+//! Multipart forms for audio and file uploads
+//!
+//! 音频与文件上传的 multipart 表单构建
+//!
+//! Only compiled with the `audio` or `files` feature, which also enable
+//! reqwest's multipart support.
+
+use std::path::Path;
+
+use bytes::Bytes;
+use futures::{Stream, TryStreamExt};
+use reqwest::multipart::{Form, Part};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+
+use crate::error::GroqError;
+use crate::transport::{RequestOptions, UploadProgress};
+
+/// Size of the reads that stream an uploaded file from disk
+const UPLOAD_READ_SIZE: usize = 64 * 1024;
+
+/// Builds a file part that streams from disk instead of buffering the file
+///
+/// Memory use does not grow with the file size: at most one read of
+/// `UPLOAD_READ_SIZE` bytes is held at a time.
+async fn file_part(path: &Path, options: &RequestOptions) -> Result<Part, GroqError> {
+    let file_error = |e: std::io::Error| GroqError::InvalidMessage(format!("File error: {}", e));
+    let mut file = tokio::fs::File::open(path).await.map_err(file_error)?;
+    let length = file.metadata().await.map_err(file_error)?.len();
+    let content_type = match options.file_mime_type() {
+        Some(mime) => mime.to_string(),
+        None => match content_type_for(path) {
+            Some(mime) => mime,
+            None => sniff_content_type(&mut file).await.map_err(file_error)?,
+        }
+        .to_string(),
+    };
+    let reads = ReaderStream::with_capacity(file, UPLOAD_READ_SIZE);
+    upload_part(reads, length, &content_type, path, options)
+}
+
+/// Builds a file part sending `content` from memory
+///
+/// The content is sent in pieces of `UPLOAD_READ_SIZE` bytes, so upload
+/// progress is reported as for files read from disk.
+fn bytes_part(content: &Bytes, name: &Path, options: &RequestOptions) -> Result<Part, GroqError> {
+    let content_type = options
+        .file_mime_type()
+        .or_else(|| content_type_for(name))
+        .or_else(|| audio_content_type(content))
+        .unwrap_or("application/octet-stream")
+        .to_string();
+    let length = content.len();
+    let pieces: Vec<Result<Bytes, std::io::Error>> = (0..length)
+        .step_by(UPLOAD_READ_SIZE)
+        .map(|start| Ok(content.slice(start..(start + UPLOAD_READ_SIZE).min(length))))
+        .collect();
+    upload_part(futures::stream::iter(pieces), length as u64, &content_type, name, options)
+}
+
+/// Builds a file part of `length` bytes from `reads`, reporting upload progress
+fn upload_part<S>(
+    reads: S,
+    length: u64,
+    content_type: &str,
+    name: &Path,
+    options: &RequestOptions,
+) -> Result<Part, GroqError>
+where
+    S: Stream<Item = Result<Bytes, std::io::Error>> + Send + Sync + 'static,
+{
+    let body = match options.upload_progress_callback().cloned() {
+        Some(callback) => {
+            let mut sent = 0u64;
+            reqwest::Body::wrap_stream(reads.inspect_ok(move |bytes| {
+                sent += bytes.len() as u64;
+                callback(UploadProgress {
+                    bytes_sent: sent,
+                    total_bytes: length,
+                });
+            }))
+        }
+        None => reqwest::Body::wrap_stream(reads),
+    };
+
+    let mut part = Part::stream_with_length(body, length)
+        .mime_str(content_type)
+        .map_err(|e| GroqError::InvalidMessage(format!("Invalid content type {:?}: {}", content_type, e)))?;
+    if let Some(name) = name.file_name().and_then(|n| n.to_str()) {
+        part = part.file_name(name.to_string());
+    }
+    Ok(part)
+}
+
+/// Builds the form for the fields of `body`, sending `content` as the file if given
+pub(crate) async fn form(
+    body: &serde_json::Value,
+    content: Option<&Bytes>,
+    options: &RequestOptions,
+) -> Result<Form, GroqError> {
+    let mut form = Form::new();
+
+    if let Some(url) = body["url"].as_str() {
+        form = form.part("url", Part::text(url.to_string()));
+    }
+
+    if let Some(file) = body["file"].as_str() {
+        let part = match content {
+            Some(content) => bytes_part(content, Path::new(file), options)?,
+            None => file_part(Path::new(file), options).await?,
+        };
+        form = form.part("file", part);
+    }
+
+    if let Some(purpose) = body["purpose"].as_str() {
+        form = form.part("purpose", Part::text(purpose.to_string()));
+    }
+
+    if let Some(model) = body["model"].as_str() {
+        form = form.part("model", Part::text(model.to_string()));
+    }
+
+    if let Some(language) = body["language"].as_str() {
+        form = form.part("language", Part::text(language.to_string()));
+    }
+
+    if let Some(prompt) = body["prompt"].as_str() {
+        form = form.part("prompt", Part::text(prompt.to_string()));
+    }
+
+    if let Some(response_format) = body["response_format"].as_str() {
+        form = form.part("response_format", Part::text(response_format.to_string()));
+    }
+
+    if let Some(temperature) = body["temperature"].as_f64() {
+        form = form.part("temperature", Part::text(temperature.to_string()));
+    }
+
+    if let Some(granularities) = body["timestamp_granularities"].as_array() {
+        for granularity in granularities.iter().filter_map(|g| g.as_str()) {
+            form = form.part("timestamp_granularities[]", Part::text(granularity.to_string()));
+        }
+    }
+
+    Ok(form)
+}
+
+/// Returns the content type for an uploaded file based on its extension
+fn content_type_for(path: &Path) -> Option<&'static str> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("jsonl") => Some("application/jsonl"),
+        Some("json") => Some("application/json"),
+        Some("mp3") | Some("mpga") | Some("mpeg") => Some("audio/mpeg"),
+        Some("mp4") => Some("audio/mp4"),
+        Some("m4a") => Some("audio/m4a"),
+        Some("wav") => Some("audio/wav"),
+        Some("flac") => Some("audio/flac"),
+        Some("ogg") | Some("opus") => Some("audio/ogg"),
+        Some("webm") => Some("audio/webm"),
+        _ => None,
+    }
+}
+
+/// Detects the content type of an audio file from its first bytes, then rewinds it
+///
+/// Files that are not recognised are sent as `application/octet-stream`.
+async fn sniff_content_type(file: &mut tokio::fs::File) -> std::io::Result<&'static str> {
+    let mut header = [0u8; 12];
+    let mut read = 0;
+    while read < header.len() {
+        match file.read(&mut header[read..]).await? {
+            0 => break,
+            n => read += n,
+        }
+    }
+    file.seek(std::io::SeekFrom::Start(0)).await?;
+    Ok(audio_content_type(&header[..read]).unwrap_or("application/octet-stream"))
+}
+
+/// Returns the audio content type a file starting with `header` has, if it is known
+fn audio_content_type(header: &[u8]) -> Option<&'static str> {
+    match header {
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => Some("audio/wav"),
+        [b'f', b'L', b'a', b'C', ..] => Some("audio/flac"),
+        [b'O', b'g', b'g', b'S', ..] => Some("audio/ogg"),
+        [0x1A, 0x45, 0xDF, 0xA3, ..] => Some("audio/webm"),
+        [_, _, _, _, b'f', b't', b'y', b'p', b'M', b'4', b'A', b' ', ..] => Some("audio/m4a"),
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => Some("audio/mp4"),
+        [b'I', b'D', b'3', ..] => Some("audio/mpeg"),
+        // An MPEG audio frame starts with 11 set sync bits and a layer other than 0, which is AAC
+        [0xFF, second, ..] if second & 0xE0 == 0xE0 && second & 0x06 != 0 => Some("audio/mpeg"),
+        _ => None,
+    }
+}
+
@@ -0,0 +1,465 @@
+//! Incremental JSON parsing for streamed JSON-mode replies
+//!
+//! 流式 JSON 模式回复的增量解析模块
+//!
+//! A JSON-mode reply is only valid JSON once the stream ends, so a UI waiting
+//! for `serde_json` shows nothing until then. [`PartialJsonParser`] parses the
+//! deltas as they arrive and reports when an object key has been read, when a
+//! value is complete and when an array item is complete, each with the
+//! [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901) of its location.
+//! [`snapshot`](PartialJsonParser::snapshot) closes whatever is still open, so
+//! a partially populated struct can be rendered at any point.
+//!
+//! [`ChatStreamExt::json_events`](crate::stream::ChatStreamExt::json_events)
+//! and [`ChatStreamExt::json_partial`](crate::stream::ChatStreamExt::json_partial)
+//! apply the parser to a chat stream.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::partial_json::{JsonEvent, PartialJsonParser};
+//! use serde_json::json;
+//!
+//! let mut parser = PartialJsonParser::new();
+//! let mut events = parser.push(r#"{"title": "Release notes", "items": ["Faster upl"#)?;
+//! assert_eq!(parser.snapshot(), Some(json!({"title": "Release notes", "items": ["Faster upl"]})));
+//! events.extend(parser.push(r#"oads"]}"#)?);
+//! events.extend(parser.finish()?);
+//!
+//! assert_eq!(events[0], JsonEvent::KeyStarted { path: "/title".to_string() });
+//! assert!(events.contains(&JsonEvent::ArrayItemCompleted {
+//!     path: "/items".to_string(),
+//!     index: 0,
+//!     value: json!("Faster uploads"),
+//! }));
+//! assert_eq!(parser.value(), Some(&json!({"title": "Release notes", "items": ["Faster uploads"]})));
+//! # Ok::<(), groqai::GroqError>(())
+//! ```
+
+use std::pin::Pin;
+
+use futures::Stream;
+use serde_json::{Map, Value};
+
+use crate::error::GroqError;
+
+/// A step in parsing streamed JSON
+///
+/// Paths are JSON Pointers: `""` is the whole document and `/items/0/name`
+/// the `name` of the first item.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonEvent {
+    /// An object key was read; its value follows
+    KeyStarted {
+        /// Location of the key's value
+        path: String,
+    },
+    /// A value in an object, or the whole document, is complete
+    ValueCompleted {
+        /// Location of the value
+        path: String,
+        /// The complete value
+        value: Value,
+    },
+    /// An item of an array is complete
+    ArrayItemCompleted {
+        /// Location of the array
+        path: String,
+        /// Position of the item in the array
+        index: usize,
+        /// The complete item
+        value: Value,
+    },
+}
+
+/// A boxed stream of JSON events
+pub type JsonEventStream = Pin<Box<dyn Stream<Item = Result<JsonEvent, GroqError>> + Send>>;
+
+/// What a container expects next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expect {
+    /// A key, or `}` right after `{`
+    KeyOrEnd,
+    Key,
+    Colon,
+    /// A value, or `]` right after `[`
+    ValueOrEnd,
+    Value,
+    CommaOrEnd,
+}
+
+/// An object or array that is still open
+#[derive(Debug, Clone)]
+enum Container {
+    Object { map: Map<String, Value>, key: Option<String> },
+    Array(Vec<Value>),
+}
+
+#[derive(Debug, Clone)]
+struct Frame {
+    /// JSON Pointer of the container
+    path: String,
+    container: Container,
+    expect: Expect,
+}
+
+/// A string or scalar being read
+#[derive(Debug, Clone)]
+enum Token {
+    String {
+        text: String,
+        key: bool,
+        escape: Escape,
+        /// High half of a UTF-16 surrogate pair read from a `\u` escape
+        high_surrogate: Option<u32>,
+    },
+    /// A number, `true`, `false` or `null`
+    Scalar(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Escape {
+    None,
+    Backslash,
+    Unicode(String),
+}
+
+/// Parses JSON fed in arbitrary pieces
+///
+/// Whitespace before and after the document is ignored. After a syntax error
+/// every further call returns the same error.
+#[derive(Debug, Clone, Default)]
+pub struct PartialJsonParser {
+    stack: Vec<Frame>,
+    token: Option<Token>,
+    value: Option<Value>,
+    error: Option<String>,
+    offset: usize,
+}
+
+impl PartialJsonParser {
+    /// Creates a parser for a new document
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a delta and returns the events it completes, in order
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if the text is not valid JSON
+    pub fn push(&mut self, delta: &str) -> Result<Vec<JsonEvent>, GroqError> {
+        if let Some(message) = &self.error {
+            return Err(GroqError::InvalidMessage(message.clone()));
+        }
+        let mut events = Vec::new();
+        for c in delta.chars() {
+            if let Err(message) = self.push_char(c, &mut events) {
+                let message = format!("Invalid JSON at character {}: {}", self.offset, message);
+                self.error = Some(message.clone());
+                return Err(GroqError::InvalidMessage(message));
+            }
+            self.offset += 1;
+        }
+        Ok(events)
+    }
+
+    /// Ends the document, completing a trailing number or literal
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if the text so far is not a
+    /// complete JSON document
+    pub fn finish(&mut self) -> Result<Vec<JsonEvent>, GroqError> {
+        let mut events = Vec::new();
+        if self.error.is_none() && matches!(self.token, Some(Token::Scalar(_))) {
+            if let Err(message) = self.end_scalar(&mut events) {
+                self.error = Some(format!("Invalid JSON at character {}: {}", self.offset, message));
+            }
+        }
+        if let Some(message) = &self.error {
+            return Err(GroqError::InvalidMessage(message.clone()));
+        }
+        if self.value.is_none() {
+            let message = "JSON ended before the document was complete".to_string();
+            self.error = Some(message.clone());
+            return Err(GroqError::InvalidMessage(message));
+        }
+        Ok(events)
+    }
+
+    /// Returns the document once it is complete
+    pub fn value(&self) -> Option<&Value> {
+        self.value.as_ref()
+    }
+
+    /// Returns true once the document is complete
+    pub fn is_complete(&self) -> bool {
+        self.value.is_some()
+    }
+
+    /// Returns the document so far, with open strings, arrays and objects closed
+    ///
+    /// A key without a value and an incomplete `true`, `false` or `null` are
+    /// left out; a number shows the digits received so far. Returns `None`
+    /// before the document has started.
+    pub fn snapshot(&self) -> Option<Value> {
+        if let Some(value) = &self.value {
+            return Some(value.clone());
+        }
+        let mut partial = match &self.token {
+            Some(Token::String { text, key: false, .. }) => Some(Value::String(text.clone())),
+            Some(Token::Scalar(text)) => scalar(text).ok(),
+            _ => None,
+        };
+        for frame in self.stack.iter().rev() {
+            partial = Some(match &frame.container {
+                Container::Object { map, key } => {
+                    let mut map = map.clone();
+                    if let (Some(key), Some(value)) = (key, partial) {
+                        map.insert(key.clone(), value);
+                    }
+                    Value::Object(map)
+                }
+                Container::Array(items) => {
+                    let mut items = items.clone();
+                    items.extend(partial);
+                    Value::Array(items)
+                }
+            });
+        }
+        partial
+    }
+
+    /// Returns the snapshot deserialized into `T`, if it fits
+    ///
+    /// Give `T` optional or defaulted fields so it can be filled in piece by piece.
+    pub fn partial<T: serde::de::DeserializeOwned>(&self) -> Option<T> {
+        serde_json::from_value(self.snapshot()?).ok()
+    }
+
+    fn push_char(&mut self, c: char, events: &mut Vec<JsonEvent>) -> Result<(), String> {
+        match &mut self.token {
+            Some(Token::String {
+                text,
+                key,
+                escape,
+                high_surrogate,
+            }) => {
+                match escape {
+                    Escape::None => match c {
+                        '"' => {
+                            let (text, key) = (std::mem::take(text), *key);
+                            self.token = None;
+                            return self.end_string(text, key, events);
+                        }
+                        '\\' => *escape = Escape::Backslash,
+                        c if (c as u32) < 0x20 => return Err("control character in string".to_string()),
+                        c => text.push(c),
+                    },
+                    Escape::Backslash => {
+                        *escape = Escape::None;
+                        match c {
+                            '"' | '\\' | '/' => text.push(c),
+                            'b' => text.push('\u{8}'),
+                            'f' => text.push('\u{c}'),
+                            'n' => text.push('\n'),
+                            'r' => text.push('\r'),
+                            't' => text.push('\t'),
+                            'u' => *escape = Escape::Unicode(String::new()),
+                            c => return Err(format!("invalid escape \\{}", c)),
+                        }
+                    }
+                    Escape::Unicode(hex) => {
+                        if !c.is_ascii_hexdigit() {
+                            return Err(format!("invalid unicode escape \\u{}{}", hex, c));
+                        }
+                        hex.push(c);
+                        if hex.len() == 4 {
+                            let unit = u32::from_str_radix(hex, 16).expect("four hex digits");
+                            *escape = Escape::None;
+                            match (high_surrogate.take(), unit) {
+                                (None, 0xD800..=0xDBFF) => *high_surrogate = Some(unit),
+                                (Some(high), 0xDC00..=0xDFFF) => {
+                                    let code = 0x10000 + ((high - 0xD800) << 10) + (unit - 0xDC00);
+                                    text.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                                }
+                                (high, unit) => {
+                                    if high.is_some() {
+                                        text.push(char::REPLACEMENT_CHARACTER);
+                                    }
+                                    text.push(char::from_u32(unit).unwrap_or(char::REPLACEMENT_CHARACTER));
+                                }
+                            }
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Some(Token::Scalar(text)) => {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.') {
+                    text.push(c);
+                    return Ok(());
+                }
+                self.end_scalar(events)?;
+                self.structural(c, events)
+            }
+            None => self.structural(c, events),
+        }
+    }
+
+    /// Handles a character outside strings and scalars
+    fn structural(&mut self, c: char, events: &mut Vec<JsonEvent>) -> Result<(), String> {
+        if c.is_whitespace() {
+            return Ok(());
+        }
+        let expect = match self.stack.last() {
+            Some(frame) => frame.expect,
+            None if self.value.is_some() => return Err(format!("unexpected {:?} after the document", c)),
+            None => Expect::Value,
+        };
+        match (expect, c) {
+            (Expect::Value | Expect::ValueOrEnd, '{') => self.open(Container::Object { map: Map::new(), key: None }),
+            (Expect::Value | Expect::ValueOrEnd, '[') => self.open(Container::Array(Vec::new())),
+            (Expect::Value | Expect::ValueOrEnd, '"') => {
+                self.token = Some(Token::String {
+                    text: String::new(),
+                    key: false,
+                    escape: Escape::None,
+                    high_surrogate: None,
+                })
+            }
+            (Expect::Value | Expect::ValueOrEnd, c) if c == '-' || c.is_ascii_digit() || c.is_ascii_lowercase() => {
+                self.token = Some(Token::Scalar(c.to_string()))
+            }
+            (Expect::Key | Expect::KeyOrEnd, '"') => {
+                self.token = Some(Token::String {
+                    text: String::new(),
+                    key: true,
+                    escape: Escape::None,
+                    high_surrogate: None,
+                })
+            }
+            (Expect::Colon, ':') => self.set_expect(Expect::Value),
+            (Expect::CommaOrEnd, ',') => {
+                let next = match self.stack.last().map(|frame| &frame.container) {
+                    Some(Container::Object { .. }) => Expect::Key,
+                    _ => Expect::Value,
+                };
+                self.set_expect(next);
+            }
+            (Expect::KeyOrEnd | Expect::CommaOrEnd, '}') | (Expect::ValueOrEnd | Expect::CommaOrEnd, ']') => {
+                let frame = self.stack.pop().expect("an open container");
+                let value = match (frame.container, c) {
+                    (Container::Object { map, .. }, '}') => Value::Object(map),
+                    (Container::Array(items), ']') => Value::Array(items),
+                    _ => return Err(format!("unexpected {:?}", c)),
+                };
+                self.complete(value, events);
+            }
+            (_, c) => return Err(format!("unexpected {:?}", c)),
+        }
+        Ok(())
+    }
+
+    fn open(&mut self, container: Container) {
+        let path = self.child_path();
+        let expect = match container {
+            Container::Object { .. } => Expect::KeyOrEnd,
+            Container::Array(_) => Expect::ValueOrEnd,
+        };
+        self.stack.push(Frame { path, container, expect });
+    }
+
+    fn set_expect(&mut self, expect: Expect) {
+        if let Some(frame) = self.stack.last_mut() {
+            frame.expect = expect;
+        }
+    }
+
+    /// Returns the path of the value the innermost container reads next
+    fn child_path(&self) -> String {
+        match self.stack.last() {
+            None => String::new(),
+            Some(Frame {
+                path,
+                container: Container::Object { key, .. },
+                ..
+            }) => format!("{}/{}", path, escape_pointer(key.as_deref().unwrap_or_default())),
+            Some(Frame {
+                path,
+                container: Container::Array(items),
+                ..
+            }) => format!("{}/{}", path, items.len()),
+        }
+    }
+
+    fn end_string(&mut self, text: String, key: bool, events: &mut Vec<JsonEvent>) -> Result<(), String> {
+        if !key {
+            self.complete(Value::String(text), events);
+            return Ok(());
+        }
+        if let Some(Frame {
+            container: Container::Object { key, .. },
+            expect,
+            ..
+        }) = self.stack.last_mut()
+        {
+            *key = Some(text);
+            *expect = Expect::Colon;
+        }
+        events.push(JsonEvent::KeyStarted { path: self.child_path() });
+        Ok(())
+    }
+
+    fn end_scalar(&mut self, events: &mut Vec<JsonEvent>) -> Result<(), String> {
+        let Some(Token::Scalar(text)) = self.token.take() else {
+            return Ok(());
+        };
+        let value = scalar(&text).map_err(|_| format!("invalid value {:?}", text))?;
+        self.complete(value, events);
+        Ok(())
+    }
+
+    /// Stores a complete value in its container, or as the document
+    fn complete(&mut self, value: Value, events: &mut Vec<JsonEvent>) {
+        let path = self.child_path();
+        match self.stack.last_mut() {
+            None => {
+                events.push(JsonEvent::ValueCompleted { path, value: value.clone() });
+                self.value = Some(value);
+            }
+            Some(frame) => {
+                frame.expect = Expect::CommaOrEnd;
+                match &mut frame.container {
+                    Container::Object { map, key } => {
+                        map.insert(key.take().unwrap_or_default(), value.clone());
+                        events.push(JsonEvent::ValueCompleted { path, value });
+                    }
+                    Container::Array(items) => {
+                        items.push(value.clone());
+                        events.push(JsonEvent::ArrayItemCompleted {
+                            path: frame.path.clone(),
+                            index: items.len() - 1,
+                            value,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Parses a number, `true`, `false` or `null`
+fn scalar(text: &str) -> Result<Value, serde_json::Error> {
+    let value: Value = serde_json::from_str(text)?;
+    match value {
+        Value::Number(_) | Value::Bool(_) | Value::Null => Ok(value),
+        _ => Err(serde::de::Error::custom("not a scalar")),
+    }
+}
+
+/// Escapes a key for use in a JSON Pointer
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
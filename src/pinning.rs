@@ -0,0 +1,267 @@
+//! Certificate public key pinning
+//!
+//! 证书公钥固定模块
+//!
+//! A custom trust store still accepts any certificate chaining to a trusted
+//! CA. [`CertificatePins`] set with
+//! [`GroqClientBuilder::pin_certificates`](crate::GroqClientBuilder::pin_certificates)
+//! additionally requires the verified chain to contain a certificate whose
+//! public key matches one of the pins, so a certificate misissued by another
+//! CA is rejected before the API key is sent.
+//!
+//! A pin is the base64-encoded SHA-256 digest of a certificate's DER-encoded
+//! `SubjectPublicKeyInfo`, the format of HTTP Public Key Pinning. It can be
+//! computed with [`spki_sha256`], or with OpenSSL:
+//!
+//! ```text
+//! openssl x509 -in cert.pem -pubkey -noout \
+//!   | openssl pkey -pubin -outform der \
+//!   | openssl dgst -sha256 -binary | base64
+//! ```
+//!
+//! Pin the key of an intermediate or root CA rather than the server's own, so
+//! certificate renewals keep working, and keep a backup pin for the next key.
+//! Chains are still verified against the platform's root store, plus the
+//! roots added with [`CertificatePins::add_root_pem`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::pinning::CertificatePins;
+//! use groqai::GroqClientBuilder;
+//!
+//! let pins = CertificatePins::new()
+//!     .pin("YLh1dUR9y6Kja30RrAn7JKnbQG/uEtLMkBgFF2Fuihg=")?
+//!     .pin("Vjs8r4z+80wjNcr1YKepWQboSIRi63WsWXhIMN+eWys=")?;
+//! let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+//!     .pin_certificates(pins)
+//!     .build()?;
+//! # Ok::<(), groqai::GroqError>(())
+//! ```
+
+use std::sync::Arc;
+
+use base64::Engine;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, TrustAnchor, UnixTime};
+use rustls::{CertificateError, DigitallySignedStruct, OtherError, RootCertStore, SignatureScheme};
+use sha2::{Digest, Sha256};
+
+use crate::error::GroqError;
+
+/// Public key pins and the roots their chains are verified against
+#[derive(Debug, Clone, Default)]
+pub struct CertificatePins {
+    pins: Vec<[u8; 32]>,
+    roots: Vec<CertificateDer<'static>>,
+    custom_roots_only: bool,
+}
+
+impl CertificatePins {
+    /// Creates an empty set of pins, verified against the platform's roots
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accepts chains containing the public key with this SPKI SHA-256 digest
+    ///
+    /// May be called multiple times; a chain matching any pin is accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if `pin` is not the base64 encoding
+    /// of a SHA-256 digest.
+    pub fn pin(mut self, pin: &str) -> Result<Self, GroqError> {
+        let digest = base64::engine::general_purpose::STANDARD
+            .decode(pin.trim())
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .ok_or_else(|| {
+                GroqError::InvalidMessage(format!("Invalid certificate pin {:?}: expected a base64 SHA-256 digest", pin))
+            })?;
+        self.pins.push(digest);
+        Ok(self)
+    }
+
+    /// Trusts the CA certificates in `pem` when verifying chains
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if `pem` contains no certificate.
+    pub fn add_root_pem(mut self, pem: &[u8]) -> Result<Self, GroqError> {
+        let certs = CertificateDer::pem_slice_iter(pem)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| GroqError::InvalidMessage(format!("Invalid root certificate: {}", e)))?;
+        if certs.is_empty() {
+            return Err(GroqError::InvalidMessage(
+                "Root certificate PEM contained no certificate".to_string(),
+            ));
+        }
+        self.roots.extend(certs);
+        Ok(self)
+    }
+
+    /// Verifies chains only against the roots added with `add_root_pem`
+    pub fn custom_roots_only(mut self, enable: bool) -> Self {
+        self.custom_roots_only = enable;
+        self
+    }
+
+    /// Builds the rustls configuration enforcing the pins
+    pub(crate) fn client_config(&self) -> Result<rustls::ClientConfig, GroqError> {
+        if self.pins.is_empty() {
+            return Err(GroqError::InvalidMessage(
+                "Certificate pinning requires at least one pin".to_string(),
+            ));
+        }
+        let mut roots = RootCertStore::empty();
+        if !self.custom_roots_only {
+            let native = rustls_native_certs::load_native_certs();
+            for error in &native.errors {
+                tracing::warn!("Failed to load platform root certificates: {}", error);
+            }
+            roots.add_parsable_certificates(native.certs);
+        }
+        for root in &self.roots {
+            roots
+                .add(root.clone())
+                .map_err(|e| GroqError::InvalidMessage(format!("Invalid root certificate: {}", e)))?;
+        }
+        if roots.is_empty() {
+            return Err(GroqError::InvalidMessage(
+                "Certificate pinning found no root certificate to verify chains against".to_string(),
+            ));
+        }
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let verifier = PinnedVerifier {
+            roots,
+            pins: self.pins.clone(),
+            provider: provider.clone(),
+        };
+        let mut config = rustls::ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| GroqError::InvalidMessage(format!("Invalid TLS configuration: {}", e)))?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(verifier))
+            .with_no_client_auth();
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(config)
+    }
+}
+
+/// Returns the pin of a DER-encoded certificate: the base64 SHA-256 digest of its public key
+///
+/// # Errors
+///
+/// Returns `GroqError::InvalidMessage` if `der` is not a certificate.
+pub fn spki_sha256(der: &[u8]) -> Result<String, GroqError> {
+    let der = CertificateDer::from(der);
+    let cert = webpki::EndEntityCert::try_from(&der)
+        .map_err(|e| GroqError::InvalidMessage(format!("Invalid certificate: {}", e)))?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(Sha256::digest(cert.subject_public_key_info())))
+}
+
+/// Verifies chains like rustls, then requires a pinned key on the verified path
+#[derive(Debug)]
+struct PinnedVerifier {
+    roots: RootCertStore,
+    pins: Vec<[u8; 32]>,
+    provider: Arc<CryptoProvider>,
+}
+
+impl PinnedVerifier {
+    fn is_pinned(&self, spki: &[u8]) -> bool {
+        let digest: [u8; 32] = Sha256::digest(spki).into();
+        self.pins.contains(&digest)
+    }
+}
+
+impl ServerCertVerifier for PinnedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let cert = webpki::EndEntityCert::try_from(end_entity).map_err(invalid_certificate)?;
+        // Only keys on a path that verified count, so a pinned certificate
+        // appended to an otherwise unrelated chain does not match
+        let unpinned = std::cell::Cell::new(false);
+        let check_pins = |path: &webpki::VerifiedPath<'_>| {
+            let pinned = self.is_pinned(&path.end_entity().subject_public_key_info())
+                || path
+                    .intermediate_certificates()
+                    .any(|cert| self.is_pinned(&cert.subject_public_key_info()))
+                || self.is_pinned(&anchor_spki(path.anchor()));
+            if pinned {
+                return Ok(());
+            }
+            unpinned.set(true);
+            Err(webpki::Error::UnknownIssuer)
+        };
+        let verified = cert.verify_for_usage(
+            self.provider.signature_verification_algorithms.all,
+            &self.roots.roots,
+            intermediates,
+            now,
+            webpki::KeyUsage::server_auth(),
+            None,
+            Some(&check_pins),
+        );
+        match verified {
+            Ok(_) => {}
+            Err(_) if unpinned.get() => {
+                return Err(rustls::Error::InvalidCertificate(CertificateError::ApplicationVerificationFailure))
+            }
+            Err(e) => return Err(invalid_certificate(e)),
+        }
+        cert.verify_is_valid_for_subject_name(server_name).map_err(invalid_certificate)?;
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.provider.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.provider.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+fn invalid_certificate(error: webpki::Error) -> rustls::Error {
+    rustls::Error::InvalidCertificate(CertificateError::Other(OtherError(Arc::new(error))))
+}
+
+/// Wraps the SPKI contents of a trust anchor back into a DER `SEQUENCE`
+fn anchor_spki(anchor: &TrustAnchor<'_>) -> Vec<u8> {
+    let contents = anchor.subject_public_key_info.as_ref();
+    let mut der = vec![0x30];
+    match contents.len() {
+        len @ 0..=0x7f => der.push(len as u8),
+        len => {
+            let bytes: Vec<u8> = len.to_be_bytes().into_iter().skip_while(|b| *b == 0).collect();
+            der.push(0x80 | bytes.len() as u8);
+            der.extend(bytes);
+        }
+    }
+    der.extend_from_slice(contents);
+    der
+}
@@ -0,0 +1,185 @@
+//! Plain-text formatting of responses for terminals
+//!
+//! 面向终端的纯文本响应格式化模块
+//!
+//! Chat responses, batch lists and model lists implement [`Display`](std::fmt::Display)
+//! and a `pretty()` method producing human-friendly output for CLI tools and
+//! examples, without pulling in a TUI dependency.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::GroqClient;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # groqai::doctest::set_api_key();
+//! let client = GroqClient::new()?;
+//! # let client = groqai::doctest::client();
+//! let models = client.models().list().await?;
+//! println!("{}", models.pretty());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use crate::types::{BatchList, ChatCompletionResponse, MessageContent, ModelList};
+
+/// Line width used by `pretty()` methods
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// Word-wraps `text` to `width` characters, keeping existing line breaks
+///
+/// Words longer than `width` are placed on their own line rather than split.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let needed = line.chars().count() + word.chars().count() + usize::from(!line.is_empty());
+            if !line.is_empty() && needed > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Renders rows as a left-aligned table with a header separator
+pub fn table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let render = |cells: Vec<&str>| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        line.join("  ").trim_end().to_string()
+    };
+
+    let separators: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    let mut out = vec![render(headers.to_vec())];
+    out.push(render(separators.iter().map(String::as_str).collect()));
+    for row in rows {
+        out.push(render(row.iter().map(String::as_str).collect()));
+    }
+    out.join("\n")
+}
+
+impl ChatCompletionResponse {
+    /// Formats the response as role-labelled, wrapped text followed by token stats
+    pub fn pretty(&self) -> String {
+        let mut out = Vec::new();
+        for choice in &self.choices {
+            let role = format!("{:?}", choice.message.role).to_lowercase();
+            if self.choices.len() > 1 {
+                out.push(format!("[{}] {}:", choice.index, role));
+            } else {
+                out.push(format!("{}:", role));
+            }
+            let text = match &choice.message.content {
+                MessageContent::Text(text) => text.clone(),
+                other => other.to_string(),
+            };
+            if !text.is_empty() {
+                out.extend(wrap(&text, DEFAULT_WIDTH));
+            }
+            for call in choice.message.tool_calls.iter().flatten() {
+                out.push(format!("-> {}({})", call.function.name, call.function.arguments));
+            }
+            out.push(String::new());
+        }
+
+        let finish: Vec<&str> = self
+            .choices
+            .iter()
+            .filter_map(|c| c.finish_reason.as_deref())
+            .collect();
+        let mut stats = format!(
+            "{} · {} prompt + {} completion = {} tokens",
+            self.model, self.usage.prompt_tokens, self.usage.completion_tokens, self.usage.total_tokens
+        );
+        if let Some(total) = self.usage.total_time {
+            stats.push_str(&format!(" · {:.2}s", total));
+        }
+        if !finish.is_empty() {
+            stats.push_str(&format!(" · finish: {}", finish.join(", ")));
+        }
+        out.push(stats);
+        out.join("\n")
+    }
+}
+
+impl fmt::Display for ChatCompletionResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.pretty())
+    }
+}
+
+impl BatchList {
+    /// Formats the batches as a summary table
+    pub fn pretty(&self) -> String {
+        let rows: Vec<Vec<String>> = self
+            .data
+            .iter()
+            .map(|batch| {
+                let counts = &batch.request_counts;
+                let mut progress = format!("{}/{}", counts.completed, counts.total);
+                if counts.failed > 0 {
+                    progress.push_str(&format!(" ({} failed)", counts.failed));
+                }
+                vec![
+                    batch.id.clone(),
+                    batch.status.clone(),
+                    batch.endpoint.clone(),
+                    progress,
+                    batch.created_at.to_string(),
+                ]
+            })
+            .collect();
+        table(&["ID", "STATUS", "ENDPOINT", "PROGRESS", "CREATED"], &rows)
+    }
+}
+
+impl fmt::Display for BatchList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.pretty())
+    }
+}
+
+impl ModelList {
+    /// Formats the models as a table sorted by ID
+    pub fn pretty(&self) -> String {
+        let mut models: Vec<_> = self.data.iter().collect();
+        models.sort_by(|a, b| a.id.cmp(&b.id));
+        let rows: Vec<Vec<String>> = models
+            .into_iter()
+            .map(|model| {
+                vec![
+                    model.id.clone(),
+                    model.owned_by.clone(),
+                    model.context_window.to_string(),
+                    if model.active { "yes" } else { "no" }.to_string(),
+                ]
+            })
+            .collect();
+        table(&["ID", "OWNER", "CONTEXT", "ACTIVE"], &rows)
+    }
+}
+
+impl fmt::Display for ModelList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.pretty())
+    }
+}
@@ -0,0 +1,265 @@
+//! Model pricing and spending guards
+//!
+//! 模型价格表与花费上限模块
+//!
+//! A [`PricingTable`] holds the per-token prices of chat models, preloaded
+//! with Groq's published on-demand prices. A [`SpendGuard`] set with
+//! [`GroqClientBuilder::spend_guard`](crate::GroqClientBuilder::spend_guard)
+//! uses it to reject chat requests before they are sent when their worst-case
+//! cost, or the tokens used in the past hour, would exceed a limit. Rejected
+//! requests fail with `GroqError::BudgetExceeded` instead of waiting, unlike
+//! the rate-shaping [`Budget`](crate::rate_limit::Budget).
+//!
+//! Cost limits can be tightened, never raised, per request with
+//! [`ChatRequestBuilder::max_cost_usd`](crate::api::chat::ChatRequestBuilder::max_cost_usd)
+//! and skipped with
+//! [`ChatRequestBuilder::override_spend_guard`](crate::api::chat::ChatRequestBuilder::override_spend_guard).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::pricing::{ModelPrice, PricingTable};
+//!
+//! let pricing = PricingTable::new().set("my-fine-tune", ModelPrice::new(0.20, 0.40));
+//! let cost = pricing.cost("llama-3.3-70b-versatile", 1_000_000, 500_000).unwrap();
+//! assert!((cost - 0.985).abs() < 1e-9);
+//! assert_eq!(pricing.cost("unknown-model", 1, 1), None);
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::api::chat::ChatCompletionRequest;
+use crate::error::GroqError;
+use crate::text::estimate_tokens;
+
+/// On-demand prices in USD per million input and output tokens
+const GROQ_PRICES: &[(&str, f64, f64)] = &[
+    ("llama-3.1-8b-instant", 0.05, 0.08),
+    ("llama-3.3-70b-versatile", 0.59, 0.79),
+    ("meta-llama/llama-4-scout-17b-16e-instruct", 0.11, 0.34),
+    ("meta-llama/llama-4-maverick-17b-128e-instruct", 0.20, 0.60),
+    ("meta-llama/llama-guard-4-12b", 0.20, 0.20),
+    ("openai/gpt-oss-20b", 0.10, 0.50),
+    ("openai/gpt-oss-120b", 0.15, 0.75),
+    ("qwen/qwen3-32b", 0.29, 0.59),
+    ("moonshotai/kimi-k2-instruct", 1.00, 3.00),
+];
+
+/// Tokens added per message for role and formatting
+const MESSAGE_OVERHEAD_TOKENS: u64 = 4;
+
+/// Price of one model
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    /// USD per million prompt tokens
+    pub input_per_million: f64,
+    /// USD per million completion tokens
+    pub output_per_million: f64,
+}
+
+impl ModelPrice {
+    /// Creates a price from USD per million input and output tokens
+    pub fn new(input_per_million: f64, output_per_million: f64) -> Self {
+        Self {
+            input_per_million,
+            output_per_million,
+        }
+    }
+
+    /// Returns the cost in USD of the given token counts
+    pub fn cost(&self, prompt_tokens: u64, completion_tokens: u64) -> f64 {
+        (prompt_tokens as f64 * self.input_per_million + completion_tokens as f64 * self.output_per_million)
+            / 1_000_000.0
+    }
+}
+
+/// Prices of chat models
+///
+/// [`new`](PricingTable::new) starts with Groq's on-demand prices at the time
+/// of release. Prices change; override them with [`set`](PricingTable::set).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PricingTable {
+    prices: HashMap<String, ModelPrice>,
+}
+
+impl Default for PricingTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PricingTable {
+    /// Creates a table with Groq's published prices
+    pub fn new() -> Self {
+        Self {
+            prices: GROQ_PRICES
+                .iter()
+                .map(|(model, input, output)| (model.to_string(), ModelPrice::new(*input, *output)))
+                .collect(),
+        }
+    }
+
+    /// Creates a table without any prices
+    pub fn empty() -> Self {
+        Self { prices: HashMap::new() }
+    }
+
+    /// Sets or replaces the price of `model`
+    pub fn set(mut self, model: impl Into<String>, price: ModelPrice) -> Self {
+        self.prices.insert(model.into(), price);
+        self
+    }
+
+    /// Returns the price of `model`, if known
+    pub fn get(&self, model: &str) -> Option<ModelPrice> {
+        self.prices.get(model).copied()
+    }
+
+    /// Returns the cost in USD of the given token counts, if `model` is known
+    pub fn cost(&self, model: &str, prompt_tokens: u64, completion_tokens: u64) -> Option<f64> {
+        self.get(model).map(|price| price.cost(prompt_tokens, completion_tokens))
+    }
+}
+
+/// Estimates the prompt tokens of `request` from the length of its messages
+pub(crate) fn estimate_prompt_tokens(request: &ChatCompletionRequest) -> u64 {
+    request
+        .messages
+        .iter()
+        .map(|m| estimate_tokens(&m.content.to_string()) as u64 + MESSAGE_OVERHEAD_TOKENS)
+        .sum()
+}
+
+/// Rejects chat requests that would exceed a spending limit
+///
+/// Checks run before a request is sent, against its worst case: the
+/// estimated prompt tokens (four characters per token) plus all of
+/// `max_completion_tokens`. Without `max_completion_tokens` the worst case is
+/// unbounded, so such requests fail any cost limit. Requests to models missing
+/// from the pricing table pass the cost check. Clones share the hourly token
+/// count, which includes streams once they report their usage.
+///
+/// # Examples
+///
+/// ```rust
+/// use groqai::pricing::SpendGuard;
+/// use groqai::GroqClientBuilder;
+///
+/// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+///     .spend_guard(SpendGuard::new().max_cost_usd(0.05).max_total_tokens_per_hour(500_000))
+/// #     .transport(groqai::doctest::FixtureTransport::new())
+///     .build()?;
+/// # Ok::<(), groqai::GroqError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SpendGuard {
+    pricing: Arc<PricingTable>,
+    max_cost_usd: Option<f64>,
+    max_total_tokens_per_hour: Option<u64>,
+    /// `(time, tokens)` of the completions in the past hour
+    used: Arc<Mutex<VecDeque<(Instant, u64)>>>,
+}
+
+impl SpendGuard {
+    /// Creates a guard without limits, priced with [`PricingTable::new`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prices requests with `pricing` instead of the built-in table
+    pub fn pricing(mut self, pricing: PricingTable) -> Self {
+        self.pricing = Arc::new(pricing);
+        self
+    }
+
+    /// Rejects requests whose worst-case cost exceeds `usd`
+    pub fn max_cost_usd(mut self, usd: f64) -> Self {
+        self.max_cost_usd = Some(usd);
+        self
+    }
+
+    /// Rejects requests once the tokens of the past hour would exceed `tokens`
+    ///
+    /// Counts the total tokens of completed chat requests in a sliding window,
+    /// plus the worst case of the request being checked. Streams are counted
+    /// when their last chunk reports usage.
+    pub fn max_total_tokens_per_hour(mut self, tokens: u64) -> Self {
+        self.max_total_tokens_per_hour = Some(tokens);
+        self
+    }
+
+    /// Returns the pricing table
+    pub fn pricing_table(&self) -> &PricingTable {
+        &self.pricing
+    }
+
+    /// Returns the worst-case cost of `request` in USD, if its model is priced
+    ///
+    /// Returns infinity for priced models when `max_completion_tokens` is unset.
+    pub fn estimate_cost(&self, request: &ChatCompletionRequest) -> Option<f64> {
+        let prompt = estimate_prompt_tokens(request);
+        match request.max_completion_tokens {
+            Some(completion) => self.pricing.cost(&request.model, prompt, u64::from(completion)),
+            None => self.pricing.get(&request.model).map(|_| f64::INFINITY),
+        }
+    }
+
+    /// Returns the total tokens of the chat completions in the past hour
+    pub fn tokens_used_last_hour(&self) -> u64 {
+        self.used_window().iter().map(|(_, tokens)| tokens).sum()
+    }
+
+    fn used_window(&self) -> std::sync::MutexGuard<'_, VecDeque<(Instant, u64)>> {
+        let mut used = self.used.lock().unwrap_or_else(|e| e.into_inner());
+        let hour = Duration::from_secs(3600);
+        while used.front().is_some_and(|(at, _)| at.elapsed() >= hour) {
+            used.pop_front();
+        }
+        used
+    }
+
+    /// Fails with `BudgetExceeded` if `request` would exceed a limit
+    ///
+    /// `max_cost_usd` applies on top of the guard's own cost limit, so the
+    /// lower of the two wins.
+    pub(crate) fn check(&self, request: &ChatCompletionRequest, max_cost_usd: Option<f64>) -> Result<(), GroqError> {
+        let limit = match (self.max_cost_usd, max_cost_usd) {
+            (Some(guard), Some(request)) => Some(guard.min(request)),
+            (guard, request) => guard.or(request),
+        };
+        if let (Some(max), Some(cost)) = (limit, self.estimate_cost(request)) {
+            if cost.is_infinite() {
+                return Err(GroqError::BudgetExceeded(format!(
+                    "request to {} sets no max_completion_tokens, so its cost is unbounded; the limit is ${:.6}",
+                    request.model, max
+                )));
+            }
+            if cost > max {
+                return Err(GroqError::BudgetExceeded(format!(
+                    "request to {} may cost up to ${:.6}, over the limit of ${:.6}",
+                    request.model, cost, max
+                )));
+            }
+        }
+        if let Some(max) = self.max_total_tokens_per_hour {
+            let worst_case = estimate_prompt_tokens(request) + request.max_completion_tokens.map_or(0, u64::from);
+            let used = self.tokens_used_last_hour();
+            if used + worst_case > max {
+                return Err(GroqError::BudgetExceeded(format!(
+                    "{} tokens used in the past hour plus up to {} for this request exceed the limit of {}",
+                    used, worst_case, max
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Counts the tokens of a completed request
+    pub(crate) fn record(&self, total_tokens: u64) {
+        if self.max_total_tokens_per_hour.is_some() {
+            self.used_window().push_back((Instant::now(), total_tokens));
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! System prompt versioning and A/B assignment
+//!
+//! 系统提示词版本管理与 A/B 分组模块
+//!
+//! A [`PromptExperiment`] holds several weighted versions of a system prompt.
+//! Each user (or any other key) is deterministically assigned one variant, so
+//! the same user always sees the same prompt. Requests sent with
+//! [`ChatRequestBuilder::prompt_variant`](crate::ChatRequestBuilder::prompt_variant)
+//! are attributed to their variant in the client's
+//! [`UsageTracker`](crate::usage::UsageTracker).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::prompts::PromptExperiment;
+//! use groqai::usage::UsageTracker;
+//! use groqai::{ChatMessage, GroqClientBuilder, Role};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let tracker = UsageTracker::new();
+//! let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+//!     .usage_tracker(tracker.clone())
+//! #     .transport(groqai::doctest::FixtureTransport::new())
+//!     .build()?;
+//!
+//! let experiment = PromptExperiment::new("support-tone")
+//!     .variant("formal", "You are a formal support agent.", 50)
+//!     .variant("friendly", "You are a friendly support agent.", 50);
+//!
+//! client
+//!     .chat("llama-3.1-8b-instant")
+//!     .prompt_variant(&experiment, "user-42")
+//!     .message(ChatMessage::new_text(Role::User, "My order is late"))
+//!     .send()
+//!     .await?;
+//!
+//! println!("{:?}", tracker.totals_for_experiment("support-tone"));
+//! # Ok(())
+//! # }
+//! ```
+
+/// One version of a system prompt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptVariant {
+    /// Name used to attribute usage, e.g. "v2" or "friendly"
+    pub name: String,
+    /// The system prompt text
+    pub system_prompt: String,
+    /// Relative share of keys assigned to this variant
+    pub weight: u32,
+}
+
+/// A set of weighted prompt variants with deterministic assignment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptExperiment {
+    name: String,
+    variants: Vec<PromptVariant>,
+}
+
+impl PromptExperiment {
+    /// Creates an experiment without variants
+    ///
+    /// The name salts assignment, so different experiments split the same
+    /// users independently.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            variants: Vec::new(),
+        }
+    }
+
+    /// Registers a variant with a relative weight
+    pub fn variant(
+        mut self,
+        name: impl Into<String>,
+        system_prompt: impl Into<String>,
+        weight: u32,
+    ) -> Self {
+        self.variants.push(PromptVariant {
+            name: name.into(),
+            system_prompt: system_prompt.into(),
+            weight,
+        });
+        self
+    }
+
+    /// Returns the experiment name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the registered variants
+    pub fn variants(&self) -> &[PromptVariant] {
+        &self.variants
+    }
+
+    /// Returns the variant assigned to `key`, e.g. a user ID or tag
+    ///
+    /// Assignment is stable across processes and releases for the same
+    /// experiment name and variant list. Returns `None` if no variant has a
+    /// positive weight.
+    pub fn assign(&self, key: &str) -> Option<&PromptVariant> {
+        let total: u64 = self.variants.iter().map(|v| u64::from(v.weight)).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut point = fnv1a(&[self.name.as_bytes(), b"\0", key.as_bytes()]) % total;
+        for variant in &self.variants {
+            let weight = u64::from(variant.weight);
+            if point < weight {
+                return Some(variant);
+            }
+            point -= weight;
+        }
+        None
+    }
+}
+
+/// 64-bit FNV-1a, used because its output never changes between Rust releases
+fn fnv1a(parts: &[&[u8]]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in parts.iter().flat_map(|p| p.iter()) {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
@@ -0,0 +1,329 @@
+//! Client-side request queue with priority scheduling
+//!
+//! 客户端请求队列：按优先级调度并限制并发
+//!
+//! A [`RequestQueue`] set with
+//! [`GroqClientBuilder::request_queue`](crate::GroqClientBuilder::request_queue)
+//! limits how many requests a client and its clones send at once. Requests
+//! wait in one line per [`Priority`], first come first served, and a free slot
+//! goes to the most urgent line: interactive before background before batch
+//! preparation. A burst of background work then queues behind user-facing
+//! chat instead of in front of it.
+//!
+//! Two settings keep the scheduling fair. [`limit`](RequestQueue::limit) caps
+//! the slots one priority may hold, so slow background requests cannot occupy
+//! all of them, and [`max_wait`](RequestQueue::max_wait) lets a request that
+//! has waited that long go ahead of more urgent ones, so lower priorities are
+//! not starved either.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::queue::{Priority, RequestQueue};
+//! use groqai::{ChatMessage, GroqClientBuilder, Role};
+//! use std::time::Duration;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let queue = RequestQueue::new(8)
+//!     .limit(Priority::Background, 6)
+//!     .limit(Priority::BatchPrep, 2)
+//!     .max_wait(Duration::from_secs(30));
+//! let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+//!     .request_queue(queue.clone())
+//! #     .transport(groqai::doctest::FixtureTransport::new())
+//!     .build()?;
+//!
+//! // Requests are interactive unless marked otherwise
+//! let digest = client
+//!     .chat("llama-3.1-8b-instant")
+//!     .message(ChatMessage::new_text(Role::User, "Summarize yesterday's tickets."))
+//!     .priority(Priority::Background)
+//!     .send()
+//!     .await?;
+//! println!("{} background requests waiting", queue.waiting(Priority::Background));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use tokio::sync::Notify;
+use url::Url;
+
+use crate::error::GroqError;
+use crate::metrics::TransportMetrics;
+use crate::transport::{ByteStream, Request, Response, Transport};
+
+/// How urgently a request should be sent when it has to wait in a [`RequestQueue`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Priority {
+    /// A user is waiting for the answer; the default
+    #[default]
+    Interactive,
+    /// Work whose result is not awaited by a user, such as summaries or evaluations
+    Background,
+    /// Preparation of batch jobs, such as uploading input files
+    BatchPrep,
+}
+
+impl Priority {
+    /// Every priority, most urgent first
+    pub const ALL: [Priority; 3] = [Priority::Interactive, Priority::Background, Priority::BatchPrep];
+
+    /// Returns the name of the priority
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Priority::Interactive => "interactive",
+            Priority::Background => "background",
+            Priority::BatchPrep => "batch_prep",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A request waiting for a slot
+struct Ticket {
+    id: u64,
+    since: Instant,
+}
+
+#[derive(Default)]
+struct QueueState {
+    active: [usize; 3],
+    waiting: [VecDeque<Ticket>; 3],
+    next_id: u64,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<QueueState>,
+    /// Woken whenever a slot frees up or the order of the lines changes
+    changed: Notify,
+}
+
+/// Limits concurrent requests and hands free slots out by [`Priority`]
+///
+/// Configure it before handing it to the client; clones share the waiting
+/// requests and occupied slots, so a clone can be kept to inspect them.
+#[derive(Clone)]
+pub struct RequestQueue {
+    max_concurrent: usize,
+    limits: [Option<usize>; 3],
+    max_wait: Option<Duration>,
+    shared: Arc<Shared>,
+}
+
+impl fmt::Debug for RequestQueue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestQueue")
+            .field("max_concurrent", &self.max_concurrent)
+            .field("limits", &self.limits)
+            .field("max_wait", &self.max_wait)
+            .finish()
+    }
+}
+
+impl RequestQueue {
+    /// Creates a queue sending at most `max_concurrent` requests at a time
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            max_concurrent: max_concurrent.max(1),
+            limits: [None; 3],
+            max_wait: None,
+            shared: Arc::default(),
+        }
+    }
+
+    /// Lets requests of `priority` hold at most `max_concurrent` slots
+    ///
+    /// Limit the lower priorities to keep slots free for interactive requests.
+    pub fn limit(mut self, priority: Priority, max_concurrent: usize) -> Self {
+        self.limits[priority.index()] = Some(max_concurrent.max(1));
+        self
+    }
+
+    /// Serves a request that has waited for `max_wait` before more urgent ones
+    ///
+    /// Requests that waited that long are served oldest first.
+    pub fn max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = Some(max_wait);
+        self
+    }
+
+    /// Returns the number of requests being sent
+    pub fn active(&self) -> usize {
+        self.lock().active.iter().sum()
+    }
+
+    /// Returns the number of requests of `priority` waiting for a slot
+    pub fn waiting(&self, priority: Priority) -> usize {
+        self.lock().waiting[priority.index()].len()
+    }
+
+    /// Waits for a slot for a request of `priority`
+    ///
+    /// The slot is held until the returned permit is dropped. A request that
+    /// stops waiting leaves the line.
+    pub async fn acquire(&self, priority: Priority) -> QueuePermit {
+        let mut waiter = Waiter {
+            queue: self,
+            priority,
+            id: self.enqueue(priority),
+            granted: false,
+        };
+        loop {
+            let changed = self.shared.changed.notified();
+            tokio::pin!(changed);
+            // Register before checking so a release in between is not missed
+            changed.as_mut().enable();
+            if self.try_grant(priority, waiter.id) {
+                waiter.granted = true;
+                // Slots may be left for the next request in line
+                self.shared.changed.notify_waiters();
+                return QueuePermit {
+                    queue: self.clone(),
+                    priority,
+                };
+            }
+            changed.await;
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, QueueState> {
+        self.shared.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn enqueue(&self, priority: Priority) -> u64 {
+        let mut state = self.lock();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.waiting[priority.index()].push_back(Ticket {
+            id,
+            since: Instant::now(),
+        });
+        id
+    }
+
+    /// Takes a slot if the request is the next one to be served
+    fn try_grant(&self, priority: Priority, id: u64) -> bool {
+        let mut state = self.lock();
+        if self.next(&state) != Some((priority, id)) {
+            return false;
+        }
+        state.waiting[priority.index()].pop_front();
+        state.active[priority.index()] += 1;
+        true
+    }
+
+    /// Returns the request that gets the next free slot, if a slot is free
+    fn next(&self, state: &QueueState) -> Option<(Priority, u64)> {
+        if state.active.iter().sum::<usize>() >= self.max_concurrent {
+            return None;
+        }
+        let fronts = Priority::ALL.into_iter().filter_map(|priority| {
+            let index = priority.index();
+            let below_limit = self.limits[index].is_none_or(|limit| state.active[index] < limit);
+            let ticket = state.waiting[index].front().filter(|_| below_limit)?;
+            Some((priority, ticket))
+        });
+        let overdue = self.max_wait.and_then(|max_wait| {
+            fronts
+                .clone()
+                .filter(|(_, ticket)| ticket.since.elapsed() >= max_wait)
+                .min_by_key(|(_, ticket)| ticket.since)
+        });
+        overdue.or_else(|| fronts.clone().next()).map(|(priority, ticket)| (priority, ticket.id))
+    }
+}
+
+/// A request in line; leaves the line if dropped before it got a slot
+struct Waiter<'a> {
+    queue: &'a RequestQueue,
+    priority: Priority,
+    id: u64,
+    granted: bool,
+}
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        if self.granted {
+            return;
+        }
+        self.queue.lock().waiting[self.priority.index()].retain(|ticket| ticket.id != self.id);
+        self.queue.shared.changed.notify_waiters();
+    }
+}
+
+/// A slot in a [`RequestQueue`], freed when dropped
+pub struct QueuePermit {
+    queue: RequestQueue,
+    priority: Priority,
+}
+
+impl fmt::Debug for QueuePermit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueuePermit").field("priority", &self.priority).finish()
+    }
+}
+
+impl Drop for QueuePermit {
+    fn drop(&mut self) {
+        self.queue.lock().active[self.priority.index()] -= 1;
+        self.queue.shared.changed.notify_waiters();
+    }
+}
+
+/// Sends every request through a [`RequestQueue`], by its [`Priority`]
+///
+/// Streams hold their slot until they end or are dropped.
+pub(crate) struct QueuedTransport {
+    inner: Arc<dyn Transport>,
+    queue: RequestQueue,
+}
+
+impl QueuedTransport {
+    pub(crate) fn new(inner: Arc<dyn Transport>, queue: RequestQueue) -> Self {
+        Self { inner, queue }
+    }
+}
+
+#[async_trait]
+impl Transport for QueuedTransport {
+    async fn execute(&self, request: Request) -> Result<Response, GroqError> {
+        let _permit = self.queue.acquire(request.options.request_priority().unwrap_or_default()).await;
+        self.inner.execute(request).await
+    }
+
+    async fn execute_stream(&self, request: Request) -> Result<ByteStream, GroqError> {
+        let permit = self.queue.acquire(request.options.request_priority().unwrap_or_default()).await;
+        let stream = self.inner.execute_stream(request).await?;
+        let stream = futures::stream::unfold((stream, permit), |(mut stream, permit)| async move {
+            let item = stream.next().await?;
+            Some((item, (stream, permit)))
+        });
+        Ok(Box::pin(stream))
+    }
+
+    fn base_url(&self) -> &Url {
+        self.inner.base_url()
+    }
+
+    fn metrics(&self) -> Option<&TransportMetrics> {
+        self.inner.metrics()
+    }
+}
@@ -2,17 +2,40 @@
 //! 
 //! 速率限制模块，提供 API 请求的重试和退避机制
 
+use async_trait::async_trait;
 use backoff::{backoff::Backoff, ExponentialBackoff};
-use std::time::Duration;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::api::chat::ChatCompletionRequest;
+use crate::error::GroqError;
+use crate::pricing::estimate_prompt_tokens;
+use crate::types::{ChatCompletionResponse, Usage};
 
 /// Rate limiter with exponential backoff for handling API rate limits
 /// 
 /// This struct provides configuration for retry logic when API requests
 /// are rate limited or encounter transient errors.
+/// 
+/// Clones are handles to the same state: when one request is rate limited,
+/// every clone waits out the same pause before sending, and the backoff
+/// sequence of [`next_backoff`](RateLimiter::next_backoff) is shared.
 #[derive(Clone)]
 pub struct RateLimiter {
     /// Exponential backoff configuration
     pub backoff: ExponentialBackoff,
+    state: Arc<Mutex<LimiterState>>,
+}
+
+/// Mutable state shared by all clones of a [`RateLimiter`]
+#[derive(Default)]
+struct LimiterState {
+    /// Backoff sequence in progress, started from the configuration on first use
+    current: Option<ExponentialBackoff>,
+    /// No request may be sent before this instant
+    paused_until: Option<Instant>,
 }
 
 impl RateLimiter {
@@ -32,6 +55,7 @@ impl RateLimiter {
                 max_elapsed_time: Some(Duration::from_secs(3600)),
                 ..Default::default()
             },
+            state: Arc::default(),
         }
     }
 
@@ -46,8 +70,8 @@ impl RateLimiter {
     }
 
     /// Resets the backoff state
-    pub fn reset(&mut self) {
-        self.backoff.reset();
+    pub fn reset(&self) {
+        self.lock().current = None;
     }
 
     /// Gets the next backoff duration
@@ -59,8 +83,46 @@ impl RateLimiter {
     /// # Returns
     /// 
     /// The duration to wait before the next retry attempt
-    pub fn next_backoff(&mut self, retry_after: Option<Duration>) -> Option<Duration> {
-        retry_after.or_else(|| self.backoff.next_backoff())
+    pub fn next_backoff(&self, retry_after: Option<Duration>) -> Option<Duration> {
+        if retry_after.is_some() {
+            return retry_after;
+        }
+        let mut state = self.lock();
+        let current = state.current.get_or_insert_with(|| {
+            // 复制的配置带着创建时的起始时间，需要从现在重新计时
+            let mut backoff = self.backoff.clone();
+            backoff.reset();
+            backoff
+        });
+        current.next_backoff()
+    }
+
+    /// Holds back requests through this limiter and its clones for `duration`
+    /// 
+    /// An existing longer pause is kept.
+    pub fn pause_for(&self, duration: Duration) {
+        let until = Instant::now() + duration;
+        let mut state = self.lock();
+        if state.paused_until.is_none_or(|current| current < until) {
+            state.paused_until = Some(until);
+        }
+    }
+
+    /// Returns how much longer requests are held back, if at all
+    pub fn paused_for(&self) -> Option<Duration> {
+        let until = self.lock().paused_until?;
+        until.checked_duration_since(Instant::now()).filter(|d| !d.is_zero())
+    }
+
+    /// Waits until requests may be sent again
+    pub async fn wait(&self) {
+        while let Some(remaining) = self.paused_for() {
+            tokio::time::sleep(remaining).await;
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LimiterState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
     }
 }
 
@@ -68,4 +130,465 @@ impl Default for RateLimiter {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+/// Shared counters backing a [`Budget`]
+///
+/// The default [`MemoryBudgetStore`] counts per process. Implement this trait
+/// over a shared store (see the `redis` feature) so several instances of a
+/// service draw from one budget.
+#[async_trait]
+pub trait BudgetStore: Send + Sync {
+    /// Adds `amount` to the counter `key` and returns its new value
+    ///
+    /// A counter that does not exist yet starts at zero and may be discarded
+    /// once `ttl` has passed.
+    async fn increment(&self, key: &str, amount: u64, ttl: Duration) -> Result<u64, GroqError>;
+}
+
+/// In-process [`BudgetStore`]
+#[derive(Default)]
+pub struct MemoryBudgetStore {
+    counters: Mutex<HashMap<String, (Instant, u64)>>,
+}
+
+impl MemoryBudgetStore {
+    /// Creates an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl BudgetStore for MemoryBudgetStore {
+    async fn increment(&self, key: &str, amount: u64, ttl: Duration) -> Result<u64, GroqError> {
+        let mut counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        counters.retain(|_, (expires, _)| *expires > now);
+        let (_, value) = counters.entry(key.to_string()).or_insert((now + ttl, 0));
+        *value += amount;
+        Ok(*value)
+    }
+}
+
+/// Request and token limits per fixed time window
+///
+/// Windows are aligned to the Unix epoch, so every instance sharing a store
+/// agrees on when a window starts. When a limit is reached, chat requests
+/// wait for the next window instead of failing. Store errors are logged and
+/// the request proceeds.
+///
+/// # Examples
+///
+/// ```rust
+/// use groqai::GroqClientBuilder;
+/// use groqai::rate_limit::Budget;
+/// use std::time::Duration;
+///
+/// let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+///     .budget(
+///         Budget::new(Duration::from_secs(60))
+///             .max_requests(30)
+///             .max_tokens(6_000),
+///     )
+/// #     .transport(groqai::doctest::FixtureTransport::new())
+///     .build()?;
+/// # Ok::<(), groqai::GroqError>(())
+/// ```
+#[derive(Clone)]
+pub struct Budget {
+    store: Arc<dyn BudgetStore>,
+    namespace: String,
+    window: Duration,
+    max_requests: Option<u64>,
+    max_tokens: Option<u64>,
+    throughput: Option<ThroughputEstimator>,
+    in_flight: Arc<InFlight>,
+}
+
+impl Budget {
+    /// Creates an unlimited budget over windows of `window`, counted in memory
+    ///
+    /// Windows shorter than one second are rounded up to one second.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            store: Arc::new(MemoryBudgetStore::new()),
+            namespace: "groqai".to_string(),
+            window: window.max(Duration::from_secs(1)),
+            max_requests: None,
+            max_tokens: None,
+            throughput: None,
+            in_flight: Arc::default(),
+        }
+    }
+
+    /// Counts usage in `store` instead of in memory
+    pub fn store(mut self, store: impl BudgetStore + 'static) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// Prefixes counter keys, so unrelated services can share a store
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = namespace.into();
+        self
+    }
+
+    /// Limits the number of chat requests started per window
+    pub fn max_requests(mut self, max: u64) -> Self {
+        self.max_requests = Some(max);
+        self
+    }
+
+    /// Limits the total tokens of chat completions per window
+    ///
+    /// Tokens are counted when a response arrives, so the requests in flight
+    /// when the limit is reached may overshoot it, unless
+    /// [`throughput`](Budget::throughput) is set.
+    pub fn max_tokens(mut self, max: u64) -> Self {
+        self.max_tokens = Some(max);
+        self
+    }
+
+    /// Holds back requests that would exceed `max_tokens` once the requests in flight complete
+    ///
+    /// Without it, tokens are only counted when responses arrive, so a burst
+    /// of requests can overshoot the limit and run into `429` responses.
+    /// With it, each request is expected to use the tokens `estimator`
+    /// observed for its model, and a request that would exceed the limit
+    /// together with the requests in flight waits until they are expected to
+    /// complete. The estimator learns from every response counted by this
+    /// budget; keep a clone of it to read the estimates.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use groqai::rate_limit::{Budget, ThroughputEstimator};
+    /// use std::time::Duration;
+    ///
+    /// let throughput = ThroughputEstimator::new();
+    /// let budget = Budget::new(Duration::from_secs(60))
+    ///     .max_tokens(100_000)
+    ///     .throughput(throughput.clone());
+    /// // Later, e.g. in a metrics exporter
+    /// for (model, estimate) in throughput.snapshot() {
+    ///     println!("{}: {:.0} tokens/s", model, estimate.tokens_per_second);
+    /// }
+    /// ```
+    pub fn throughput(mut self, estimator: ThroughputEstimator) -> Self {
+        self.throughput = Some(estimator);
+        self
+    }
+
+    /// Returns the throughput estimator set with [`throughput`](Budget::throughput)
+    pub fn throughput_estimator(&self) -> Option<&ThroughputEstimator> {
+        self.throughput.as_ref()
+    }
+
+    /// Waits until the current window has room for `request`
+    ///
+    /// The returned reservation counts the request as in flight until it is dropped.
+    pub(crate) async fn acquire(&self, request: &ChatCompletionRequest) -> BudgetReservation {
+        loop {
+            let (window_id, remaining) = self.current_window();
+            let used = match self.max_tokens {
+                Some(_) => self.count("tokens", window_id, 0).await,
+                None => None,
+            };
+            let tokens_full = match (self.max_tokens, used) {
+                (Some(max), Some(used)) => used >= max,
+                _ => false,
+            };
+            if let (Some(estimator), Some(max), Some(used), false) = (&self.throughput, self.max_tokens, used, tokens_full) {
+                let expected = estimator.expected_tokens(request);
+                if let Some(settle) = self.in_flight.would_exceed(used, expected, max) {
+                    let delay = settle.min(remaining);
+                    tracing::debug!("Requests in flight may exhaust the token budget, waiting {:?}", delay);
+                    tokio::time::sleep(delay).await;
+                    continue;
+                }
+            }
+            let requests_full = !tokens_full
+                && match self.max_requests {
+                    Some(max) => self.count("requests", window_id, 1).await.is_some_and(|used| used > max),
+                    None => false,
+                };
+            if !tokens_full && !requests_full {
+                return match &self.throughput {
+                    Some(estimator) => self.in_flight.reserve(
+                        estimator.expected_tokens(request),
+                        estimator.expected_duration(&request.model),
+                    ),
+                    None => BudgetReservation::default(),
+                };
+            }
+            tracing::debug!("Budget exhausted, waiting {:?} for the next window", remaining);
+            tokio::time::sleep(remaining).await;
+        }
+    }
+
+    /// Adds the tokens of a completed request to the current window
+    ///
+    /// `elapsed` is the time the request took, used by the throughput
+    /// estimator when the response carries no `completion_time`.
+    pub(crate) async fn record(&self, model: &str, usage: &Usage, elapsed: Duration) {
+        if let Some(estimator) = &self.throughput {
+            estimator.record_usage(model, usage, elapsed);
+        }
+        if self.max_tokens.is_some() {
+            let (window_id, _) = self.current_window();
+            self.count("tokens", window_id, u64::from(usage.total_tokens)).await;
+        }
+    }
+
+    /// Returns the current window number and the time left in it
+    fn current_window(&self) -> (u64, Duration) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let window_ms = self.window.as_millis().max(1);
+        let id = now.as_millis() / window_ms;
+        let remaining = (id + 1) * window_ms - now.as_millis();
+        (id as u64, Duration::from_millis(remaining as u64))
+    }
+
+    async fn count(&self, kind: &str, window_id: u64, amount: u64) -> Option<u64> {
+        let key = format!("{}:{}:{}:{}", self.namespace, kind, self.window.as_secs(), window_id);
+        match self.store.increment(&key, amount, self.window).await {
+            Ok(value) => Some(value),
+            Err(e) => {
+                tracing::warn!("Budget store failed: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// Default weight of the newest sample in a [`ThroughputEstimator`]
+const DEFAULT_SMOOTHING: f64 = 0.2;
+
+/// How long to wait for requests in flight whose duration cannot be estimated
+const UNKNOWN_SETTLE: Duration = Duration::from_secs(1);
+
+/// Shortest wait between checks while requests in flight are overdue
+const MIN_SETTLE: Duration = Duration::from_millis(50);
+
+/// Smoothed throughput observed for one model
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThroughputEstimate {
+    /// Completion tokens generated per second
+    pub tokens_per_second: f64,
+    /// Completion tokens per response
+    pub completion_tokens: f64,
+    /// Total tokens, prompt included, per response
+    pub total_tokens: f64,
+    /// Number of responses observed
+    pub samples: u64,
+}
+
+impl ThroughputEstimate {
+    /// Returns how long a response of the usual length takes to generate
+    pub fn expected_duration(&self) -> Option<Duration> {
+        (self.tokens_per_second > 0.0)
+            .then(|| Duration::try_from_secs_f64(self.completion_tokens / self.tokens_per_second).ok())
+            .flatten()
+    }
+}
+
+/// Exponentially smoothed completion throughput per model
+///
+/// Each response moves the estimate of its model towards the observed value
+/// by the smoothing factor, so the estimate follows sustained changes in
+/// load while single slow responses barely move it. Used by
+/// [`Budget::throughput`] to hold back requests; clones share the estimates.
+///
+/// # Examples
+///
+/// ```rust
+/// use groqai::rate_limit::ThroughputEstimator;
+/// use std::time::Duration;
+///
+/// let estimator = ThroughputEstimator::new().smoothing(0.5);
+/// estimator.record("llama-3.1-8b-instant", 100, 150, Duration::from_secs(1));
+/// estimator.record("llama-3.1-8b-instant", 300, 350, Duration::from_secs(1));
+/// let estimate = estimator.estimate("llama-3.1-8b-instant").unwrap();
+/// assert_eq!(estimate.tokens_per_second, 200.0);
+/// assert_eq!(estimate.samples, 2);
+/// ```
+#[derive(Clone)]
+pub struct ThroughputEstimator {
+    smoothing: f64,
+    models: Arc<Mutex<HashMap<String, ThroughputEstimate>>>,
+}
+
+impl ThroughputEstimator {
+    /// Creates an estimator weighting each new response by `0.2`
+    pub fn new() -> Self {
+        Self {
+            smoothing: DEFAULT_SMOOTHING,
+            models: Arc::default(),
+        }
+    }
+
+    /// Sets the weight of the newest response, clamped to `0.01..=1.0`
+    ///
+    /// Higher values follow changes faster; `1.0` keeps only the last response.
+    pub fn smoothing(mut self, smoothing: f64) -> Self {
+        self.smoothing = smoothing.clamp(0.01, 1.0);
+        self
+    }
+
+    /// Adds a response of `model` that generated `completion_tokens` in `elapsed`
+    ///
+    /// Responses without completion tokens or time only update the token counts.
+    pub fn record(&self, model: &str, completion_tokens: u32, total_tokens: u32, elapsed: Duration) {
+        let rate = (completion_tokens > 0 && !elapsed.is_zero())
+            .then(|| f64::from(completion_tokens) / elapsed.as_secs_f64());
+        let mut models = self.lock();
+        let Some(estimate) = models.get_mut(model) else {
+            models.insert(
+                model.to_string(),
+                ThroughputEstimate {
+                    tokens_per_second: rate.unwrap_or(0.0),
+                    completion_tokens: f64::from(completion_tokens),
+                    total_tokens: f64::from(total_tokens),
+                    samples: 1,
+                },
+            );
+            return;
+        };
+        let smooth = |current: f64, sample: f64| current + self.smoothing * (sample - current);
+        if let Some(rate) = rate {
+            estimate.tokens_per_second = match estimate.tokens_per_second {
+                current if current > 0.0 => smooth(current, rate),
+                _ => rate,
+            };
+        }
+        estimate.completion_tokens = smooth(estimate.completion_tokens, f64::from(completion_tokens));
+        estimate.total_tokens = smooth(estimate.total_tokens, f64::from(total_tokens));
+        estimate.samples += 1;
+    }
+
+    /// Adds `response` as a response of `model`
+    ///
+    /// Uses the `completion_time` reported in its usage, or `elapsed` when the
+    /// API sent none.
+    pub fn record_response(&self, model: &str, response: &ChatCompletionResponse, elapsed: Duration) {
+        self.record_usage(model, &response.usage, elapsed);
+    }
+
+    /// Adds the usage of a response or stream of `model`
+    pub(crate) fn record_usage(&self, model: &str, usage: &Usage, elapsed: Duration) {
+        let generation = usage
+            .completion_time
+            .filter(|seconds| *seconds > 0.0)
+            .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+            .unwrap_or(elapsed);
+        self.record(model, usage.completion_tokens, usage.total_tokens, generation);
+    }
+
+    /// Returns the estimate of `model`, if a response of it was observed
+    pub fn estimate(&self, model: &str) -> Option<ThroughputEstimate> {
+        self.lock().get(model).copied()
+    }
+
+    /// Returns the estimates of every observed model
+    pub fn snapshot(&self) -> HashMap<String, ThroughputEstimate> {
+        self.lock().clone()
+    }
+
+    /// Returns the tokens `request` is expected to use
+    ///
+    /// Before a response of its model was observed, this is the estimated
+    /// prompt plus `max_completion_tokens`.
+    fn expected_tokens(&self, request: &ChatCompletionRequest) -> u64 {
+        match self.estimate(&request.model) {
+            Some(estimate) => estimate.total_tokens.ceil() as u64,
+            None => estimate_prompt_tokens(request) + request.max_completion_tokens.map_or(0, u64::from),
+        }
+    }
+
+    fn expected_duration(&self, model: &str) -> Option<Duration> {
+        self.estimate(model)?.expected_duration()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, ThroughputEstimate>> {
+        self.models.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for ThroughputEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Requests admitted by a [`Budget`] whose tokens are not counted yet
+#[derive(Default)]
+struct InFlight {
+    next_id: AtomicU64,
+    requests: Mutex<HashMap<u64, InFlightRequest>>,
+}
+
+struct InFlightRequest {
+    tokens: u64,
+    started: Instant,
+    duration: Option<Duration>,
+}
+
+impl InFlight {
+    /// Returns how long to wait if `expected` more tokens could exceed `max`
+    ///
+    /// A request is never held back when nothing is in flight, since waiting
+    /// would not change the count.
+    fn would_exceed(&self, used: u64, expected: u64, max: u64) -> Option<Duration> {
+        let requests = self.lock();
+        if requests.is_empty() {
+            return None;
+        }
+        let pending: u64 = requests.values().map(|request| request.tokens).sum();
+        if used + pending + expected <= max {
+            return None;
+        }
+        let settle = requests
+            .values()
+            .map(|request| match request.duration {
+                Some(duration) => duration.saturating_sub(request.started.elapsed()),
+                None => UNKNOWN_SETTLE,
+            })
+            .min()
+            .unwrap_or(UNKNOWN_SETTLE);
+        Some(settle.max(MIN_SETTLE))
+    }
+
+    fn reserve(self: &Arc<Self>, tokens: u64, duration: Option<Duration>) -> BudgetReservation {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.lock().insert(
+            id,
+            InFlightRequest {
+                tokens,
+                started: Instant::now(),
+                duration,
+            },
+        );
+        BudgetReservation {
+            slot: Some((self.clone(), id)),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, InFlightRequest>> {
+        self.requests.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Counts a request admitted by a [`Budget`] as in flight until dropped
+#[derive(Default)]
+pub(crate) struct BudgetReservation {
+    slot: Option<(Arc<InFlight>, u64)>,
+}
+
+impl Drop for BudgetReservation {
+    fn drop(&mut self) {
+        if let Some((in_flight, id)) = self.slot.take() {
+            in_flight.lock().remove(&id);
+        }
+    }
+}
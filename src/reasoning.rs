@@ -0,0 +1,216 @@
+//! Separating model reasoning from the final answer
+//!
+//! 推理内容与最终答案的分离模块
+//!
+//! Reasoning models either return their chain of thought inline, wrapped in
+//! `<think>` blocks (`reasoning_format: "raw"`), or in a separate `reasoning`
+//! field (`"parsed"`). A [`ReasoningStripper`] moves inline blocks into the
+//! `reasoning` field so callers always see the answer alone in `content`,
+//! whichever format the model used. Enable it per request with
+//! [`ChatRequestBuilder::strip_reasoning`](crate::api::chat::ChatRequestBuilder::strip_reasoning),
+//! which also applies to streams.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::reasoning::ReasoningStripper;
+//!
+//! let split = ReasoningStripper::new().split("<think>2 + 2 is 4</think>The answer is 4.");
+//! assert_eq!(split.reasoning.as_deref(), Some("2 + 2 is 4"));
+//! assert_eq!(split.answer, "The answer is 4.");
+//! ```
+
+use std::collections::HashMap;
+
+use futures::StreamExt;
+
+use crate::transport::ChatCompletionStream;
+use crate::types::{ChatCompletionResponse, MessageContent};
+
+/// Text split into reasoning and answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitReasoning {
+    /// Contents of all reasoning blocks, joined by blank lines
+    pub reasoning: Option<String>,
+    /// The text outside reasoning blocks, trimmed
+    pub answer: String,
+}
+
+/// Moves reasoning blocks out of message content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReasoningStripper {
+    open: String,
+    close: String,
+}
+
+impl Default for ReasoningStripper {
+    fn default() -> Self {
+        Self {
+            open: "<think>".to_string(),
+            close: "</think>".to_string(),
+        }
+    }
+}
+
+impl ReasoningStripper {
+    /// Creates a stripper for `<think>...</think>` blocks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses other block markers, e.g. `<reasoning>` and `</reasoning>`
+    pub fn markers(mut self, open: impl Into<String>, close: impl Into<String>) -> Self {
+        self.open = open.into();
+        self.close = close.into();
+        self
+    }
+
+    /// Splits `text` into reasoning and answer
+    ///
+    /// A block that is opened but never closed runs to the end of the text.
+    pub fn split(&self, text: &str) -> SplitReasoning {
+        let mut filter = ReasoningFilter::new(self.clone());
+        let (mut reasoning, mut answer) = filter.push(text);
+        let (rest_reasoning, rest_answer) = filter.finish();
+        reasoning.push_str(&rest_reasoning);
+        answer.push_str(&rest_answer);
+        let blocks: Vec<&str> = reasoning
+            .split('\0')
+            .map(str::trim)
+            .filter(|b| !b.is_empty())
+            .collect();
+        SplitReasoning {
+            reasoning: (!blocks.is_empty()).then(|| blocks.join("\n\n")),
+            answer: answer.trim().to_string(),
+        }
+    }
+
+    /// Moves reasoning blocks of every choice into `Choice::reasoning`
+    ///
+    /// Choices whose reasoning the API already returned separately
+    /// (`reasoning_format: "parsed"`) have no blocks and are left unchanged.
+    pub fn apply(&self, response: &mut ChatCompletionResponse) {
+        for choice in &mut response.choices {
+            let MessageContent::Text(text) = &choice.message.content else {
+                continue;
+            };
+            let split = self.split(text);
+            if let Some(reasoning) = split.reasoning {
+                choice.reasoning = Some(match choice.reasoning.take() {
+                    Some(existing) => format!("{}\n\n{}", existing, reasoning),
+                    None => reasoning,
+                });
+                choice.message.content = MessageContent::Text(split.answer);
+            }
+        }
+    }
+
+    /// Moves reasoning blocks in streamed content deltas into `MessageDelta::reasoning`
+    ///
+    /// Markers split across chunks are recognized; text that might start a
+    /// marker is held back until the next chunk or the choice's final chunk.
+    pub fn strip_stream(&self, stream: ChatCompletionStream) -> ChatCompletionStream {
+        let stripper = self.clone();
+        let mut filters: HashMap<i32, ReasoningFilter> = HashMap::new();
+        Box::pin(stream.map(move |item| {
+            let mut chunk = item?;
+            for choice in &mut chunk.choices {
+                let filter = filters
+                    .entry(choice.index)
+                    .or_insert_with(|| ReasoningFilter::new(stripper.clone()));
+                let (mut reasoning, mut answer) = match &choice.delta.content {
+                    Some(MessageContent::Text(text)) => filter.push(text),
+                    _ => (String::new(), String::new()),
+                };
+                if choice.finish_reason.is_some() {
+                    let (rest_reasoning, rest_answer) = filter.finish();
+                    reasoning.push_str(&rest_reasoning);
+                    answer.push_str(&rest_answer);
+                }
+                if matches!(choice.delta.content, Some(MessageContent::Text(_))) || !answer.is_empty() {
+                    choice.delta.content = (!answer.is_empty()).then_some(MessageContent::Text(answer));
+                }
+                let reasoning = reasoning.replace('\0', "");
+                if !reasoning.is_empty() {
+                    choice.delta.reasoning.get_or_insert_with(String::new).push_str(&reasoning);
+                }
+            }
+            Ok(chunk)
+        }))
+    }
+}
+
+/// Incremental splitter that tolerates markers cut across pushes
+///
+/// Reasoning blocks are separated by `\0` in the reasoning output so callers
+/// can tell them apart.
+struct ReasoningFilter {
+    stripper: ReasoningStripper,
+    inside: bool,
+    pending: String,
+    answer_started: bool,
+}
+
+impl ReasoningFilter {
+    fn new(stripper: ReasoningStripper) -> Self {
+        Self {
+            stripper,
+            inside: false,
+            pending: String::new(),
+            answer_started: false,
+        }
+    }
+
+    /// Returns the reasoning and answer text that is certain after `text`
+    fn push(&mut self, text: &str) -> (String, String) {
+        self.pending.push_str(text);
+        let mut reasoning = String::new();
+        let mut answer = String::new();
+        loop {
+            let marker = if self.inside { &self.stripper.close } else { &self.stripper.open };
+            let out = if self.inside { &mut reasoning } else { &mut answer };
+            match self.pending.find(marker.as_str()) {
+                Some(i) => {
+                    out.push_str(&self.pending[..i]);
+                    if self.inside {
+                        out.push('\0');
+                    }
+                    self.pending.drain(..i + marker.len());
+                    self.inside = !self.inside;
+                }
+                None => {
+                    // Keep back a suffix that could be the start of the marker
+                    let keep = (1..marker.len())
+                        .rev()
+                        .find(|&n| marker.is_char_boundary(n) && self.pending.ends_with(&marker[..n]))
+                        .unwrap_or(0);
+                    let cut = self.pending.len() - keep;
+                    out.push_str(&self.pending[..cut]);
+                    self.pending.drain(..cut);
+                    let answer = self.start_answer(answer);
+                    return (reasoning, answer);
+                }
+            }
+        }
+    }
+
+    /// Returns whatever text is still held back
+    fn finish(&mut self) -> (String, String) {
+        let rest = std::mem::take(&mut self.pending);
+        if self.inside {
+            (rest, String::new())
+        } else {
+            (String::new(), self.start_answer(rest))
+        }
+    }
+
+    /// Drops whitespace before the first answer text, e.g. after a closing marker
+    fn start_answer(&mut self, answer: String) -> String {
+        if self.answer_started {
+            return answer;
+        }
+        let answer = answer.trim_start().to_string();
+        self.answer_started = !answer.is_empty();
+        answer
+    }
+}
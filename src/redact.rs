@@ -0,0 +1,285 @@
+//! PII and profanity redaction of transcripts
+//!
+//! 转录文本的个人信息与不当用语脱敏模块
+//!
+//! A [`Redactor`] masks email addresses, phone numbers and payment card
+//! numbers by default, plus any patterns or word lists you add. It can also
+//! run a chat-model pass to catch what patterns miss, such as names and
+//! street addresses. Results keep the raw text next to the redacted one so
+//! audit trails can be stored separately.
+//!
+//! Requires the `redact` feature.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::redact::Redactor;
+//! use groqai::{AudioTranscriptionRequest, GroqClient};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # groqai::doctest::set_api_key();
+//! let client = GroqClient::new()?;
+//! # let client = groqai::doctest::client();
+//! let transcription = client
+//!     .audio()
+//!     .transcribe(AudioTranscriptionRequest {
+//!         file: Some("support-call.mp3".into()),
+//!         model: "whisper-large-v3".to_string(),
+//!         ..Default::default()
+//!     })
+//!     .await?;
+//!
+//! let redactor = Redactor::new()
+//!     .words("PROFANITY", ["darn", "heck"])
+//!     .chat_pass("llama-3.1-8b-instant");
+//! let redacted = redactor.redact_with(&client, &transcription.text).await?;
+//! println!("{}", redacted.redacted);
+//! # Ok(())
+//! # }
+//! ```
+
+use regex::Regex;
+
+use crate::client::GroqClient;
+use crate::error::GroqError;
+use crate::types::{ChatMessage, Role, Transcription};
+
+const EMAIL: &str = r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}";
+const PHONE: &str = r"(?:\+\d{1,3}[\s.-]?)?(?:\(\d{2,4}\)[\s.-]?)?\d{2,4}(?:[\s.-]\d{2,4}){1,3}";
+const CARD: &str = r"\b\d(?:[ -]?\d){12,18}\b";
+
+/// A masked span of the raw text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedactedSpan {
+    /// Label of the rule that matched, e.g. "EMAIL"
+    pub label: String,
+    /// Byte offset of the start of the match in the raw text
+    pub start: usize,
+    /// Byte offset of the end of the match in the raw text
+    pub end: usize,
+}
+
+/// Raw and redacted versions of a text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redacted {
+    /// The original text
+    pub raw: String,
+    /// The text with matches replaced by `[LABEL]`
+    pub redacted: String,
+    /// Spans masked by pattern and word rules (not by the chat pass)
+    pub spans: Vec<RedactedSpan>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    label: String,
+    regex: Regex,
+    /// Extra check on a match, e.g. the Luhn checksum for card numbers
+    verify: Option<fn(&str) -> bool>,
+}
+
+/// Masks personal data and unwanted words in text
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    rules: Vec<Rule>,
+    chat_model: Option<String>,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Redactor {
+    /// Creates a redactor for emails (`EMAIL`), card numbers (`CARD`) and
+    /// phone numbers (`PHONE`)
+    ///
+    /// Card numbers must pass the Luhn checksum, and phone numbers need at
+    /// least seven digits, to limit false positives on ordinary figures.
+    pub fn new() -> Self {
+        let builtin = |label: &str, pattern: &str, verify: fn(&str) -> bool| Rule {
+            label: label.to_string(),
+            regex: Regex::new(pattern).expect("built-in pattern is valid"),
+            verify: Some(verify),
+        };
+        Self {
+            rules: vec![
+                builtin("EMAIL", EMAIL, |_| true),
+                builtin("CARD", CARD, luhn_valid),
+                builtin("PHONE", PHONE, |m| m.chars().filter(char::is_ascii_digit).count() >= 7),
+            ],
+            chat_model: None,
+        }
+    }
+
+    /// Creates a redactor without any rules
+    pub fn empty() -> Self {
+        Self {
+            rules: Vec::new(),
+            chat_model: None,
+        }
+    }
+
+    /// Adds a regular expression whose matches are replaced by `[label]`
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if the pattern is not a valid regex.
+    pub fn pattern(mut self, label: impl Into<String>, pattern: &str) -> Result<Self, GroqError> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| GroqError::InvalidMessage(format!("Invalid redaction pattern: {}", e)))?;
+        self.rules.push(Rule {
+            label: label.into(),
+            regex,
+            verify: None,
+        });
+        Ok(self)
+    }
+
+    /// Masks whole-word, case-insensitive occurrences of `words` as `[label]`
+    ///
+    /// Use it for profanity or internal code names.
+    pub fn words<I, S>(mut self, label: impl Into<String>, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let alternatives: Vec<String> = words
+            .into_iter()
+            .map(|w| regex::escape(w.as_ref()))
+            .filter(|w| !w.is_empty())
+            .collect();
+        if !alternatives.is_empty() {
+            let pattern = format!(r"(?i)\b(?:{})\b", alternatives.join("|"));
+            self.rules.push(Rule {
+                label: label.into(),
+                regex: Regex::new(&pattern).expect("escaped words form a valid pattern"),
+                verify: None,
+            });
+        }
+        self
+    }
+
+    /// Also asks `model` to mask personal data the rules missed
+    ///
+    /// Only used by [`redact_with`](Self::redact_with). The model sees text
+    /// already masked by the rules.
+    pub fn chat_pass(mut self, model: impl Into<String>) -> Self {
+        self.chat_model = Some(model.into());
+        self
+    }
+
+    /// Applies the pattern and word rules
+    pub fn redact(&self, text: &str) -> Redacted {
+        // Collect matches from every rule, then keep the earliest, longest
+        // non-overlapping ones
+        let mut spans: Vec<RedactedSpan> = Vec::new();
+        for rule in &self.rules {
+            for m in rule.regex.find_iter(text) {
+                if rule.verify.is_none_or(|verify| verify(m.as_str())) {
+                    spans.push(RedactedSpan {
+                        label: rule.label.clone(),
+                        start: m.start(),
+                        end: m.end(),
+                    });
+                }
+            }
+        }
+        spans.sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+        let mut kept: Vec<RedactedSpan> = Vec::new();
+        for span in spans {
+            if kept.last().is_none_or(|last| span.start >= last.end) {
+                kept.push(span);
+            }
+        }
+
+        let mut redacted = String::with_capacity(text.len());
+        let mut cursor = 0;
+        for span in &kept {
+            redacted.push_str(&text[cursor..span.start]);
+            redacted.push_str(&format!("[{}]", span.label));
+            cursor = span.end;
+        }
+        redacted.push_str(&text[cursor..]);
+        Redacted {
+            raw: text.to_string(),
+            redacted,
+            spans: kept,
+        }
+    }
+
+    /// Applies the rules, then the chat-model pass if one is configured
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError` if the chat request fails, and
+    /// `GroqError::InvalidMessage` if the reply has no choices or was cut off
+    /// at the token limit, rather than returning partially redacted text.
+    pub async fn redact_with(&self, client: &GroqClient, text: &str) -> Result<Redacted, GroqError> {
+        let mut result = self.redact(text);
+        let Some(model) = &self.chat_model else {
+            return Ok(result);
+        };
+        let response = client
+            .chat(model.clone())
+            .message(ChatMessage::new_text(
+                Role::System,
+                "Redact personal data in the user's text: replace names of people, street \
+                 addresses, account or ID numbers and dates of birth with [REDACTED]. Keep \
+                 everything else, including existing [LABEL] markers, exactly as written. \
+                 Reply with the redacted text only.",
+            ))
+            .message(ChatMessage::new_text(Role::User, result.redacted.clone()))
+            .temperature(0.0)
+            .max_completion_tokens((crate::text::estimate_tokens(&result.redacted) * 2 + 64).min(32_768) as u32)
+            .send()
+            .await?;
+        let choice = response
+            .choices
+            .first()
+            .ok_or_else(|| GroqError::InvalidMessage("Response contained no choices".to_string()))?;
+        // 截断的回复会丢失原文，不能当作脱敏结果
+        if choice.finish_reason.as_deref() == Some("length") {
+            return Err(GroqError::InvalidMessage(
+                "Redaction reply was cut off at the token limit".to_string(),
+            ));
+        }
+        result.redacted = choice.message.content.to_string();
+        Ok(result)
+    }
+}
+
+impl Transcription {
+    /// Redacts the transcript text with `redactor`'s rules
+    pub fn redact(&self, redactor: &Redactor) -> Redacted {
+        redactor.redact(&self.text)
+    }
+}
+
+/// Checks the Luhn checksum of the digits in `candidate`
+fn luhn_valid(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().filter_map(|c| c.to_digit(10)).collect();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
@@ -0,0 +1,162 @@
+//! Redis-backed response cache and budget store
+//!
+//! 基于 Redis 的响应缓存与预算计数存储
+//!
+//! Lets horizontally scaled services share cache hits and request/token
+//! budgets: every instance pointing at the same Redis sees the same entries
+//! and counters. Requires the `redis` feature.
+//!
+//! # Examples
+//!
+//! The example needs a running Redis server, so it is compiled but not run.
+//!
+//! ```rust,no_run
+//! use groqai::rate_limit::Budget;
+//! use groqai::redis::{RedisBudgetStore, RedisCache};
+//! use groqai::GroqClientBuilder;
+//! use std::time::Duration;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let url = "redis://127.0.0.1/";
+//! let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+//!     .response_cache(RedisCache::connect(url).await?.ttl(Duration::from_secs(3600)))
+//!     .budget(
+//!         Budget::new(Duration::from_secs(60))
+//!             .max_requests(30)
+//!             .store(RedisBudgetStore::connect(url).await?),
+//!     )
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::Duration;
+
+use ::redis::aio::ConnectionManager;
+use ::redis::AsyncCommands;
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+
+use crate::cache::ResponseCache;
+use crate::error::GroqError;
+use crate::rate_limit::BudgetStore;
+use crate::types::ChatCompletionResponse;
+
+fn redis_error(e: ::redis::RedisError) -> GroqError {
+    GroqError::InvalidMessage(format!("Redis error: {}", e))
+}
+
+async fn connect(url: &str) -> Result<ConnectionManager, GroqError> {
+    let client = ::redis::Client::open(url).map_err(redis_error)?;
+    ConnectionManager::new(client).await.map_err(redis_error)
+}
+
+/// [`ResponseCache`] storing responses as JSON in Redis
+///
+/// Keys are the SHA-256 of the cache key under a prefix (`groqai:cache:` by
+/// default), so prompts never appear in key names.
+#[derive(Clone)]
+pub struct RedisCache {
+    conn: ConnectionManager,
+    prefix: String,
+    ttl: Option<Duration>,
+}
+
+impl RedisCache {
+    /// Wraps an existing connection
+    pub fn new(conn: ConnectionManager) -> Self {
+        Self {
+            conn,
+            prefix: "groqai:cache:".to_string(),
+            ttl: None,
+        }
+    }
+
+    /// Connects to the Redis server at `url`
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if the URL is invalid or the server
+    /// cannot be reached.
+    pub async fn connect(url: &str) -> Result<Self, GroqError> {
+        Ok(Self::new(connect(url).await?))
+    }
+
+    /// Sets the key prefix
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Expires entries after `ttl`
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    fn redis_key(&self, key: &str) -> String {
+        let digest = Sha256::digest(key.as_bytes());
+        let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        format!("{}{}", self.prefix, hex)
+    }
+}
+
+#[async_trait]
+impl ResponseCache for RedisCache {
+    async fn get(&self, key: &str) -> Result<Option<ChatCompletionResponse>, GroqError> {
+        let mut conn = self.conn.clone();
+        let value: Option<String> = conn.get(self.redis_key(key)).await.map_err(redis_error)?;
+        value.map(|json| serde_json::from_str(&json)).transpose().map_err(Into::into)
+    }
+
+    async fn put(&self, key: &str, response: &ChatCompletionResponse) -> Result<(), GroqError> {
+        let mut conn = self.conn.clone();
+        let json = serde_json::to_string(response)?;
+        let key = self.redis_key(key);
+        match self.ttl {
+            Some(ttl) => conn.set_ex::<_, _, ()>(key, json, ttl.as_secs().max(1)).await,
+            None => conn.set::<_, _, ()>(key, json).await,
+        }
+        .map_err(redis_error)
+    }
+}
+
+/// [`BudgetStore`] keeping counters in Redis
+#[derive(Clone)]
+pub struct RedisBudgetStore {
+    conn: ConnectionManager,
+}
+
+impl RedisBudgetStore {
+    /// Wraps an existing connection
+    pub fn new(conn: ConnectionManager) -> Self {
+        Self { conn }
+    }
+
+    /// Connects to the Redis server at `url`
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if the URL is invalid or the server
+    /// cannot be reached.
+    pub async fn connect(url: &str) -> Result<Self, GroqError> {
+        Ok(Self::new(connect(url).await?))
+    }
+}
+
+#[async_trait]
+impl BudgetStore for RedisBudgetStore {
+    async fn increment(&self, key: &str, amount: u64, ttl: Duration) -> Result<u64, GroqError> {
+        let mut conn = self.conn.clone();
+        let (value,): (u64,) = ::redis::pipe()
+            .atomic()
+            .incr(key, amount)
+            .expire(key, ttl.as_secs().max(1) as i64)
+            .ignore()
+            .query_async(&mut conn)
+            .await
+            .map_err(redis_error)?;
+        Ok(value)
+    }
+}
@@ -0,0 +1,379 @@
+//! Strict-mode JSON schema adjustments
+//!
+//! 严格模式 JSON Schema 自动调整模块
+//!
+//! Structured outputs with `"strict": true` only accept a subset of JSON
+//! Schema: every object must set `additionalProperties: false` and list all of
+//! its properties in `required`. [`make_strict`] rewrites a schema to meet
+//! these rules, turning optional properties into nullable required ones, and
+//! reports every construct strict mode cannot express. Chat requests whose
+//! `json_schema` response format is strict are rewritten automatically before
+//! they are sent, so an unsupported schema fails locally with the exact
+//! location instead of with a 400 from the API.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::schema::make_strict;
+//! use serde_json::json;
+//!
+//! let schema = json!({
+//!     "type": "object",
+//!     "properties": {
+//!         "city": {"type": "string"},
+//!         "zip": {"type": "string"}
+//!     },
+//!     "required": ["city"]
+//! });
+//! let strict = make_strict(&schema).unwrap();
+//! assert_eq!(strict["additionalProperties"], json!(false));
+//! assert_eq!(strict["required"], json!(["city", "zip"]));
+//! assert_eq!(strict["properties"]["zip"]["type"], json!(["string", "null"]));
+//! ```
+//!
+//! With the `schema-export` feature, [`export`] produces JSON Schemas of the
+//! SDK's request and response types, so gateways can validate payloads and
+//! clients in other languages can be generated from the same definitions.
+
+#[cfg(feature = "schema-export")]
+use std::collections::BTreeMap;
+use std::fmt;
+#[cfg(feature = "schema-export")]
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::api::chat::ChatCompletionRequest;
+use crate::error::GroqError;
+
+/// Keywords strict mode does not support
+const UNSUPPORTED_KEYWORDS: &[&str] = &[
+    "allOf",
+    "oneOf",
+    "not",
+    "if",
+    "then",
+    "else",
+    "dependentRequired",
+    "dependentSchemas",
+    "patternProperties",
+    "unevaluatedProperties",
+    "unevaluatedItems",
+    "propertyNames",
+    "prefixItems",
+    "contains",
+];
+
+/// A schema construct that strict mode cannot express
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StrictSchemaIssue {
+    /// JSON pointer to the construct within the schema ("" for the root)
+    pub path: String,
+    /// Human readable description of the problem
+    pub message: String,
+}
+
+impl fmt::Display for StrictSchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() { "/" } else { &self.path };
+        write!(f, "{}: {}", path, self.message)
+    }
+}
+
+/// Rewrites `schema` to satisfy strict-mode constraints
+///
+/// Every object gets `additionalProperties: false` and a `required` list with
+/// all of its properties; properties that were optional become nullable.
+/// Nested objects are rewritten through `properties`, `items`, `anyOf`,
+/// `$defs` and `definitions`.
+///
+/// # Errors
+///
+/// Returns every construct that cannot be made strict: a root that is not an
+/// object, `additionalProperties` other than `false`, boolean schemas, tuple
+/// `items`, and the keywords `allOf`, `oneOf`, `not`, `if`/`then`/`else`,
+/// `dependentRequired`, `dependentSchemas`, `patternProperties`,
+/// `unevaluatedProperties`, `unevaluatedItems`, `propertyNames`,
+/// `prefixItems` and `contains`.
+pub fn make_strict(schema: &Value) -> Result<Value, Vec<StrictSchemaIssue>> {
+    let mut schema = schema.clone();
+    let mut issues = Vec::new();
+    if !is_object_schema(&schema) {
+        issues.push(issue("", "the root schema must be an object"));
+    }
+    rewrite(&mut schema, "", &mut issues);
+    if issues.is_empty() {
+        Ok(schema)
+    } else {
+        Err(issues)
+    }
+}
+
+/// Returns the JSON Schemas of every request and response type, keyed by type name
+///
+/// Schemas are JSON Schema draft 2020-12 documents with their nested types
+/// under `$defs`. Request schemas describe the JSON this SDK sends and
+/// response schemas the JSON it accepts, so fields the SDK fills in with a
+/// default are optional in responses. [`FileCreateRequest`](crate::FileCreateRequest)
+/// describes the fields of the multipart upload form. Types of API features
+/// that are not enabled, such as `audio`, are left out.
+///
+/// # Examples
+///
+/// ```rust
+/// let schemas = groqai::schema::export();
+/// let request = &schemas["ChatCompletionRequest"];
+/// assert!(request["required"].as_array().unwrap().contains(&"messages".into()));
+/// assert!(schemas.contains_key("ChatCompletionResponse"));
+/// ```
+#[cfg(feature = "schema-export")]
+pub fn export() -> BTreeMap<String, Value> {
+    #[cfg(feature = "audio")]
+    use crate::api::audio::{AudioSpeechRequest, AudioTranscriptionRequest, AudioTranslationRequest};
+    #[cfg(feature = "batches")]
+    use crate::api::batches::BatchCreateRequest;
+    #[cfg(feature = "files")]
+    use crate::api::files::FileCreateRequest;
+    #[cfg(feature = "fine-tuning")]
+    use crate::api::fine_tunings::{FineTuning, FineTuningCreateRequest, FineTuningEventList, FineTuningList};
+    use crate::error::ErrorBody;
+    use crate::types::{ChatCompletionChunk, ChatCompletionResponse, Model, ModelList};
+    #[cfg(feature = "audio")]
+    use crate::types::{Transcription, Translation};
+    #[cfg(feature = "batches")]
+    use crate::types::{Batch, BatchList};
+    #[cfg(feature = "files")]
+    use crate::types::{WorkFile, WorkFileDeletion, WorkFileList};
+    use schemars::generate::SchemaSettings;
+    use schemars::JsonSchema;
+
+    fn add<T: JsonSchema>(schemas: &mut BTreeMap<String, Value>, settings: &SchemaSettings) {
+        let schema = settings.clone().into_generator().root_schema_for::<T>();
+        schemas.insert(T::schema_name().into_owned(), schema.to_value());
+    }
+
+    let requests = SchemaSettings::draft2020_12().for_serialize();
+    let responses = SchemaSettings::draft2020_12().for_deserialize();
+    let mut schemas = BTreeMap::new();
+    add::<ChatCompletionRequest>(&mut schemas, &requests);
+    add::<ChatCompletionResponse>(&mut schemas, &responses);
+    add::<ChatCompletionChunk>(&mut schemas, &responses);
+    add::<Model>(&mut schemas, &responses);
+    add::<ModelList>(&mut schemas, &responses);
+    #[cfg(feature = "audio")]
+    {
+        add::<AudioTranscriptionRequest>(&mut schemas, &requests);
+        add::<AudioTranslationRequest>(&mut schemas, &requests);
+        add::<AudioSpeechRequest>(&mut schemas, &requests);
+        add::<Transcription>(&mut schemas, &responses);
+        add::<Translation>(&mut schemas, &responses);
+    }
+    #[cfg(feature = "files")]
+    {
+        add::<FileCreateRequest>(&mut schemas, &requests);
+        add::<WorkFile>(&mut schemas, &responses);
+        add::<WorkFileList>(&mut schemas, &responses);
+        add::<WorkFileDeletion>(&mut schemas, &responses);
+    }
+    #[cfg(feature = "batches")]
+    {
+        add::<BatchCreateRequest>(&mut schemas, &requests);
+        add::<Batch>(&mut schemas, &responses);
+        add::<BatchList>(&mut schemas, &responses);
+    }
+    #[cfg(feature = "fine-tuning")]
+    {
+        add::<FineTuningCreateRequest>(&mut schemas, &requests);
+        add::<FineTuning>(&mut schemas, &responses);
+        add::<FineTuningList>(&mut schemas, &responses);
+        add::<FineTuningEventList>(&mut schemas, &responses);
+    }
+    add::<ErrorBody>(&mut schemas, &responses);
+    schemas
+}
+
+/// Writes every schema from [`export`] to `dir` as `<TypeName>.schema.json`
+///
+/// Creates `dir` if it does not exist. Returns the written paths.
+///
+/// # Errors
+///
+/// Returns `GroqError::InvalidMessage` if the directory or a file cannot be written
+#[cfg(feature = "schema-export")]
+pub fn export_to_dir(dir: impl AsRef<Path>) -> Result<Vec<std::path::PathBuf>, GroqError> {
+    let io_err = |e: std::io::Error| GroqError::InvalidMessage(format!("Failed to write schema: {}", e));
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir).map_err(io_err)?;
+    export()
+        .into_iter()
+        .map(|(name, schema)| {
+            let path = dir.join(format!("{}.schema.json", name));
+            std::fs::write(&path, serde_json::to_string_pretty(&schema)?).map_err(io_err)?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Rewrites the schema of a strict `json_schema` response format in place
+///
+/// Requests without one are left unchanged.
+pub(crate) fn apply_strict_mode(request: &mut ChatCompletionRequest) -> Result<(), GroqError> {
+    let Some(format) = &mut request.response_format else {
+        return Ok(());
+    };
+    let Some(json_schema) = format.json_schema.as_mut().filter(|_| format.type_ == "json_schema") else {
+        return Ok(());
+    };
+    if json_schema.get("strict") != Some(&Value::Bool(true)) {
+        return Ok(());
+    }
+    let Some(schema) = json_schema.get_mut("schema") else {
+        return Ok(());
+    };
+    match make_strict(schema) {
+        Ok(strict) => {
+            *schema = strict;
+            Ok(())
+        }
+        Err(issues) => {
+            let described: Vec<String> = issues.iter().map(ToString::to_string).collect();
+            Err(GroqError::InvalidMessage(format!(
+                "Response schema is not supported in strict mode: {}",
+                described.join("; ")
+            )))
+        }
+    }
+}
+
+fn issue(path: &str, message: impl Into<String>) -> StrictSchemaIssue {
+    StrictSchemaIssue {
+        path: path.to_string(),
+        message: message.into(),
+    }
+}
+
+/// Appends `key` to a JSON pointer, escaping `~` and `/` as RFC 6901 requires
+pub(crate) fn pointer(path: &str, key: &str) -> String {
+    format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn is_object_schema(schema: &Value) -> bool {
+    match schema.get("type") {
+        Some(Value::String(t)) => t == "object",
+        Some(Value::Array(types)) => types.iter().any(|t| t == "object"),
+        _ => schema.get("properties").is_some(),
+    }
+}
+
+fn rewrite(schema: &mut Value, path: &str, issues: &mut Vec<StrictSchemaIssue>) {
+    let is_object = is_object_schema(schema);
+    let Some(map) = schema.as_object_mut() else {
+        if schema.is_boolean() {
+            issues.push(issue(path, "boolean schemas are not supported"));
+        }
+        return;
+    };
+    for keyword in UNSUPPORTED_KEYWORDS {
+        if map.contains_key(*keyword) {
+            issues.push(issue(&pointer(path, keyword), format!("`{}` is not supported", keyword)));
+        }
+    }
+
+    if is_object {
+        match map.get("additionalProperties") {
+            None | Some(Value::Bool(false)) => {
+                map.insert("additionalProperties".to_string(), Value::Bool(false));
+            }
+            Some(_) => issues.push(issue(
+                &pointer(path, "additionalProperties"),
+                "`additionalProperties` must be false",
+            )),
+        }
+    }
+    let required: Vec<String> = map
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+    if let Some(Value::Object(properties)) = map.get_mut("properties") {
+        let properties_path = pointer(path, "properties");
+        for (name, property) in properties.iter_mut() {
+            rewrite(property, &pointer(&properties_path, name), issues);
+            // Made nullable after rewriting so issues point at the original schema
+            if is_object && !required.contains(name) {
+                make_nullable(property);
+            }
+        }
+    }
+    if is_object {
+        let all = match map.get("properties") {
+            Some(Value::Object(properties)) => properties.keys().cloned().map(Value::String).collect(),
+            _ => Vec::new(),
+        };
+        map.insert("required".to_string(), Value::Array(all));
+    }
+    match map.get_mut("items") {
+        Some(Value::Array(_)) => issues.push(issue(&pointer(path, "items"), "tuple `items` are not supported")),
+        Some(items) => rewrite(items, &pointer(path, "items"), issues),
+        None => {}
+    }
+    if let Some(Value::Array(variants)) = map.get_mut("anyOf") {
+        let any_of_path = pointer(path, "anyOf");
+        for (i, variant) in variants.iter_mut().enumerate() {
+            rewrite(variant, &pointer(&any_of_path, &i.to_string()), issues);
+        }
+    }
+    for keyword in ["$defs", "definitions"] {
+        if let Some(Value::Object(definitions)) = map.get_mut(keyword) {
+            let definitions_path = pointer(path, keyword);
+            for (name, definition) in definitions.iter_mut() {
+                rewrite(definition, &pointer(&definitions_path, name), issues);
+            }
+        }
+    }
+}
+
+/// Lets `schema` also accept `null`
+fn make_nullable(schema: &mut Value) {
+    let null = Value::String("null".to_string());
+    let Some(map) = schema.as_object_mut() else {
+        return;
+    };
+    let has_enum = match map.get_mut("enum") {
+        Some(Value::Array(values)) => {
+            if !values.contains(&Value::Null) {
+                values.push(Value::Null);
+            }
+            true
+        }
+        _ => false,
+    };
+    match map.get_mut("type") {
+        Some(Value::String(t)) if t != "null" => {
+            let t = Value::String(std::mem::take(t));
+            map.insert("type".to_string(), Value::Array(vec![t, null]));
+            return;
+        }
+        Some(Value::Array(types)) => {
+            if !types.contains(&null) {
+                types.push(null);
+            }
+            return;
+        }
+        Some(_) => return,
+        None => {}
+    }
+    // An enum without a type accepts null once null is one of its values
+    if has_enum {
+        return;
+    }
+    if let Some(Value::Array(variants)) = map.get_mut("anyOf") {
+        if !variants.iter().any(|v| v.get("type") == Some(&null)) {
+            variants.push(json!({"type": "null"}));
+        }
+        return;
+    }
+    let inner = std::mem::take(schema);
+    *schema = json!({"anyOf": [inner, {"type": "null"}]});
+}
@@ -0,0 +1,190 @@
+//! Graceful client shutdown
+//!
+//! 客户端优雅关闭模块
+//!
+//! [`GroqClient::shutdown`] stops a client (and all its clones) from starting
+//! new requests, waits for requests and streams already in flight to finish,
+//! and aborts whatever is still running when the grace period ends. Services
+//! holding long streams call it on SIGTERM during rolling deploys.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use groqai::GroqClient;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # groqai::doctest::set_api_key();
+//! let client = GroqClient::new()?;
+//! # let client = groqai::doctest::client();
+//! // ... serve traffic until the process is asked to stop ...
+//! let report = client.shutdown(Duration::from_secs(30)).await;
+//! println!("{} of {} requests aborted", report.aborted, report.in_flight);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+use crate::client::GroqClient;
+use crate::error::GroqError;
+use crate::metrics::TransportMetrics;
+use crate::transport::{ByteStream, Request, Response, Transport};
+
+/// Outcome of [`GroqClient::shutdown`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// Requests and streams in flight when shutdown started
+    pub in_flight: usize,
+    /// Requests and streams aborted when the grace period ended
+    pub aborted: usize,
+}
+
+/// Shared state of a client's requests
+#[derive(Default)]
+pub(crate) struct Lifecycle {
+    closing: AtomicBool,
+    in_flight: AtomicUsize,
+    idle: Notify,
+    abort: CancellationToken,
+}
+
+/// Counts one request or stream as in flight until dropped
+struct InFlight(Arc<Lifecycle>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+impl Lifecycle {
+    fn enter(self: &Arc<Self>) -> Result<InFlight, GroqError> {
+        // Count the request before checking, so shutdown never misses it
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let guard = InFlight(self.clone());
+        if self.closing.load(Ordering::SeqCst) {
+            return Err(GroqError::Shutdown);
+        }
+        Ok(guard)
+    }
+
+    async fn shutdown(&self, grace: Duration) -> ShutdownReport {
+        self.closing.store(true, Ordering::SeqCst);
+        let in_flight = self.in_flight.load(Ordering::SeqCst);
+        let drained = tokio::time::timeout(grace, async {
+            loop {
+                let idle = self.idle.notified();
+                if self.in_flight.load(Ordering::SeqCst) == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        })
+        .await
+        .is_ok();
+        let aborted = if drained {
+            0
+        } else {
+            let remaining = self.in_flight.load(Ordering::SeqCst);
+            self.abort.cancel();
+            remaining
+        };
+        ShutdownReport { in_flight, aborted }
+    }
+}
+
+/// A [`Transport`] that tracks in-flight calls so they can be drained
+pub(crate) struct DrainingTransport {
+    inner: Arc<dyn Transport>,
+    lifecycle: Arc<Lifecycle>,
+}
+
+impl DrainingTransport {
+    pub(crate) fn new(inner: Arc<dyn Transport>, lifecycle: Arc<Lifecycle>) -> Self {
+        Self { inner, lifecycle }
+    }
+
+    /// Runs `call` unless the client is closing, aborting it on shutdown
+    async fn guarded<T>(&self, call: impl Future<Output = Result<T, GroqError>>) -> Result<T, GroqError> {
+        let _in_flight = self.lifecycle.enter()?;
+        self.abortable(call).await
+    }
+
+    async fn abortable<T>(&self, call: impl Future<Output = Result<T, GroqError>>) -> Result<T, GroqError> {
+        tokio::select! {
+            result = call => result,
+            _ = self.lifecycle.abort.cancelled() => Err(GroqError::Shutdown),
+        }
+    }
+
+    /// Opens a stream that counts as in flight until it ends or is dropped
+    async fn guarded_stream(
+        &self,
+        open: impl Future<Output = Result<ByteStream, GroqError>>,
+    ) -> Result<ByteStream, GroqError> {
+        let in_flight = self.lifecycle.enter()?;
+        let stream = self.abortable(open).await?;
+        let abort = self.lifecycle.abort.clone();
+        let stream = futures::stream::unfold(
+            (Some(stream), in_flight, abort),
+            |(stream, in_flight, abort)| async move {
+                let mut stream = stream?;
+                tokio::select! {
+                    item = stream.next() => item.map(|item| (item, (Some(stream), in_flight, abort))),
+                    // End the stream with an error after the abort
+                    _ = abort.cancelled() => Some((Err(GroqError::Shutdown), (None, in_flight, abort))),
+                }
+            },
+        );
+        Ok(Box::pin(stream))
+    }
+}
+
+#[async_trait]
+impl Transport for DrainingTransport {
+    async fn execute(&self, request: Request) -> Result<Response, GroqError> {
+        self.guarded(self.inner.execute(request)).await
+    }
+
+    async fn execute_stream(&self, request: Request) -> Result<ByteStream, GroqError> {
+        self.guarded_stream(self.inner.execute_stream(request)).await
+    }
+
+    fn base_url(&self) -> &Url {
+        self.inner.base_url()
+    }
+
+    fn metrics(&self) -> Option<&TransportMetrics> {
+        self.inner.metrics()
+    }
+}
+
+impl GroqClient {
+    /// Stops the client and drains its in-flight requests
+    ///
+    /// New requests on this client and all its clones fail with
+    /// `GroqError::Shutdown` from now on. Requests and streams already in
+    /// flight may finish within `grace`; whatever is still running then is
+    /// aborted and fails with `GroqError::Shutdown`. Calling it again only
+    /// waits for the remaining requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `grace` - How long in-flight requests may take to finish
+    pub async fn shutdown(&self, grace: Duration) -> ShutdownReport {
+        self.lifecycle.shutdown(grace).await
+    }
+}
@@ -0,0 +1,109 @@
+//! Request signing hooks for authenticating gateways
+//!
+//! 面向网关鉴权的请求签名扩展模块
+//!
+//! Some deployments put Groq behind a gateway that only accepts signed
+//! requests, such as AWS SigV4 or an HMAC of the body. A [`RequestSigner`]
+//! set with [`GroqClientBuilder::request_signer`](crate::GroqClientBuilder::request_signer)
+//! runs right before each request is sent, after all other headers were
+//! added, and may add or replace headers based on the method, URL, headers
+//! and body.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use async_trait::async_trait;
+//! use groqai::signing::{RequestSigner, SigningRequest};
+//! use groqai::{GroqClientBuilder, GroqError};
+//!
+//! struct GatewaySigner {
+//!     key_id: String,
+//! }
+//!
+//! #[async_trait]
+//! impl RequestSigner for GatewaySigner {
+//!     async fn sign(&self, request: &mut SigningRequest<'_>) -> Result<(), GroqError> {
+//!         let body_len = request.body().map_or(0, <[u8]>::len);
+//!         let signature = format!("{} {} {}", request.method(), request.url().path(), body_len);
+//!         request.set_header("x-gateway-key", &self.key_id)?;
+//!         request.set_header("x-gateway-signature", &signature)?;
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let client = GroqClientBuilder::new("gsk_your_api_key".to_string())?
+//!     .request_signer(GatewaySigner { key_id: "team-a".to_string() })
+//!     .build()?;
+//! # Ok::<(), groqai::GroqError>(())
+//! ```
+
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::Method;
+use url::Url;
+
+use crate::error::GroqError;
+
+/// A request about to be sent, as seen by a [`RequestSigner`]
+pub struct SigningRequest<'a> {
+    request: &'a mut reqwest::Request,
+}
+
+impl<'a> SigningRequest<'a> {
+    pub(crate) fn new(request: &'a mut reqwest::Request) -> Self {
+        Self { request }
+    }
+
+    /// Returns the HTTP method
+    pub fn method(&self) -> &Method {
+        self.request.method()
+    }
+
+    /// Returns the full request URL, including the query string
+    pub fn url(&self) -> &Url {
+        self.request.url()
+    }
+
+    /// Returns the headers, including `Authorization` and `Content-Type`
+    pub fn headers(&self) -> &HeaderMap {
+        self.request.headers()
+    }
+
+    /// Returns the headers for modification
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        self.request.headers_mut()
+    }
+
+    /// Sets a header, replacing any existing value
+    ///
+    /// # Errors
+    ///
+    /// Returns `GroqError::InvalidMessage` if the name or value is not a
+    /// valid header.
+    pub fn set_header(&mut self, name: &str, value: &str) -> Result<(), GroqError> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| GroqError::InvalidMessage(format!("Invalid header name {:?}: {}", name, e)))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|e| GroqError::InvalidMessage(format!("Invalid value for header {}: {}", name, e)))?;
+        self.request.headers_mut().insert(name, value);
+        Ok(())
+    }
+
+    /// Returns the body bytes
+    ///
+    /// `None` for requests without a body and for streamed multipart
+    /// uploads, whose content is read from disk while sending; sign those
+    /// with an unsigned-payload scheme.
+    pub fn body(&self) -> Option<&[u8]> {
+        self.request.body().and_then(reqwest::Body::as_bytes)
+    }
+}
+
+/// Signs requests before they are sent
+///
+/// Returning an error fails the request with that error; it is not sent.
+#[async_trait]
+pub trait RequestSigner: Send + Sync {
+    /// Adds authentication headers to `request`
+    async fn sign(&self, request: &mut SigningRequest<'_>) -> Result<(), GroqError>;
+}
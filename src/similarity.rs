@@ -0,0 +1,246 @@
+//! Response similarity scoring and diffs
+//!
+//! 响应相似度评分与差异比较模块
+//!
+//! Compare two model outputs when checking prompt changes for regressions or
+//! validating a model upgrade. Every method returns a score between `0.0`
+//! (unrelated) and `1.0` (equivalent):
+//!
+//! - [`Comparison::Exact`] and [`Comparison::NormalizedWhitespace`] are local
+//!   and free.
+//! - [`Comparison::Embedding`] embeds both texts through the OpenAI-compatible
+//!   `/embeddings` endpoint and returns their cosine similarity. Use it with a
+//!   gateway that serves embeddings.
+//! - [`Comparison::Judge`] asks a chat model to grade semantic equivalence.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::similarity::{compare, Comparison};
+//! use groqai::GroqClient;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # groqai::doctest::set_api_key();
+//! let client = GroqClient::new()?;
+//! # let client = groqai::doctest::client_replying(r#"{"score": 9}"#);
+//! let score = compare(
+//!     &client,
+//!     "Paris is the capital of France.",
+//!     "The capital of France is Paris.",
+//!     &Comparison::Judge { model: "llama-3.3-70b-versatile".to_string() },
+//! )
+//! .await?;
+//! assert!(score > 0.8);
+//! # Ok(())
+//! # }
+//! ```
+
+use serde_json::json;
+
+use crate::client::GroqClient;
+use crate::endpoint::Endpoint;
+use crate::error::GroqError;
+use crate::transport::Request;
+use crate::types::{ChatCompletionResponse, ChatMessage, ResponseFormat, Role};
+
+const JUDGE_PROMPT: &str = "You compare two answers for semantic equivalence. \
+Reply with a JSON object {\"score\": n} where n is an integer from 0 (unrelated or contradictory) \
+to 10 (same meaning; wording and formatting may differ).";
+
+/// How two texts are compared
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Comparison {
+    /// `1.0` if the texts are byte-identical, else `0.0`
+    Exact,
+    /// `1.0` if the texts are equal after trimming and collapsing whitespace, else `0.0`
+    NormalizedWhitespace,
+    /// Cosine similarity of embeddings from `model`, clamped to `0.0..=1.0`
+    Embedding {
+        /// Embedding model name
+        model: String,
+    },
+    /// Equivalence grade from a chat model, scaled to `0.0..=1.0`
+    Judge {
+        /// Chat model used as the judge
+        model: String,
+    },
+}
+
+/// Scores the similarity of two texts
+///
+/// # Errors
+///
+/// Returns `GroqError` if an embedding or judge request fails, or if its
+/// response cannot be interpreted.
+pub async fn compare(
+    client: &GroqClient,
+    a: &str,
+    b: &str,
+    method: &Comparison,
+) -> Result<f64, GroqError> {
+    match method {
+        Comparison::Exact => Ok(score(a == b)),
+        Comparison::NormalizedWhitespace => Ok(score(normalize_whitespace(a) == normalize_whitespace(b))),
+        Comparison::Embedding { model } => {
+            let (x, y) = embed_pair(client, model, a, b).await?;
+            Ok(cosine(&x, &y).clamp(0.0, 1.0))
+        }
+        Comparison::Judge { model } => judge(client, model, a, b).await,
+    }
+}
+
+/// Scores the similarity of the first choices of two responses
+///
+/// # Errors
+///
+/// See [`compare`].
+pub async fn compare_responses(
+    client: &GroqClient,
+    a: &ChatCompletionResponse,
+    b: &ChatCompletionResponse,
+    method: &Comparison,
+) -> Result<f64, GroqError> {
+    compare(client, &first_text(a), &first_text(b), method).await
+}
+
+/// Trims and collapses every run of whitespace to a single space
+pub fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Cosine similarity of two vectors
+///
+/// Returns `0.0` if the lengths differ or either vector is zero.
+pub fn cosine(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f64, 0.0f64, 0.0f64);
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (f64::from(*x), f64::from(*y));
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// One line of a [`line_diff`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// Present in both texts
+    Same(String),
+    /// Only in the first text
+    Removed(String),
+    /// Only in the second text
+    Added(String),
+}
+
+/// Computes a line-based diff from `a` to `b`
+///
+/// Uses a longest-common-subsequence table, so it is meant for model outputs
+/// rather than large files.
+pub fn line_diff(a: &str, b: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = a.lines().collect();
+    let b: Vec<&str> = b.lines().collect();
+    // lcs[i][j] = length of the LCS of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::new();
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            diff.push(DiffLine::Same(a[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(DiffLine::Removed(a[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(b[j].to_string()));
+            j += 1;
+        }
+    }
+    diff.extend(a[i..].iter().map(|l| DiffLine::Removed(l.to_string())));
+    diff.extend(b[j..].iter().map(|l| DiffLine::Added(l.to_string())));
+    diff
+}
+
+fn score(equal: bool) -> f64 {
+    if equal {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn first_text(response: &ChatCompletionResponse) -> String {
+    response
+        .choices
+        .first()
+        .map(|c| c.message.content.to_string())
+        .unwrap_or_default()
+}
+
+async fn embed_pair(
+    client: &GroqClient,
+    model: &str,
+    a: &str,
+    b: &str,
+) -> Result<(Vec<f32>, Vec<f32>), GroqError> {
+    let request = Request::post(Endpoint::Embeddings).json(json!({ "model": model, "input": [a, b] }));
+    let response = client.transport.execute(request).await?.body;
+    let mut data: Vec<(usize, Vec<f32>)> = response["data"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .map(|(position, item)| {
+            let index = item["index"].as_u64().map_or(position, |i| i as usize);
+            Ok((index, serde_json::from_value(item["embedding"].clone())?))
+        })
+        .collect::<Result<_, GroqError>>()?;
+    data.sort_by_key(|(index, _)| *index);
+    let mut vectors = data.into_iter().map(|(_, v)| v);
+    match (vectors.next(), vectors.next()) {
+        (Some(x), Some(y)) => Ok((x, y)),
+        _ => Err(GroqError::InvalidMessage(
+            "Embeddings response did not contain two vectors".to_string(),
+        )),
+    }
+}
+
+async fn judge(client: &GroqClient, model: &str, a: &str, b: &str) -> Result<f64, GroqError> {
+    let response = client
+        .chat(model)
+        .message(ChatMessage::new_text(Role::System, JUDGE_PROMPT))
+        .message(ChatMessage::new_text(
+            Role::User,
+            format!("Answer A:\n{}\n\nAnswer B:\n{}", a, b),
+        ))
+        .temperature(0.0)
+        .response_format(ResponseFormat {
+            type_: "json_object".to_string(),
+            json_schema: None,
+        })
+        .send()
+        .await?;
+    let reply: serde_json::Value = serde_json::from_str(&first_text(&response))?;
+    reply["score"]
+        .as_f64()
+        .map(|s| (s / 10.0).clamp(0.0, 1.0))
+        .ok_or_else(|| GroqError::InvalidMessage("Judge reply did not contain a score".to_string()))
+}
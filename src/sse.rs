@@ -0,0 +1,235 @@
+//! Server-sent events parsing for chat completion streams
+//!
+//! 聊天补全流的 SSE 解析模块
+//!
+//! [`SseParser`] turns the raw bytes of a streamed response into chat
+//! completion chunks. Network reads split events at arbitrary points, so the
+//! parser keeps the unfinished last line between calls. Complete lines are
+//! parsed straight from the received [`Bytes`] without copying them into a
+//! `String`; only a trailing partial line is buffered. Lines are split at
+//! `\n`, which never occurs inside a multi-byte UTF-8 sequence, so a character
+//! split across reads is reassembled before it is decoded.
+//!
+//! The client uses it for [`send_stream`](crate::api::chat::ChatRequestBuilder::send_stream)
+//! through [`decode_chat_stream`], which turns the body returned by
+//! [`Transport::execute_stream`](crate::transport::Transport::execute_stream)
+//! into chunks; proxies that forward a Groq stream can use it to inspect the
+//! chunks they pass on. The output does not depend on how the body was split
+//! into reads.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use bytes::Bytes;
+//! use groqai::sse::SseParser;
+//!
+//! let body = concat!(
+//!     r#"data: {"id":"chatcmpl-1","object":"chat.completion.chunk","created":0,"#,
+//!     r#""model":"llama-3.1-8b-instant","choices":[{"index":0,"delta":{"content":"Grüße"},"finish_reason":null}]}"#,
+//!     "\n\ndata: [DONE]\n\n",
+//! );
+//! // The first read ends inside the two-byte `ü`
+//! let split = body.find('ü').unwrap() + 1;
+//!
+//! let mut parser = SseParser::new();
+//! assert!(parser.push(Bytes::copy_from_slice(&body.as_bytes()[..split])).is_empty());
+//! let chunks = parser.push(Bytes::copy_from_slice(&body.as_bytes()[split..]));
+//! let chunk = chunks.into_iter().next().unwrap().unwrap();
+//! assert_eq!(chunk.choices[0].delta.content.as_ref().unwrap().to_string(), "Grüße");
+//! ```
+
+use bytes::{Bytes, BytesMut};
+use futures::StreamExt;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use tracing::debug;
+
+use crate::error::{GroqApiError, GroqError};
+use crate::transport::{ByteStream, ChatCompletionStream};
+use crate::types::ChatCompletionChunk;
+
+/// Decodes the body of a chat stream into chunks
+///
+/// The stream ends after the first error, whether the API reported it in an
+/// event or reading the body failed.
+pub fn decode_chat_stream(body: ByteStream) -> ChatCompletionStream {
+    let mut parser = SseParser::new();
+    let stream = body
+        .flat_map(move |read| {
+            let chunks = match read {
+                Ok(bytes) => parser.push(bytes),
+                Err(e) => vec![Err(e)],
+            };
+            futures::stream::iter(chunks)
+        })
+        // 错误之后不再有有效数据，结束流
+        .scan(false, |failed, result| {
+            let item = (!*failed).then(|| {
+                *failed = result.is_err();
+                result
+            });
+            futures::future::ready(item)
+        });
+    Box::pin(stream)
+}
+
+/// Encodes `chunk` as the `data:` event the API sends for it
+pub(crate) fn encode_chunk(chunk: &ChatCompletionChunk) -> Result<Bytes, GroqError> {
+    Ok(Bytes::from(format!("data: {}\n\n", serde_json::to_string(chunk)?)))
+}
+
+/// Incremental parser for the `text/event-stream` body of a chat stream
+///
+/// Create one parser per response and [`push`](SseParser::push) every read
+/// into it. Lines other than `data:` and `event:` lines, such as `:` comments
+/// used as keep-alives, are ignored, as is the final `data: [DONE]`.
+#[derive(Debug, Clone)]
+pub struct SseParser {
+    /// Unfinished last line of the bytes received so far
+    buffer: BytesMut,
+    consecutive_errors: u32,
+    max_consecutive_errors: u32,
+    /// Set after an `event: error` line until the event's data is read
+    error_event: bool,
+}
+
+impl Default for SseParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SseParser {
+    /// Creates a parser for a new stream
+    pub fn new() -> Self {
+        Self {
+            buffer: BytesMut::new(),
+            consecutive_errors: 0,
+            max_consecutive_errors: 5,
+            error_event: false,
+        }
+    }
+
+    /// Adds bytes read from the response and returns the chunks of every completed line
+    ///
+    /// Malformed data lines are skipped (or repaired when possible). An error
+    /// sent by the API, as an `error` object or an `event: error` event, is
+    /// returned as `GroqError::Api` and ends the parsing of these bytes.
+    pub fn push(&mut self, bytes: Bytes) -> Vec<Result<ChatCompletionChunk, GroqError>> {
+        let mut chunks = Vec::new();
+        let Some(last_newline) = bytes.iter().rposition(|&b| b == b'\n') else {
+            self.buffer.extend_from_slice(&bytes);
+            return chunks;
+        };
+
+        // 先补全上次留下的不完整行，只有这一部分需要复制
+        let mut start = 0;
+        let mut ok = true;
+        if !self.buffer.is_empty() {
+            let first_newline = bytes.iter().position(|&b| b == b'\n').unwrap_or(last_newline);
+            self.buffer.extend_from_slice(&bytes[..=first_newline]);
+            let mut line = self.buffer.split();
+            ok = self.process_lines(&line, &mut chunks);
+            // Hand the allocation back for the next partial line
+            line.clear();
+            self.buffer = line;
+            start = first_newline + 1;
+        }
+        if ok && start <= last_newline {
+            self.process_lines(&bytes[start..=last_newline], &mut chunks);
+        }
+        self.buffer.extend_from_slice(&bytes[last_newline + 1..]);
+        chunks
+    }
+
+    /// Parses complete lines; returns false once the stream reported an error
+    ///
+    /// `\n` never occurs inside a multi-byte character, so complete lines
+    /// are valid UTF-8 and are decoded without copying.
+    fn process_lines(&mut self, lines: &[u8], chunks: &mut Vec<Result<ChatCompletionChunk, GroqError>>) -> bool {
+        for line in String::from_utf8_lossy(lines).lines() {
+            if let Some(event) = line.strip_prefix("event:") {
+                self.error_event = event.trim() == "error";
+                continue;
+            }
+            if line.trim().is_empty() {
+                self.error_event = false;
+                continue;
+            }
+            let Some(json) = line.strip_prefix("data: ") else {
+                continue;
+            };
+            if json.ends_with("[DONE]") {
+                continue;
+            }
+            // 流中的错误事件：以类型化错误结束流
+            if let Some(error) = Self::stream_error(json, self.error_event) {
+                chunks.push(Err(GroqError::Api(error)));
+                return false;
+            }
+            match serde_json::from_str::<ChatCompletionChunk>(json) {
+                Ok(chunk) => {
+                    chunks.push(Ok(chunk));
+                    self.consecutive_errors = 0; // 重置错误计数
+                }
+                Err(e) => {
+                    self.consecutive_errors += 1;
+                    debug!("Failed to parse chunk (error {}): {}", self.consecutive_errors, e);
+
+                    // 尝试处理部分数据
+                    if let Some(partial_chunk) = Self::try_recover_partial_chunk(json) {
+                        chunks.push(partial_chunk);
+                    }
+
+                    // 如果连续错误过多，记录但继续处理
+                    if self.consecutive_errors >= self.max_consecutive_errors {
+                        debug!("Too many consecutive parsing errors, but continuing...");
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Returns the API error carried by a data line, if it is an error event
+    fn stream_error(json: &str, error_event: bool) -> Option<GroqApiError> {
+        if !json.contains("\"error\"") && !error_event {
+            return None;
+        }
+        match GroqApiError::from_stream_event(json) {
+            Some(error) => Some(error),
+            None if error_event => Some(GroqApiError::from_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                json.to_string(),
+                &HeaderMap::new(),
+            )),
+            None => None,
+        }
+    }
+
+    fn try_recover_partial_chunk(json: &str) -> Option<Result<ChatCompletionChunk, GroqError>> {
+        // 尝试修复常见的JSON格式问题
+        let mut fixed_json = json.to_string();
+
+        // 修复未闭合的字符串
+        if fixed_json.matches('"').count() % 2 == 1 {
+            fixed_json.push('"');
+        }
+
+        // 修复未闭合的对象
+        if fixed_json.matches('{').count() > fixed_json.matches('}').count() {
+            let missing_braces = fixed_json.matches('{').count() - fixed_json.matches('}').count();
+            fixed_json.push_str(&"}".repeat(missing_braces));
+        }
+
+        // 尝试解析修复后的JSON
+        match serde_json::from_str::<ChatCompletionChunk>(&fixed_json) {
+            Ok(chunk) => {
+                debug!("Successfully recovered partial chunk");
+                Some(Ok(chunk))
+            }
+            // 如果仍然失败，不存储部分数据
+            Err(_) => None,
+        }
+    }
+}
@@ -0,0 +1,633 @@
+//! Adapters for streaming chat completions
+//!
+//! 流式聊天补全的适配器模块
+//!
+//! [`ChatStreamExt`] adds combinators to the stream returned by
+//! [`ChatRequestBuilder::send_stream`](crate::api::chat::ChatRequestBuilder::send_stream).
+//!
+//! [`ChatStreamExt::smooth`] paces text at a steady rate for typing
+//! animations, however bursty the network delivers it.
+//!
+//! [`ChatStreamExt::coalesce`] does the opposite for token-by-token streams:
+//! it merges adjacent text deltas, so consumers render fewer, larger pieces
+//! at the cost of a bounded delay.
+//!
+//! [`ChatStreamExt::markdown`] reports markdown structure such as code blocks
+//! opening and closing, so UIs never render a half-open fence.
+//!
+//! [`ChatStreamExt::json_events`] and [`ChatStreamExt::json_partial`] parse
+//! JSON-mode replies as they stream, reporting completed keys and values or
+//! yielding a partially populated struct after each chunk.
+//!
+//! [`StreamRecorder`] persists every chunk of a stream as NDJSON while the
+//! consumer reads it normally, so production incidents can be replayed later.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use futures::StreamExt;
+//! use groqai::stream::{ChatEvent, ChatStreamExt};
+//! use groqai::{ChatMessage, GroqClient, Role};
+//! use std::time::Duration;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # groqai::doctest::set_api_key();
+//! let client = GroqClient::new()?;
+//! # let client = groqai::doctest::client();
+//! let mut events = client
+//!     .chat("llama-3.1-70b-versatile")
+//!     .message(ChatMessage::new_text(Role::User, "Think hard, then answer"))
+//!     .stream(true)
+//!     .send_stream()
+//!     .await?
+//!     .heartbeat(Duration::from_secs(1));
+//!
+//! while let Some(event) = events.next().await {
+//!     match event? {
+//!         ChatEvent::Chunk(chunk) => println!("{:?}", chunk.choices.first()),
+//!         ChatEvent::Heartbeat { elapsed } => println!("still thinking ({:?})", elapsed),
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::Path;
+use std::pin::Pin;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::warn;
+
+use crate::error::GroqError;
+use crate::markdown::{MarkdownEventStream, MarkdownTracker};
+use crate::partial_json::{JsonEventStream, PartialJsonParser};
+use crate::transport::ChatCompletionStream;
+use crate::types::{ChatCompletionChunk, LogProbs, MessageContent};
+
+/// Most chunks per second [`ChatStreamExt::smooth`] emits
+const SMOOTH_EMITS_PER_SECOND: f64 = 60.0;
+
+/// How long [`Coalesce::min_chars`] holds text back at most
+const COALESCE_MAX_DELAY: Duration = Duration::from_millis(100);
+
+/// Shortest interval [`ChatStreamExt::heartbeat`] accepts
+const HEARTBEAT_MIN_INTERVAL: Duration = Duration::from_millis(10);
+
+/// An event produced by a chat stream adapter
+#[derive(Debug, Clone)]
+// Nearly every event is a chunk; boxing it would allocate for each one
+#[allow(clippy::large_enum_variant)]
+pub enum ChatEvent {
+    /// A chunk received from the API
+    Chunk(ChatCompletionChunk),
+    /// No data has arrived for the configured interval; the stream is still open
+    Heartbeat {
+        /// Time since the last chunk arrived (or since the stream started)
+        elapsed: Duration,
+    },
+}
+
+/// A boxed stream of chat events
+pub type ChatEventStream = Pin<Box<dyn Stream<Item = Result<ChatEvent, GroqError>> + Send>>;
+
+/// When [`ChatStreamExt::coalesce`] emits the text it merged
+///
+/// Merged text is emitted once `max_delay` has passed since its first delta
+/// arrived or, with [`min_chars`](Coalesce::min_chars), as soon as that many
+/// characters are buffered, whichever comes first. A `Duration` converts into
+/// [`Coalesce::window`] and a `usize` into [`Coalesce::min_chars`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Coalesce {
+    max_delay: Duration,
+    min_chars: Option<usize>,
+}
+
+impl Coalesce {
+    /// Merges the text arriving within `max_delay` of the first delta
+    pub fn window(max_delay: Duration) -> Self {
+        Self {
+            max_delay,
+            min_chars: None,
+        }
+    }
+
+    /// Merges text until at least `min_chars` characters are buffered
+    ///
+    /// Text is held back for at most 100 ms; change it with
+    /// [`max_delay`](Coalesce::max_delay).
+    pub fn min_chars(min_chars: usize) -> Self {
+        Self {
+            max_delay: COALESCE_MAX_DELAY,
+            min_chars: Some(min_chars.max(1)),
+        }
+    }
+
+    /// Sets how long text is held back at most
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    fn is_full(&self, chunk: &ChatCompletionChunk) -> bool {
+        self.min_chars.is_some_and(|min_chars| text_chars(chunk) >= min_chars)
+    }
+}
+
+impl From<Duration> for Coalesce {
+    fn from(max_delay: Duration) -> Self {
+        Self::window(max_delay)
+    }
+}
+
+impl From<usize> for Coalesce {
+    fn from(min_chars: usize) -> Self {
+        Self::min_chars(min_chars)
+    }
+}
+
+/// One line of a recorded stream transcript
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordedChunk {
+    /// Wall-clock time the chunk was received, in milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    /// Time since recording started, in milliseconds
+    pub elapsed_ms: u64,
+    /// The chunk as received from the API
+    pub chunk: ChatCompletionChunk,
+}
+
+/// A destination for recorded NDJSON lines
+pub trait RecordSink: Send {
+    /// Writes one line; `line` does not include the trailing newline
+    fn write_line(&mut self, line: &str) -> std::io::Result<()>;
+}
+
+/// Writes recorded lines to any [`Write`] implementation on a background thread
+///
+/// Lines are buffered and flushed whenever the thread catches up, so slow
+/// disks never block the task reading the stream. The thread ends once the
+/// sink is dropped and every queued line has been written; a write error is
+/// logged and ends it early.
+pub struct WriterSink {
+    sender: std::sync::mpsc::Sender<String>,
+}
+
+impl WriterSink {
+    /// Starts the writer thread for `writer`
+    pub fn new<W: Write + Send + 'static>(writer: W) -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel::<String>();
+        std::thread::spawn(move || {
+            let mut out = std::io::BufWriter::new(writer);
+            while let Ok(line) = receiver.recv() {
+                // 先写完已排队的行再刷新，减少系统调用
+                let written = std::iter::once(line)
+                    .chain(receiver.try_iter())
+                    .try_for_each(|line| writeln!(out, "{}", line))
+                    .and_then(|()| out.flush());
+                if let Err(e) = written {
+                    warn!("Failed to write recorded stream: {}", e);
+                    return;
+                }
+            }
+        });
+        Self { sender }
+    }
+}
+
+impl RecordSink for WriterSink {
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        self.sender.send(line.to_string()).map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::BrokenPipe, "record writer stopped")
+        })
+    }
+}
+
+impl RecordSink for UnboundedSender<String> {
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        self.send(line.to_string()).map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::BrokenPipe, "record channel closed")
+        })
+    }
+}
+
+/// Records stream chunks with timestamps to a [`RecordSink`] as NDJSON
+///
+/// Attach it with [`ChatStreamExt::record`]. Sink failures are logged and never
+/// interrupt the stream.
+pub struct StreamRecorder {
+    sink: Box<dyn RecordSink>,
+    started: Instant,
+}
+
+impl StreamRecorder {
+    /// Creates a recorder writing to `sink`
+    pub fn new(sink: impl RecordSink + 'static) -> Self {
+        Self {
+            sink: Box::new(sink),
+            started: Instant::now(),
+        }
+    }
+
+    /// Creates a recorder appending to the file at `path`, creating it if needed
+    ///
+    /// Lines are written by a [`WriterSink`] thread, so the file may lag the
+    /// stream slightly and is complete shortly after the recorder is dropped.
+    pub fn to_file(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        Ok(Self::new(WriterSink::new(file)))
+    }
+
+    /// Creates a recorder sending each line to a channel
+    pub fn to_channel(sender: UnboundedSender<String>) -> Self {
+        Self::new(sender)
+    }
+
+    fn record(&mut self, chunk: &ChatCompletionChunk) {
+        let record = RecordedChunk {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            chunk: chunk.clone(),
+        };
+        let written = serde_json::to_string(&record)
+            .map_err(std::io::Error::from)
+            .and_then(|line| self.sink.write_line(&line));
+        if let Err(e) = written {
+            warn!("Failed to record stream chunk: {}", e);
+        }
+    }
+}
+
+/// Extension methods for streams of chat completion chunks
+pub trait ChatStreamExt: Stream<Item = Result<ChatCompletionChunk, GroqError>> + Sized {
+    /// Emits [`ChatEvent::Heartbeat`] whenever no chunk has arrived for `interval`
+    ///
+    /// Heartbeats repeat every `interval` while the stream stays silent and never
+    /// terminate the stream, so UIs can drive "still thinking" indicators
+    /// without their own timers. Intervals below 10ms, including zero, are
+    /// raised to 10ms so a silent stream does not spin.
+    fn heartbeat(self, interval: Duration) -> ChatEventStream
+    where
+        Self: Send + Unpin + 'static,
+    {
+        let interval = interval.max(HEARTBEAT_MIN_INTERVAL);
+        Box::pin(futures::stream::unfold(
+            (self, Instant::now()),
+            move |(mut stream, last_data)| async move {
+                match tokio::time::timeout(interval, stream.next()).await {
+                    Ok(Some(item)) => Some((item.map(ChatEvent::Chunk), (stream, Instant::now()))),
+                    Ok(None) => None,
+                    Err(_) => {
+                        let elapsed = last_data.elapsed();
+                        Some((Ok(ChatEvent::Heartbeat { elapsed }), (stream, last_data)))
+                    }
+                }
+            },
+        ))
+    }
+
+    /// Re-emits text at no more than `chars_per_second`, buffering bursts
+    ///
+    /// Text deltas are split into pieces emitted at most 60 times per second,
+    /// so a large chunk arriving at once appears as steady typing. Role and
+    /// reasoning stay on the first piece of their chunk; tool calls, the
+    /// finish reason, logprobs, usage and `x_groq` on the last. Errors are
+    /// forwarded after the text received before them. The stream ends once the
+    /// buffered text was emitted; at low rates that can be well after the API
+    /// finished.
+    fn smooth(self, chars_per_second: f64) -> ChatCompletionStream
+    where
+        Self: Send + Unpin + 'static,
+    {
+        let chars_per_second = chars_per_second.max(1.0);
+        let piece_chars = (chars_per_second / SMOOTH_EMITS_PER_SECOND).ceil() as usize;
+        Box::pin(futures::stream::unfold(
+            (self, VecDeque::new(), tokio::time::Instant::now()),
+            move |(mut stream, mut pending, next_at)| async move {
+                if pending.is_empty() {
+                    match stream.next().await? {
+                        Ok(chunk) => pending.extend(split_text(chunk, piece_chars).into_iter().map(Ok)),
+                        Err(e) => pending.push_back(Err(e)),
+                    }
+                }
+                let item = pending.pop_front()?;
+                tokio::time::sleep_until(next_at).await;
+                let chars = item.as_ref().map_or(0, text_chars);
+                let delay = Duration::from_secs_f64(chars as f64 / chars_per_second);
+                let next_at = next_at.max(tokio::time::Instant::now()) + delay;
+                Some((item, (stream, pending, next_at)))
+            },
+        ))
+    }
+
+    /// Merges adjacent text deltas into fewer, larger chunks
+    ///
+    /// Each emitted chunk carries the text of the chunks merged into it, so
+    /// consumers re-render less often; `policy` bounds how long text is held
+    /// back (see [`Coalesce`]). Only single-choice text deltas are merged:
+    /// tool calls, usage and multi-choice chunks pass through unchanged, and
+    /// the chunk with the finish reason is merged and emitted at once. Errors
+    /// are forwarded after the text received before them.
+    fn coalesce(self, policy: impl Into<Coalesce>) -> ChatCompletionStream
+    where
+        Self: Send + Unpin + 'static,
+    {
+        let policy = policy.into();
+        Box::pin(futures::stream::unfold(
+            (Some(self), None::<ChatCompletionChunk>, VecDeque::new(), tokio::time::Instant::now()),
+            move |(mut stream, mut merged, mut pending, mut deadline)| async move {
+                loop {
+                    if let Some(item) = pending.pop_front() {
+                        return Some((item, (stream, merged, pending, deadline)));
+                    }
+                    let Some(source) = stream.as_mut() else {
+                        return merged.take().map(|chunk| (Ok(chunk), (stream, merged, pending, deadline)));
+                    };
+                    let Some(mut chunk) = merged.take() else {
+                        match source.next().await {
+                            Some(Ok(chunk)) if is_text_delta(&chunk) && !policy.is_full(&chunk) => {
+                                deadline = tokio::time::Instant::now() + policy.max_delay;
+                                merged = Some(chunk);
+                            }
+                            Some(item) => pending.push_back(item),
+                            None => stream = None,
+                        }
+                        continue;
+                    };
+                    match tokio::time::timeout_at(deadline, source.next()).await {
+                        Ok(Some(Ok(next))) if merge_delta(&mut chunk, &next) => {
+                            let done = chunk.choices[0].finish_reason.is_some() || policy.is_full(&chunk);
+                            if done {
+                                pending.push_back(Ok(chunk));
+                            } else {
+                                merged = Some(chunk);
+                            }
+                        }
+                        Ok(Some(item)) => pending.extend([Ok(chunk), item]),
+                        Ok(None) => {
+                            stream = None;
+                            pending.push_back(Ok(chunk));
+                        }
+                        Err(_) => pending.push_back(Ok(chunk)),
+                    }
+                }
+            },
+        ))
+    }
+
+    /// Turns the text of the first choice into markdown structure events
+    ///
+    /// Every delta is forwarded as [`MarkdownEvent::Text`](crate::markdown::MarkdownEvent::Text),
+    /// split at line ends so structural events appear right after the line
+    /// that completes them. When the stream ends, the last line is completed
+    /// and an open code block is closed. Chunks without text are dropped.
+    fn markdown(self) -> MarkdownEventStream
+    where
+        Self: Send + Unpin + 'static,
+    {
+        Box::pin(futures::stream::unfold(
+            (Some(self), MarkdownTracker::new(), VecDeque::new()),
+            |(mut stream, mut tracker, mut pending)| async move {
+                loop {
+                    if let Some(event) = pending.pop_front() {
+                        return Some((event, (stream, tracker, pending)));
+                    }
+                    match stream.as_mut()?.next().await {
+                        Some(Ok(chunk)) => {
+                            if let Some(MessageContent::Text(text)) =
+                                chunk.choices.first().and_then(|c| c.delta.content.as_ref())
+                            {
+                                pending.extend(tracker.push(text).into_iter().map(Ok));
+                            }
+                        }
+                        Some(Err(e)) => pending.push_back(Err(e)),
+                        None => {
+                            stream = None;
+                            pending.extend(tracker.finish().into_iter().map(Ok));
+                        }
+                    }
+                }
+            },
+        ))
+    }
+
+    /// Parses the text of the first choice as JSON, reporting keys and values as they complete
+    ///
+    /// Meant for JSON-mode replies (see
+    /// [`ResponseFormat::json_object`](crate::types::ResponseFormat::json_object));
+    /// see [`JsonEvent`](crate::partial_json::JsonEvent). The stream ends after
+    /// the first error, including a reply that is not valid JSON or ends
+    /// before the document is complete.
+    fn json_events(self) -> JsonEventStream
+    where
+        Self: Send + Unpin + 'static,
+    {
+        Box::pin(futures::stream::unfold(
+            (Some(self), PartialJsonParser::new(), VecDeque::new()),
+            |(mut stream, mut parser, mut pending)| async move {
+                loop {
+                    if let Some(event) = pending.pop_front() {
+                        return Some((event, (stream, parser, pending)));
+                    }
+                    let events = match stream.as_mut()?.next().await {
+                        Some(Ok(chunk)) => match first_text(&chunk) {
+                            Some(text) => parser.push(text),
+                            None => continue,
+                        },
+                        Some(Err(e)) => Err(e),
+                        None => {
+                            stream = None;
+                            parser.finish()
+                        }
+                    };
+                    match events {
+                        Ok(events) => pending.extend(events.into_iter().map(Ok)),
+                        Err(e) => {
+                            stream = None;
+                            pending.push_back(Err(e));
+                        }
+                    }
+                }
+            },
+        ))
+    }
+
+    /// Parses the text of the first choice as JSON, yielding it as `T` while it streams
+    ///
+    /// After each chunk the document so far, with open strings, arrays and
+    /// objects closed, is deserialized into `T` and yielded if it fits and
+    /// changed; give `T` optional or defaulted fields so it fits early. The
+    /// last item is the complete document. The stream ends after the first
+    /// error, as for [`json_events`](ChatStreamExt::json_events).
+    fn json_partial<T>(self) -> Pin<Box<dyn Stream<Item = Result<T, GroqError>> + Send>>
+    where
+        Self: Send + Unpin + 'static,
+        T: serde::de::DeserializeOwned + Send + 'static,
+    {
+        Box::pin(futures::stream::unfold(
+            (Some(self), PartialJsonParser::new(), None::<serde_json::Value>),
+            |(mut stream, mut parser, mut last)| async move {
+                loop {
+                    let result = match stream.as_mut()?.next().await {
+                        Some(Ok(chunk)) => match first_text(&chunk) {
+                            Some(text) => parser.push(text).map(|_| ()),
+                            None => continue,
+                        },
+                        Some(Err(e)) => Err(e),
+                        None => {
+                            stream = None;
+                            parser.finish().map(|_| ())
+                        }
+                    };
+                    if let Err(e) = result {
+                        return Some((Err(e), (None, parser, last)));
+                    }
+                    let Some(snapshot) = parser.snapshot().filter(|s| last.as_ref() != Some(s)) else {
+                        continue;
+                    };
+                    match serde_json::from_value(snapshot.clone()) {
+                        Ok(value) => {
+                            last = Some(snapshot);
+                            return Some((Ok(value), (stream, parser, last)));
+                        }
+                        // The complete document has to fit
+                        Err(e) if stream.is_none() => return Some((Err(e.into()), (None, parser, last))),
+                        Err(_) => {}
+                    }
+                }
+            },
+        ))
+    }
+
+    /// Writes every received chunk to `recorder` while passing it through unchanged
+    ///
+    /// Errors are forwarded to the consumer but not recorded.
+    fn record(self, mut recorder: StreamRecorder) -> ChatCompletionStream
+    where
+        Self: Send + 'static,
+    {
+        recorder.started = Instant::now();
+        Box::pin(self.map(move |item| {
+            if let Ok(chunk) = &item {
+                recorder.record(chunk);
+            }
+            item
+        }))
+    }
+}
+
+impl<S> ChatStreamExt for S where S: Stream<Item = Result<ChatCompletionChunk, GroqError>> + Sized {}
+
+/// Returns the text delta of a chunk's first choice
+fn first_text(chunk: &ChatCompletionChunk) -> Option<&str> {
+    match chunk.choices.first().and_then(|c| c.delta.content.as_ref()) {
+        Some(MessageContent::Text(text)) => Some(text),
+        _ => None,
+    }
+}
+
+/// Returns the number of text characters in a chunk's deltas
+fn text_chars(chunk: &ChatCompletionChunk) -> usize {
+    chunk
+        .choices
+        .iter()
+        .filter_map(|c| match &c.delta.content {
+            Some(MessageContent::Text(text)) => Some(text.chars().count()),
+            _ => None,
+        })
+        .sum()
+}
+
+/// Returns true for a single-choice chunk that only adds text
+fn is_text_delta(chunk: &ChatCompletionChunk) -> bool {
+    match chunk.choices.as_slice() {
+        [choice] => {
+            choice.finish_reason.is_none()
+                && choice.delta.tool_calls.is_none()
+                && matches!(choice.delta.content, None | Some(MessageContent::Text(_)))
+                && chunk.usage.is_none()
+        }
+        _ => false,
+    }
+}
+
+/// Appends the text of `next` to `chunk` if it continues the same choice
+///
+/// `next` may carry the finish reason, usage and Groq fields of the last chunk.
+fn merge_delta(chunk: &mut ChatCompletionChunk, next: &ChatCompletionChunk) -> bool {
+    let ([choice], [next_choice]) = (chunk.choices.as_mut_slice(), next.choices.as_slice()) else {
+        return false;
+    };
+    let delta = &next_choice.delta;
+    let mergeable = next_choice.index == choice.index
+        && delta.role.is_none()
+        && delta.tool_calls.is_none()
+        && matches!(delta.content, None | Some(MessageContent::Text(_)))
+        && (chunk.x_groq.is_none() || next.x_groq.is_none());
+    if !mergeable {
+        return false;
+    }
+    if let Some(MessageContent::Text(text)) = &delta.content {
+        match &mut choice.delta.content {
+            Some(MessageContent::Text(merged)) => merged.push_str(text),
+            content => *content = Some(MessageContent::Text(text.clone())),
+        }
+    }
+    if let Some(reasoning) = &delta.reasoning {
+        choice.delta.reasoning.get_or_insert_with(String::new).push_str(reasoning);
+    }
+    if let Some(tokens) = next_choice.logprobs.as_ref().and_then(|l| l.content.as_ref()) {
+        let logprobs = choice.logprobs.get_or_insert(LogProbs { content: None });
+        logprobs.content.get_or_insert_with(Vec::new).extend(tokens.iter().cloned());
+    }
+    choice.finish_reason = next_choice.finish_reason.clone();
+    chunk.x_groq = chunk.x_groq.take().or_else(|| next.x_groq.clone());
+    chunk.usage = next.usage.clone();
+    true
+}
+
+/// Splits a single-choice text chunk into chunks of at most `piece_chars` characters
+fn split_text(chunk: ChatCompletionChunk, piece_chars: usize) -> Vec<ChatCompletionChunk> {
+    let text = match chunk.choices.as_slice() {
+        [choice] => match &choice.delta.content {
+            Some(MessageContent::Text(text)) if text.chars().count() > piece_chars => text.clone(),
+            _ => return vec![chunk],
+        },
+        _ => return vec![chunk],
+    };
+    let chars: Vec<char> = text.chars().collect();
+    let pieces: Vec<String> = chars.chunks(piece_chars).map(|c| c.iter().collect()).collect();
+    let last = pieces.len() - 1;
+    pieces
+        .into_iter()
+        .enumerate()
+        .map(|(i, piece)| {
+            let mut part = chunk.clone();
+            let choice = &mut part.choices[0];
+            choice.delta.content = Some(MessageContent::Text(piece));
+            if i > 0 {
+                choice.delta.role = None;
+                choice.delta.reasoning = None;
+            }
+            if i < last {
+                choice.delta.tool_calls = None;
+                choice.finish_reason = None;
+                choice.logprobs = None;
+                part.usage = None;
+                part.x_groq = None;
+            }
+            part
+        })
+        .collect()
+}
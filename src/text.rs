@@ -0,0 +1,314 @@
+//! Text splitting and map-reduce summarization of long documents
+//!
+//! 长文本切分与 map-reduce 摘要模块
+//!
+//! [`Splitter`] cuts text into chunks that fit a token budget, preferring
+//! natural boundaries (markdown sections, then sentences, then words).
+//! [`map_reduce_summarize`] summarizes each chunk concurrently and combines the
+//! partial summaries, repeating until the result fits one request.
+//!
+//! Token counts are estimated at four characters per token, which is close
+//! enough for budgeting English text without shipping a tokenizer.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::text::{map_reduce_summarize, Splitter, SummarizeOptions};
+//! use groqai::GroqClient;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # groqai::doctest::set_api_key();
+//! let client = GroqClient::new()?;
+//! # let client = groqai::doctest::client();
+//! # groqai::doctest::scratch_dir();
+//! # std::fs::write("report.md", "## Results\n\nRevenue grew 12%.\n")?;
+//! let report = std::fs::read_to_string("report.md")?;
+//! let options = SummarizeOptions::new().splitter(Splitter::markdown_headings(2_000));
+//! let summary = map_reduce_summarize(&client, "llama-3.1-8b-instant", &report, &options).await?;
+//! println!("{}", summary);
+//! # Ok(())
+//! # }
+//! ```
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+
+use crate::client::GroqClient;
+use crate::error::GroqError;
+use crate::types::{ChatMessage, Role};
+
+/// Estimates the number of tokens in `text` (four characters per token)
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Boundaries a [`Splitter`] prefers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitMode {
+    /// Whitespace-separated words
+    Tokens,
+    /// Sentences and paragraphs
+    Sentences,
+    /// Markdown sections starting at `#` headings
+    MarkdownHeadings,
+}
+
+/// Splits text into chunks of at most a given number of estimated tokens
+///
+/// Pieces that exceed the budget on their own fall back to the next finer
+/// boundary: sections to sentences, sentences to words. A single word longer
+/// than the budget is kept whole.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Splitter {
+    mode: SplitMode,
+    max_tokens: usize,
+}
+
+impl Splitter {
+    /// Splits on word boundaries only
+    pub fn tokens(max_tokens: usize) -> Self {
+        Self::new(SplitMode::Tokens, max_tokens)
+    }
+
+    /// Keeps sentences together where possible
+    pub fn sentences(max_tokens: usize) -> Self {
+        Self::new(SplitMode::Sentences, max_tokens)
+    }
+
+    /// Keeps markdown sections together where possible
+    pub fn markdown_headings(max_tokens: usize) -> Self {
+        Self::new(SplitMode::MarkdownHeadings, max_tokens)
+    }
+
+    fn new(mode: SplitMode, max_tokens: usize) -> Self {
+        Self {
+            mode,
+            max_tokens: max_tokens.max(1),
+        }
+    }
+
+    /// Returns the boundary mode
+    pub fn mode(&self) -> SplitMode {
+        self.mode
+    }
+
+    /// Returns the token budget per chunk
+    pub fn max_tokens(&self) -> usize {
+        self.max_tokens
+    }
+
+    /// Splits `text` into trimmed, non-empty chunks
+    pub fn split(&self, text: &str) -> Vec<String> {
+        self.split_mode(text, self.mode)
+    }
+
+    fn split_mode(&self, text: &str, mode: SplitMode) -> Vec<String> {
+        let (pieces, separator, finer) = match mode {
+            SplitMode::MarkdownHeadings => (markdown_sections(text), "\n\n", Some(SplitMode::Sentences)),
+            SplitMode::Sentences => (sentences(text), " ", Some(SplitMode::Tokens)),
+            SplitMode::Tokens => (text.split_whitespace().map(str::to_string).collect(), " ", None),
+        };
+
+        let mut chunks = Vec::new();
+        let mut current = String::new();
+        for piece in pieces {
+            if estimate_tokens(&piece) > self.max_tokens {
+                if let Some(finer) = finer {
+                    chunks.push(std::mem::take(&mut current));
+                    chunks.extend(self.split_mode(&piece, finer));
+                    continue;
+                }
+            }
+            let candidate_len =
+                (current.chars().count() + separator.len() + piece.chars().count()).div_ceil(4);
+            if !current.is_empty() && candidate_len > self.max_tokens {
+                chunks.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push_str(separator);
+            }
+            current.push_str(&piece);
+        }
+        chunks.push(current);
+        chunks
+            .into_iter()
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect()
+    }
+}
+
+/// Splits markdown before every heading line
+fn markdown_sections(text: &str) -> Vec<String> {
+    let mut sections = Vec::new();
+    let mut current = String::new();
+    for line in text.lines() {
+        if line.trim_start().starts_with('#') && !current.trim().is_empty() {
+            sections.push(std::mem::take(&mut current));
+        }
+        current.push_str(line);
+        current.push('\n');
+    }
+    sections.push(current);
+    sections
+}
+
+/// Splits after `.`, `!` or `?` followed by whitespace, and at blank lines
+fn sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    for paragraph in text.split("\n\n") {
+        let mut current = String::new();
+        let mut chars = paragraph.chars().peekable();
+        while let Some(c) = chars.next() {
+            current.push(c);
+            if matches!(c, '.' | '!' | '?') && chars.peek().is_some_and(|n| n.is_whitespace()) {
+                sentences.push(std::mem::take(&mut current).trim().to_string());
+            }
+        }
+        sentences.push(current.trim().to_string());
+    }
+    sentences.retain(|s| !s.is_empty());
+    sentences
+}
+
+/// Options for [`map_reduce_summarize`]
+#[derive(Debug, Clone)]
+pub struct SummarizeOptions {
+    splitter: Splitter,
+    concurrency: usize,
+    map_prompt: String,
+    reduce_prompt: String,
+    max_completion_tokens: u32,
+}
+
+impl Default for SummarizeOptions {
+    fn default() -> Self {
+        Self {
+            splitter: Splitter::sentences(3_000),
+            concurrency: 4,
+            map_prompt: "Summarize the following excerpt of a longer document. \
+                Keep names, figures and conclusions."
+                .to_string(),
+            reduce_prompt: "Combine the following partial summaries of one document \
+                into a single coherent summary without repetition."
+                .to_string(),
+            max_completion_tokens: 512,
+        }
+    }
+}
+
+impl SummarizeOptions {
+    /// Creates the default options: sentence chunks of 3000 tokens, 4 requests at a time
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how the document is chunked
+    pub fn splitter(mut self, splitter: Splitter) -> Self {
+        self.splitter = splitter;
+        self
+    }
+
+    /// Sets the maximum number of chunk requests in flight
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Sets the system prompt used to summarize each chunk
+    pub fn map_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.map_prompt = prompt.into();
+        self
+    }
+
+    /// Sets the system prompt used to combine partial summaries
+    pub fn reduce_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.reduce_prompt = prompt.into();
+        self
+    }
+
+    /// Sets the token limit of each summary
+    pub fn max_completion_tokens(mut self, max: u32) -> Self {
+        self.max_completion_tokens = max;
+        self
+    }
+}
+
+/// Summarizes a document of any length
+///
+/// Chunks the text with the options' splitter, summarizes chunks concurrently
+/// (subject to the client's retry and budget settings), then combines the
+/// partial summaries. If they are still too long for one request, they are
+/// chunked and combined again. Text that fits in one chunk takes a single
+/// request.
+///
+/// # Errors
+///
+/// Returns the first `GroqError` of any chunk request, or
+/// `GroqError::InvalidMessage` if a round of combining does not reduce the
+/// number of chunks, as the summaries would not fit one request.
+pub async fn map_reduce_summarize(
+    client: &GroqClient,
+    model: &str,
+    text: &str,
+    options: &SummarizeOptions,
+) -> Result<String, GroqError> {
+    let chunks = options.splitter.split(text);
+    if chunks.len() <= 1 {
+        return summarize(client, model, &options.map_prompt, text, options).await;
+    }
+    let mut summaries = summarize_all(client, model, &options.map_prompt, chunks, options).await?;
+    loop {
+        let combined = summaries.join("\n\n");
+        let groups = options.splitter.split(&combined);
+        if groups.len() <= 1 {
+            return summarize(client, model, &options.reduce_prompt, &combined, options).await;
+        }
+        // 摘要没有变短时继续合并只会超出预算
+        if groups.len() >= summaries.len() {
+            return Err(GroqError::InvalidMessage(format!(
+                "Summaries do not fit one request: {} partial summaries still split into {} chunks; \
+                 lower max_completion_tokens or raise the splitter's token budget",
+                summaries.len(),
+                groups.len()
+            )));
+        }
+        summaries = summarize_all(client, model, &options.reduce_prompt, groups, options).await?;
+    }
+}
+
+async fn summarize_all(
+    client: &GroqClient,
+    model: &str,
+    prompt: &str,
+    chunks: Vec<String>,
+    options: &SummarizeOptions,
+) -> Result<Vec<String>, GroqError> {
+    stream::iter(chunks)
+        .map(|chunk| async move { summarize(client, model, prompt, &chunk, options).await })
+        .buffered(options.concurrency)
+        .try_collect()
+        .await
+}
+
+async fn summarize(
+    client: &GroqClient,
+    model: &str,
+    prompt: &str,
+    text: &str,
+    options: &SummarizeOptions,
+) -> Result<String, GroqError> {
+    let response = client
+        .chat(model)
+        .message(ChatMessage::new_text(Role::System, prompt))
+        .message(ChatMessage::new_text(Role::User, text))
+        .temperature(0.0)
+        .max_completion_tokens(options.max_completion_tokens)
+        .send()
+        .await?;
+    response
+        .choices
+        .first()
+        .map(|c| c.message.content.to_string())
+        .ok_or_else(|| GroqError::InvalidMessage("Response contained no choices".to_string()))
+}
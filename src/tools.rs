@@ -0,0 +1,525 @@
+//! Validation of model-proposed tool calls
+//!
+//! 工具调用校验模块，在执行模型提出的工具调用之前检查参数
+//!
+//! Models can be coaxed (e.g. via prompt injection in retrieved content) into
+//! proposing tool calls with dangerous or malformed arguments. [`ToolGuard`]
+//! runs a configurable set of validators over the arguments before your code
+//! executes anything, and turns rejections into a structured tool message that
+//! can be sent back to the model so it can correct itself.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::tools::{DenyListValidator, ToolGuard};
+//! use groqai::types::{FunctionCall, FunctionDef, Tool, ToolCall};
+//!
+//! let tools = vec![Tool {
+//!     type_: "function".to_string(),
+//!     function: FunctionDef {
+//!         name: "run_query".to_string(),
+//!         description: None,
+//!         parameters: serde_json::json!({
+//!             "type": "object",
+//!             "properties": { "sql": { "type": "string" } },
+//!             "required": ["sql"]
+//!         }),
+//!     },
+//! }];
+//!
+//! let guard = ToolGuard::new()
+//!     .validator(DenyListValidator::new(["drop table"]))
+//!     .numeric_bounds("/limit", Some(1.0), Some(100.0));
+//!
+//! let call = ToolCall {
+//!     id: "call_1".to_string(),
+//!     type_: "function".to_string(),
+//!     function: FunctionCall {
+//!         name: "run_query".to_string(),
+//!         arguments: r#"{"sql": "DROP TABLE users"}"#.to_string(),
+//!     },
+//! };
+//!
+//! let rejection = guard.check(&call, &tools).unwrap_err();
+//! // Send `rejection.to_tool_message()` back to the model instead of executing the call
+//! let _reply = rejection.to_tool_message();
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+#[cfg(feature = "jsonschema")]
+use jsonschema::error::ValidationErrorKind;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::schema::pointer;
+use crate::types::{ChatMessage, FunctionDef, Tool, ToolCall};
+
+/// A single problem found in a tool call's arguments
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolViolation {
+    /// JSON pointer to the offending value ("" for the arguments root)
+    pub path: String,
+    /// Human readable description of the problem
+    pub message: String,
+}
+
+impl ToolViolation {
+    /// Creates a new violation at the given JSON pointer
+    pub fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// A check run against the parsed arguments of a tool call
+///
+/// Implement this trait to add custom checks (allow-lists, rate limits per tool,
+/// business rules) to a [`ToolGuard`].
+pub trait ToolArgumentValidator: Send + Sync {
+    /// Validates `arguments` for the call to `function`
+    ///
+    /// `function` is the declared definition of the called tool, if the model
+    /// called a tool that exists in the request.
+    fn validate(&self, function: Option<&FunctionDef>, arguments: &Value) -> Vec<ToolViolation>;
+}
+
+/// Validates arguments against the tool's declared JSON schema
+///
+/// With the `jsonschema` feature enabled the full JSON Schema specification is
+/// supported. Otherwise a built-in checker covers the subset that function
+/// definitions use in practice: `type`, `properties`, `required`,
+/// `additionalProperties: false`, `enum`, `items`, `minimum`/`maximum` and
+/// `minLength`/`maxLength`.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaValidator;
+
+#[cfg(not(feature = "jsonschema"))]
+impl SchemaValidator {
+    fn check(schema: &Value, value: &Value, path: &str, out: &mut Vec<ToolViolation>) {
+        let Some(schema) = schema.as_object() else {
+            return;
+        };
+
+        if let Some(expected) = schema.get("type") {
+            let matches = match expected {
+                Value::String(t) => type_matches(t, value),
+                Value::Array(types) => types
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .any(|t| type_matches(t, value)),
+                _ => true,
+            };
+            if !matches {
+                out.push(ToolViolation::new(
+                    path,
+                    format!("expected type {}, got {}", expected, type_name(value)),
+                ));
+                return;
+            }
+        }
+
+        if let Some(Value::Array(allowed)) = schema.get("enum") {
+            if !allowed.contains(value) {
+                out.push(ToolViolation::new(
+                    path,
+                    format!("value {} is not one of {}", value, Value::Array(allowed.clone())),
+                ));
+            }
+        }
+
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::Array(required)) = schema.get("required") {
+                    for name in required.iter().filter_map(Value::as_str) {
+                        if !map.contains_key(name) {
+                            out.push(ToolViolation::new(
+                                pointer(path, name),
+                                "required property is missing",
+                            ));
+                        }
+                    }
+                }
+                let properties = schema.get("properties").and_then(Value::as_object);
+                let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+                for (key, child) in map {
+                    let child_path = pointer(path, key);
+                    match properties.and_then(|p| p.get(key)) {
+                        Some(child_schema) => Self::check(child_schema, child, &child_path, out),
+                        None if closed => {
+                            out.push(ToolViolation::new(child_path, "unexpected property"))
+                        }
+                        None => {}
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for (i, item) in items.iter().enumerate() {
+                        Self::check(item_schema, item, &pointer(path, &i.to_string()), out);
+                    }
+                }
+            }
+            Value::Number(n) => {
+                let n = n.as_f64().unwrap_or_default();
+                if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                    if n < min {
+                        out.push(ToolViolation::new(path, format!("{} is less than minimum {}", n, min)));
+                    }
+                }
+                if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                    if n > max {
+                        out.push(ToolViolation::new(path, format!("{} is greater than maximum {}", n, max)));
+                    }
+                }
+            }
+            Value::String(s) => {
+                let len = s.chars().count() as u64;
+                if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                    if len < min {
+                        out.push(ToolViolation::new(path, format!("string is shorter than {} characters", min)));
+                    }
+                }
+                if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                    if len > max {
+                        out.push(ToolViolation::new(path, format!("string is longer than {} characters", max)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl ToolArgumentValidator for SchemaValidator {
+    #[cfg(not(feature = "jsonschema"))]
+    fn validate(&self, function: Option<&FunctionDef>, arguments: &Value) -> Vec<ToolViolation> {
+        let mut out = Vec::new();
+        if let Some(def) = function {
+            Self::check(&def.parameters, arguments, "", &mut out);
+        }
+        out
+    }
+
+    #[cfg(feature = "jsonschema")]
+    fn validate(&self, function: Option<&FunctionDef>, arguments: &Value) -> Vec<ToolViolation> {
+        let Some(def) = function else {
+            return Vec::new();
+        };
+        match jsonschema::validator_for(&def.parameters) {
+            Ok(validator) => validator
+                .iter_errors(arguments)
+                .flat_map(|e| {
+                    // Point at the property itself, as the built-in checker does
+                    let path = e.instance_path().as_str().to_string();
+                    match e.kind() {
+                        ValidationErrorKind::Required { property } => vec![ToolViolation::new(
+                            pointer(&path, property.as_str().unwrap_or_default()),
+                            "required property is missing",
+                        )],
+                        ValidationErrorKind::AdditionalProperties { unexpected } => unexpected
+                            .iter()
+                            .map(|name| ToolViolation::new(pointer(&path, name), "unexpected property"))
+                            .collect(),
+                        _ => vec![ToolViolation::new(path, e.to_string())],
+                    }
+                })
+                .collect(),
+            Err(e) => vec![ToolViolation::new(
+                "",
+                format!("function `{}` declares an invalid schema: {}", def.name, e),
+            )],
+        }
+    }
+}
+
+#[cfg(not(feature = "jsonschema"))]
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        _ => true,
+    }
+}
+
+#[cfg(not(feature = "jsonschema"))]
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Rejects calls whose string arguments contain any of a set of patterns
+///
+/// Matching is case-insensitive and applies to every string value nested
+/// anywhere in the arguments.
+#[derive(Debug, Clone)]
+pub struct DenyListValidator {
+    patterns: Vec<String>,
+}
+
+impl DenyListValidator {
+    /// Creates a validator rejecting the given patterns
+    pub fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            patterns: patterns.into_iter().map(|p| p.into().to_lowercase()).collect(),
+        }
+    }
+
+    /// A starter set of phrases commonly seen in prompt injection attempts
+    pub fn prompt_injection_defaults() -> Self {
+        Self::new([
+            "ignore previous instructions",
+            "ignore all previous instructions",
+            "disregard previous instructions",
+            "ignore the above",
+            "you are now",
+            "system prompt",
+            "<|im_start|>",
+            "rm -rf",
+        ])
+    }
+
+    fn scan(&self, value: &Value, path: &str, out: &mut Vec<ToolViolation>) {
+        match value {
+            Value::String(s) => {
+                let lower = s.to_lowercase();
+                for pattern in &self.patterns {
+                    if lower.contains(pattern.as_str()) {
+                        out.push(ToolViolation::new(
+                            path,
+                            format!("value contains denied pattern {:?}", pattern),
+                        ));
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    self.scan(item, &pointer(path, &i.to_string()), out);
+                }
+            }
+            Value::Object(map) => {
+                for (key, item) in map {
+                    self.scan(item, &pointer(path, key), out);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl ToolArgumentValidator for DenyListValidator {
+    fn validate(&self, _function: Option<&FunctionDef>, arguments: &Value) -> Vec<ToolViolation> {
+        let mut out = Vec::new();
+        self.scan(arguments, "", &mut out);
+        out
+    }
+}
+
+/// Enforces numeric bounds on values addressed by JSON pointer
+///
+/// Bounds only apply when the addressed value is present and numeric; use the
+/// schema's `required` list to enforce presence.
+#[derive(Debug, Clone, Default)]
+pub struct NumericBoundsValidator {
+    bounds: HashMap<String, (Option<f64>, Option<f64>)>,
+}
+
+impl NumericBoundsValidator {
+    /// Creates a validator without any bounds
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds inclusive bounds for the value at `pointer` (e.g. "/limit")
+    pub fn bound(mut self, pointer: impl Into<String>, min: Option<f64>, max: Option<f64>) -> Self {
+        self.bounds.insert(pointer.into(), (min, max));
+        self
+    }
+}
+
+impl ToolArgumentValidator for NumericBoundsValidator {
+    fn validate(&self, _function: Option<&FunctionDef>, arguments: &Value) -> Vec<ToolViolation> {
+        let mut out = Vec::new();
+        for (pointer, (min, max)) in &self.bounds {
+            let Some(n) = arguments.pointer(pointer).and_then(Value::as_f64) else {
+                continue;
+            };
+            if let Some(min) = min {
+                if n < *min {
+                    out.push(ToolViolation::new(pointer, format!("{} is less than {}", n, min)));
+                }
+            }
+            if let Some(max) = max {
+                if n > *max {
+                    out.push(ToolViolation::new(pointer, format!("{} is greater than {}", n, max)));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// A tool call that failed validation
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolRejection {
+    /// ID of the rejected tool call
+    pub tool_call_id: String,
+    /// Name of the function the model tried to call
+    pub function: String,
+    /// All problems found in the call
+    pub violations: Vec<ToolViolation>,
+}
+
+impl ToolRejection {
+    /// Structured error payload describing the rejection
+    pub fn to_json(&self) -> Value {
+        serde_json::json!({
+            "error": {
+                "type": "invalid_tool_arguments",
+                "message": format!(
+                    "The call to `{}` was rejected and not executed. Fix the arguments and try again.",
+                    self.function
+                ),
+                "violations": self.violations,
+            }
+        })
+    }
+
+    /// Tool response message to send back to the model in place of a result
+    pub fn to_tool_message(&self) -> ChatMessage {
+        ChatMessage::tool_response(self.tool_call_id.clone(), self.to_json().to_string())
+    }
+}
+
+impl fmt::Display for ToolRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tool call `{}` rejected: ", self.function)?;
+        for (i, v) in self.violations.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            if v.path.is_empty() {
+                write!(f, "{}", v.message)?;
+            } else {
+                write!(f, "{}: {}", v.path, v.message)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ToolRejection {}
+
+/// Runs validators over model-proposed tool calls before they are executed
+///
+/// A guard created with [`ToolGuard::new`] always validates against the
+/// declared schema; add more checks with the builder methods.
+pub struct ToolGuard {
+    validators: Vec<Box<dyn ToolArgumentValidator>>,
+    allow_unknown_functions: bool,
+}
+
+impl Default for ToolGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ToolGuard {
+    /// Creates a guard that validates arguments against the declared schema
+    pub fn new() -> Self {
+        Self {
+            validators: vec![Box::new(SchemaValidator)],
+            allow_unknown_functions: false,
+        }
+    }
+
+    /// Adds a validator to the guard
+    pub fn validator(mut self, validator: impl ToolArgumentValidator + 'static) -> Self {
+        self.validators.push(Box::new(validator));
+        self
+    }
+
+    /// Rejects string arguments containing any of the given patterns
+    pub fn deny_patterns<I, S>(self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.validator(DenyListValidator::new(patterns))
+    }
+
+    /// Enforces inclusive numeric bounds for the value at `pointer`
+    pub fn numeric_bounds(self, pointer: impl Into<String>, min: Option<f64>, max: Option<f64>) -> Self {
+        self.validator(NumericBoundsValidator::new().bound(pointer, min, max))
+    }
+
+    /// Whether calls to functions not declared in `tools` are let through
+    ///
+    /// Defaults to `false`: models occasionally hallucinate tool names.
+    pub fn allow_unknown_functions(mut self, allow: bool) -> Self {
+        self.allow_unknown_functions = allow;
+        self
+    }
+
+    /// Validates a tool call against the tools declared in the request
+    ///
+    /// # Returns
+    ///
+    /// The parsed arguments if every validator passes
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ToolRejection`] listing every violation found
+    pub fn check(&self, call: &ToolCall, tools: &[Tool]) -> Result<Value, ToolRejection> {
+        let reject = |violations| ToolRejection {
+            tool_call_id: call.id.clone(),
+            function: call.function.name.clone(),
+            violations,
+        };
+
+        let def = tools
+            .iter()
+            .map(|t| &t.function)
+            .find(|f| f.name == call.function.name);
+        if def.is_none() && !self.allow_unknown_functions {
+            return Err(reject(vec![ToolViolation::new(
+                "",
+                format!("unknown function `{}`", call.function.name),
+            )]));
+        }
+
+        let arguments: Value = serde_json::from_str(&call.function.arguments).map_err(|e| {
+            reject(vec![ToolViolation::new("", format!("arguments are not valid JSON: {}", e))])
+        })?;
+
+        let violations: Vec<_> = self
+            .validators
+            .iter()
+            .flat_map(|v| v.validate(def, &arguments))
+            .collect();
+        if violations.is_empty() {
+            Ok(arguments)
+        } else {
+            Err(reject(violations))
+        }
+    }
+}
@@ -0,0 +1,366 @@
+//! Markdown and HTML transcripts of chat histories
+//!
+//! 对话记录的 Markdown 与 HTML 导出模块
+//!
+//! [`to_markdown`] and [`to_html`] render a message history for sharing or
+//! for audit views: each message gets a role header, code fences in replies
+//! are kept as code blocks, and tool calls and tool results are collapsed in
+//! `<details>` elements. System prompts are redacted by default; choose with
+//! [`TranscriptOptions::system_prompts`]. [`Conversation`](crate::conversation::Conversation)
+//! has `to_markdown()` and `to_html()` shortcuts.
+//!
+//! The HTML is a fragment (an `<article>`) without styles, meant to be
+//! embedded in a page. Every message is escaped, so replies cannot inject
+//! markup.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use groqai::transcript::{self, SystemPrompts, TranscriptOptions};
+//! use groqai::types::{ChatMessage, Role};
+//!
+//! let messages = [
+//!     ChatMessage::new_text(Role::System, "You are a support agent for Acme."),
+//!     ChatMessage::new_text(Role::User, "How do I print in Rust?"),
+//!     ChatMessage::new_text(Role::Assistant, "Use `println!`:\n\n```rust\nprintln!(\"hi\");\n```"),
+//! ];
+//!
+//! let markdown = transcript::to_markdown(&messages, &TranscriptOptions::new());
+//! assert!(markdown.contains("## User\n\nHow do I print in Rust?"));
+//! assert!(!markdown.contains("Acme"));
+//!
+//! let options = TranscriptOptions::new().system_prompts(SystemPrompts::Show);
+//! let html = transcript::to_html(&messages, &options);
+//! assert!(html.contains("<pre><code class=\"language-rust\">println!(&quot;hi&quot;);\n</code></pre>"));
+//! assert!(html.contains("Acme"));
+//! ```
+
+use std::fmt::Write;
+
+use crate::types::{ChatMessage, ImageUrl, MessageContent, MessagePart, Role, ToolCall};
+
+/// Text shown instead of a redacted system prompt
+pub const REDACTED_SYSTEM_PROMPT: &str = "[system prompt redacted]";
+
+/// How system prompts appear in a transcript
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SystemPrompts {
+    /// Show system prompts as written
+    Show,
+    /// Keep the message but replace its text with [`REDACTED_SYSTEM_PROMPT`]
+    #[default]
+    Redact,
+    /// Leave system messages out
+    Omit,
+}
+
+/// Options for rendering a transcript
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptOptions {
+    /// How system prompts appear, redacted by default
+    pub system_prompts: SystemPrompts,
+    /// Heading above the messages, if any
+    pub title: Option<String>,
+}
+
+impl TranscriptOptions {
+    /// Creates options redacting system prompts, without a title
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how system prompts appear
+    pub fn system_prompts(mut self, system_prompts: SystemPrompts) -> Self {
+        self.system_prompts = system_prompts;
+        self
+    }
+
+    /// Sets the heading above the messages
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+}
+
+/// Renders `messages` as Markdown
+///
+/// Message text is copied as is, so the model's own Markdown renders; a code
+/// fence left open by a truncated reply is closed. Tool calls and tool
+/// results are collapsed in `<details>` elements, which GitHub and most
+/// Markdown viewers support.
+pub fn to_markdown(messages: &[ChatMessage], options: &TranscriptOptions) -> String {
+    let mut out = String::new();
+    if let Some(title) = &options.title {
+        let _ = write!(out, "# {}\n\n", title);
+    }
+    for message in messages {
+        let Some(content) = visible_content(message, options) else {
+            continue;
+        };
+        let _ = write!(out, "## {}\n\n", role_name(&message.role));
+        match (&message.role, &message.tool_call_id) {
+            (Role::Tool, id) => {
+                let summary = match id {
+                    Some(id) => format!("Tool result ({})", id),
+                    None => "Tool result".to_string(),
+                };
+                markdown_details(&mut out, &summary, None, &content.to_string());
+            }
+            _ => markdown_content(&mut out, &content),
+        }
+        for call in message.tool_calls.iter().flatten() {
+            let summary = format!("Tool call: {}", call.function.name);
+            markdown_details(&mut out, &summary, Some("json"), &tool_arguments(call));
+        }
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+/// Renders `messages` as an HTML fragment
+///
+/// Each message is a `<section class="message {role}">` with an `<h2>` role
+/// header inside an `<article class="transcript">`. Fenced code becomes
+/// `<pre><code class="language-…">`, other text becomes paragraphs, and tool
+/// calls and tool results are collapsed in `<details>` elements. All text is
+/// HTML-escaped; no other Markdown is interpreted.
+pub fn to_html(messages: &[ChatMessage], options: &TranscriptOptions) -> String {
+    let mut out = String::from("<article class=\"transcript\">\n");
+    if let Some(title) = &options.title {
+        let _ = writeln!(out, "<h1>{}</h1>", escape(title));
+    }
+    for message in messages {
+        let Some(content) = visible_content(message, options) else {
+            continue;
+        };
+        let role = role_name(&message.role);
+        let _ = writeln!(out, "<section class=\"message {}\">", role.to_lowercase());
+        let _ = writeln!(out, "<h2>{}</h2>", role);
+        match (&message.role, &message.tool_call_id) {
+            (Role::Tool, id) => {
+                let summary = match id {
+                    Some(id) => format!("Tool result ({})", id),
+                    None => "Tool result".to_string(),
+                };
+                html_details(&mut out, "tool-result", &summary, None, &content.to_string());
+            }
+            _ => html_content(&mut out, &content),
+        }
+        for call in message.tool_calls.iter().flatten() {
+            let summary = format!("Tool call: {}", call.function.name);
+            html_details(&mut out, "tool-call", &summary, Some("json"), &tool_arguments(call));
+        }
+        out.push_str("</section>\n");
+    }
+    out.push_str("</article>\n");
+    out
+}
+
+/// Returns the content to show for `message`, or `None` to leave it out
+fn visible_content(message: &ChatMessage, options: &TranscriptOptions) -> Option<MessageContent> {
+    match (&message.role, options.system_prompts) {
+        (Role::System, SystemPrompts::Omit) => None,
+        (Role::System, SystemPrompts::Redact) => Some(MessageContent::text(REDACTED_SYSTEM_PROMPT)),
+        _ => Some(message.content.clone()),
+    }
+}
+
+fn role_name(role: &Role) -> &'static str {
+    match role {
+        Role::System => "System",
+        Role::User => "User",
+        Role::Assistant => "Assistant",
+        Role::Tool => "Tool",
+    }
+}
+
+/// Returns the call's arguments, pretty-printed if they are valid JSON
+fn tool_arguments(call: &ToolCall) -> String {
+    serde_json::from_str::<serde_json::Value>(&call.function.arguments)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+        .unwrap_or_else(|_| call.function.arguments.clone())
+}
+
+/// Returns the text and image parts of `content` in order
+fn parts(content: &MessageContent) -> Vec<MessagePart> {
+    match content {
+        MessageContent::Text(text) => vec![MessagePart::Text { text: text.clone() }],
+        MessageContent::ImageUrl(image_url) => vec![MessagePart::ImageUrl {
+            image_url: image_url.clone(),
+        }],
+        MessageContent::Parts(parts) => parts.clone(),
+    }
+}
+
+/// Returns the URL to link for `image`, or `None` for inline `data:` images
+fn image_link(image: &ImageUrl) -> Option<&str> {
+    (!image.url.starts_with("data:")).then_some(image.url.as_str())
+}
+
+fn markdown_content(out: &mut String, content: &MessageContent) {
+    for part in parts(content) {
+        match part {
+            MessagePart::Text { text } if text.trim().is_empty() => {}
+            MessagePart::Text { text } => {
+                out.push_str(text.trim_end());
+                out.push('\n');
+                if let Some(Block::Code { fence, closed: false, .. }) = blocks(&text).last() {
+                    out.push_str(fence);
+                    out.push('\n');
+                }
+                out.push('\n');
+            }
+            MessagePart::ImageUrl { image_url } => match image_link(&image_url) {
+                Some(url) => {
+                    let _ = write!(out, "![image](<{}>)\n\n", url);
+                }
+                None => out.push_str("*[inline image]*\n\n"),
+            },
+        }
+    }
+}
+
+/// Writes a collapsed code block, fenced with more backticks than `code` contains
+fn markdown_details(out: &mut String, summary: &str, language: Option<&str>, code: &str) {
+    let longest = code
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat((longest + 1).max(3));
+    let _ = write!(
+        out,
+        "<details>\n<summary>{}</summary>\n\n{}{}\n{}\n{}\n\n</details>\n\n",
+        escape(summary),
+        fence,
+        language.unwrap_or(""),
+        code.trim_end(),
+        fence
+    );
+}
+
+fn html_content(out: &mut String, content: &MessageContent) {
+    for part in parts(content) {
+        match part {
+            MessagePart::Text { text } => {
+                for block in blocks(&text) {
+                    match block {
+                        Block::Text(text) => {
+                            for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+                                let lines: Vec<String> = paragraph.lines().map(escape).collect();
+                                let _ = writeln!(out, "<p>{}</p>", lines.join("<br>\n"));
+                            }
+                        }
+                        Block::Code { language, code, .. } => html_code(out, language.as_deref(), &code),
+                    }
+                }
+            }
+            MessagePart::ImageUrl { image_url } => {
+                let _ = writeln!(out, "<p><img src=\"{}\" alt=\"image\"></p>", escape(&image_url.url));
+            }
+        }
+    }
+}
+
+fn html_details(out: &mut String, class: &str, summary: &str, language: Option<&str>, code: &str) {
+    let _ = writeln!(out, "<details class=\"{}\">", class);
+    let _ = writeln!(out, "<summary>{}</summary>", escape(summary));
+    html_code(out, language, code);
+    out.push_str("</details>\n");
+}
+
+fn html_code(out: &mut String, language: Option<&str>, code: &str) {
+    match language {
+        Some(language) => {
+            let _ = write!(out, "<pre><code class=\"language-{}\">", escape(language));
+        }
+        None => out.push_str("<pre><code>"),
+    }
+    out.push_str(&escape(code));
+    if !code.is_empty() && !code.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str("</code></pre>\n");
+}
+
+/// Escapes text for use in HTML content and attribute values
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A run of prose or a fenced code block
+enum Block {
+    Text(String),
+    Code {
+        language: Option<String>,
+        code: String,
+        /// The opening fence, e.g. "```"
+        fence: String,
+        /// Whether a closing fence was found
+        closed: bool,
+    },
+}
+
+/// Splits `text` into prose and fenced code blocks
+fn blocks(text: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut prose = String::new();
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let Some((fence, info)) = opening_fence(line) else {
+            prose.push_str(line);
+            prose.push('\n');
+            continue;
+        };
+        if !prose.is_empty() {
+            blocks.push(Block::Text(std::mem::take(&mut prose)));
+        }
+        let marker = fence.chars().next().unwrap_or('`');
+        let mut code = String::new();
+        let mut closed = false;
+        for line in lines.by_ref() {
+            let trimmed = line.trim();
+            if trimmed.len() >= fence.len() && trimmed.chars().all(|c| c == marker) {
+                closed = true;
+                break;
+            }
+            code.push_str(line);
+            code.push('\n');
+        }
+        blocks.push(Block::Code {
+            language: info.split_whitespace().next().map(str::to_string),
+            code,
+            fence,
+            closed,
+        });
+    }
+    if !prose.is_empty() {
+        blocks.push(Block::Text(prose));
+    }
+    blocks
+}
+
+/// Returns the fence and info string if `line` opens a code block
+fn opening_fence(line: &str) -> Option<(String, &str)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    let line = &line[indent..];
+    let marker = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = line.len() - line.trim_start_matches(marker).len();
+    let info = &line[len..];
+    if indent > 3 || len < 3 || (marker == '`' && info.contains('`')) {
+        return None;
+    }
+    Some((marker.to_string().repeat(len), info.trim()))
+}
@@ -3,167 +3,632 @@
 //! 传输层模块，处理与 Groq API 的 HTTP 通信
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use futures::Stream;
 use futures::StreamExt;
-use futures::TryStreamExt;
-use reqwest::multipart::{Form, Part};
+use reqwest::header::{HeaderName, HeaderValue, CONTENT_LENGTH};
 use reqwest::{Client, RequestBuilder};
+use std::future::Future;
 use std::pin::Pin;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::debug;
+use serde::de::DeserializeOwned;
 use url::Url;
 
+pub use reqwest::Method;
+
+#[cfg(feature = "audio")]
+use crate::api::audio::is_text_format;
 use crate::api::chat::ChatCompletionRequest;
+use crate::build_info::{sdk_header_value, SDK_HEADER};
+use crate::endpoint::Endpoint;
 use crate::error::{GroqApiError, GroqError};
+use crate::metrics::{RequestSample, TransportMetrics};
+#[cfg(any(feature = "audio", feature = "files"))]
+use crate::multipart;
+use crate::queue::Priority;
+use crate::signing::{RequestSigner, SigningRequest};
+use crate::sse::{decode_chat_stream, encode_chunk};
 use crate::types::{ChatCompletionChunk, ChatCompletionResponse};
 
-/// 流式数据缓冲区，用于处理不完整的SSE数据
-struct StreamBuffer {
-    buffer: String,
-    consecutive_errors: u32,
-    max_consecutive_errors: u32,
+/// A boxed stream of chat completion chunks
+pub type ChatCompletionStream =
+    Pin<Box<dyn Stream<Item = Result<ChatCompletionChunk, GroqError>> + Send>>;
+
+/// A boxed stream of raw response bytes, such as generated audio
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, GroqError>> + Send>>;
+
+/// Progress of a file upload, reported as the file is read from disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadProgress {
+    /// Bytes of the file handed to the connection so far
+    pub bytes_sent: u64,
+    /// Size of the file
+    pub total_bytes: u64,
 }
 
-impl StreamBuffer {
-    fn new() -> Self {
-        Self {
-            buffer: String::new(),
-            consecutive_errors: 0,
-            max_consecutive_errors: 5,
-        }
+/// A callback receiving [`UploadProgress`] updates
+pub type UploadProgressCallback = Arc<dyn Fn(UploadProgress) + Send + Sync>;
+
+/// Wraps the progress callback so [`RequestOptions`] keeps its derives
+#[derive(Clone)]
+struct ProgressHook(UploadProgressCallback);
+
+impl std::fmt::Debug for ProgressHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressHook")
     }
+}
 
-    fn add_bytes(&mut self, bytes: &[u8]) {
-        self.buffer.push_str(&String::from_utf8_lossy(bytes));
+impl PartialEq for ProgressHook {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
     }
+}
 
-    fn process_lines(&mut self) -> Vec<Result<ChatCompletionChunk, GroqError>> {
-        let mut chunks = Vec::new();
+impl Eq for ProgressHook {}
 
-        // 检查是否有换行符
-        if !self.buffer.contains('\n') {
-            return chunks; // 没有完整的行
+/// How often a [`Transport`] retries a request that failed transiently
+///
+/// Connection errors, `429 Too Many Requests` and `5xx` responses are retried;
+/// a `retry-after` sent with the error takes precedence over the backoff.
+/// Streams are only retried until the response starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; `0` disables retrying
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled for every further retry
+    pub initial_delay: Duration,
+    /// Upper bound for the wait between two attempts
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl RetryPolicy {
+    /// Retries up to `max_retries` times, waiting 200 ms, 400 ms, ... in between
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            initial_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(10),
         }
+    }
 
-        // 找到最后一个换行符的位置
-        let last_newline = self.buffer.rfind('\n').unwrap();
-
-        // 处理完整的行（不包括最后一行）
-        let complete_lines = &self.buffer[..last_newline];
-        let remaining = &self.buffer[last_newline + 1..];
-
-        // 处理完整的行
-        for line in complete_lines.lines() {
-            if line.starts_with("data: ") && !line.ends_with("[DONE]") {
-                let json = line.strip_prefix("data: ").unwrap_or(line);
-                match serde_json::from_str::<ChatCompletionChunk>(json) {
-                    Ok(chunk) => {
-                        chunks.push(Ok(chunk));
-                        self.consecutive_errors = 0; // 重置错误计数
-                    }
-                    Err(e) => {
-                        self.consecutive_errors += 1;
-                        debug!(
-                            "Failed to parse chunk (error {}): {}",
-                            self.consecutive_errors, e
-                        );
-
-                        // 尝试处理部分数据
-                        if let Some(partial_chunk) = self.try_recover_partial_chunk(json) {
-                            chunks.push(partial_chunk);
-                        }
-
-                        // 如果连续错误过多，记录但继续处理
-                        if self.consecutive_errors >= self.max_consecutive_errors {
-                            debug!("Too many consecutive parsing errors, but continuing...");
-                        }
-                    }
-                }
-            }
+    /// Returns the wait before retry number `retry`, counting from 1
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// Returns true if `error` is worth retrying
+    fn should_retry(error: &GroqError) -> bool {
+        match error {
+            GroqError::Api(api) => api.status == reqwest::StatusCode::TOO_MANY_REQUESTS || api.status.is_server_error(),
+            other => other.is_retryable(),
         }
+    }
+}
 
-        // 更新缓冲区，保留不完整的行
-        self.buffer = remaining.to_string();
+/// Per-request settings passed to every [`Transport`] call
+///
+/// Builders fill this in from methods such as
+/// [`ChatRequestBuilder::header`](crate::api::chat::ChatRequestBuilder::header),
+/// so a single request can carry settings that differ from the client-level
+/// defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestOptions {
+    headers: Vec<(String, String)>,
+    timeout: Option<Duration>,
+    idempotency_key: Option<String>,
+    retry_policy: Option<RetryPolicy>,
+    upload_progress: Option<ProgressHook>,
+    file_content_type: Option<String>,
+    priority: Option<Priority>,
+}
 
-        chunks
+impl RequestOptions {
+    /// Creates options with no extra settings
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    fn try_recover_partial_chunk(
-        &self,
-        json: &str,
-    ) -> Option<Result<ChatCompletionChunk, GroqError>> {
-        // 尝试修复常见的JSON格式问题
-        let mut fixed_json = json.to_string();
-
-        // 修复未闭合的字符串
-        if fixed_json.matches('"').count() % 2 == 1 {
-            fixed_json.push('"');
-        }
+    /// Adds a header sent with the request, e.g. a gateway routing hint
+    ///
+    /// Headers are sent in the order they were added, after the client's own
+    /// headers; adding the same name twice sends it twice.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Returns the extra headers in the order they were added
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Limits the request to `timeout`, overriding the client's timeout
+    ///
+    /// For streams the timeout covers the whole response, not just its start.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the per-request timeout, if one was set
+    pub fn request_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Sends `key` as the `Idempotency-Key` header of every attempt
+    ///
+    /// Gateways that deduplicate by this header then apply a retried request
+    /// only once.
+    pub fn idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Returns the idempotency key, if one was set
+    pub fn idempotency(&self) -> Option<&str> {
+        self.idempotency_key.as_deref()
+    }
+
+    /// Retries transient failures of the request according to `policy`
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Returns the retry policy, if one was set
+    pub fn retry(&self) -> Option<RetryPolicy> {
+        self.retry_policy
+    }
+
+    /// Calls `callback` as the file of a multipart upload is sent
+    ///
+    /// The callback runs on the connection's task after each read from disk,
+    /// so it should return quickly. Requests without a file never call it.
+    pub fn upload_progress(mut self, callback: impl Fn(UploadProgress) + Send + Sync + 'static) -> Self {
+        self.upload_progress = Some(ProgressHook(Arc::new(callback)));
+        self
+    }
+
+    /// Returns the upload progress callback, if one was set
+    pub fn upload_progress_callback(&self) -> Option<&UploadProgressCallback> {
+        self.upload_progress.as_ref().map(|hook| &hook.0)
+    }
+
+    /// Sends the file of a multipart upload with `mime` as its content type
+    ///
+    /// Without it the content type is taken from the file extension, or from
+    /// the first bytes of the file if the extension is not known.
+    pub fn file_content_type(mut self, mime: impl Into<String>) -> Self {
+        self.file_content_type = Some(mime.into());
+        self
+    }
+
+    /// Returns the content type set for the uploaded file, if any
+    pub fn file_mime_type(&self) -> Option<&str> {
+        self.file_content_type.as_deref()
+    }
+
+    /// Queues the request at `priority` when the client has a [`RequestQueue`](crate::queue::RequestQueue)
+    ///
+    /// Requests without a priority are [`Priority::Interactive`].
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Returns the queue priority, if one was set
+    pub fn request_priority(&self) -> Option<Priority> {
+        self.priority
+    }
+}
 
-        // 修复未闭合的对象
-        if fixed_json.matches('{').count() > fixed_json.matches('}').count() {
-            let missing_braces = fixed_json.matches('{').count() - fixed_json.matches('}').count();
-            fixed_json.push_str(&"}".repeat(missing_braces));
+/// Body of a [`Request`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum RequestBody {
+    /// No body, as for `GET` and `DELETE` requests
+    #[default]
+    Empty,
+    /// A JSON body
+    Json(serde_json::Value),
+    /// A multipart form built from the fields of a JSON object
+    ///
+    /// A `file` field holds the path of a file, which is streamed from disk.
+    Multipart(serde_json::Value),
+    /// A multipart form whose file is sent from memory
+    ///
+    /// `fields` are sent as for [`Multipart`](RequestBody::Multipart), except
+    /// that the `file` field holds only the file name.
+    MultipartFile {
+        /// The form fields, as a JSON object
+        fields: serde_json::Value,
+        /// Content of the file
+        content: Bytes,
+    },
+}
+
+/// A request to the API, as sent by [`Transport::execute`] and [`Transport::execute_stream`]
+///
+/// # Examples
+///
+/// ```rust
+/// use groqai::transport::{Request, RequestOptions, RetryPolicy};
+/// use groqai::Endpoint;
+/// use std::time::Duration;
+///
+/// let request = Request::get(Endpoint::Files)
+///     .query("limit", "20")
+///     .options(RequestOptions::new().timeout(Duration::from_secs(5)).retry_policy(RetryPolicy::new(2)));
+/// assert_eq!(request.endpoint.path(), "files");
+/// assert_eq!(request.query, [("limit".to_string(), "20".to_string())]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Request {
+    /// HTTP method
+    pub method: Method,
+    /// Endpoint the request is sent to
+    pub endpoint: Endpoint,
+    /// Query parameters, in order
+    pub query: Vec<(String, String)>,
+    /// Request body
+    pub body: RequestBody,
+    /// Per-request settings
+    pub options: RequestOptions,
+}
+
+impl Request {
+    /// Creates a request without a body
+    pub fn new(method: Method, endpoint: Endpoint) -> Self {
+        Self {
+            method,
+            endpoint,
+            query: Vec::new(),
+            body: RequestBody::Empty,
+            options: RequestOptions::default(),
         }
+    }
 
-        // 尝试解析修复后的JSON
-        match serde_json::from_str::<ChatCompletionChunk>(&fixed_json) {
-            Ok(chunk) => {
-                debug!("Successfully recovered partial chunk");
-                Some(Ok(chunk))
-            }
-            Err(_) => {
-                // 如果仍然失败，不存储部分数据（避免借用问题）
-                None
-            }
+    /// Creates a `GET` request
+    pub fn get(endpoint: Endpoint) -> Self {
+        Self::new(Method::GET, endpoint)
+    }
+
+    /// Creates a `POST` request
+    pub fn post(endpoint: Endpoint) -> Self {
+        Self::new(Method::POST, endpoint)
+    }
+
+    /// Creates a `DELETE` request
+    pub fn delete(endpoint: Endpoint) -> Self {
+        Self::new(Method::DELETE, endpoint)
+    }
+
+    /// Sends `body` as JSON
+    pub fn json(mut self, body: serde_json::Value) -> Self {
+        self.body = RequestBody::Json(body);
+        self
+    }
+
+    /// Sends the fields of `body` as a multipart form
+    pub fn multipart(mut self, body: serde_json::Value) -> Self {
+        self.body = RequestBody::Multipart(body);
+        self
+    }
+
+    /// Sends the fields of `body` as a multipart form with `content` as its file
+    ///
+    /// The `file` field of `body` names the file.
+    pub fn multipart_file(mut self, body: serde_json::Value, content: Bytes) -> Self {
+        self.body = RequestBody::MultipartFile { fields: body, content };
+        self
+    }
+
+    /// Appends a query parameter
+    pub fn query(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.query.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the per-request settings
+    pub fn options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Returns the body as JSON, or the query parameters as an object for requests without one
+    pub(crate) fn body_json(&self) -> Option<serde_json::Value> {
+        match &self.body {
+            RequestBody::Json(body)
+            | RequestBody::Multipart(body)
+            | RequestBody::MultipartFile { fields: body, .. } => Some(body.clone()),
+            RequestBody::Empty if self.query.is_empty() => None,
+            RequestBody::Empty => Some(serde_json::Value::Object(
+                self.query
+                    .iter()
+                    .map(|(name, value)| (name.clone(), serde_json::Value::String(value.clone())))
+                    .collect(),
+            )),
         }
     }
 }
 
+/// A response returned by [`Transport::execute`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    /// The decoded body; plain text bodies are a JSON string
+    pub body: serde_json::Value,
+}
+
+impl Response {
+    /// Creates a response with `body`
+    pub fn new(body: serde_json::Value) -> Self {
+        Self { body }
+    }
+
+    /// Deserializes the body
+    pub fn json<T: DeserializeOwned>(self) -> Result<T, GroqError> {
+        serde_json::from_value(self.body).map_err(GroqError::from)
+    }
+}
+
+/// Returns the endpoint of a path passed to one of the deprecated path-based methods
+fn endpoint_for(path: &str) -> Result<Endpoint, GroqError> {
+    Endpoint::from_path(path)
+        .ok_or_else(|| GroqError::InvalidMessage(format!("Unknown API path: {}", path)))
+}
+
+/// Sends requests to the API
+///
+/// A transport implements [`execute`](Transport::execute) and
+/// [`execute_stream`](Transport::execute_stream); every API call goes through
+/// one of the two. The path-based methods from before [`Request`] existed
+/// remain as deprecated wrappers around them.
+///
+/// # Migrating from the path-based interface
+///
+/// `execute` and `execute_stream` are required, so an existing
+/// `impl Transport for MyTransport` with the path-based methods no longer
+/// compiles. Either rename it to `impl LegacyTransport for MyTransport`,
+/// which keeps the methods and makes the type a `Transport` through a blanket
+/// impl, or implement `execute` and `execute_stream`, matching on
+/// [`Request::endpoint`] and [`Request::method`].
 #[async_trait]
 pub trait Transport: Send + Sync {
+    /// Sends `request` and returns its decoded response
+    ///
+    /// When a multipart body asks for a plain text audio `response_format`
+    /// (`text`, `srt` or `vtt`), the response body is returned as a JSON string.
+    async fn execute(&self, request: Request) -> Result<Response, GroqError>;
+
+    /// Sends `request` and streams the raw response body as it arrives
+    ///
+    /// Chat completion streams are server-sent events; decode them with
+    /// [`decode_chat_stream`].
+    async fn execute_stream(&self, request: Request) -> Result<ByteStream, GroqError>;
+
+    fn base_url(&self) -> &Url;
+
+    /// Returns the metrics this transport records, if it records any
+    fn metrics(&self) -> Option<&TransportMetrics> {
+        None
+    }
+
+    #[deprecated(note = "use `execute` with `Request::post(Endpoint::ChatCompletions)`")]
     async fn post_chat(
         &self,
         path: &str,
         body: &ChatCompletionRequest,
+        options: &RequestOptions,
+    ) -> Result<ChatCompletionResponse, GroqError> {
+        let request = Request::post(endpoint_for(path)?)
+            .json(serde_json::to_value(body)?)
+            .options(options.clone());
+        self.execute(request).await?.json()
+    }
+
+    #[deprecated(note = "use `execute_stream` and `sse::decode_chat_stream`")]
+    async fn post_stream(
+        &self,
+        url: Url,
+        body: &ChatCompletionRequest,
+        options: &RequestOptions,
+    ) -> Result<ChatCompletionStream, GroqError> {
+        let path = url
+            .as_str()
+            .strip_prefix(self.base_url().as_str())
+            .unwrap_or(url.path());
+        let mut body = body.clone();
+        body.stream = Some(true);
+        let request = Request::post(endpoint_for(path)?)
+            .json(serde_json::to_value(&body)?)
+            .options(options.clone());
+        Ok(decode_chat_stream(self.execute_stream(request).await?))
+    }
+
+    #[deprecated(note = "use `execute_stream` with `RequestOptions::retry_policy`")]
+    async fn post_stream_with_retry(
+        &self,
+        url: Url,
+        body: &ChatCompletionRequest,
+        max_retries: u32,
+        options: &RequestOptions,
+    ) -> Result<ChatCompletionStream, GroqError> {
+        let options = options.clone().retry_policy(RetryPolicy::new(max_retries));
+        #[allow(deprecated)]
+        self.post_stream(url, body, &options).await
+    }
+
+    #[deprecated(note = "use `execute` with `Request::post`")]
+    async fn post_json(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+        options: &RequestOptions,
+    ) -> Result<serde_json::Value, GroqError> {
+        let request = Request::post(endpoint_for(path)?)
+            .json(body.clone())
+            .options(options.clone());
+        Ok(self.execute(request).await?.body)
+    }
+
+    #[deprecated(note = "use `execute` with `Request::post(..).multipart(..)`")]
+    async fn post_multipart(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+        options: &RequestOptions,
+    ) -> Result<serde_json::Value, GroqError> {
+        let request = Request::post(endpoint_for(path)?)
+            .multipart(body.clone())
+            .options(options.clone());
+        Ok(self.execute(request).await?.body)
+    }
+
+    #[deprecated(note = "use `execute` with `Request::get`")]
+    async fn get_json(&self, path: &str, options: &RequestOptions) -> Result<serde_json::Value, GroqError> {
+        let request = Request::get(endpoint_for(path)?).options(options.clone());
+        Ok(self.execute(request).await?.body)
+    }
+
+    #[deprecated(note = "use `execute` with `Request::get(..).query(..)`")]
+    async fn get_with_params(
+        &self,
+        path: &str,
+        params: &[(&str, String)],
+        options: &RequestOptions,
+    ) -> Result<serde_json::Value, GroqError> {
+        let request = params
+            .iter()
+            .fold(Request::get(endpoint_for(path)?), |request, (name, value)| request.query(*name, value.clone()))
+            .options(options.clone());
+        Ok(self.execute(request).await?.body)
+    }
+
+    #[deprecated(note = "use `execute` with `Request::delete`")]
+    async fn delete_json(&self, path: &str, options: &RequestOptions) -> Result<serde_json::Value, GroqError> {
+        let request = Request::delete(endpoint_for(path)?).options(options.clone());
+        Ok(self.execute(request).await?.body)
+    }
+
+    #[deprecated(note = "use `execute` with `Request::post(Endpoint::Batches)`")]
+    async fn post_batch_create(&self, body: &serde_json::Value) -> Result<serde_json::Value, GroqError> {
+        Ok(self.execute(Request::post(Endpoint::Batches).json(body.clone())).await?.body)
+    }
+
+    #[deprecated(note = "use `execute` with `Request::get(Endpoint::Batch(id))`")]
+    async fn get_batch_retrieve(&self, batch_id: &str) -> Result<serde_json::Value, GroqError> {
+        Ok(self.execute(Request::get(Endpoint::Batch(batch_id.to_string()))).await?.body)
+    }
+
+    #[deprecated(note = "use `execute` with `Request::get(Endpoint::Batches)`")]
+    async fn get_batch_list(&self, params: &[(&str, String)]) -> Result<serde_json::Value, GroqError> {
+        let request = params
+            .iter()
+            .fold(Request::get(Endpoint::Batches), |request, (name, value)| request.query(*name, value.clone()));
+        Ok(self.execute(request).await?.body)
+    }
+
+    #[deprecated(note = "use `execute` with `Request::post(Endpoint::BatchCancel(id))`")]
+    async fn post_batch_cancel(&self, batch_id: &str) -> Result<serde_json::Value, GroqError> {
+        let request = Request::post(Endpoint::BatchCancel(batch_id.to_string())).json(serde_json::json!({}));
+        Ok(self.execute(request).await?.body)
+    }
+
+    /// Posts a JSON body and streams the binary response body as it arrives
+    #[deprecated(note = "use `execute_stream` with `Request::post`")]
+    async fn post_binary_stream(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+        options: &RequestOptions,
+    ) -> Result<ByteStream, GroqError> {
+        let request = Request::post(endpoint_for(path)?)
+            .json(body.clone())
+            .options(options.clone());
+        self.execute_stream(request).await
+    }
+}
+
+/// The path-based transport interface from before [`Request`] existed
+///
+/// Implementations of it keep working: every `LegacyTransport` is a
+/// [`Transport`] whose [`execute`](Transport::execute) calls the matching
+/// method below. The batch methods are no longer called.
+///
+/// To migrate a transport written before [`Request`] existed, change
+/// `impl Transport for MyTransport` to `impl LegacyTransport for MyTransport`;
+/// the method bodies stay the same.
+#[deprecated(note = "implement `Transport::execute` and `Transport::execute_stream` instead")]
+#[async_trait]
+pub trait LegacyTransport: Send + Sync {
+    async fn post_chat(
+        &self,
+        path: &str,
+        body: &ChatCompletionRequest,
+        options: &RequestOptions,
     ) -> Result<ChatCompletionResponse, GroqError>;
 
     async fn post_stream(
         &self,
         url: Url,
         body: &ChatCompletionRequest,
-    ) -> Result<Pin<Box<dyn Stream<Item = Result<ChatCompletionChunk, GroqError>> + Send>>, GroqError>;
+        options: &RequestOptions,
+    ) -> Result<ChatCompletionStream, GroqError>;
 
     async fn post_stream_with_retry(
         &self,
         url: Url,
         body: &ChatCompletionRequest,
         max_retries: u32,
-    ) -> Result<Pin<Box<dyn Stream<Item = Result<ChatCompletionChunk, GroqError>> + Send>>, GroqError>;
+        options: &RequestOptions,
+    ) -> Result<ChatCompletionStream, GroqError>;
 
     async fn post_json(
         &self,
         path: &str,
         body: &serde_json::Value,
+        options: &RequestOptions,
     ) -> Result<serde_json::Value, GroqError>;
 
+    /// Sends a multipart form built from `body`
+    ///
+    /// When `body` asks for a plain text audio `response_format` (`text`,
+    /// `srt` or `vtt`), the response body is returned as a JSON string.
     async fn post_multipart(
         &self,
         path: &str,
         body: &serde_json::Value,
+        options: &RequestOptions,
     ) -> Result<serde_json::Value, GroqError>;
 
-    async fn get_json(&self, path: &str) -> Result<serde_json::Value, GroqError>;
+    async fn get_json(
+        &self,
+        path: &str,
+        options: &RequestOptions,
+    ) -> Result<serde_json::Value, GroqError>;
 
     async fn get_with_params(
         &self,
         path: &str,
         params: &[(&str, String)],
+        options: &RequestOptions,
     ) -> Result<serde_json::Value, GroqError>;
 
-    async fn delete_json(&self, path: &str) -> Result<serde_json::Value, GroqError>;
+    async fn delete_json(
+        &self,
+        path: &str,
+        options: &RequestOptions,
+    ) -> Result<serde_json::Value, GroqError>;
 
-    // 批处理相关方法
     async fn post_batch_create(
         &self,
         body: &serde_json::Value,
@@ -184,13 +649,177 @@ pub trait Transport: Send + Sync {
         batch_id: &str,
     ) -> Result<serde_json::Value, GroqError>;
 
+    /// Posts a JSON body and streams the binary response body as it arrives
+    ///
+    /// The default implementation fails, so transports written before binary
+    /// responses existed keep compiling.
+    async fn post_binary_stream(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+        options: &RequestOptions,
+    ) -> Result<ByteStream, GroqError> {
+        let _ = (body, options);
+        Err(GroqError::InvalidMessage(format!(
+            "This transport cannot stream the binary response of {}",
+            path
+        )))
+    }
+
     fn base_url(&self) -> &Url;
+
+    /// Returns the metrics this transport records, if it records any
+    fn metrics(&self) -> Option<&TransportMetrics> {
+        None
+    }
+}
+
+#[allow(deprecated)]
+#[async_trait]
+impl<T: LegacyTransport> Transport for T {
+    async fn execute(&self, request: Request) -> Result<Response, GroqError> {
+        let path = request.endpoint.path();
+        let options = &request.options;
+        let body = match (request.method, &request.body) {
+            (Method::POST, RequestBody::Json(body)) if request.endpoint == Endpoint::ChatCompletions => {
+                let chat: ChatCompletionRequest = serde_json::from_value(body.clone())?;
+                serde_json::to_value(LegacyTransport::post_chat(self, &path, &chat, options).await?)?
+            }
+            (Method::POST, RequestBody::Json(body)) => {
+                LegacyTransport::post_json(self, &path, body, options).await?
+            }
+            (Method::POST, RequestBody::Multipart(body)) => {
+                LegacyTransport::post_multipart(self, &path, body, options).await?
+            }
+            (Method::POST, RequestBody::Empty) => {
+                LegacyTransport::post_json(self, &path, &serde_json::json!({}), options).await?
+            }
+            (Method::GET, _) if request.query.is_empty() => {
+                LegacyTransport::get_json(self, &path, options).await?
+            }
+            (Method::GET, _) => {
+                let params: Vec<(&str, String)> = request
+                    .query
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.clone()))
+                    .collect();
+                LegacyTransport::get_with_params(self, &path, &params, options).await?
+            }
+            (Method::DELETE, _) => LegacyTransport::delete_json(self, &path, options).await?,
+            (method, _) => {
+                return Err(GroqError::InvalidMessage(format!(
+                    "This transport cannot send {} {}",
+                    method, path
+                )));
+            }
+        };
+        Ok(Response::new(body))
+    }
+
+    async fn execute_stream(&self, request: Request) -> Result<ByteStream, GroqError> {
+        let path = request.endpoint.path();
+        let body = request.body_json().unwrap_or_else(|| serde_json::json!({}));
+        if request.endpoint != Endpoint::ChatCompletions {
+            return LegacyTransport::post_binary_stream(self, &path, &body, &request.options).await;
+        }
+        let chat: ChatCompletionRequest = serde_json::from_value(body)?;
+        let url = LegacyTransport::base_url(self).join(&path)?;
+        let max_retries = request.options.retry().map_or(0, |policy| policy.max_retries);
+        let stream = LegacyTransport::post_stream_with_retry(self, url, &chat, max_retries, &request.options).await?;
+        Ok(Box::pin(stream.map(|chunk| chunk.and_then(|chunk| encode_chunk(&chunk)))))
+    }
+
+    fn base_url(&self) -> &Url {
+        LegacyTransport::base_url(self)
+    }
+
+    fn metrics(&self) -> Option<&TransportMetrics> {
+        LegacyTransport::metrics(self)
+    }
+}
+
+/// Wire compatibility mode for chat completion requests
+///
+/// Some OpenAI-compatible gateways only understand OpenAI's parameter names.
+/// The compatibility mode rewrites the serialized request body so the same code
+/// works against Groq and other OpenAI-compatible backends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CompatMode {
+    /// Send requests exactly as the Groq API documents them
+    #[default]
+    Groq,
+    /// Rename `max_completion_tokens` to `max_tokens` and drop Groq-only
+    /// extensions (`search_settings`, `compound_custom`)
+    OpenAI,
+}
+
+impl CompatMode {
+    /// Serializes a chat request according to this compatibility mode
+    pub fn chat_body(&self, request: &ChatCompletionRequest) -> Result<serde_json::Value, GroqError> {
+        Ok(self.apply(serde_json::to_value(request)?))
+    }
+
+    /// Rewrites a serialized chat request according to this compatibility mode
+    fn apply(&self, mut body: serde_json::Value) -> serde_json::Value {
+        if let (CompatMode::OpenAI, Some(map)) = (self, body.as_object_mut()) {
+            if let Some(max_tokens) = map.remove("max_completion_tokens") {
+                map.entry("max_tokens").or_insert(max_tokens);
+            }
+            map.remove("search_settings");
+            map.remove("compound_custom");
+        }
+        body
+    }
 }
 
 pub struct HttpTransport {
     client: Client,
     base_url: Url,
     api_key: ApiKey,
+    compat_mode: CompatMode,
+    request_timeout: Option<Duration>,
+    metrics: TransportMetrics,
+    signer: Option<Arc<dyn RequestSigner>>,
+    sdk_header: Option<HeaderValue>,
+    #[cfg(feature = "middleware")]
+    middleware: Option<reqwest_middleware::ClientWithMiddleware>,
+}
+
+/// A call whose outcome has not been recorded yet
+struct PendingCall {
+    path: String,
+    started: Instant,
+    request_bytes: Option<usize>,
+}
+
+impl PendingCall {
+    fn finish(self, metrics: &TransportMetrics, response_bytes: Option<usize>, ok: bool) {
+        metrics.record(
+            &self.path,
+            RequestSample {
+                request_bytes: self.request_bytes,
+                response_bytes,
+                duration: self.started.elapsed(),
+                ok,
+            },
+        );
+    }
+}
+
+/// Records a stream's call when the stream ends or is dropped
+struct StreamCall {
+    call: Option<PendingCall>,
+    metrics: TransportMetrics,
+    response_bytes: usize,
+    ok: bool,
+}
+
+impl Drop for StreamCall {
+    fn drop(&mut self) {
+        if let Some(call) = self.call.take() {
+            call.finish(&self.metrics, Some(self.response_bytes), self.ok);
+        }
+    }
 }
 
 impl HttpTransport {
@@ -204,283 +833,323 @@ impl HttpTransport {
         if let Some(p) = proxy {
             builder = builder.proxy(p);
         }
-        let client = builder.build()?;
-        Ok(Self {
+        Ok(Self::from_client(builder.build()?, base_url, api_key))
+    }
+
+    /// Creates a transport around a preconfigured `reqwest::Client`
+    pub fn from_client(client: Client, base_url: Url, api_key: ApiKey) -> Self {
+        Self {
             client,
             base_url,
             api_key,
-        })
-    }
-
-    async fn send(&self, builder: RequestBuilder) -> Result<reqwest::Response, GroqError> {
-        debug!("Sending request: {:?}", builder);
-        let response = builder
-            .header("Authorization", format!("Bearer {}", self.api_key.0))
-            .send()
-            .await
-            .map_err(GroqError::from)?;
-        debug!(
-            "Response status: {}, headers: {:?}",
-            response.status(),
-            response.headers()
-        );
-        if !response.status().is_success() {
-            let headers = response.headers().clone();
-            let status = response.status();
-            let text = response.text().await?;
-            debug!("Error response body: {}", text);
-            return Err(GroqError::Api(GroqApiError::from_response(
-                status, text, &headers,
-            )));
+            compat_mode: CompatMode::default(),
+            request_timeout: None,
+            metrics: TransportMetrics::default(),
+            signer: None,
+            sdk_header: HeaderValue::from_str(&sdk_header_value()).ok(),
+            #[cfg(feature = "middleware")]
+            middleware: None,
         }
-        Ok(response)
     }
 
-    async fn build_multipart(body: &serde_json::Value) -> Result<Form, GroqError> {
-        let mut form = Form::new();
-
-        if let Some(url) = body["url"].as_str() {
-            form = form.part("url", Part::text(url.to_string()));
-        }
-
-        if let Some(file_path) = body["file"].as_str() {
-            let part = Part::file(file_path).await.map_err(|e| GroqError::InvalidMessage(format!("File error: {}", e)))?;
-            form = form.part("file", part);
-        }
-
-        if let Some(model) = body["model"].as_str() {
-            form = form.part("model", Part::text(model.to_string()));
-        }
-
-        if let Some(language) = body["language"].as_str() {
-            form = form.part("language", Part::text(language.to_string()));
-        }
-
-        if let Some(prompt) = body["prompt"].as_str() {
-            form = form.part("prompt", Part::text(prompt.to_string()));
-        }
-
-        if let Some(response_format) = body["response_format"].as_str() {
-            form = form.part("response_format", Part::text(response_format.to_string()));
-        }
-
-        if let Some(temperature) = body["temperature"].as_f64() {
-            form = form.part("temperature", Part::text(temperature.to_string()));
-        }
+    /// Creates a transport that sends requests through a `reqwest-middleware` client
+    ///
+    /// Requests are built as usual and executed by `client`, so its tracing,
+    /// retry or caching middleware applies to every Groq call. Each request's
+    /// [`Endpoint`] is in its extensions, for middleware that labels requests.
+    #[cfg(feature = "middleware")]
+    pub fn from_middleware_client(
+        client: reqwest_middleware::ClientWithMiddleware,
+        base_url: Url,
+        api_key: ApiKey,
+    ) -> Self {
+        // The plain client only builds requests; `client` executes them
+        let mut transport = Self::from_client(Client::new(), base_url, api_key);
+        transport.middleware = Some(client);
+        transport
+    }
 
-        Ok(form)
+    /// Applies `timeout` to every request instead of relying on the client's own
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
     }
 
-    async fn attempt_stream_request(
-        &self,
-        url: Url,
-        body: &ChatCompletionRequest,
-    ) -> Result<Pin<Box<dyn Stream<Item = Result<ChatCompletionChunk, GroqError>> + Send>>, GroqError>
-    {
-        let mut request = body.clone();
-        request.stream = Some(true);
-        let builder = self.client.post(url).json(&request);
-        let response = self.send(builder).await?;
-
-        // 改进的流式处理：使用map_with进行状态管理
-        let mut buffer = StreamBuffer::new();
-        let stream = response
-            .bytes_stream()
-            .map_err(GroqError::from)
-            .map(move |result| {
-                match result {
-                    Ok(bytes) => {
-                        // 将新字节添加到缓冲区
-                        buffer.add_bytes(&bytes);
-
-                        // 处理完整的行
-                        let chunks = buffer.process_lines();
-
-                        if chunks.is_empty() {
-                            futures::stream::iter(vec![])
-                        } else {
-                            futures::stream::iter(chunks)
-                        }
-                    }
-                    Err(e) => {
-                        // 记录错误但继续处理
-                        debug!("Stream bytes error: {:?}", e);
-                        futures::stream::iter(vec![Err(GroqError::from(e))])
-                    }
-                }
-            })
-            .flatten()
-            .filter_map(|result| async move {
-                match result {
-                    Ok(chunk) => Some(Ok(chunk)),
-                    Err(e) => {
-                        // 对于解析错误，记录但不中断流
-                        debug!("Chunk parsing error: {:?}", e);
-                        None
-                    }
-                }
-            });
+    /// Sets the wire compatibility mode used for chat requests
+    pub fn with_compat_mode(mut self, mode: CompatMode) -> Self {
+        self.compat_mode = mode;
+        self
+    }
 
-        Ok(Box::pin(stream))
+    /// Sets the value of the `x-groqai-sdk` header, or stops sending it with `None`
+    ///
+    /// Defaults to `groqai-rust/<version>`.
+    pub fn with_sdk_header(mut self, value: Option<HeaderValue>) -> Self {
+        self.sdk_header = value;
+        self
     }
-}
 
-#[async_trait]
-impl Transport for HttpTransport {
-    async fn post_chat(
-        &self,
-        path: &str,
-        body: &ChatCompletionRequest,
-    ) -> Result<ChatCompletionResponse, GroqError> {
-        let url = self
-            .base_url
-            .join(path)
-            .map_err(|e| GroqError::InvalidMessage(format!("URL parse error: {}", e)))?;
-        let builder = self.client.post(url).json(body);
-        let response = self.send(builder).await?;
-        response.json().await.map_err(GroqError::from)
+    /// Signs every request with `signer` right before it is sent
+    pub fn with_signer(mut self, signer: Arc<dyn RequestSigner>) -> Self {
+        self.signer = Some(signer);
+        self
     }
 
-    async fn post_stream(
-        &self,
-        url: Url,
-        body: &ChatCompletionRequest,
-    ) -> Result<Pin<Box<dyn Stream<Item = Result<ChatCompletionChunk, GroqError>> + Send>>, GroqError>
-    {
-        self.post_stream_with_retry(url, body, 0).await
+    /// Builds the HTTP request for `request`
+    ///
+    /// Multipart bodies are rebuilt for every attempt, since a file part is
+    /// streamed and can only be sent once.
+    async fn build(&self, request: &Request) -> Result<RequestBuilder, GroqError> {
+        let url = self.base_url.join(&request.endpoint.path())?;
+        let mut builder = self.client.request(request.method.clone(), url);
+        if !request.query.is_empty() {
+            builder = builder.query(&request.query);
+        }
+        let builder = match &request.body {
+            RequestBody::Empty => builder,
+            RequestBody::Json(body) if request.endpoint == Endpoint::ChatCompletions => {
+                builder.json(&self.compat_mode.apply(body.clone()))
+            }
+            RequestBody::Json(body) => builder.json(body),
+            #[cfg(any(feature = "audio", feature = "files"))]
+            RequestBody::Multipart(body) => builder.multipart(multipart::form(body, None, &request.options).await?),
+            #[cfg(any(feature = "audio", feature = "files"))]
+            RequestBody::MultipartFile { fields, content } => {
+                builder.multipart(multipart::form(fields, Some(content), &request.options).await?)
+            }
+            #[cfg(not(any(feature = "audio", feature = "files")))]
+            RequestBody::Multipart(_) | RequestBody::MultipartFile { .. } => {
+                return Err(GroqError::InvalidMessage(
+                    "Multipart uploads need the `audio` or `files` feature".to_string(),
+                ))
+            }
+        };
+        Ok(builder)
     }
 
-    async fn post_stream_with_retry(
-        &self,
-        url: Url,
-        body: &ChatCompletionRequest,
-        max_retries: u32,
-    ) -> Result<Pin<Box<dyn Stream<Item = Result<ChatCompletionChunk, GroqError>> + Send>>, GroqError>
+    /// Runs `attempt` until it succeeds or the request's retry policy gives up
+    async fn with_retries<T, F, Fut>(&self, request: &Request, mut attempt: F) -> Result<T, GroqError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, GroqError>>,
     {
-        let mut retry_count = 0;
-        let mut last_error = None;
-
-        while retry_count <= max_retries {
-            match self.attempt_stream_request(url.clone(), body).await {
-                Ok(stream) => {
-                    debug!("Stream request successful after {} retries", retry_count);
-                    return Ok(stream);
+        let policy = request.options.retry().unwrap_or_default();
+        let mut retries = 0;
+        loop {
+            match attempt().await {
+                Err(e) if retries < policy.max_retries && RetryPolicy::should_retry(&e) => {
+                    retries += 1;
+                    self.metrics.record_retry(&request.endpoint.path());
+                    let retry_after = match &e {
+                        GroqError::Api(api) => api.retry_after,
+                        _ => None,
+                    };
+                    debug!(
+                        "Request to {} failed (attempt {}/{}), retrying: {}",
+                        request.endpoint, retries, policy.max_retries, e
+                    );
+                    tokio::time::sleep(retry_after.unwrap_or_else(|| policy.delay(retries))).await;
                 }
-                Err(e) => {
-                    last_error = Some(e.clone());
-                    retry_count += 1;
-
-                    if retry_count <= max_retries {
-                        debug!(
-                            "Stream request failed (attempt {}/{}), retrying...",
-                            retry_count, max_retries
-                        );
-                        // 指数退避重试
-                        let delay = Duration::from_millis(100 * 2_u64.pow(retry_count as u32));
-                        tokio::time::sleep(delay).await;
+                result => return result,
+            }
+        }
+    }
+
+    /// Sends `request` once, recording failed calls in the metrics
+    ///
+    /// Successful calls are recorded by the caller once the body was read.
+    async fn send(&self, request: &Request) -> Result<(reqwest::Response, PendingCall), GroqError> {
+        let mut call = PendingCall {
+            path: request.endpoint.path(),
+            started: Instant::now(),
+            request_bytes: None,
+        };
+        let result = match self.build(request).await {
+            Ok(builder) => match self.prepare(builder, &request.options) {
+                Ok((builder, request_bytes)) => {
+                    call.request_bytes = request_bytes;
+                    match self.sign(builder).await {
+                        Ok(builder) => self.dispatch(&request.endpoint, builder).await,
+                        Err(e) => Err(e),
                     }
                 }
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(response) => Ok((response, call)),
+            Err(e) => {
+                call.finish(&self.metrics, None, false);
+                Err(e)
             }
         }
-
-        Err(last_error.unwrap_or_else(|| {
-            GroqError::InvalidMessage("Max retries exceeded for stream request".to_string())
-        }))
     }
 
-    async fn post_json(
-        &self,
-        path: &str,
-        body: &serde_json::Value,
-    ) -> Result<serde_json::Value, GroqError> {
-        let url = self
-            .base_url
-            .join(path)
-            .map_err(|e| GroqError::InvalidMessage(format!("URL parse error: {}", e)))?;
-        let builder = self.client.post(url).json(body);
-        let response = self.send(builder).await?;
-        response.json().await.map_err(GroqError::from)
+    /// Sends `request` once and reads the whole response body
+    async fn send_body(&self, request: &Request) -> Result<Bytes, GroqError> {
+        let (response, call) = self.send(request).await?;
+        match response.bytes().await {
+            Ok(body) => {
+                call.finish(&self.metrics, Some(body.len()), true);
+                Ok(body)
+            }
+            Err(e) => {
+                call.finish(&self.metrics, None, false);
+                Err(e.into())
+            }
+        }
     }
 
-    async fn post_multipart(
+    /// Applies the timeout, headers and credentials, returning the body size if known
+    fn prepare(
         &self,
-        path: &str,
-        body: &serde_json::Value,
-    ) -> Result<serde_json::Value, GroqError> {
-        let url = self.base_url.join(path)?;
-        let form = Self::build_multipart(body).await?;
-        let builder = self.client.post(url).multipart(form);
-        let response = self.send(builder).await?;
-        response.json().await.map_err(GroqError::from)
+        mut builder: RequestBuilder,
+        options: &RequestOptions,
+    ) -> Result<(RequestBuilder, Option<usize>), GroqError> {
+        if let Some(timeout) = options.request_timeout().or(self.request_timeout) {
+            builder = builder.timeout(timeout);
+        }
+        // A per-request header of the same name replaces the default
+        if let Some(value) = &self.sdk_header {
+            if !options.headers().iter().any(|(name, _)| name.eq_ignore_ascii_case(SDK_HEADER)) {
+                builder = builder.header(SDK_HEADER, value.clone());
+            }
+        }
+        for (name, value) in options.headers() {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| GroqError::InvalidMessage(format!("Invalid header name {:?}: {}", name, e)))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| GroqError::InvalidMessage(format!("Invalid value for header {}: {}", name, e)))?;
+            builder = builder.header(name, value);
+        }
+        if let Some(key) = options.idempotency() {
+            let value = HeaderValue::from_str(key)
+                .map_err(|e| GroqError::InvalidMessage(format!("Invalid idempotency key: {}", e)))?;
+            builder = builder.header("Idempotency-Key", value);
+        }
+        debug!("Sending request: {:?}", builder);
+        let builder = builder.header("Authorization", format!("Bearer {}", self.api_key.0));
+        let (client, request) = builder.build_split();
+        let request = request?;
+        // Streamed multipart bodies only know their length from the header
+        let request_bytes = request
+            .body()
+            .and_then(reqwest::Body::as_bytes)
+            .map(<[u8]>::len)
+            .or_else(|| {
+                request
+                    .headers()
+                    .get(CONTENT_LENGTH)
+                    .and_then(|v| v.to_str().ok()?.parse().ok())
+            });
+        Ok((RequestBuilder::from_parts(client, request), request_bytes))
     }
 
-    async fn get_json(&self, path: &str) -> Result<serde_json::Value, GroqError> {
-        let url = self.base_url.join(path)?;
-        let builder = self.client.get(url);
-        let response = self.send(builder).await?;
-        response.json().await.map_err(GroqError::from)
+    /// Lets the signer, if any, adjust the finished request
+    async fn sign(&self, builder: RequestBuilder) -> Result<RequestBuilder, GroqError> {
+        let Some(signer) = &self.signer else {
+            return Ok(builder);
+        };
+        let (client, request) = builder.build_split();
+        let mut request = request?;
+        signer.sign(&mut SigningRequest::new(&mut request)).await?;
+        Ok(RequestBuilder::from_parts(client, request))
     }
 
-    async fn get_with_params(
-        &self,
-        path: &str,
-        params: &[(&str, String)],
-    ) -> Result<serde_json::Value, GroqError> {
-        let url = self.base_url.join(path)?;
-        let mut url_builder = self.client.get(url);
-        for (key, value) in params {
-            url_builder = url_builder.query(&[(*key, value)]);
+    #[cfg_attr(not(feature = "middleware"), allow(unused_variables))]
+    async fn dispatch(&self, endpoint: &Endpoint, builder: RequestBuilder) -> Result<reqwest::Response, GroqError> {
+        #[cfg(feature = "middleware")]
+        let response = match &self.middleware {
+            Some(client) => {
+                // Lets middleware label or route requests by endpoint
+                let mut extensions = http::Extensions::new();
+                extensions.insert(endpoint.clone());
+                client.execute_with_extensions(builder.build()?, &mut extensions).await?
+            }
+            None => builder.send().await?,
+        };
+
+        #[cfg(not(feature = "middleware"))]
+        let response = builder.send().await?;
+        debug!(
+            "Response status: {}, headers: {:?}",
+            response.status(),
+            response.headers()
+        );
+        if !response.status().is_success() {
+            let headers = response.headers().clone();
+            let status = response.status();
+            let text = response.text().await?;
+            debug!("Error response body: {}", text);
+            return Err(GroqError::Api(GroqApiError::from_response(
+                status, text, &headers,
+            )));
         }
-        let response = self.send(url_builder).await?;
-        response.json().await.map_err(GroqError::from)
+        Ok(response)
     }
+}
 
-    async fn delete_json(&self, path: &str) -> Result<serde_json::Value, GroqError> {
-        let url = self.base_url.join(path)?;
-        let builder = self.client.delete(url);
-        let response = self.send(builder).await?;
-        response.json().await.map_err(GroqError::from)
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn execute(&self, request: Request) -> Result<Response, GroqError> {
+        // Plain text audio formats come back as a JSON string
+        #[cfg(feature = "audio")]
+        let text = match &request.body {
+            RequestBody::Multipart(body) | RequestBody::MultipartFile { fields: body, .. } => body.get("response_format").and_then(|f| f.as_str()).is_some_and(is_text_format),
+            _ => false,
+        };
+        #[cfg(not(feature = "audio"))]
+        let text = false;
+        let body = self.with_retries(&request, || self.send_body(&request)).await?;
+        let body = if text {
+            serde_json::Value::String(String::from_utf8_lossy(&body).into_owned())
+        } else {
+            serde_json::from_slice(&body)?
+        };
+        Ok(Response::new(body))
     }
 
-    async fn post_batch_create(
-        &self,
-        body: &serde_json::Value,
-    ) -> Result<serde_json::Value, GroqError> {
-        self.post_json("batches", body).await
-    }
-    
-    async fn get_batch_retrieve(
-        &self,
-        batch_id: &str,
-    ) -> Result<serde_json::Value, GroqError> {
-        self.get_json(&format!("batches/{}", batch_id)).await
-    }
-    
-    async fn get_batch_list(
-        &self,
-        params: &[(&str, String)],
-    ) -> Result<serde_json::Value, GroqError> {
-        self.get_with_params("batches", params).await
-    }
-    
-    async fn post_batch_cancel(
-        &self,
-        batch_id: &str,
-    ) -> Result<serde_json::Value, GroqError> {
-        let empty_body = serde_json::json!({});
-        self.post_json(&format!("batches/{}/cancel", batch_id), &empty_body).await
+    async fn execute_stream(&self, request: Request) -> Result<ByteStream, GroqError> {
+        let (response, call) = self.with_retries(&request, || self.send(&request)).await?;
+        let mut call = StreamCall {
+            call: Some(call),
+            metrics: self.metrics.clone(),
+            response_bytes: 0,
+            ok: true,
+        };
+        let stream = response.bytes_stream().map(move |result| {
+            // Capture the whole guard so it is dropped with the stream
+            let call = &mut call;
+            match result {
+                Ok(bytes) => {
+                    call.response_bytes += bytes.len();
+                    Ok(bytes)
+                }
+                Err(e) => {
+                    call.ok = false;
+                    Err(GroqError::from(e))
+                }
+            }
+        });
+        Ok(Box::pin(stream))
     }
 
     fn base_url(&self) -> &Url {
         &self.base_url
     }
+
+    fn metrics(&self) -> Option<&TransportMetrics> {
+        Some(&self.metrics)
+    }
 }
 
 #[derive(Clone)]
 pub struct ApiKey(String);
 
 impl ApiKey {
-    pub fn new(key: String) -> Result<Self, GroqError> {
+    pub fn new(key: impl Into<String>) -> Result<Self, GroqError> {
+        let key = key.into();
         let trimmed = key.trim();
         if trimmed.is_empty() || !trimmed.starts_with("gsk_") {
             return Err(GroqError::InvalidApiKey(
@@ -2,10 +2,15 @@
 //! 
 //! 类型定义模块，包含所有 API 请求和响应的数据结构
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::cache::CacheHit;
+use crate::tools::{ToolGuard, ToolRejection, ToolViolation};
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum Role {
     System,
@@ -14,8 +19,14 @@ pub enum Role {
     Tool,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+/// A Groq model id
+///
+/// Serialized as the raw model id (`"llama-3.1-8b-instant"`), so it can be
+/// read from configuration files; ids without a variant become
+/// [`KnownModel::Other`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[serde(into = "String", try_from = "String")]
 pub enum KnownModel {
     Llama3_1_8bInstant,
     Llama3_1_70bVersatile,
@@ -26,27 +37,126 @@ pub enum KnownModel {
     Other(String),
 }
 
+/// Decommissioned model ids and the ids Groq recommends instead
+const MODEL_MIGRATIONS: &[(&str, &str)] = &[
+    ("llama-3.1-70b-versatile", "llama-3.3-70b-versatile"),
+    ("llama-3.1-405b-reasoning", "llama-3.3-70b-versatile"),
+    ("llama3-70b-8192", "llama-3.3-70b-versatile"),
+    ("llama3-8b-8192", "llama-3.1-8b-instant"),
+    ("llama-3.2-1b-preview", "llama-3.1-8b-instant"),
+    ("llama-3.2-3b-preview", "llama-3.1-8b-instant"),
+    ("llama-3.2-11b-vision-preview", "meta-llama/llama-4-scout-17b-16e-instruct"),
+    ("llama-3.2-90b-vision-preview", "meta-llama/llama-4-maverick-17b-128e-instruct"),
+    ("mixtral-8x7b-32768", "llama-3.3-70b-versatile"),
+    ("gemma-7b-it", "llama-3.1-8b-instant"),
+    ("gemma2-9b-it", "llama-3.1-8b-instant"),
+    ("qwen2.5-72b-instruct", "qwen/qwen3-32b"),
+    ("qwen-qwq-32b", "qwen/qwen3-32b"),
+    ("deepseek-r1-distill-llama-70b", "openai/gpt-oss-120b"),
+];
+
+impl KnownModel {
+    /// Returns the model id sent to the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            KnownModel::Llama3_1_8bInstant => "llama-3.1-8b-instant",
+            KnownModel::Llama3_1_70bVersatile => "llama-3.1-70b-versatile",
+            KnownModel::Llama3_1_405bReasoning => "llama-3.1-405b-reasoning",
+            KnownModel::Mixtral8x7b32768 => "mixtral-8x7b-32768",
+            KnownModel::Gemma2_9bIt => "gemma2-9b-it",
+            KnownModel::Qwen2_5_72bInstruct => "qwen2.5-72b-instruct",
+            KnownModel::Other(s) => s,
+        }
+    }
+
+    /// Returns true if Groq has decommissioned this model
+    pub fn is_deprecated(&self) -> bool {
+        self.replacement().is_some()
+    }
+
+    /// Returns the id of the model Groq recommends instead of a decommissioned one
+    pub fn replacement(&self) -> Option<&'static str> {
+        let id = self.as_str();
+        MODEL_MIGRATIONS.iter().find(|(old, _)| *old == id).map(|(_, new)| *new)
+    }
+
+    /// Returns the recommended replacement, or the model itself if it is current
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use groqai::KnownModel;
+    ///
+    /// let model: KnownModel = "mixtral-8x7b-32768".parse().unwrap();
+    /// assert_eq!(model.migrate().as_str(), "llama-3.3-70b-versatile");
+    /// assert_eq!(KnownModel::Llama3_1_8bInstant.migrate(), KnownModel::Llama3_1_8bInstant);
+    /// ```
+    pub fn migrate(&self) -> KnownModel {
+        match self.replacement() {
+            Some(id) => KnownModel::from_id(id),
+            None => self.clone(),
+        }
+    }
+
+    fn from_id(id: &str) -> KnownModel {
+        match id {
+            "llama-3.1-8b-instant" => KnownModel::Llama3_1_8bInstant,
+            "llama-3.1-70b-versatile" => KnownModel::Llama3_1_70bVersatile,
+            "llama-3.1-405b-reasoning" => KnownModel::Llama3_1_405bReasoning,
+            "mixtral-8x7b-32768" => KnownModel::Mixtral8x7b32768,
+            "gemma2-9b-it" => KnownModel::Gemma2_9bIt,
+            "qwen2.5-72b-instruct" => KnownModel::Qwen2_5_72bInstruct,
+            other => KnownModel::Other(other.to_string()),
+        }
+    }
+}
+
 impl From<KnownModel> for String {
     fn from(model: KnownModel) -> Self {
         match model {
-            KnownModel::Llama3_1_8bInstant => "llama-3.1-8b-instant".to_string(),
-            KnownModel::Llama3_1_70bVersatile => "llama-3.1-70b-versatile".to_string(),
-            KnownModel::Llama3_1_405bReasoning => "llama-3.1-405b-reasoning".to_string(),
-            KnownModel::Mixtral8x7b32768 => "mixtral-8x7b-32768".to_string(),
-            KnownModel::Gemma2_9bIt => "gemma2-9b-it".to_string(),
-            KnownModel::Qwen2_5_72bInstruct => "qwen2.5-72b-instruct".to_string(),
             KnownModel::Other(s) => s,
+            known => known.as_str().to_string(),
         }
     }
 }
 
 impl fmt::Display for KnownModel {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", String::from(self.clone()))
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for KnownModel {
+    type Err = String;
+
+    /// Parses a model id; ids without a variant become [`KnownModel::Other`]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let id = s.trim();
+        if id.is_empty() {
+            return Err("model id is empty".to_string());
+        }
+        Ok(KnownModel::from_id(id))
+    }
+}
+
+impl TryFrom<&str> for KnownModel {
+    type Error = String;
+
+    fn try_from(id: &str) -> Result<Self, Self::Error> {
+        id.parse()
+    }
+}
+
+impl TryFrom<String> for KnownModel {
+    type Error = String;
+
+    fn try_from(id: String) -> Result<Self, Self::Error> {
+        id.parse()
     }
 }
 
 #[derive(PartialEq, Serialize, Clone, Deserialize, Debug)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum MessageContent {
     Text(String),
@@ -64,9 +174,66 @@ impl MessageContent {
     pub fn parts(parts: Vec<MessagePart>) -> Self {
         Self::Parts(parts)
     }
+    /// Builds content from several images, keeping their order
+    ///
+    /// Each item may be a URL string or an [`ImageUrl`] with its own `detail`.
+    pub fn from_images<I>(images: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<ImageUrl>,
+    {
+        Self::Parts(
+            images
+                .into_iter()
+                .map(|image| MessagePart::ImageUrl { image_url: image.into() })
+                .collect(),
+        )
+    }
+    /// Puts a text part before the existing content
+    pub fn with_text(self, text: impl Into<String>) -> Self {
+        let mut parts = vec![MessagePart::Text { text: text.into() }];
+        match self {
+            MessageContent::Text(existing) => parts.push(MessagePart::Text { text: existing }),
+            MessageContent::ImageUrl(image_url) => parts.push(MessagePart::ImageUrl { image_url }),
+            MessageContent::Parts(existing) => parts.extend(existing),
+        }
+        Self::Parts(parts)
+    }
+    /// Returns the images in the content, in order
+    pub fn images(&self) -> Vec<&ImageUrl> {
+        match self {
+            MessageContent::Text(_) => Vec::new(),
+            MessageContent::ImageUrl(image) => vec![image],
+            MessageContent::Parts(parts) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    MessagePart::ImageUrl { image_url } => Some(image_url),
+                    MessagePart::Text { .. } => None,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for MessageContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageContent::Text(text) => write!(f, "{}", text),
+            MessageContent::ImageUrl(image) => write!(f, "{}", image.url),
+            MessageContent::Parts(parts) => {
+                for part in parts {
+                    if let MessagePart::Text { text } = part {
+                        write!(f, "{}", text)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
 }
 
 #[derive(Serialize, Clone, Deserialize, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum MessagePart {
     #[serde(rename = "text")]
@@ -76,6 +243,7 @@ pub enum MessagePart {
 }
 
 #[derive(Serialize, Clone, Deserialize, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 pub struct ImageUrl {
     pub url: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -89,9 +257,28 @@ impl ImageUrl {
             detail: None,
         }
     }
+    /// Sets the detail level (`"low"`, `"high"` or `"auto"`)
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+impl From<&str> for ImageUrl {
+    fn from(url: &str) -> Self {
+        Self::new(url)
+    }
+}
+
+impl From<String> for ImageUrl {
+    fn from(url: String) -> Self {
+        Self::new(url)
+    }
 }
 
 #[derive(Serialize, Clone, Deserialize, Debug)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct ToolCall {
     pub id: String,
     #[serde(rename = "type")]
@@ -99,15 +286,79 @@ pub struct ToolCall {
     pub function: FunctionCall,
 }
 
+impl ToolCall {
+    /// Deserializes the call's arguments into a typed value
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ToolRejection`] if the arguments are not valid JSON or do not
+    /// match `T`. The rejection can be sent back to the model with
+    /// [`ToolRejection::to_tool_message`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use groqai::types::{FunctionCall, ToolCall};
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Weather {
+    ///     location: String,
+    /// }
+    ///
+    /// let call = ToolCall {
+    ///     id: "call_1".to_string(),
+    ///     type_: "function".to_string(),
+    ///     function: FunctionCall {
+    ///         name: "get_weather".to_string(),
+    ///         arguments: r#"{"location": "Tokyo"}"#.to_string(),
+    ///     },
+    /// };
+    ///
+    /// let args: Weather = call.parse_arguments()?;
+    /// assert_eq!(args.location, "Tokyo");
+    /// # Ok::<(), groqai::ToolRejection>(())
+    /// ```
+    pub fn parse_arguments<T: DeserializeOwned>(&self) -> Result<T, ToolRejection> {
+        serde_json::from_str(&self.function.arguments).map_err(|e| self.rejection(e))
+    }
+
+    /// Validates the arguments against the declared schema, then deserializes them
+    ///
+    /// The schema is taken from the tool in `tools` whose name matches the call.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ToolRejection`] if the function is not declared in `tools`,
+    /// the arguments violate its schema, or they do not match `T`.
+    pub fn parse_arguments_for<T: DeserializeOwned>(&self, tools: &[Tool]) -> Result<T, ToolRejection> {
+        let arguments = ToolGuard::new().check(self, tools)?;
+        serde_json::from_value(arguments).map_err(|e| self.rejection(e))
+    }
+
+    fn rejection(&self, err: serde_json::Error) -> ToolRejection {
+        ToolRejection {
+            tool_call_id: self.id.clone(),
+            function: self.function.name.clone(),
+            violations: vec![ToolViolation::new("", format!("invalid arguments: {}", err))],
+        }
+    }
+}
+
 #[derive(Serialize, Clone, Deserialize, Debug)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct FunctionCall {
     pub name: String,
     pub arguments: String,
 }
 
 #[derive(Serialize, Clone, Deserialize, Debug)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct ChatMessage {
     pub role: Role,
+    /// Assistant messages that only call tools have `null` or no content; it is read as empty text
+    #[serde(default = "empty_content", deserialize_with = "content_or_empty")]
     pub content: MessageContent,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_calls: Option<Vec<ToolCall>>,
@@ -115,6 +366,17 @@ pub struct ChatMessage {
     pub tool_call_id: Option<String>,
 }
 
+fn empty_content() -> MessageContent {
+    MessageContent::Text(String::new())
+}
+
+fn content_or_empty<'de, D>(deserializer: D) -> Result<MessageContent, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<MessageContent>::deserialize(deserializer)?.unwrap_or_else(empty_content))
+}
+
 impl ChatMessage {
     pub fn new_text(role: Role, content: impl Into<String>) -> Self {
         Self {
@@ -145,7 +407,7 @@ impl ChatMessage {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 pub struct Tool {
     #[serde(rename = "type")]
     pub type_: String,
@@ -153,7 +415,7 @@ pub struct Tool {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 pub struct FunctionDef {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -162,7 +424,7 @@ pub struct FunctionDef {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 pub struct ResponseFormat {
     #[serde(rename = "type")]
     pub type_: String,
@@ -170,16 +432,111 @@ pub struct ResponseFormat {
     pub json_schema: Option<serde_json::Value>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "snake_case")]
-pub struct ToolChoice {
-    #[serde(rename = "type")]
-    pub type_: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub function: Option<serde_json::Value>,
+impl ResponseFormat {
+    /// JSON mode: the reply is any valid JSON object
+    pub fn json_object() -> Self {
+        Self {
+            type_: "json_object".to_string(),
+            json_schema: None,
+        }
+    }
+
+    /// Structured outputs: the reply follows `schema`
+    ///
+    /// A strict schema is rewritten to satisfy strict-mode constraints when
+    /// the request is sent; see [`make_strict`](crate::schema::make_strict).
+    pub fn json_schema(name: impl Into<String>, schema: serde_json::Value, strict: bool) -> Self {
+        Self {
+            type_: "json_schema".to_string(),
+            json_schema: Some(serde_json::json!({
+                "name": name.into(),
+                "schema": schema,
+                "strict": strict,
+            })),
+        }
+    }
+}
+
+/// Which tool, if any, the model must call
+///
+/// The modes are sent as plain strings (`"auto"`); a named function is sent
+/// as `{"type": "function", "function": {"name": ...}}`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[serde(into = "ToolChoiceWire", try_from = "ToolChoiceWire")]
+pub enum ToolChoice {
+    /// Never call a tool
+    None,
+    /// Let the model decide (the API default when tools are given)
+    Auto,
+    /// Call at least one tool
+    Required,
+    /// Call the named function
+    Function { name: String },
 }
 
-#[derive(Deserialize, Debug, Clone)] // 添加 Clone
+impl ToolChoice {
+    /// Forces a call to the function `name`
+    pub fn function(name: impl Into<String>) -> Self {
+        ToolChoice::Function { name: name.into() }
+    }
+}
+
+/// Wire representation of [`ToolChoice`]
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+enum ToolChoiceWire {
+    Mode(String),
+    Named {
+        #[serde(rename = "type")]
+        type_: String,
+        function: NamedFunction,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+struct NamedFunction {
+    name: String,
+}
+
+impl From<ToolChoice> for ToolChoiceWire {
+    fn from(choice: ToolChoice) -> Self {
+        match choice {
+            ToolChoice::None => ToolChoiceWire::Mode("none".to_string()),
+            ToolChoice::Auto => ToolChoiceWire::Mode("auto".to_string()),
+            ToolChoice::Required => ToolChoiceWire::Mode("required".to_string()),
+            ToolChoice::Function { name } => ToolChoiceWire::Named {
+                type_: "function".to_string(),
+                function: NamedFunction { name },
+            },
+        }
+    }
+}
+
+impl TryFrom<ToolChoiceWire> for ToolChoice {
+    type Error = String;
+
+    fn try_from(wire: ToolChoiceWire) -> Result<Self, Self::Error> {
+        match wire {
+            ToolChoiceWire::Mode(mode) => match mode.as_str() {
+                "none" => Ok(ToolChoice::None),
+                "auto" => Ok(ToolChoice::Auto),
+                "required" => Ok(ToolChoice::Required),
+                other => Err(format!("unknown tool choice: {}", other)),
+            },
+            ToolChoiceWire::Named { type_, function } if type_ == "function" => {
+                Ok(ToolChoice::Function { name: function.name })
+            }
+            ToolChoiceWire::Named { type_, .. } => Err(format!("unknown tool choice type: {}", type_)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)] // 添加 Clone
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct ChatCompletionResponse {
     pub id: String,
     pub object: String,
@@ -193,9 +550,162 @@ pub struct ChatCompletionResponse {
     pub x_groq: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reasoning: Option<String>,
+    /// Service tier that actually served the request, as reported by the API
+    #[serde(default)]
+    pub service_tier: Option<String>,
+    /// Set when the client served the response from a cache instead of the API
+    #[serde(skip)]
+    pub cache_hit: Option<CacheHit>,
 }
 
-#[derive(Deserialize, Debug, Clone)] // 添加 Clone
+impl ChatCompletionResponse {
+    /// Returns the service tier that actually served the request
+    ///
+    /// This can differ from the requested tier, e.g. when a `flex` request falls
+    /// back to another tier. Returns `None` if the API did not report a tier or
+    /// reported one this SDK does not know.
+    pub fn effective_service_tier(&self) -> Option<ServiceTier> {
+        self.service_tier.as_deref().and_then(|s| s.parse().ok())
+    }
+
+    /// Returns how long the request waited in the queue before processing
+    pub fn queue_time(&self) -> Option<std::time::Duration> {
+        self.usage.queue_time.and_then(|seconds| std::time::Duration::try_from_secs_f64(seconds).ok())
+    }
+
+    /// Returns true if the content filter stopped any of the replies
+    pub fn is_content_filtered(&self) -> bool {
+        self.choices.iter().any(Choice::is_content_filtered)
+    }
+
+    /// Returns true if the reply was cut off before the model finished
+    ///
+    /// A reply counts as truncated when its `finish_reason` is `length`. Use
+    /// `was_truncated_json` for replies to requests with a JSON `response_format`.
+    pub fn was_truncated(&self) -> bool {
+        self.choices
+            .first()
+            .is_some_and(|choice| choice.finish_reason.as_deref() == Some("length"))
+    }
+
+    /// Returns true if the JSON reply was cut off before the model finished
+    ///
+    /// Like `was_truncated`, but also counts a reply that starts like a JSON
+    /// document whose objects, arrays or strings are never closed. Only use it
+    /// for requests sent with a JSON `response_format`: a plain text reply
+    /// that merely starts with `{` or `[` would count as truncated.
+    pub fn was_truncated_json(&self) -> bool {
+        if self.was_truncated() {
+            return true;
+        }
+        match self.choices.first().map(|choice| &choice.message.content) {
+            Some(MessageContent::Text(text)) => is_unterminated_json(text),
+            _ => false,
+        }
+    }
+
+    /// Converts the response into the JSON shape OpenAI clients expect
+    ///
+    /// Intended for proxies exposing Groq behind an OpenAI-compatible API.
+    /// Groq-specific fields (`x_groq`, `reasoning`, timing fields in `usage`)
+    /// are dropped, and fields OpenAI clients rely on (`logprobs`,
+    /// `finish_reason`, `system_fingerprint`) are always present.
+    pub fn to_openai_json(&self) -> serde_json::Value {
+        let choices: Vec<serde_json::Value> = self
+            .choices
+            .iter()
+            .map(|choice| {
+                serde_json::json!({
+                    "index": choice.index,
+                    "message": openai_message(
+                        Some(&choice.message.role),
+                        Some(&choice.message.content),
+                        choice.message.tool_calls.as_ref().map(|calls| serde_json::json!(calls)),
+                        false,
+                    ),
+                    "finish_reason": choice.finish_reason,
+                    "logprobs": choice.logprobs,
+                })
+            })
+            .collect();
+        let mut json = serde_json::json!({
+            "id": self.id,
+            "object": "chat.completion",
+            "created": self.created,
+            "model": self.model,
+            "system_fingerprint": self.system_fingerprint,
+            "choices": choices,
+            "usage": {
+                "prompt_tokens": self.usage.prompt_tokens,
+                "completion_tokens": self.usage.completion_tokens,
+                "total_tokens": self.usage.total_tokens,
+            },
+        });
+        if let Some(tier) = &self.service_tier {
+            json["service_tier"] = serde_json::Value::String(tier.clone());
+        }
+        json
+    }
+}
+
+/// Returns true if `text` opens a JSON object or array that it never closes
+fn is_unterminated_json(text: &str) -> bool {
+    let text = text.trim();
+    if !text.starts_with(['{', '[']) {
+        return false;
+    }
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in text.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    in_string || depth > 0
+}
+
+/// Builds an OpenAI message or delta object, omitting absent fields
+fn openai_message(
+    role: Option<&Role>,
+    content: Option<&MessageContent>,
+    tool_calls: Option<serde_json::Value>,
+    is_delta: bool,
+) -> serde_json::Value {
+    let mut message = serde_json::Map::new();
+    if let Some(role) = role {
+        message.insert("role".to_string(), serde_json::json!(role));
+    }
+    match content {
+        Some(content) => {
+            message.insert("content".to_string(), serde_json::json!(content));
+        }
+        None if !is_delta => {
+            message.insert("content".to_string(), serde_json::Value::Null);
+        }
+        None => {}
+    }
+    if let Some(calls) = tool_calls {
+        message.insert("tool_calls".to_string(), calls);
+    }
+    serde_json::Value::Object(message)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)] // 添加 Clone
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct Choice {
     pub index: u32,
     pub message: ChatMessage,
@@ -203,16 +713,179 @@ pub struct Choice {
     pub finish_reason: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reasoning: Option<String>,
+    /// Token log probabilities, present when the request set `logprobs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<LogProbs>,
+}
+
+impl Choice {
+    /// Returns true if the content filter stopped this reply
+    pub fn is_content_filtered(&self) -> bool {
+        self.finish_reason.as_deref() == Some(crate::content_filter::CONTENT_FILTER)
+    }
+}
+
+/// Log probabilities of the generated tokens
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct LogProbs {
+    /// One entry per generated content token
+    #[serde(default)]
+    pub content: Option<Vec<TokenLogProb>>,
+}
+
+impl LogProbs {
+    /// Returns the content tokens, or an empty slice if none were reported
+    pub fn tokens(&self) -> &[TokenLogProb] {
+        self.content.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the mean log probability per token
+    pub fn average_logprob(&self) -> Option<f64> {
+        let tokens = self.tokens();
+        if tokens.is_empty() {
+            return None;
+        }
+        Some(tokens.iter().map(|t| t.logprob).sum::<f64>() / tokens.len() as f64)
+    }
+
+    /// Returns the perplexity of the generated text, `exp(-average_logprob)`
+    ///
+    /// 1.0 means the model was certain of every token; higher is less certain.
+    pub fn perplexity(&self) -> Option<f64> {
+        self.average_logprob().map(|avg| (-avg).exp())
+    }
+
+    /// Returns the joint probability of the tokens overlapping the byte range
+    /// `start..end` of the generated text
+    pub fn span_probability(&self, start: usize, end: usize) -> Option<f64> {
+        let mut offset = 0;
+        let mut logprob = 0.0;
+        let mut covered = false;
+        for token in self.tokens() {
+            let token_end = offset + token.token.len();
+            if token_end > start && offset < end {
+                logprob += token.logprob;
+                covered = true;
+            }
+            offset = token_end;
+        }
+        covered.then(|| logprob.exp())
+    }
+
+    /// Returns how likely the answer was to be each of `options`, judged from
+    /// the alternatives at the first token
+    ///
+    /// Meant for short answers such as yes/no or multiple-choice letters.
+    /// Alternatives match an option when, trimmed and ignoring case, the option
+    /// starts with them. Probabilities are normalized over the options, so
+    /// request enough `top_logprobs` for every option to appear.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use groqai::LogProbs;
+    ///
+    /// let logprobs: LogProbs = serde_json::from_value(serde_json::json!({
+    ///     "content": [{
+    ///         "token": "Yes",
+    ///         "logprob": -0.105,
+    ///         "top_logprobs": [
+    ///             {"token": "Yes", "logprob": -0.105},
+    ///             {"token": "No", "logprob": -2.303}
+    ///         ]
+    ///     }]
+    /// }))?;
+    /// let confidence = logprobs.option_confidence(&["yes", "no"]).unwrap();
+    /// assert!(confidence[0].1 > 0.85);
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn option_confidence<S: AsRef<str>>(&self, options: &[S]) -> Option<Vec<(String, f64)>> {
+        let first = self.tokens().first()?;
+        let alternatives: Vec<(&str, f64)> = if first.top_logprobs.is_empty() {
+            vec![(first.token.as_str(), first.logprob)]
+        } else {
+            first.top_logprobs.iter().map(|t| (t.token.as_str(), t.logprob)).collect()
+        };
+        let scores: Vec<(String, f64)> = options
+            .iter()
+            .map(|option| {
+                let option = option.as_ref();
+                let probability = alternatives
+                    .iter()
+                    .filter(|(token, _)| {
+                        let token = token.trim().to_lowercase();
+                        !token.is_empty() && option.trim().to_lowercase().starts_with(&token)
+                    })
+                    .map(|(_, logprob)| logprob.exp())
+                    .sum::<f64>();
+                (option.to_string(), probability)
+            })
+            .collect();
+        let total: f64 = scores.iter().map(|(_, p)| p).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        Some(scores.into_iter().map(|(o, p)| (o, p / total)).collect())
+    }
 }
 
-#[derive(Deserialize, Debug, Clone)] // 添加 Clone
+/// Log probability of one generated token
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct TokenLogProb {
+    /// The token text
+    pub token: String,
+    /// Natural log of the token's probability
+    pub logprob: f64,
+    /// UTF-8 bytes of the token, if reported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<Vec<u8>>,
+    /// Most likely alternatives at this position, if `top_logprobs` was requested
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogProb>,
+}
+
+/// A candidate token at one position
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct TopLogProb {
+    /// The token text
+    pub token: String,
+    /// Natural log of the token's probability
+    pub logprob: f64,
+    /// UTF-8 bytes of the token, if reported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<Vec<u8>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)] // 添加 Clone
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct Usage {
     pub prompt_tokens: u32,
     pub completion_tokens: u32,
     pub total_tokens: u32,
+    /// Seconds the request spent queued before processing started
+    #[serde(default)]
+    pub queue_time: Option<f64>,
+    /// Seconds spent processing the prompt
+    #[serde(default)]
+    pub prompt_time: Option<f64>,
+    /// Seconds spent generating the completion
+    #[serde(default)]
+    pub completion_time: Option<f64>,
+    /// Total processing time in seconds
+    #[serde(default)]
+    pub total_time: Option<f64>,
 }
 
-#[derive(Deserialize, Debug, Clone)] // 添加 Clone
+#[derive(Serialize, Deserialize, Debug, Clone)] // 添加 Clone
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct ChatCompletionChunk {
     pub id: String,
     pub object: String,
@@ -220,25 +893,122 @@ pub struct ChatCompletionChunk {
     pub model: String,
     pub choices: Vec<ChoiceChunk>,
     pub system_fingerprint: Option<String>,
+    /// Groq-specific fields, such as the request ID on the first chunk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x_groq: Option<serde_json::Value>,
+    /// Token usage, sent on the last chunk when `stream_options.include_usage` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
 }
 
-#[derive(Deserialize, Debug, Clone)] // 添加 Clone
+impl ChatCompletionChunk {
+    /// Converts the chunk into the JSON shape OpenAI streaming clients expect
+    ///
+    /// See [`ChatCompletionResponse::to_openai_json`]. Tool call deltas keep
+    /// the `index` sent by the API, which OpenAI clients use to assemble calls.
+    pub fn to_openai_json(&self) -> serde_json::Value {
+        let choices: Vec<serde_json::Value> = self
+            .choices
+            .iter()
+            .map(|choice| {
+                serde_json::json!({
+                    "index": choice.index,
+                    "delta": openai_message(
+                        choice.delta.role.as_ref(),
+                        choice.delta.content.as_ref(),
+                        choice.delta.tool_calls.as_ref().map(|calls| serde_json::json!(calls)),
+                        true,
+                    ),
+                    "finish_reason": choice.finish_reason,
+                    "logprobs": null,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "id": self.id,
+            "object": "chat.completion.chunk",
+            "created": self.created,
+            "model": self.model,
+            "system_fingerprint": self.system_fingerprint,
+            "choices": choices,
+        })
+    }
+
+    /// Formats the chunk as an OpenAI-compatible server-sent event (`data: ...\n\n`)
+    pub fn to_openai_sse(&self) -> String {
+        format!("data: {}\n\n", self.to_openai_json())
+    }
+
+    /// Returns the token usage of the stream, if this chunk reports it
+    ///
+    /// Groq sends it in `x_groq.usage` on the last chunk; OpenAI-compatible
+    /// servers send it in `usage` when `stream_options.include_usage` is set.
+    pub fn reported_usage(&self) -> Option<Usage> {
+        self.usage.clone().or_else(|| {
+            let usage = self.x_groq.as_ref()?.get("usage")?;
+            serde_json::from_value(usage.clone()).ok()
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)] // 添加 Clone
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct ChoiceChunk {
     pub index: i32,
     pub delta: MessageDelta,
     pub finish_reason: Option<String>,
+    /// Token log probabilities of this delta, present when the request set `logprobs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<LogProbs>,
 }
 
-#[derive(Deserialize, Debug, Clone)] // 添加 Clone
+#[derive(Serialize, Deserialize, Debug, Clone)] // 添加 Clone
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct MessageDelta {
     pub role: Option<Role>,
     pub content: Option<MessageContent>,
-    pub tool_calls: Option<Vec<ToolCall>>,
+    pub tool_calls: Option<Vec<ToolCallDelta>>,
+    /// Reasoning text, sent by reasoning models with `reasoning_format: "parsed"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+}
+
+/// Part of a tool call sent in a stream
+///
+/// The first delta of a call carries its `id`, `type` and function name;
+/// later deltas with the same `index` append to its arguments.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct ToolCallDelta {
+    /// Position of the call among the tool calls of the message
+    pub index: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<FunctionCallDelta>,
+}
+
+/// Part of a function call sent in a stream
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct FunctionCallDelta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<String>,
 }
 
 // 现有内容...
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct Model {
     pub id: String,
     pub object: String,
@@ -247,45 +1017,211 @@ pub struct Model {
     pub active: bool,
     pub context_window: u32,
     pub public_apps: Option<serde_json::Value>,
+    /// Largest `max_completion_tokens` the model accepts
+    #[serde(default)]
+    pub max_completion_tokens: Option<u32>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct ModelList {
     pub object: String,
     pub data: Vec<Model>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct Transcription {
     pub text: String,
+    /// `transcribe`, returned when `response_format` is `verbose_json`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    /// Detected or requested language, returned when `response_format` is `verbose_json`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Audio duration in seconds, returned when `response_format` is `verbose_json`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub x_groq: Option<serde_json::Value>,
+    /// Timed segments, returned when `response_format` is `verbose_json`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<TranscriptionSegment>,
+    /// Timed words, returned with `verbose_json` when `timestamp_granularities` includes `word`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<TranscriptionWord>,
+}
+
+impl Transcription {
+    /// Returns the words that start within `segment`
+    ///
+    /// Empty unless word timestamps were requested.
+    pub fn segment_words<'a>(&'a self, segment: &'a TranscriptionSegment) -> impl Iterator<Item = &'a TranscriptionWord> {
+        self.words
+            .iter()
+            .filter(move |word| word.start >= segment.start && word.start < segment.end)
+    }
+}
+
+/// A timed word of a transcription
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct TranscriptionWord {
+    pub word: String,
+    /// Start time in seconds
+    pub start: f64,
+    /// End time in seconds
+    pub end: f64,
+}
+
+/// A timed span of a transcription
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct TranscriptionSegment {
+    #[serde(default)]
+    pub id: u32,
+    /// Start time in seconds
+    pub start: f64,
+    /// End time in seconds
+    pub end: f64,
+    pub text: String,
+    /// Offset of the decoding window the segment was found in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seek: Option<u64>,
+    /// Token IDs of the segment text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<Vec<u32>>,
+    /// Sampling temperature used for the segment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    /// Average log probability of the tokens; very low values suggest a poor transcription
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_logprob: Option<f64>,
+    /// Compression ratio of the text; high values suggest repetitive output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression_ratio: Option<f64>,
+    /// Probability that the segment contains no speech
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_speech_prob: Option<f64>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct Translation {
     pub text: String,
+    /// `translate`, returned when `response_format` is `verbose_json`
+    #[serde(default)]
+    pub task: Option<String>,
+    /// Language of the translation, returned when `response_format` is `verbose_json`
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Audio duration in seconds, returned when `response_format` is `verbose_json`
+    #[serde(default)]
+    pub duration: Option<f64>,
+    /// Timed segments of the translated text, returned when `response_format` is `verbose_json`
+    #[serde(default)]
+    pub segments: Vec<TranscriptionSegment>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub x_groq: Option<serde_json::Value>,
 }
 
+/// What an uploaded file is used for
+/// 
+/// Serializes to the value expected by the API (e.g. `"batch"`, `"fine-tune"`).
+/// 
+/// # Examples
+/// 
+/// ```rust
+/// use groqai::FilePurpose;
+/// 
+/// assert_eq!(FilePurpose::from("fine-tune"), FilePurpose::FineTune);
+/// assert_eq!(FilePurpose::Batch.as_str(), "batch");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[serde(into = "String", from = "String")]
+pub enum FilePurpose {
+    /// `batch`: input of a batch job
+    Batch,
+    /// `fine-tune`: training data of a fine-tuning job
+    FineTune,
+    /// Any other purpose, sent as-is (e.g. `"batch_output"`)
+    Other(String),
+}
+
+impl FilePurpose {
+    /// Returns the value sent to the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            FilePurpose::Batch => "batch",
+            FilePurpose::FineTune => "fine-tune",
+            FilePurpose::Other(purpose) => purpose,
+        }
+    }
+}
+
+impl fmt::Display for FilePurpose {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for FilePurpose {
+    fn from(purpose: &str) -> Self {
+        match purpose {
+            "batch" => FilePurpose::Batch,
+            "fine-tune" => FilePurpose::FineTune,
+            _ => FilePurpose::Other(purpose.to_string()),
+        }
+    }
+}
+
+impl From<String> for FilePurpose {
+    fn from(purpose: String) -> Self {
+        FilePurpose::from(purpose.as_str())
+    }
+}
+
+impl From<FilePurpose> for String {
+    fn from(purpose: FilePurpose) -> Self {
+        match purpose {
+            FilePurpose::Other(purpose) => purpose,
+            known => known.as_str().to_string(),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct WorkFile {
     pub id: String,
     pub object: String,
     pub bytes: u64,
     pub created_at: u64,
     pub filename: String,
-    pub purpose: String,
+    pub purpose: FilePurpose,
 }
 
 #[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct WorkFileList {
     pub object: String,
     pub data: Vec<WorkFile>,
+    /// Whether more files can be fetched by listing after the last one
+    #[serde(default)]
+    pub has_more: bool,
 }
 
 #[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct WorkFileDeletion {
     pub id: String,
     pub object: String,
@@ -293,11 +1229,14 @@ pub struct WorkFileDeletion {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct Batch {
     pub id: String,
     pub object: String,
     pub endpoint: String,
-    pub errors: Option<serde_json::Value>,
+    /// Problems found while validating the input file
+    pub errors: Option<BatchErrors>,
     pub input_file_id: String,
     pub completion_window: String,
     pub status: String,
@@ -316,7 +1255,99 @@ pub struct Batch {
     pub metadata: Option<serde_json::Value>,
 }
 
+impl Batch {
+    /// Returns true if the batch finished processing its requests
+    pub fn is_completed(&self) -> bool {
+        self.status == "completed"
+    }
+
+    /// Returns true if the batch failed validation, expired or was cancelled
+    pub fn is_failed(&self) -> bool {
+        matches!(self.status.as_str(), "failed" | "expired" | "cancelled")
+    }
+
+    /// Returns true if the batch's status will not change anymore
+    pub fn is_terminal(&self) -> bool {
+        self.is_completed() || self.is_failed()
+    }
+
+    /// Returns a one-line summary of the validation errors, or `None` if there are none
+    ///
+    /// See [`BatchErrors::summary`].
+    pub fn error_summary(&self) -> Option<String> {
+        self.errors.as_ref().filter(|errors| !errors.data.is_empty()).map(BatchErrors::summary)
+    }
+}
+
+/// Validation errors of a batch's input file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct BatchErrors {
+    #[serde(default)]
+    pub object: String,
+    #[serde(default)]
+    pub data: Vec<BatchErrorDetail>,
+}
+
+impl BatchErrors {
+    /// Summarizes the errors by code, with the input lines each occurred on
+    ///
+    /// Each code is listed once, in order of first occurrence, with its first
+    /// message, e.g. `2 errors: invalid_json on lines 3, 7 (Invalid JSON)`.
+    pub fn summary(&self) -> String {
+        let mut groups: Vec<(&str, &str, Vec<u64>)> = Vec::new();
+        for error in &self.data {
+            let code = if error.code.is_empty() { "error" } else { error.code.as_str() };
+            match groups.iter_mut().find(|(c, _, _)| *c == code) {
+                Some((_, _, lines)) => lines.extend(error.line),
+                None => groups.push((code, error.message.as_str(), error.line.into_iter().collect())),
+            }
+        }
+        let groups: Vec<String> = groups
+            .into_iter()
+            .map(|(code, message, lines)| {
+                let mut group = code.to_string();
+                match lines.as_slice() {
+                    [] => {}
+                    [line] => group.push_str(&format!(" on line {}", line)),
+                    lines => {
+                        let lines: Vec<String> = lines.iter().map(u64::to_string).collect();
+                        group.push_str(&format!(" on lines {}", lines.join(", ")));
+                    }
+                }
+                if !message.is_empty() {
+                    group.push_str(&format!(" ({})", message));
+                }
+                group
+            })
+            .collect();
+        let noun = if self.data.len() == 1 { "error" } else { "errors" };
+        format!("{} {}: {}", self.data.len(), noun, groups.join("; "))
+    }
+}
+
+/// An error in a batch's input file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct BatchErrorDetail {
+    /// Machine-readable error code, e.g. `invalid_json`
+    #[serde(default)]
+    pub code: String,
+    #[serde(default)]
+    pub message: String,
+    /// Name of the parameter that caused the error
+    #[serde(default)]
+    pub param: Option<String>,
+    /// Line of the input file the error was found on, counting from 1
+    #[serde(default)]
+    pub line: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct RequestCounts {
     pub total: u32,
     pub completed: u32,
@@ -324,6 +1355,8 @@ pub struct RequestCounts {
 }
 
 #[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct BatchList {
     pub object: String,
     pub data: Vec<Batch>,
@@ -332,7 +1365,8 @@ pub struct BatchList {
     pub has_more: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum ServiceTier {
     Auto,
@@ -341,7 +1375,40 @@ pub enum ServiceTier {
     Performance,
 }
 
+impl ServiceTier {
+    /// Returns the wire name of the tier
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServiceTier::Auto => "auto",
+            ServiceTier::OnDemand => "on_demand",
+            ServiceTier::Flex => "flex",
+            ServiceTier::Performance => "performance",
+        }
+    }
+}
+
+impl fmt::Display for ServiceTier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ServiceTier {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ServiceTier::Auto),
+            "on_demand" => Ok(ServiceTier::OnDemand),
+            "flex" => Ok(ServiceTier::Flex),
+            "performance" => Ok(ServiceTier::Performance),
+            other => Err(format!("unknown service tier: {}", other)),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum StopSequence {
     Single(String),
@@ -349,6 +1416,7 @@ pub enum StopSequence {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub struct StreamOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -356,6 +1424,7 @@ pub struct StreamOptions {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub struct CompoundCustom {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -365,6 +1434,7 @@ pub struct CompoundCustom {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schema-export", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub struct SearchSettings {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -0,0 +1,185 @@
+//! Token usage tracking
+//!
+//! 令牌用量统计模块
+//!
+//! Attach a [`UsageTracker`] with
+//! [`GroqClientBuilder::usage_tracker`](crate::GroqClientBuilder::usage_tracker)
+//! to record the usage of every successful chat completion, aggregated per
+//! model and per prompt variant. Streams are recorded when their last chunk
+//! reports usage, and a response shared by deduplicated requests is recorded
+//! once.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::types::{ChatCompletionResponse, Usage};
+
+/// Usage of a single chat completion
+#[derive(Debug, Clone)]
+pub struct UsageRecord {
+    /// Model that served the request
+    pub model: String,
+    /// Tokens in the prompt
+    pub prompt_tokens: u32,
+    /// Tokens in the completion
+    pub completion_tokens: u32,
+    /// Total tokens
+    pub total_tokens: u32,
+    /// Experiment the prompt variant belongs to, if any
+    pub experiment: Option<String>,
+    /// Prompt variant that served the request, if any
+    pub prompt_variant: Option<String>,
+    /// When the usage was recorded
+    pub recorded_at: SystemTime,
+}
+
+impl UsageRecord {
+    /// Creates a record from a response
+    pub fn from_response(response: &ChatCompletionResponse) -> Self {
+        Self::from_usage(&response.model, &response.usage)
+    }
+
+    /// Creates a record from the usage reported for `model`, such as by a stream
+    pub fn from_usage(model: impl Into<String>, usage: &Usage) -> Self {
+        Self {
+            model: model.into(),
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            total_tokens: usage.total_tokens,
+            experiment: None,
+            prompt_variant: None,
+            recorded_at: SystemTime::now(),
+        }
+    }
+
+    /// Attributes the record to `variant` of `experiment`
+    pub fn with_prompt_variant(mut self, experiment: impl Into<String>, variant: impl Into<String>) -> Self {
+        self.experiment = Some(experiment.into());
+        self.prompt_variant = Some(variant.into());
+        self
+    }
+}
+
+/// Aggregated usage over many requests
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageTotals {
+    /// Number of requests
+    pub requests: u64,
+    /// Sum of prompt tokens
+    pub prompt_tokens: u64,
+    /// Sum of completion tokens
+    pub completion_tokens: u64,
+    /// Sum of total tokens
+    pub total_tokens: u64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, record: &UsageRecord) {
+        self.requests += 1;
+        self.prompt_tokens += u64::from(record.prompt_tokens);
+        self.completion_tokens += u64::from(record.completion_tokens);
+        self.total_tokens += u64::from(record.total_tokens);
+    }
+}
+
+#[derive(Default)]
+struct UsageState {
+    recent: VecDeque<UsageRecord>,
+    totals: UsageTotals,
+    by_model: HashMap<String, UsageTotals>,
+    by_variant: HashMap<(String, String), UsageTotals>,
+}
+
+/// Records token usage of chat completions
+///
+/// Clones share the same data. Totals cover every recorded request; only the
+/// most recent records are kept individually (1000 by default).
+#[derive(Clone)]
+pub struct UsageTracker {
+    capacity: usize,
+    state: Arc<Mutex<UsageState>>,
+}
+
+impl Default for UsageTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UsageTracker {
+    /// Creates a tracker keeping the 1000 most recent records
+    pub fn new() -> Self {
+        Self::with_capacity(1000)
+    }
+
+    /// Creates a tracker keeping the `capacity` most recent records
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Arc::new(Mutex::new(UsageState::default())),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, UsageState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Records one request's usage
+    pub fn record(&self, record: UsageRecord) {
+        let mut state = self.state();
+        state.totals.add(&record);
+        state.by_model.entry(record.model.clone()).or_default().add(&record);
+        if let (Some(experiment), Some(variant)) = (&record.experiment, &record.prompt_variant) {
+            state
+                .by_variant
+                .entry((experiment.clone(), variant.clone()))
+                .or_default()
+                .add(&record);
+        }
+        if self.capacity > 0 {
+            if state.recent.len() == self.capacity {
+                state.recent.pop_front();
+            }
+            state.recent.push_back(record);
+        }
+    }
+
+    /// Returns the most recent records, oldest first
+    pub fn records(&self) -> Vec<UsageRecord> {
+        self.state().recent.iter().cloned().collect()
+    }
+
+    /// Returns totals over all recorded requests
+    pub fn totals(&self) -> UsageTotals {
+        self.state().totals
+    }
+
+    /// Returns totals per model
+    pub fn totals_by_model(&self) -> HashMap<String, UsageTotals> {
+        self.state().by_model.clone()
+    }
+
+    /// Returns totals per experiment and prompt variant
+    ///
+    /// Keys are `(experiment, variant)`, so variants with the same name in
+    /// different experiments are counted separately.
+    pub fn totals_by_variant(&self) -> HashMap<(String, String), UsageTotals> {
+        self.state().by_variant.clone()
+    }
+
+    /// Returns totals per prompt variant of `experiment`
+    pub fn totals_for_experiment(&self, experiment: &str) -> HashMap<String, UsageTotals> {
+        self.state()
+            .by_variant
+            .iter()
+            .filter(|((name, _), _)| name == experiment)
+            .map(|((_, variant), totals)| (variant.clone(), *totals))
+            .collect()
+    }
+
+    /// Discards all records and totals
+    pub fn reset(&self) {
+        *self.state() = UsageState::default();
+    }
+}
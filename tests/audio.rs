@@ -1,3 +1,5 @@
+#![cfg(feature = "audio")]
+
 use groqai::client::GroqClientBuilder;
 use groqai::error::GroqError;
 use groqai::api::audio::{AudioTranscriptionRequest, AudioTranslationRequest};
@@ -39,10 +41,12 @@ async fn test_audio_transcription_success() -> Result<(), GroqError> {
 #[tokio::test]
 async fn test_audio_transcription_rate_limit() -> Result<(), GroqError> {
     let mock = MockServer::start().await;
-    let client = GroqClientBuilder::new("gsk_test_key".to_string())
+    let mut client = GroqClientBuilder::new("gsk_test_key".to_string())
         .unwrap()
         .base_url(mock.uri().parse().unwrap())
         .build()?;
+    // Retry once after the 1 s `retry-after`, then give up
+    client.rate_limiter.backoff.max_elapsed_time = Some(std::time::Duration::from_millis(1500));
 
     Mock::given(method("POST"))
         .and(path("/audio/transcriptions"))
@@ -64,7 +68,8 @@ async fn test_audio_transcription_rate_limit() -> Result<(), GroqError> {
     };
 
     let result = client.audio().transcribe(req).await;
-    assert!(result.is_err());
+    assert!(matches!(result, Err(GroqError::RateLimited)));
+    assert_eq!(mock.received_requests().await.unwrap().len(), 2);
     Ok(())
 }
 
@@ -117,4 +122,432 @@ async fn test_audio_transcription_invalid_file() -> Result<(), GroqError> {
     let result = client.audio().transcribe(req).await;
     assert!(result.is_err());
     Ok(())
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn test_audio_transcription_file_part() -> Result<(), GroqError> {
+    let mock = MockServer::start().await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())
+        .unwrap()
+        .base_url(mock.uri().parse().unwrap())
+        .build()?;
+
+    Mock::given(method("POST"))
+        .and(path("/audio/transcriptions"))
+        .respond_with(ResponseTemplate::new(200)
+            .set_body_json(serde_json::json!({"text": "Hello"})))
+        .mount(&mock)
+        .await;
+
+    let file = std::env::temp_dir().join(format!("groqai_audio_{}.wav", std::process::id()));
+    std::fs::write(&file, b"RIFF0000WAVE").unwrap();
+
+    let req = AudioTranscriptionRequest {
+        file: Some(file.clone()),
+        model: "whisper-large-v3".to_string(),
+        timestamp_granularities: Some(vec!["word".to_string(), "segment".to_string()]),
+        ..Default::default()
+    };
+    client.audio().transcribe(req).await?;
+
+    let requests = mock.received_requests().await.unwrap();
+    let body = String::from_utf8_lossy(&requests[0].body);
+    assert!(body.contains("Content-Type: audio/wav"));
+    assert!(body.contains("RIFF0000WAVE"));
+    assert!(body.contains("name=\"model\"\r\n\r\nwhisper-large-v3"));
+    assert_eq!(body.matches("name=\"timestamp_granularities[]\"").count(), 2);
+
+    std::fs::remove_file(&file).unwrap();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_audio_upload_progress() -> Result<(), GroqError> {
+    let mock = MockServer::start().await;
+    let client = GroqClientBuilder::new("gsk_test_key")?
+        .base_url(mock.uri().parse().unwrap())
+        .build()?;
+    Mock::given(method("POST"))
+        .and(path("/audio/transcriptions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"text": "Hello"})))
+        .mount(&mock)
+        .await;
+
+    let file = std::env::temp_dir().join(format!("groqai_progress_{}.wav", std::process::id()));
+    let audio: Vec<u8> = (0..200_000u32).map(|i| i as u8).collect();
+    std::fs::write(&file, &audio).unwrap();
+
+    let updates = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let seen = updates.clone();
+    let req = AudioTranscriptionRequest {
+        file: Some(file.clone()),
+        model: "whisper-large-v3".to_string(),
+        ..Default::default()
+    };
+    client
+        .audio()
+        .upload_progress(move |progress| seen.lock().unwrap().push(progress))
+        .transcribe(req)
+        .await?;
+    std::fs::remove_file(&file).unwrap();
+
+    let updates = updates.lock().unwrap().clone();
+    assert!(updates.len() >= 4, "expected one update per read, got {}", updates.len());
+    assert!(updates.windows(2).all(|w| w[0].bytes_sent < w[1].bytes_sent));
+    assert!(updates.iter().all(|p| p.total_bytes == audio.len() as u64));
+    assert_eq!(updates.last().unwrap().bytes_sent, audio.len() as u64);
+
+    let requests = mock.received_requests().await.unwrap();
+    let body = &requests[0].body;
+    assert!(body.windows(audio.len()).any(|w| w == audio.as_slice()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_text_response_formats() -> Result<(), GroqError> {
+    use groqai::{AudioResponseFormat, TranscriptionOutput};
+
+    let srt = "1\n00:00:00,000 --> 00:00:01,400\nHello there.\n";
+    let mock = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/audio/transcriptions"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(srt, "text/plain"))
+        .mount(&mock)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/audio/translations"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw("Good morning.\n", "text/plain"))
+        .mount(&mock)
+        .await;
+    let client = GroqClientBuilder::new("gsk_test_key")?
+        .base_url(mock.uri().parse().unwrap())
+        .build()?;
+
+    let request = |format: AudioResponseFormat| AudioTranscriptionRequest {
+        url: Some("https://example.com/talk.mp3".to_string()),
+        model: "whisper-large-v3".to_string(),
+        response_format: Some(format),
+        ..Default::default()
+    };
+    match client.audio().transcribe_output(request(AudioResponseFormat::Srt)).await? {
+        TranscriptionOutput::Srt(subtitles) => assert_eq!(subtitles, srt),
+        other => panic!("expected srt, got {:?}", other),
+    }
+    let transcription = client.audio().transcribe(request(AudioResponseFormat::Text)).await?;
+    assert_eq!(transcription.text, srt);
+
+    let translation = client
+        .audio()
+        .translate(AudioTranslationRequest {
+            url: Some("https://example.com/talk.mp3".to_string()),
+            model: "whisper-large-v3".to_string(),
+            response_format: Some(AudioResponseFormat::Text),
+            ..Default::default()
+        })
+        .await?;
+    assert_eq!(translation.text, "Good morning.\n");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transcribe_and_translate() -> Result<(), GroqError> {
+    use groqai::mock::MockTransport;
+
+    let reply = serde_json::from_value(serde_json::json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "llama-3.3-70b-versatile",
+        "choices": [{"index": 0, "message": {"role": "assistant", "content": "Hallo Welt"}, "finish_reason": "stop"}],
+        "usage": {"prompt_tokens": 12, "completion_tokens": 3, "total_tokens": 15}
+    }))?;
+    let mock = MockTransport::new()
+        .with_json_response(Ok(serde_json::json!({"text": "Hello world"})))
+        .with_chat_response(Ok(reply));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    let result = client
+        .audio()
+        .translation_model("llama-3.1-8b-instant")
+        .transcribe_and_translate("speech.mp3", "German")
+        .await?;
+
+    assert_eq!(result.transcript.text, "Hello world");
+    assert_eq!(result.translation, "Hallo Welt");
+    assert_eq!(result.translation_usage.total_tokens, 15);
+    let requests = mock.requests();
+    assert_eq!(requests[0].path, "audio/transcriptions");
+    assert_eq!(requests[0].body["model"], "whisper-large-v3");
+    assert_eq!(requests[1].body["model"], "llama-3.1-8b-instant");
+    assert!(requests[1].body["messages"][0]["content"].as_str().unwrap().contains("German"));
+    assert_eq!(requests[1].body["messages"][1]["content"], "Hello world");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_speech_stream() -> Result<(), Box<dyn std::error::Error>> {
+    use futures::StreamExt;
+    use groqai::api::audio::{AudioFormat, AudioSpeechRequest, Voice};
+    use wiremock::matchers::body_json;
+
+    let audio: Vec<u8> = (0..=255u8).cycle().take(64 * 1024).collect();
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/audio/speech"))
+        .and(body_json(serde_json::json!({
+            "model": "playai-tts",
+            "input": "Hello there",
+            "voice": "Fritz-PlayAI",
+            "response_format": "wav"
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(audio.clone(), "audio/wav"))
+        .mount(&server)
+        .await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(format!("{}/", server.uri()).parse()?)
+        .build()?;
+    let request = AudioSpeechRequest {
+        model: "playai-tts".to_string(),
+        input: "Hello there".to_string(),
+        voice: Voice::Fritz,
+        response_format: Some(AudioFormat::Wav),
+        ..Default::default()
+    };
+
+    let mut stream = client.audio().speech_stream(request.clone()).await?;
+    let mut received = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        received.extend_from_slice(&chunk?);
+    }
+    assert_eq!(received, audio);
+    assert_eq!(client.audio().speech(request.clone()).await?.as_ref(), audio.as_slice());
+
+    // Invalid requests fail before anything is sent
+    let invalid = [
+        AudioSpeechRequest { input: " ".to_string(), ..request.clone() },
+        AudioSpeechRequest { response_format: Some(AudioFormat::Mulaw), sample_rate: Some(48_000), ..request.clone() },
+        AudioSpeechRequest { sample_rate: Some(12_345), ..request.clone() },
+        AudioSpeechRequest { voice: Voice::Amira, ..request.clone() },
+        AudioSpeechRequest { speed: Some(9.0), ..request.clone() },
+    ];
+    for request in invalid {
+        assert!(matches!(client.audio().speech(request).await, Err(GroqError::InvalidMessage(_))));
+    }
+    let arabic = AudioSpeechRequest { model: "playai-tts-arabic".to_string(), voice: Voice::Amira, ..request.clone() };
+    assert!(arabic.validate().is_ok());
+    let custom = AudioSpeechRequest { voice: Voice::from("Nova-Custom"), ..request };
+    assert_eq!(serde_json::to_value(&custom)?["voice"], "Nova-Custom");
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fluent_audio_requests() -> Result<(), GroqError> {
+    use groqai::api::audio::AudioResponseFormat;
+    use groqai::mock::MockTransport;
+
+    let mock = MockTransport::new()
+        .with_json_response(Ok(serde_json::json!({"text": "Hello"})))
+        .with_json_response(Ok(serde_json::json!({"text": "Good morning"})));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    let builder = client
+        .audio()
+        .transcribe_file("meeting.mp3")
+        .model("whisper-large-v3-turbo")
+        .language("en")
+        .response_format("verbose_json")
+        .timestamp_granularities(["word", "segment"]);
+    assert_eq!(builder.request().file, Some(PathBuf::from("meeting.mp3")));
+    assert_eq!(builder.request().response_format, Some(AudioResponseFormat::VerboseJson));
+    assert_eq!(builder.send().await?.text, "Hello");
+
+    let translation = client
+        .audio()
+        .header("x-trace", "fluent")
+        .translate_url("https://example.com/audio.mp3")
+        .temperature(0.0)
+        .send()
+        .await?;
+    assert_eq!(translation.text, "Good morning");
+
+    let requests = mock.requests();
+    assert_eq!(requests[0].path, "audio/transcriptions");
+    assert_eq!(requests[0].body["model"], "whisper-large-v3-turbo");
+    assert_eq!(requests[0].body["language"], "en");
+    assert_eq!(requests[0].body["timestamp_granularities"], serde_json::json!(["word", "segment"]));
+    assert_eq!(requests[1].path, "audio/translations");
+    assert_eq!(requests[1].body["model"], "whisper-large-v3");
+    assert_eq!(requests[1].body["url"], "https://example.com/audio.mp3");
+    assert!(requests[1].headers.contains(&("x-trace".to_string(), "fluent".to_string())));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_and_url_are_exclusive() -> Result<(), GroqError> {
+    use groqai::mock::MockTransport;
+
+    let mock = MockTransport::new();
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    let both = AudioTranscriptionRequest {
+        file: Some(PathBuf::from("audio.mp3")),
+        url: Some("https://example.com/audio.mp3".to_string()),
+        model: "whisper-large-v3".to_string(),
+        ..Default::default()
+    };
+    let result = client.audio().transcribe(both).await;
+    assert!(matches!(result, Err(GroqError::InvalidMessage(m)) if m.contains("not both")));
+
+    let neither = AudioTranslationRequest {
+        model: "whisper-large-v3".to_string(),
+        ..Default::default()
+    };
+    assert!(neither.validate().is_err());
+    let result = client.audio().translate(neither).await;
+    assert!(matches!(result, Err(GroqError::InvalidMessage(_))));
+    assert!(mock.requests().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_word_timestamps() -> Result<(), GroqError> {
+    use groqai::api::audio::AudioResponseFormat;
+    use groqai::mock::MockTransport;
+    use groqai::TranscriptionWord;
+
+    let mock = MockTransport::new().with_json_response(Ok(serde_json::json!({
+        "task": "transcribe",
+        "language": "english",
+        "duration": 1.2,
+        "text": "Hello world",
+        "segments": [],
+        "words": [
+            {"word": "Hello", "start": 0.0, "end": 0.5},
+            {"word": "world", "start": 0.6, "end": 1.1}
+        ]
+    })));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    let transcription = client.audio().transcribe_file("hello.wav").word_timestamps().send().await?;
+    assert_eq!(
+        transcription.words[1],
+        TranscriptionWord { word: "world".to_string(), start: 0.6, end: 1.1 }
+    );
+    let body = &mock.requests()[0].body;
+    assert_eq!(body["response_format"], "verbose_json");
+    assert_eq!(body["timestamp_granularities"], serde_json::json!(["word", "segment"]));
+
+    // Other formats cannot carry timestamps
+    let result = client
+        .audio()
+        .transcribe_file("hello.wav")
+        .response_format(AudioResponseFormat::Srt)
+        .timestamp_granularities(["word"])
+        .send()
+        .await;
+    assert!(matches!(result, Err(GroqError::InvalidMessage(m)) if m.contains("verbose_json")));
+    assert_eq!(mock.requests().len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_content_type_detection() -> Result<(), Box<dyn std::error::Error>> {
+    let mock = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/audio/transcriptions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"text": "Hello"})))
+        .mount(&mock)
+        .await;
+    let client = GroqClientBuilder::new("gsk_test_key")?
+        .base_url(mock.uri().parse()?)
+        .build()?;
+
+    let dir = std::env::temp_dir().join(format!("groqai_mime_{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let files = [
+        ("recording.rec", b"fLaC\0\0\0\x22audio".to_vec()),
+        ("voice", b"ID3\x04\0\0audio".to_vec()),
+        ("unknown.bin", b"\0\x01\x02audio".to_vec()),
+    ];
+    for (name, bytes) in &files {
+        std::fs::write(dir.join(name), bytes)?;
+        client.audio().transcribe_file(dir.join(name)).send().await?;
+    }
+    client
+        .audio()
+        .transcribe_file(dir.join("voice"))
+        .file_content_type("audio/x-custom")
+        .send()
+        .await?;
+    let invalid = client.audio().file_content_type("not a mime").transcribe_file(dir.join("voice")).send().await;
+    std::fs::remove_dir_all(&dir)?;
+    assert!(matches!(invalid, Err(GroqError::InvalidMessage(_))));
+
+    let requests = mock.received_requests().await.unwrap();
+    let expected = ["audio/flac", "audio/mpeg", "application/octet-stream", "audio/x-custom"];
+    for (request, mime) in requests.iter().zip(expected) {
+        let body = String::from_utf8_lossy(&request.body);
+        assert!(body.contains(&format!("Content-Type: {}\r\n", mime)), "{}: {}", mime, body);
+        // Sniffing rewinds the file, so it is sent whole
+        assert!(body.contains("audio\r\n"));
+    }
+    assert_eq!(requests.len(), 4);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_audio_retries_rate_limits_and_unavailable() -> Result<(), Box<dyn std::error::Error>> {
+    let mock = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/audio/transcriptions"))
+        .respond_with(ResponseTemplate::new(503).insert_header("retry-after", "0"))
+        .up_to_n_times(1)
+        .mount(&mock)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/audio/transcriptions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"text": "Hello"})))
+        .mount(&mock)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/audio/translations"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+        .up_to_n_times(2)
+        .mount(&mock)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/audio/translations"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"text": "Hello"})))
+        .mount(&mock)
+        .await;
+    let client = GroqClientBuilder::new("gsk_test_key")?
+        .base_url(mock.uri().parse()?)
+        .build()?;
+
+    let transcription = client.audio().transcribe_url("https://example.com/talk.mp3").send().await?;
+    assert_eq!(transcription.text, "Hello");
+    let translation = client.audio().translate_url("https://example.com/talk.mp3").send().await?;
+    assert_eq!(translation.text, "Hello");
+    assert_eq!(mock.received_requests().await.unwrap().len(), 5);
+
+    // Other errors are returned without retrying
+    mock.reset().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": {"message": "Invalid file", "type": "invalid_request_error"}
+        })))
+        .mount(&mock)
+        .await;
+    let result = client.audio().transcribe_url("https://example.com/talk.mp3").send().await;
+    assert!(matches!(result, Err(GroqError::Api(ref e)) if e.status == 400));
+    assert_eq!(mock.received_requests().await.unwrap().len(), 1);
+    Ok(())
+}
@@ -1,54 +1,400 @@
-use groqai::client::GroqClientBuilder;
+#![cfg(feature = "batches")]
+
+use groqai::client::{GroqClient, GroqClientBuilder};
 use groqai::error::GroqError;
-use groqai::api::batches::BatchCreateRequest;
+use groqai::api::batches::{BatchCreateRequest, BatchEndpoint, CompletionWindow};
+use wiremock::matchers::{body_json, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mock_client(mock: &MockServer) -> GroqClient {
+    GroqClientBuilder::new("gsk_test_key".to_string())
+        .unwrap()
+        .base_url(mock.uri().parse().unwrap())
+        .build()
+        .unwrap()
+}
+
+fn fixture(body: &'static str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "application/json")
+}
 
 #[tokio::test]
 async fn test_batch_create_success() -> Result<(), GroqError> {
-    let client = GroqClientBuilder::new("gsk_test_key".to_string())
-        .unwrap()
-        .build()?;
+    let mock = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/batches"))
+        .and(body_json(serde_json::json!({
+            "input_file_id": "file_01jh6x76wtemjr74t1fh0faj5t",
+            "endpoint": "/v1/chat/completions",
+            "completion_window": "24h"
+        })))
+        .respond_with(fixture(groqai::fixtures::BATCH))
+        .expect(1)
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
 
     let req = BatchCreateRequest {
-        input_file_id: "file_123".to_string(),
-        endpoint: "/chat/completions".to_string(),
-        completion_window: "24h".to_string(),
+        input_file_id: "file_01jh6x76wtemjr74t1fh0faj5t".to_string(),
+        endpoint: BatchEndpoint::ChatCompletions,
+        completion_window: CompletionWindow::Hours24,
         metadata: None,
     };
 
-    let result = client.batches().create(req).await;
-    assert!(result.is_err());
+    let batch = client.batches().create(req).await?;
+    assert_eq!(batch.id, "batch_01jh6xa7reempvjyh6n3yst2zw");
+    assert_eq!(batch.request_counts.completed, 3);
     Ok(())
 }
 
 #[tokio::test]
 async fn test_batch_retrieve_success() -> Result<(), GroqError> {
-    let client = GroqClientBuilder::new("gsk_test_key".to_string())
-        .unwrap()
-        .build()?;
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/batches/batch_01jh6xa7reempvjyh6n3yst2zw"))
+        .respond_with(fixture(groqai::fixtures::BATCH))
+        .expect(1)
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
 
-    let result = client.batches().retrieve("batch_123".to_string()).await;
-    assert!(result.is_err());
+    let batch = client.batches().retrieve("batch_01jh6xa7reempvjyh6n3yst2zw".to_string()).await?;
+    assert_eq!(batch.status, "completed");
+    assert_eq!(batch.output_file_id.as_deref(), Some("file_01jh6xa97be52b7pg88czwrrwb"));
     Ok(())
 }
 
 #[tokio::test]
 async fn test_batch_list_with_params() -> Result<(), GroqError> {
-    let client = GroqClientBuilder::new("gsk_test_key".to_string())
-        .unwrap()
-        .build()?;
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/batches"))
+        .and(query_param("after", "batch_123"))
+        .and(query_param("limit", "10"))
+        .respond_with(fixture(groqai::fixtures::BATCH_LIST))
+        .expect(1)
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
 
-    let result = client.batches().list(Some("batch_123".to_string()), Some(10)).await;
-    assert!(result.is_err());
+    let batches = client.batches().list(Some("batch_123".to_string()), Some(10)).await?;
+    assert_eq!(batches.data.len(), 1);
+    assert_eq!(batches.data[0].status, "in_progress");
+    assert!(!batches.has_more);
     Ok(())
 }
 
 #[tokio::test]
 async fn test_batch_cancel_error() -> Result<(), GroqError> {
-    let client = GroqClientBuilder::new("gsk_test_key".to_string())
-        .unwrap()
-        .build()?;
+    let mock = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/batches/batch_123/cancel"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": {"message": "Batch not found", "type": "invalid_request_error"}
+        })))
+        .expect(1)
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
 
     let result = client.batches().cancel("batch_123".to_string()).await;
-    assert!(result.is_err());
+    assert!(matches!(result, Err(GroqError::Api(e)) if e.status.as_u16() == 404));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_batch_cancel_success() -> Result<(), GroqError> {
+    let mut batch: serde_json::Value = serde_json::from_str(groqai::fixtures::BATCH)?;
+    batch["status"] = serde_json::json!("cancelling");
+    let mock = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/batches/batch_01jh6xa7reempvjyh6n3yst2zw/cancel"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(batch))
+        .expect(1)
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
+
+    let batch = client.batches().cancel("batch_01jh6xa7reempvjyh6n3yst2zw").await?;
+    assert_eq!(batch.status, "cancelling");
     Ok(())
-}
\ No newline at end of file
+}
+#[test]
+fn test_batch_endpoint_serde() {
+    let json = serde_json::to_value(BatchEndpoint::ChatCompletions).unwrap();
+    assert_eq!(json, "/v1/chat/completions");
+
+    let parsed: BatchEndpoint = serde_json::from_value(serde_json::json!("/chat/completions")).unwrap();
+    assert_eq!(parsed, BatchEndpoint::ChatCompletions);
+
+    let other: BatchEndpoint = serde_json::from_value(serde_json::json!("/v1/responses")).unwrap();
+    assert_eq!(other, BatchEndpoint::Other("/v1/responses".to_string()));
+}
+
+#[test]
+fn test_batch_endpoint_validate_input_file() {
+    let path = std::env::temp_dir().join(format!("groqai_batch_endpoint_{}.jsonl", std::process::id()));
+    std::fs::write(
+        &path,
+        concat!(
+            r#"{"custom_id": "1", "method": "POST", "url": "/v1/chat/completions", "body": {}}"#, "\n",
+            r#"{"custom_id": "2", "method": "POST", "url": "/v1/audio/transcriptions", "body": {}}"#, "\n",
+        ),
+    )
+    .unwrap();
+
+    let err = BatchEndpoint::ChatCompletions.validate_input_file(&path).unwrap_err();
+    assert!(err.to_string().contains("Line 2"));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_batch_input_builder() -> Result<(), GroqError> {
+    use groqai::{BatchInputBuilder, ChatCompletionRequest, ChatMessage, Role};
+
+    let request = |text: &str| ChatCompletionRequest {
+        messages: vec![ChatMessage::new_text(Role::User, text)],
+        model: "llama-3.1-8b-instant".to_string(),
+        stream: Some(true),
+        ..Default::default()
+    };
+    let mut input = BatchInputBuilder::from_requests([("a", request("Hello")), ("b", request("Hi"))])?;
+    assert!(matches!(input.push("a", request("Again")), Err(GroqError::InvalidMessage(_))));
+    assert!(input.push("", request("Nameless")).is_err());
+    assert_eq!(input.len(), 2);
+
+    let jsonl = input.to_bytes()?;
+    let lines: Vec<serde_json::Value> = std::str::from_utf8(&jsonl)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines[1]["custom_id"], "b");
+    assert_eq!(lines[1]["method"], "POST");
+    assert_eq!(lines[1]["body"]["messages"][0]["content"], "Hi");
+    assert!(lines[1]["body"].get("stream").is_none());
+
+    let path = input.write_temp_file()?;
+    BatchEndpoint::ChatCompletions.validate_input_file(&path)?;
+    std::fs::remove_file(&path).unwrap();
+
+    #[cfg(feature = "files")]
+    assert_eq!(input.file_request("inputs.jsonl")?.purpose, groqai::FilePurpose::Batch);
+    assert!(BatchInputBuilder::new().to_bytes().is_err());
+    Ok(())
+}
+
+#[cfg(feature = "files")]
+#[tokio::test]
+async fn test_batch_submit_chat_requests() -> Result<(), GroqError> {
+    use groqai::{ChatCompletionRequest, ChatMessage, Role};
+    use wiremock::matchers::body_string_contains;
+
+    let mock = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/files"))
+        .and(body_string_contains("\"custom_id\":\"greeting\""))
+        .respond_with(fixture(groqai::fixtures::FILE))
+        .expect(2)
+        .mount(&mock)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/batches"))
+        .and(body_json(serde_json::json!({
+            "input_file_id": "file_01jh6x76wtemjr74t1fh0faj5t",
+            "endpoint": "/v1/chat/completions",
+            "completion_window": "24h"
+        })))
+        .respond_with(fixture(groqai::fixtures::BATCH))
+        .up_to_n_times(1)
+        .mount(&mock)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/batches"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": {"message": "Invalid completion window", "type": "invalid_request_error"}
+        })))
+        .mount(&mock)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/files/file_01jh6x76wtemjr74t1fh0faj5t"))
+        .respond_with(fixture(groqai::fixtures::FILE_DELETION))
+        .expect(1)
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
+    let requests = || {
+        let request = ChatCompletionRequest {
+            messages: vec![ChatMessage::new_text(Role::User, "Hello!")],
+            model: "llama-3.1-8b-instant".to_string(),
+            ..Default::default()
+        };
+        vec![("greeting", request)]
+    };
+
+    let submission = client.batches().submit_chat_requests(requests(), "24h").await?;
+    assert_eq!(submission.batch.id, "batch_01jh6xa7reempvjyh6n3yst2zw");
+    assert_eq!(submission.input_file_id, "file_01jh6x76wtemjr74t1fh0faj5t");
+
+    // A rejected batch does not leave its input file behind
+    let result = client.batches().submit_chat_requests(requests(), "1h").await;
+    assert!(matches!(result, Err(GroqError::Api(ref e)) if e.status == 400));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_batch_create_rejects_unsupported_endpoint() -> Result<(), GroqError> {
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?.build()?;
+    let req = BatchCreateRequest {
+        input_file_id: "file_123".to_string(),
+        endpoint: BatchEndpoint::Embeddings,
+        completion_window: CompletionWindow::Hours24,
+        metadata: None,
+    };
+    assert!(matches!(client.batches().create(req).await, Err(GroqError::InvalidMessage(_))));
+    Ok(())
+}
+
+#[test]
+fn test_completion_window_serde() {
+    let request = BatchCreateRequest {
+        input_file_id: "file_123".to_string(),
+        endpoint: BatchEndpoint::ChatCompletions,
+        completion_window: CompletionWindow::Days7,
+        metadata: None,
+    };
+    let json = serde_json::to_value(&request).unwrap();
+    assert_eq!(json["completion_window"], "7d");
+    assert_eq!(json["endpoint"], "/v1/chat/completions");
+
+    let window = CompletionWindow::from("48h");
+    assert_eq!(window.as_duration(), Some(std::time::Duration::from_secs(48 * 3600)));
+    assert_eq!(CompletionWindow::from("24ч").as_duration(), None);
+    assert_eq!(CompletionWindow::from("").as_duration(), None);
+    assert_eq!(CompletionWindow::from(format!("{}d", u64::MAX)).as_duration(), None);
+}
+
+#[test]
+fn test_batch_errors_are_typed() {
+    let mut batch: groqai::Batch = serde_json::from_str(groqai::fixtures::BATCH).unwrap();
+    assert!(batch.errors.is_none());
+    assert_eq!(batch.error_summary(), None);
+
+    batch.errors = Some(serde_json::from_value(serde_json::json!({
+        "object": "list",
+        "data": [
+            {"code": "invalid_json", "message": "Line is not valid JSON", "param": null, "line": 3},
+            {"code": "missing_required_parameter", "message": "Missing model", "param": "body.model", "line": 5},
+            {"code": "invalid_json", "message": "Line is not valid JSON", "param": null, "line": 7}
+        ]
+    })).unwrap());
+    let errors = batch.errors.as_ref().unwrap();
+    assert_eq!(errors.data[1].param.as_deref(), Some("body.model"));
+    assert_eq!(errors.data[2].line, Some(7));
+    assert_eq!(
+        batch.error_summary().as_deref(),
+        Some("3 errors: invalid_json on lines 3, 7 (Line is not valid JSON); missing_required_parameter on line 5 (Missing model)")
+    );
+}
+
+fn batch_state(status: &str, completed: u32) -> ResponseTemplate {
+    let mut batch: serde_json::Value = serde_json::from_str(groqai::fixtures::BATCH).unwrap();
+    batch["status"] = serde_json::json!(status);
+    batch["request_counts"]["completed"] = serde_json::json!(completed);
+    ResponseTemplate::new(200).set_body_json(batch)
+}
+
+/// Mounts responses for consecutive retrievals of the fixture batch
+async fn mount_states(mock: &MockServer, states: &[(&str, u32)]) {
+    for (i, (status, completed)) in states.iter().enumerate() {
+        let mut retrieve = Mock::given(method("GET"))
+            .and(path("/batches/batch_01jh6xa7reempvjyh6n3yst2zw"))
+            .respond_with(batch_state(status, *completed));
+        if i + 1 < states.len() {
+            retrieve = retrieve.up_to_n_times(1);
+        }
+        retrieve.mount(mock).await;
+    }
+}
+
+#[tokio::test]
+async fn test_batch_wait() -> Result<(), GroqError> {
+    use futures_util::TryStreamExt;
+    use groqai::api::poll::PollOptions;
+    use std::time::Duration;
+
+    let opts = PollOptions::new().interval(Duration::from_millis(1));
+    let mock = MockServer::start().await;
+    let states = [("validating", 0), ("in_progress", 1), ("in_progress", 1), ("in_progress", 2), ("completed", 3)];
+    mount_states(&mock, &states).await;
+    let client = mock_client(&mock).await;
+
+    // Unchanged states are skipped and the terminal state ends the stream
+    let updates: Vec<_> = client
+        .batches()
+        .wait_stream("batch_01jh6xa7reempvjyh6n3yst2zw", opts)
+        .try_collect()
+        .await?;
+    let seen: Vec<_> = updates
+        .iter()
+        .map(|progress| (progress.status().to_string(), progress.batch.request_counts.completed))
+        .collect();
+    let expected = [("validating", 0), ("in_progress", 1), ("in_progress", 2), ("completed", 3)];
+    assert_eq!(seen, expected.map(|(status, completed)| (status.to_string(), completed)));
+    assert!(updates.last().unwrap().is_terminal());
+    assert_eq!(updates.last().unwrap().fraction_done(), Some(1.0));
+    assert_eq!(mock.received_requests().await.unwrap().len(), 5);
+
+    let batch = client.batches().wait("batch_01jh6xa7reempvjyh6n3yst2zw", opts).await?;
+    assert!(batch.is_completed());
+
+    // Batches that expire fail, and unfinished ones time out
+    let mock = MockServer::start().await;
+    mount_states(&mock, &[("in_progress", 1), ("expired", 1)]).await;
+    let client = mock_client(&mock).await;
+    match client.batches().wait("batch_01jh6xa7reempvjyh6n3yst2zw", opts).await {
+        Err(GroqError::JobFailed { status, .. }) => assert_eq!(status, "expired"),
+        other => panic!("expected JobFailed, got {:?}", other.map(|b| b.status)),
+    }
+
+    let mock = MockServer::start().await;
+    mount_states(&mock, &[("in_progress", 1)]).await;
+    let client = mock_client(&mock).await;
+    let opts = opts.timeout(Duration::from_millis(20));
+    match client.batches().wait("batch_01jh6xa7reempvjyh6n3yst2zw", opts).await {
+        Err(GroqError::JobTimeout { id, .. }) => assert_eq!(id, "batch_01jh6xa7reempvjyh6n3yst2zw"),
+        other => panic!("expected JobTimeout, got {:?}", other.map(|b| b.status)),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_batch_wait_retries_and_stops_at_timeout() -> Result<(), GroqError> {
+    use groqai::api::poll::PollOptions;
+    use std::time::{Duration, Instant};
+
+    let mock = MockServer::start().await;
+    // A temporary outage is retried instead of ending the wait
+    Mock::given(method("GET"))
+        .and(path("/batches/batch_01jh6xa7reempvjyh6n3yst2zw"))
+        .respond_with(ResponseTemplate::new(503).insert_header("retry-after", "0"))
+        .up_to_n_times(1)
+        .mount(&mock)
+        .await;
+    mount_states(&mock, &[("in_progress", 1)]).await;
+    let client = mock_client(&mock).await;
+
+    // The sleep is cut short by the timeout instead of running a full interval
+    let opts = PollOptions::new()
+        .interval(Duration::from_secs(60))
+        .timeout(Duration::from_millis(50));
+    let started = Instant::now();
+    match client.batches().wait("batch_01jh6xa7reempvjyh6n3yst2zw", opts).await {
+        Err(GroqError::JobTimeout { .. }) => {}
+        other => panic!("expected JobTimeout, got {:?}", other.map(|b| b.status)),
+    }
+    assert!(started.elapsed() < Duration::from_secs(10));
+    // The outage, the first check and a last check at the deadline
+    assert_eq!(mock.received_requests().await.unwrap().len(), 3);
+    Ok(())
+}
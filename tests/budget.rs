@@ -0,0 +1,188 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use groqai::mock::MockTransport;
+use groqai::rate_limit::{Budget, BudgetStore, MemoryBudgetStore, ThroughputEstimator};
+use groqai::types::ChatCompletionResponse;
+use groqai::{ChatMessage, GroqClientBuilder, GroqError, Role};
+
+mod common;
+
+fn reply() -> ChatCompletionResponse {
+    let mut response = common::reply("ok");
+    response.usage = common::usage(5, 2);
+    response
+}
+
+/// Records every increment and its result
+#[derive(Clone, Default)]
+struct RecordingStore {
+    inner: Arc<MemoryBudgetStore>,
+    calls: Arc<Mutex<Vec<(String, u64, u64)>>>,
+}
+
+#[async_trait]
+impl BudgetStore for RecordingStore {
+    async fn increment(&self, key: &str, amount: u64, ttl: Duration) -> Result<u64, GroqError> {
+        let value = self.inner.increment(key, amount, ttl).await?;
+        self.calls.lock().unwrap().push((key.to_string(), amount, value));
+        Ok(value)
+    }
+}
+
+#[tokio::test]
+async fn test_budget_defers_requests_to_next_window() -> Result<(), GroqError> {
+    let store = RecordingStore::default();
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(MockTransport::new().with_chat_response(Ok(reply())).with_chat_response(Ok(reply())))
+        .budget(
+            Budget::new(Duration::from_secs(1))
+                .namespace("test")
+                .max_requests(1)
+                .max_tokens(100)
+                .store(store.clone()),
+        )
+        .build()?;
+
+    for _ in 0..2 {
+        client
+            .chat("llama-3.1-8b-instant")
+            .message(ChatMessage::new_text(Role::User, "Hi"))
+            .send()
+            .await?;
+    }
+
+    let calls = store.calls.lock().unwrap().clone();
+    // Each request was admitted in a different window
+    let admitted: Vec<&String> = calls
+        .iter()
+        .filter(|(key, _, value)| key.starts_with("test:requests:1:") && *value == 1)
+        .map(|(key, _, _)| key)
+        .collect();
+    assert_eq!(admitted.len(), 2);
+    assert_ne!(admitted[0], admitted[1]);
+    // Completed tokens were counted
+    assert!(calls.iter().any(|(key, amount, _)| key.starts_with("test:tokens:") && *amount == 7));
+    Ok(())
+}
+
+#[test]
+fn test_throughput_estimator_smooths_per_model() {
+    let estimator = ThroughputEstimator::new().smoothing(0.25);
+    estimator.record("fast", 400, 500, Duration::from_secs(1));
+    estimator.record("fast", 800, 900, Duration::from_secs(1));
+    estimator.record("slow", 50, 60, Duration::from_secs(1));
+
+    let fast = estimator.estimate("fast").unwrap();
+    assert_eq!(fast.tokens_per_second, 500.0);
+    assert_eq!(fast.completion_tokens, 500.0);
+    assert_eq!(fast.total_tokens, 600.0);
+    assert_eq!(fast.samples, 2);
+    assert_eq!(fast.expected_duration(), Some(Duration::from_secs(1)));
+    assert_eq!(estimator.snapshot().len(), 2);
+    assert!(estimator.estimate("unknown").is_none());
+
+    // Responses with the API's completion time are measured by it
+    let mut response = reply();
+    response.usage.completion_time = Some(0.01);
+    estimator.record_response("timed", &response, Duration::from_secs(5));
+    assert_eq!(estimator.estimate("timed").unwrap().tokens_per_second, 200.0);
+
+    // Out-of-range completion times fall back to the measured time
+    response.usage.completion_time = Some(1e300);
+    estimator.record_response("huge", &response, Duration::from_secs(1));
+    assert_eq!(estimator.estimate("huge").unwrap().tokens_per_second, 2.0);
+}
+
+#[tokio::test]
+async fn test_budget_throughput_holds_back_requests_in_flight() -> Result<(), GroqError> {
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::to_value(reply())?)
+                .set_delay(Duration::from_millis(300)),
+        )
+        .mount(&mock)
+        .await;
+    let throughput = ThroughputEstimator::new();
+    // Each request is expected to use 7 tokens, so only one fits in the budget at a time
+    throughput.record("llama-3.1-8b-instant", 2, 7, Duration::from_millis(100));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(mock.uri().parse().unwrap())
+        .budget(Budget::new(Duration::from_secs(3600)).max_tokens(10).throughput(throughput.clone()))
+        .build()?;
+
+    let send = || {
+        client
+            .chat("llama-3.1-8b-instant")
+            .message(ChatMessage::new_text(Role::User, "Hi"))
+            .send()
+    };
+    let started = std::time::Instant::now();
+    let (first, second) = tokio::join!(send(), send());
+    first?;
+    second?;
+    // The second request waited for the first to complete
+    assert!(started.elapsed() >= Duration::from_millis(550), "took {:?}", started.elapsed());
+    assert_eq!(throughput.estimate("llama-3.1-8b-instant").unwrap().samples, 3);
+    Ok(())
+}
+
+#[cfg(feature = "streaming")]
+#[tokio::test]
+async fn test_budget_defers_streams_to_next_window() -> Result<(), GroqError> {
+    use futures::StreamExt;
+    use groqai::types::ChatCompletionChunk;
+
+    let chunk: ChatCompletionChunk = serde_json::from_value(serde_json::json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion.chunk",
+        "created": 0,
+        "model": "llama-3.1-8b-instant",
+        "system_fingerprint": null,
+        "choices": [{"index": 0, "delta": {"content": "ok"}, "finish_reason": "stop"}],
+        "x_groq": {"usage": {"prompt_tokens": 5, "completion_tokens": 2, "total_tokens": 7}}
+    }))?;
+    let store = RecordingStore::default();
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(MockTransport::new().with_stream(vec![chunk.clone()]).with_stream(vec![chunk]))
+        .budget(
+            Budget::new(Duration::from_secs(1))
+                .namespace("test")
+                .max_requests(1)
+                .max_tokens(100)
+                .store(store.clone()),
+        )
+        .build()?;
+
+    for _ in 0..2 {
+        let mut stream = client
+            .chat("llama-3.1-8b-instant")
+            .message(ChatMessage::new_text(Role::User, "Hi"))
+            .stream(true)
+            .send_stream()
+            .await?;
+        while let Some(chunk) = stream.next().await {
+            chunk?;
+        }
+    }
+
+    let calls = store.calls.lock().unwrap().clone();
+    // The second stream waited for the next window
+    let admitted: Vec<&String> = calls
+        .iter()
+        .filter(|(key, _, value)| key.starts_with("test:requests:1:") && *value == 1)
+        .map(|(key, _, _)| key)
+        .collect();
+    assert_eq!(admitted.len(), 2);
+    assert_ne!(admitted[0], admitted[1]);
+    // The usage of each stream was counted when it ended
+    let counted = calls.iter().filter(|(key, amount, _)| key.starts_with("test:tokens:") && *amount == 7);
+    assert_eq!(counted.count(), 2);
+    Ok(())
+}
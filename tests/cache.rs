@@ -0,0 +1,304 @@
+use groqai::cache::prefix::PrefixCache;
+use groqai::cache::{CacheHit, MemoryCache};
+use groqai::mock::MockTransport;
+use groqai::{ChatCompletionRequest, ChatMessage, Conversation, GroqClientBuilder, GroqError, Role};
+
+mod common;
+
+use common::reply;
+
+#[tokio::test]
+async fn test_memory_cache_serves_repeated_requests() -> Result<(), GroqError> {
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply("first")))
+        .with_chat_response(Ok(reply("second")));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .response_cache(MemoryCache::new())
+        .build()?;
+    let ask = || {
+        client
+            .chat("llama-3.1-8b-instant")
+            .message(ChatMessage::new_text(Role::User, "Hi"))
+            .temperature(0.0)
+    };
+
+    let fresh = ask().send().await?;
+    assert_eq!(fresh.cache_hit, None);
+    let cached = ask().send().await?;
+    assert_eq!(cached.choices[0].message.content.to_string(), "first");
+    assert_eq!(cached.cache_hit, Some(CacheHit::Exact));
+    assert_eq!(mock.requests().len(), 1);
+
+    let bypassed = ask().cache(false).send().await?;
+    assert_eq!(bypassed.choices[0].message.content.to_string(), "second");
+    assert_eq!(mock.requests().len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_memory_cache_skips_sampled_requests_by_default() -> Result<(), GroqError> {
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply("first")))
+        .with_chat_response(Ok(reply("second")))
+        .with_chat_response(Ok(reply("third")));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .response_cache(MemoryCache::new())
+        .build()?;
+    let ask = || {
+        client
+            .chat("llama-3.1-8b-instant")
+            .message(ChatMessage::new_text(Role::User, "Hi"))
+            .temperature(0.9)
+    };
+
+    ask().send().await?;
+    assert_eq!(ask().send().await?.choices[0].message.content.to_string(), "second");
+    // An explicit opt-in caches a sampled request
+    ask().cache(true).send().await?;
+    let cached = ask().cache(true).send().await?;
+    assert_eq!(cached.choices[0].message.content.to_string(), "third");
+    assert_eq!(cached.cache_hit, Some(CacheHit::Exact));
+    assert_eq!(mock.requests().len(), 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_memory_cache_is_bounded() -> Result<(), GroqError> {
+    use groqai::cache::ResponseCache;
+    use std::time::Duration;
+
+    let cache = MemoryCache::new().max_entries(2);
+    cache.put("a", &reply("A")).await?;
+    cache.put("b", &reply("B")).await?;
+    cache.put("c", &reply("C")).await?;
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get("a").await?.is_none());
+    assert!(cache.get("c").await?.is_some());
+
+    // Expired entries are swept when another is stored
+    let cache = MemoryCache::with_ttl(Duration::from_millis(20));
+    cache.put("a", &reply("A")).await?;
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    cache.put("b", &reply("B")).await?;
+    assert_eq!(cache.len(), 1);
+    Ok(())
+}
+
+#[cfg(feature = "encrypted-cache")]
+#[tokio::test]
+async fn test_encrypted_file_cache_roundtrip() -> Result<(), GroqError> {
+    use groqai::cache::encrypted::EncryptedFileCache;
+    use groqai::cache::ResponseCache;
+
+    let dir = std::env::temp_dir().join(format!("groqai-cache-test-{}", std::process::id()));
+    let cache = EncryptedFileCache::new(&dir, [7; 32]);
+    cache.put("secret prompt", &reply("cached")).await?;
+
+    let restored = EncryptedFileCache::new(&dir, [7; 32]).get("secret prompt").await?;
+    assert_eq!(restored.unwrap().choices[0].message.content.to_string(), "cached");
+    assert!(cache.get("other prompt").await?.is_none());
+
+    // Entries are opaque on disk and unreadable with another key
+    for entry in std::fs::read_dir(&dir).unwrap() {
+        let data = std::fs::read(entry.unwrap().path()).unwrap();
+        assert!(!String::from_utf8_lossy(&data).contains("secret prompt"));
+    }
+    assert!(EncryptedFileCache::new(&dir, [8; 32]).get("secret prompt").await.is_err());
+
+    std::fs::remove_dir_all(&dir).ok();
+    Ok(())
+}
+
+#[cfg(feature = "encrypted-cache")]
+#[tokio::test]
+async fn test_encrypted_file_cache_sweeps_on_put() -> Result<(), GroqError> {
+    use groqai::cache::encrypted::EncryptedFileCache;
+    use groqai::cache::ResponseCache;
+    use std::sync::Arc;
+
+    let dir = std::env::temp_dir().join(format!("groqai-cache-sweep-test-{}", std::process::id()));
+    let cache = Arc::new(EncryptedFileCache::new(&dir, [7; 32]).max_entries(3));
+    // Concurrent writes of the same key use separate temporary files
+    let writes: Vec<_> = (0..8)
+        .map(|i| {
+            let cache = cache.clone();
+            tokio::spawn(async move { cache.put("same prompt", &reply(&format!("reply {}", i))).await })
+        })
+        .collect();
+    for write in writes {
+        write.await.unwrap()?;
+    }
+    assert!(cache.get("same prompt").await?.is_some());
+
+    for i in 0..5 {
+        cache.put(&format!("prompt {}", i), &reply("cached")).await?;
+    }
+    let files: Vec<_> = std::fs::read_dir(&dir).unwrap().map(|e| e.unwrap().path()).collect();
+    assert_eq!(files.len(), 3, "{:?}", files);
+    assert!(files.iter().all(|path| path.extension().is_some_and(|ext| ext == "bin")));
+
+    std::fs::remove_dir_all(&dir).ok();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_prefix_cache_serves_deterministic_turns() -> Result<(), GroqError> {
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply("first")))
+        .with_chat_response(Ok(reply("second")))
+        .with_chat_response(Ok(reply("sampled")))
+        .with_chat_response(Ok(reply("sampled again")));
+    let cache = PrefixCache::new();
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .prefix_cache(cache.clone())
+        .build()?;
+
+    let mut conversation = Conversation::new("llama-3.1-8b-instant").with_temperature(0.0);
+    let hook_cache = cache.clone();
+    conversation.on_edit(move |stale| {
+        hook_cache.invalidate(stale);
+    });
+    conversation.send(&client, "Hi").await?;
+    let regenerated = conversation.regenerate(&client).await?;
+    assert_eq!(regenerated.choices[0].message.content.to_string(), "first");
+    assert_eq!(mock.requests().len(), 1);
+    assert_eq!(cache.len(), 1);
+
+    // Dropping the turn invalidates the replies built on it
+    conversation.truncate(0);
+    assert!(cache.is_empty());
+    let resent = conversation.send(&client, "Hi").await?;
+    assert_eq!(resent.choices[0].message.content.to_string(), "second");
+    assert_eq!(mock.requests().len(), 2);
+
+    // Sampled requests are neither served nor stored
+    let ask = || client.chat("llama-3.1-8b-instant").message(ChatMessage::new_text(Role::User, "Hi"));
+    assert_eq!(ask().send().await?.choices[0].message.content.to_string(), "sampled");
+    assert_eq!(ask().send().await?.choices[0].message.content.to_string(), "sampled again");
+    assert_eq!(cache.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_prefix_cache_invalidates_by_prefix() -> Result<(), GroqError> {
+    let cache = PrefixCache::new();
+    let system = ChatMessage::new_text(Role::System, "Be brief.");
+    let question = |text: &str| ChatMessage::new_text(Role::User, text);
+    let request = |messages: Vec<ChatMessage>| ChatCompletionRequest {
+        model: "llama-3.1-8b-instant".to_string(),
+        messages,
+        seed: Some(7),
+        ..Default::default()
+    };
+    let first = request(vec![system.clone(), question("a")]);
+    let second = request(vec![system.clone(), question("b")]);
+    cache.put(&first, &reply("A"))?;
+    cache.put(&second, &reply("B"))?;
+
+    // Other parameters are part of the key
+    let mut other_model = first.clone();
+    other_model.model = "llama-3.3-70b-versatile".to_string();
+    assert!(cache.get(&other_model)?.is_none());
+
+    assert_eq!(cache.invalidate(&[system.clone(), question("a")]), 1);
+    assert!(cache.get(&first)?.is_none());
+    assert!(cache.get(&second)?.is_some());
+    assert_eq!(cache.invalidate(&[system]), 1);
+    assert!(cache.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_prefix_cache_drops_oldest_when_full() -> Result<(), GroqError> {
+    let cache = PrefixCache::new().max_entries(2);
+    let request = |text: &str| ChatCompletionRequest {
+        model: "llama-3.1-8b-instant".to_string(),
+        messages: vec![ChatMessage::new_text(Role::User, text)],
+        temperature: Some(0.0),
+        ..Default::default()
+    };
+    cache.put(&request("a"), &reply("A"))?;
+    cache.put(&request("b"), &reply("B"))?;
+    // Replacing an entry does not evict another
+    cache.put(&request("a"), &reply("A2"))?;
+    assert_eq!(cache.len(), 2);
+    cache.put(&request("c"), &reply("C"))?;
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get(&request("b"))?.is_none());
+    assert!(cache.get(&request("a"))?.is_some());
+    assert!(cache.get(&request("c"))?.is_some());
+    Ok(())
+}
+
+/// Embeds text as letter counts, so anagrams are identical
+#[cfg(feature = "semantic-cache")]
+struct LetterEmbedder;
+
+#[cfg(feature = "semantic-cache")]
+#[async_trait::async_trait]
+impl groqai::cache::semantic::Embedder for LetterEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, GroqError> {
+        let mut counts = vec![0.0; 26];
+        for c in text.to_ascii_lowercase().bytes().filter(u8::is_ascii_lowercase) {
+            counts[usize::from(c - b'a')] += 1.0;
+        }
+        Ok(counts)
+    }
+}
+
+#[cfg(feature = "semantic-cache")]
+#[tokio::test]
+async fn test_semantic_cache_serves_similar_prompts() -> Result<(), GroqError> {
+    use groqai::cache::semantic::SemanticCache;
+
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply("Paris")))
+        .with_chat_response(Ok(reply("Berlin")))
+        .with_chat_response(Ok(reply("Madrid")));
+    let cache = SemanticCache::new(LetterEmbedder).threshold(0.99).max_entries(2);
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .semantic_cache(cache.clone())
+        .build()?;
+    let ask = |text: &str| {
+        client
+            .chat("llama-3.1-8b-instant")
+            .message(ChatMessage::new_text(Role::User, text))
+            .temperature(0.0)
+    };
+
+    assert_eq!(ask("capital of France?").send().await?.cache_hit, None);
+
+    let similar = ask("Capital of france").send().await?;
+    assert_eq!(similar.choices[0].message.content.to_string(), "Paris");
+    assert!(matches!(similar.cache_hit, Some(CacheHit::Semantic { similarity }) if similarity > 0.99));
+    assert_eq!(mock.requests().len(), 1);
+
+    // A different prompt, or the same prompt in another context, is a miss
+    assert_eq!(ask("capital of Germany?").send().await?.choices[0].message.content.to_string(), "Berlin");
+    let with_system = client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::System, "Answer in Spanish."))
+        .message(ChatMessage::new_text(Role::User, "capital of France?"))
+        .temperature(0.0)
+        .send()
+        .await?;
+    assert_eq!(with_system.choices[0].message.content.to_string(), "Madrid");
+    assert_eq!(mock.requests().len(), 3);
+
+    // The oldest entry was dropped to make room
+    assert_eq!(cache.len(), 2);
+    assert!(ask("capital of France?").send().await.is_err());
+    // Sampled requests are not served from the cache
+    let sampled = client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "capital of Germany?"))
+        .send()
+        .await;
+    assert!(sampled.is_err());
+    Ok(())
+}
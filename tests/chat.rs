@@ -1,27 +1,40 @@
-use groqai::client::GroqClientBuilder;
+use groqai::client::{GroqClient, GroqClientBuilder};
 use groqai::error::GroqError;
 use groqai::types::{ChatMessage, Role, Tool, FunctionDef, ToolChoice};
+#[cfg(feature = "streaming")]
 use futures_util::stream::StreamExt;
-use std::env;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
 
-fn create_client() -> Result<GroqClientBuilder, GroqError> {
-    let api_key = env::var("GROQ_API_KEY").expect("GROQ_API_KEY must be set");
-    let mut builder = GroqClientBuilder::new(api_key)?;
-    
-    // 添加代理支持
-    if let Ok(proxy_url) = env::var("PROXY_URL") {
-        let proxy = reqwest::Proxy::all(&proxy_url).map_err(|e| {
-            GroqError::InvalidMessage(format!("Invalid proxy URL: {}", e))
-        })?;
-        builder = builder.proxy(proxy);
-    }
-    
-    Ok(builder)
+/// Starts a server answering chat completions with `response`
+async fn chat_server(response: ResponseTemplate) -> (MockServer, GroqClient) {
+    let mock = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .and(header("authorization", "Bearer gsk_test_key"))
+        .respond_with(response)
+        .mount(&mock)
+        .await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())
+        .unwrap()
+        .base_url(mock.uri().parse().unwrap())
+        .build()
+        .unwrap();
+    (mock, client)
+}
+
+fn completion() -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(groqai::fixtures::CHAT_COMPLETION_RESPONSE, "application/json")
+}
+
+async fn sent_body(mock: &MockServer) -> serde_json::Value {
+    let requests = mock.received_requests().await.unwrap();
+    serde_json::from_slice(&requests.last().unwrap().body).unwrap()
 }
 
 #[tokio::test]
 async fn test_chat_non_streaming() -> Result<(), GroqError> {
-    let client = create_client()?.build()?;
+    let (mock, client) = chat_server(completion()).await;
     
     let response = client
         .chat("llama-3.1-70b-versatile")
@@ -32,14 +45,26 @@ async fn test_chat_non_streaming() -> Result<(), GroqError> {
         .send()
         .await?;
     
-    assert!(response.choices.first().is_some());
+    assert!(!response.choices.is_empty());
     assert_eq!(response.object, "chat.completion");
+    let body = sent_body(&mock).await;
+    assert_eq!(body["model"], "llama-3.1-70b-versatile");
+    assert_eq!(body["messages"], serde_json::json!([{"role": "user", "content": "Hello, how are you?"}]));
+    assert_eq!(body["frequency_penalty"], 0.5);
+    assert_eq!(body["presence_penalty"], serde_json::json!(0.3f32));
+    assert!(body.get("stream").is_none());
     Ok(())
 }
 
+#[cfg(feature = "streaming")]
 #[tokio::test]
 async fn test_chat_streaming() -> Result<(), GroqError> {
-    let client = create_client()?.build()?;
+    let mut chunk: serde_json::Value = serde_json::from_str(groqai::fixtures::CHAT_COMPLETION_CHUNK)?;
+    let mut events = format!("data: {}\n\n", chunk);
+    chunk["choices"][0]["delta"] = serde_json::json!({"content": " there"});
+    chunk["choices"][0]["finish_reason"] = serde_json::json!("stop");
+    events.push_str(&format!("data: {}\n\ndata: [DONE]\n\n", chunk));
+    let (mock, client) = chat_server(ResponseTemplate::new(200).set_body_raw(events, "text/event-stream")).await;
     
     let mut stream = client
         .chat("llama-3.1-70b-versatile")
@@ -50,12 +75,36 @@ async fn test_chat_streaming() -> Result<(), GroqError> {
     
     let first_chunk = stream.next().await.unwrap()?;
     assert_eq!(first_chunk.object, "chat.completion.chunk");
+    let second_chunk = stream.next().await.unwrap()?;
+    assert_eq!(second_chunk.choices[0].delta.content.as_ref().unwrap().to_string(), " there");
+    assert!(stream.next().await.is_none());
+    assert_eq!(sent_body(&mock).await["stream"], true);
     Ok(())
 }
 
 #[tokio::test]
 async fn test_chat_with_tools() -> Result<(), GroqError> {
-    let client = create_client()?.build()?;
+    let (mock, client) = chat_server(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "llama-3.1-70b-versatile",
+        "choices": [{
+            "index": 0,
+            "message": {
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": {"name": "get_weather", "arguments": "{\"location\": \"Tokyo\"}"}
+                }]
+            },
+            "finish_reason": "tool_calls"
+        }],
+        "usage": {"prompt_tokens": 20, "completion_tokens": 10, "total_tokens": 30}
+    })))
+    .await;
     
     let tools = vec![Tool {
         type_: "function".to_string(),
@@ -72,10 +121,7 @@ async fn test_chat_with_tools() -> Result<(), GroqError> {
         },
     }];
     
-    let tool_choice = ToolChoice {
-        type_: "function".to_string(),
-        function: Some(serde_json::json!({ "name": "get_weather" })),
-    };
+    let tool_choice = ToolChoice::function("get_weather");
     
     let response = client
         .chat("llama-3.1-70b-versatile")
@@ -83,19 +129,19 @@ async fn test_chat_with_tools() -> Result<(), GroqError> {
         .tools(tools)
         .tool_choice(tool_choice)
         .send()
-        .await
-        .map_err(|e| {
-            eprintln!("Tool test error: {:?}", e);
-            e
-        })?;
+        .await?;
     
-    assert!(response.choices.first().is_some());
+    let calls = response.choices[0].message.tool_calls.as_ref().unwrap();
+    assert_eq!(calls[0].function.name, "get_weather");
+    let body = sent_body(&mock).await;
+    assert_eq!(body["tools"][0]["function"]["name"], "get_weather");
+    assert_eq!(body["tool_choice"], serde_json::json!({"type": "function", "function": {"name": "get_weather"}}));
     Ok(())
 }
 
 #[tokio::test]
 async fn test_chat_with_logprobs() -> Result<(), GroqError> {
-    let client = create_client()?.build()?;
+    let (mock, client) = chat_server(completion()).await;
     
     let response = client
         .chat("llama-3.1-70b-versatile")
@@ -105,6 +151,385 @@ async fn test_chat_with_logprobs() -> Result<(), GroqError> {
         .send()
         .await?;
     
-    assert!(response.choices.first().is_some());
+    assert!(!response.choices.is_empty());
+    let body = sent_body(&mock).await;
+    assert_eq!(body["logprobs"], false);
+    assert_eq!(body["top_logprobs"], 5);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_chat_api_error() -> Result<(), GroqError> {
+    let error = ResponseTemplate::new(400).set_body_json(serde_json::json!({
+        "error": {"message": "The model `llama-9` does not exist", "type": "invalid_request_error", "code": "model_not_found"}
+    }));
+    let (_mock, client) = chat_server(error).await;
+
+    let result = client
+        .chat("llama-9")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .send()
+        .await;
+    match result {
+        Err(GroqError::Api(e)) => {
+            assert_eq!(e.status.as_u16(), 400);
+            assert_eq!(e.error.code.as_deref(), Some("model_not_found"));
+        }
+        other => panic!("expected an API error, got {:?}", other.map(|r| r.id)),
+    }
     Ok(())
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_chat_effective_service_tier() -> Result<(), GroqError> {
+    use groqai::types::ServiceTier;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock = MockServer::start().await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(mock.uri().parse().unwrap())
+        .build()?;
+
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "llama-3.1-8b-instant",
+            "service_tier": "on_demand",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "Hi"},
+                "finish_reason": "stop"
+            }],
+            "usage": {
+                "prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2,
+                "queue_time": 0.25, "total_time": 0.5
+            }
+        })))
+        .mount(&mock)
+        .await;
+
+    let response = client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hi"))
+        .service_tier(ServiceTier::Flex)
+        .send()
+        .await?;
+
+    assert_eq!(response.effective_service_tier(), Some(ServiceTier::OnDemand));
+    assert_eq!(response.queue_time(), Some(std::time::Duration::from_millis(250)));
+
+    // Out-of-range times from the server are ignored instead of panicking
+    let mut response = response;
+    for bad in [-1.0, f64::NAN, 1e300] {
+        response.usage.queue_time = Some(bad);
+        assert_eq!(response.queue_time(), None);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_chat_openai_compat_mode() -> Result<(), GroqError> {
+    use groqai::CompatMode;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock = MockServer::start().await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(mock.uri().parse().unwrap())
+        .compat_mode(CompatMode::OpenAI)
+        .build()?;
+
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .and(body_partial_json(serde_json::json!({"max_tokens": 42})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "gpt-4o-mini",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "Hi"},
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2}
+        })))
+        .expect(1)
+        .mount(&mock)
+        .await;
+
+    client
+        .chat("gpt-4o-mini")
+        .message(ChatMessage::new_text(Role::User, "Hi"))
+        .max_completion_tokens(42)
+        .send()
+        .await?;
+
+    let requests = mock.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert!(body.get("max_completion_tokens").is_none());
+    Ok(())
+}
+
+#[test]
+fn test_chat_to_openai_json() {
+    use groqai::types::{ChatCompletionChunk, ChatCompletionResponse};
+
+    let response: ChatCompletionResponse = serde_json::from_value(serde_json::json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "llama-3.1-8b-instant",
+        "choices": [{
+            "index": 0,
+            "message": {"role": "assistant", "content": "Hi"},
+            "finish_reason": "stop",
+            "reasoning": "The user greeted me"
+        }],
+        "usage": {"prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2, "queue_time": 0.1},
+        "x_groq": {"id": "req_1"}
+    }))
+    .unwrap();
+    let json = response.to_openai_json();
+    assert_eq!(json["object"], "chat.completion");
+    assert_eq!(json["choices"][0]["message"], serde_json::json!({"role": "assistant", "content": "Hi"}));
+    assert!(json["choices"][0]["logprobs"].is_null());
+    assert!(json.get("x_groq").is_none());
+    assert!(json["usage"].get("queue_time").is_none());
+
+    let chunk: ChatCompletionChunk = serde_json::from_value(serde_json::json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion.chunk",
+        "created": 0,
+        "model": "llama-3.1-8b-instant",
+        "system_fingerprint": null,
+        "choices": [{
+            "index": 0,
+            "delta": {"tool_calls": [
+                {"index": 1, "id": "call_2", "type": "function", "function": {"name": "g", "arguments": ""}},
+                {"index": 0, "function": {"arguments": "}"}}
+            ]},
+            "finish_reason": null
+        }]
+    }))
+    .unwrap();
+    let json = chunk.to_openai_json();
+    let delta = &json["choices"][0]["delta"];
+    assert!(delta.get("content").is_none());
+    // The upstream index is kept, and continuation deltas stay partial
+    assert_eq!(delta["tool_calls"][0]["index"], 1);
+    assert_eq!(delta["tool_calls"][1]["index"], 0);
+    assert!(delta["tool_calls"][1].get("id").is_none());
+    assert_eq!(delta["tool_calls"][1]["function"]["arguments"], "}");
+    assert!(chunk.to_openai_sse().starts_with("data: {"));
+}
+
+#[test]
+fn test_choice_logprobs_utilities() {
+    use groqai::types::ChatCompletionResponse;
+
+    let response: ChatCompletionResponse = serde_json::from_value(serde_json::json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "llama-3.1-8b-instant",
+        "choices": [{
+            "index": 0,
+            "message": {"role": "assistant", "content": "B."},
+            "finish_reason": "stop",
+            "logprobs": {"content": [
+                {"token": "B", "logprob": -0.5, "top_logprobs": [
+                    {"token": "B", "logprob": -0.5},
+                    {"token": " A", "logprob": -1.5},
+                    {"token": "Hmm", "logprob": -3.0}
+                ]},
+                {"token": ".", "logprob": -1.5, "top_logprobs": []}
+            ]}
+        }],
+        "usage": {"prompt_tokens": 1, "completion_tokens": 2, "total_tokens": 3}
+    }))
+    .unwrap();
+    let logprobs = response.choices[0].logprobs.as_ref().unwrap();
+    assert_eq!(logprobs.average_logprob(), Some(-1.0));
+    assert!((logprobs.perplexity().unwrap() - std::f64::consts::E).abs() < 1e-9);
+    assert!((logprobs.span_probability(0, 1).unwrap() - (-0.5f64).exp()).abs() < 1e-9);
+
+    let confidence = logprobs.option_confidence(&["A", "B", "C"]).unwrap();
+    let expected_b = (-0.5f64).exp() / ((-0.5f64).exp() + (-1.5f64).exp());
+    assert!((confidence[1].1 - expected_b).abs() < 1e-9);
+    assert_eq!(confidence[2].1, 0.0);
+
+    let json = response.to_openai_json();
+    assert_eq!(json["choices"][0]["logprobs"]["content"][1]["token"], ".");
+}
+
+#[test]
+fn test_chat_request_validate_limits() {
+    use groqai::api::chat::{ChatCompletionRequest, MAX_LOGIT_BIAS_ENTRIES, MAX_TOOLS};
+    use std::collections::HashMap;
+
+    let tool = |name: &str, description: String| Tool {
+        type_: "function".to_string(),
+        function: FunctionDef {
+            name: name.to_string(),
+            description: Some(description),
+            parameters: serde_json::json!({"type": "object"}),
+        },
+    };
+    let mut request = ChatCompletionRequest {
+        model: "llama-3.1-8b-instant".to_string(),
+        messages: vec![ChatMessage::new_text(Role::User, "Hi")],
+        tools: Some((0..=MAX_TOOLS).map(|i| tool(&format!("t{}", i), String::new())).collect()),
+        ..Default::default()
+    };
+    let err = request.validate().unwrap_err();
+    assert!(err.to_string().contains("129 tools; the limit is 128"));
+
+    request.tools = Some(vec![tool("small", String::new()), tool("huge", "x".repeat(300_000))]);
+    let err = request.validate().unwrap_err();
+    assert!(err.to_string().contains("largest: `huge`"));
+
+    request.tools = None;
+    request.logit_bias = Some((0..=MAX_LOGIT_BIAS_ENTRIES).map(|i| (i.to_string(), 1.0)).collect());
+    assert!(request.validate().unwrap_err().to_string().contains("301 entries"));
+    request.logit_bias = Some(HashMap::from([("1234".to_string(), 150.0)]));
+    assert!(request.validate().unwrap_err().to_string().contains("token 1234 is 150"));
+    request.logit_bias = Some(HashMap::from([("hello".to_string(), 5.0)]));
+    assert!(request.validate().unwrap_err().to_string().contains("\"hello\" is not a token ID"));
+    request.logit_bias = Some(HashMap::from([("1234".to_string(), -100.0)]));
+    assert!(request.validate().is_ok());
+}
+
+fn filtered_completion() -> ResponseTemplate {
+    let mut body: serde_json::Value = serde_json::from_str(groqai::fixtures::CHAT_COMPLETION_RESPONSE).unwrap();
+    body["choices"][0]["finish_reason"] = serde_json::json!("content_filter");
+    ResponseTemplate::new(200).set_body_json(body)
+}
+
+#[tokio::test]
+async fn test_chat_content_filter_policy() -> Result<(), GroqError> {
+    use groqai::content_filter::ContentFilterAction;
+    use groqai::ChatCompletionRequest;
+    use groqai::ChatCompletionResponse;
+
+    // Without a policy, a filtered reply is an error
+    let (filtered, client) = chat_server(filtered_completion()).await;
+    let result = client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .send()
+        .await;
+    match result {
+        Err(GroqError::ContentFiltered { response, .. }) => assert!(response.is_content_filtered()),
+        other => panic!("expected ContentFiltered, got {:?}", other.map(|r| r.id)),
+    }
+
+    // Allow returns the filtered reply
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(filtered.uri().parse().unwrap())
+        .content_filter_policy(|_: &ChatCompletionRequest, _: &ChatCompletionResponse| ContentFilterAction::Allow)
+        .build()?;
+    let response = client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .send()
+        .await?;
+    assert_eq!(response.choices[0].finish_reason.as_deref(), Some("content_filter"));
+
+    // Retry sends the reworded request once
+    let mock = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(filtered_completion())
+        .up_to_n_times(1)
+        .mount(&mock)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(completion())
+        .mount(&mock)
+        .await;
+    let tracker = groqai::usage::UsageTracker::new();
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(mock.uri().parse().unwrap())
+        .usage_tracker(tracker.clone())
+        .content_filter_policy(|request: &ChatCompletionRequest, _: &ChatCompletionResponse| {
+            let mut softened = request.clone();
+            softened.messages.insert(0, ChatMessage::new_text(Role::System, "Keep it neutral."));
+            ContentFilterAction::Retry(Box::new(softened))
+        })
+        .build()?;
+    let response = client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .send()
+        .await?;
+    assert!(!response.is_content_filtered());
+    assert_eq!(mock.received_requests().await.unwrap().len(), 2);
+    assert_eq!(sent_body(&mock).await["messages"][0]["role"], "system");
+    // The filtered reply is counted along with the retried one
+    assert_eq!(tracker.records().len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_chat_content_filter_retry_is_checked_and_shared() -> Result<(), GroqError> {
+    use groqai::content_filter::ContentFilterAction;
+    use groqai::ChatCompletionRequest;
+    use groqai::ChatCompletionResponse;
+
+    // A retry breaking the request limits is rejected before it is sent
+    let (filtered, _) = chat_server(filtered_completion()).await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(filtered.uri().parse().unwrap())
+        .content_filter_policy(|request: &ChatCompletionRequest, _: &ChatCompletionResponse| {
+            let mut broken = request.clone();
+            broken.logit_bias = Some([("hello".to_string(), 5.0)].into_iter().collect());
+            ContentFilterAction::Retry(Box::new(broken))
+        })
+        .build()?;
+    let result = client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .send()
+        .await;
+    assert!(matches!(result, Err(GroqError::InvalidMessage(_))), "{:?}", result.map(|r| r.id));
+    assert_eq!(filtered.received_requests().await.unwrap().len(), 1);
+
+    // Callers sharing a filtered reply wait for a single retry
+    let mock = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(filtered_completion().set_delay(std::time::Duration::from_millis(100)))
+        .up_to_n_times(1)
+        .mount(&mock)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(completion())
+        .mount(&mock)
+        .await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(mock.uri().parse().unwrap())
+        .coalesce_requests(true)
+        .content_filter_policy(|request: &ChatCompletionRequest, _: &ChatCompletionResponse| {
+            ContentFilterAction::Retry(Box::new(request.clone()))
+        })
+        .build()?;
+    let send = || {
+        client
+            .chat("llama-3.1-8b-instant")
+            .message(ChatMessage::new_text(Role::User, "Hello"))
+            .temperature(0.0)
+            .send()
+    };
+    let (first, second) = tokio::join!(send(), send());
+    assert!(!first?.is_content_filtered());
+    assert!(!second?.is_content_filtered());
+    assert_eq!(mock.received_requests().await.unwrap().len(), 2);
+    Ok(())
+}
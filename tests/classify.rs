@@ -0,0 +1,95 @@
+use groqai::mock::MockTransport;
+use groqai::types::ChatCompletionResponse;
+use groqai::{GroqClientBuilder, GroqError};
+use serde_json::json;
+
+mod common;
+
+fn reply(text: &str, logprobs: serde_json::Value) -> ChatCompletionResponse {
+    let mut response = common::reply(text);
+    response.choices[0].logprobs = serde_json::from_value(logprobs).unwrap();
+    response
+}
+
+#[tokio::test]
+async fn test_classify_constrains_labels_and_reads_confidence() -> Result<(), GroqError> {
+    let tokens: Vec<_> = [("{\"", 0.0), ("label", 0.0), ("\":", 0.0), (" \"", 0.0), ("bill", -0.1), ("ing", -0.2), ("\"}", 0.0)]
+        .iter()
+        .map(|(token, logprob)| json!({"token": token, "logprob": logprob, "top_logprobs": []}))
+        .collect();
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply(r#"{"label": "billing"}"#, json!({"content": tokens}))))
+        .with_chat_response(Ok(reply(r#"{"label": "Bug"}"#, serde_json::Value::Null)))
+        .with_chat_response(Ok(reply(r#"{"label": "refund"}"#, serde_json::Value::Null)));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+    let labels = ["billing", "bug", "other"];
+
+    let result = client.classify("llama-3.1-8b-instant", "Charged twice", &labels).await?;
+    assert_eq!(result.label, "billing");
+    assert!((result.confidence.unwrap() - (-0.3f64).exp()).abs() < 1e-9);
+    let body = &mock.requests()[0].body;
+    assert_eq!(body["logprobs"], true);
+    assert!(body["messages"][0]["content"].as_str().unwrap().contains(r#"["billing","bug","other"]"#));
+    // The reply is constrained to the labels by a strict schema
+    assert_eq!(body["response_format"]["type"], "json_schema");
+    assert_eq!(body["response_format"]["json_schema"]["strict"], true);
+    assert_eq!(
+        body["response_format"]["json_schema"]["schema"]["properties"]["label"]["enum"],
+        json!(["billing", "bug", "other"])
+    );
+
+    let err = client
+        .classify_many("llama-3.1-8b-instant", &["App crashes", "Refund please"], &labels, 1)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("unknown label \"refund\""));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_classify_confidence_of_escaped_labels() -> Result<(), GroqError> {
+    let logprobs = |tokens: &[(&str, f64)]| {
+        let tokens: Vec<_> = tokens
+            .iter()
+            .map(|(token, logprob)| json!({"token": token, "logprob": logprob, "top_logprobs": []}))
+            .collect();
+        json!({"content": tokens})
+    };
+    let quoted = [("{\"", 0.0), ("label", 0.0), ("\":", 0.0), (" \"", 0.0), ("say \\\"", -0.1), ("hi\\\"", -0.2), ("\"}", 0.0)];
+    let escaped = [("{\"", 0.0), ("label", 0.0), ("\": \"", 0.0), ("C:\\\\", -0.4), ("caf\\u00e9", -0.5), ("\"}", 0.0)];
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply(r#"{"label": "say \"hi\""}"#, logprobs(&quoted))))
+        .with_chat_response(Ok(reply(r#"{"label": "C:\\café"}"#, logprobs(&escaped))));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock)
+        .build()?;
+    let labels = ["say \"hi\"", "C:\\café"];
+
+    let result = client.classify("llama-3.1-8b-instant", "Greet them", &labels).await?;
+    assert_eq!(result.label, "say \"hi\"");
+    assert!((result.confidence.unwrap() - (-0.3f64).exp()).abs() < 1e-9);
+    let result = client.classify("llama-3.1-8b-instant", "Where is it?", &labels).await?;
+    assert_eq!(result.label, "C:\\café");
+    assert!((result.confidence.unwrap() - (-0.9f64).exp()).abs() < 1e-9);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_classify_many_keeps_input_order() -> Result<(), GroqError> {
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply(r#"{"label": "bug"}"#, serde_json::Value::Null)))
+        .with_chat_response(Ok(reply(r#"{"label": "Other"}"#, serde_json::Value::Null)));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    let results = client
+        .classify_many("llama-3.1-8b-instant", &["App crashes", "Hello"], &["bug", "other"], 2)
+        .await?;
+    let labels: Vec<_> = results.iter().map(|r| r.label.as_str()).collect();
+    assert_eq!(labels, ["bug", "other"]);
+    assert_eq!(results[0].confidence, None);
+    Ok(())
+}
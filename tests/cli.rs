@@ -0,0 +1,154 @@
+use assert_cmd::Command;
+use groqai::fixtures;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Runs the `groq` binary against `mock` with `stdin` as its input
+async fn groq(mock: &MockServer, args: &[&str], stdin: &str) -> std::process::Output {
+    let base_url = format!("{}/", mock.uri());
+    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    let stdin = stdin.to_string();
+    tokio::task::spawn_blocking(move || {
+        Command::cargo_bin("groq")
+            .unwrap()
+            .env("GROQ_API_KEY", "gsk_test_key_12345")
+            .env("GROQ_BASE_URL", base_url)
+            .env_remove("GROQ_PROXY_URL")
+            .args(args)
+            .write_stdin(stdin)
+            .output()
+            .unwrap()
+    })
+    .await
+    .unwrap()
+}
+
+fn text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+async fn mount_failing_then_ok(mock: &MockServer, ok: ResponseTemplate) {
+    let error = serde_json::json!({
+        "error": {"message": "The model `nope` does not exist", "type": "invalid_request_error", "code": "model_not_found"}
+    });
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(error))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(mock)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ok)
+        .mount(mock)
+        .await;
+}
+
+/// Messages sent with each chat request, as (role, content) pairs
+async fn sent_messages(mock: &MockServer) -> Vec<Vec<(String, String)>> {
+    mock.received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|r| {
+            let body: serde_json::Value = serde_json::from_slice(&r.body).unwrap();
+            body["messages"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|m| (m["role"].as_str().unwrap().to_string(), m["content"].as_str().unwrap().to_string()))
+                .collect()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_chat_survives_failed_turns() {
+    let mock = MockServer::start().await;
+    let ok = ResponseTemplate::new(200).set_body_raw(fixtures::CHAT_COMPLETION_RESPONSE, "application/json");
+    mount_failing_then_ok(&mock, ok).await;
+
+    let output = groq(&mock, &["chat", "--system", "Be brief."], "first\nsecond\nthird\nexit\n").await;
+    assert!(output.status.success(), "{}", text(&output.stderr));
+    assert!(text(&output.stderr).contains("does not exist"));
+    assert!(text(&output.stdout).contains("Fast language models matter"));
+
+    // The failed turn is dropped from the history
+    let system = ("system".to_string(), "Be brief.".to_string());
+    let user = |content: &str| ("user".to_string(), content.to_string());
+    let messages = sent_messages(&mock).await;
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[1], [system.clone(), user("second")]);
+    assert_eq!(messages[2].len(), 4);
+    assert_eq!(messages[2][0], system);
+    assert_eq!(messages[2][2].0, "assistant");
+    assert_eq!(messages[2][3], user("third"));
+}
+
+#[tokio::test]
+async fn test_chat_stream_survives_failed_turns() {
+    let mock = MockServer::start().await;
+    let chunk: serde_json::Value = serde_json::from_str(fixtures::CHAT_COMPLETION_CHUNK).unwrap();
+    let body = format!("data: {}\n\ndata: [DONE]\n\n", chunk);
+    mount_failing_then_ok(&mock, ResponseTemplate::new(200).set_body_raw(body, "text/event-stream")).await;
+
+    let output = groq(&mock, &["chat", "--stream"], "first\nsecond\n").await;
+    assert!(output.status.success(), "{}", text(&output.stderr));
+    assert!(text(&output.stderr).contains("does not exist"));
+    assert!(text(&output.stdout).contains("Hello"));
+    let messages = sent_messages(&mock).await;
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[1], [("user".to_string(), "second".to_string())]);
+}
+
+#[tokio::test]
+async fn test_models_lists_models() {
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(fixtures::MODEL_LIST, "application/json"))
+        .mount(&mock)
+        .await;
+
+    let output = groq(&mock, &["models"], "").await;
+    assert!(output.status.success(), "{}", text(&output.stderr));
+    assert!(text(&output.stdout).contains("llama-3.1-8b-instant"));
+
+    // Unknown arguments are usage errors
+    let output = groq(&mock, &["models", "--all"], "").await;
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(mock.received_requests().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_transcribe_arguments() {
+    let mock = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/audio/transcriptions"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(fixtures::TRANSCRIPTION, "application/json"))
+        .mount(&mock)
+        .await;
+    let dir = std::env::temp_dir().join(format!("groq-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let audio = dir.join("hello.wav");
+    std::fs::write(&audio, b"RIFF fake audio").unwrap();
+    let audio = audio.to_str().unwrap();
+
+    let output = groq(&mock, &["transcribe", audio, "--language", "de", "--model", "whisper-large-v3-turbo"], "").await;
+    assert!(output.status.success(), "{}", text(&output.stderr));
+    assert!(text(&output.stdout).contains("Hello there. How can I help?"));
+    let requests = mock.received_requests().await.unwrap();
+    let body = text(&requests[0].body);
+    assert!(body.contains("name=\"language\"\r\n\r\nde"));
+    assert!(body.contains("name=\"model\"\r\n\r\nwhisper-large-v3-turbo"));
+
+    // A missing file argument is a usage error, a missing file a runtime error
+    let output = groq(&mock, &["transcribe"], "").await;
+    assert_eq!(output.status.code(), Some(2));
+    let output = groq(&mock, &["transcribe", dir.join("missing.wav").to_str().unwrap()], "").await;
+    assert_eq!(output.status.code(), Some(1));
+    assert!(text(&output.stderr).starts_with("error:"));
+    assert_eq!(mock.received_requests().await.unwrap().len(), 1);
+    std::fs::remove_dir_all(&dir).ok();
+}
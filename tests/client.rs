@@ -1,4 +1,4 @@
-use groqai::{GroqClient, GroqError};
+use groqai::{GroqClient, GroqClientBuilder, GroqError};
 
 #[tokio::test]
 async fn test_with_api_key() {
@@ -30,4 +30,536 @@ async fn test_new_alias_with_valid_key() {
     std::env::set_var("GROQ_API_KEY", "gsk_test_key_12345");
     let result = GroqClient::new();
     assert!(result.is_ok());
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn test_custom_root_certificate() -> Result<(), Box<dyn std::error::Error>> {
+    let pem = std::fs::read("tests/fixtures/private-ca.pem")?;
+    let cert = reqwest::Certificate::from_pem(&pem)?;
+    let result = GroqClientBuilder::new("gsk_test_key_12345".to_string())?
+        .add_root_certificate(cert)
+        .custom_roots_only(true)
+        .build();
+    assert!(result.is_ok());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_custom_roots_only_without_certificates() -> Result<(), GroqError> {
+    let result = GroqClientBuilder::new("gsk_test_key_12345".to_string())?
+        .custom_roots_only(true)
+        .build();
+    assert!(matches!(result, Err(GroqError::InvalidMessage(_))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_custom_transport_conflicts() -> Result<(), Box<dyn std::error::Error>> {
+    use groqai::mock::MockTransport;
+
+    let builder = || GroqClientBuilder::new("gsk_test_key_12345".to_string());
+    let pem = std::fs::read("tests/fixtures/private-ca.pem")?;
+    let conflicting = [
+        builder()?.http_client(reqwest::Client::new()),
+        builder()?.proxy(reqwest::Proxy::all("http://proxy.example.com:8080")?),
+        builder()?.add_root_certificate(reqwest::Certificate::from_pem(&pem)?),
+        builder()?.custom_roots_only(true),
+        builder()?.sdk_header("my-app/2.0"),
+    ];
+    for builder in conflicting {
+        let result = builder.transport(MockTransport::new()).build();
+        assert!(matches!(result, Err(GroqError::InvalidMessage(_))));
+    }
+
+    // The default header, or none at all, does not conflict
+    builder()?.transport(MockTransport::new()).build()?;
+    builder()?.disable_sdk_header().transport(MockTransport::new()).build()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_injected_http_client() -> Result<(), Box<dyn std::error::Error>> {
+    use std::time::Duration;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/models"))
+        .and(header("x-app", "shared"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"object": "list", "data": []}))
+                .set_delay(Duration::from_millis(300)),
+        )
+        .mount(&mock)
+        .await;
+
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("x-app", reqwest::header::HeaderValue::from_static("shared"));
+    let http = reqwest::Client::builder().default_headers(headers.clone()).build()?;
+
+    let client = GroqClientBuilder::new("gsk_test_key_12345".to_string())?
+        .base_url(mock.uri().parse()?)
+        .http_client(http.clone())
+        .build()?;
+    client.models().list().await?;
+
+    // The builder timeout applies per request to the injected client
+    let client = GroqClientBuilder::new("gsk_test_key_12345".to_string())?
+        .base_url(mock.uri().parse()?)
+        .http_client(http.clone())
+        .timeout(Duration::from_millis(50))
+        .build()?;
+    assert!(matches!(client.models().list().await, Err(GroqError::Transport(_))));
+
+    // Without an explicit builder timeout the injected client's own applies
+    let impatient = reqwest::Client::builder()
+        .default_headers(headers)
+        .timeout(Duration::from_millis(50))
+        .build()?;
+    let client = GroqClientBuilder::new("gsk_test_key_12345".to_string())?
+        .base_url(mock.uri().parse()?)
+        .http_client(impatient)
+        .build()?;
+    assert!(matches!(client.models().list().await, Err(GroqError::Transport(_))));
+
+    let result = GroqClientBuilder::new("gsk_test_key_12345".to_string())?
+        .http_client(http)
+        .proxy(reqwest::Proxy::all("http://proxy.example.com:8080")?)
+        .build();
+    assert!(matches!(result, Err(GroqError::InvalidMessage(_))));
+    Ok(())
+}
+
+#[cfg(feature = "middleware")]
+#[tokio::test]
+async fn test_middleware_client() -> Result<(), Box<dyn std::error::Error>> {
+    use groqai::Endpoint;
+    use std::sync::{Arc, Mutex};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    struct RecordEndpoints(Arc<Mutex<Vec<Option<Endpoint>>>>);
+
+    #[async_trait::async_trait]
+    impl reqwest_middleware::Middleware for RecordEndpoints {
+        async fn handle(
+            &self,
+            req: reqwest::Request,
+            extensions: &mut http::Extensions,
+            next: reqwest_middleware::Next<'_>,
+        ) -> reqwest_middleware::Result<reqwest::Response> {
+            self.0.lock().unwrap().push(extensions.get::<Endpoint>().cloned());
+            next.run(req, extensions).await
+        }
+    }
+
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/models"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({"object": "list", "data": []})),
+        )
+        .mount(&mock)
+        .await;
+
+    let endpoints = Arc::new(Mutex::new(Vec::new()));
+    let middleware = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+        .with(RecordEndpoints(endpoints.clone()))
+        .build();
+    let client = GroqClientBuilder::new("gsk_test_key_12345".to_string())?
+        .base_url(mock.uri().parse()?)
+        .middleware_client(middleware)
+        .build()?;
+    client.models().list().await?;
+    assert_eq!(*endpoints.lock().unwrap(), [Some(Endpoint::Models)]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_per_request_headers() -> Result<(), Box<dyn std::error::Error>> {
+    use groqai::{ChatMessage, Role};
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .and(header("x-route", "eu-west"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            groqai::fixtures::CHAT_COMPLETION_RESPONSE,
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&mock)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/models"))
+        .and(header("x-route", "us-east"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({"object": "list", "data": []})),
+        )
+        .expect(1)
+        .mount(&mock)
+        .await;
+
+    let client = GroqClientBuilder::new("gsk_test_key_12345".to_string())?
+        .base_url(format!("{}/", mock.uri()).parse()?)
+        .build()?;
+    client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .header("x-route", "eu-west")
+        .send()
+        .await?;
+    client.models().header("x-route", "us-east").list().await?;
+
+    let result = client.models().header("bad header", "x").list().await;
+    assert!(matches!(result, Err(GroqError::InvalidMessage(_))));
+    Ok(())
+}
+
+#[cfg(feature = "streaming")]
+#[tokio::test]
+async fn test_metrics_snapshot() -> Result<(), Box<dyn std::error::Error>> {
+    use futures::StreamExt;
+    use groqai::{ChatMessage, Role};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/models"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({"object": "list", "data": []})),
+        )
+        .mount(&mock)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/models/missing-1"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("{}"))
+        .mount(&mock)
+        .await;
+    let chunk: serde_json::Value = serde_json::from_str(groqai::fixtures::CHAT_COMPLETION_CHUNK)?;
+    let sse = format!("data: {}\n\ndata: [DONE]\n\n", chunk);
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(sse.clone(), "text/event-stream"))
+        .mount(&mock)
+        .await;
+
+    let client = GroqClientBuilder::new("gsk_test_key_12345".to_string())?
+        .base_url(format!("{}/", mock.uri()).parse()?)
+        .build()?;
+    client.models().list().await?;
+    client.models().list().await?;
+    assert!(client.models().retrieve("missing-1".to_string()).await.is_err());
+    let mut stream = client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .stream(true)
+        .send_stream()
+        .await?;
+    while stream.next().await.is_some() {}
+    drop(stream);
+
+    let snapshot = client.metrics_snapshot();
+    let models = &snapshot.endpoints["models"];
+    assert_eq!((models.requests, models.errors), (2, 0));
+    assert_eq!(models.response_bytes.count, 2);
+    assert_eq!(models.request_bytes.count, 0);
+    assert!(models.duration_ms.quantile(0.5).is_some());
+
+    let missing = &snapshot.endpoints["models/{id}"];
+    assert_eq!((missing.requests, missing.errors), (1, 1));
+
+    let chat = &snapshot.endpoints["chat/completions"];
+    assert_eq!(chat.requests, 1);
+    assert_eq!(chat.response_bytes.sum, sse.len() as f64);
+    assert!(chat.request_bytes.sum > 0.0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_prometheus_metrics() -> Result<(), Box<dyn std::error::Error>> {
+    use groqai::{ChatMessage, Role};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(groqai::fixtures::CHAT_COMPLETION_RESPONSE, "application/json"),
+        )
+        .mount(&mock)
+        .await;
+    let client = GroqClientBuilder::new("gsk_test_key_12345".to_string())?
+        .base_url(format!("{}/", mock.uri()).parse()?)
+        .build()?;
+    for _ in 0..2 {
+        client
+            .chat("llama-3.1-8b-instant")
+            .message(ChatMessage::new_text(Role::User, "Hello"))
+            .send()
+            .await?;
+    }
+
+    let usage: serde_json::Value = serde_json::from_str(groqai::fixtures::CHAT_COMPLETION_RESPONSE)?;
+    let prompt_tokens = usage["usage"]["prompt_tokens"].as_u64().unwrap() * 2;
+    let text = client.prometheus_metrics();
+    assert!(text.contains("# TYPE groqai_requests_total counter\n"));
+    assert!(text.contains("groqai_requests_total{endpoint=\"chat/completions\"} 2\n"));
+    assert!(text.contains("groqai_request_errors_total{endpoint=\"chat/completions\"} 0\n"));
+    assert!(text.contains(&format!(
+        "groqai_prompt_tokens_total{{endpoint=\"chat/completions\"}} {}\n",
+        prompt_tokens
+    )));
+    assert!(text.contains("# TYPE groqai_request_duration_seconds histogram\n"));
+    assert!(text.contains("groqai_request_duration_seconds_bucket{endpoint=\"chat/completions\",le=\"0.005\"}"));
+    assert!(text.contains("groqai_request_duration_seconds_bucket{endpoint=\"chat/completions\",le=\"+Inf\"} 2\n"));
+    assert!(text.contains("groqai_request_duration_seconds_count{endpoint=\"chat/completions\"} 2\n"));
+    assert!(text.lines().all(|line| line.starts_with('#') || line.starts_with("groqai_")));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_request_signer() -> Result<(), Box<dyn std::error::Error>> {
+    use async_trait::async_trait;
+    use groqai::signing::{RequestSigner, SigningRequest};
+    use groqai::{ChatMessage, Role};
+    use std::sync::{Arc, Mutex};
+    use wiremock::matchers::{header, header_exists, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[derive(Clone, Default)]
+    struct BodyLengthSigner {
+        seen: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl RequestSigner for BodyLengthSigner {
+        async fn sign(&self, request: &mut SigningRequest<'_>) -> Result<(), GroqError> {
+            assert!(request.headers().contains_key("authorization"));
+            let line = format!(
+                "{} {} {}",
+                request.method(),
+                request.url().path(),
+                request.body().map_or(0, <[u8]>::len)
+            );
+            if request.url().path().ends_with("/forbidden") {
+                return Err(GroqError::InvalidMessage("signing key unavailable".to_string()));
+            }
+            request.headers_mut().remove("authorization");
+            request.set_header("x-signature", &line)?;
+            self.seen.lock().unwrap().push(line);
+            Ok(())
+        }
+    }
+
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/models"))
+        .and(header("x-signature", "GET /models 0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"object": "list", "data": []})))
+        .expect(1)
+        .mount(&mock)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .and(header_exists("x-signature"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(groqai::fixtures::CHAT_COMPLETION_RESPONSE, "application/json"),
+        )
+        .expect(1)
+        .mount(&mock)
+        .await;
+
+    let signer = BodyLengthSigner::default();
+    let client = GroqClientBuilder::new("gsk_test_key_12345".to_string())?
+        .base_url(format!("{}/", mock.uri()).parse()?)
+        .request_signer(signer.clone())
+        .build()?;
+    client.models().list().await?;
+    client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .send()
+        .await?;
+    let seen = signer.seen.lock().unwrap().clone();
+    assert_eq!(seen[0], "GET /models 0");
+    assert!(seen[1].starts_with("POST /chat/completions "));
+    assert_ne!(seen[1], "POST /chat/completions 0");
+    let requests = mock.received_requests().await.unwrap();
+    assert!(requests.iter().all(|r| !r.headers.contains_key("authorization")));
+
+    // Signing errors fail the request before it is sent
+    let result = client.models().retrieve("forbidden".to_string()).await;
+    assert!(matches!(result, Err(GroqError::InvalidMessage(m)) if m == "signing key unavailable"));
+    assert_eq!(mock.received_requests().await.unwrap().len(), 2);
+
+    // Custom transports cannot be signed
+    let result = GroqClientBuilder::new("gsk_test_key_12345".to_string())?
+        .transport(groqai::mock::MockTransport::new())
+        .request_signer(signer)
+        .build();
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_clones_share_rate_limit_pause() -> Result<(), Box<dyn std::error::Error>> {
+    use groqai::error::{GroqApiError, GroqApiErrorDetails};
+    use groqai::mock::MockTransport;
+    use groqai::{ChatCompletionResponse, ChatMessage, Role};
+    use std::time::{Duration, Instant};
+
+    let reply: ChatCompletionResponse = serde_json::from_str(groqai::fixtures::CHAT_COMPLETION_RESPONSE)?;
+    let limited = GroqApiError {
+        status: reqwest::StatusCode::TOO_MANY_REQUESTS,
+        error: GroqApiErrorDetails {
+            message: "Rate limit reached".to_string(),
+            error_type: Some("tokens".to_string()),
+            code: Some("rate_limit_exceeded".to_string()),
+            param: None,
+        },
+        retry_after: Some(Duration::from_millis(400)),
+    };
+    let mock = MockTransport::new()
+        .with_chat_response(Err(GroqError::Api(limited)))
+        .with_chat_response(Ok(reply.clone()))
+        .with_chat_response(Ok(reply));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+    let clone = client.clone();
+
+    let first = tokio::spawn(async move {
+        client
+            .chat("llama-3.1-8b-instant")
+            .message(ChatMessage::new_text(Role::User, "Hello"))
+            .send()
+            .await
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(clone.rate_limiter.paused_for().is_some());
+
+    // The clone waits out the pause its sibling hit instead of sending now
+    let started = Instant::now();
+    clone
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .send()
+        .await?;
+    assert!(started.elapsed() >= Duration::from_millis(200), "{:?}", started.elapsed());
+    first.await??;
+    assert_eq!(mock.requests().len(), 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_retries_share_backoff_sequence() -> Result<(), Box<dyn std::error::Error>> {
+    use groqai::error::{GroqApiError, GroqApiErrorDetails};
+    use groqai::mock::MockTransport;
+    use groqai::{ChatCompletionResponse, ChatMessage, Role};
+    use std::time::Duration;
+
+    let reply: ChatCompletionResponse = serde_json::from_str(groqai::fixtures::CHAT_COMPLETION_RESPONSE)?;
+    let unavailable = GroqApiError {
+        status: reqwest::StatusCode::SERVICE_UNAVAILABLE,
+        error: GroqApiErrorDetails {
+            message: "Service unavailable".to_string(),
+            error_type: None,
+            code: None,
+            param: None,
+        },
+        retry_after: None,
+    };
+    let mock = MockTransport::new()
+        .with_chat_response(Err(GroqError::Api(unavailable)))
+        .with_chat_response(Ok(reply));
+    let mut client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+    client.rate_limiter.backoff.initial_interval = Duration::from_millis(200);
+    client.rate_limiter.backoff.randomization_factor = 0.0;
+    let clone = client.clone();
+
+    let first = tokio::spawn(async move {
+        client
+            .chat("llama-3.1-8b-instant")
+            .message(ChatMessage::new_text(Role::User, "Hello"))
+            .send()
+            .await
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // The retry took the first wait from the sequence the clone sees too
+    assert_eq!(clone.rate_limiter.next_backoff(None), Some(Duration::from_millis(400)));
+    first.await??;
+    assert_eq!(mock.requests().len(), 2);
+
+    // A successful request starts the sequence over
+    assert_eq!(clone.rate_limiter.next_backoff(None), Some(Duration::from_millis(200)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sdk_header() -> Result<(), Box<dyn std::error::Error>> {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"object": "list", "data": []})))
+        .mount(&mock)
+        .await;
+    let base_url: url::Url = format!("{}/", mock.uri()).parse()?;
+    let sdk_headers = || async {
+        mock.received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| r.headers.get_all("x-groqai-sdk").iter().map(|v| v.to_str().unwrap().to_string()).collect())
+            .collect::<Vec<Vec<String>>>()
+    };
+
+    let default = GroqClientBuilder::new("gsk_test_key_12345")?.base_url(base_url.clone()).build()?;
+    default.models().list().await?;
+    default.models().header("X-Groqai-Sdk", "proxy/1.0").list().await?;
+    let custom = GroqClientBuilder::new("gsk_test_key_12345")?
+        .base_url(base_url.clone())
+        .sdk_header(format!("groqai-rust/{} my-app/2.0", groqai::VERSION))
+        .build()?;
+    custom.models().list().await?;
+    let disabled = GroqClientBuilder::new("gsk_test_key_12345")?
+        .base_url(base_url.clone())
+        .disable_sdk_header()
+        .build()?;
+    disabled.models().list().await?;
+
+    let expected = format!("groqai-rust/{}", env!("CARGO_PKG_VERSION"));
+    assert_eq!(
+        sdk_headers().await,
+        [
+            vec![expected.clone()],
+            vec!["proxy/1.0".to_string()],
+            vec![format!("{} my-app/2.0", expected)],
+            vec![],
+        ]
+    );
+
+    let invalid = GroqClientBuilder::new("gsk_test_key_12345")?.sdk_header("bad\nvalue").build();
+    assert!(matches!(invalid, Err(GroqError::InvalidMessage(_))));
+    Ok(())
+}
+
+#[test]
+fn test_build_info() {
+    let info = groqai::BuildInfo::current();
+    assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(info.features.contains(&"html"), cfg!(feature = "html"));
+    let report = info.to_string();
+    assert!(report.starts_with(&format!("groqai {} ({} ", groqai::VERSION, std::env::consts::OS)));
+}
@@ -0,0 +1,23 @@
+//! Helpers shared by the integration tests
+#![allow(dead_code)]
+
+use groqai::fixtures;
+use groqai::types::{ChatCompletionResponse, MessageContent, Usage};
+
+/// The golden chat completion replying `text`, with one prompt and one completion token
+pub fn reply(text: &str) -> ChatCompletionResponse {
+    let mut response: ChatCompletionResponse = serde_json::from_str(fixtures::CHAT_COMPLETION_RESPONSE).unwrap();
+    response.choices[0].message.content = MessageContent::Text(text.to_string());
+    response.usage = usage(1, 1);
+    response
+}
+
+/// Token usage without timings
+pub fn usage(prompt_tokens: u32, completion_tokens: u32) -> Usage {
+    serde_json::from_value(serde_json::json!({
+        "prompt_tokens": prompt_tokens,
+        "completion_tokens": completion_tokens,
+        "total_tokens": prompt_tokens + completion_tokens
+    }))
+    .unwrap()
+}
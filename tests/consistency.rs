@@ -0,0 +1,42 @@
+use groqai::consistency::{final_answer, ConsistencyOptions};
+use groqai::mock::MockTransport;
+use groqai::{GroqClientBuilder, GroqError};
+
+mod common;
+
+use common::reply;
+
+#[test]
+fn test_final_answer_extraction() {
+    assert_eq!(final_answer("5 + 5 = 10\nAnswer: **10**."), Some("10".to_string()));
+    assert_eq!(final_answer("Thinking...\n\nThe ball costs $0.05\n"), Some("The ball costs $0.05".to_string()));
+    assert_eq!(final_answer("  \n"), None);
+}
+
+#[tokio::test]
+async fn test_self_consistency_majority_vote() -> Result<(), GroqError> {
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply("... Answer: 0.10")))
+        .with_chat_response(Ok(reply("... Answer: 0.05")))
+        .with_chat_response(Ok(reply("I am not sure")))
+        .with_chat_response(Ok(reply("... Answer: 0.05")));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    let options = ConsistencyOptions::new()
+        .concurrency(1)
+        .temperature(1.0)
+        .extractor(|r| r.split_once("Answer:").map(|(_, a)| a.trim().to_string()));
+    let vote = client
+        .self_consistency_with("llama-3.3-70b-versatile", "How much is the ball?", 4, &options)
+        .await?;
+    assert_eq!(vote.answer, "0.05");
+    assert_eq!(vote.votes, vec![("0.05".to_string(), 2), ("0.10".to_string(), 1)]);
+    assert_eq!(vote.responses.len(), 4);
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 4);
+    assert_eq!(requests[0].body["temperature"], 1.0);
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use groqai::mock::MockTransport;
+use groqai::types::{ChatCompletionResponse, ResponseFormat};
+use groqai::{ChatMessage, GroqClientBuilder, GroqError, Role};
+
+mod common;
+
+fn reply(text: &str, finish_reason: &str, completion_tokens: u32) -> ChatCompletionResponse {
+    let mut response = common::reply(text);
+    response.model = "llama-3.3-70b-versatile".to_string();
+    response.choices[0].finish_reason = Some(finish_reason.to_string());
+    response.usage = common::usage(10, completion_tokens);
+    response.usage.total_time = Some(0.5);
+    response
+}
+
+#[test]
+fn test_was_truncated() {
+    assert!(reply("The capital of", "length", 3).was_truncated());
+    assert!(!reply("The capital of France is Paris.", "stop", 7).was_truncated());
+    assert!(!reply("{\"city\": \"Par", "stop", 5).was_truncated());
+    assert!(reply("The capital of", "length", 3).was_truncated_json());
+    assert!(reply("{\"city\": \"Par", "stop", 5).was_truncated_json());
+    assert!(reply("[{\"a\": 1}, {\"b\": \"}\"", "stop", 9).was_truncated_json());
+    assert!(!reply("{\"city\": \"Paris {\"}", "stop", 6).was_truncated_json());
+    assert!(!reply("[1] is the reference", "stop", 4).was_truncated_json());
+}
+
+#[tokio::test]
+async fn test_auto_continue_splices_replies() -> Result<(), GroqError> {
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply("{\"city\": \"Pa", "stop", 5)))
+        .with_chat_response(Ok(reply("ris\", \"country\":", "length", 5)))
+        .with_chat_response(Ok(reply(" \"France\"}", "stop", 4)));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    let response = client
+        .chat("llama-3.3-70b-versatile")
+        .message(ChatMessage::new_text(Role::User, "Where is the Louvre? Answer in JSON."))
+        .response_format(ResponseFormat::json_object())
+        .auto_continue(3)
+        .send()
+        .await?;
+    assert_eq!(
+        response.choices[0].message.content.to_string(),
+        "{\"city\": \"Paris\", \"country\": \"France\"}"
+    );
+    assert_eq!(response.choices[0].finish_reason.as_deref(), Some("stop"));
+    assert!(!response.was_truncated());
+    assert_eq!(response.usage.prompt_tokens, 30);
+    assert_eq!(response.usage.completion_tokens, 14);
+    assert_eq!(response.usage.total_tokens, 44);
+    assert_eq!(response.usage.total_time, Some(1.5));
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].body["response_format"]["type"], "json_object");
+    assert!(requests[1].body.get("response_format").is_none());
+    let messages = requests[2].body["messages"].as_array().unwrap();
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[1]["role"], "assistant");
+    assert_eq!(messages[1]["content"], "{\"city\": \"Paris\", \"country\":");
+    assert_eq!(messages[2]["role"], "user");
+
+    // Continuations stop at the limit
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply("One", "length", 1)))
+        .with_chat_response(Ok(reply(" two", "length", 1)));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+    let response = client
+        .chat("llama-3.3-70b-versatile")
+        .message(ChatMessage::new_text(Role::User, "Count"))
+        .auto_continue(1)
+        .send()
+        .await?;
+    assert_eq!(response.choices[0].message.content.to_string(), "One two");
+    assert!(response.was_truncated());
+    assert_eq!(mock.requests().len(), 2);
+
+    // Unclosed brackets in a plain text reply are not continued
+    let mock = MockTransport::new().with_chat_response(Ok(reply("[1, 2, 3", "stop", 5)));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+    let response = client
+        .chat("llama-3.3-70b-versatile")
+        .message(ChatMessage::new_text(Role::User, "Show an unclosed list"))
+        .auto_continue(3)
+        .send()
+        .await?;
+    assert_eq!(response.choices[0].message.content.to_string(), "[1, 2, 3");
+    assert_eq!(mock.requests().len(), 1);
+    Ok(())
+}
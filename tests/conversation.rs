@@ -0,0 +1,126 @@
+use groqai::mock::MockTransport;
+use groqai::{ChatMessage, Conversation, GroqClientBuilder, GroqError, Role, SharedConversation};
+use std::sync::{Arc, Mutex};
+
+mod common;
+
+use common::reply;
+
+#[tokio::test]
+async fn test_shared_conversation_serializes_turns() -> Result<(), GroqError> {
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply("first")))
+        .with_chat_response(Ok(reply("second")));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+    let shared = SharedConversation::new(
+        client,
+        Conversation::new("llama-3.1-8b-instant").with_system("Be brief."),
+    );
+
+    let tasks: Vec<_> = ["a", "b"]
+        .into_iter()
+        .map(|text| {
+            let shared = shared.clone();
+            tokio::spawn(async move { shared.send(text).await })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap()?;
+    }
+
+    let roles: Vec<Role> = shared.messages().await.into_iter().map(|m| m.role).collect();
+    assert_eq!(
+        roles,
+        vec![Role::System, Role::User, Role::Assistant, Role::User, Role::Assistant]
+    );
+    // The second request saw the complete first turn
+    let requests = mock.requests();
+    assert_eq!(requests[0].body["messages"].as_array().unwrap().len(), 2);
+    assert_eq!(requests[1].body["messages"].as_array().unwrap().len(), 4);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_regenerate_restores_reply_on_failure() -> Result<(), GroqError> {
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply("first")))
+        .with_chat_response(Err(GroqError::RateLimited))
+        .with_chat_response(Ok(reply("again")));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock)
+        .build()?;
+    let mut conversation = Conversation::new("llama-3.1-8b-instant");
+
+    conversation.send(&client, "hi").await?;
+    assert!(conversation.regenerate(&client).await.is_err());
+    assert_eq!(conversation.messages().len(), 2);
+    assert_eq!(conversation.messages()[1].content.to_string(), "first");
+
+    conversation.regenerate(&client).await?;
+    assert_eq!(conversation.messages()[1].content.to_string(), "again");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_aborted_send_leaves_history_unchanged() -> Result<(), GroqError> {
+    use std::time::Duration;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::to_value(reply("late"))?)
+                .set_delay(Duration::from_secs(5)),
+        )
+        .mount(&server)
+        .await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(server.uri().parse().unwrap())
+        .build()?;
+    let shared = SharedConversation::new(
+        client,
+        Conversation::new("llama-3.1-8b-instant").with_system("Be brief."),
+    );
+
+    let task = {
+        let shared = shared.clone();
+        tokio::spawn(async move { shared.send("hi").await })
+    };
+    while server.received_requests().await.unwrap().is_empty() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    task.abort();
+    assert!(task.await.unwrap_err().is_cancelled());
+
+    // No dangling user message was left for the next turn
+    let roles: Vec<Role> = shared.messages().await.into_iter().map(|m| m.role).collect();
+    assert_eq!(roles, vec![Role::System]);
+    Ok(())
+}
+
+#[test]
+fn test_edit_drops_later_messages_and_notifies_hooks() {
+    let mut conversation = Conversation::new("llama-3.1-8b-instant").with_system("Be brief.");
+    conversation.push(ChatMessage::new_text(Role::User, "Hi"));
+    conversation.push(ChatMessage::new_text(Role::Assistant, "Hello!"));
+    let stale = Arc::new(Mutex::new(Vec::new()));
+    let seen = stale.clone();
+    conversation.on_edit(move |prefix| seen.lock().unwrap().push(prefix.len()));
+
+    conversation.edit(1, ChatMessage::new_text(Role::User, "Hey")).unwrap();
+    assert_eq!(conversation.messages().len(), 2);
+    assert_eq!(conversation.messages()[1].content.to_string(), "Hey");
+    assert!(matches!(
+        conversation.edit(5, ChatMessage::new_text(Role::User, "?")),
+        Err(GroqError::InvalidMessage(_))
+    ));
+
+    conversation.truncate(5);
+    conversation.clear();
+    assert_eq!(*stale.lock().unwrap(), [2, 2]);
+    assert_eq!(conversation.messages().len(), 1);
+}
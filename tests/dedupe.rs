@@ -0,0 +1,140 @@
+use groqai::api::chat::ChatCompletionRequest;
+use groqai::client::GroqClientBuilder;
+use groqai::dedupe::RequestDeduplicator;
+use groqai::error::GroqError;
+use groqai::types::{ChatMessage, Role};
+use groqai::usage::UsageTracker;
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+fn chat_response() -> serde_json::Value {
+    serde_json::to_value(common::reply("Hi")).unwrap()
+}
+
+#[tokio::test]
+async fn test_dedupe_window_shares_identical_requests() -> Result<(), GroqError> {
+    let mock = MockServer::start().await;
+    let tracker = UsageTracker::new();
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(mock.uri().parse().unwrap())
+        .dedupe_window(Duration::from_secs(60))
+        .usage_tracker(tracker.clone())
+        .build()?;
+
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(chat_response())
+                .set_delay(Duration::from_millis(100)),
+        )
+        .expect(2)
+        .mount(&mock)
+        .await;
+
+    let send = |text: &'static str| {
+        client
+            .chat("llama-3.1-8b-instant")
+            .message(ChatMessage::new_text(Role::User, text))
+            .send()
+    };
+
+    // Concurrent double-submit, then a late duplicate inside the window
+    let (a, b) = tokio::join!(send("Hello"), send("Hello"));
+    assert_eq!(a?.id, b?.id);
+    send("Hello").await?;
+    // Usage is counted once per upstream response
+    assert_eq!(tracker.totals().requests, 1);
+
+    // A different body is a different request
+    send("Goodbye").await?;
+    assert_eq!(tracker.totals().requests, 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dedupe_does_not_reuse_failures() -> Result<(), GroqError> {
+    let mock = MockServer::start().await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(mock.uri().parse().unwrap())
+        .dedupe_window(Duration::from_secs(60))
+        .build()?;
+
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(2)
+        .mount(&mock)
+        .await;
+
+    for _ in 0..2 {
+        let result = client
+            .chat("llama-3.1-8b-instant")
+            .message(ChatMessage::new_text(Role::User, "Hello"))
+            .send()
+            .await;
+        assert!(result.is_err());
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_coalesce_concurrent_deterministic_requests() -> Result<(), GroqError> {
+    let mock = MockServer::start().await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(mock.uri().parse().unwrap())
+        .coalesce_requests(true)
+        .build()?;
+
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(chat_response())
+                .set_delay(Duration::from_millis(100)),
+        )
+        .expect(4)
+        .mount(&mock)
+        .await;
+
+    let send = |temperature: f32, dedupe: bool| {
+        client
+            .chat("llama-3.1-8b-instant")
+            .message(ChatMessage::new_text(Role::User, "Hello"))
+            .temperature(temperature)
+            .dedupe(dedupe)
+            .send()
+    };
+
+    // Three concurrent deterministic requests share one call
+    let (a, b, c) = tokio::join!(send(0.0, true), send(0.0, true), send(0.0, true));
+    a?;
+    b?;
+    c?;
+
+    // Sampled requests are never coalesced
+    let (d, e) = tokio::join!(send(0.7, true), send(0.7, true));
+    d?;
+    e?;
+
+    // Completed requests are not reused, and requests can opt out
+    send(0.0, false).await?;
+    Ok(())
+}
+
+#[test]
+fn test_key_is_the_request_body() -> Result<(), GroqError> {
+    let request = |text: &str| ChatCompletionRequest {
+        model: "llama-3.1-8b-instant".to_string(),
+        messages: vec![ChatMessage::new_text(Role::User, text)],
+        ..Default::default()
+    };
+    let key = RequestDeduplicator::key(&request("Hello"))?;
+    assert_eq!(key, RequestDeduplicator::key(&request("Hello"))?);
+    assert_ne!(key, RequestDeduplicator::key(&request("Goodbye"))?);
+    assert!(key.contains("\"Hello\""));
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use groqai::defaults::{ModelDefaults, ModelDefaultsTable};
+use groqai::mock::MockTransport;
+use groqai::{ChatMessage, GroqClientBuilder, GroqError, Role};
+
+mod common;
+
+use common::reply;
+
+
+#[tokio::test]
+async fn test_model_defaults_fill_unset_parameters() -> Result<(), GroqError> {
+    let table = ModelDefaultsTable::from_json_str(
+        r#"{
+            "*": {"max_completion_tokens": 64},
+            "llama-3.1-8b-instant": {"temperature": 0.2, "stop": ["END"]}
+        }"#,
+    )?;
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply("ok")))
+        .with_chat_response(Ok(reply("ok")))
+        .with_chat_response(Ok(reply("ok")));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .model_defaults(table.clone())
+        .build()?;
+    let hi = || ChatMessage::new_text(Role::User, "Hi");
+
+    client.chat("llama-3.1-8b-instant").message(hi()).send().await?;
+    client.chat("llama-3.1-8b-instant").message(hi()).temperature(0.9).send().await?;
+    // Runtime updates apply to later requests
+    table.set("other-model", ModelDefaults { temperature: Some(0.0), ..Default::default() });
+    client.chat("other-model").message(hi()).send().await?;
+
+    let bodies: Vec<_> = mock.requests().into_iter().map(|r| r.body).collect();
+    assert_eq!(bodies[0]["temperature"].as_f64().unwrap() as f32, 0.2);
+    assert_eq!(bodies[0]["stop"], serde_json::json!(["END"]));
+    assert_eq!(bodies[0]["max_completion_tokens"], 1000);
+    assert_eq!(bodies[1]["temperature"].as_f64().unwrap() as f32, 0.9);
+    assert_eq!(bodies[2]["temperature"], 0.0);
+    assert_eq!(bodies[2]["max_completion_tokens"], 1000);
+    Ok(())
+}
+
+#[test]
+fn test_model_defaults_fallback_and_validation() {
+    let table = ModelDefaultsTable::from_json_str(r#"{"*": {"max_completion_tokens": 64}}"#).unwrap();
+    assert_eq!(table.get("any-model").unwrap().max_completion_tokens, Some(64));
+    assert!(ModelDefaultsTable::from_json_str(r#"{"m": {"temprature": 0.1}}"#).is_err());
+}
@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use groqai::diarize::{label_speakers, PauseDiarizer};
+use groqai::mock::MockTransport;
+use groqai::{GroqClientBuilder, GroqError, Transcription};
+
+mod common;
+
+fn transcription() -> Transcription {
+    serde_json::from_value(serde_json::json!({
+        "text": "How are you? Fine thanks. And you? Great.",
+        "segments": [
+            {"id": 0, "start": 0.0, "end": 1.0, "text": " How are you?"},
+            {"id": 1, "start": 3.0, "end": 4.0, "text": " Fine thanks."},
+            {"id": 2, "start": 4.2, "end": 5.0, "text": " And you?"},
+            {"id": 3, "start": 7.0, "end": 8.0, "text": " Great."}
+        ]
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_pause_diarizer_alternates_speakers() -> Result<(), GroqError> {
+    let utterances = transcription()
+        .diarize(&PauseDiarizer::new().min_pause(Duration::from_secs(1)))
+        .await?;
+    let summary: Vec<(&str, &str)> = utterances
+        .iter()
+        .map(|u| (u.speaker.as_str(), u.text.as_str()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("SPEAKER_1", "How are you?"),
+            ("SPEAKER_2", "Fine thanks. And you?"),
+            ("SPEAKER_1", "Great."),
+        ]
+    );
+    assert_eq!((utterances[1].start, utterances[1].end), (3.0, 5.0));
+
+    let plain: Transcription = serde_json::from_value(serde_json::json!({"text": "hi"}))?;
+    assert!(plain.diarize(&PauseDiarizer::new()).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_label_speakers_with_chat_model() -> Result<(), GroqError> {
+    let reply = common::reply("{\"SPEAKER_1\": \"Host\"}");
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(MockTransport::new().with_chat_response(Ok(reply)))
+        .build()?;
+
+    let utterances = transcription().diarize(&PauseDiarizer::new()).await?;
+    let labelled = label_speakers(&client, "llama-3.3-70b-versatile", utterances).await?;
+    assert_eq!(labelled[0].speaker, "Host");
+    assert_eq!(labelled[1].speaker, "SPEAKER_2");
+    Ok(())
+}
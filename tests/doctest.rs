@@ -0,0 +1,54 @@
+#![cfg(all(feature = "audio", feature = "batches", feature = "fine-tuning", feature = "streaming"))]
+
+use futures_util::stream::StreamExt;
+use groqai::doctest;
+use groqai::{AudioResponseFormat, ChatMessage, GroqError, Role, TranscriptionOutput};
+
+#[tokio::test]
+async fn test_fixture_client_answers_every_call() -> Result<(), GroqError> {
+    let client = doctest::client();
+
+    for _ in 0..2 {
+        let response = client
+            .chat("llama-3.1-8b-instant")
+            .message(ChatMessage::new_text(Role::User, "Hello!"))
+            .send()
+            .await?;
+        assert_eq!(response.choices.len(), 1);
+    }
+
+    let mut stream = client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hello!"))
+        .stream(true)
+        .send_stream()
+        .await?;
+    let chunk = stream.next().await.expect("one chunk")?;
+    assert_eq!(chunk.choices[0].delta.content.as_ref().unwrap().to_string(), "Hello");
+    assert!(stream.next().await.is_none());
+
+    let batch = client.batches().retrieve("batch_abc123").await?;
+    assert_eq!(batch.id, "batch_abc123");
+    let model = client.models().retrieve("llama-3.3-70b-versatile").await?;
+    assert_eq!(model.id, "llama-3.3-70b-versatile");
+    let job = client.fine_tunings().retrieve("ft_abc123").await?;
+    assert!(job.is_succeeded());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fixture_transcripts_follow_response_format() -> Result<(), GroqError> {
+    let client = doctest::client();
+
+    let output = client
+        .audio()
+        .transcribe_url("https://example.com/talk.mp3")
+        .response_format(AudioResponseFormat::Text)
+        .send_output()
+        .await?;
+    assert!(matches!(output, TranscriptionOutput::Text(ref text) if text == "Hello there. How can I help?"));
+
+    let transcription = client.audio().transcribe_url("https://example.com/talk.mp3").word_timestamps().send().await?;
+    assert_eq!(transcription.words.len(), 6);
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use groqai::document::{AttachOptions, Document, DocumentFormat};
+use groqai::mock::MockTransport;
+use groqai::text::{estimate_tokens, Splitter};
+use groqai::{Conversation, GroqClientBuilder, GroqError, Role};
+
+mod common;
+
+use common::reply;
+
+fn handbook() -> String {
+    (1..=20)
+        .map(|i| format!("# Section {}\n\nPolicy number {} applies to every employee of the company.", i, i))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[test]
+fn test_format_from_extension() {
+    assert_eq!(DocumentFormat::from_extension("MD"), Some(DocumentFormat::Markdown));
+    assert_eq!(DocumentFormat::from_extension("htm"), Some(DocumentFormat::Html));
+    assert_eq!(DocumentFormat::from_extension("pdf"), Some(DocumentFormat::Pdf));
+    assert_eq!(DocumentFormat::from_extension("docx"), None);
+}
+
+#[tokio::test]
+async fn test_open_reads_markdown() -> Result<(), GroqError> {
+    let path = std::env::temp_dir().join(format!("groqai_document_{}.md", std::process::id()));
+    std::fs::write(&path, handbook()).unwrap();
+    let document = Document::open(&path).await;
+    std::fs::remove_file(&path).unwrap();
+
+    let document = document?;
+    assert_eq!(document.format(), DocumentFormat::Markdown);
+    assert_eq!(document.name(), path.file_name().unwrap().to_str().unwrap());
+    assert_eq!(document.chunks(&Splitter::markdown_headings(20)).len(), 20);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_attached_document_is_sent_with_the_question() -> Result<(), GroqError> {
+    let mock = MockTransport::new().with_chat_response(Ok(reply("Ten days.")));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+    let document = Document::from_markdown("handbook.md", handbook());
+
+    let mut conversation = Conversation::new("llama-3.1-8b-instant").with_system("Answer from the document.");
+    let attached = conversation.attach_document(&document, &AttachOptions::new(4_000))?;
+    assert!(!attached.is_truncated());
+    assert_eq!(attached.chunks, attached.total_chunks);
+    conversation.send(&client, "How many vacation days?").await?;
+
+    let roles: Vec<Role> = conversation.messages().iter().map(|m| m.role.clone()).collect();
+    assert_eq!(roles, [Role::System, Role::User, Role::User, Role::Assistant]);
+    let body = &mock.requests()[0].body;
+    let attachment = body["messages"][1]["content"].as_str().unwrap();
+    assert!(attachment.starts_with("Document: handbook.md\n\n# Section 1"));
+    assert!(attachment.contains("Policy number 20 applies"));
+    Ok(())
+}
+
+#[test]
+fn test_attach_truncates_to_budget() -> Result<(), GroqError> {
+    let document = Document::from_markdown("handbook.md", handbook());
+    let options = AttachOptions::new(100).splitter(Splitter::markdown_headings(20));
+    let mut conversation = Conversation::new("llama-3.1-8b-instant");
+
+    let attached = conversation.attach_document(&document, &options)?;
+    assert!(attached.is_truncated());
+    assert!(attached.chunks > 0);
+    assert_eq!(attached.total_chunks, 20);
+    assert!(attached.tokens <= 100);
+
+    let text = conversation.messages()[0].content.to_string();
+    assert_eq!(estimate_tokens(&text), attached.tokens);
+    assert!(text.ends_with(&format!("[Truncated: the first {} of 20 parts of the document]", attached.chunks)));
+    Ok(())
+}
+
+#[test]
+fn test_attach_rejects_documents_that_do_not_fit() {
+    let mut conversation = Conversation::new("llama-3.1-8b-instant");
+    let document = Document::from_text("notes.txt", "A sentence that is longer than the budget allows.");
+    let result = conversation.attach_document(&document, &AttachOptions::new(5));
+    assert!(matches!(result, Err(GroqError::InvalidMessage(_))));
+
+    let empty = Document::from_text("empty.txt", "  \n");
+    let result = conversation.attach_document(&empty, &AttachOptions::new(1_000));
+    assert!(matches!(result, Err(GroqError::InvalidMessage(_))));
+    assert!(conversation.messages().is_empty());
+}
+
+#[cfg(not(feature = "pdf"))]
+#[test]
+fn test_pdf_requires_feature() {
+    let result = Document::from_bytes("report.pdf", DocumentFormat::Pdf, b"%PDF-1.4");
+    assert!(matches!(result, Err(GroqError::InvalidMessage(m)) if m.contains("`pdf` feature")));
+}
+
+#[cfg(feature = "html")]
+#[test]
+fn test_html_is_rendered_to_text() -> Result<(), GroqError> {
+    let html = b"<html><body><h1>Leave</h1><p>Ten <b>days</b> per year.</p><script>ignored()</script></body></html>";
+    let document = Document::from_bytes("leave.html", DocumentFormat::Html, html)?;
+    assert!(document.text().contains("# Leave"));
+    assert!(document.text().contains("Ten **days** per year."));
+    assert!(!document.text().contains("ignored()"));
+    Ok(())
+}
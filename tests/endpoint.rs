@@ -0,0 +1,35 @@
+use groqai::Endpoint;
+
+#[test]
+fn test_endpoint_paths_round_trip() {
+    let id = || "id_01abc".to_string();
+    let endpoints = [
+        Endpoint::ChatCompletions,
+        Endpoint::AudioTranscriptions,
+        Endpoint::AudioTranslations,
+        Endpoint::AudioSpeech,
+        Endpoint::Embeddings,
+        Endpoint::Files,
+        Endpoint::File(id()),
+        Endpoint::FileContent(id()),
+        Endpoint::Batches,
+        Endpoint::Batch(id()),
+        Endpoint::BatchCancel(id()),
+        Endpoint::Models,
+        Endpoint::Model("meta-llama/llama-4-scout-17b-16e-instruct".to_string()),
+        Endpoint::FineTuningJobs,
+        Endpoint::FineTuningJob(id()),
+        Endpoint::FineTuningJobEvents(id()),
+        Endpoint::FineTuningJobCancel(id()),
+    ];
+    for endpoint in endpoints {
+        assert_eq!(Endpoint::from_path(&endpoint.path()), Some(endpoint.clone()));
+        assert_eq!(endpoint.label().replace("{id}", endpoint.id().unwrap_or("{id}")), endpoint.path());
+    }
+
+    assert_eq!(Endpoint::from_path("/files?purpose=batch"), Some(Endpoint::Files));
+    assert_eq!(Endpoint::File("file_1".to_string()).to_string(), "files/file_1");
+    assert_eq!(Endpoint::from_path("files/"), Some(Endpoint::Files));
+    assert_eq!(Endpoint::from_path("batches//cancel"), None);
+    assert_eq!(Endpoint::from_path("chat/completion"), None);
+}
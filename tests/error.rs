@@ -0,0 +1,62 @@
+use groqai::error::GroqApiError;
+use groqai::{ErrorBody, GroqError};
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::StatusCode;
+
+fn api_error(status: StatusCode, body: &str, headers: &HeaderMap) -> GroqError {
+    GroqError::Api(GroqApiError::from_response(status, body.to_string(), headers))
+}
+
+#[test]
+fn test_client_errors_pass_through() {
+    let err = api_error(
+        StatusCode::NOT_FOUND,
+        r#"{"error": {"message": "The model `nope` does not exist", "type": "invalid_request_error", "code": "model_not_found"}}"#,
+        &HeaderMap::new(),
+    );
+    assert_eq!(err.to_http_status(), StatusCode::NOT_FOUND);
+
+    let body = ErrorBody::from(&err);
+    assert_eq!(body.error.message, "The model `nope` does not exist");
+    assert_eq!(body.error.code.as_deref(), Some("model_not_found"));
+}
+
+#[test]
+fn test_upstream_auth_and_server_errors_are_hidden() {
+    let err = api_error(
+        StatusCode::UNAUTHORIZED,
+        r#"{"error": {"message": "Invalid API Key gsk_abc", "type": "invalid_request_error"}}"#,
+        &HeaderMap::new(),
+    );
+    assert_eq!(err.to_http_status(), StatusCode::BAD_GATEWAY);
+    let json = serde_json::to_value(err.to_error_body()).unwrap();
+    assert_eq!(json["error"]["type"], "upstream_error");
+    assert!(!json.to_string().contains("gsk_abc"));
+
+    let err = api_error(StatusCode::SERVICE_UNAVAILABLE, "overloaded", &HeaderMap::new());
+    assert_eq!(err.to_http_status(), StatusCode::BAD_GATEWAY);
+}
+
+#[test]
+fn test_rate_limit_body_includes_retry_after() {
+    let mut headers = HeaderMap::new();
+    headers.insert("retry-after", HeaderValue::from_static("7"));
+    let err = api_error(StatusCode::TOO_MANY_REQUESTS, "{}", &headers);
+    assert_eq!(err.to_http_status(), StatusCode::TOO_MANY_REQUESTS);
+    let body = err.to_error_body();
+    assert_eq!(body.error.error_type, "rate_limit_exceeded");
+    assert_eq!(body.error.retry_after, Some(7));
+
+    let json = serde_json::to_value(GroqError::RateLimited.to_error_body()).unwrap();
+    assert_eq!(json["error"]["type"], "rate_limit_exceeded");
+    assert!(json["error"].get("retry_after").is_none());
+}
+
+#[test]
+fn test_local_errors_are_hidden() {
+    let err = GroqError::InvalidMessage("Failed to read file /srv/app/secrets.env: permission denied".to_string());
+    assert_eq!(err.to_http_status(), StatusCode::BAD_REQUEST);
+    let json = serde_json::to_value(err.to_error_body()).unwrap();
+    assert_eq!(json["error"]["type"], "invalid_request_error");
+    assert!(!json.to_string().contains("/srv/app"));
+}
@@ -0,0 +1,102 @@
+use groqai::extract::{ExtractOptions, ExtractSchema};
+use groqai::mock::MockTransport;
+use groqai::{GroqClientBuilder, GroqError};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+mod common;
+
+use common::reply;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Invoice {
+    number: String,
+    total: f64,
+}
+
+impl ExtractSchema for Invoice {
+    fn json_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "number": {"type": "string"},
+                "total": {"type": "number"}
+            },
+            "required": ["number", "total"]
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_extract_retries_with_schema_feedback() -> Result<(), GroqError> {
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply(r#"{"number": "INV-7"}"#)))
+        .with_chat_response(Ok(reply(r#"{"number": "INV-7", "total": 120.5}"#)));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    let invoice: Invoice = client
+        .extract("llama-3.3-70b-versatile", "Invoice INV-7 is due: 120.50 EUR")
+        .await?;
+    assert_eq!(invoice, Invoice { number: "INV-7".to_string(), total: 120.5 });
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].body["response_format"]["type"], "json_object");
+    let retry = &requests[1].body["messages"];
+    assert_eq!(retry.as_array().unwrap().len(), 4);
+    assert!(retry[3]["content"].as_str().unwrap().contains("total"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_extract_gives_up_after_max_attempts() -> Result<(), GroqError> {
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply("not json")))
+        .with_chat_response(Ok(reply(r#"{"number": 7, "total": 1}"#)));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    let err = client
+        .extract_with::<Invoice>("llama-3.3-70b-versatile", "?", &ExtractOptions::new().max_attempts(2))
+        .await
+        .unwrap_err();
+    assert!(matches!(&err, GroqError::InvalidMessage(m) if m.contains("after 2 attempts")));
+    assert_eq!(mock.requests().len(), 2);
+    Ok(())
+}
+
+#[cfg(feature = "schema-export")]
+#[tokio::test]
+async fn test_extract_derives_schema_from_json_schema() -> Result<(), GroqError> {
+    #[derive(Debug, Deserialize, PartialEq, schemars::JsonSchema)]
+    struct Line {
+        item: String,
+        quantity: u32,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq, schemars::JsonSchema)]
+    struct Order {
+        number: String,
+        lines: Vec<Line>,
+    }
+
+    // Nested types are inlined, so the reply is checked without `$ref` support
+    let schema = Order::json_schema();
+    assert!(!schema.to_string().contains("$ref"));
+    assert_eq!(schema["properties"]["lines"]["items"]["required"], json!(["item", "quantity"]));
+
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply(r#"{"number": "PO-3", "lines": [{"item": "pen"}]}"#)))
+        .with_chat_response(Ok(reply(r#"{"number": "PO-3", "lines": [{"item": "pen", "quantity": 2}]}"#)));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    let order: Order = client.extract("llama-3.3-70b-versatile", "PO-3: two pens").await?;
+    assert_eq!(order.lines, vec![Line { item: "pen".to_string(), quantity: 2 }]);
+    assert_eq!(mock.requests().len(), 2);
+    Ok(())
+}
@@ -1,6 +1,11 @@
-use groqai::client::GroqClientBuilder;
+#![cfg(feature = "files")]
+
+use groqai::client::{GroqClient, GroqClientBuilder};
 use groqai::error::GroqError;
+use groqai::FilePurpose;
 use std::path::PathBuf;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
 
 #[tokio::test]
 async fn test_file_create_invalid_extension() -> Result<(), GroqError> {
@@ -9,36 +14,96 @@ async fn test_file_create_invalid_extension() -> Result<(), GroqError> {
     Ok(())
 }
 
+#[test]
+fn test_file_purpose_wire_format() -> Result<(), GroqError> {
+    assert_eq!(serde_json::to_value(FilePurpose::FineTune)?, serde_json::json!("fine-tune"));
+    let purposes: Vec<FilePurpose> = serde_json::from_str(r#"["batch", "fine-tune", "batch_output"]"#)?;
+    assert_eq!(
+        purposes,
+        [FilePurpose::Batch, FilePurpose::FineTune, FilePurpose::Other("batch_output".to_string())]
+    );
+    assert_eq!(String::from(purposes[2].clone()), "batch_output");
+
+    let request = groqai::FileCreateRequest::unchecked("shard.jsonl", FilePurpose::Batch);
+    assert_eq!(serde_json::to_value(&request)?["purpose"], "batch");
+    Ok(())
+}
+
+async fn mock_client(mock: &MockServer) -> GroqClient {
+    GroqClientBuilder::new("gsk_test_key".to_string())
+        .unwrap()
+        .base_url(mock.uri().parse().unwrap())
+        .build()
+        .unwrap()
+}
+
+fn fixture(body: &'static str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "application/json")
+}
+
 #[tokio::test]
 async fn test_file_list_success() -> Result<(), GroqError> {
-    let client = GroqClientBuilder::new("gsk_test_key".to_string())
-        .unwrap()
-        .build()?;
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/files"))
+        .respond_with(fixture(groqai::fixtures::FILE_LIST))
+        .expect(1)
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
+
+    let files = client.files().list().await?;
+    assert_eq!(files.data.len(), 1);
+    assert_eq!(files.data[0].filename, "batch_file.jsonl");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_retrieve_success() -> Result<(), GroqError> {
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/files/file_01jh6x76wtemjr74t1fh0faj5t"))
+        .respond_with(fixture(groqai::fixtures::FILE))
+        .expect(1)
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
 
-    let result = client.files().list().await;
-    assert!(result.is_err());
+    let file = client.files().retrieve("file_01jh6x76wtemjr74t1fh0faj5t").await?;
+    assert_eq!(file.id, "file_01jh6x76wtemjr74t1fh0faj5t");
     Ok(())
 }
 
 #[tokio::test]
 async fn test_file_retrieve_error() -> Result<(), GroqError> {
-    let client = GroqClientBuilder::new("gsk_test_key".to_string())
-        .unwrap()
-        .build()?;
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/files/file_123"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": {"message": "File not found", "type": "invalid_request_error"}
+        })))
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
 
     let result = client.files().retrieve("file_123".to_string()).await;
-    assert!(result.is_err());
+    assert!(matches!(result, Err(GroqError::Api(e)) if e.status.as_u16() == 404));
     Ok(())
 }
 
 #[tokio::test]
 async fn test_file_delete_success() -> Result<(), GroqError> {
-    let client = GroqClientBuilder::new("gsk_test_key".to_string())
-        .unwrap()
-        .build()?;
+    let mock = MockServer::start().await;
+    Mock::given(method("DELETE"))
+        .and(path("/files/file_01jh6x76wtemjr74t1fh0faj5t"))
+        .respond_with(fixture(groqai::fixtures::FILE_DELETION))
+        .expect(1)
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
 
-    let result = client.files().delete("file_123".to_string()).await;
-    assert!(result.is_err());
+    let deletion = client.files().delete("file_01jh6x76wtemjr74t1fh0faj5t".to_string()).await?;
+    assert!(deletion.deleted);
     Ok(())
 }
 
@@ -52,4 +117,374 @@ async fn test_files_methods_exist() -> Result<(), GroqError> {
     let _builder = client.files();
     
     Ok(())
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn test_file_create_multipart_layout() -> Result<(), GroqError> {
+    let mock = MockServer::start().await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(mock.uri().parse().unwrap())
+        .build()?;
+
+    Mock::given(method("POST"))
+        .and(path("/files"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "file_123",
+            "object": "file",
+            "bytes": 42,
+            "created_at": 0,
+            "filename": "upload.jsonl",
+            "purpose": "batch"
+        })))
+        .mount(&mock)
+        .await;
+
+    let dir = std::env::temp_dir().join(format!("groqai_multipart_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("upload.jsonl");
+    std::fs::write(&file, "{\"custom_id\": \"1\"}\n").unwrap();
+
+    let req = groqai::api::files::FileCreateRequest::new(file, "batch".to_string())?;
+    let uploaded = client.files().create(req).await?;
+    assert_eq!(uploaded.id, "file_123");
+
+    let requests = mock.received_requests().await.unwrap();
+    let content_type = requests[0].headers.get("content-type").unwrap().to_str().unwrap();
+    assert!(content_type.starts_with("multipart/form-data; boundary="));
+    let body = String::from_utf8_lossy(&requests[0].body);
+    assert!(body.contains("Content-Disposition: form-data; name=\"file\"; filename=\"upload.jsonl\""));
+    assert!(body.contains("Content-Type: application/jsonl"));
+    assert!(body.contains("{\"custom_id\": \"1\"}"));
+    assert!(body.contains("Content-Disposition: form-data; name=\"purpose\"\r\n\r\nbatch"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_create_from_memory() -> Result<(), GroqError> {
+    use groqai::api::files::FileCreateRequest;
+    use std::sync::{Arc, Mutex};
+
+    let mock = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/files"))
+        .respond_with(fixture(groqai::fixtures::FILE))
+        .expect(2)
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
+
+    assert!(FileCreateRequest::from_bytes("{}\n", "inputs.json", "batch").is_err());
+    assert!(FileCreateRequest::from_bytes("{}\nnot json\n", "inputs.jsonl", "batch").is_err());
+
+    let content = "{\"custom_id\": \"1\"}\n".repeat(5000);
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let seen = progress.clone();
+    let req = FileCreateRequest::from_bytes(content.clone(), "generated.jsonl", "batch")?;
+    client
+        .files()
+        .upload_progress(move |p| seen.lock().unwrap().push(p.bytes_sent))
+        .create(req)
+        .await?;
+    let reader = std::io::Cursor::new(b"{\"custom_id\": \"2\"}\n".to_vec());
+    let req = FileCreateRequest::from_reader(reader, "read.jsonl", "batch").await?;
+    client.files().create(req).await?;
+
+    let progress = progress.lock().unwrap().clone();
+    assert!(progress.len() >= 2, "expected one update per piece, got {}", progress.len());
+    assert_eq!(progress.last(), Some(&(content.len() as u64)));
+
+    let requests = mock.received_requests().await.unwrap();
+    let body = String::from_utf8_lossy(&requests[0].body);
+    assert!(body.contains("Content-Disposition: form-data; name=\"file\"; filename=\"generated.jsonl\""));
+    assert!(body.contains("Content-Type: application/jsonl"));
+    assert!(body.contains(&content));
+    let body = String::from_utf8_lossy(&requests[1].body);
+    assert!(body.contains("filename=\"read.jsonl\""));
+    assert!(body.contains("{\"custom_id\": \"2\"}"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_create_many() -> Result<(), GroqError> {
+    use groqai::api::files::FileCreateRequest;
+    use wiremock::matchers::body_string_contains;
+
+    let mock = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("filename=\"a.jsonl\""))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+        .up_to_n_times(1)
+        .mount(&mock)
+        .await;
+    Mock::given(method("POST"))
+        .and(body_string_contains("filename=\"b.jsonl\""))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": {"message": "Invalid file", "type": "invalid_request_error"}
+        })))
+        .mount(&mock)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/files"))
+        .respond_with(fixture(groqai::fixtures::FILE))
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
+
+    let requests = ["a.jsonl", "b.jsonl", "c.jsonl"]
+        .iter()
+        .map(|name| FileCreateRequest::from_bytes("{}\n", *name, "batch"))
+        .collect::<Result<Vec<_>, _>>()?;
+    let results = client.files().concurrency(2).create_many(requests).await;
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok());
+    assert!(matches!(results[1], Err(GroqError::Api(ref e)) if e.status == 400));
+    assert!(results[2].is_ok());
+    // The rate-limited upload was retried; the rejected one was not
+    assert_eq!(mock.received_requests().await.unwrap().len(), 4);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_delete_where() -> Result<(), GroqError> {
+    use wiremock::matchers::query_param;
+
+    let file = |id: &str, purpose: &str, created_at: u64| {
+        serde_json::json!({
+            "id": id, "object": "file", "bytes": 10, "created_at": created_at,
+            "filename": format!("{}.jsonl", id), "purpose": purpose
+        })
+    };
+    let mock = MockServer::start().await;
+    // Rate limits while listing and deleting are retried
+    Mock::given(method("GET"))
+        .and(path("/files"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+        .up_to_n_times(1)
+        .mount(&mock)
+        .await;
+    Mock::given(method("DELETE"))
+        .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "0"))
+        .up_to_n_times(1)
+        .mount(&mock)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/files"))
+        .and(query_param("after", "file_2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": "list",
+            "data": [file("file_3", "batch", 50)],
+            "has_more": false
+        })))
+        .mount(&mock)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/files"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": "list",
+            "data": [file("file_1", "batch", 100), file("file_2", "fine-tune", 10)],
+            "has_more": true
+        })))
+        .mount(&mock)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/files/file_3"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "file_3", "object": "file", "deleted": true
+        })))
+        .expect(1)
+        .mount(&mock)
+        .await;
+
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(mock.uri().parse().unwrap())
+        .build()?;
+
+    // Dry run reports matches without deleting
+    let report = client.files().dry_run(true).delete_all("batch").await?;
+    assert_eq!(report.matched.len(), 2);
+    assert!(report.deleted.is_empty());
+    // The purpose was sent as a filter
+    let listed = mock.received_requests().await.unwrap();
+    assert!(listed.iter().all(|r| r.url.query().unwrap_or_default().contains("purpose=batch")));
+
+    let report = client
+        .files()
+        .concurrency(2)
+        .delete_where(|f| f.purpose == FilePurpose::Batch && f.created_at < 60)
+        .await?;
+    assert_eq!(report.matched.len(), 1);
+    assert_eq!(report.deleted, vec!["file_3".to_string()]);
+    assert!(report.failed.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_list_filtered() -> Result<(), GroqError> {
+    use groqai::api::files::FileListQuery;
+    use wiremock::matchers::query_param;
+
+    let mock = MockServer::start().await;
+    // The server ignores the purpose filter; the client applies it anyway
+    Mock::given(method("GET"))
+        .and(path("/files"))
+        .and(query_param("purpose", "batch"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": "list",
+            "data": [
+                {"id": "file_1", "object": "file", "bytes": 1, "created_at": 100, "filename": "a.jsonl", "purpose": "batch"},
+                {"id": "file_2", "object": "file", "bytes": 1, "created_at": 200, "filename": "b.jsonl", "purpose": "batch"},
+                {"id": "file_3", "object": "file", "bytes": 1, "created_at": 300, "filename": "c.jsonl", "purpose": "fine-tune"}
+            ]
+        })))
+        .expect(1)
+        .mount(&mock)
+        .await;
+
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(mock.uri().parse().unwrap())
+        .build()?;
+
+    let ids: Vec<String> = client
+        .files()
+        .list_filtered(FileListQuery::new().purpose("batch").created_after(150))
+        .await?
+        .map(|f| f.id)
+        .collect();
+    assert_eq!(ids, vec!["file_2".to_string()]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_list_pages() -> Result<(), GroqError> {
+    use futures::TryStreamExt;
+    use groqai::api::files::FileListParams;
+    use wiremock::matchers::{query_param, query_param_is_missing};
+
+    let file = |id: &str| {
+        serde_json::json!({"id": id, "object": "file", "bytes": 1, "created_at": 1, "filename": "a.jsonl", "purpose": "batch"})
+    };
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/files"))
+        .and(query_param("purpose", "batch"))
+        .and(query_param("limit", "2"))
+        .and(query_param_is_missing("after"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": "list",
+            "data": [file("file_1"), file("file_2")],
+            "has_more": true
+        })))
+        .expect(2)
+        .mount(&mock)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/files"))
+        .and(query_param("purpose", "batch"))
+        .and(query_param("limit", "2"))
+        .and(query_param("after", "file_2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": "list",
+            "data": [file("file_3")],
+            "has_more": false
+        })))
+        .expect(2)
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
+    let params = FileListParams::new().purpose("batch").limit(2);
+
+    let page = client.files().list_with(params.clone()).await?;
+    assert!(page.has_more);
+    let next = client.files().list_with(params.clone().after("file_2")).await?;
+    assert_eq!(next.data[0].id, "file_3");
+    assert!(!next.has_more);
+
+    let ids: Vec<String> = client.files().list_stream(params).map_ok(|f| f.id).try_collect().await?;
+    assert_eq!(ids, ["file_1", "file_2", "file_3"]);
+    Ok(())
+}
+
+#[cfg(feature = "batches")]
+#[tokio::test]
+async fn test_ids_accept_str_and_string() -> Result<(), GroqError> {
+    use groqai::mock::MockTransport;
+
+    let file: serde_json::Value = serde_json::from_str(groqai::fixtures::FILE)?;
+    let batch: serde_json::Value = serde_json::from_str(groqai::fixtures::BATCH)?;
+    let mock = MockTransport::new()
+        .with_json_response(Ok(file.clone()))
+        .with_json_response(Ok(batch))
+        .with_json_response(Ok(serde_json::json!({"id": "file_1", "object": "file", "deleted": true})));
+    let client = GroqClientBuilder::new("gsk_test_key")?.transport(mock.clone()).build()?;
+
+    let id = file["id"].as_str().unwrap().to_string();
+    client.files().retrieve(id.as_str()).await?;
+    client.batches().cancel(&id).await?;
+    client.files().delete(id.clone()).await?;
+    let paths: Vec<String> = mock.requests().into_iter().map(|r| r.path).collect();
+    assert_eq!(paths, [format!("files/{}", id), format!("batches/{}/cancel", id), format!("files/{}", id)]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_content_download() -> Result<(), GroqError> {
+    use futures_util::stream::StreamExt;
+
+    let output = "{\"custom_id\":\"request-1\"}\n{\"custom_id\":\"request-2\"}\n";
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/files/file_01jh6x76wtemjr74t1fh0faj5t/content"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(output, "application/octet-stream"))
+        .expect(2)
+        .mount(&mock)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/files/file_123/content"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": {"message": "File not found", "type": "invalid_request_error"}
+        })))
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
+
+    let content = client.files().content("file_01jh6x76wtemjr74t1fh0faj5t").await?;
+    assert_eq!(content, output.as_bytes());
+
+    let mut stream = client.files().content_stream("file_01jh6x76wtemjr74t1fh0faj5t").await?;
+    let mut streamed = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        streamed.extend_from_slice(&chunk?);
+    }
+    assert_eq!(streamed, output.as_bytes());
+
+    let result = client.files().content("file_123").await;
+    assert!(matches!(result, Err(GroqError::Api(e)) if e.status.as_u16() == 404));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_create_request_open_and_unchecked() -> Result<(), GroqError> {
+    use groqai::api::files::FileCreateRequest;
+
+    let dir = std::env::temp_dir().join(format!("groqai_open_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let valid = dir.join("valid.jsonl");
+    std::fs::write(&valid, "{\"custom_id\": \"1\"}\n\n{\"custom_id\": \"2\"}\n").unwrap();
+    let invalid = dir.join("invalid.jsonl");
+    std::fs::write(&invalid, "{\"custom_id\": \"1\"}\nnot json\n").unwrap();
+
+    let request = FileCreateRequest::open(&valid, "batch").await?;
+    assert_eq!(request.file, valid);
+    let error = FileCreateRequest::open(&invalid, "batch").await.err().unwrap();
+    assert!(error.to_string().contains("line 2"), "{}", error);
+    assert!(FileCreateRequest::open(dir.join("missing.jsonl"), "batch").await.is_err());
+
+    // Nothing is read or checked
+    let request = FileCreateRequest::unchecked(&invalid, "batch");
+    assert_eq!(request.file, invalid);
+    assert_eq!(request.purpose, FilePurpose::Batch);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    Ok(())
+}
@@ -0,0 +1,198 @@
+#![cfg(feature = "fine-tuning")]
+
+use groqai::api::poll::PollOptions;
+use groqai::{GroqClientBuilder, GroqError};
+use std::time::Duration;
+use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn job(status: &str, error: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "id": "ft_1",
+        "name": "my-model",
+        "base_model": "llama-3.1-8b-instant",
+        "type": "supervised",
+        "input_file_id": "file_1",
+        "created_at": 0,
+        "status": status,
+        "fine_tuned_model": if status == "succeeded" { serde_json::json!("ft:my-model") } else { serde_json::Value::Null },
+        "training_progress": null,
+        "error": error
+    })
+}
+
+async fn mount_job_sequence(mock: &MockServer, statuses: &[(&str, serde_json::Value)]) {
+    for (i, (status, error)) in statuses.iter().enumerate() {
+        let template = ResponseTemplate::new(200).set_body_json(job(status, error.clone()));
+        let m = Mock::given(method("GET")).and(path("/fine_tuning/jobs/ft_1"));
+        if i + 1 < statuses.len() {
+            m.respond_with(template).up_to_n_times(1).mount(mock).await;
+        } else {
+            m.respond_with(template).mount(mock).await;
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_wait_for_completion_reports_events() -> Result<(), GroqError> {
+    let mock = MockServer::start().await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(mock.uri().parse().unwrap())
+        .build()?;
+
+    mount_job_sequence(
+        &mock,
+        &[("running", serde_json::Value::Null), ("succeeded", serde_json::Value::Null)],
+    )
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/fine_tuning/jobs/ft_1/events"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": "list",
+            "data": [
+                {"id": "ev_2", "created_at": 2, "level": "info", "message": "Step 10/100"},
+                {"id": "ev_1", "created_at": 1, "level": "info", "message": "Job started"}
+            ],
+            "has_more": false
+        })))
+        .mount(&mock)
+        .await;
+
+    let mut messages = Vec::new();
+    let job = client
+        .fine_tunings()
+        .on_event(|event| messages.push(event.message.clone()))
+        .wait_for_completion("ft_1", PollOptions::new().interval(Duration::from_millis(10)))
+        .await?;
+
+    assert_eq!(job.fine_tuned_model.as_deref(), Some("ft:my-model"));
+    assert_eq!(messages, vec!["Job started", "Step 10/100"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_wait_for_completion_failure() -> Result<(), GroqError> {
+    let mock = MockServer::start().await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(mock.uri().parse().unwrap())
+        .build()?;
+
+    mount_job_sequence(
+        &mock,
+        &[("failed", serde_json::json!({"message": "invalid training file"}))],
+    )
+    .await;
+
+    let err = client
+        .fine_tunings()
+        .wait_for_completion("ft_1", PollOptions::new())
+        .await
+        .unwrap_err();
+    match err {
+        GroqError::JobFailed { id, status, error } => {
+            assert_eq!(id, "ft_1");
+            assert_eq!(status, "failed");
+            assert_eq!(error.unwrap()["message"], "invalid training file");
+        }
+        other => panic!("expected JobFailed, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_wait_for_completion_timeout() -> Result<(), GroqError> {
+    let mock = MockServer::start().await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(mock.uri().parse().unwrap())
+        .build()?;
+
+    mount_job_sequence(&mock, &[("running", serde_json::Value::Null)]).await;
+
+    let opts = PollOptions::new()
+        .interval(Duration::from_millis(10))
+        .timeout(Duration::from_millis(50));
+    let result = client.fine_tunings().wait_for_completion("ft_1", opts).await;
+    assert!(matches!(result, Err(GroqError::JobTimeout { .. })));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_wait_for_completion_pages_events() -> Result<(), GroqError> {
+    let mock = MockServer::start().await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(mock.uri().parse().unwrap())
+        .build()?;
+
+    mount_job_sequence(
+        &mock,
+        &[
+            ("running", serde_json::Value::Null),
+            ("running", serde_json::Value::Null),
+            ("succeeded", serde_json::Value::Null),
+        ],
+    )
+    .await;
+    let event = |id: &str, created_at: u64| {
+        serde_json::json!({"id": id, "created_at": created_at, "level": "info", "message": id})
+    };
+    // Newest first; the second page is only reachable with `after`
+    Mock::given(method("GET"))
+        .and(path("/fine_tuning/jobs/ft_1/events"))
+        .and(query_param_is_missing("after"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": "list",
+            "data": [event("ev_4", 4), event("ev_3", 3)],
+            "has_more": true
+        })))
+        .mount(&mock)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/fine_tuning/jobs/ft_1/events"))
+        .and(query_param("after", "ev_3"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "object": "list",
+            "data": [event("ev_2", 2), event("ev_1", 1)],
+            "has_more": false
+        })))
+        .expect(1)
+        .mount(&mock)
+        .await;
+
+    let mut messages = Vec::new();
+    client
+        .fine_tunings()
+        .on_event(|event| messages.push(event.message.clone()))
+        .wait_for_completion("ft_1", PollOptions::new().interval(Duration::from_millis(10)))
+        .await?;
+    assert_eq!(messages, vec!["ev_1", "ev_2", "ev_3", "ev_4"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_wait_for_completion_retries_and_stops_at_timeout() -> Result<(), GroqError> {
+    let mock = MockServer::start().await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(mock.uri().parse().unwrap())
+        .build()?;
+
+    // A temporary outage is retried instead of ending the wait
+    Mock::given(method("GET"))
+        .and(path("/fine_tuning/jobs/ft_1"))
+        .respond_with(ResponseTemplate::new(503).insert_header("retry-after", "0"))
+        .up_to_n_times(1)
+        .mount(&mock)
+        .await;
+    mount_job_sequence(&mock, &[("running", serde_json::Value::Null)]).await;
+
+    // The sleep is cut short by the timeout instead of running a full interval
+    let opts = PollOptions::new()
+        .interval(Duration::from_secs(60))
+        .timeout(Duration::from_millis(50));
+    let started = std::time::Instant::now();
+    let result = client.fine_tunings().wait_for_completion("ft_1", opts).await;
+    assert!(matches!(result, Err(GroqError::JobTimeout { .. })));
+    assert!(started.elapsed() < Duration::from_secs(10));
+    // The outage, the first check and a last check at the deadline
+    assert_eq!(mock.received_requests().await.unwrap().len(), 3);
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use groqai::import::{from_chatml, from_openai_json};
+use groqai::types::{MessageContent, MessagePart};
+use groqai::{GroqError, Role};
+
+#[test]
+fn test_import_openai_json() -> Result<(), GroqError> {
+    let messages = from_openai_json(
+        r#"[
+            {"role": "system", "content": "You are a weather bot."},
+            {"role": "user", "content": [
+                {"type": "text", "text": "Weather where this photo was taken?"},
+                {"type": "image_url", "image_url": {"url": "https://example.com/paris.jpg", "detail": "low"}}
+            ]},
+            {"role": "assistant", "content": null, "tool_calls": [
+                {"id": "call_abc", "type": "function", "function": {"name": "get_weather", "arguments": "{\"city\":\"Paris\"}"}}
+            ]},
+            {"role": "tool", "tool_call_id": "call_abc", "content": "18°C"},
+            {"role": "assistant", "function_call": {"name": "get_time", "arguments": {"tz": "CET"}}},
+            {"role": "function", "name": "get_time", "content": "14:00"},
+            {"role": "assistant", "content": [{"type": "text", "text": "18°C at 14:00."}]}
+        ]"#,
+    )?;
+    assert_eq!(messages.len(), 7);
+    assert_eq!(messages[0].role, Role::System);
+
+    let MessageContent::Parts(parts) = &messages[1].content else {
+        panic!("expected parts, got {:?}", messages[1].content);
+    };
+    assert_eq!(parts[0], MessagePart::Text { text: "Weather where this photo was taken?".to_string() });
+    assert_eq!(messages[1].content.images()[0].detail.as_deref(), Some("low"));
+
+    let calls = messages[2].tool_calls.as_ref().unwrap();
+    assert_eq!(calls[0].id, "call_abc");
+    assert_eq!(calls[0].function.arguments, r#"{"city":"Paris"}"#);
+    assert_eq!(messages[2].content.to_string(), "");
+    assert_eq!(messages[3].tool_call_id.as_deref(), Some("call_abc"));
+
+    // Legacy function calls get an id that the function result refers to
+    let legacy = &messages[4].tool_calls.as_ref().unwrap()[0];
+    assert_eq!(legacy.function.arguments, r#"{"tz":"CET"}"#);
+    assert_eq!(messages[5].role, Role::Tool);
+    assert_eq!(messages[5].tool_call_id.as_deref(), Some(legacy.id.as_str()));
+
+    assert!(matches!(&messages[6].content, MessageContent::Text(text) if text == "18°C at 14:00."));
+
+    // A request body or dataset line works as well
+    let messages = from_openai_json(r#"{"model": "gpt-4o", "messages": [{"role": "developer", "content": "Be brief."}]}"#)?;
+    assert_eq!(messages[0].role, Role::System);
+
+    let error = from_openai_json(r#"[{"role": "user", "content": "Hi"}, {"role": "narrator", "content": "..."}]"#).unwrap_err();
+    assert!(error.to_string().contains("Invalid OpenAI message 1: unknown role `narrator`"), "{}", error);
+    let error = from_openai_json(r#"[{"role": "user", "content": [{"type": "input_audio"}]}]"#).unwrap_err();
+    assert!(error.to_string().contains("unsupported content part `input_audio`"), "{}", error);
+    Ok(())
+}
+
+#[test]
+fn test_import_chatml() -> Result<(), GroqError> {
+    let text = "<|im_start|>system\nYou are a weather bot.<|im_end|>\n\
+                <|im_start|>user name=alice\nWeather in Paris?\nThanks!<|im_end|>\n\
+                <|im_start|>assistant\nLet me check.\n<tool_call>\n{\"name\": \"get_weather\", \"arguments\": {\"city\": \"Paris\"}}\n</tool_call><|im_end|>\n\
+                <|im_start|>tool\n<tool_response>\n18°C\n</tool_response><|im_end|>\n\
+                <|im_start|>assistant\nIt is 18°C.<|im_end|>\n\
+                <|im_start|>assistant\n";
+    let messages = from_chatml(text)?;
+    assert_eq!(messages.len(), 5);
+    assert_eq!(messages[0].content.to_string(), "You are a weather bot.");
+    assert_eq!(messages[1].role, Role::User);
+    assert_eq!(messages[1].content.to_string(), "Weather in Paris?\nThanks!");
+
+    assert_eq!(messages[2].content.to_string(), "Let me check.");
+    let call = &messages[2].tool_calls.as_ref().unwrap()[0];
+    assert_eq!(call.function.name, "get_weather");
+    assert_eq!(call.function.arguments, r#"{"city":"Paris"}"#);
+    assert_eq!(messages[3].role, Role::Tool);
+    assert_eq!(messages[3].content.to_string(), "18°C");
+    assert_eq!(messages[3].tool_call_id.as_deref(), Some(call.id.as_str()));
+    assert_eq!(messages[4].content.to_string(), "It is 18°C.");
+
+    let error = from_chatml("Hello <|im_start|>user\nHi<|im_end|>").unwrap_err();
+    assert!(error.to_string().contains("Invalid ChatML message 0: text outside of a message"), "{}", error);
+    let error = from_chatml("<|im_start|>user\nHi\n<|im_start|>assistant\nHello<|im_end|>").unwrap_err();
+    assert!(error.to_string().contains("missing <|im_end|>"), "{}", error);
+    Ok(())
+}
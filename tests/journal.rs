@@ -0,0 +1,204 @@
+#[cfg(feature = "streaming")]
+use futures::StreamExt;
+#[cfg(feature = "streaming")]
+use groqai::journal::Journal;
+use groqai::journal::{FileJournal, JournalEntry};
+#[cfg(feature = "streaming")]
+use groqai::mock::MockTransport;
+#[cfg(all(feature = "streaming", feature = "redact"))]
+use groqai::redact::Redactor;
+#[cfg(feature = "streaming")]
+use groqai::types::ChatCompletionChunk;
+#[cfg(feature = "streaming")]
+use groqai::{ChatMessage, GroqClientBuilder, GroqError, Role};
+use serde_json::json;
+
+mod common;
+
+use common::reply;
+
+#[cfg(feature = "streaming")]
+#[cfg(feature = "streaming")]
+fn chunk(text: &str) -> ChatCompletionChunk {
+    serde_json::from_value(json!({
+        "id": "chatcmpl-2",
+        "object": "chat.completion.chunk",
+        "created": 0,
+        "model": "llama-3.1-8b-instant",
+        "system_fingerprint": null,
+        "choices": [{"index": 0, "delta": {"content": text}, "finish_reason": null}]
+    }))
+    .unwrap()
+}
+
+fn read_entries(path: &std::path::Path) -> Vec<JournalEntry> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[cfg(feature = "streaming")]
+#[tokio::test]
+async fn test_journal_records_redacted_calls() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::temp_dir().join(format!("groqai-journal-{}", std::process::id()));
+    let path = dir.join("journal.jsonl");
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply("Sure, I emailed jane@example.com.")))
+        .with_stream(vec![chunk("Hel"), chunk("lo")])
+        .with_json_response(Err(GroqError::InvalidMessage("model not found".to_string())));
+    let journal = Journal::new(FileJournal::open(&path)?).redact_field("seed");
+    #[cfg(feature = "redact")]
+    let journal = journal.redactor(Redactor::new());
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock)
+        .journal(journal)
+        .build()?;
+
+    client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Email jane@example.com"))
+        .seed(42)
+        .send()
+        .await?;
+    let mut stream = client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .header("x-request-id", "trace-7")
+        .stream(true)
+        .send_stream()
+        .await?;
+    while stream.next().await.is_some() {}
+    drop(stream);
+    assert!(client.models().retrieve("missing".to_string()).await.is_err());
+
+    let entries = read_entries(&path);
+    std::fs::remove_dir_all(&dir)?;
+    assert_eq!(entries.len(), 3);
+
+    let chat = &entries[0];
+    assert_eq!((chat.method.as_str(), chat.endpoint.as_str()), ("POST", "chat/completions"));
+    assert_eq!(chat.request_id.as_deref(), Some("req_01jbd6g2qdfw2adyrt2az8hz4w"));
+    let request = chat.request.as_ref().unwrap();
+    assert_eq!(request["seed"], "[REDACTED]");
+    #[cfg(feature = "redact")]
+    {
+        assert_eq!(request["messages"][0]["content"], "Email [EMAIL]");
+        let response = chat.response.as_ref().unwrap();
+        assert_eq!(response["choices"][0]["message"]["content"], "Sure, I emailed [EMAIL].");
+    }
+
+    let stream = &entries[1];
+    assert_eq!(stream.request_id.as_deref(), Some("trace-7"));
+    let response = stream.response.as_ref().unwrap();
+    assert_eq!(response["content"], "Hello");
+    assert_eq!(response["chunks"], 2);
+
+    let failed = &entries[2];
+    assert_eq!((failed.method.as_str(), failed.endpoint.as_str()), ("GET", "models/missing"));
+    assert!(failed.response.is_none());
+    assert!(failed.error.as_deref().unwrap().contains("model not found"));
+    Ok(())
+}
+
+#[test]
+fn test_file_journal_rotation() -> Result<(), Box<dyn std::error::Error>> {
+    use groqai::journal::JournalSink;
+
+    let dir = std::env::temp_dir().join(format!("groqai-journal-rotation-{}", std::process::id()));
+    let path = dir.join("journal.jsonl");
+    let sink = FileJournal::open(&path)?.max_file_bytes(300).max_files(2);
+    for i in 0..8 {
+        sink.record(&JournalEntry {
+            timestamp_ms: i,
+            request_id: Some(format!("req_{}", i)),
+            method: "GET".to_string(),
+            endpoint: "models".to_string(),
+            request: None,
+            response: Some(json!({"object": "list", "data": []})),
+            error: None,
+            duration_ms: 5,
+        })?;
+    }
+
+    let current = read_entries(&path);
+    let rotated: Vec<JournalEntry> = read_entries(&dir.join("journal.jsonl.1"));
+    let oldest: Vec<JournalEntry> = read_entries(&dir.join("journal.jsonl.2"));
+    let has_third = dir.join("journal.jsonl.3").exists();
+    std::fs::remove_dir_all(&dir)?;
+    assert!(!has_third);
+    assert!(std::iter::once(&current).chain([&rotated, &oldest]).all(|f| !f.is_empty()));
+    // Files hold consecutive entries, newest in the current file
+    assert_eq!(current.last().unwrap().timestamp_ms, 7);
+    assert_eq!(rotated.last().unwrap().timestamp_ms + 1, current[0].timestamp_ms);
+    assert_eq!(oldest.last().unwrap().timestamp_ms + 1, rotated[0].timestamp_ms);
+    assert!(oldest[0].timestamp_ms > 0);
+    Ok(())
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn test_sqlite_journal() -> Result<(), Box<dyn std::error::Error>> {
+    use groqai::journal::sqlite::SqliteJournal;
+    use groqai::journal::JournalSink;
+
+    let sink = SqliteJournal::open_in_memory()?.max_rows(2);
+    for i in 0..3 {
+        sink.record(&JournalEntry {
+            timestamp_ms: i,
+            request_id: None,
+            method: "POST".to_string(),
+            endpoint: "chat/completions".to_string(),
+            request: Some(json!({"model": "llama-3.1-8b-instant"})),
+            response: None,
+            error: Some("timeout".to_string()),
+            duration_ms: 30_000,
+        })?;
+    }
+    let entries = sink.entries()?;
+    assert_eq!(entries.iter().map(|e| e.timestamp_ms).collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(entries[0].request, Some(json!({"model": "llama-3.1-8b-instant"})));
+    assert_eq!(entries[0].error.as_deref(), Some("timeout"));
+
+    // Large caps prune in batches of 1% of the cap
+    let sink = SqliteJournal::open_in_memory()?.max_rows(200);
+    for i in 0..202 {
+        sink.record(&JournalEntry {
+            timestamp_ms: i,
+            request_id: None,
+            method: "GET".to_string(),
+            endpoint: "models".to_string(),
+            request: None,
+            response: None,
+            error: None,
+            duration_ms: 5,
+        })?;
+    }
+    let entries = sink.entries()?;
+    assert_eq!(entries.len(), 201);
+    assert_eq!(entries[0].timestamp_ms, 1);
+    Ok(())
+}
+
+#[cfg(feature = "streaming")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_journal_writes_on_multi_thread_runtime() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::temp_dir().join(format!("groqai-journal-mt-{}", std::process::id()));
+    let path = dir.join("journal.jsonl");
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(MockTransport::new().with_chat_response(Ok(reply("Hi"))))
+        .journal(Journal::new(FileJournal::open(&path)?))
+        .build()?;
+
+    client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .send()
+        .await?;
+    let entries = read_entries(&path);
+    std::fs::remove_dir_all(&dir)?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].request_id.as_deref(), Some("req_01jbd6g2qdfw2adyrt2az8hz4w"));
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use groqai::language::{detect_language, LanguageEnforcer};
+use groqai::mock::MockTransport;
+use groqai::{ChatMessage, GroqClientBuilder, GroqError, Role};
+
+mod common;
+
+use common::reply;
+
+#[test]
+fn test_detect_language() {
+    assert_eq!(detect_language("The capital of France is Paris, and it is big."), Some("en"));
+    assert_eq!(detect_language("Die Hauptstadt von Frankreich ist Paris."), Some("de"));
+    assert_eq!(detect_language("La capitale de la France est Paris et elle est belle."), Some("fr"));
+    assert_eq!(detect_language("La capital de Francia es París y es muy bonita para los turistas."), Some("es"));
+    assert_eq!(detect_language("Столица Франции — Париж."), Some("ru"));
+    assert_eq!(detect_language("フランスの首都はパリです。"), Some("ja"));
+    assert_eq!(detect_language("프랑스의 수도는 파리입니다."), Some("ko"));
+    assert_eq!(detect_language("Paris!"), None);
+
+    let enforcer = LanguageEnforcer::new("de-DE");
+    assert!(enforcer.matches("<think>The user wants German.</think>Die Antwort ist nicht einfach."));
+    assert!(!enforcer.matches("The answer is not that simple, and it is long."));
+    assert!(enforcer.matches("42"));
+}
+
+#[tokio::test]
+async fn test_force_language_retries_wrong_language() -> Result<(), GroqError> {
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply("The capital of France is Paris.")))
+        .with_chat_response(Ok(reply("Die Hauptstadt von Frankreich ist Paris.")))
+        .with_chat_response(Ok(reply("Die Hauptstadt ist Paris.")));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    let response = client
+        .chat("llama-3.3-70b-versatile")
+        .language_enforcer(LanguageEnforcer::new("de").validate(true))
+        .message(ChatMessage::new_text(Role::User, "What is the capital of France?"))
+        .send()
+        .await?;
+    assert_eq!(response.choices[0].message.content.to_string(), "Die Hauptstadt von Frankreich ist Paris.");
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    let first = requests[0].body["messages"][0]["content"].as_str().unwrap();
+    assert!(first.starts_with("Always respond in German (de)"));
+    let second = requests[1].body["messages"][0]["content"].as_str().unwrap();
+    assert!(second.starts_with("Your previous reply was not in German (de)"));
+    assert_eq!(requests[1].body["messages"].as_array().unwrap().len(), 2);
+
+    // Without validation the instruction is appended to an existing system message
+    client
+        .chat("llama-3.3-70b-versatile")
+        .force_language("de")
+        .message(ChatMessage::new_text(Role::System, "Be brief."))
+        .message(ChatMessage::new_text(Role::User, "Hi"))
+        .send()
+        .await?;
+    let requests = mock.requests();
+    let system = requests[2].body["messages"][0]["content"].as_str().unwrap();
+    assert_eq!(system, "Be brief.\n\nAlways respond in German (de), regardless of the language of the user's messages.");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_shared_script_targets_are_accepted() -> Result<(), GroqError> {
+    let ukrainian = "Столиця Франції — Париж, і це гарне місто.";
+    let persian = "پایتخت فرانسه پاریس است.";
+    assert!(LanguageEnforcer::new("uk").matches(ukrainian));
+    assert!(LanguageEnforcer::new("fa").matches(persian));
+    assert!(!LanguageEnforcer::new("uk").matches(persian));
+    assert!(!LanguageEnforcer::new("fa").matches("The capital of France is Paris, and it is big."));
+
+    let mock = MockTransport::new().with_chat_response(Ok(reply(ukrainian))).with_chat_response(Ok(reply(persian)));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+    for (language, expected) in [("uk", ukrainian), ("fa", persian)] {
+        let response = client
+            .chat("llama-3.3-70b-versatile")
+            .language_enforcer(LanguageEnforcer::new(language).validate(true))
+            .message(ChatMessage::new_text(Role::User, "What is the capital of France?"))
+            .send()
+            .await?;
+        assert_eq!(response.choices[0].message.content.to_string(), expected);
+    }
+    assert_eq!(mock.requests().len(), 2);
+    assert!(mock.requests()[0].body["messages"][0]["content"]
+        .as_str()
+        .unwrap()
+        .starts_with("Always respond in Ukrainian (uk)"));
+    Ok(())
+}
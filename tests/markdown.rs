@@ -0,0 +1,67 @@
+#![cfg(feature = "streaming")]
+
+use futures::StreamExt;
+use groqai::markdown::{MarkdownEvent, MarkdownTracker};
+use groqai::stream::ChatStreamExt;
+use groqai::types::ChatCompletionChunk;
+
+fn chunk(text: &str) -> ChatCompletionChunk {
+    serde_json::from_value(serde_json::json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion.chunk",
+        "created": 0,
+        "model": "llama-3.1-8b-instant",
+        "system_fingerprint": null,
+        "choices": [{"index": 0, "delta": {"content": text}, "finish_reason": null}]
+    }))
+    .unwrap()
+}
+
+fn structure(events: Vec<MarkdownEvent>) -> Vec<MarkdownEvent> {
+    events.into_iter().filter(|e| !matches!(e, MarkdownEvent::Text(_))).collect()
+}
+
+#[test]
+fn test_tracker_structure_across_deltas() {
+    let mut tracker = MarkdownTracker::new();
+    let mut events = Vec::new();
+    for delta in ["## Ste", "ps\n\n1. Instal", "l\n   - nested\n---\n~~", "~~ sh\nls ```\n", "~~~~\n", "* last"] {
+        events.extend(tracker.push(delta));
+    }
+    events.extend(tracker.finish());
+    assert_eq!(
+        structure(events),
+        [
+            MarkdownEvent::Heading { level: 2, text: "Steps".to_string() },
+            MarkdownEvent::ListItem { ordered: true, indent: 0, text: "Install".to_string() },
+            MarkdownEvent::ListItem { ordered: false, indent: 3, text: "nested".to_string() },
+            MarkdownEvent::CodeBlockStart { language: Some("sh".to_string()) },
+            MarkdownEvent::CodeBlockEnd { language: Some("sh".to_string()), code: "ls ```\n".to_string() },
+            MarkdownEvent::ListItem { ordered: false, indent: 0, text: "last".to_string() },
+        ]
+    );
+    assert!(!tracker.in_code_block());
+    assert_eq!(tracker.snapshot(), tracker.text());
+}
+
+#[tokio::test]
+async fn test_markdown_stream_closes_open_block() {
+    let source = futures::stream::iter(vec![Ok(chunk("Run:\n```")), Ok(chunk("\necho hi\n")), Ok(chunk("echo bye"))]);
+    let events: Vec<MarkdownEvent> = source.markdown().map(|e| e.unwrap()).collect().await;
+
+    let text: String = events
+        .iter()
+        .filter_map(|e| match e {
+            MarkdownEvent::Text(text) => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(text, "Run:\n```\necho hi\necho bye");
+    // The start follows the fence line's text; the end comes when the stream ends
+    assert_eq!(events[2], MarkdownEvent::Text("\n".to_string()));
+    assert_eq!(events[3], MarkdownEvent::CodeBlockStart { language: None });
+    assert_eq!(
+        events.last(),
+        Some(&MarkdownEvent::CodeBlockEnd { language: None, code: "echo hi\necho bye\n".to_string() })
+    );
+}
@@ -1,36 +1,66 @@
-use groqai::client::GroqClientBuilder;
+use groqai::client::{GroqClient, GroqClientBuilder};
 use groqai::error::GroqError;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mock_client(mock: &MockServer) -> GroqClient {
+    GroqClientBuilder::new("gsk_test_key".to_string())
+        .unwrap()
+        .base_url(mock.uri().parse().unwrap())
+        .build()
+        .unwrap()
+}
 
 #[tokio::test]
 async fn test_models_list_success() -> Result<(), GroqError> {
-    let client = GroqClientBuilder::new("gsk_test_key".to_string())
-        .unwrap()
-        .build()?;
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(groqai::fixtures::MODEL_LIST, "application/json"))
+        .expect(1)
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
 
-    let result = client.models().list().await;
-    assert!(result.is_err());
+    let models = client.models().list().await?;
+    assert_eq!(models.data.len(), 1);
+    assert_eq!(models.data[0].id, "llama-3.1-8b-instant");
+    assert_eq!(models.data[0].context_window, 131072);
     Ok(())
 }
 
 #[tokio::test]
 async fn test_model_retrieve_success() -> Result<(), GroqError> {
-    let client = GroqClientBuilder::new("gsk_test_key".to_string())
-        .unwrap()
-        .build()?;
+    let model: serde_json::Value = serde_json::from_str(groqai::fixtures::MODEL_LIST)?;
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/models/llama-3.1-8b-instant"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&model["data"][0]))
+        .expect(1)
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
 
-    let result = client.models().retrieve("llama-3.1-70b-versatile".to_string()).await;
-    assert!(result.is_err());
+    let model = client.models().retrieve("llama-3.1-8b-instant").await?;
+    assert_eq!(model.id, "llama-3.1-8b-instant");
+    assert_eq!(model.owned_by, "Meta");
     Ok(())
 }
 
 #[tokio::test]
 async fn test_model_retrieve_not_found() -> Result<(), GroqError> {
-    let client = GroqClientBuilder::new("gsk_test_key".to_string())
-        .unwrap()
-        .build()?;
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/models/invalid_model"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": {"message": "The model `invalid_model` does not exist", "type": "invalid_request_error"}
+        })))
+        .mount(&mock)
+        .await;
+    let client = mock_client(&mock).await;
 
     let result = client.models().retrieve("invalid_model".to_string()).await;
-    assert!(result.is_err());
+    assert!(matches!(result, Err(GroqError::Api(e)) if e.status.as_u16() == 404));
     Ok(())
 }
 
@@ -44,4 +74,24 @@ async fn test_models_methods_exist() -> Result<(), GroqError> {
     let _builder = client.models();
     
     Ok(())
-}
\ No newline at end of file
+}
+#[test]
+fn test_known_model_ids() {
+    use groqai::KnownModel;
+
+    let model: KnownModel = "llama-3.1-8b-instant".parse().unwrap();
+    assert_eq!(model, KnownModel::Llama3_1_8bInstant);
+    assert_eq!(KnownModel::try_from("openai/gpt-oss-20b").unwrap(), KnownModel::Other("openai/gpt-oss-20b".to_string()));
+    assert!("  ".parse::<KnownModel>().is_err());
+
+    // Serialized as the raw id, not the variant name
+    assert_eq!(serde_json::to_value(KnownModel::Gemma2_9bIt).unwrap(), serde_json::json!("gemma2-9b-it"));
+    let config: Vec<KnownModel> = serde_json::from_str(r#"["mixtral-8x7b-32768", "qwen/qwen3-32b"]"#).unwrap();
+    assert_eq!(config, [KnownModel::Mixtral8x7b32768, KnownModel::Other("qwen/qwen3-32b".to_string())]);
+    assert!(serde_json::from_str::<KnownModel>(r#""""#).is_err());
+
+    assert!(KnownModel::Llama3_1_70bVersatile.is_deprecated());
+    assert_eq!(KnownModel::Llama3_1_70bVersatile.replacement(), Some("llama-3.3-70b-versatile"));
+    assert_eq!(KnownModel::Gemma2_9bIt.migrate(), KnownModel::Llama3_1_8bInstant);
+    assert_eq!(config[1].migrate(), config[1]);
+}
@@ -0,0 +1,162 @@
+#![cfg(feature = "streaming")]
+
+use futures::StreamExt;
+use groqai::error::GroqError;
+use groqai::partial_json::{JsonEvent, PartialJsonParser};
+use groqai::stream::ChatStreamExt;
+use groqai::types::ChatCompletionChunk;
+use serde::Deserialize;
+use serde_json::json;
+
+const DOCUMENT: &str = r#"{"title": "Café 😀", "tags": ["a/b", 2, true, null], "meta": {"draft": false, "score": -1.5e2}, "empty": {}}"#;
+
+fn chunk(text: &str) -> ChatCompletionChunk {
+    serde_json::from_value(json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion.chunk",
+        "created": 0,
+        "model": "llama-3.1-8b-instant",
+        "system_fingerprint": null,
+        "choices": [{"index": 0, "delta": {"content": text}, "finish_reason": null}]
+    }))
+    .unwrap()
+}
+
+fn parse_in_pieces(text: &str, size: usize) -> Result<(Vec<JsonEvent>, PartialJsonParser), GroqError> {
+    let mut parser = PartialJsonParser::new();
+    let chars: Vec<char> = text.chars().collect();
+    let mut events = Vec::new();
+    for piece in chars.chunks(size) {
+        events.extend(parser.push(&piece.iter().collect::<String>())?);
+    }
+    events.extend(parser.finish()?);
+    Ok((events, parser))
+}
+
+#[test]
+fn test_events_do_not_depend_on_chunking() -> Result<(), GroqError> {
+    let (events, parser) = parse_in_pieces(DOCUMENT, DOCUMENT.len())?;
+    let expected: serde_json::Value = serde_json::from_str(DOCUMENT).unwrap();
+    assert_eq!(parser.value(), Some(&expected));
+    for size in [1, 2, 3, 7] {
+        assert_eq!(parse_in_pieces(DOCUMENT, size)?.0, events);
+    }
+
+    assert_eq!(
+        events[..3],
+        [
+            JsonEvent::KeyStarted { path: "/title".to_string() },
+            JsonEvent::ValueCompleted {
+                path: "/title".to_string(),
+                value: json!("Café 😀"),
+            },
+            JsonEvent::KeyStarted { path: "/tags".to_string() },
+        ]
+    );
+    assert!(events.contains(&JsonEvent::ArrayItemCompleted {
+        path: "/tags".to_string(),
+        index: 3,
+        value: json!(null),
+    }));
+    assert!(events.contains(&JsonEvent::ValueCompleted {
+        path: "/meta/score".to_string(),
+        value: json!(-150.0),
+    }));
+    assert_eq!(
+        events.last(),
+        Some(&JsonEvent::ValueCompleted {
+            path: String::new(),
+            value: expected,
+        })
+    );
+
+    let (events, _) = parse_in_pieces(r#"{"a/b~c": [[1]]}"#, 1)?;
+    assert!(events.contains(&JsonEvent::ArrayItemCompleted {
+        path: "/a~1b~0c/0".to_string(),
+        index: 0,
+        value: json!(1),
+    }));
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_closes_open_values() -> Result<(), GroqError> {
+    let mut parser = PartialJsonParser::new();
+    assert_eq!(parser.snapshot(), None);
+    parser.push(r#"  {"items": [{"name": "Wid"#)?;
+    assert_eq!(parser.snapshot(), Some(json!({"items": [{"name": "Wid"}]})));
+    parser.push(r#"get", "count": 12"#)?;
+    assert_eq!(parser.snapshot(), Some(json!({"items": [{"name": "Widget", "count": 12}]})));
+    parser.push(r#"}], "done": tr"#)?;
+    assert_eq!(parser.snapshot(), Some(json!({"items": [{"name": "Widget", "count": 12}]})));
+    parser.push(r#"ue, "no"#)?;
+    assert_eq!(parser.snapshot(), Some(json!({"items": [{"name": "Widget", "count": 12}], "done": true})));
+    assert!(!parser.is_complete());
+    Ok(())
+}
+
+#[test]
+fn test_invalid_json_is_reported() {
+    let mut parser = PartialJsonParser::new();
+    let error = parser.push(r#"{"a": 1,, "b": 2}"#).unwrap_err();
+    assert!(error.to_string().contains("character 8"), "{}", error);
+    assert!(parser.push("}").is_err());
+
+    for text in [r#"{"a": tru}"#, r#"{"a": 1} {"#, r#"["\x"]"#, r#"{"a" 1}"#, "[1,]"] {
+        assert!(PartialJsonParser::new().push(text).is_err(), "{}", text);
+    }
+
+    let mut parser = PartialJsonParser::new();
+    parser.push(r#"{"a": [1, 2"#).unwrap();
+    assert!(parser.finish().is_err());
+
+    // A number at the end of the document completes when it ends
+    let mut parser = PartialJsonParser::new();
+    assert!(parser.push("42").unwrap().is_empty());
+    assert_eq!(parser.finish().unwrap().len(), 1);
+    assert_eq!(parser.value(), Some(&json!(42)));
+}
+
+#[derive(Deserialize, Debug, Default, PartialEq)]
+struct Summary {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    points: Vec<String>,
+}
+
+#[tokio::test]
+async fn test_json_stream_adapters() {
+    let pieces = [r#"{"ti"#, r#"tle": "Q3"#, r#"", "points": ["Revenue"#, r#" up", "Costs down"]"#, "}"];
+    let source = || futures::stream::iter(pieces.map(|p| Ok::<_, GroqError>(chunk(p))));
+
+    let summaries: Vec<Summary> = source().json_partial().map(|s| s.unwrap()).collect().await;
+    assert_eq!(summaries.first(), Some(&Summary::default()));
+    assert_eq!(
+        summaries.last(),
+        Some(&Summary {
+            title: "Q3".to_string(),
+            points: vec!["Revenue up".to_string(), "Costs down".to_string()],
+        })
+    );
+    // The closing brace changes nothing, so no summary is repeated
+    assert_eq!(summaries.len(), 4);
+
+    let events: Vec<_> = source().json_events().collect().await;
+    let items: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            Ok(JsonEvent::ArrayItemCompleted { value, .. }) => Some(value.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(items, [json!("Revenue up"), json!("Costs down")]);
+
+    let truncated = futures::stream::iter([Ok::<_, GroqError>(chunk(r#"{"title": "Q"#))]);
+    let events: Vec<_> = truncated.json_events().collect().await;
+    assert!(matches!(events.last(), Some(Err(GroqError::InvalidMessage(_)))));
+    let truncated = futures::stream::iter([Ok::<_, GroqError>(chunk(r#"{"title": "Q"#))]);
+    let summaries: Vec<Result<Summary, _>> = truncated.json_partial().collect().await;
+    assert_eq!(summaries.len(), 2);
+    assert!(summaries[1].is_err());
+}
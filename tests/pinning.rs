@@ -0,0 +1,129 @@
+#![cfg(feature = "cert-pinning")]
+
+use std::sync::Arc;
+
+use groqai::pinning::{spki_sha256, CertificatePins};
+use groqai::{ChatMessage, GroqClientBuilder, GroqError, Role};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+
+const CA: &[u8] = include_bytes!("fixtures/tls/ca.pem");
+const SERVER_CERT: &[u8] = include_bytes!("fixtures/tls/server.pem");
+const SERVER_KEY: &[u8] = include_bytes!("fixtures/tls/server.key");
+
+/// Serves a chat completion over TLS with the test server certificate
+async fn tls_server() -> String {
+    let chain = CertificateDer::pem_slice_iter(SERVER_CERT).collect::<Result<Vec<_>, _>>().unwrap();
+    let key = PrivateKeyDer::from_pem_slice(SERVER_KEY).unwrap();
+    let provider = Arc::new(tokio_rustls::rustls::crypto::ring::default_provider());
+    let config = ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_no_client_auth()
+        .with_single_cert(chain, key)
+        .unwrap();
+    let acceptor = TlsAcceptor::from(Arc::new(config));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                let Ok(mut stream) = acceptor.accept(socket).await else {
+                    return;
+                };
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                // Read the headers and the body announced by content-length
+                loop {
+                    let Ok(n @ 1..) = stream.read(&mut buf).await else {
+                        return;
+                    };
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request).to_ascii_lowercase();
+                    if let Some(end) = text.find("\r\n\r\n") {
+                        let length = text
+                            .lines()
+                            .find_map(|line| line.strip_prefix("content-length:"))
+                            .and_then(|value| value.trim().parse::<usize>().ok())
+                            .unwrap_or(0);
+                        if request.len() >= end + 4 + length {
+                            break;
+                        }
+                    }
+                }
+                let body = groqai::fixtures::CHAT_COMPLETION_RESPONSE;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            });
+        }
+    });
+    format!("https://localhost:{}/", port)
+}
+
+fn der(pem: &[u8]) -> Vec<u8> {
+    CertificateDer::from_pem_slice(pem).unwrap().to_vec()
+}
+
+async fn ask(pins: CertificatePins, url: &str) -> Result<(), GroqError> {
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(url.parse().unwrap())
+        .pin_certificates(pins)
+        .build()?;
+    client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hi"))
+        .send()
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pinned_keys_are_required() -> Result<(), GroqError> {
+    let url = tls_server().await;
+    let trusted = || CertificatePins::new().add_root_pem(CA).unwrap().custom_roots_only(true);
+
+    // Pins on the server's key or on its CA's key are accepted
+    ask(trusted().pin(&spki_sha256(&der(SERVER_CERT))?)?, &url).await?;
+    ask(trusted().pin(&spki_sha256(&der(CA))?)?, &url).await?;
+
+    // A chain that verifies but carries no pinned key is rejected
+    let other = "Vjs8r4z+80wjNcr1YKepWQboSIRi63WsWXhIMN+eWys=";
+    assert!(ask(trusted().pin(other)?, &url).await.is_err());
+    // A pinned key does not help a chain that does not verify
+    let untrusted = CertificatePins::new()
+        .add_root_pem(include_bytes!("fixtures/private-ca.pem"))?
+        .custom_roots_only(true)
+        .pin(&spki_sha256(&der(SERVER_CERT))?)?;
+    assert!(ask(untrusted, &url).await.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_certificate_pins_are_validated() -> Result<(), GroqError> {
+    assert!(matches!(CertificatePins::new().pin("not base64!"), Err(GroqError::InvalidMessage(_))));
+    assert!(matches!(CertificatePins::new().pin("c2hvcnQ="), Err(GroqError::InvalidMessage(_))));
+    assert!(matches!(CertificatePins::new().add_root_pem(b"no certificate"), Err(GroqError::InvalidMessage(_))));
+
+    // Pins without any pin, or combined with the builder's roots, are rejected
+    let result = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .pin_certificates(CertificatePins::new())
+        .build();
+    assert!(matches!(result, Err(GroqError::InvalidMessage(_))));
+    let pins = CertificatePins::new().pin(&spki_sha256(&der(CA))?)?;
+    let result = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .add_root_certificate(reqwest::Certificate::from_pem(CA).unwrap())
+        .pin_certificates(pins)
+        .build();
+    assert!(matches!(result, Err(GroqError::InvalidMessage(_))));
+    Ok(())
+}
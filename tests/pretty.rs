@@ -0,0 +1,55 @@
+use groqai::pretty::{table, wrap};
+use groqai::types::{ChatCompletionResponse, ModelList};
+
+#[test]
+fn test_wrap_keeps_paragraphs() {
+    let lines = wrap("one two three four\n\nfive", 9);
+    assert_eq!(lines, vec!["one two", "three", "four", "", "five"]);
+}
+
+#[test]
+fn test_table_alignment() {
+    let rendered = table(
+        &["ID", "STATUS"],
+        &[vec!["batch_1".to_string(), "completed".to_string()]],
+    );
+    assert_eq!(rendered, "ID       STATUS\n-------  ---------\nbatch_1  completed");
+}
+
+#[test]
+fn test_chat_response_pretty() {
+    let response: ChatCompletionResponse = serde_json::from_value(serde_json::json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "llama-3.1-8b-instant",
+        "choices": [{
+            "index": 0,
+            "message": {"role": "assistant", "content": "Hello there"},
+            "finish_reason": "stop"
+        }],
+        "usage": {"prompt_tokens": 3, "completion_tokens": 2, "total_tokens": 5}
+    }))
+    .unwrap();
+    assert_eq!(
+        response.to_string(),
+        "assistant:\nHello there\n\nllama-3.1-8b-instant · 3 prompt + 2 completion = 5 tokens · finish: stop"
+    );
+}
+
+#[test]
+fn test_models_table_sorted() {
+    let models: ModelList = serde_json::from_value(serde_json::json!({
+        "object": "list",
+        "data": [
+            {"id": "whisper-large-v3", "object": "model", "created": 0, "owned_by": "OpenAI", "active": true, "context_window": 448, "public_apps": null},
+            {"id": "llama-3.1-8b-instant", "object": "model", "created": 0, "owned_by": "Meta", "active": false, "context_window": 131072, "public_apps": null}
+        ]
+    }))
+    .unwrap();
+    let pretty = models.pretty();
+    let lines: Vec<&str> = pretty.lines().collect();
+    assert_eq!(lines[0], "ID                    OWNER   CONTEXT  ACTIVE");
+    assert!(lines[2].starts_with("llama-3.1-8b-instant  Meta    131072   no"));
+    assert!(lines[3].starts_with("whisper-large-v3"));
+}
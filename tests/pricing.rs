@@ -0,0 +1,153 @@
+use groqai::api::chat::ChatCompletionRequest;
+use groqai::mock::MockTransport;
+use groqai::pricing::{ModelPrice, PricingTable, SpendGuard};
+use groqai::types::ChatCompletionResponse;
+use groqai::{ChatMessage, GroqClientBuilder, GroqError, Role};
+#[cfg(feature = "streaming")]
+use futures::StreamExt;
+#[cfg(feature = "streaming")]
+use groqai::types::ChatCompletionChunk;
+
+mod common;
+
+fn reply(total_tokens: u32) -> ChatCompletionResponse {
+    let mut response = common::reply("Paris");
+    response.model = "llama-3.3-70b-versatile".to_string();
+    response.usage = common::usage(total_tokens - 1, 1);
+    response
+}
+
+#[tokio::test]
+async fn test_max_cost_per_request() -> Result<(), GroqError> {
+    let mock = MockTransport::new().with_chat_response(Ok(reply(20)));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+    let chat = || {
+        client
+            .chat("llama-3.3-70b-versatile")
+            .message(ChatMessage::new_text(Role::User, "What is the capital of France?"))
+            .max_completion_tokens(1000)
+    };
+
+    // Up to 1000 completion tokens at $0.79 per million may cost $0.00079
+    let result = chat().max_cost_usd(0.0005).send().await;
+    assert!(matches!(result, Err(GroqError::BudgetExceeded(m)) if m.contains("llama-3.3-70b-versatile")));
+    assert!(mock.requests().is_empty());
+    chat().max_cost_usd(0.001).send().await?;
+    assert_eq!(mock.requests().len(), 1);
+
+    // Without max_completion_tokens the worst case is unbounded
+    let request = ChatCompletionRequest {
+        model: "llama-3.3-70b-versatile".to_string(),
+        messages: vec![ChatMessage::new_text(Role::User, "What is the capital of France?")],
+        ..Default::default()
+    };
+    assert_eq!(SpendGuard::new().estimate_cost(&request), Some(f64::INFINITY));
+    let guarded = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .spend_guard(SpendGuard::new().max_cost_usd(1.0))
+        .build()?;
+    let result = guarded.chat_completions(request).await;
+    assert!(matches!(result, Err(GroqError::BudgetExceeded(m)) if m.contains("unbounded")));
+    assert_eq!(mock.requests().len(), 1);
+
+    // Unpriced models pass the cost check
+    let guard = SpendGuard::new().pricing(PricingTable::empty().set("my-model", ModelPrice::new(1.0, 1.0)));
+    assert!(guard.pricing_table().get("llama-3.3-70b-versatile").is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hourly_token_guard() -> Result<(), GroqError> {
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply(300)))
+        .with_chat_response(Ok(reply(300)))
+        .with_chat_response(Ok(reply(300)));
+    let guard = SpendGuard::new().max_total_tokens_per_hour(1_000);
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .spend_guard(guard.clone())
+        .build()?;
+    let chat = || {
+        client
+            .chat("llama-3.3-70b-versatile")
+            .message(ChatMessage::new_text(Role::User, "Hi"))
+            .max_completion_tokens(200)
+    };
+
+    chat().send().await?;
+    chat().send().await?;
+    assert_eq!(guard.tokens_used_last_hour(), 600);
+    // 600 used plus up to ~205 for the next request would still fit, but not after another 300
+    let result = chat().max_completion_tokens(500).send().await;
+    assert!(matches!(result, Err(GroqError::BudgetExceeded(_))));
+    assert_eq!(GroqError::BudgetExceeded(String::new()).to_http_status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+
+    // The override sends it anyway and still counts its tokens
+    chat().max_completion_tokens(500).override_spend_guard(true).send().await?;
+    assert_eq!(guard.tokens_used_last_hour(), 900);
+    assert_eq!(mock.requests().len(), 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_request_cannot_raise_guard_cost_limit() -> Result<(), GroqError> {
+    let mock = MockTransport::new().with_chat_response(Ok(reply(20)));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .spend_guard(SpendGuard::new().max_cost_usd(0.0005))
+        .build()?;
+    let chat = || {
+        client
+            .chat("llama-3.3-70b-versatile")
+            .message(ChatMessage::new_text(Role::User, "What is the capital of France?"))
+            .max_completion_tokens(1000)
+    };
+
+    let result = chat().max_cost_usd(0.001).send().await;
+    assert!(matches!(result, Err(GroqError::BudgetExceeded(_))));
+    assert!(mock.requests().is_empty());
+    chat().max_completion_tokens(100).max_cost_usd(0.001).send().await?;
+    assert_eq!(mock.requests().len(), 1);
+    Ok(())
+}
+
+#[cfg(feature = "streaming")]
+#[tokio::test]
+async fn test_hourly_token_guard_counts_streams() -> Result<(), GroqError> {
+    let chunk = |content: &str, x_groq: serde_json::Value| -> ChatCompletionChunk {
+        serde_json::from_value(serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion.chunk",
+            "created": 0,
+            "model": "llama-3.3-70b-versatile",
+            "system_fingerprint": null,
+            "choices": [{"index": 0, "delta": {"content": content}, "finish_reason": null}],
+            "x_groq": x_groq
+        }))
+        .unwrap()
+    };
+    let mock = MockTransport::new().with_stream(vec![
+        chunk("Par", serde_json::json!({"id": "req_1"})),
+        chunk("is", serde_json::json!({"usage": {"prompt_tokens": 240, "completion_tokens": 2, "total_tokens": 242}})),
+    ]);
+    let guard = SpendGuard::new().max_total_tokens_per_hour(1_000);
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock)
+        .spend_guard(guard.clone())
+        .build()?;
+
+    let mut stream = client
+        .chat("llama-3.3-70b-versatile")
+        .message(ChatMessage::new_text(Role::User, "Hi"))
+        .max_completion_tokens(200)
+        .stream(true)
+        .send_stream()
+        .await?;
+    while let Some(chunk) = stream.next().await {
+        chunk?;
+    }
+    assert_eq!(guard.tokens_used_last_hour(), 242);
+    Ok(())
+}
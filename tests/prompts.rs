@@ -0,0 +1,127 @@
+use groqai::mock::MockTransport;
+use groqai::prompts::PromptExperiment;
+use groqai::types::ChatCompletionResponse;
+use groqai::usage::{UsageRecord, UsageTracker};
+use groqai::{ChatMessage, GroqClientBuilder, GroqError, Role};
+
+mod common;
+
+fn reply(prompt_tokens: u32) -> ChatCompletionResponse {
+    let mut response = common::reply("ok");
+    response.usage = common::usage(prompt_tokens, 1);
+    response
+}
+
+#[test]
+fn test_assignment_is_stable_and_weighted() {
+    let experiment = PromptExperiment::new("tone")
+        .variant("a", "Be brief.", 3)
+        .variant("b", "Be thorough.", 1)
+        .variant("off", "Unused.", 0);
+
+    let first = experiment.assign("user-1").unwrap().name.clone();
+    for _ in 0..10 {
+        assert_eq!(experiment.assign("user-1").unwrap().name, first);
+    }
+
+    let a = (0..4000)
+        .filter(|i| experiment.assign(&format!("user-{}", i)).unwrap().name == "a")
+        .count();
+    assert!((2700..3300).contains(&a), "variant a got {} of 4000", a);
+    assert!((0..4000).all(|i| experiment.assign(&format!("user-{}", i)).unwrap().name != "off"));
+    assert!(PromptExperiment::new("empty").assign("user-1").is_none());
+}
+
+#[tokio::test]
+async fn test_prompt_variant_replaces_system_and_records_usage() -> Result<(), GroqError> {
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply(10)))
+        .with_chat_response(Ok(reply(20)));
+    let tracker = UsageTracker::new();
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .usage_tracker(tracker.clone())
+        .build()?;
+    let experiment = PromptExperiment::new("tone").variant("only", "Variant prompt.", 1);
+
+    client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::System, "Original prompt."))
+        .message(ChatMessage::new_text(Role::User, "Hi"))
+        .prompt_variant(&experiment, "user-1")
+        .send()
+        .await?;
+    client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hi"))
+        .send()
+        .await?;
+
+    let messages = mock.requests()[0].body["messages"].clone();
+    assert_eq!(messages.as_array().unwrap().len(), 2);
+    assert_eq!(messages[0]["content"], "Variant prompt.");
+
+    let records = tracker.records();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].experiment.as_deref(), Some("tone"));
+    assert_eq!(records[0].prompt_variant.as_deref(), Some("only"));
+    assert_eq!(records[1].prompt_variant, None);
+    assert_eq!(tracker.totals().prompt_tokens, 30);
+    assert_eq!(tracker.totals_by_variant()[&("tone".to_string(), "only".to_string())].requests, 1);
+    assert_eq!(tracker.totals_for_experiment("tone")["only"].requests, 1);
+    assert_eq!(tracker.totals_by_model()["llama-3.1-8b-instant"].requests, 2);
+    Ok(())
+}
+
+#[test]
+fn test_variant_totals_are_per_experiment() {
+    let tracker = UsageTracker::new();
+    let usage = serde_json::from_value(serde_json::json!({
+        "prompt_tokens": 4, "completion_tokens": 1, "total_tokens": 5
+    }))
+    .unwrap();
+    tracker.record(UsageRecord::from_usage("llama-3.1-8b-instant", &usage).with_prompt_variant("tone", "a"));
+    tracker.record(UsageRecord::from_usage("llama-3.1-8b-instant", &usage).with_prompt_variant("length", "a"));
+    tracker.record(UsageRecord::from_usage("llama-3.1-8b-instant", &usage).with_prompt_variant("length", "a"));
+
+    assert_eq!(tracker.totals_for_experiment("tone")["a"].requests, 1);
+    assert_eq!(tracker.totals_for_experiment("length")["a"].total_tokens, 10);
+    assert_eq!(tracker.totals_by_variant().len(), 2);
+}
+
+#[cfg(feature = "streaming")]
+#[tokio::test]
+async fn test_stream_usage_is_recorded() -> Result<(), GroqError> {
+    use futures::StreamExt;
+
+    let chunk: groqai::types::ChatCompletionChunk = serde_json::from_value(serde_json::json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion.chunk",
+        "created": 0,
+        "model": "llama-3.1-8b-instant",
+        "system_fingerprint": null,
+        "choices": [{"index": 0, "delta": {"content": "ok"}, "finish_reason": "stop"}],
+        "x_groq": {"usage": {"prompt_tokens": 12, "completion_tokens": 1, "total_tokens": 13}}
+    }))
+    .unwrap();
+    let tracker = UsageTracker::new();
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(MockTransport::new().with_stream(vec![chunk]))
+        .usage_tracker(tracker.clone())
+        .build()?;
+    let experiment = PromptExperiment::new("tone").variant("only", "Variant prompt.", 1);
+
+    let mut stream = client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hi"))
+        .prompt_variant(&experiment, "user-1")
+        .stream(true)
+        .send_stream()
+        .await?;
+    while let Some(chunk) = stream.next().await {
+        chunk?;
+    }
+    assert_eq!(tracker.totals().total_tokens, 13);
+    assert_eq!(tracker.totals_for_experiment("tone")["only"].requests, 1);
+    Ok(())
+}
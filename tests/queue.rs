@@ -0,0 +1,113 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "streaming")]
+use futures_util::stream::StreamExt;
+#[cfg(feature = "streaming")]
+use groqai::mock::MockTransport;
+use groqai::{Priority, RequestQueue};
+#[cfg(feature = "streaming")]
+use groqai::{ChatMessage, GroqClientBuilder, GroqError, Role};
+
+/// Queues a request of `priority` that records `label` once it gets a slot
+async fn enqueue(queue: &RequestQueue, priority: Priority, label: &'static str, served: &Arc<Mutex<Vec<&'static str>>>) {
+    let before = queue.waiting(priority);
+    let (queue_clone, served) = (queue.clone(), served.clone());
+    tokio::spawn(async move {
+        let _permit = queue_clone.acquire(priority).await;
+        served.lock().unwrap().push(label);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    });
+    while queue.waiting(priority) == before {
+        tokio::task::yield_now().await;
+    }
+}
+
+async fn wait_until_idle(queue: &RequestQueue) {
+    while queue.active() > 0 || Priority::ALL.iter().any(|p| queue.waiting(*p) > 0) {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_interactive_requests_go_first() {
+    let queue = RequestQueue::new(1);
+    let served = Arc::new(Mutex::new(Vec::new()));
+    let held = queue.acquire(Priority::Background).await;
+
+    enqueue(&queue, Priority::BatchPrep, "prep", &served).await;
+    enqueue(&queue, Priority::Background, "background 1", &served).await;
+    enqueue(&queue, Priority::Background, "background 2", &served).await;
+    enqueue(&queue, Priority::Interactive, "interactive", &served).await;
+    assert_eq!(queue.active(), 1);
+    drop(held);
+    wait_until_idle(&queue).await;
+
+    assert_eq!(*served.lock().unwrap(), ["interactive", "background 1", "background 2", "prep"]);
+}
+
+#[tokio::test]
+async fn test_limit_keeps_slots_for_interactive_requests() {
+    let queue = RequestQueue::new(2).limit(Priority::Background, 1);
+    let _background = queue.acquire(Priority::Background).await;
+
+    let blocked = tokio::time::timeout(Duration::from_millis(50), queue.acquire(Priority::Background)).await;
+    assert!(blocked.is_err());
+    // The abandoned request left the line
+    assert_eq!(queue.waiting(Priority::Background), 0);
+
+    let interactive = tokio::time::timeout(Duration::from_millis(50), queue.acquire(Priority::Interactive)).await;
+    assert!(interactive.is_ok());
+    assert_eq!(queue.active(), 2);
+}
+
+#[tokio::test]
+async fn test_max_wait_prevents_starvation() {
+    let queue = RequestQueue::new(1).max_wait(Duration::from_millis(30));
+    let served = Arc::new(Mutex::new(Vec::new()));
+    let held = queue.acquire(Priority::Interactive).await;
+
+    enqueue(&queue, Priority::BatchPrep, "prep", &served).await;
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    enqueue(&queue, Priority::Interactive, "interactive", &served).await;
+    drop(held);
+    wait_until_idle(&queue).await;
+
+    assert_eq!(*served.lock().unwrap(), ["prep", "interactive"]);
+}
+
+#[cfg(feature = "streaming")]
+#[tokio::test]
+async fn test_client_requests_hold_a_slot() -> Result<(), GroqError> {
+    let chunk = serde_json::from_str(groqai::fixtures::CHAT_COMPLETION_CHUNK).unwrap();
+    let response = serde_json::from_str(groqai::fixtures::CHAT_COMPLETION_RESPONSE).unwrap();
+    let mock = MockTransport::new().with_chat_response(Ok(response)).with_stream(vec![chunk]);
+    let queue = RequestQueue::new(4);
+    let client = GroqClientBuilder::new("gsk_test_key")?
+        .transport(mock)
+        .request_queue(queue.clone())
+        .build()?;
+    assert!(client.request_queue().is_some());
+
+    client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .priority(Priority::Background)
+        .send()
+        .await?;
+    assert_eq!(queue.active(), 0);
+
+    let mut stream = client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .stream(true)
+        .send_stream()
+        .await?;
+    assert_eq!(queue.active(), 1);
+    while let Some(chunk) = stream.next().await {
+        chunk?;
+    }
+    drop(stream);
+    assert_eq!(queue.active(), 0);
+    Ok(())
+}
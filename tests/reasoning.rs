@@ -0,0 +1,97 @@
+#[cfg(feature = "streaming")]
+use futures::StreamExt;
+#[cfg(feature = "streaming")]
+use groqai::mock::MockTransport;
+use groqai::reasoning::ReasoningStripper;
+#[cfg(feature = "streaming")]
+use groqai::types::{ChatCompletionChunk, ChatCompletionResponse};
+#[cfg(feature = "streaming")]
+use groqai::{ChatMessage, GroqClientBuilder, GroqError, Role};
+
+#[cfg(feature = "streaming")]
+fn chunk(text: &str, finish_reason: Option<&str>) -> ChatCompletionChunk {
+    serde_json::from_value(serde_json::json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion.chunk",
+        "created": 0,
+        "model": "qwen/qwen3-32b",
+        "system_fingerprint": null,
+        "choices": [{"index": 0, "delta": {"content": text}, "finish_reason": finish_reason}]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_split_reasoning_blocks() {
+    let stripper = ReasoningStripper::new();
+    let split = stripper.split("<think>first</think>Hello <think> second </think>world");
+    assert_eq!(split.reasoning.as_deref(), Some("first\n\nsecond"));
+    assert_eq!(split.answer, "Hello world");
+
+    let split = stripper.split("Answer<think>cut off");
+    assert_eq!(split.reasoning.as_deref(), Some("cut off"));
+    assert_eq!(split.answer, "Answer");
+
+    let split = stripper.split("No reasoning here");
+    assert_eq!(split.reasoning, None);
+
+    let custom = ReasoningStripper::new().markers("[[", "]]");
+    assert_eq!(custom.split("[[hmm]]Yes").answer, "Yes");
+}
+
+#[cfg(feature = "streaming")]
+#[tokio::test]
+async fn test_strip_reasoning_on_send_and_stream() -> Result<(), GroqError> {
+    let response: ChatCompletionResponse = serde_json::from_value(serde_json::json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion",
+        "created": 0,
+        "model": "qwen/qwen3-32b",
+        "choices": [{"index": 0, "message": {"role": "assistant", "content": "<think>Greet back</think>\n\nHi!"}, "finish_reason": "stop"}],
+        "usage": {"prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2}
+    }))
+    .unwrap();
+    let mock = MockTransport::new().with_chat_response(Ok(response)).with_stream(vec![
+        chunk("<thi", None),
+        chunk("nk>Greet", None),
+        chunk(" back</th", None),
+        chunk("ink>\n\nHi", None),
+        chunk("!", Some("stop")),
+    ]);
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    let response = client
+        .chat("qwen/qwen3-32b")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .reasoning_format("raw")
+        .strip_reasoning(true)
+        .send()
+        .await?;
+    assert_eq!(response.choices[0].message.content.to_string(), "Hi!");
+    assert_eq!(response.choices[0].reasoning.as_deref(), Some("Greet back"));
+    assert_eq!(mock.requests()[0].body["reasoning_format"], "raw");
+
+    let chunks: Vec<ChatCompletionChunk> = client
+        .chat("qwen/qwen3-32b")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .stream(true)
+        .strip_reasoning(true)
+        .send_stream()
+        .await?
+        .map(|c| c.unwrap())
+        .collect()
+        .await;
+    let answer: String = chunks
+        .iter()
+        .filter_map(|c| c.choices[0].delta.content.as_ref().map(|m| m.to_string()))
+        .collect();
+    let reasoning: String = chunks
+        .iter()
+        .filter_map(|c| c.choices[0].delta.reasoning.clone())
+        .collect();
+    assert_eq!(answer, "Hi!");
+    assert_eq!(reasoning, "Greet back");
+    Ok(())
+}
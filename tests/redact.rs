@@ -0,0 +1,80 @@
+#![cfg(feature = "redact")]
+use groqai::mock::MockTransport;
+use groqai::redact::Redactor;
+use groqai::{GroqClientBuilder, GroqError, Transcription};
+
+mod common;
+
+#[test]
+fn test_default_rules_mask_pii() {
+    let transcription: Transcription = serde_json::from_value(serde_json::json!({
+        "text": "Mail jane.doe@example.com or call +1 415-555-0132. Card 4111 1111 1111 1111, order 12345."
+    }))
+    .unwrap();
+
+    let result = transcription.redact(&Redactor::new());
+    assert_eq!(
+        result.redacted,
+        "Mail [EMAIL] or call [PHONE]. Card [CARD], order 12345."
+    );
+    assert_eq!(result.raw, transcription.text);
+    let labels: Vec<&str> = result.spans.iter().map(|s| s.label.as_str()).collect();
+    assert_eq!(labels, vec!["EMAIL", "PHONE", "CARD"]);
+}
+
+#[test]
+fn test_custom_words_and_patterns() -> Result<(), GroqError> {
+    let redactor = Redactor::empty()
+        .words("PROFANITY", ["darn"])
+        .pattern("TICKET", r"TCK-\d+")?;
+    let result = redactor.redact("Darn, TCK-42 is still open; darned thing.");
+    assert_eq!(result.redacted, "[PROFANITY], [TICKET] is still open; darned thing.");
+    assert!(Redactor::empty().pattern("BAD", "(").is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_chat_pass_runs_on_masked_text() -> Result<(), GroqError> {
+    let reply = common::reply("[REDACTED] wrote from [EMAIL].");
+    let mock = MockTransport::new().with_chat_response(Ok(reply));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    let result = Redactor::new()
+        .chat_pass("llama-3.1-8b-instant")
+        .redact_with(&client, "Jane wrote from jane@example.com.")
+        .await?;
+    assert_eq!(result.redacted, "[REDACTED] wrote from [EMAIL].");
+    assert_eq!(mock.requests()[0].body["messages"][1]["content"], "Jane wrote from [EMAIL].");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_chat_pass_rejects_truncated_and_empty_replies() -> Result<(), GroqError> {
+    let reply = |choices: serde_json::Value| -> Result<_, GroqError> {
+        Ok(serde_json::from_value(serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "llama-3.1-8b-instant",
+            "choices": choices,
+            "usage": {"prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2}
+        }))?)
+    };
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply(serde_json::json!([
+            {"index": 0, "message": {"role": "assistant", "content": "[REDACTED] wrote"}, "finish_reason": "length"}
+        ]))?))
+        .with_chat_response(Ok(reply(serde_json::json!([]))?));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock)
+        .build()?;
+
+    let redactor = Redactor::new().chat_pass("llama-3.1-8b-instant");
+    let truncated = redactor.redact_with(&client, "Jane wrote from jane@example.com.").await;
+    assert!(matches!(truncated, Err(GroqError::InvalidMessage(m)) if m.contains("cut off")));
+    let empty = redactor.redact_with(&client, "Jane wrote from jane@example.com.").await;
+    assert!(matches!(empty, Err(GroqError::InvalidMessage(m)) if m.contains("no choices")));
+    Ok(())
+}
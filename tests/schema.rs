@@ -0,0 +1,129 @@
+use groqai::mock::MockTransport;
+use groqai::schema::{make_strict, StrictSchemaIssue};
+use groqai::types::ResponseFormat;
+use groqai::{ChatMessage, GroqClientBuilder, GroqError, Role};
+use serde_json::json;
+
+mod common;
+
+use common::reply;
+
+#[test]
+fn test_make_strict_rewrites_nested_objects() {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "name": {"type": "string"},
+            "tags": {"type": "array", "items": {"type": "object", "properties": {"label": {"type": "string"}}}},
+            "status": {"enum": ["open", "closed"]},
+            "owner": {"$ref": "#/$defs/person"}
+        },
+        "required": ["name"],
+        "$defs": {
+            "person": {"type": "object", "properties": {"email": {"type": ["string"]}}, "required": ["email"]}
+        }
+    });
+    let strict = make_strict(&schema).unwrap();
+    assert_eq!(strict["additionalProperties"], json!(false));
+    assert_eq!(strict["required"], json!(["name", "owner", "status", "tags"]));
+    assert_eq!(strict["properties"]["name"]["type"], json!("string"));
+    assert_eq!(strict["properties"]["tags"]["type"], json!(["array", "null"]));
+    let item = &strict["properties"]["tags"]["items"];
+    assert_eq!(item["required"], json!(["label"]));
+    assert_eq!(item["properties"]["label"]["type"], json!(["string", "null"]));
+    assert_eq!(strict["properties"]["status"]["enum"], json!(["open", "closed", null]));
+    assert_eq!(
+        strict["properties"]["owner"],
+        json!({"anyOf": [{"$ref": "#/$defs/person"}, {"type": "null"}]})
+    );
+    assert_eq!(strict["$defs"]["person"]["additionalProperties"], json!(false));
+    assert_eq!(strict["$defs"]["person"]["properties"]["email"]["type"], json!(["string"]));
+}
+
+#[test]
+fn test_make_strict_reports_unsupported_constructs() {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "meta": {"type": "object", "additionalProperties": {"type": "string"}},
+            "value": {"oneOf": [{"type": "string"}, {"type": "number"}]},
+            "pair": {"type": "array", "items": [{"type": "string"}, {"type": "number"}]}
+        }
+    });
+    let issues = make_strict(&schema).unwrap_err();
+    let paths: Vec<&str> = issues.iter().map(|i| i.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        ["/properties/meta/additionalProperties", "/properties/pair/items", "/properties/value/oneOf"]
+    );
+    assert_eq!(
+        make_strict(&json!({"type": "string"})).unwrap_err(),
+        vec![StrictSchemaIssue {
+            path: String::new(),
+            message: "the root schema must be an object".to_string()
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_strict_response_format_is_rewritten() -> Result<(), GroqError> {
+    let mock = MockTransport::new().with_chat_response(Ok(reply("{\"city\": \"Paris\", \"zip\": null}")));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+    let schema = json!({"type": "object", "properties": {"city": {"type": "string"}, "zip": {"type": "string"}}});
+
+    client
+        .chat("openai/gpt-oss-20b")
+        .message(ChatMessage::new_text(Role::User, "Where is the Louvre?"))
+        .response_format(ResponseFormat::json_schema("address", schema, true))
+        .send()
+        .await?;
+    let body = &mock.requests()[0].body;
+    let sent = &body["response_format"]["json_schema"]["schema"];
+    assert_eq!(sent["required"], json!(["city", "zip"]));
+    assert_eq!(sent["additionalProperties"], json!(false));
+
+    // Unsupported schemas fail before anything is sent
+    let result = client
+        .chat("openai/gpt-oss-20b")
+        .message(ChatMessage::new_text(Role::User, "Where is the Louvre?"))
+        .response_format(ResponseFormat::json_schema("address", json!({"type": "object", "not": {}}), true))
+        .send()
+        .await;
+    match result {
+        Err(GroqError::InvalidMessage(message)) => assert!(message.contains("/not: `not` is not supported")),
+        other => panic!("expected InvalidMessage, got {:?}", other.map(|r| r.id)),
+    }
+    assert_eq!(mock.requests().len(), 1);
+    Ok(())
+}
+
+#[cfg(feature = "schema-export")]
+#[test]
+fn test_export_covers_requests_and_responses() -> Result<(), GroqError> {
+    let schemas = groqai::schema::export();
+    for name in ["ChatCompletionRequest", "BatchCreateRequest", "ChatCompletionResponse", "Batch", "WorkFileList"] {
+        assert_eq!(schemas[name]["$schema"], json!("https://json-schema.org/draft/2020-12/schema"), "{}", name);
+    }
+
+    let request = &schemas["ChatCompletionRequest"];
+    assert_eq!(request["required"], json!(["messages", "model"]));
+    assert!(request["$defs"]["ChatMessage"].is_object());
+    // Enums with a custom wire format are described by that format
+    assert_eq!(schemas["BatchCreateRequest"]["properties"]["completion_window"]["$ref"], json!("#/$defs/CompletionWindow"));
+    assert_eq!(schemas["BatchCreateRequest"]["$defs"]["CompletionWindow"]["type"], json!("string"));
+
+    // Fields the client fills in are not part of the wire format
+    let response = &schemas["ChatCompletionResponse"];
+    assert!(response["properties"]["cache_hit"].is_null());
+    assert!(response["properties"]["service_tier"].is_object());
+
+    let dir = std::env::temp_dir().join(format!("groqai-schemas-{}", std::process::id()));
+    let written = groqai::schema::export_to_dir(&dir)?;
+    assert_eq!(written.len(), schemas.len());
+    let stored: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(dir.join("Batch.schema.json")).unwrap())?;
+    assert_eq!(stored, schemas["Batch"]);
+    std::fs::remove_dir_all(&dir).ok();
+    Ok(())
+}
@@ -0,0 +1,109 @@
+#[cfg(feature = "streaming")]
+use futures::StreamExt;
+use groqai::error::GroqError;
+#[cfg(feature = "streaming")]
+use groqai::mock::MockTransport;
+use groqai::shutdown::ShutdownReport;
+#[cfg(feature = "streaming")]
+use groqai::stream::RecordedChunk;
+#[cfg(feature = "streaming")]
+use groqai::types::ChatCompletionChunk;
+use groqai::GroqClientBuilder;
+#[cfg(feature = "streaming")]
+use groqai::{ChatMessage, Role};
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[cfg(feature = "streaming")]
+fn chunk(text: &str) -> ChatCompletionChunk {
+    serde_json::from_value(serde_json::json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion.chunk",
+        "created": 0,
+        "model": "llama-3.1-8b-instant",
+        "system_fingerprint": null,
+        "choices": [{"index": 0, "delta": {"content": text}, "finish_reason": null}]
+    }))
+    .unwrap()
+}
+
+async fn slow_models(delay: Duration) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/models"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"object": "list", "data": []}))
+                .set_delay(delay),
+        )
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn test_shutdown_drains_and_aborts() -> Result<(), Box<dyn std::error::Error>> {
+    // A request that finishes within the grace period completes normally
+    let server = slow_models(Duration::from_millis(200)).await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(format!("{}/", server.uri()).parse()?)
+        .build()?;
+    let worker = client.clone();
+    let pending = tokio::spawn(async move { worker.models().list().await });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let report = client.shutdown(Duration::from_secs(5)).await;
+    assert_eq!(report, ShutdownReport { in_flight: 1, aborted: 0 });
+    assert!(pending.await?.is_ok());
+    assert!(matches!(client.models().list().await, Err(GroqError::Shutdown)));
+
+    // A request still running when the grace period ends is aborted
+    let server = slow_models(Duration::from_secs(10)).await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(format!("{}/", server.uri()).parse()?)
+        .build()?;
+    let worker = client.clone();
+    let pending = tokio::spawn(async move { worker.models().list().await });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let report = client.shutdown(Duration::from_millis(100)).await;
+    assert_eq!(report, ShutdownReport { in_flight: 1, aborted: 1 });
+    assert!(matches!(pending.await?, Err(GroqError::Shutdown)));
+    Ok(())
+}
+
+#[cfg(feature = "streaming")]
+#[tokio::test]
+async fn test_shutdown_aborts_open_stream() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::temp_dir().join(format!("groqai-shutdown-{}.ndjson", std::process::id()));
+    let records: Vec<String> = [(0, "Hel"), (10_000, "lo")]
+        .iter()
+        .map(|(elapsed_ms, text)| {
+            serde_json::to_string(&RecordedChunk {
+                timestamp_ms: 0,
+                elapsed_ms: *elapsed_ms,
+                chunk: chunk(text),
+            })
+            .unwrap()
+        })
+        .collect();
+    std::fs::write(&path, records.join("\n"))?;
+    let mock = MockTransport::stream_from_ndjson(&path)?;
+    std::fs::remove_file(&path)?;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock)
+        .build()?;
+
+    let mut stream = client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .stream(true)
+        .send_stream()
+        .await?;
+    assert!(stream.next().await.unwrap().is_ok());
+
+    let report = client.shutdown(Duration::from_millis(50)).await;
+    assert_eq!(report, ShutdownReport { in_flight: 1, aborted: 1 });
+    assert!(matches!(stream.next().await, Some(Err(GroqError::Shutdown))));
+    assert!(stream.next().await.is_none());
+    Ok(())
+}
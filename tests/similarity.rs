@@ -0,0 +1,57 @@
+use groqai::mock::MockTransport;
+use groqai::similarity::{compare, cosine, line_diff, Comparison, DiffLine};
+use groqai::{GroqClientBuilder, GroqError};
+use serde_json::json;
+
+mod common;
+
+#[tokio::test]
+async fn test_local_comparisons() -> Result<(), GroqError> {
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(MockTransport::new())
+        .build()?;
+    assert_eq!(compare(&client, "a  b\n", "a b", &Comparison::Exact).await?, 0.0);
+    assert_eq!(compare(&client, "a  b\n", "a b", &Comparison::NormalizedWhitespace).await?, 1.0);
+    assert!((cosine(&[1.0, 0.0], &[1.0, 1.0]) - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-9);
+    assert_eq!(cosine(&[1.0], &[1.0, 2.0]), 0.0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_embedding_and_judge_comparisons() -> Result<(), GroqError> {
+    let judge = common::reply("{\"score\": 8}");
+    let mock = MockTransport::new()
+        .with_json_response(Ok(json!({"data": [
+            {"index": 1, "embedding": [0.0, 1.0]},
+            {"index": 0, "embedding": [0.0, 2.0]}
+        ]})))
+        .with_chat_response(Ok(judge));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    let embedding = Comparison::Embedding { model: "embed".to_string() };
+    assert!((compare(&client, "x", "y", &embedding).await? - 1.0).abs() < 1e-9);
+    let judged = compare(&client, "x", "y", &Comparison::Judge { model: "judge".to_string() }).await?;
+    assert!((judged - 0.8).abs() < 1e-9);
+
+    let requests = mock.requests();
+    assert_eq!(requests[0].path, "embeddings");
+    assert_eq!(requests[0].body["input"], json!(["x", "y"]));
+    assert_eq!(requests[1].body["temperature"], 0.0);
+    Ok(())
+}
+
+#[test]
+fn test_line_diff() {
+    let diff = line_diff("a\nb\nc", "a\nc\nd");
+    assert_eq!(
+        diff,
+        vec![
+            DiffLine::Same("a".to_string()),
+            DiffLine::Removed("b".to_string()),
+            DiffLine::Same("c".to_string()),
+            DiffLine::Added("d".to_string()),
+        ]
+    );
+}
@@ -0,0 +1,137 @@
+use bytes::Bytes;
+use groqai::sse::SseParser;
+use groqai::types::ChatCompletionChunk;
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+fn chunk_json(text: &str) -> String {
+    serde_json::json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion.chunk",
+        "created": 0,
+        "model": "llama-3.1-8b-instant",
+        "system_fingerprint": null,
+        "choices": [{"index": 0, "delta": {"content": text}, "finish_reason": null}]
+    })
+    .to_string()
+}
+
+/// Builds a stream body with one event per text, keep-alive comments and the given line ending
+fn transcript(texts: &[String], crlf: bool, comments: bool) -> Vec<u8> {
+    let eol = if crlf { "\r\n" } else { "\n" };
+    let mut body = String::new();
+    for text in texts {
+        if comments {
+            body.push_str(&format!(": keep-alive{eol}"));
+        }
+        body.push_str(&format!("data: {}{eol}{eol}", chunk_json(text)));
+    }
+    body.push_str(&format!("data: [DONE]{eol}{eol}"));
+    body.into_bytes()
+}
+
+/// Cuts `body` into reads at the given positions
+fn split(body: &[u8], cuts: &[Index]) -> Vec<Bytes> {
+    let mut positions: Vec<usize> = cuts.iter().map(|i| i.index(body.len() + 1)).collect();
+    positions.sort_unstable();
+    positions.dedup();
+    let mut reads = Vec::new();
+    let mut start = 0;
+    for position in positions.into_iter().chain([body.len()]) {
+        reads.push(Bytes::copy_from_slice(&body[start..position]));
+        start = position;
+    }
+    reads
+}
+
+/// Parses the reads and describes the results up to and including the first error
+fn parse(reads: Vec<Bytes>) -> Vec<String> {
+    let mut parser = SseParser::new();
+    let mut results = Vec::new();
+    for bytes in reads {
+        for result in parser.push(bytes) {
+            match result {
+                Ok(chunk) => results.push(serde_json::to_string(&chunk).unwrap()),
+                Err(e) => {
+                    results.push(e.to_string());
+                    return results;
+                }
+            }
+        }
+    }
+    results
+}
+
+fn contents(chunks: Vec<String>) -> Vec<String> {
+    chunks
+        .iter()
+        .map(|c| {
+            let chunk: ChatCompletionChunk = serde_json::from_str(c).unwrap();
+            chunk.choices[0].delta.content.as_ref().unwrap().to_string()
+        })
+        .collect()
+}
+
+/// Pieces of SSE syntax, JSON and raw bytes that concatenate into malformed streams
+fn fragment() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        Just(b"data: ".to_vec()),
+        Just(b"event: error\n".to_vec()),
+        Just(b"\n".to_vec()),
+        Just(b"\r\n".to_vec()),
+        Just(b"[DONE]".to_vec()),
+        Just(b"{\"error\": {\"message\": \"overloaded\"}}".to_vec()),
+        Just(b"{\"".to_vec()),
+        Just(b"}".to_vec()),
+        "\\PC{0,8}".prop_map(|text| chunk_json(&text).into_bytes()),
+        "\\PC{0,8}".prop_map(|text| chunk_json(&text).as_bytes()[..20].to_vec()),
+        prop::collection::vec(any::<u8>(), 0..8),
+    ]
+}
+
+#[test]
+fn test_sse_parser_reassembles_split_characters() {
+    let texts = ["héllo 世界".to_string(), "🦀".to_string()];
+    let body = transcript(&texts, true, false);
+    // One byte per read splits every multi-byte character
+    let reads = body.iter().map(|b| Bytes::copy_from_slice(&[*b])).collect();
+    assert_eq!(contents(parse(reads)), texts);
+}
+
+#[test]
+fn test_sse_parser_stops_at_error_event() {
+    let body = format!(
+        "data: {}\n\nevent: error\ndata: {{\"error\": {{\"message\": \"Internal server error\"}}}}\n\ndata: {}\n\n",
+        chunk_json("a"),
+        chunk_json("b")
+    );
+    let results = parse(vec![Bytes::from(body)]);
+    assert_eq!(results.len(), 2);
+    assert!(results[1].contains("Internal server error"), "{}", results[1]);
+}
+
+proptest! {
+    #[test]
+    fn prop_split_transcripts_parse_identically(
+        texts in prop::collection::vec("\\PC{0,16}", 0..8),
+        crlf in any::<bool>(),
+        comments in any::<bool>(),
+        cuts in prop::collection::vec(any::<Index>(), 0..24),
+    ) {
+        let body = transcript(&texts, crlf, comments);
+        let whole = parse(vec![Bytes::copy_from_slice(&body)]);
+        prop_assert_eq!(contents(whole.clone()), texts);
+        prop_assert_eq!(parse(split(&body, &cuts)), whole);
+    }
+
+    #[test]
+    fn prop_malformed_streams_parse_identically(
+        fragments in prop::collection::vec(fragment(), 0..48),
+        cuts in prop::collection::vec(any::<Index>(), 0..24),
+    ) {
+        // Malformed streams must not panic, and their parse must not depend on the reads
+        let body = fragments.concat();
+        let whole = parse(vec![Bytes::copy_from_slice(&body)]);
+        prop_assert_eq!(parse(split(&body, &cuts)), whole);
+    }
+}
@@ -0,0 +1,325 @@
+#![cfg(feature = "streaming")]
+
+use futures::StreamExt;
+use groqai::error::GroqError;
+use groqai::stream::{ChatEvent, ChatStreamExt, Coalesce, RecordedChunk, StreamRecorder};
+use groqai::mock::MockTransport;
+use groqai::types::ChatCompletionChunk;
+use groqai::{ChatMessage, GroqClientBuilder, Role};
+use std::time::Duration;
+
+fn chunk(text: &str) -> ChatCompletionChunk {
+    serde_json::from_value(serde_json::json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion.chunk",
+        "created": 0,
+        "model": "llama-3.1-8b-instant",
+        "system_fingerprint": null,
+        "choices": [{"index": 0, "delta": {"content": text}, "finish_reason": null}]
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_heartbeat_while_silent() {
+    let source = futures::stream::unfold(0, |i| async move {
+        match i {
+            0 => Some((Ok::<_, GroqError>(chunk("a")), 1)),
+            1 => {
+                tokio::time::sleep(Duration::from_millis(250)).await;
+                Some((Ok(chunk("b")), 2))
+            }
+            _ => None,
+        }
+    });
+
+    let events: Vec<_> = Box::pin(source)
+        .heartbeat(Duration::from_millis(100))
+        .collect()
+        .await;
+
+    assert!(matches!(events.first(), Some(Ok(ChatEvent::Chunk(_)))));
+    assert!(matches!(events.last(), Some(Ok(ChatEvent::Chunk(_)))));
+    let heartbeats: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            Ok(ChatEvent::Heartbeat { elapsed }) => Some(*elapsed),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(heartbeats.len(), 2);
+    assert!(heartbeats[1] > heartbeats[0]);
+}
+
+#[tokio::test]
+async fn test_heartbeat_zero_interval_is_clamped() {
+    let source = futures::stream::once(async {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        Ok::<_, GroqError>(chunk("a"))
+    });
+
+    let events: Vec<_> = Box::pin(source).heartbeat(Duration::ZERO).collect().await;
+
+    // At most one heartbeat per 10ms of silence
+    let heartbeats = events.iter().filter(|e| matches!(e, Ok(ChatEvent::Heartbeat { .. }))).count();
+    assert!((1..=10).contains(&heartbeats), "{}", heartbeats);
+    assert!(matches!(events.last(), Some(Ok(ChatEvent::Chunk(_)))));
+}
+
+#[tokio::test]
+async fn test_record_stream_to_channel() {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let source = futures::stream::iter(vec![
+        Ok(chunk("a")),
+        Err(GroqError::InvalidMessage("boom".to_string())),
+        Ok(chunk("b")),
+    ]);
+
+    let items: Vec<_> = source.record(StreamRecorder::to_channel(tx)).collect().await;
+    assert_eq!(items.len(), 3);
+    assert!(items[1].is_err());
+
+    let mut recorded = Vec::new();
+    while let Ok(line) = rx.try_recv() {
+        recorded.push(serde_json::from_str::<RecordedChunk>(&line).unwrap());
+    }
+    assert_eq!(recorded.len(), 2);
+    assert!(recorded[0].elapsed_ms <= recorded[1].elapsed_ms);
+    assert_eq!(recorded[1].chunk.choices[0].delta.content.as_ref().unwrap().to_string(), "b");
+}
+
+#[tokio::test]
+async fn test_record_stream_to_file() {
+    let path = std::env::temp_dir().join(format!("groqai-record-{}.ndjson", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let source = futures::stream::iter(vec![Ok(chunk("a")), Ok(chunk("b")), Ok(chunk("c"))]);
+    let recorder = StreamRecorder::to_file(&path).unwrap();
+    let items: Vec<_> = source.record(recorder).collect().await;
+    assert_eq!(items.len(), 3);
+
+    // The writer thread finishes shortly after the recorder is dropped
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    let lines = loop {
+        let text = std::fs::read_to_string(&path).unwrap_or_default();
+        if text.lines().count() == 3 || std::time::Instant::now() > deadline {
+            break text;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    };
+    std::fs::remove_file(&path).unwrap();
+    let text: String = lines
+        .lines()
+        .map(|line| serde_json::from_str::<RecordedChunk>(line).unwrap())
+        .map(|r| r.chunk.choices[0].delta.content.as_ref().unwrap().to_string())
+        .collect();
+    assert_eq!(text, "abc");
+}
+
+#[tokio::test]
+async fn test_replay_recorded_stream() -> Result<(), GroqError> {
+    // Virtual time keeps the timing assertions stable on a busy machine
+    tokio::time::pause();
+    let path = std::env::temp_dir().join(format!("groqai-replay-{}.ndjson", std::process::id()));
+    let lines: Vec<String> = [(0, "Hel"), (200, "lo")]
+        .into_iter()
+        .map(|(elapsed_ms, text)| {
+            let record = RecordedChunk {
+                timestamp_ms: 1_700_000_000_000 + elapsed_ms,
+                elapsed_ms,
+                chunk: chunk(text),
+            };
+            serde_json::to_string(&record).unwrap()
+        })
+        .collect();
+    std::fs::write(&path, lines.join("\n")).unwrap();
+
+    let mock = MockTransport::stream_from_ndjson(&path)?.time_scale(0.5);
+    std::fs::remove_file(&path).unwrap();
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock)
+        .build()?;
+
+    let started = tokio::time::Instant::now();
+    let chunks: Vec<_> = client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .stream(true)
+        .send_stream()
+        .await?
+        .collect()
+        .await;
+    let elapsed = started.elapsed();
+
+    let text: String = chunks
+        .into_iter()
+        .map(|c| c.unwrap().choices[0].delta.content.as_ref().unwrap().to_string())
+        .collect();
+    assert_eq!(text, "Hello");
+    // Timers round up to the next millisecond
+    assert!(elapsed >= Duration::from_millis(100), "replayed too fast: {:?}", elapsed);
+    assert!(elapsed <= Duration::from_millis(101), "timing not scaled: {:?}", elapsed);
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "finite")]
+fn test_time_scale_rejects_non_finite() {
+    let _ = MockTransport::new().time_scale(f64::INFINITY);
+}
+
+async fn sse_server(fixture: &str) -> wiremock::MockServer {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let body = std::fs::read_to_string(format!("tests/fixtures/{}", fixture)).unwrap();
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn test_stream_error_payload_ends_stream() -> Result<(), Box<dyn std::error::Error>> {
+    let server = sse_server("stream-error.sse").await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(format!("{}/", server.uri()).parse()?)
+        .build()?;
+
+    let items: Vec<_> = client
+        .chat("llama-3.3-70b-versatile")
+        .message(ChatMessage::new_text(Role::User, "What is the capital of France?"))
+        .stream(true)
+        .send_stream()
+        .await?
+        .collect()
+        .await;
+    assert_eq!(items.len(), 3);
+    assert!(items[..2].iter().all(Result::is_ok));
+    match &items[2] {
+        Err(GroqError::Api(error)) => {
+            assert_eq!(error.status, reqwest::StatusCode::TOO_MANY_REQUESTS);
+            assert!(error.error.message.starts_with("Rate limit reached"));
+            assert_eq!(error.error.error_type.as_deref(), Some("tokens"));
+            assert_eq!(error.error.code.as_deref(), Some("rate_limit_exceeded"));
+        }
+        other => panic!("expected an API error, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stream_error_event() -> Result<(), Box<dyn std::error::Error>> {
+    let server = sse_server("stream-error-event.sse").await;
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .base_url(format!("{}/", server.uri()).parse()?)
+        .build()?;
+
+    let mut stream = client
+        .chat("llama-3.1-8b-instant")
+        .message(ChatMessage::new_text(Role::User, "Hello"))
+        .stream(true)
+        .send_stream()
+        .await?;
+    assert!(stream.next().await.unwrap().is_ok());
+    match stream.next().await {
+        Some(Err(GroqError::Api(error))) => {
+            assert_eq!(error.status, reqwest::StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(error.error.message, "Internal server error");
+        }
+        other => panic!("expected an API error, got {:?}", other.map(|r| r.map(|c| c.id))),
+    }
+    assert!(stream.next().await.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_smooth_paces_bursts() {
+    let mut last = chunk(" and the rest.");
+    last.choices[0].finish_reason = Some("stop".to_string());
+    let mut burst = chunk("Forty characters arrive all at once!");
+    burst.x_groq = Some(serde_json::json!({"id": "req_1"}));
+    burst.usage = serde_json::from_value(serde_json::json!({
+        "prompt_tokens": 5, "completion_tokens": 8, "total_tokens": 13
+    }))
+    .unwrap();
+    burst.choices[0].logprobs = serde_json::from_value(serde_json::json!({"content": []})).unwrap();
+    let source = futures::stream::iter(vec![
+        Ok(burst),
+        Err(GroqError::InvalidMessage("boom".to_string())),
+        Ok(last),
+    ]);
+
+    let started = std::time::Instant::now();
+    let items: Vec<_> = source.smooth(300.0).collect().await;
+    let elapsed = started.elapsed();
+
+    // 300 chars/s is emitted in pieces of 5 characters
+    let texts: Vec<String> = items
+        .iter()
+        .filter_map(|item| item.as_ref().ok())
+        .map(|c| c.choices[0].delta.content.as_ref().unwrap().to_string())
+        .collect();
+    assert!(texts.iter().all(|t| t.chars().count() <= 5));
+    assert_eq!(texts.concat(), "Forty characters arrive all at once! and the rest.");
+    assert!(matches!(items[8], Err(GroqError::InvalidMessage(_))));
+    // Usage, x_groq and logprobs stay on the burst's last piece only
+    let burst: Vec<_> = items[..8].iter().map(|item| item.as_ref().unwrap()).collect();
+    assert!(burst[..7].iter().all(|c| c.usage.is_none() && c.x_groq.is_none() && c.choices[0].logprobs.is_none()));
+    assert!(burst[7].usage.is_some() && burst[7].x_groq.is_some() && burst[7].choices[0].logprobs.is_some());
+    let finished: Vec<_> = items
+        .iter()
+        .filter_map(|item| item.as_ref().ok())
+        .map(|c| c.choices[0].finish_reason.is_some())
+        .collect();
+    assert_eq!(finished.iter().filter(|f| **f).count(), 1);
+    assert!(finished.last().unwrap());
+    // All but the last piece's characters are paced before the stream ends
+    assert!(elapsed >= Duration::from_millis(140), "{:?}", elapsed);
+}
+
+#[tokio::test]
+async fn test_coalesce_merges_text_deltas() {
+    let mut last = chunk("!");
+    last.choices[0].finish_reason = Some("stop".to_string());
+    let mut items: Vec<_> = "Hello there, world".chars().map(|c| Ok(chunk(&c.to_string()))).collect();
+    items.push(Err(GroqError::InvalidMessage("boom".to_string())));
+    items.push(Ok(last));
+
+    let merged: Vec<_> = futures::stream::iter(items).coalesce(Coalesce::min_chars(5)).collect().await;
+
+    let texts: Vec<String> = merged
+        .iter()
+        .filter_map(|item| item.as_ref().ok())
+        .map(|c| c.choices[0].delta.content.as_ref().unwrap().to_string())
+        .collect();
+    assert_eq!(texts, ["Hello", " ther", "e, wo", "rld", "!"]);
+    assert!(matches!(merged[4], Err(GroqError::InvalidMessage(_))));
+    let last = merged.last().unwrap().as_ref().unwrap();
+    assert_eq!(last.choices[0].finish_reason.as_deref(), Some("stop"));
+}
+
+#[tokio::test]
+async fn test_coalesce_bounds_the_delay() {
+    let source = futures::stream::unfold(0, |i| async move {
+        match i {
+            0 | 1 => Some((Ok::<_, GroqError>(chunk(if i == 0 { "a" } else { "b" })), i + 1)),
+            2 => {
+                tokio::time::sleep(Duration::from_millis(150)).await;
+                Some((Ok(chunk("c")), 3))
+            }
+            _ => None,
+        }
+    });
+
+    let started = std::time::Instant::now();
+    let mut merged = Box::pin(source).coalesce(Duration::from_millis(50));
+    let first = merged.next().await.unwrap().unwrap();
+    assert_eq!(first.choices[0].delta.content.as_ref().unwrap().to_string(), "ab");
+    assert!(started.elapsed() < Duration::from_millis(140), "{:?}", started.elapsed());
+    let second = merged.next().await.unwrap().unwrap();
+    assert_eq!(second.choices[0].delta.content.as_ref().unwrap().to_string(), "c");
+    assert!(merged.next().await.is_none());
+}
@@ -0,0 +1,74 @@
+use groqai::mock::MockTransport;
+use groqai::text::{estimate_tokens, map_reduce_summarize, Splitter, SummarizeOptions};
+use groqai::{GroqClientBuilder, GroqError};
+
+mod common;
+
+use common::reply;
+
+#[test]
+fn test_splitters_respect_boundaries_and_budget() {
+    let text = "First sentence here. Second sentence follows! Third one?\n\nNew paragraph.";
+    let chunks = Splitter::sentences(12).split(text);
+    assert_eq!(
+        chunks,
+        vec!["First sentence here. Second sentence follows!", "Third one? New paragraph."]
+    );
+    for chunk in Splitter::tokens(3).split(text) {
+        assert!(estimate_tokens(&chunk) <= 3 || !chunk.contains(' '));
+    }
+
+    let markdown = "# Intro\nShort intro.\n\n## Details\nSome details.\n## End\nBye.";
+    let sections = Splitter::markdown_headings(8).split(markdown);
+    assert_eq!(sections.len(), 3);
+    assert!(sections[1].starts_with("## Details"));
+}
+
+#[tokio::test]
+async fn test_map_reduce_summarize() -> Result<(), GroqError> {
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply("summary one")))
+        .with_chat_response(Ok(reply("summary two")))
+        .with_chat_response(Ok(reply("final summary")));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+    let options = SummarizeOptions::new()
+        .splitter(Splitter::markdown_headings(8))
+        .concurrency(1);
+
+    let summary = map_reduce_summarize(
+        &client,
+        "llama-3.1-8b-instant",
+        "# A\nFirst part of the text.\n# B\nSecond part of the text.",
+        &options,
+    )
+    .await?;
+
+    assert_eq!(summary, "final summary");
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[2].body["messages"][1]["content"], "summary one\n\nsummary two");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_map_reduce_summarize_stops_when_summaries_do_not_shrink() -> Result<(), GroqError> {
+    let long = "This summary is every bit as long as the part of the text it summarizes.";
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply(long)))
+        .with_chat_response(Ok(reply(long)));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+    let options = SummarizeOptions::new().splitter(Splitter::sentences(20)).concurrency(1);
+
+    let text = format!("{} {}", long, long);
+    let err = map_reduce_summarize(&client, "llama-3.1-8b-instant", &text, &options)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, GroqError::InvalidMessage(m) if m.contains("2 partial summaries still split into 2 chunks")));
+    // Nothing is sent after the map round
+    assert_eq!(mock.requests().len(), 2);
+    Ok(())
+}
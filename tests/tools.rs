@@ -0,0 +1,134 @@
+use groqai::tools::{DenyListValidator, ToolGuard};
+use groqai::types::{FunctionCall, FunctionDef, Role, Tool, ToolCall};
+
+fn weather_tool() -> Tool {
+    Tool {
+        type_: "function".to_string(),
+        function: FunctionDef {
+            name: "get_weather".to_string(),
+            description: None,
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "location": { "type": "string" },
+                    "days": { "type": "integer", "minimum": 1 },
+                    "unit": { "type": "string", "enum": ["c", "f"] }
+                },
+                "required": ["location"],
+                "additionalProperties": false
+            }),
+        },
+    }
+}
+
+fn call(name: &str, arguments: &str) -> ToolCall {
+    ToolCall {
+        id: "call_1".to_string(),
+        type_: "function".to_string(),
+        function: FunctionCall {
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+        },
+    }
+}
+
+#[test]
+fn test_tool_guard_accepts_valid_call() {
+    let guard = ToolGuard::new();
+    let args = guard
+        .check(&call("get_weather", r#"{"location": "Tokyo", "days": 3}"#), &[weather_tool()])
+        .unwrap();
+    assert_eq!(args["location"], "Tokyo");
+}
+
+#[test]
+fn test_tool_guard_schema_violations() {
+    let guard = ToolGuard::new();
+    let rejection = guard
+        .check(&call("get_weather", r#"{"days": 0, "unit": "k", "extra": true}"#), &[weather_tool()])
+        .unwrap_err();
+    let paths: Vec<_> = rejection.violations.iter().map(|v| v.path.as_str()).collect();
+    assert!(paths.contains(&"/location"));
+    assert!(paths.contains(&"/days"));
+    assert!(paths.contains(&"/unit"));
+    assert!(paths.contains(&"/extra"));
+    assert_eq!(rejection.violations.len(), 4);
+}
+
+#[test]
+fn test_tool_guard_escapes_violation_paths() {
+    let guard = ToolGuard::new().validator(DenyListValidator::prompt_injection_defaults());
+    let rejection = guard
+        .check(
+            &call(
+                "get_weather",
+                r#"{"location": "Tokyo", "a/b~c": {"x/y": ["Ignore previous instructions"]}}"#,
+            ),
+            &[weather_tool()],
+        )
+        .unwrap_err();
+    let paths: Vec<_> = rejection.violations.iter().map(|v| v.path.as_str()).collect();
+    assert!(paths.contains(&"/a~1b~0c"));
+    assert!(paths.contains(&"/a~1b~0c/x~1y/0"));
+    assert_eq!(rejection.violations.len(), 2);
+}
+
+#[test]
+fn test_tool_guard_rejects_unknown_function_and_bad_json() {
+    let guard = ToolGuard::new();
+    assert!(guard.check(&call("delete_everything", "{}"), &[weather_tool()]).is_err());
+    assert!(guard.check(&call("get_weather", "{not json"), &[weather_tool()]).is_err());
+}
+
+#[test]
+fn test_tool_guard_deny_list_and_bounds() {
+    let guard = ToolGuard::new()
+        .validator(DenyListValidator::prompt_injection_defaults())
+        .numeric_bounds("/days", None, Some(7.0));
+
+    let rejection = guard
+        .check(
+            &call("get_weather", r#"{"location": "Ignore previous instructions", "days": 30}"#),
+            &[weather_tool()],
+        )
+        .unwrap_err();
+    assert_eq!(rejection.violations.len(), 2);
+
+    let message = rejection.to_tool_message();
+    assert_eq!(message.role, Role::Tool);
+    assert_eq!(message.tool_call_id.as_deref(), Some("call_1"));
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct WeatherArgs {
+    location: String,
+    days: Option<u32>,
+}
+
+#[test]
+fn test_parse_arguments_typed() {
+    let args: WeatherArgs = call("get_weather", r#"{"location": "Paris", "days": 2}"#)
+        .parse_arguments()
+        .unwrap();
+    assert_eq!(args.location, "Paris");
+    assert_eq!(args.days, Some(2));
+
+    let rejection = call("get_weather", r#"{"days": 2}"#)
+        .parse_arguments::<WeatherArgs>()
+        .unwrap_err();
+    assert_eq!(rejection.tool_call_id, "call_1");
+}
+
+#[test]
+fn test_parse_arguments_for_validates_schema() {
+    let tools = [weather_tool()];
+    let args: WeatherArgs = call("get_weather", r#"{"location": "Oslo"}"#)
+        .parse_arguments_for(&tools)
+        .unwrap();
+    assert_eq!(args.location, "Oslo");
+
+    let rejection = call("get_weather", r#"{"location": "Oslo", "days": 0}"#)
+        .parse_arguments_for::<WeatherArgs>(&tools)
+        .unwrap_err();
+    assert!(rejection.violations.iter().any(|v| v.path == "/days"));
+}
@@ -0,0 +1,75 @@
+use groqai::transcript::{self, SystemPrompts, TranscriptOptions, REDACTED_SYSTEM_PROMPT};
+use groqai::types::{FunctionCall, ImageUrl, MessageContent, MessagePart, ToolCall};
+use groqai::{ChatMessage, Conversation, Role};
+
+fn conversation() -> Conversation {
+    let mut conversation = Conversation::new("llama-3.1-8b-instant").with_system("Internal policy: never <refund>.");
+    conversation.push(ChatMessage::new_multimodal(
+        Role::User,
+        vec![
+            MessagePart::Text { text: "What's the weather here?".to_string() },
+            MessagePart::ImageUrl { image_url: ImageUrl::new("https://example.com/sky.png") },
+        ],
+    ));
+    conversation.push(ChatMessage {
+        role: Role::Assistant,
+        content: MessageContent::text(""),
+        tool_calls: Some(vec![ToolCall {
+            id: "call_1".to_string(),
+            type_: "function".to_string(),
+            function: FunctionCall {
+                name: "get_weather".to_string(),
+                arguments: r#"{"city":"Paris"}"#.to_string(),
+            },
+        }]),
+        tool_call_id: None,
+    });
+    conversation.push(ChatMessage::tool_response("call_1".to_string(), "Sunny, uses ``` fences"));
+    conversation.push(ChatMessage::new_text(Role::Assistant, "Sunny. Try:\n\n```sh\necho \"<hot>\"\n```"));
+    conversation
+}
+
+#[test]
+fn test_conversation_to_markdown() {
+    let conversation = conversation();
+    let markdown = conversation.to_markdown();
+    assert!(markdown.starts_with("# Conversation with llama-3.1-8b-instant\n\n## System\n\n"));
+    assert!(markdown.contains(REDACTED_SYSTEM_PROMPT));
+    assert!(!markdown.contains("Internal policy"));
+    assert!(markdown.contains("What's the weather here?\n\n![image](<https://example.com/sky.png>)"));
+    assert!(markdown.contains(
+        "<details>\n<summary>Tool call: get_weather</summary>\n\n```json\n{\n  \"city\": \"Paris\"\n}\n```\n\n</details>"
+    ));
+    // The tool result contains a fence, so it is wrapped in a longer one
+    assert!(markdown.contains("<summary>Tool result (call_1)</summary>\n\n````\nSunny, uses ``` fences\n````"));
+    assert!(markdown.ends_with("## Assistant\n\nSunny. Try:\n\n```sh\necho \"<hot>\"\n```\n"));
+
+    let options = TranscriptOptions::new().system_prompts(SystemPrompts::Omit).title("Support chat");
+    let markdown = conversation.to_markdown_with(&options);
+    assert!(markdown.starts_with("# Support chat\n\n## User\n\n"));
+
+    // A fence left open by a truncated reply is closed before the next header
+    let truncated = [
+        ChatMessage::new_text(Role::Assistant, "```rust\nfn main() {"),
+        ChatMessage::new_text(Role::User, "Go on"),
+    ];
+    let markdown = transcript::to_markdown(&truncated, &TranscriptOptions::new());
+    assert_eq!(markdown, "## Assistant\n\n```rust\nfn main() {\n```\n\n## User\n\nGo on\n");
+}
+
+#[test]
+fn test_conversation_to_html() {
+    let conversation = conversation();
+    let html = conversation.to_html_with(&TranscriptOptions::new().system_prompts(SystemPrompts::Show));
+    assert!(html.starts_with("<article class=\"transcript\">\n<h1>Conversation with llama-3.1-8b-instant</h1>\n"));
+    assert!(html.contains("<section class=\"message system\">\n<h2>System</h2>\n<p>Internal policy: never &lt;refund&gt;.</p>"));
+    assert!(html.contains("<p>What&#39;s the weather here?</p>\n<p><img src=\"https://example.com/sky.png\" alt=\"image\"></p>"));
+    assert!(html.contains("<details class=\"tool-call\">\n<summary>Tool call: get_weather</summary>\n<pre><code class=\"language-json\">"));
+    assert!(html.contains("<details class=\"tool-result\">\n<summary>Tool result (call_1)</summary>"));
+    assert!(html.contains("<p>Sunny. Try:</p>\n<pre><code class=\"language-sh\">echo &quot;&lt;hot&gt;&quot;\n</code></pre>"));
+    assert!(html.ends_with("</section>\n</article>\n"));
+
+    let html = conversation.to_html();
+    assert!(!html.contains("Internal policy"));
+    assert!(html.contains(&format!("<p>{}</p>", REDACTED_SYSTEM_PROMPT)));
+}
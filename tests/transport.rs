@@ -0,0 +1,266 @@
+use groqai::error::GroqError;
+use groqai::transport::{Request, RequestOptions, RetryPolicy};
+use groqai::{Endpoint, GroqClientBuilder};
+use std::time::Duration;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn empty_list() -> serde_json::Value {
+    serde_json::json!({"object": "list", "data": [], "has_more": false})
+}
+
+#[tokio::test]
+async fn test_retry_policy_reuses_idempotency_key() -> Result<(), Box<dyn std::error::Error>> {
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/files"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&mock)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/files"))
+        .and(query_param("purpose", "batch"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(empty_list()))
+        .mount(&mock)
+        .await;
+    let client = GroqClientBuilder::new("gsk_test_key")?
+        .base_url(mock.uri().parse().unwrap())
+        .build()?;
+
+    let policy = RetryPolicy {
+        initial_delay: Duration::from_millis(10),
+        ..RetryPolicy::new(2)
+    };
+    let request = Request::get(Endpoint::Files)
+        .query("purpose", "batch")
+        .options(
+            RequestOptions::new()
+                .idempotency_key("list-1")
+                .retry_policy(policy),
+        );
+    let response = client.transport.execute(request).await?;
+    assert_eq!(response.body, empty_list());
+
+    let received = mock.received_requests().await.unwrap();
+    assert_eq!(received.len(), 2);
+    assert!(received
+        .iter()
+        .all(|r| r.headers.get("idempotency-key").unwrap() == "list-1"));
+    assert_eq!(client.metrics_snapshot().endpoints["files"].retries, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_client_errors_are_not_retried() -> Result<(), Box<dyn std::error::Error>> {
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/models/missing"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": {"message": "model not found", "type": "invalid_request_error"}
+        })))
+        .expect(1)
+        .mount(&mock)
+        .await;
+    let client = GroqClientBuilder::new("gsk_test_key")?
+        .base_url(mock.uri().parse().unwrap())
+        .build()?;
+
+    let request = Request::get(Endpoint::Model("missing".to_string()))
+        .options(RequestOptions::new().retry_policy(RetryPolicy::new(3)));
+    let result = client.transport.execute(request).await;
+    assert!(matches!(result, Err(GroqError::Api(e)) if e.status.as_u16() == 404));
+    Ok(())
+}
+
+#[cfg(feature = "streaming")]
+#[tokio::test]
+async fn test_request_timeout_overrides_client_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    let mock = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/models"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(empty_list())
+                .set_delay(Duration::from_millis(500)),
+        )
+        .mount(&mock)
+        .await;
+    let client = GroqClientBuilder::new("gsk_test_key")?
+        .base_url(mock.uri().parse().unwrap())
+        .build()?;
+
+    let request = Request::get(Endpoint::Models)
+        .options(RequestOptions::new().timeout(Duration::from_millis(50)));
+    let result = client.transport.execute(request).await;
+    assert!(
+        matches!(result, Err(GroqError::Transport(_))),
+        "{:?}",
+        result
+    );
+    assert!(client
+        .transport
+        .execute(Request::get(Endpoint::Models))
+        .await
+        .is_ok());
+    Ok(())
+}
+
+#[allow(deprecated)]
+mod legacy {
+    use async_trait::async_trait;
+    #[cfg(feature = "streaming")]
+    use futures::StreamExt;
+    use groqai::api::chat::ChatCompletionRequest;
+    use groqai::error::GroqError;
+    use groqai::transport::{ChatCompletionStream, LegacyTransport, RequestOptions};
+    use groqai::types::{ChatCompletionChunk, ChatCompletionResponse};
+    use groqai::{ChatMessage, GroqClientBuilder, Role};
+    use serde_json::Value;
+    use url::Url;
+
+    /// A transport written against the path-based interface
+    struct PathTransport {
+        base_url: Url,
+    }
+
+    fn unsupported(path: &str) -> Result<Value, GroqError> {
+        Err(GroqError::InvalidMessage(format!("unsupported: {}", path)))
+    }
+
+    #[async_trait]
+    impl LegacyTransport for PathTransport {
+        async fn post_chat(
+            &self,
+            path: &str,
+            body: &ChatCompletionRequest,
+            _options: &RequestOptions,
+        ) -> Result<ChatCompletionResponse, GroqError> {
+            assert_eq!(path, "chat/completions");
+            assert_eq!(body.model, "llama-3.1-8b-instant");
+            Ok(serde_json::from_str(
+                groqai::fixtures::CHAT_COMPLETION_RESPONSE,
+            )?)
+        }
+
+        async fn post_stream(
+            &self,
+            url: Url,
+            body: &ChatCompletionRequest,
+            options: &RequestOptions,
+        ) -> Result<ChatCompletionStream, GroqError> {
+            self.post_stream_with_retry(url, body, 0, options).await
+        }
+
+        async fn post_stream_with_retry(
+            &self,
+            url: Url,
+            body: &ChatCompletionRequest,
+            _max_retries: u32,
+            _options: &RequestOptions,
+        ) -> Result<ChatCompletionStream, GroqError> {
+            assert_eq!(
+                url.as_str(),
+                "https://api.groq.com/openai/v1/chat/completions"
+            );
+            assert_eq!(body.stream, Some(true));
+            let chunk: ChatCompletionChunk =
+                serde_json::from_str(groqai::fixtures::CHAT_COMPLETION_CHUNK)?;
+            Ok(Box::pin(futures::stream::iter(vec![Ok(chunk)])))
+        }
+
+        async fn post_json(
+            &self,
+            path: &str,
+            _body: &Value,
+            _options: &RequestOptions,
+        ) -> Result<Value, GroqError> {
+            unsupported(path)
+        }
+
+        async fn post_multipart(
+            &self,
+            path: &str,
+            _body: &Value,
+            _options: &RequestOptions,
+        ) -> Result<Value, GroqError> {
+            unsupported(path)
+        }
+
+        async fn get_json(
+            &self,
+            path: &str,
+            _options: &RequestOptions,
+        ) -> Result<Value, GroqError> {
+            assert_eq!(path, "models");
+            Ok(serde_json::json!({"object": "list", "data": []}))
+        }
+
+        async fn get_with_params(
+            &self,
+            path: &str,
+            _params: &[(&str, String)],
+            _options: &RequestOptions,
+        ) -> Result<Value, GroqError> {
+            unsupported(path)
+        }
+
+        async fn delete_json(
+            &self,
+            path: &str,
+            _options: &RequestOptions,
+        ) -> Result<Value, GroqError> {
+            unsupported(path)
+        }
+
+        async fn post_batch_create(&self, _body: &Value) -> Result<Value, GroqError> {
+            unsupported("batches")
+        }
+
+        async fn get_batch_retrieve(&self, batch_id: &str) -> Result<Value, GroqError> {
+            unsupported(batch_id)
+        }
+
+        async fn get_batch_list(&self, _params: &[(&str, String)]) -> Result<Value, GroqError> {
+            unsupported("batches")
+        }
+
+        async fn post_batch_cancel(&self, batch_id: &str) -> Result<Value, GroqError> {
+            unsupported(batch_id)
+        }
+
+        fn base_url(&self) -> &Url {
+            &self.base_url
+        }
+    }
+
+    #[tokio::test]
+    async fn test_legacy_transport_still_works() -> Result<(), Box<dyn std::error::Error>> {
+        let client = GroqClientBuilder::new("gsk_test_key")?
+            .transport(PathTransport {
+                base_url: Url::parse("https://api.groq.com/openai/v1/")?,
+            })
+            .build()?;
+
+        let response = client
+            .chat("llama-3.1-8b-instant")
+            .message(ChatMessage::new_text(Role::User, "Hello"))
+            .send()
+            .await?;
+        assert!(!response.choices.is_empty());
+        assert!(client.models().list().await?.data.is_empty());
+
+        #[cfg(feature = "streaming")]
+        {
+            let mut stream = client
+                .chat("llama-3.1-8b-instant")
+                .message(ChatMessage::new_text(Role::User, "Hello"))
+                .stream(true)
+                .send_stream()
+                .await?;
+            assert!(stream.next().await.unwrap().is_ok());
+            assert!(stream.next().await.is_none());
+        }
+        Ok(())
+    }
+}
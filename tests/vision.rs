@@ -0,0 +1,111 @@
+use groqai::mock::MockTransport;
+use groqai::api::vision::MAX_INLINE_IMAGE_BYTES;
+use groqai::types::{ImageUrl, MessageContent};
+use groqai::{ChatMessage, GroqClientBuilder, GroqError, ImageSource, Role};
+
+mod common;
+
+use common::reply;
+
+#[tokio::test]
+async fn test_ocr_sends_image_and_prompt() -> Result<(), GroqError> {
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply("TOTAL 12.50")))
+        .with_chat_response(Ok(reply(r#"{"blocks": [{"lines": ["ACME"]}, {"lines": ["TOTAL", "12.50"]}]}"#)));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    let text = client.vision().ocr("https://example.com/receipt.png").await?;
+    assert_eq!(text, "TOTAL 12.50");
+
+    let bytes = ImageSource::Bytes { data: vec![1, 2, 3], mime_type: "image/png".to_string() };
+    let structured = client.vision().model("custom-vision").ocr_structured(bytes).await?;
+    assert_eq!(structured.text(), "ACME\n\nTOTAL\n12.50");
+
+    let requests = mock.requests();
+    let parts = &requests[0].body["messages"][0]["content"];
+    assert_eq!(parts[1]["image_url"]["url"], "https://example.com/receipt.png");
+    assert_eq!(requests[1].body["model"], "custom-vision");
+    assert_eq!(requests[1].body["response_format"]["type"], "json_object");
+    assert_eq!(
+        requests[1].body["messages"][0]["content"][1]["image_url"]["url"],
+        "data:image/png;base64,AQID"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_image_source_rejects_unknown_extension() {
+    let err = ImageSource::from("notes.txt").to_image_url().await.unwrap_err();
+    assert!(matches!(err, GroqError::InvalidMessage(_)));
+}
+
+#[tokio::test]
+async fn test_describe_and_ask_set_detail_levels() -> Result<(), GroqError> {
+    let mock = MockTransport::new()
+        .with_chat_response(Ok(reply("A dog on a beach.")))
+        .with_chat_response(Ok(reply("Two.")));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    assert_eq!(client.vision().describe("https://example.com/dog.jpg").await?, "A dog on a beach.");
+    let answer = client
+        .vision()
+        .detail("low")
+        .ask("https://example.com/dog.jpg", "How many dogs?")
+        .await?;
+    assert_eq!(answer, "Two.");
+
+    let requests = mock.requests();
+    assert_eq!(requests[0].body["model"], "meta-llama/llama-4-scout-17b-16e-instruct");
+    assert_eq!(requests[0].body["messages"][0]["content"][1]["image_url"]["detail"], "auto");
+    let content = &requests[1].body["messages"][0]["content"];
+    assert_eq!(content[1]["image_url"]["detail"], "low");
+    assert!(content[0]["text"].as_str().unwrap().ends_with("Question: How many dogs?"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_from_images_keeps_order_and_validates_limits() -> Result<(), GroqError> {
+    let mock = MockTransport::new().with_chat_response(Ok(reply("Two charts")));
+    let client = GroqClientBuilder::new("gsk_test_key".to_string())?
+        .transport(mock.clone())
+        .build()?;
+
+    let content = MessageContent::from_images([
+        ImageUrl::new("https://example.com/a.png").with_detail("high"),
+        ImageUrl::from("https://example.com/b.png"),
+    ])
+    .with_text("Compare these charts");
+    let message = ChatMessage { content, ..ChatMessage::new_text(Role::User, "") };
+    client
+        .chat("meta-llama/llama-4-scout-17b-16e-instruct")
+        .message(message)
+        .send()
+        .await?;
+    let parts = &mock.requests()[0].body["messages"][0]["content"];
+    assert_eq!(parts[0]["text"], "Compare these charts");
+    assert_eq!(parts[1]["image_url"]["url"], "https://example.com/a.png");
+    assert_eq!(parts[1]["image_url"]["detail"], "high");
+    assert_eq!(parts[2]["image_url"]["url"], "https://example.com/b.png");
+    assert!(parts[2]["image_url"].get("detail").is_none());
+
+    let too_many = MessageContent::from_images((0..6).map(|i| format!("https://example.com/{}.png", i)));
+    let message = ChatMessage { content: too_many, ..ChatMessage::new_text(Role::User, "") };
+    let err = client
+        .chat("meta-llama/llama-4-scout-17b-16e-instruct")
+        .message(message)
+        .send()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("at most 5 images"));
+
+    let huge = format!("data:image/png;base64,{}", "A".repeat(MAX_INLINE_IMAGE_BYTES));
+    let message = ChatMessage { content: MessageContent::from_images([huge]), ..ChatMessage::new_text(Role::User, "") };
+    let err = client.chat("custom-model").message(message).send().await.unwrap_err();
+    assert!(err.to_string().contains("byte limit"));
+    assert_eq!(mock.requests().len(), 1);
+    Ok(())
+}
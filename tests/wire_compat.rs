@@ -0,0 +1,293 @@
+//! Serde compatibility against the golden fixtures in `fixtures/wire`
+//!
+//! Serializable types must round-trip each fixture unchanged; deserialize-only
+//! types must parse it with every documented field intact.
+
+use std::collections::HashMap;
+
+use groqai::api::chat::ChatCompletionRequest;
+use groqai::error::GroqApiError;
+use groqai::fixtures;
+use groqai::types::*;
+#[cfg(feature = "batches")]
+use groqai::{BatchCreateRequest, BatchEndpoint, CompletionWindow};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+fn golden(fixture: &str) -> Value {
+    serde_json::from_str(fixture).unwrap()
+}
+
+fn assert_round_trip<T: DeserializeOwned + Serialize>(fixture: &str) -> T {
+    let parsed: T = serde_json::from_str(fixture).unwrap();
+    assert_eq!(serde_json::to_value(&parsed).unwrap(), golden(fixture));
+    parsed
+}
+
+#[test]
+fn test_every_fixture_is_valid_json() {
+    let names: Vec<&str> = fixtures::ALL.iter().map(|(name, _)| *name).collect();
+    let unique: HashMap<&str, ()> = names.iter().map(|n| (*n, ())).collect();
+    assert_eq!(unique.len(), names.len());
+    for (name, fixture) in fixtures::ALL {
+        assert!(serde_json::from_str::<Value>(fixture).is_ok(), "{} is not valid JSON", name);
+    }
+}
+
+#[test]
+fn test_chat_completion_request_wire_format() {
+    let request = ChatCompletionRequest {
+        messages: vec![
+            ChatMessage::new_text(Role::System, "You are a helpful assistant."),
+            ChatMessage {
+                content: MessageContent::from_images([
+                    ImageUrl::new("https://example.com/cat.png").with_detail("low")
+                ])
+                .with_text("What is in this image?"),
+                ..ChatMessage::new_text(Role::User, "")
+            },
+        ],
+        model: "meta-llama/llama-4-scout-17b-16e-instruct".to_string(),
+        temperature: Some(0.5),
+        max_completion_tokens: Some(256),
+        tools: Some(vec![Tool {
+            type_: "function".to_string(),
+            function: FunctionDef {
+                name: "get_weather".to_string(),
+                description: Some("Get the current weather".to_string()),
+                parameters: json!({
+                    "type": "object",
+                    "properties": {"location": {"type": "string"}},
+                    "required": ["location"]
+                }),
+            },
+        }]),
+        tool_choice: Some(ToolChoice::function("get_weather")),
+        frequency_penalty: Some(0.5),
+        presence_penalty: Some(0.25),
+        logprobs: Some(true),
+        top_logprobs: Some(2),
+        parallel_tool_calls: Some(false),
+        response_format: Some(ResponseFormat {
+            type_: "json_object".to_string(),
+            json_schema: None,
+        }),
+        seed: Some(42),
+        service_tier: Some(ServiceTier::Flex),
+        stop: Some(StopSequence::Multiple(vec!["\n\n".to_string()])),
+        stream_options: Some(StreamOptions { include_usage: Some(true) }),
+        ..Default::default()
+    };
+    assert_eq!(serde_json::to_value(&request).unwrap(), golden(fixtures::CHAT_COMPLETION_REQUEST));
+}
+
+#[test]
+fn test_structured_request_wire_format() {
+    let request = ChatCompletionRequest {
+        messages: vec![ChatMessage::new_text(Role::User, "Book a table for two at 7pm.")],
+        model: "openai/gpt-oss-120b".to_string(),
+        tools: Some(vec![Tool {
+            type_: "function".to_string(),
+            function: FunctionDef {
+                name: "book_table".to_string(),
+                description: None,
+                parameters: json!({
+                    "type": "object",
+                    "properties": {"party_size": {"type": "integer"}, "time": {"type": "string"}},
+                    "required": ["party_size", "time"]
+                }),
+            },
+        }]),
+        tool_choice: Some(ToolChoice::Required),
+        response_format: Some(ResponseFormat::json_schema(
+            "booking",
+            json!({"type": "object", "properties": {"confirmed": {"type": "boolean"}}}),
+            false,
+        )),
+        ..Default::default()
+    };
+    assert_eq!(serde_json::to_value(&request).unwrap(), golden(fixtures::CHAT_COMPLETION_REQUEST_STRUCTURED));
+
+    // Tools and formats also parse back unchanged, e.g. when proxying requests
+    for fixture in [fixtures::CHAT_COMPLETION_REQUEST, fixtures::CHAT_COMPLETION_REQUEST_STRUCTURED] {
+        let body = golden(fixture);
+        let tool: Tool = serde_json::from_value(body["tools"][0].clone()).unwrap();
+        assert_eq!(serde_json::to_value(&tool).unwrap(), body["tools"][0]);
+        let choice: ToolChoice = serde_json::from_value(body["tool_choice"].clone()).unwrap();
+        assert_eq!(serde_json::to_value(&choice).unwrap(), body["tool_choice"]);
+        let format: ResponseFormat = serde_json::from_value(body["response_format"].clone()).unwrap();
+        assert_eq!(serde_json::to_value(&format).unwrap(), body["response_format"]);
+    }
+}
+
+#[test]
+fn test_tool_choice_wire_format() {
+    let forms = [
+        (ToolChoice::None, json!("none")),
+        (ToolChoice::Auto, json!("auto")),
+        (ToolChoice::Required, json!("required")),
+        (
+            ToolChoice::function("get_weather"),
+            json!({"type": "function", "function": {"name": "get_weather"}}),
+        ),
+    ];
+    for (choice, wire) in forms {
+        assert_eq!(serde_json::to_value(&choice).unwrap(), wire);
+        assert_eq!(serde_json::from_value::<ToolChoice>(wire).unwrap(), choice);
+    }
+    assert!(serde_json::from_value::<ToolChoice>(json!("any")).is_err());
+    assert!(serde_json::from_value::<ToolChoice>(json!({"type": "auto"})).is_err());
+
+    assert_eq!(serde_json::to_value(ResponseFormat::json_object()).unwrap(), json!({"type": "json_object"}));
+}
+
+#[test]
+fn test_chat_completion_response_wire_format() {
+    let response: ChatCompletionResponse = assert_round_trip(fixtures::CHAT_COMPLETION_RESPONSE);
+    assert_eq!(response.effective_service_tier(), Some(ServiceTier::OnDemand));
+    assert_eq!(response.usage.total_tokens, 30);
+
+    let chunk: ChatCompletionChunk = assert_round_trip(fixtures::CHAT_COMPLETION_CHUNK);
+    assert_eq!(chunk.choices[0].delta.content, Some(MessageContent::Text("Hello".to_string())));
+}
+
+#[test]
+fn test_audio_wire_format() {
+    let transcription: Transcription = assert_round_trip(fixtures::TRANSCRIPTION);
+    assert_eq!(transcription.text, " Hello there. How can I help?");
+    assert!(transcription.segments.is_empty() && transcription.language.is_none());
+
+    let transcription: Transcription = assert_round_trip(fixtures::TRANSCRIPTION_VERBOSE);
+    assert_eq!(transcription.text, " Hello there. How can I help?");
+    assert_eq!(transcription.segments.len(), 2);
+    assert_eq!(transcription.segments[1].start, 2.1);
+    assert_eq!(transcription.segments[1].avg_logprob, Some(-0.18));
+    assert_eq!((transcription.language.as_deref(), transcription.duration), (Some("English"), Some(4.2)));
+    assert_eq!(transcription.words.len(), 6);
+    let second: Vec<&str> = transcription
+        .segment_words(&transcription.segments[1])
+        .map(|w| w.word.as_str())
+        .collect();
+    assert_eq!(second, ["How", "can", "I", "help?"]);
+    assert!(transcription.x_groq.is_some());
+
+    let translation: Translation = serde_json::from_str(fixtures::TRANSLATION).unwrap();
+    assert_eq!(translation.text, " Good morning, everyone.");
+}
+
+#[cfg(feature = "audio")]
+#[test]
+fn test_speech_request_wire_format() {
+    use groqai::api::audio::{AudioFormat, AudioSpeechRequest, Voice};
+
+    let request = AudioSpeechRequest {
+        model: "playai-tts".to_string(),
+        input: "Hello from Groq.".to_string(),
+        voice: Voice::Fritz,
+        response_format: Some(AudioFormat::Wav),
+        sample_rate: Some(48_000),
+        speed: Some(1.25),
+    };
+    assert!(request.validate().is_ok());
+    assert_eq!(serde_json::to_value(&request).unwrap(), golden(fixtures::SPEECH_REQUEST));
+    let parsed: AudioSpeechRequest = assert_round_trip(fixtures::SPEECH_REQUEST);
+    assert_eq!(parsed.voice, Voice::Fritz);
+}
+
+#[test]
+fn test_models_and_files_wire_format() {
+    let models: ModelList = assert_round_trip(fixtures::MODEL_LIST);
+    assert_eq!(models.data[0].context_window, 131072);
+    assert!(models.data[0].active);
+    assert_eq!(models.data[0].max_completion_tokens, Some(131072));
+    let model: Model = assert_round_trip(fixtures::MODEL);
+    assert_eq!((model.id.as_str(), model.max_completion_tokens), ("llama-3.3-70b-versatile", Some(32768)));
+    assert!(model.public_apps.is_none());
+
+    let file: WorkFile = serde_json::from_str(fixtures::FILE).unwrap();
+    assert_eq!((file.bytes, file.purpose.as_str()), (966, "batch"));
+    assert_eq!(file.purpose, groqai::FilePurpose::Batch);
+    let files: WorkFileList = serde_json::from_str(fixtures::FILE_LIST).unwrap();
+    assert_eq!(files.data[0].id, file.id);
+    assert!(!files.has_more);
+    let deletion: WorkFileDeletion = serde_json::from_str(fixtures::FILE_DELETION).unwrap();
+    assert!(deletion.deleted);
+}
+
+#[cfg(feature = "batches")]
+#[test]
+fn test_batches_wire_format() {
+    let request = BatchCreateRequest {
+        input_file_id: "file_01jh6x76wtemjr74t1fh0faj5t".to_string(),
+        endpoint: BatchEndpoint::ChatCompletions,
+        completion_window: CompletionWindow::Hours24,
+        metadata: Some(json!({"project": "nightly-eval"})),
+    };
+    assert_eq!(serde_json::to_value(&request).unwrap(), golden(fixtures::BATCH_CREATE_REQUEST));
+
+    let batch: Batch = assert_round_trip(fixtures::BATCH);
+    assert_eq!(batch.request_counts.completed, 3);
+    let list: BatchList = serde_json::from_str(fixtures::BATCH_LIST).unwrap();
+    assert_eq!(list.data[0].status, "in_progress");
+    assert_eq!(list.last_id.as_deref(), Some("batch_01jh6xa7reempvjyh6n3yst2zw"));
+
+    let failed: Batch = assert_round_trip(fixtures::BATCH_FAILED);
+    assert!(failed.is_failed());
+    let errors = failed.errors.as_ref().unwrap();
+    assert_eq!(errors.data[1].param.as_deref(), Some("url"));
+    assert_eq!(errors.data[1].line, Some(7));
+    assert_eq!(
+        failed.error_summary().as_deref(),
+        Some("2 errors: invalid_json on line 3 (Line is not valid JSON); invalid_url on line 7 (url must be /v1/chat/completions)")
+    );
+}
+
+#[cfg(feature = "fine-tuning")]
+#[test]
+fn test_fine_tuning_wire_format() {
+    use groqai::api::fine_tunings::{FineTuning, FineTuningCreateRequest, FineTuningEventList, FineTuningList};
+
+    let request = FineTuningCreateRequest {
+        base_model: "llama-3.1-8b-instant".to_string(),
+        input_file_id: "file_01jh6x76wtemjr74t1fh0faj5t".to_string(),
+        name: "support-bot".to_string(),
+        type_: "lora".to_string(),
+    };
+    assert_eq!(serde_json::to_value(&request).unwrap(), golden(fixtures::FINE_TUNING_CREATE_REQUEST));
+    let parsed: FineTuningCreateRequest = assert_round_trip(fixtures::FINE_TUNING_CREATE_REQUEST);
+    assert_eq!(parsed.type_, "lora");
+
+    let job: FineTuning = assert_round_trip(fixtures::FINE_TUNING);
+    assert!(job.is_succeeded());
+    assert_eq!(job.fine_tuned_model.as_deref(), Some("ft:llama-3.1-8b-instant:support-bot"));
+    let jobs: FineTuningList = assert_round_trip(fixtures::FINE_TUNING_LIST);
+    assert!(jobs.data[0].is_failed());
+    assert_eq!(jobs.data[0].error.as_ref().unwrap()["code"], "invalid_training_file");
+    let events: FineTuningEventList = assert_round_trip(fixtures::FINE_TUNING_EVENT_LIST);
+    assert!(events.has_more);
+    assert_eq!(events.data[1].data.as_ref().unwrap()["step"], 100);
+}
+
+#[test]
+fn test_error_wire_format() {
+    let error = GroqApiError::from_response(
+        reqwest::StatusCode::TOO_MANY_REQUESTS,
+        fixtures::ERROR.to_string(),
+        &reqwest::header::HeaderMap::new(),
+    );
+    assert_eq!(error.error.code.as_deref(), Some("rate_limit_exceeded"));
+    assert_eq!(error.error.error_type.as_deref(), Some("tokens"));
+}
+
+#[test]
+fn test_unknown_response_fields() {
+    let mut model = golden(fixtures::MODEL_LIST);
+    model["data"][0]["new_field"] = json!(true);
+    let parsed = serde_json::from_value::<ModelList>(model);
+    if cfg!(feature = "strict-schema") {
+        assert!(parsed.unwrap_err().to_string().contains("unknown field `new_field`"));
+    } else {
+        assert_eq!(parsed.unwrap().data[0].id, "llama-3.1-8b-instant");
+    }
+}